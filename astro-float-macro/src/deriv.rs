@@ -0,0 +1,304 @@
+//! Symbolic differentiation of expressions.
+
+use crate::util::{check_arg_num, var_name};
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::{format_ident, ToTokens};
+use syn::{parse_quote, spanned::Spanned, BinOp, Error, Expr, ExprBinary, ExprCall, Ident, UnOp};
+
+// Derivative of an expression.
+enum Deriv {
+    Zero,
+    One,
+    Expr(Box<Expr>),
+}
+
+impl Deriv {
+    fn expr(e: Expr) -> Self {
+        Deriv::Expr(Box::new(e))
+    }
+
+    fn is_zero(&self) -> bool {
+        matches!(self, Deriv::Zero)
+    }
+
+    fn into_expr(self) -> Expr {
+        match self {
+            Deriv::Zero => parse_quote!(0),
+            Deriv::One => parse_quote!(1),
+            Deriv::Expr(e) => *e,
+        }
+    }
+
+    fn neg(self) -> Self {
+        match self {
+            Deriv::Zero => Deriv::Zero,
+            d => {
+                let e = d.into_expr();
+                Deriv::expr(parse_quote!(-(#e)))
+            }
+        }
+    }
+
+    fn add(self, d: Deriv) -> Self {
+        match (self, d) {
+            (Deriv::Zero, d) | (d, Deriv::Zero) => d,
+            (d1, d2) => {
+                let (e1, e2) = (d1.into_expr(), d2.into_expr());
+                Deriv::expr(parse_quote!((#e1) + (#e2)))
+            }
+        }
+    }
+
+    fn sub(self, d: Deriv) -> Self {
+        self.add(d.neg())
+    }
+
+    // Multiplies the derivative by the expression `e`.
+    fn mul(self, e: Expr) -> Self {
+        match self {
+            Deriv::Zero => Deriv::Zero,
+            Deriv::One => Deriv::expr(e),
+            Deriv::Expr(d) => Deriv::expr(parse_quote!((#d) * (#e))),
+        }
+    }
+
+    // Divides the derivative by the expression `e`.
+    fn div(self, e: Expr) -> Self {
+        match self {
+            Deriv::Zero => Deriv::Zero,
+            Deriv::One => Deriv::expr(parse_quote!(1 / (#e))),
+            Deriv::Expr(d) => Deriv::expr(parse_quote!((#d) / (#e))),
+        }
+    }
+}
+
+/// Returns the derivative of the expression `f` of the call `deriv(f, x)` with respect to the variable `x`.
+pub fn deriv_call(expr: &ExprCall) -> Result<Expr, Error> {
+    check_arg_num(2, expr)?;
+
+    let var = var_name(&expr.args[1], "variable of differentiation")?;
+
+    derive(&expr.args[0], &var).map(Deriv::into_expr)
+}
+
+fn derive(expr: &Expr, var: &Ident) -> Result<Deriv, Error> {
+    match expr {
+        Expr::Binary(e) => derive_binary(e, var),
+        Expr::Call(e) => derive_call(e, var),
+        Expr::Group(e) => derive(&e.expr, var),
+        Expr::Lit(_) => Ok(Deriv::Zero),
+        Expr::MethodCall(_) => Ok(Deriv::Zero),
+        Expr::Paren(e) => derive(&e.expr, var),
+        Expr::Path(e) => Ok(if e.path.is_ident(var) { Deriv::One } else { Deriv::Zero }),
+        Expr::Unary(e) => match e.op {
+            UnOp::Neg(_) => derive(&e.expr, var).map(Deriv::neg),
+            _ => Err(Error::new(
+                e.span(),
+                "unexpected unary operator. Only \"-\" is allowed.",
+            )),
+        },
+        _ => Err(Error::new(
+            expr.span(),
+            "the expression can't be differentiated.",
+        )),
+    }
+}
+
+fn derive_binary(expr: &ExprBinary, var: &Ident) -> Result<Deriv, Error> {
+    let u = &expr.left;
+    let v = &expr.right;
+    let du = derive(u, var)?;
+    let dv = derive(v, var)?;
+
+    let ret = match expr.op {
+        BinOp::Add(_) => du.add(dv),
+        BinOp::Sub(_) => du.sub(dv),
+        BinOp::Mul(_) => du.mul(parse_quote!(#v)).add(dv.mul(parse_quote!(#u))),
+        BinOp::Div(_) => {
+            if dv.is_zero() {
+                du.div(parse_quote!(#v))
+            } else {
+                du.mul(parse_quote!(#v))
+                    .sub(dv.mul(parse_quote!(#u)))
+                    .div(parse_quote!((#v) * (#v)))
+            }
+        }
+        BinOp::Rem(_) => {
+            if !dv.is_zero() {
+                return Err(Error::new(
+                    v.span(),
+                    "the divisor of \"%\" can't depend on the variable of differentiation.",
+                ));
+            }
+            du
+        }
+        _ => return Err(Error::new(
+            expr.span(),
+            "unexpected binary operator. Only \"+\", \"-\", \"*\", \"/\", and \"%\" are allowed.",
+        )),
+    };
+
+    Ok(ret)
+}
+
+fn derive_call(expr: &ExprCall, var: &Ident) -> Result<Deriv, Error> {
+    let fname = match expr.func.as_ref() {
+        Expr::Path(fun) => fun.path.get_ident().map(|id| id.to_string()),
+        _ => None,
+    }
+    .ok_or_else(|| Error::new(expr.span(), "unexpected function name."))?;
+
+    let narg = match fname.as_str() {
+        "hypot" | "log" | "pow" | "besselj" | "bessely" | "besseli" | "besselk" | "deriv" => 2,
+        "sum" | "product" => 4,
+        _ => 1,
+    };
+    check_arg_num(narg, expr)?;
+
+    let args = &expr.args;
+
+    if fname == "deriv" {
+        let inner = deriv_call(expr)?;
+        return derive(&inner, var);
+    }
+
+    if fname == "sum" || fname == "product" {
+        let idx = var_name(&args[0], "index variable")?;
+        let (lo, hi, f) = (&args[1], &args[2], &args[3]);
+        let df = derive(f, var)?;
+
+        // the index hides the variable of differentiation
+        if idx == *var || df.is_zero() {
+            return Ok(Deriv::Zero);
+        }
+
+        let df = df.into_expr();
+        let ret = if fname == "sum" {
+            parse_quote!(sum(#idx, #lo, #hi, #df))
+        } else {
+            // the sum of the products where one factor is replaced with its derivative,
+            // the other factors use a separate index variable
+            let other = format_ident!("{}_other", idx);
+            let g = rename_var(f, &idx, &other)?;
+            parse_quote!(sum(#idx, #lo, #hi, (#df) * product(#other, #lo, #idx - 1, #g) * product(#other, #idx + 1, #hi, #g)))
+        };
+
+        return Ok(Deriv::expr(ret));
+    }
+
+    if fname == "sum_of" || fname == "product_of" {
+        return Ok(Deriv::Zero);
+    }
+
+    let u = &args[0];
+    let du = derive(u, var)?;
+
+    let ret = match fname.as_str() {
+        "recip" => du.div(parse_quote!((#u) * (#u))).neg(),
+        "sqrt" => du.div(parse_quote!(2 * sqrt(#u))),
+        "cbrt" => du.div(parse_quote!(3 * cbrt(#u) * cbrt(#u))),
+        "hypot" => {
+            let v = &args[1];
+            let dv = derive(v, var)?;
+            du.mul(parse_quote!(#u))
+                .add(dv.mul(parse_quote!(#v)))
+                .div(parse_quote!(hypot(#u, #v)))
+        }
+        "ln" => du.div(parse_quote!(#u)),
+        "log2" => du.div(parse_quote!((#u) * ln_2)),
+        "log10" => du.div(parse_quote!((#u) * ln_10)),
+        "log" => {
+            let b = &args[1];
+            return derive(&parse_quote!(ln(#u) / ln(#b)), var);
+        }
+        "exp" => du.mul(parse_quote!(exp(#u))),
+        "pow" => {
+            let x = &args[1];
+            let dx = derive(x, var)?;
+            if dx.is_zero() {
+                du.mul(parse_quote!((#x) * pow(#u, (#x) - 1)))
+            } else if du.is_zero() {
+                dx.mul(parse_quote!(pow(#u, #x) * ln(#u)))
+            } else {
+                dx.mul(parse_quote!(ln(#u)))
+                    .add(du.mul(parse_quote!(#x)).div(parse_quote!(#u)))
+                    .mul(parse_quote!(pow(#u, #x)))
+            }
+        }
+        "sin" => du.mul(parse_quote!(cos(#u))),
+        "cos" => du.mul(parse_quote!(sin(#u))).neg(),
+        "tan" => du.div(parse_quote!(cos(#u) * cos(#u))),
+        "asin" => du.div(parse_quote!(sqrt(1 - (#u) * (#u)))),
+        "acos" => du.div(parse_quote!(sqrt(1 - (#u) * (#u)))).neg(),
+        "atan" => du.div(parse_quote!(1 + (#u) * (#u))),
+        "sinh" => du.mul(parse_quote!(cosh(#u))),
+        "cosh" => du.mul(parse_quote!(sinh(#u))),
+        "tanh" => du.div(parse_quote!(cosh(#u) * cosh(#u))),
+        "asinh" => du.div(parse_quote!(sqrt((#u) * (#u) + 1))),
+        "acosh" => du.div(parse_quote!(sqrt((#u) * (#u) - 1))),
+        "atanh" => du.div(parse_quote!(1 - (#u) * (#u))),
+        "sech" => du.mul(parse_quote!(sech(#u) * tanh(#u))).neg(),
+        "csch" => du.mul(parse_quote!(csch(#u) * coth(#u))).neg(),
+        "coth" => du.div(parse_quote!(sinh(#u) * sinh(#u))).neg(),
+        "asech" => du.div(parse_quote!((#u) * sqrt(1 - (#u) * (#u)))).neg(),
+        "acsch" => du
+            .div(parse_quote!((#u) * (#u) * sqrt(1 + 1 / ((#u) * (#u)))))
+            .neg(),
+        "acoth" => du.div(parse_quote!(1 - (#u) * (#u))),
+        "besselj" | "bessely" | "besseli" | "besselk" => {
+            // the order is the first argument
+            if !du.is_zero() {
+                return Err(Error::new(
+                    u.span(),
+                    "the order of a Bessel function can't depend on the variable of differentiation.",
+                ));
+            }
+
+            let x = &args[1];
+            let dx = derive(x, var)?;
+            let f = Ident::new(&fname, expr.func.span());
+            let (prev, next): (Expr, Expr) = (
+                parse_quote!(#f((#u) - 1, #x)),
+                parse_quote!(#f((#u) + 1, #x)),
+            );
+
+            match fname.as_str() {
+                "besseli" => dx.mul(parse_quote!(((#prev) + (#next)) / 2)),
+                "besselk" => dx.mul(parse_quote!(((#prev) + (#next)) / 2)).neg(),
+                _ => dx.mul(parse_quote!(((#prev) - (#next)) / 2)),
+            }
+        }
+        "gamma" => {
+            if du.is_zero() {
+                Deriv::Zero
+            } else {
+                return Err(Error::new(
+                    expr.span(),
+                    "the derivative of \"gamma\" is not supported.",
+                ));
+            }
+        }
+        _ => return Err(Error::new(expr.span(), "unexpected function name.")),
+    };
+
+    Ok(ret)
+}
+
+// Returns the expression `expr` with the variable `from` renamed to `to`.
+fn rename_var(expr: &Expr, from: &Ident, to: &Ident) -> Result<Expr, Error> {
+    fn rename(ts: TokenStream, from: &Ident, to: &Ident) -> TokenStream {
+        ts.into_iter()
+            .map(|tt| match tt {
+                TokenTree::Ident(id) if id == *from => TokenTree::Ident(to.clone()),
+                TokenTree::Group(g) => {
+                    let mut ng = Group::new(g.delimiter(), rename(g.stream(), from, to));
+                    ng.set_span(g.span());
+                    TokenTree::Group(ng)
+                }
+                tt => tt,
+            })
+            .collect()
+    }
+
+    syn::parse2(rename(expr.to_token_stream(), from, to))
+}
@@ -5,16 +5,19 @@
 #![deny(missing_docs)]
 #![deny(clippy::suspicious)]
 
+mod deriv;
+mod literal;
 mod util;
 
 use astro_float_num::{Consts, EXPONENT_BIT_SIZE};
+use literal::{literal_to_bigfloat_expr, LiteralInput};
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
     parse::Parse, spanned::Spanned, BinOp, Error, Expr, ExprBinary, ExprCall, ExprGroup, ExprLit,
-    ExprParen, ExprPath, ExprUnary, Lit, Token, UnOp,
+    ExprMethodCall, ExprParen, ExprPath, ExprUnary, Lit, Token, UnOp,
 };
-use util::{check_arg_num, str_to_bigfloat_expr};
+use util::{check_arg_num, str_to_bigfloat_expr, var_name};
 
 // Speculative error estimation.
 // This error is added upfront, before actual error is known.
@@ -60,7 +63,7 @@ fn traverse_binary(
                             let newerr = (e1.max(e2) as isize - e3 as isize).unsigned_abs() + 1;
                             if errs[#errs_id] < newerr {
                                 errs[#errs_id] = newerr;
-                                continue;
+                                continue 'expr;
                             }
                         }
                     }
@@ -80,7 +83,7 @@ fn traverse_binary(
                             let newerr = (e1.max(e2) as isize - e3 as isize).unsigned_abs() + 1;
                             if errs[#errs_id] < newerr {
                                 errs[#errs_id] = newerr;
-                                continue;
+                                continue 'expr;
                             }
                         }
                     }
@@ -109,26 +112,37 @@ fn traverse_binary(
     Ok(ts)
 }
 
+// Returns the code which computes the built-in function `fun` of the arguments `args`,
+// using the cache of function calls of the context if memoization is enabled.
+fn call_fun(fun: &TokenStream, args: TokenStream) -> TokenStream {
+    quote!(astro_float::macro_util::call(
+        call_cache.as_deref_mut(),
+        astro_float::ctx::Func::#fun,
+        &[#args],
+        p_wrk,
+        astro_float::RoundingMode::None,
+        cc
+    ))
+}
+
 fn one_arg_fun(
     fun: TokenStream,
     expr: &ExprCall,
     initial_err: usize,
     err: &mut Vec<usize>,
     cc: &mut Consts,
-    use_cc: bool,
 ) -> Result<TokenStream, Error> {
     check_arg_num(1, expr)?;
 
     let arg = traverse_expr(&expr.args[0], err, cc)?;
     err.push(initial_err);
 
-    let ret = if use_cc {
-        quote!(#fun(&(#arg), p_wrk, astro_float::RoundingMode::None, cc))
-    } else {
-        quote!(#fun(&(#arg), p_wrk, astro_float::RoundingMode::None))
-    };
+    let call = call_fun(&fun, quote!(&arg));
 
-    Ok(ret)
+    Ok(quote!({
+        let arg = #arg;
+        #call
+    }))
 }
 
 fn one_arg_fun_errcheck(
@@ -145,16 +159,18 @@ fn one_arg_fun_errcheck(
     let errs_id = err.len();
     err.push(initial_err);
 
+    let call = call_fun(&fun, quote!(&arg));
+
     Ok(quote!({
         let arg = #arg;
 
         let newerr = astro_float::macro_util::compute_added_err(#errcheck);
         if errs[#errs_id] < newerr {
             errs[#errs_id] = newerr;
-            continue;
+            continue 'expr;
         }
 
-        #fun(&arg, p_wrk, astro_float::RoundingMode::None, cc)
+        #call
     }))
 }
 
@@ -172,16 +188,40 @@ fn trig_fun(
     let errs_id = err.len();
     err.push(initial_err);
 
+    let call = call_fun(&fun, quote!(&arg));
+
     Ok(quote!({
-        let arg = astro_float::macro_util::check_exponent_range(#arg, emin, emax);
+        let arg = astro_float::macro_util::check_exponent_range_sticky(#arg, emin, emax, &mut flags);
 
         let newerr = astro_float::macro_util::compute_added_err(astro_float::macro_util::ErrAlgo::Trig(&arg, p_wrk, #errfun, cc, emin));
         if errs[#errs_id] < newerr {
             errs[#errs_id] = newerr;
-            continue;
+            continue 'expr;
         }
 
-        #fun(&arg, p_wrk, astro_float::RoundingMode::None, cc)
+        #call
+    }))
+}
+
+fn two_arg_fun(
+    fun: TokenStream,
+    expr: &ExprCall,
+    initial_err: usize,
+    err: &mut Vec<usize>,
+    cc: &mut Consts,
+) -> Result<TokenStream, Error> {
+    check_arg_num(2, expr)?;
+
+    let arg1 = traverse_expr(&expr.args[0], err, cc)?;
+    let arg2 = traverse_expr(&expr.args[1], err, cc)?;
+    err.push(initial_err);
+
+    let call = call_fun(&fun, quote!(&arg1, &arg2));
+
+    Ok(quote!({
+        let arg1 = #arg1;
+        let arg2 = #arg2;
+        #call
     }))
 }
 
@@ -202,6 +242,8 @@ fn two_arg_fun_errcheck(
 
     err.push(initial_err);
 
+    let call = call_fun(&fun, quote!(&arg1, &arg2));
+
     Ok(quote!({
         let arg1 = #arg1;
         let arg2 = #arg2;
@@ -209,10 +251,160 @@ fn two_arg_fun_errcheck(
         let newerr = astro_float::macro_util::compute_added_err(#errcheck);
         if errs[#errs_id] < newerr {
             errs[#errs_id] = newerr;
-            continue;
+            continue 'expr;
+        }
+
+        #call
+    }))
+}
+
+fn bessel_fun(
+    fun: TokenStream,
+    expr: &ExprCall,
+    initial_err: usize,
+    err: &mut Vec<usize>,
+    errfun: TokenStream,
+    cc: &mut Consts,
+) -> Result<TokenStream, Error> {
+    check_arg_num(2, expr)?;
+
+    let arg1 = traverse_expr(&expr.args[0], err, cc)?;
+    let arg2 = traverse_expr(&expr.args[1], err, cc)?;
+
+    let errs_id = err.len();
+
+    err.push(initial_err);
+
+    let call = call_fun(&fun, quote!(&arg, &n));
+
+    // the order is the first argument of the function in the expression
+    Ok(quote!({
+        let n = #arg1;
+        let arg = #arg2;
+
+        let newerr = astro_float::macro_util::compute_added_err(astro_float::macro_util::ErrAlgo::Bessel(&arg, &n, #errfun, cc, emin));
+        if errs[#errs_id] < newerr {
+            errs[#errs_id] = newerr;
+            continue 'expr;
         }
 
-        #fun(&arg1, &arg2, p_wrk, astro_float::RoundingMode::None, cc)
+        #call
+    }))
+}
+
+// Returns the code which increases the working precision according to the number of terms `cnt` of a sum or a product.
+fn series_cnt_check(cnt: TokenStream, err: &mut Vec<usize>) -> TokenStream {
+    let errs_id = err.len();
+    err.push(4);
+
+    quote!({
+        let cnt: u128 = #cnt;
+        let newerr = (u128::BITS - cnt.leading_zeros()) as usize + 2;
+        if errs[#errs_id] < newerr {
+            errs[#errs_id] = newerr;
+            continue 'expr;
+        }
+    })
+}
+
+// Returns the initial value of a sum or a product, and the code which adds or multiplies `series_ret` by the term `arg`.
+fn series_op(is_sum: bool, err: &mut Vec<usize>) -> (TokenStream, TokenStream) {
+    if is_sum {
+        let errs_id = err.len();
+        err.push(2);
+        (
+            quote!(astro_float::BigFloat::from_word(0, p_wrk)),
+            quote!({
+                let ret = astro_float::BigFloat::add(&series_ret, &arg, p_wrk, astro_float::RoundingMode::None);
+                if series_ret.inexact() || arg.inexact() {
+                    if let (Some(e1), Some(e2), Some(e3)) = (series_ret.exponent(), arg.exponent(), ret.exponent()) {
+                        if (e1 as isize - e2 as isize).abs() <= 1 && series_ret.sign() != arg.sign() {
+                            let newerr = (e1.max(e2) as isize - e3 as isize).unsigned_abs() + 1;
+                            if errs[#errs_id] < newerr {
+                                errs[#errs_id] = newerr;
+                                continue 'expr;
+                            }
+                        }
+                    }
+                }
+                ret
+            }),
+        )
+    } else {
+        (
+            quote!(astro_float::BigFloat::from_word(1, p_wrk)),
+            quote!(astro_float::BigFloat::mul(
+                &series_ret,
+                &arg,
+                p_wrk,
+                astro_float::RoundingMode::None
+            )),
+        )
+    }
+}
+
+fn series_fun(
+    expr: &ExprCall,
+    is_sum: bool,
+    err: &mut Vec<usize>,
+    cc: &mut Consts,
+) -> Result<TokenStream, Error> {
+    check_arg_num(4, expr)?;
+
+    let var = var_name(&expr.args[0], "index variable")?;
+    let lo = &expr.args[1];
+    let hi = &expr.args[2];
+    let term = traverse_expr(&expr.args[3], err, cc)?;
+
+    // the error grows with the number of terms,
+    // and in addition to that a sum can lose precision because of cancellation
+    let cnt_check = series_cnt_check(
+        quote!((series_hi as i128 - series_lo as i128 + 1).max(0) as u128),
+        err,
+    );
+
+    let (init, op) = series_op(is_sum, err);
+
+    Ok(quote!({
+        let series_lo = (#lo) as i64;
+        let series_hi = (#hi) as i64;
+
+        #cnt_check
+
+        let mut series_ret = #init;
+        for #var in series_lo..=series_hi {
+            let arg = #term;
+            series_ret = #op;
+        }
+
+        series_ret
+    }))
+}
+
+fn slice_fun(expr: &ExprCall, is_sum: bool, err: &mut Vec<usize>) -> Result<TokenStream, Error> {
+    check_arg_num(1, expr)?;
+
+    let v = &expr.args[0];
+
+    // the elements are converted exactly, like variables
+    let elements = quote!((#v).iter().map(|x| {
+        let mut arg = astro_float::BigFloat::from_ext(x.clone(), p_wrk, astro_float::RoundingMode::ToEven, cc);
+        arg.set_inexact(false);
+        astro_float::macro_util::check_exponent_range_sticky(arg, emin, emax, &mut flags)
+    }));
+
+    let cnt_check = series_cnt_check(quote!((#v).len() as u128), err);
+    let (init, op) = series_op(is_sum, err);
+
+    Ok(quote!({
+        #cnt_check
+
+        let mut series_ret = #init;
+        for arg in #elements {
+            series_ret = #op;
+        }
+
+        series_ret
     }))
 }
 
@@ -221,23 +413,22 @@ fn traverse_call(
     err: &mut Vec<usize>,
     cc: &mut Consts,
 ) -> Result<TokenStream, Error> {
-    let errmes = "unexpected function name. Only \"recip\", \"sqrt\", \"cbrt\", \"ln\", \"log2\", \"log10\", \"log\", \"exp\", \"pow\", \"sin\", \"cos\", \"tan\", \"asin\", \"acos\", \"atan\", \"sinh\", \"cosh\", \"tanh\", \"asinh\", \"acosh\", \"atanh\" are allowed.";
+    let errmes = "unexpected function name. Only \"recip\", \"sqrt\", \"cbrt\", \"hypot\", \"ln\", \"log2\", \"log10\", \"log\", \"exp\", \"pow\", \"sin\", \"cos\", \"tan\", \"asin\", \"acos\", \"atan\", \"sinh\", \"cosh\", \"tanh\", \"asinh\", \"acosh\", \"atanh\", \"sech\", \"csch\", \"coth\", \"asech\", \"acsch\", \"acoth\", \"gamma\", \"besselj\", \"bessely\", \"besseli\", \"besselk\", \"sum\", \"product\", \"sum_of\", \"product_of\", \"deriv\" are allowed.";
 
     if let Expr::Path(fun) = expr.func.as_ref() {
         if let Some(fname) = fun.path.get_ident() {
             let ts = match fname.to_string().as_str() {
-                "recip" => one_arg_fun(
-                    quote!(astro_float::BigFloat::reciprocal),
-                    expr,
-                    2,
-                    err,
-                    cc,
-                    false,
-                ),
-                "sqrt" => one_arg_fun(quote!(astro_float::BigFloat::sqrt), expr, 1, err, cc, false),
-                "cbrt" => one_arg_fun(quote!(astro_float::BigFloat::cbrt), expr, 1, err, cc, false),
+                "sum" => series_fun(expr, true, err, cc),
+                "product" => series_fun(expr, false, err, cc),
+                "sum_of" => slice_fun(expr, true, err),
+                "product_of" => slice_fun(expr, false, err),
+                "deriv" => deriv::deriv_call(expr).and_then(|f| traverse_expr(&f, err, cc)),
+                "recip" => one_arg_fun(quote!(Recip), expr, 2, err, cc),
+                "sqrt" => one_arg_fun(quote!(Sqrt), expr, 1, err, cc),
+                "cbrt" => one_arg_fun(quote!(Cbrt), expr, 1, err, cc),
+                "hypot" => two_arg_fun(quote!(Hypot), expr, 2, err, cc),
                 "ln" => one_arg_fun_errcheck(
-                    quote!(astro_float::BigFloat::ln),
+                    quote!(Ln),
                     expr,
                     SPEC_ADD_ERR,
                     err,
@@ -245,7 +436,7 @@ fn traverse_call(
                     cc,
                 ),
                 "log2" => one_arg_fun_errcheck(
-                    quote!(astro_float::BigFloat::log2),
+                    quote!(Log2),
                     expr,
                     SPEC_ADD_ERR,
                     err,
@@ -253,7 +444,7 @@ fn traverse_call(
                     cc,
                 ),
                 "log10" => one_arg_fun_errcheck(
-                    quote!(astro_float::BigFloat::log10),
+                    quote!(Log10),
                     expr,
                     SPEC_ADD_ERR,
                     err,
@@ -261,23 +452,16 @@ fn traverse_call(
                     cc,
                 ),
                 "log" => two_arg_fun_errcheck(
-                    quote!(astro_float::BigFloat::log),
+                    quote!(Log),
                     expr,
                     SPEC_ADD_ERR,
                     err,
                     quote!(astro_float::macro_util::ErrAlgo::Log2(&arg2, &arg1, emin)),
                     cc,
                 ),
-                "exp" => one_arg_fun(
-                    quote!(astro_float::BigFloat::exp),
-                    expr,
-                    EXPONENT_BIT_SIZE + 1,
-                    err,
-                    cc,
-                    true,
-                ),
+                "exp" => one_arg_fun(quote!(Exp), expr, EXPONENT_BIT_SIZE + 1, err, cc),
                 "pow" => two_arg_fun_errcheck(
-                    quote!(astro_float::BigFloat::pow),
+                    quote!(Pow),
                     expr,
                     EXPONENT_BIT_SIZE + SPEC_ADD_ERR,
                     err,
@@ -285,7 +469,7 @@ fn traverse_call(
                     cc,
                 ),
                 "sin" => trig_fun(
-                    quote!(astro_float::BigFloat::sin),
+                    quote!(Sin),
                     expr,
                     SPEC_ADD_ERR,
                     err,
@@ -293,7 +477,7 @@ fn traverse_call(
                     cc,
                 ),
                 "cos" => trig_fun(
-                    quote!(astro_float::BigFloat::cos),
+                    quote!(Cos),
                     expr,
                     SPEC_ADD_ERR,
                     err,
@@ -301,7 +485,7 @@ fn traverse_call(
                     cc,
                 ),
                 "tan" => trig_fun(
-                    quote!(astro_float::BigFloat::tan),
+                    quote!(Tan),
                     expr,
                     SPEC_ADD_ERR,
                     err,
@@ -309,7 +493,7 @@ fn traverse_call(
                     cc,
                 ),
                 "asin" => one_arg_fun_errcheck(
-                    quote!(astro_float::BigFloat::asin),
+                    quote!(Asin),
                     expr,
                     SPEC_ADD_ERR / 2,
                     err,
@@ -317,48 +501,92 @@ fn traverse_call(
                     cc,
                 ),
                 "acos" => one_arg_fun_errcheck(
-                    quote!(astro_float::BigFloat::acos),
+                    quote!(Acos),
                     expr,
                     SPEC_ADD_ERR / 2,
                     err,
                     quote!(astro_float::macro_util::ErrAlgo::Acos(&arg, emin)),
                     cc,
                 ),
-                "atan" => one_arg_fun(quote!(astro_float::BigFloat::atan), expr, 2, err, cc, true),
-                "sinh" => one_arg_fun(
-                    quote!(astro_float::BigFloat::sinh),
+                "atan" => one_arg_fun(quote!(Atan), expr, 2, err, cc),
+                "sinh" => one_arg_fun(quote!(Sinh), expr, EXPONENT_BIT_SIZE + 1, err, cc),
+                "cosh" => one_arg_fun(quote!(Cosh), expr, EXPONENT_BIT_SIZE + 1, err, cc),
+                "tanh" => one_arg_fun(quote!(Tanh), expr, 2, err, cc),
+                "asinh" => one_arg_fun(quote!(Asinh), expr, 2, err, cc),
+                "acosh" => one_arg_fun_errcheck(
+                    quote!(Acosh),
                     expr,
-                    EXPONENT_BIT_SIZE + 1,
+                    SPEC_ADD_ERR,
                     err,
+                    quote!(astro_float::macro_util::ErrAlgo::Acosh(&arg, emin)),
                     cc,
-                    true,
                 ),
-                "cosh" => one_arg_fun(
-                    quote!(astro_float::BigFloat::cosh),
+                "atanh" => one_arg_fun_errcheck(
+                    quote!(Atanh),
                     expr,
-                    EXPONENT_BIT_SIZE + 1,
+                    SPEC_ADD_ERR,
                     err,
+                    quote!(astro_float::macro_util::ErrAlgo::Atanh(&arg, emin)),
                     cc,
-                    true,
                 ),
-                "tanh" => one_arg_fun(quote!(astro_float::BigFloat::tanh), expr, 2, err, cc, true),
-                "asinh" => {
-                    one_arg_fun(quote!(astro_float::BigFloat::asinh), expr, 2, err, cc, true)
-                }
-                "acosh" => one_arg_fun_errcheck(
-                    quote!(astro_float::BigFloat::acosh),
+                "sech" => one_arg_fun(quote!(Sech), expr, EXPONENT_BIT_SIZE + 1, err, cc),
+                "csch" => one_arg_fun(quote!(Csch), expr, EXPONENT_BIT_SIZE + 1, err, cc),
+                "coth" => one_arg_fun(quote!(Coth), expr, 2, err, cc),
+                "asech" => one_arg_fun_errcheck(
+                    quote!(Asech),
                     expr,
                     SPEC_ADD_ERR,
                     err,
-                    quote!(astro_float::macro_util::ErrAlgo::Acosh(&arg, emin)),
+                    quote!(astro_float::macro_util::ErrAlgo::Asech(&arg, emin)),
                     cc,
                 ),
-                "atanh" => one_arg_fun_errcheck(
-                    quote!(astro_float::BigFloat::atanh),
+                "acsch" => one_arg_fun(quote!(Acsch), expr, 2, err, cc),
+                "acoth" => one_arg_fun_errcheck(
+                    quote!(Acoth),
                     expr,
                     SPEC_ADD_ERR,
                     err,
-                    quote!(astro_float::macro_util::ErrAlgo::Atanh(&arg, emin)),
+                    quote!(astro_float::macro_util::ErrAlgo::Acoth(&arg, emin)),
+                    cc,
+                ),
+                "gamma" => one_arg_fun_errcheck(
+                    quote!(Gamma),
+                    expr,
+                    SPEC_ADD_ERR,
+                    err,
+                    quote!(astro_float::macro_util::ErrAlgo::Gamma(&arg, emin)),
+                    cc,
+                ),
+                "besselj" => bessel_fun(
+                    quote!(BesselJ),
+                    expr,
+                    SPEC_ADD_ERR,
+                    err,
+                    quote!(astro_float::macro_util::BesselFun::J),
+                    cc,
+                ),
+                "bessely" => bessel_fun(
+                    quote!(BesselY),
+                    expr,
+                    SPEC_ADD_ERR,
+                    err,
+                    quote!(astro_float::macro_util::BesselFun::Y),
+                    cc,
+                ),
+                "besseli" => bessel_fun(
+                    quote!(BesselI),
+                    expr,
+                    SPEC_ADD_ERR,
+                    err,
+                    quote!(astro_float::macro_util::BesselFun::I),
+                    cc,
+                ),
+                "besselk" => bessel_fun(
+                    quote!(BesselK),
+                    expr,
+                    SPEC_ADD_ERR,
+                    err,
+                    quote!(astro_float::macro_util::BesselFun::K),
                     cc,
                 ),
                 _ => return Err(Error::new(expr.span(), errmes)),
@@ -409,16 +637,37 @@ fn traverse_path(expr: &ExprPath) -> Result<TokenStream, Error> {
         quote!({ cc.ln_2(p_wrk, astro_float::RoundingMode::None) })
     } else if expr.path.is_ident("ln_10") {
         quote!({ cc.ln_10(p_wrk, astro_float::RoundingMode::None) })
+    } else if expr.path.is_ident("sqrt_2") {
+        quote!({ cc.sqrt_2(p_wrk, astro_float::RoundingMode::None) })
+    } else if expr.path.is_ident("phi") {
+        quote!({ cc.phi(p_wrk, astro_float::RoundingMode::None) })
+    } else if expr.path.is_ident("ln_pi") {
+        quote!({ cc.ln_pi(p_wrk, astro_float::RoundingMode::None) })
     } else {
         quote!({
             let mut arg = astro_float::BigFloat::from_ext((#expr).clone(), p_wrk, astro_float::RoundingMode::ToEven, cc);
             arg.set_inexact(false);
-            arg = astro_float::macro_util::check_exponent_range(arg, emin, emax);
+            arg = astro_float::macro_util::check_exponent_range_sticky(arg, emin, emax, &mut flags);
             arg
         })
     })
 }
 
+fn traverse_method_call(expr: &ExprMethodCall) -> Result<TokenStream, Error> {
+    if expr.method == "len" && expr.args.is_empty() {
+        let receiver = &expr.receiver;
+        Ok(quote!({
+            let arg = astro_float::BigFloat::from_u64((#receiver).len() as u64, p_wrk);
+            astro_float::macro_util::check_exponent_range_sticky(arg, emin, emax, &mut flags)
+        }))
+    } else {
+        Err(Error::new(
+            expr.span(),
+            "unexpected method call. Only \"len()\" is allowed.",
+        ))
+    }
+}
+
 fn traverse_unary(
     expr: &ExprUnary,
     err: &mut Vec<usize>,
@@ -441,15 +690,17 @@ fn traverse_expr(expr: &Expr, err: &mut Vec<usize>, cc: &mut Consts) -> Result<T
         Expr::Call(e) => traverse_call(e, err,cc),
         Expr::Group(e) => traverse_group(e, err,cc),
         Expr::Lit(e) => traverse_lit(e, cc),
+        Expr::MethodCall(e) => traverse_method_call(e),
         Expr::Paren(e) => traverse_paren(e, err, cc),
         Expr::Path(e) => traverse_path(e),
         Expr::Unary(e) => traverse_unary(e, err, cc),
-        _ => Err(Error::new(expr.span(), "unexpected expression. Only operators \"+\", \"-\", \"*\", \"/\", \"%\", functions \"recip\", \"sqrt\", \"cbrt\", \"ln\", \"log2\", \"log10\", \"log\", \"exp\", \"pow\", \"sin\", \"cos\", \"tan\", \"asin\", \"acos\", \"atan\", \"sinh\", \"cosh\", \"tanh\", \"asinh\", \"acosh\", \"atanh\", literals and variables, and grouping with parentheses are supported.")),
+        _ => Err(Error::new(expr.span(), "unexpected expression. Only operators \"+\", \"-\", \"*\", \"/\", \"%\", functions \"recip\", \"sqrt\", \"cbrt\", \"hypot\", \"ln\", \"log2\", \"log10\", \"log\", \"exp\", \"pow\", \"sin\", \"cos\", \"tan\", \"asin\", \"acos\", \"atan\", \"sinh\", \"cosh\", \"tanh\", \"asinh\", \"acosh\", \"atanh\", \"sech\", \"csch\", \"coth\", \"asech\", \"acsch\", \"acoth\", \"gamma\", \"besselj\", \"bessely\", \"besseli\", \"besselk\", finite sums \"sum\" and products \"product\", sums \"sum_of\" and products \"product_of\" of slices, \"len()\" of slices, derivatives \"deriv\", literals and variables, and grouping with parentheses are supported.")),
     }
 }
 
 // Docs for the macro are in the astro-float crate.
 
+#[allow(clippy::empty_docs)]
 ///
 #[proc_macro]
 pub fn expr(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -474,23 +725,41 @@ pub fn expr(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         let rm = ctx.rounding_mode();
         let emin = ctx.emin();
         let emax = ctx.emax();
-        let cc = ctx.consts();
+        let range_mode = ctx.range_mode();
+        #[allow(unused_mut, unused_variables)]
+        let (cc, mut call_cache) = ctx.consts_and_call_cache();
 
         let mut p_rnd = p + astro_float::WORD_BIT_SIZE;
         let mut errs: [usize; #err_sz] = [#(#err, )*];
+        let mut flags = astro_float::ctx::Status::default();
 
-        loop {
+        let ret = 'expr: loop {
             let p_wrk = p_rnd.saturating_add(errs.iter().sum());
 
-            let mut ret: astro_float::BigFloat = (#expr).into();
+            let ret: astro_float::BigFloat = (#expr).into();
 
-            if let Err(err) = ret.set_precision(p, rm) {
-                ret = astro_float::BigFloat::nan(Some(err));
-            }
+            break astro_float::macro_util::round_to_exponent_range(ret, p, rm, emin, emax, range_mode, &mut flags);
+        };
 
-            break astro_float::macro_util::check_exponent_range(ret, emin, emax);
-        }
+        let ret = ctx.tag_result(ret);
+        ctx.raise_flags(&ret, flags);
+
+        ret
     });
 
     ret.into()
 }
+
+// Docs for the macro are in the astro-float crate.
+
+/// Constructs a number from a literal parsed at compile time.
+#[proc_macro]
+pub fn bigfloat(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as LiteralInput);
+
+    let mut cc = Consts::new().expect("Failed to initialize constant cache.");
+
+    literal_to_bigfloat_expr(&input, &mut cc)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,70 @@
+//! Compile-time BigFloat literals.
+
+use crate::util::raw_parts_expr;
+use astro_float_num::{BigFloat, Consts, Radix, RoundingMode};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse::Parse, Error, Lit, LitInt, Token};
+
+/// Input of the macro `bigfloat`.
+pub struct LiteralInput {
+    neg: bool,
+    lit: Lit,
+    p: LitInt,
+}
+
+impl Parse for LiteralInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let neg = input.parse::<Option<Token![-]>>()?.is_some();
+        let lit = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let p = input.parse()?;
+
+        Ok(LiteralInput { neg, lit, p })
+    }
+}
+
+/// Parses the literal and returns the code constructing the number from its raw parts.
+pub fn literal_to_bigfloat_expr(
+    input: &LiteralInput,
+    cc: &mut Consts,
+) -> Result<TokenStream, Error> {
+    let LiteralInput { neg, lit, p } = input;
+
+    let s = match lit {
+        Lit::Str(v) => v.value(),
+        Lit::Int(v) => v.base10_digits().to_owned(),
+        Lit::Float(v) => v.base10_digits().to_owned(),
+        _ => {
+            return Err(Error::new(
+                lit.span(),
+                "expected a string, an integer, or a floating point literal.",
+            ))
+        }
+    };
+    let s = if *neg { format!("-{}", s) } else { s };
+
+    // the precision of the mantissa stored in 64-bit words
+    let p = p.base10_parse::<usize>()?.saturating_add(63) / 64 * 64;
+
+    let f = BigFloat::parse(&s, Radix::Dec, p, RoundingMode::ToEven, cc);
+    if let Some(err) = f.err() {
+        return Err(Error::new(
+            lit.span(),
+            format!("failed to parse BigFloat from {}: {}", s, err),
+        ));
+    }
+
+    let q = if let Some(raw) = raw_parts_expr(&f) {
+        raw
+    } else if f.is_inf_pos() {
+        quote!(astro_float::INF_POS)
+    } else if f.is_inf_neg() {
+        quote!(astro_float::INF_NEG)
+    } else {
+        quote!(astro_float::NAN)
+    };
+
+    Ok(q)
+}
@@ -4,12 +4,15 @@ use astro_float_num::BigFloat;
 use astro_float_num::Consts;
 use astro_float_num::Radix;
 use astro_float_num::RoundingMode;
+use astro_float_num::WORD_BIT_SIZE;
 use proc_macro2::Span;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::spanned::Spanned;
 use syn::Error;
+use syn::Expr;
 use syn::ExprCall;
+use syn::Ident;
 
 pub fn str_to_bigfloat_expr(s: &str, span: Span, cc: &mut Consts) -> Result<TokenStream, Error> {
     let f = BigFloat::parse(s, Radix::Dec, usize::MAX, RoundingMode::ToEven, cc);
@@ -21,14 +24,9 @@ pub fn str_to_bigfloat_expr(s: &str, span: Span, cc: &mut Consts) -> Result<Toke
     }
 
     let q = if f.inexact() {
-        quote!(astro_float::macro_util::check_exponent_range(astro_float::BigFloat::parse(#s, astro_float::Radix::Dec, p_wrk, astro_float::RoundingMode::ToEven, cc), emin, emax))
-    } else if let Some((m, n, s, e, inexact)) = f.as_raw_parts() {
-        let stoken = if s.is_positive() {
-            quote!(astro_float::Sign::Pos)
-        } else {
-            quote!(astro_float::Sign::Neg)
-        };
-        quote!(astro_float::macro_util::check_exponent_range(astro_float::BigFloat::from_raw_parts(&[#(#m),*], #n, #stoken, #e, #inexact), emin, emax))
+        quote!(astro_float::macro_util::check_exponent_range_sticky(astro_float::BigFloat::parse(#s, astro_float::Radix::Dec, p_wrk, astro_float::RoundingMode::ToEven, cc), emin, emax, &mut flags))
+    } else if let Some(raw) = raw_parts_expr(&f) {
+        quote!(astro_float::macro_util::check_exponent_range_sticky(#raw, emin, emax, &mut flags))
     } else {
         quote!(astro_float::BigFloat::nan())
     };
@@ -36,12 +34,54 @@ pub fn str_to_bigfloat_expr(s: &str, span: Span, cc: &mut Consts) -> Result<Toke
     Ok(q)
 }
 
+/// Returns the code constructing `f` from its raw parts, or None if `f` is Inf or NaN.
+/// The mantissa is stored in 64-bit words regardless of the word size of the host and the target.
+pub fn raw_parts_expr(f: &BigFloat) -> Option<TokenStream> {
+    let (m, n, s, e, inexact) = f.as_raw_parts()?;
+
+    #[allow(clippy::unnecessary_cast)]
+    let words = m.iter().map(|w| *w as u64);
+
+    let (m, n): (Vec<u64>, usize) = if WORD_BIT_SIZE == 64 {
+        (words.collect(), n)
+    } else {
+        // an odd number of 32-bit words is padded with a zero least significant word
+        let pad = m.len() % 2;
+        let words: Vec<u64> = core::iter::repeat_n(0, pad).chain(words).collect();
+        (words.chunks(2).map(|w| w[0] | w[1] << 32).collect(), n + pad * 32)
+    };
+
+    let stoken = if s.is_positive() {
+        quote!(astro_float::Sign::Pos)
+    } else {
+        quote!(astro_float::Sign::Neg)
+    };
+
+    Some(quote!(astro_float::macro_util::from_u64_words(&[#(#m),*], #n, #stoken, #e, #inexact)))
+}
+
 pub fn check_arg_num(narg: usize, expr: &ExprCall) -> Result<(), Error> {
     if expr.args.len() != narg {
         return Err(Error::new(
             expr.func.span(),
-            if narg == 1 { "expected 1 argument." } else { "expected 2 arguments." },
+            if narg == 1 {
+                "expected 1 argument.".to_owned()
+            } else {
+                format!("expected {} arguments.", narg)
+            },
         ));
     }
     Ok(())
 }
+
+pub fn var_name(expr: &Expr, what: &str) -> Result<Ident, Error> {
+    if let Expr::Path(v) = expr {
+        if let Some(id) = v.path.get_ident() {
+            return Ok(id.clone());
+        }
+    }
+    Err(Error::new(
+        expr.span(),
+        format!("expected a name of the {}.", what),
+    ))
+}
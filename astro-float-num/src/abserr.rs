@@ -0,0 +1,112 @@
+//! Evaluation with a target absolute error.
+//!
+//! Functions of the library take the precision of the result as an argument, i.e. they bound the relative error of the result.
+//! Near zeros of a function the relative error is the wrong contract: the result can be very small,
+//! and the caller often needs only a bound on the absolute error.
+//! The functions of this module choose the precision of the result from a given bound on the absolute error.
+
+use crate::common::util::round_p;
+use crate::BigFloat;
+use crate::Error;
+use crate::Exponent;
+use crate::RoundingMode;
+use crate::WORD_BIT_SIZE;
+
+/// Evaluates `f` with the absolute error of the result not exceeding `2^e`.
+///
+/// The function `f` is called as `f(p, rm)` and is expected to return the result with precision `p`
+/// rounded according to `rm`, as the built-in functions do, e.g. `|p, rm| x.sin(p, rm, &mut cc)`.
+/// The precision of the result is chosen such that the value of its least significant bit does not exceed `2^e`.
+/// If the exponent of the value computed with the current precision shows that the precision is not sufficient,
+/// `f` is called again with a larger precision.
+///
+/// For rounding to nearest modes the absolute error of the result does not exceed `2^(e-1)`.
+/// If the absolute value of the result is less than `2^e`, the result is computed with the precision of one word.
+///
+/// NaN and infinity returned by `f` are returned as is.
+/// The function returns NaN if the required precision exceeds the maximum value of `usize`.
+pub fn eval_with_abs_err<F>(mut f: F, e: Exponent, rm: RoundingMode) -> BigFloat
+where
+    F: FnMut(usize, RoundingMode) -> BigFloat,
+{
+    let mut p = WORD_BIT_SIZE;
+
+    loop {
+        let ret = f(p, rm);
+
+        if ret.is_nan() || ret.is_inf() || ret.is_zero() {
+            return ret;
+        }
+
+        let ey = match ret.exponent() {
+            Some(ey) => ey,
+            None => return ret,
+        };
+
+        // the value of the least significant bit is 2^(ey - p)
+        let required = ey as isize - e as isize;
+
+        if required <= p as isize {
+            return ret;
+        }
+
+        if required as usize > usize::MAX - WORD_BIT_SIZE {
+            return BigFloat::nan(Some(Error::InvalidArgument));
+        }
+
+        p = round_p(required as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Consts;
+
+    #[test]
+    fn test_eval_with_abs_err() {
+        let mut cc = Consts::new().unwrap();
+
+        // abs(a - b) <= 2^e
+        let check = |a: &BigFloat, b: &BigFloat, e: Exponent| {
+            let mut eps = BigFloat::from_word(1, 1);
+            eps.set_exponent(e + 1);
+            let d = a.sub(b, 4096, RoundingMode::None).abs();
+            assert!(d <= eps, "{:?} {:?}", a, b);
+        };
+
+        // sin of an approximation of pi is small
+        let x = cc.pi(128, RoundingMode::ToEven);
+
+        for e in [-1, -100, -300, -1000] {
+            for rm in [RoundingMode::ToEven, RoundingMode::Down, RoundingMode::Up] {
+                let y = eval_with_abs_err(|p, rm| x.sin(p, rm, &mut cc), e, rm);
+                let z = x.sin(2048, RoundingMode::ToEven, &mut cc);
+                check(&y, &z, e);
+
+                let ey = y.exponent().unwrap();
+                let p = y.mantissa_max_bit_len().unwrap();
+                assert!(ey - (p as Exponent) <= e);
+                assert!(p == WORD_BIT_SIZE || ey - (p - WORD_BIT_SIZE) as Exponent > e);
+            }
+        }
+
+        // large value
+        let x = BigFloat::from_word(100, 64);
+        let y = eval_with_abs_err(|p, rm| x.exp(p, rm, &mut cc), -10, RoundingMode::ToEven);
+        let z = x.exp(1024, RoundingMode::ToEven, &mut cc);
+        check(&y, &z, -10);
+        assert_eq!(y.mantissa_max_bit_len(), Some(round_p(145 + 10)));
+
+        // exact zero
+        let x = BigFloat::new(64);
+        let y = eval_with_abs_err(|p, rm| x.sin(p, rm, &mut cc), -10, RoundingMode::ToEven);
+        assert!(y.is_zero());
+
+        // nan is propagated
+        let x = BigFloat::from_word(2, 64);
+        let y = eval_with_abs_err(|p, rm| x.asin(p, rm, &mut cc), -10, RoundingMode::ToEven);
+        assert!(y.is_nan());
+    }
+}
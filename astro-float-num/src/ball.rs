@@ -0,0 +1,356 @@
+//! Ball arithmetic.
+//!
+//! A ball is a midpoint and a radius which represent the set of all numbers `x` such that `|x - mid| <= rad`.
+//! Each operation on balls returns a ball which contains the results of the operation on all numbers
+//! of the argument balls. The radius of the result accounts for the radii of the arguments and for the rounding error
+//! of the midpoint, so the true value of a computation is guaranteed to be inside of the resulting ball.
+//! The midpoint is computed with the requested precision and rounded to the nearest, and the radius is computed
+//! with a small fixed precision and rounded upwards.
+//!
+//! If a ball can't be bounded, e.g. a ball containing zero is used as a divisor, the result is the indeterminate ball
+//! with NaN midpoint and infinite radius.
+//!
+//! ```
+//! use astro_float_num::ball::BigFloatBall;
+//! use astro_float_num::{BigFloat, Consts};
+//!
+//! let mut cc = Consts::new().unwrap();
+//! let p = 256;
+//!
+//! let x = BigFloatBall::exact(BigFloat::from_word(2, 64));
+//! let y = x.sqrt(p).mul(&x.ln(p, &mut cc), p);
+//!
+//! // the result has at least 250 correct bits
+//! assert!(y.is_finite());
+//! assert!(y.rad().exponent().unwrap() < y.mid().exponent().unwrap() - 250);
+//! ```
+
+use crate::defs::{Error, RoundingMode};
+use crate::{BigFloat, Consts, Exponent, EXPONENT_MIN, INF_POS};
+
+/// Precision of the radius.
+const RAD_P: usize = 64;
+
+/// Number with a rigorous bound of its error: a ball with midpoint `mid` and radius `rad`.
+#[derive(Debug, Clone)]
+pub struct BigFloatBall {
+    mid: BigFloat,
+    rad: BigFloat,
+}
+
+impl BigFloatBall {
+    /// Constructs a ball with the midpoint `mid` and the radius `|rad|`.
+    /// The midpoint is treated as an exact number, i.e. its inexact flag is cleared.
+    pub fn new(mut mid: BigFloat, rad: BigFloat) -> Self {
+        mid.set_inexact(false);
+        let mut rad = rad.abs();
+        if matches!(rad.mantissa_max_bit_len(), Some(p) if p > RAD_P)
+            && rad.set_precision(RAD_P, RoundingMode::Up).is_err()
+        {
+            rad = INF_POS;
+        }
+        BigFloatBall { mid, rad }
+    }
+
+    /// Constructs a ball of radius 0 containing `mid` only.
+    pub fn exact(mid: BigFloat) -> Self {
+        Self::new(mid, BigFloat::new(RAD_P))
+    }
+
+    /// Returns the indeterminate ball with NaN midpoint and infinite radius.
+    pub fn indeterminate() -> Self {
+        BigFloatBall {
+            mid: BigFloat::nan(Some(Error::InvalidArgument)),
+            rad: INF_POS,
+        }
+    }
+
+    /// Returns the midpoint of the ball.
+    pub fn mid(&self) -> &BigFloat {
+        &self.mid
+    }
+
+    /// Returns the radius of the ball.
+    pub fn rad(&self) -> &BigFloat {
+        &self.rad
+    }
+
+    /// Returns true if both the midpoint and the radius are finite numbers.
+    pub fn is_finite(&self) -> bool {
+        self.mid.exponent().is_some() && self.rad.exponent().is_some()
+    }
+
+    /// Returns true if `x` is inside of the ball.
+    pub fn contains(&self, x: &BigFloat) -> bool {
+        let d = x.sub_full_prec(&self.mid);
+        matches!(d.abs_cmp(&self.rad), Some(v) if v <= 0)
+    }
+
+    /// Returns the lower bound of the ball with precision `p` rounded downwards.
+    /// Precision is rounded upwards to the word size.
+    pub fn lower(&self, p: usize) -> BigFloat {
+        self.mid.sub(&self.rad, p, RoundingMode::Down)
+    }
+
+    /// Returns the upper bound of the ball with precision `p` rounded upwards.
+    /// Precision is rounded upwards to the word size.
+    pub fn upper(&self, p: usize) -> BigFloat {
+        self.mid.add(&self.rad, p, RoundingMode::Up)
+    }
+
+    /// Returns the negated ball.
+    pub fn neg(&self) -> Self {
+        BigFloatBall {
+            mid: self.mid.neg(),
+            rad: self.rad.clone(),
+        }
+    }
+
+    /// Adds `d2` to `self`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    pub fn add(&self, d2: &Self, p: usize) -> Self {
+        let mid = self.mid.add(&d2.mid, p, RoundingMode::ToEven);
+        let rad = self.rad.add(&d2.rad, RAD_P, RoundingMode::Up);
+        Self::with_rounding_err(mid, rad)
+    }
+
+    /// Subtracts `d2` from `self`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    pub fn sub(&self, d2: &Self, p: usize) -> Self {
+        let mid = self.mid.sub(&d2.mid, p, RoundingMode::ToEven);
+        let rad = self.rad.add(&d2.rad, RAD_P, RoundingMode::Up);
+        Self::with_rounding_err(mid, rad)
+    }
+
+    /// Multiplies `self` by `d2`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    pub fn mul(&self, d2: &Self, p: usize) -> Self {
+        let rm = RoundingMode::Up;
+        let mid = self.mid.mul(&d2.mid, p, RoundingMode::ToEven);
+
+        // |m1| * r2 + |m2| * r1 + r1 * r2
+        let r1 = self.mid.abs().mul(&d2.rad, RAD_P, rm);
+        let r2 = d2.mid.abs().mul(&self.rad, RAD_P, rm);
+        let r3 = self.rad.mul(&d2.rad, RAD_P, rm);
+        let rad = r1.add(&r2, RAD_P, rm).add(&r3, RAD_P, rm);
+
+        Self::with_rounding_err(mid, rad)
+    }
+
+    /// Divides `self` by `d2`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    /// The result is indeterminate if `d2` contains zero.
+    pub fn div(&self, d2: &Self, p: usize) -> Self {
+        let m2 = d2.mid.abs();
+
+        if !matches!(m2.cmp(&d2.rad), Some(v) if v > 0) {
+            return Self::indeterminate();
+        }
+
+        let mid = self.mid.div(&d2.mid, p, RoundingMode::ToEven);
+
+        // (|m1| * r2 + |m2| * r1) / (|m2| * (|m2| - r2))
+        let n1 = self.mid.abs().mul(&d2.rad, RAD_P, RoundingMode::Up);
+        let n2 = m2.mul(&self.rad, RAD_P, RoundingMode::Up);
+        let n = n1.add(&n2, RAD_P, RoundingMode::Up);
+        let d = m2.sub(&d2.rad, RAD_P, RoundingMode::Down);
+        let d = d.mul(&m2, RAD_P, RoundingMode::Down);
+        let rad = n.div(&d, RAD_P, RoundingMode::Up);
+
+        Self::with_rounding_err(mid, rad)
+    }
+
+    /// Computes the square root of `self`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    /// The result is indeterminate if `self` contains negative numbers.
+    pub fn sqrt(&self, p: usize) -> Self {
+        let lower = self.lower(RAD_P);
+        if lower.is_nan() || (lower.is_negative() && !lower.is_zero()) {
+            return Self::indeterminate();
+        }
+
+        let mid = self.mid.sqrt(p, RoundingMode::ToEven);
+
+        let rad = if lower.is_zero() {
+            // |sqrt(x) - sqrt(m)| <= sqrt(|x - m|)
+            self.rad.sqrt(RAD_P, RoundingMode::Up)
+        } else {
+            // r / (2 * sqrt(m - r))
+            let mut d = lower.sqrt(RAD_P, RoundingMode::Down);
+            if let Some(e) = d.exponent() {
+                d.set_exponent(e + 1);
+            }
+            self.rad.div(&d, RAD_P, RoundingMode::Up)
+        };
+
+        Self::with_rounding_err(mid, rad)
+    }
+
+    /// Computes `e` to the power of `self`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    /// This function requires constants cache `cc` for computing the result.
+    pub fn exp(&self, p: usize, cc: &mut Consts) -> Self {
+        let mid = self.mid.exp(p, RoundingMode::ToEven, cc);
+
+        // r * exp(m + r)
+        let d = self.upper(RAD_P).exp(RAD_P, RoundingMode::Up, cc);
+        let rad = self.rad.mul(&d, RAD_P, RoundingMode::Up);
+
+        Self::with_rounding_err(mid, rad)
+    }
+
+    /// Computes the natural logarithm of `self`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    /// The result is indeterminate if `self` contains zero or negative numbers.
+    /// This function requires constants cache `cc` for computing the result.
+    pub fn ln(&self, p: usize, cc: &mut Consts) -> Self {
+        let lower = self.lower(RAD_P);
+        if !lower.is_positive() || lower.is_zero() {
+            return Self::indeterminate();
+        }
+
+        let mid = self.mid.ln(p, RoundingMode::ToEven, cc);
+
+        // r / (m - r)
+        let rad = self.rad.div(&lower, RAD_P, RoundingMode::Up);
+
+        Self::with_rounding_err(mid, rad)
+    }
+
+    /// Computes the sine of `self`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    /// This function requires constants cache `cc` for computing the result.
+    pub fn sin(&self, p: usize, cc: &mut Consts) -> Self {
+        let mid = self.mid.sin(p, RoundingMode::ToEven, cc);
+        Self::with_rounding_err(mid, self.rad.clone())
+    }
+
+    /// Computes the cosine of `self`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    /// This function requires constants cache `cc` for computing the result.
+    pub fn cos(&self, p: usize, cc: &mut Consts) -> Self {
+        let mid = self.mid.cos(p, RoundingMode::ToEven, cc);
+        Self::with_rounding_err(mid, self.rad.clone())
+    }
+
+    /// Computes the arctangent of `self`. The midpoint of the result is computed with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    /// This function requires constants cache `cc` for computing the result.
+    pub fn atan(&self, p: usize, cc: &mut Consts) -> Self {
+        let mid = self.mid.atan(p, RoundingMode::ToEven, cc);
+        Self::with_rounding_err(mid, self.rad.clone())
+    }
+
+    // Constructs a ball adding the rounding error of `mid` to `rad`.
+    fn with_rounding_err(mut mid: BigFloat, rad: BigFloat) -> Self {
+        let rad = if mid.inexact() {
+            match (mid.exponent(), mid.mantissa_max_bit_len()) {
+                (Some(e), Some(p)) => {
+                    // the error is less than the unit in the last place of `mid`
+                    let e = (e as isize - p as isize + 1).max(EXPONENT_MIN as isize);
+                    let mut ulp = BigFloat::from_word(1, RAD_P);
+                    ulp.set_exponent(e as Exponent);
+                    rad.add(&ulp, RAD_P, RoundingMode::Up)
+                }
+                _ => INF_POS,
+            }
+        } else if mid.exponent().is_none() {
+            INF_POS
+        } else {
+            rad
+        };
+
+        mid.set_inexact(false);
+
+        BigFloatBall { mid, rad }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_ball() {
+        let mut cc = Consts::new().unwrap();
+        const PR: usize = 1024;
+        const RM: RoundingMode = RoundingMode::ToEven;
+
+        let p = 128;
+        let rm = RM;
+        let pr = PR;
+
+        let one = BigFloat::from_word(1, 64);
+        let three = BigFloat::from_word(3, 64);
+        let seven = BigFloat::from_word(7, 64);
+
+        // exact operations don't increase the radius
+        let x = BigFloatBall::exact(three.clone());
+        let y = BigFloatBall::exact(seven.clone());
+        let z = x.add(&y, p).mul(&x, p).sub(&y, p);
+        assert!(z.rad().is_zero());
+        assert_eq!(z.mid(), &BigFloat::from_word(23, p));
+
+        // (1/3 + 1/7) * e / pi
+        let x = BigFloatBall::exact(one.clone()).div(&BigFloatBall::exact(three.clone()), p);
+        let y = BigFloatBall::exact(one.clone()).div(&BigFloatBall::exact(seven.clone()), p);
+        let e = BigFloatBall::exact(one.clone()).exp(p, &mut cc);
+        let mut ulp = BigFloat::from_word(1, 64);
+        ulp.set_exponent(3 - p as Exponent);
+        let pi = BigFloatBall::new(cc.pi(p, rm), ulp);
+        let z = x.add(&y, p).mul(&e, p).div(&pi, p);
+
+        let x = one.div(&three, pr, rm);
+        let y = one.div(&seven, pr, rm);
+        let r = x
+            .add(&y, pr, rm)
+            .mul(&one.exp(pr, rm, &mut cc), pr, rm)
+            .div(&cc.pi(pr, rm), pr, rm);
+
+        assert!(z.contains(&r));
+        assert!(z.rad().exponent().unwrap() < z.mid().exponent().unwrap() - 120);
+        assert!(z.lower(p) < r && r < z.upper(p));
+
+        // elementary functions
+        let x = BigFloatBall::new(BigFloat::from_f64(0.75, p), BigFloat::from_f64(1.0e-30, 64));
+        let xr = [0.75 - 1.0e-30, 0.75, 0.75 + 1.0e-30].map(|v| BigFloat::from_f64(v, pr));
+
+        type Func = fn(&BigFloat, &mut Consts) -> BigFloat;
+        let fns: [(BigFloatBall, Func); 6] = [
+            (x.sqrt(p), |v, _| v.sqrt(PR, RM)),
+            (x.exp(p, &mut cc), |v, cc| v.exp(PR, RM, cc)),
+            (x.ln(p, &mut cc), |v, cc| v.ln(PR, RM, cc)),
+            (x.sin(p, &mut cc), |v, cc| v.sin(PR, RM, cc)),
+            (x.cos(p, &mut cc), |v, cc| v.cos(PR, RM, cc)),
+            (x.atan(p, &mut cc), |v, cc| v.atan(PR, RM, cc)),
+        ];
+
+        for (b, f) in fns {
+            assert!(b.is_finite());
+            assert!(b.rad().exponent().unwrap() < b.mid().exponent().unwrap() - 90);
+            for v in &xr {
+                assert!(b.contains(&f(v, &mut cc)));
+            }
+        }
+
+        let x = BigFloatBall::new(BigFloat::from_word(4, p), BigFloat::from_word(4, p));
+        let y = x.sqrt(p);
+        assert_eq!(y.mid(), &BigFloat::from_word(2, p));
+        assert!(y.contains(&BigFloat::new(p)));
+        assert!(y.contains(&BigFloat::from_word(8, p).sqrt(pr, rm)));
+
+        // indeterminate results
+        let x = BigFloatBall::new(BigFloat::from_word(1, p), BigFloat::from_word(1, p));
+        assert!(!BigFloatBall::exact(one.clone()).div(&x, p).is_finite());
+        assert!(!x.ln(p, &mut cc).is_finite());
+        assert!(!x.neg().sqrt(p).is_finite());
+        assert!(x.sqrt(p).is_finite());
+        assert!(!BigFloatBall::exact(one.clone())
+            .div(&BigFloatBall::exact(BigFloat::new(p)), p)
+            .is_finite());
+        assert!(!BigFloatBall::exact(crate::NAN).add(&x, p).is_finite());
+        assert!(BigFloatBall::indeterminate().mid().is_nan());
+        assert!(!BigFloatBall::indeterminate().contains(&one));
+    }
+}
@@ -0,0 +1,222 @@
+//! Benchmarks of the scaling of operations with precision.
+//!
+//! The benchmarks measure the time of multiplication, division, exponent, and sine
+//! for a range of precisions, so that the performance on a particular machine can be validated,
+//! and regressions can be reported together with the measured curves.
+//! The measurements are made with `std::time::Instant`: each operation is repeated
+//! until the elapsed time reaches the configured minimum, and the mean time of a single operation is reported.
+//!
+//! ```no_run
+//! use astro_float_num::bench::{run_scaling_benchmarks, ScalingConfig};
+//!
+//! let samples = run_scaling_benchmarks(&ScalingConfig::default()).unwrap();
+//!
+//! for s in samples {
+//!     println!("{:?} {} {:?}", s.op, s.p, s.time);
+//! }
+//! ```
+
+use crate::common::util::round_p;
+use crate::BigFloat;
+use crate::Consts;
+use crate::Error;
+use crate::RoundingMode;
+use crate::Sign;
+use crate::Word;
+use crate::WORD_BIT_SIZE;
+
+use std::time::Duration;
+use std::time::Instant;
+
+/// Operation to benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Multiplication.
+    Mul,
+
+    /// Division.
+    Div,
+
+    /// Exponent.
+    Exp,
+
+    /// Sine.
+    Sin,
+}
+
+impl Operation {
+    /// All operations.
+    pub const ALL: [Operation; 4] =
+        [Operation::Mul, Operation::Div, Operation::Exp, Operation::Sin];
+}
+
+/// Parameters of the benchmarks.
+#[derive(Debug, Clone)]
+pub struct ScalingConfig {
+    /// Operations to benchmark.
+    pub operations: Vec<Operation>,
+
+    /// Precisions in bits. Precision is rounded upwards to the word size.
+    pub precisions: Vec<usize>,
+
+    /// Minimum time spent measuring each operation at each precision.
+    pub min_time: Duration,
+
+    /// Rounding mode of the operations.
+    pub rm: RoundingMode,
+}
+
+impl Default for ScalingConfig {
+    /// Returns the configuration measuring all operations with precisions from 128 to 32768 bits, 100 ms per measurement.
+    fn default() -> Self {
+        ScalingConfig {
+            operations: Operation::ALL.to_vec(),
+            precisions: vec![128, 512, 2048, 8192, 32768],
+            min_time: Duration::from_millis(100),
+            rm: RoundingMode::ToEven,
+        }
+    }
+}
+
+/// Result of the measurement of an operation with a precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    /// The operation.
+    pub op: Operation,
+
+    /// Precision in bits.
+    pub p: usize,
+
+    /// Number of times the operation was performed.
+    pub iterations: u64,
+
+    /// Mean time of a single operation.
+    pub time: Duration,
+}
+
+/// Measures the time of the operations for each of the precisions specified in `config`.
+/// The samples are ordered by operation, and then by precision.
+///
+/// ## Errors
+///
+///  - MemoryAllocation: failed to allocate memory.
+///  - InvalidArgument: a precision is incorrect.
+pub fn run_scaling_benchmarks(config: &ScalingConfig) -> Result<Vec<Sample>, Error> {
+    let mut cc = Consts::new()?;
+    let mut ret = Vec::new();
+
+    for &op in &config.operations {
+        for &p in &config.precisions {
+            let p = round_p(p);
+
+            // the arguments have all words of the mantissa filled
+            let d1 = operand(p, 0x9E3779B97F4A7C15)?;
+            let d2 = operand(p, 0xD1B54A32D192ED03)?;
+
+            let mut f = || match op {
+                Operation::Mul => d1.mul(&d2, p, config.rm),
+                Operation::Div => d1.div(&d2, p, config.rm),
+                Operation::Exp => d1.exp(p, config.rm, &mut cc),
+                Operation::Sin => d1.sin(p, config.rm, &mut cc),
+            };
+
+            // warm up, e.g. compute the constants
+            if let Some(err) = f().err() {
+                return Err(err);
+            }
+
+            let mut iterations = 0u64;
+            let mut batch = 1u64;
+            let start = Instant::now();
+
+            let elapsed = loop {
+                for _ in 0..batch {
+                    core::hint::black_box(f());
+                }
+
+                iterations += batch;
+
+                let elapsed = start.elapsed();
+                if elapsed >= config.min_time {
+                    break elapsed;
+                }
+
+                batch *= 2;
+            };
+
+            ret.push(Sample {
+                op,
+                p,
+                iterations,
+                time: elapsed / iterations.min(u32::MAX as u64) as u32,
+            });
+        }
+    }
+
+    Ok(ret)
+}
+
+// Returns a number in the range [0.5, 1) with `p` bits of mantissa filled with pseudo-random bits generated from `seed`.
+fn operand(p: usize, seed: u64) -> Result<BigFloat, Error> {
+    if p == 0 {
+        return Err(Error::InvalidArgument);
+    }
+
+    let mut x = seed;
+    let mut m = Vec::new();
+    m.try_reserve_exact(p / WORD_BIT_SIZE)?;
+
+    for _ in 0..p / WORD_BIT_SIZE {
+        // xorshift64
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        m.push(x as Word);
+    }
+
+    if let Some(w) = m.last_mut() {
+        *w |= 1 << (WORD_BIT_SIZE - 1);
+    }
+
+    BigFloat::from_words_checked(&m, Sign::Pos, 0)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_scaling_benchmarks() {
+        let config = ScalingConfig {
+            operations: Operation::ALL.to_vec(),
+            precisions: vec![1, 200],
+            min_time: Duration::from_millis(1),
+            rm: RoundingMode::Down,
+        };
+
+        let samples = run_scaling_benchmarks(&config).unwrap();
+        assert_eq!(samples.len(), 8);
+
+        for (s, (op, p)) in samples.iter().zip(
+            Operation::ALL
+                .iter()
+                .flat_map(|op| [(*op, WORD_BIT_SIZE), (*op, round_p(200))]),
+        ) {
+            assert_eq!((s.op, s.p), (op, p));
+            assert!(s.iterations > 0);
+        }
+
+        let d = operand(round_p(200), 1).unwrap();
+        assert!(d.exponent() == Some(0) && d.is_positive() && !d.inexact());
+
+        let config = ScalingConfig {
+            precisions: vec![0],
+            ..config
+        };
+        assert_eq!(
+            run_scaling_benchmarks(&config).unwrap_err(),
+            Error::InvalidArgument
+        );
+    }
+}
@@ -15,6 +15,90 @@ use crate::common::util::shift_slice_right;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+
+/// The largest number of buffers kept by the pool.
+#[cfg(feature = "std")]
+const POOL_MAX_BUFFERS: usize = 64;
+
+#[cfg(feature = "std")]
+thread_local! {
+    static POOL: RefCell<Pool> = const { RefCell::new(Pool { depth: 0, bufs: Vec::new() }) };
+}
+
+// Memory of released buffers of the current thread.
+// Outside of `with_pool` it keeps just the buffer released by the last in-place operation,
+// and inside `with_pool` it keeps all released buffers up to `POOL_MAX_BUFFERS`.
+#[cfg(feature = "std")]
+struct Pool {
+    depth: usize,
+    bufs: Vec<Vec<Word>>,
+}
+
+#[cfg(feature = "std")]
+impl Pool {
+    fn take(&mut self, sz: usize) -> Option<Vec<Word>> {
+        // a much larger buffer is not used to avoid holding excess memory
+        let i = self
+            .bufs
+            .iter()
+            .rposition(|v| v.capacity() >= sz && v.capacity() <= 2 * sz + 2)?;
+
+        let mut inner = self.bufs.swap_remove(i);
+        unsafe {
+            // values of the reused words stay unitialized for performance reasons
+            inner.set_len(sz);
+        }
+
+        Some(inner)
+    }
+
+    fn put(&mut self, v: Vec<Word>) {
+        if self.depth > 0 {
+            if self.bufs.len() < POOL_MAX_BUFFERS {
+                self.bufs.push(v);
+            }
+        } else {
+            self.bufs.clear();
+            self.bufs.push(v);
+        }
+    }
+}
+
+/// Runs `f` with the buffer pool of the current thread enabled and releases the memory of the pool when `f` returns.
+#[cfg(feature = "std")]
+pub fn with_pool<R>(f: impl FnOnce() -> R) -> R {
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let _ = POOL.try_with(|pool| {
+                let mut pool = pool.borrow_mut();
+                pool.depth -= 1;
+                if pool.depth == 0 {
+                    pool.bufs = Vec::new();
+                }
+            });
+        }
+    }
+
+    POOL.with(|pool| pool.borrow_mut().depth += 1);
+    let _guard = Guard;
+
+    f()
+}
+
+/// Releases the memory of the buffers kept by the pool of the current thread.
+#[cfg(feature = "std")]
+pub fn reset_pool() {
+    let _ = POOL.try_with(|pool| {
+        if let Ok(mut pool) = pool.try_borrow_mut() {
+            pool.bufs = Vec::new();
+        }
+    });
+}
+
 /// Buffer for holding mantissa gidits.
 #[derive(Debug, Hash)]
 pub struct WordBuf {
@@ -24,6 +108,11 @@ pub struct WordBuf {
 impl WordBuf {
     #[inline]
     pub fn new(sz: usize) -> Result<Self, Error> {
+        #[cfg(feature = "std")]
+        if let Some(inner) = Self::take_spare(sz) {
+            return Ok(WordBuf { inner });
+        }
+
         let mut inner = Vec::new();
         inner.try_reserve_exact(sz)?;
         unsafe {
@@ -33,6 +122,33 @@ impl WordBuf {
         Ok(WordBuf { inner })
     }
 
+    #[cfg(feature = "std")]
+    fn take_spare(sz: usize) -> Option<Vec<Word>> {
+        POOL.try_with(|pool| pool.try_borrow_mut().ok()?.take(sz))
+            .ok()
+            .flatten()
+    }
+
+    /// Keep the memory of the buffer for reuse by the next allocation on the current thread.
+    pub fn recycle(self) {
+        #[cfg(feature = "std")]
+        {
+            let mut buf = self;
+            Self::put_spare(core::mem::take(&mut buf.inner), true);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn put_spare(v: Vec<Word>, always: bool) {
+        let _ = POOL.try_with(|pool| {
+            if let Ok(mut pool) = pool.try_borrow_mut() {
+                if always || pool.depth > 0 {
+                    pool.put(v);
+                }
+            }
+        });
+    }
+
     #[inline]
     pub fn fill(&mut self, d: Word) {
         self.inner.fill(d);
@@ -43,9 +159,15 @@ impl WordBuf {
         self.inner.len()
     }
 
+    /// Release unused memory.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
     /// Decrease length of the buffer to l bits. Data is shifted.
     pub fn trunc_to(&mut self, l: usize) {
-        let n = (l + WORD_BIT_SIZE - 1) / WORD_BIT_SIZE;
+        let n = l.div_ceil(WORD_BIT_SIZE);
         let sz = self.len();
         shift_slice_right(&mut self.inner, (sz - n) * WORD_BIT_SIZE);
         self.inner.truncate(n);
@@ -53,13 +175,13 @@ impl WordBuf {
 
     /// Decrease length of the buffer to l bits. Data is not moved.
     pub fn trunc_to_2(&mut self, l: usize) {
-        let n = (l + WORD_BIT_SIZE - 1) / WORD_BIT_SIZE;
+        let n = l.div_ceil(WORD_BIT_SIZE);
         self.inner.truncate(n);
     }
 
     /// Try to exted the size to fit the precision p. Data is shifted to the left.
     pub fn try_extend(&mut self, p: usize) -> Result<(), Error> {
-        let n = (p + WORD_BIT_SIZE - 1) / WORD_BIT_SIZE;
+        let n = p.div_ceil(WORD_BIT_SIZE);
         let l = self.inner.len();
         self.inner.try_reserve(n - l)?;
         unsafe {
@@ -72,7 +194,7 @@ impl WordBuf {
 
     /// Try to exted the size to fit the precision p. Fill new elements with 0. Data is not moved.
     pub fn try_extend_2(&mut self, p: usize) -> Result<(), Error> {
-        let n = (p + WORD_BIT_SIZE - 1) / WORD_BIT_SIZE;
+        let n = p.div_ceil(WORD_BIT_SIZE);
         if n > self.inner.capacity() {
             self.inner.try_reserve(n - self.inner.capacity())?;
         }
@@ -84,7 +206,7 @@ impl WordBuf {
 
     /// Try to extend the size to fit the precision p. Data is shifted to the left by d bits.
     pub fn try_extend_3(&mut self, p: usize, d: usize) -> Result<(), Error> {
-        let n = (p + WORD_BIT_SIZE - 1) / WORD_BIT_SIZE;
+        let n = p.div_ceil(WORD_BIT_SIZE);
         let l = self.inner.len();
         self.inner.try_reserve(n - l)?;
         unsafe {
@@ -134,6 +256,15 @@ impl WordBuf {
     }
 }
 
+#[cfg(feature = "std")]
+impl Drop for WordBuf {
+    fn drop(&mut self) {
+        if self.inner.capacity() > 0 {
+            Self::put_spare(core::mem::take(&mut self.inner), false);
+        }
+    }
+}
+
 impl<I: SliceIndex<[Word]>> IndexMut<I> for WordBuf {
     #[inline]
     fn index_mut(&mut self, index: I) -> &mut Self::Output {
@@ -2,13 +2,12 @@
 
 use crate::{
     defs::{Word, WORD_BIT_SIZE, WORD_MAX, WORD_SIGNIFICANT_BIT},
-    RoundingMode,
+    tuning, RoundingMode,
 };
 
 #[cfg(test)]
 use crate::{num::BigFloatNumber, Sign, EXPONENT_MIN};
 
-#[cfg(test)]
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
@@ -89,16 +88,20 @@ fn nroot_step(x: i128, n: i128, a: i128) -> i128 {
 
 // cost of multiplication of two numbers with precision p.
 pub fn calc_mul_cost(p: usize) -> usize {
+    let fft_threshold = tuning::mul_toom3() * WORD_BIT_SIZE;
+
     if p < 70 {
         p * p
-    } else {
+    } else if p < 1625 {
         // toom-3
-        if p < 1625 {
-            sqrt_int((p * p * p) as u32) as usize
-        } else {
-            let q = sqrt_int(p as u32) as usize;
-            q * q * q
-        }
+        sqrt_int((p * p * p) as u32) as usize
+    } else if p <= fft_threshold {
+        let q = sqrt_int(p as u32) as usize;
+        q * q * q
+    } else {
+        // fft: p*log2(p) scaled to match toom-3 at the threshold
+        let k = sqrt_int(fft_threshold as u32) as usize;
+        p / log2_floor(fft_threshold) * log2_floor(p) * k
     }
 }
 
@@ -116,6 +119,7 @@ pub fn calc_sqrt_cost(p: usize, cost_mul: usize, cost_add: usize) -> usize {
 }
 
 #[inline(always)]
+#[allow(unused_unsafe)] // the intrinsics are safe in newer compilers
 pub fn add_carry(a: Word, b: Word, c: Word, r: &mut Word) -> Word {
     #[cfg(target_arch = "x86_64")]
     {
@@ -147,6 +151,7 @@ pub fn add_carry(a: Word, b: Word, c: Word, r: &mut Word) -> Word {
 }
 
 #[inline(always)]
+#[allow(unused_unsafe)] // the intrinsics are safe in newer compilers
 pub fn sub_borrow(a: Word, b: Word, c: Word, r: &mut Word) -> Word {
     #[cfg(target_arch = "x86_64")]
     {
@@ -331,6 +336,39 @@ pub fn round_p(p: usize) -> usize {
     ((p.saturating_add(WORD_BIT_SIZE - 1)) / WORD_BIT_SIZE) * WORD_BIT_SIZE
 }
 
+/// Converts the words of a mantissa to 64-bit words. The most significant bit of the mantissa is preserved
+/// as the most significant bit of the last 64-bit word.
+#[allow(clippy::unnecessary_cast)] // Word can be u32
+pub fn words_to_u64(m: &[Word]) -> Vec<u64> {
+    let mut ret = Vec::with_capacity(m.len() * WORD_BIT_SIZE / 64 + 1);
+
+    if WORD_BIT_SIZE == 64 {
+        ret.extend(m.iter().map(|w| *w as u64));
+    } else {
+        let (first, rest) = m.split_at(m.len() % 2);
+        if let Some(hi) = first.first() {
+            ret.push((*hi as u64) << 32);
+        }
+        for c in rest.chunks(2) {
+            ret.push(c[0] as u64 | (c[1] as u64) << 32);
+        }
+    }
+
+    ret
+}
+
+/// Converts 64-bit words to the words of a mantissa.
+#[allow(clippy::unnecessary_cast)] // Word can be u32
+pub fn u64_to_words(m: &[u64]) -> Vec<Word> {
+    let mut ret = Vec::with_capacity(m.len() * 64 / WORD_BIT_SIZE);
+    for v in m {
+        for i in 0..64 / WORD_BIT_SIZE {
+            ret.push((*v >> (i * WORD_BIT_SIZE)) as Word);
+        }
+    }
+    ret
+}
+
 // Convert rounding mode for an opposite sign.
 pub fn invert_rm_for_sign(rm: RoundingMode) -> RoundingMode {
     if rm == RoundingMode::Up {
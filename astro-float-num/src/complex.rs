@@ -0,0 +1,271 @@
+//! Complex numbers.
+//!
+//! A complex number is a pair of a real part and an imaginary part, each represented by `BigFloat`.
+//! The arithmetic operations accept a precision `p` and a rounding mode `rm` which are applied to each part of the result.
+//! The parts of the results of addition, subtraction, and multiplication are correctly rounded.
+//! Division is computed with an increased working precision, but its result is not guaranteed to be correctly rounded.
+//!
+//! ```
+//! use astro_float_num::complex::BigComplex;
+//! use astro_float_num::{BigFloat, RoundingMode};
+//!
+//! let p = 128;
+//! let rm = RoundingMode::ToEven;
+//!
+//! // (1 + 2i) * (3 - i) = 5 + 5i
+//! let z1 = BigComplex::new(BigFloat::from_i8(1, p), BigFloat::from_i8(2, p));
+//! let z2 = BigComplex::new(BigFloat::from_i8(3, p), BigFloat::from_i8(-1, p));
+//! let z = z1.mul(&z2, p, rm);
+//!
+//! assert_eq!(z.re(), &BigFloat::from_i8(5, p));
+//! assert_eq!(z.im(), &BigFloat::from_i8(5, p));
+//! assert_eq!(z.div(&z2, p, rm), z1);
+//! ```
+
+use crate::defs::{RoundingMode, DEFAULT_P};
+use crate::{BigFloat, Consts, WORD_BIT_SIZE};
+
+/// Complex number with the real part `re` and the imaginary part `im`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BigComplex {
+    re: BigFloat,
+    im: BigFloat,
+}
+
+impl BigComplex {
+    /// Constructs a complex number with the real part `re` and the imaginary part `im`.
+    pub fn new(re: BigFloat, im: BigFloat) -> Self {
+        BigComplex { re, im }
+    }
+
+    /// Constructs a complex number with the real part `re` and the imaginary part 0 of precision `p`.
+    pub fn from_real(re: BigFloat, p: usize) -> Self {
+        BigComplex {
+            re,
+            im: BigFloat::new(p),
+        }
+    }
+
+    /// Returns the real part of the number.
+    pub fn re(&self) -> &BigFloat {
+        &self.re
+    }
+
+    /// Returns the imaginary part of the number.
+    pub fn im(&self) -> &BigFloat {
+        &self.im
+    }
+
+    /// Decomposes the number into the real and the imaginary parts.
+    pub fn into_parts(self) -> (BigFloat, BigFloat) {
+        (self.re, self.im)
+    }
+
+    /// Returns true if the real or the imaginary part is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.re.is_nan() || self.im.is_nan()
+    }
+
+    /// Returns true if the real or the imaginary part is infinite.
+    pub fn is_inf(&self) -> bool {
+        self.re.is_inf() || self.im.is_inf()
+    }
+
+    /// Returns true if both the real and the imaginary parts are zero.
+    pub fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+
+    /// Returns the complex conjugate of the number.
+    pub fn conj(&self) -> Self {
+        BigComplex {
+            re: self.re.clone(),
+            im: self.im.neg(),
+        }
+    }
+
+    /// Returns the negated number.
+    pub fn neg(&self) -> Self {
+        BigComplex {
+            re: self.re.neg(),
+            im: self.im.neg(),
+        }
+    }
+
+    /// Adds `d2` to `self` and returns the result of the operation with precision `p` rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn add(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
+        BigComplex {
+            re: self.re.add(&d2.re, p, rm),
+            im: self.im.add(&d2.im, p, rm),
+        }
+    }
+
+    /// Subtracts `d2` from `self` and returns the result of the operation with precision `p` rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn sub(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
+        BigComplex {
+            re: self.re.sub(&d2.re, p, rm),
+            im: self.im.sub(&d2.im, p, rm),
+        }
+    }
+
+    /// Multiplies `self` by `d2` and returns the result of the operation with precision `p` rounded using the rounding mode `rm`.
+    /// The products of the parts are computed exactly, so each part of the result is rounded only once.
+    /// Precision is rounded upwards to the word size.
+    pub fn mul(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
+        let (a, b, c, d) = (&self.re, &self.im, &d2.re, &d2.im);
+
+        let ac = a.mul_full_prec(c);
+        let bd = b.mul_full_prec(d);
+        let ad = a.mul_full_prec(d);
+        let bc = b.mul_full_prec(c);
+
+        BigComplex {
+            re: ac.sub(&bd, p, rm),
+            im: ad.add(&bc, p, rm),
+        }
+    }
+
+    /// Divides `self` by `d2` and returns the result of the operation with precision `p` rounded using the rounding mode `rm`.
+    /// The intermediate results are computed with an increased working precision.
+    /// Precision is rounded upwards to the word size.
+    pub fn div(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
+        let (a, b, c, d) = (&self.re, &self.im, &d2.re, &d2.im);
+
+        if d.is_zero() {
+            return BigComplex {
+                re: a.div(c, p, rm),
+                im: b.div(c, p, rm),
+            };
+        }
+
+        let p_wrk = p.saturating_add(2 * WORD_BIT_SIZE);
+
+        // (ac + bd) / (c^2 + d^2) + (bc - ad) / (c^2 + d^2) i
+        let den = c
+            .mul_full_prec(c)
+            .add(&d.mul_full_prec(d), p_wrk, RoundingMode::None);
+        let re = a
+            .mul_full_prec(c)
+            .add(&b.mul_full_prec(d), p_wrk, RoundingMode::None);
+        let im = b
+            .mul_full_prec(c)
+            .sub(&a.mul_full_prec(d), p_wrk, RoundingMode::None);
+
+        BigComplex {
+            re: re.div(&den, p, rm),
+            im: im.div(&den, p, rm),
+        }
+    }
+
+    /// Returns the absolute value of the number `sqrt(re^2 + im^2)` with precision `p` rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn abs(&self, p: usize, rm: RoundingMode) -> BigFloat {
+        self.re.hypot(&self.im, p, rm)
+    }
+
+    /// Returns the argument of the number in the range [-pi, pi] with precision `p` rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    pub fn arg(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> BigFloat {
+        self.im.atan2(&self.re, p, rm, cc)
+    }
+}
+
+impl From<BigFloat> for BigComplex {
+    fn from(re: BigFloat) -> Self {
+        let p = re.mantissa_max_bit_len().unwrap_or(DEFAULT_P);
+        BigComplex::from_real(re, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_complex() {
+        let p = 256;
+        let rm = RoundingMode::ToEven;
+        let mut cc = Consts::new().unwrap();
+
+        let z1 = BigComplex::new(BigFloat::from_f64(1.5, p), BigFloat::from_i8(-2, p));
+        let z2 = BigComplex::new(BigFloat::from_i8(3, p), BigFloat::from_f64(0.5, p));
+
+        // arithmetic
+        let z = z1.add(&z2, p, rm);
+        assert_eq!(
+            z,
+            BigComplex::new(BigFloat::from_f64(4.5, p), BigFloat::from_f64(-1.5, p))
+        );
+        let z = z1.sub(&z2, p, rm);
+        assert_eq!(
+            z,
+            BigComplex::new(BigFloat::from_f64(-1.5, p), BigFloat::from_f64(-2.5, p))
+        );
+        let z = z1.mul(&z2, p, rm);
+        assert_eq!(
+            z,
+            BigComplex::new(BigFloat::from_f64(5.5, p), BigFloat::from_f64(-5.25, p))
+        );
+        assert_eq!(z.div(&z2, p, rm), z1);
+        assert_eq!(z1.neg().add(&z1, p, rm), BigComplex::from(BigFloat::new(p)));
+
+        // products are rounded once: (1 + e) * (1 - e) = 1 - e^2 with tiny e
+        let mut e = BigFloat::from_word(1, p);
+        e.set_exponent(-39);
+        let e2 = e.mul(&e, p, rm);
+        let za = BigComplex::new(BigFloat::from_word(1, 64), e.clone());
+        let zb = BigComplex::new(BigFloat::from_word(1, 64), e.neg());
+        let z = za.mul(&zb, 64, rm);
+        assert_eq!(z.re(), &BigFloat::from_word(1, 64).add(&e2, 64, rm));
+        assert!(z.im().is_zero());
+        let z = za.mul(&za, 64, RoundingMode::Down);
+        assert!(z.re() < &BigFloat::from_word(1, 64));
+
+        // division by a real number and by zero
+        let z = z1.div(&BigComplex::from(BigFloat::from_i8(2, p)), p, rm);
+        assert_eq!(
+            z,
+            BigComplex::new(BigFloat::from_f64(0.75, p), BigFloat::from_i8(-1, p))
+        );
+        assert!(z1.div(&BigComplex::from(BigFloat::new(p)), p, rm).is_inf());
+
+        // division with a rounded result
+        let z3 = BigComplex::new(BigFloat::from_i8(1, p), BigFloat::from_i8(1, p));
+        let z4 = BigComplex::new(BigFloat::from_i8(1, p), BigFloat::from_i8(2, p));
+        let z = z3.div(&z4, p, rm);
+        let three = BigFloat::from_i8(3, p);
+        assert_eq!(z.re(), &three.div(&BigFloat::from_i8(5, p), p, rm));
+        assert_eq!(
+            z.im(),
+            &BigFloat::from_i8(-1, p).div(&BigFloat::from_i8(5, p), p, rm)
+        );
+
+        // conjugate, absolute value, and argument
+        let z = BigComplex::new(BigFloat::from_i8(-3, p), BigFloat::from_i8(4, p));
+        assert_eq!(
+            z.conj(),
+            BigComplex::new(BigFloat::from_i8(-3, p), BigFloat::from_i8(-4, p))
+        );
+        assert_eq!(z.abs(p, rm), BigFloat::from_i8(5, p));
+        assert_eq!(
+            z.arg(p, rm, &mut cc),
+            BigFloat::from_i8(4, p).atan2(&BigFloat::from_i8(-3, p), p, rm, &mut cc)
+        );
+        let i = BigComplex::new(BigFloat::new(p), BigFloat::from_i8(1, p));
+        let half_pi = cc.pi(p, rm).div(&BigFloat::from_i8(2, p), p, rm);
+        assert_eq!(i.arg(p, rm, &mut cc), half_pi);
+        assert_eq!(i.mul(&i, p, rm), BigComplex::from(BigFloat::from_i8(-1, p)));
+
+        // special values
+        let z = BigComplex::new(BigFloat::nan(None), BigFloat::from_i8(1, p));
+        assert!(z.is_nan());
+        assert!(z.add(&z1, p, rm).is_nan());
+        assert!(BigComplex::from(BigFloat::new(p)).is_zero());
+        let (re, im) = z1.clone().into_parts();
+        assert_eq!(BigComplex::new(re, im), z1);
+    }
+}
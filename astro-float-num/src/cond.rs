@@ -0,0 +1,371 @@
+//! Estimation of condition numbers.
+//!
+//! The relative condition number of a function `f` at a point `x` is `|x·f'(x)/f(x)|`.
+//! It shows how much a relative error of the argument is amplified in the result:
+//! if `x` has a relative error `δ`, then `f(x)` has a relative error of about `δ·|x·f'(x)/f(x)|`.
+
+use crate::common::util::round_p;
+use crate::BigFloat;
+use crate::Consts;
+use crate::Error;
+use crate::Exponent;
+use crate::RoundingMode;
+use crate::WORD_BIT_SIZE;
+
+/// Built-in function for which a condition number can be estimated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fun {
+    /// Square root.
+    Sqrt,
+    /// Cube root.
+    Cbrt,
+    /// Natural logarithm.
+    Ln,
+    /// Binary logarithm.
+    Log2,
+    /// Decimal logarithm.
+    Log10,
+    /// Exponent.
+    Exp,
+    /// Sine.
+    Sin,
+    /// Cosine.
+    Cos,
+    /// Tangent.
+    Tan,
+    /// Arcsine.
+    Asin,
+    /// Arccosine.
+    Acos,
+    /// Arctangent.
+    Atan,
+    /// Hyperbolic sine.
+    Sinh,
+    /// Hyperbolic cosine.
+    Cosh,
+    /// Hyperbolic tangent.
+    Tanh,
+    /// Hyperbolic arcsine.
+    Asinh,
+    /// Hyperbolic arccosine.
+    Acosh,
+    /// Hyperbolic arctangent.
+    Atanh,
+    /// Gamma function.
+    Gamma,
+}
+
+/// Estimates the relative condition number `|x·f'(x)/f(x)|` of the function `fun` at the point `x` with precision `p`.
+/// The estimate is computed with an elevated working precision using `RoundingMode::None`, so it is an approximation
+/// rather than an upper bound. The approximation is then rounded upwards to the precision `p`.
+/// This function requires constants cache `cc` for computing the result.
+/// Precision is rounded upwards to the word size.
+///
+/// At zero the condition number is defined as the limit for `x` approaching zero.
+/// If `f(x)` is zero for a nonzero `x`, the condition number is infinite.
+/// The function returns NaN if `x` is not finite, `x` is outside of the domain of the function, or the precision `p` is incorrect.
+pub fn condition_number(fun: Fun, x: &BigFloat, p: usize, cc: &mut Consts) -> BigFloat {
+    if x.is_nan() || x.is_inf() || p == 0 {
+        return BigFloat::nan(Some(Error::InvalidArgument));
+    }
+
+    let p_wrk = round_p(p) + 2 * WORD_BIT_SIZE;
+    let rm = RoundingMode::None;
+
+    let one = BigFloat::from_word(1, 1);
+
+    let ret = match fun {
+        Fun::Sqrt | Fun::Cbrt => {
+            let dom = if fun == Fun::Sqrt { x.sqrt(p_wrk, rm) } else { x.cbrt(p_wrk, rm) };
+            if dom.is_nan() {
+                dom
+            } else {
+                let n = if fun == Fun::Sqrt { 2 } else { 3 };
+                one.div(&BigFloat::from_word(n, 1), p_wrk, rm)
+            }
+        }
+        Fun::Ln | Fun::Log2 | Fun::Log10 => {
+            let l = x.ln(p_wrk, rm, cc);
+            if x.is_zero() {
+                BigFloat::new(p_wrk)
+            } else {
+                one.div(&l, p_wrk, rm)
+            }
+        }
+        Fun::Exp => x.clone(),
+        _ if x.is_zero() => match fun {
+            Fun::Cos | Fun::Cosh | Fun::Acos => BigFloat::new(p_wrk),
+            _ => one,
+        },
+        Fun::Sin => {
+            let s = x.sin(p_wrk, rm, cc);
+            let c = x.cos(p_wrk, rm, cc);
+            x.mul(&c, p_wrk, rm).div(&s, p_wrk, rm)
+        }
+        Fun::Cos => {
+            let s = x.sin(p_wrk, rm, cc);
+            let c = x.cos(p_wrk, rm, cc);
+            x.mul(&s, p_wrk, rm).div(&c, p_wrk, rm)
+        }
+        Fun::Tan => {
+            let s = x.sin(p_wrk, rm, cc);
+            let c = x.cos(p_wrk, rm, cc);
+            x.div(&s.mul(&c, p_wrk, rm), p_wrk, rm)
+        }
+        Fun::Asin | Fun::Acos => {
+            let f = if fun == Fun::Asin { x.asin(p_wrk, rm, cc) } else { x.acos(p_wrk, rm, cc) };
+            let d = one_minus_sqr(x, p_wrk).sqrt(p_wrk, rm);
+            x.div(&d.mul(&f, p_wrk, rm), p_wrk, rm)
+        }
+        Fun::Atan => {
+            let f = x.atan(p_wrk, rm, cc);
+            let d = x.mul(x, p_wrk, rm).add(&one, p_wrk, rm);
+            x.div(&d.mul(&f, p_wrk, rm), p_wrk, rm)
+        }
+        Fun::Sinh => {
+            let s = x.sinh(p_wrk, rm, cc);
+            let c = x.cosh(p_wrk, rm, cc);
+            x.mul(&c, p_wrk, rm).div(&s, p_wrk, rm)
+        }
+        Fun::Cosh => x.mul(&x.tanh(p_wrk, rm, cc), p_wrk, rm),
+        Fun::Tanh => {
+            let s = x.sinh(p_wrk, rm, cc);
+            let c = x.cosh(p_wrk, rm, cc);
+            x.div(&s.mul(&c, p_wrk, rm), p_wrk, rm)
+        }
+        Fun::Asinh => {
+            let f = x.asinh(p_wrk, rm, cc);
+            let d = x.mul(x, p_wrk, rm).add(&one, p_wrk, rm).sqrt(p_wrk, rm);
+            x.div(&d.mul(&f, p_wrk, rm), p_wrk, rm)
+        }
+        Fun::Acosh => {
+            let f = x.acosh(p_wrk, rm, cc);
+            let d = one_minus_sqr(x, p_wrk).neg().sqrt(p_wrk, rm);
+            x.div(&d.mul(&f, p_wrk, rm), p_wrk, rm)
+        }
+        Fun::Atanh => {
+            let f = x.atanh(p_wrk, rm, cc);
+            let d = one_minus_sqr(x, p_wrk);
+            x.div(&d.mul(&f, p_wrk, rm), p_wrk, rm)
+        }
+        Fun::Gamma => {
+            if x.is_int() && x.is_negative() {
+                BigFloat::nan(Some(Error::InvalidArgument))
+            } else {
+                condition_number_with(|x, p, rm| x.gamma(p, rm, cc), x, p_wrk)
+            }
+        }
+    };
+
+    finalize(ret, p)
+}
+
+/// Estimates the relative condition number `|x·f'(x)/f(x)|` of an arbitrary function `f` at the point `x` with precision `p`.
+/// The derivative is approximated by the central difference computed with an elevated working precision.
+/// The function `f` is called as `f(x, p_wrk, rm_wrk)`, and is expected to return the result with precision `p_wrk`
+/// rounded according to `rm_wrk`, as the built-in functions do, e.g. `|x, p, rm| x.exp(p, rm, &mut cc)`.
+/// The estimate is an approximation rather than an upper bound, and it is rounded upwards to the precision `p`.
+/// Precision is rounded upwards to the word size.
+///
+/// If `x` is zero, the function returns zero when `f(0)` is nonzero.
+/// The function returns NaN if `x` is not finite, `f` returns NaN or infinity near `x`, `x` is zero and `f(0)` is zero,
+/// or the precision `p` is incorrect.
+pub fn condition_number_with<F>(mut f: F, x: &BigFloat, p: usize) -> BigFloat
+where
+    F: FnMut(&BigFloat, usize, RoundingMode) -> BigFloat,
+{
+    if x.is_nan() || x.is_inf() || p == 0 {
+        return BigFloat::nan(Some(Error::InvalidArgument));
+    }
+
+    let p_wrk = round_p(p) + 2 * WORD_BIT_SIZE;
+
+    // the difference of the function values loses about p_wrk bits
+    let p_f = 2 * p_wrk;
+    let rm = RoundingMode::ToEven;
+
+    let y = f(x, p_f, rm);
+
+    if y.is_nan() || y.is_inf() {
+        return BigFloat::nan(Some(Error::InvalidArgument));
+    }
+
+    if x.is_zero() {
+        return if y.is_zero() {
+            BigFloat::nan(Some(Error::InvalidArgument))
+        } else {
+            BigFloat::new(round_p(p))
+        };
+    }
+
+    // step relative to x
+    let mut h = BigFloat::from_word(1, 1);
+    if let Some(e) = x.exponent() {
+        h.set_exponent(e - p_wrk as Exponent);
+    }
+
+    let p_x = p_f + x.mantissa_max_bit_len().unwrap_or(0);
+    let x1 = x.add(&h, p_x, RoundingMode::None);
+    let x2 = x.sub(&h, p_x, RoundingMode::None);
+
+    let y1 = f(&x1, p_f, rm);
+    let y2 = f(&x2, p_f, rm);
+
+    if y1.is_nan() || y1.is_inf() || y2.is_nan() || y2.is_inf() {
+        return BigFloat::nan(Some(Error::InvalidArgument));
+    }
+
+    let d = y1.sub(&y2, p_f, RoundingMode::None);
+    let h2 = h.add(&h, p_f, RoundingMode::None);
+
+    let ret = x.mul(&d, p_wrk, RoundingMode::None).div(
+        &h2.mul(&y, p_wrk, RoundingMode::None),
+        p_wrk,
+        RoundingMode::None,
+    );
+
+    finalize(ret, p)
+}
+
+// Returns 1 - x^2.
+fn one_minus_sqr(x: &BigFloat, p: usize) -> BigFloat {
+    let one = BigFloat::from_word(1, 1);
+    let d1 = one.sub(x, p, RoundingMode::None);
+    let d2 = one.add(x, p, RoundingMode::None);
+    d1.mul(&d2, p, RoundingMode::None)
+}
+
+// Returns the absolute value of `x` rounded upwards to precision `p`.
+fn finalize(x: BigFloat, p: usize) -> BigFloat {
+    let mut ret = x.abs();
+    if let Err(e) = ret.set_precision(round_p(p), RoundingMode::Up) {
+        return BigFloat::nan(Some(e));
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Radix;
+
+    #[test]
+    fn test_condition_number() {
+        let mut cc = Consts::new().unwrap();
+        let p = 128;
+
+        let parse =
+            |s: &str, cc: &mut Consts| BigFloat::parse(s, Radix::Dec, p, RoundingMode::ToEven, cc);
+
+        // relative difference is small
+        let check = |a: &BigFloat, b: &BigFloat| {
+            let mut eps = BigFloat::from_word(1, 1);
+            eps.set_exponent(-100);
+            let d = a
+                .sub(b, p, RoundingMode::None)
+                .div(b, p, RoundingMode::None)
+                .abs();
+            assert!(d < eps, "{:?} {:?}", a, b);
+        };
+
+        let one = BigFloat::from_word(1, p);
+        let three = BigFloat::from_word(3, p);
+
+        assert_eq!(condition_number(Fun::Exp, &three.neg(), p, &mut cc), three);
+        assert_eq!(
+            condition_number(Fun::Sqrt, &three, p, &mut cc),
+            BigFloat::from_f64(0.5, p)
+        );
+        assert!(condition_number(Fun::Sqrt, &three.neg(), p, &mut cc).is_nan());
+        assert!(condition_number(Fun::Ln, &one, p, &mut cc).is_inf_pos());
+        assert!(condition_number(Fun::Ln, &one.neg(), p, &mut cc).is_nan());
+        assert!(condition_number(Fun::Asin, &one, p, &mut cc).is_inf_pos());
+        assert!(condition_number(Fun::Gamma, &three.neg(), p, &mut cc).is_nan());
+
+        // limits at zero
+        let zero = BigFloat::new(p);
+        for fun in [
+            Fun::Sin,
+            Fun::Tan,
+            Fun::Asin,
+            Fun::Atan,
+            Fun::Sinh,
+            Fun::Tanh,
+            Fun::Asinh,
+            Fun::Atanh,
+        ] {
+            assert_eq!(condition_number(fun, &zero, p, &mut cc), one);
+        }
+        for fun in [Fun::Cos, Fun::Cosh, Fun::Acos, Fun::Exp, Fun::Ln] {
+            assert!(condition_number(fun, &zero, p, &mut cc).is_zero());
+        }
+
+        // x·cot(x)
+        check(
+            &condition_number(Fun::Sin, &one, p, &mut cc),
+            &parse(
+                "0.64209261593433070300641998659426562023027811391817",
+                &mut cc,
+            ),
+        );
+
+        // x·tan(x)
+        check(
+            &condition_number(Fun::Cos, &BigFloat::from_f64(0.75, p), p, &mut cc),
+            &parse(
+                "0.69869734495805434587390206743045232114151850497916",
+                &mut cc,
+            ),
+        );
+
+        // 1/ln(x)
+        check(
+            &condition_number(Fun::Log10, &BigFloat::from_word(10, p), p, &mut cc),
+            &parse(
+                "0.43429448190325182765112891891660508229439700580367",
+                &mut cc,
+            ),
+        );
+
+        // x·digamma(x)
+        check(
+            &condition_number(Fun::Gamma, &BigFloat::from_word(5, p), p, &mut cc),
+            &parse(
+                "7.5305883421590023636341062162546545114558699869671",
+                &mut cc,
+            ),
+        );
+        check(
+            &condition_number(Fun::Gamma, &BigFloat::from_f64(-2.5, p), p, &mut cc),
+            &parse(
+                "2.7578916016131079680642258341697777486837676550156",
+                &mut cc,
+            ),
+        );
+
+        // numerical estimate agrees with analytical formulas
+        let x = BigFloat::from_f64(0.375, p);
+        for (fun, f) in [
+            (
+                Fun::Exp,
+                (|x, p, rm, cc| BigFloat::exp(x, p, rm, cc))
+                    as fn(&BigFloat, usize, RoundingMode, &mut Consts) -> BigFloat,
+            ),
+            (Fun::Tan, |x, p, rm, cc| x.tan(p, rm, cc)),
+            (Fun::Acos, |x, p, rm, cc| x.acos(p, rm, cc)),
+            (Fun::Atanh, |x, p, rm, cc| x.atanh(p, rm, cc)),
+            (Fun::Cosh, |x, p, rm, cc| x.cosh(p, rm, cc)),
+        ] {
+            let c1 = condition_number(fun, &x, p, &mut cc);
+            let c2 = condition_number_with(|x, p, rm| f(x, p, rm, &mut cc), &x, p);
+            check(&c2, &c1);
+        }
+
+        let c = condition_number_with(|x, p, rm| x.sin(p, rm, &mut cc), &zero, p);
+        assert!(c.is_nan());
+
+        let c = condition_number_with(|x, p, rm| x.cos(p, rm, &mut cc), &zero, p);
+        assert!(c.is_zero());
+    }
+}
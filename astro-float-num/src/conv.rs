@@ -23,7 +23,8 @@ use crate::EXPONENT_MIN;
 use alloc::vec::Vec;
 
 const TEN_PWR_MAX_TO_DEC: usize = EXPONENT_MAX as usize / 4;
-const TEN_PWR_MAX_FROM_DEC: usize = (EXPONENT_MAX as u64 * 301029995 / 1000000000) as usize;
+const TEN_PWR_MAX_FROM_DEC: usize = (EXPONENT_MAX as u128 * 301029995 / 1000000000) as usize;
+const RDX_PWR_MAX: usize = EXPONENT_MAX as usize / 6;
 
 impl BigFloatNumber {
     /// Converts an array of digits in radix `rdx` to BigFloatNumber with precision `p`.
@@ -38,16 +39,40 @@ impl BigFloatNumber {
     ///  - MemoryAllocation: failed to allocate memory for mantissa.
     ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
     ///  - InvalidArgument: the precision is incorrect, or `digits` contains unacceptable digits for given radix,
-    /// or when `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
+    ///    or when `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
     pub fn convert_from_radix(
         sign: Sign,
         digits: &[u8],
         e: Exponent,
         rdx: Radix,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        Self::convert_from_base(sign, digits, e, rdx as u32, p, rm, cc)
+    }
+
+    /// Converts an array of digits in radix `base` to BigFloatNumber with precision `p`.
+    /// `base` can be any number from 2 to 36. The other arguments are the same as in `convert_from_radix`.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///  - InvalidArgument: `base` is not in the range from 2 to 36, or any of the conditions listed in `convert_from_radix`.
+    pub fn convert_from_base(
+        sign: Sign,
+        digits: &[u8],
+        e: Exponent,
+        base: u32,
         mut p: usize,
         rm: RoundingMode,
         cc: &mut Consts,
     ) -> Result<Self, Error> {
+        if !(2..=36).contains(&base) {
+            return Err(Error::InvalidArgument);
+        }
+
         if p < usize::MAX {
             p = round_p(p);
             Self::p_assertion(p)?;
@@ -57,16 +82,23 @@ impl BigFloatNumber {
             }
         }
 
-        #[cfg(target_arch = "x86")]
-        if e < EXPONENT_MIN || e > EXPONENT_MAX {
+        #[cfg(any(target_arch = "x86", feature = "exp64"))]
+        if !(EXPONENT_MIN..=EXPONENT_MAX).contains(&e) {
             return Err(Error::InvalidArgument);
         }
 
-        match rdx {
-            Radix::Bin => Self::conv_from_binary(sign, digits, e, p, rm),
-            Radix::Oct => Self::conv_from_commensurable(sign, digits, e, 3, p, rm),
-            Radix::Dec => Self::conv_from_dec(sign, digits, e, p, rm, cc),
-            Radix::Hex => Self::conv_from_commensurable(sign, digits, e, 4, p, rm),
+        match base {
+            2 => Self::conv_from_binary(sign, digits, e, p, rm),
+            4 | 8 | 16 | 32 => Self::conv_from_commensurable(
+                sign,
+                digits,
+                e,
+                base.trailing_zeros() as usize,
+                p,
+                rm,
+            ),
+            10 => Self::conv_from_dec(sign, digits, e, p, rm, cc),
+            _ => Self::conv_from_generic(sign, digits, e, base, p, rm),
         }
     }
 
@@ -362,6 +394,123 @@ impl BigFloatNumber {
         }
     }
 
+    /// Conversion for radixes which are neither decimal nor powers of 2.
+    fn conv_from_generic(
+        sign: Sign,
+        digits: &[u8],
+        e: Exponent,
+        base: u32,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Self, Error> {
+        // mantissa part
+        let leadzeroes = digits.iter().take_while(|&&x| x == 0).count();
+
+        if digits.len() - leadzeroes == 0 {
+            return Self::new(if p < usize::MAX { p } else { DEFAULT_P });
+        }
+
+        if digits.iter().any(|&d| d as u32 >= base) {
+            return Err(Error::InvalidArgument);
+        }
+
+        let lg = log2_fixed(base);
+        let rdx = BigFloatNumber::from_word(base as Word, WORD_BIT_SIZE)?;
+        let mut x = Self::conv_int_from_digits(&digits[leadzeroes..], base, &rdx, lg)?;
+        x.set_sign(sign);
+
+        // exponent part
+        let n = e as isize - digits.len() as isize;
+
+        let p = if p < usize::MAX {
+            p
+        } else {
+            // determine from the input
+            let p = round_p(bits_for_digits(digits.len(), lg) + 1);
+            Self::p_assertion(p)?;
+            p
+        };
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p + p_inc;
+
+        // error estimation
+        let mut err = 0;
+        let npowmax = n.unsigned_abs() / RDX_PWR_MAX;
+        let pwrrem = n.unsigned_abs() % RDX_PWR_MAX;
+        if npowmax != 0 {
+            err += 3 * npowmax;
+        }
+        if pwrrem != 0 {
+            err += 3;
+        }
+
+        loop {
+            let p_f = p_wrk + err;
+
+            let mut f = x.clone()?;
+
+            if npowmax != 0 {
+                let fpnmax = rdx.powi(RDX_PWR_MAX, p_f, RoundingMode::None)?;
+
+                for _ in 0..npowmax {
+                    if n < 0 {
+                        f = f.div(&fpnmax, p_f, RoundingMode::None)?
+                    } else {
+                        f = f.mul(&fpnmax, p_f, RoundingMode::None)?
+                    }
+                }
+            };
+
+            if pwrrem != 0 {
+                let fpn = rdx.powi(pwrrem, p_f, RoundingMode::None)?;
+                if n < 0 {
+                    f = f.div(&fpn, p_f, RoundingMode::None)?
+                } else {
+                    f = f.mul(&fpn, p_f, RoundingMode::None)?
+                }
+            }
+
+            f.set_sign(sign);
+
+            if f.try_set_precision(p, rm, p_wrk)? {
+                return Ok(f);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    /// Converts digits in radix `base` to an integer number exactly.
+    /// `rdx` is `base` as a number, and `lg` is the result of `log2_fixed(base)`.
+    fn conv_int_from_digits(digits: &[u8], base: u32, rdx: &Self, lg: u64) -> Result<Self, Error> {
+        let (chunk, _) = word_chunk(base);
+
+        if digits.len() <= chunk {
+            let mut w: Word = 0;
+            for &d in digits {
+                w = w * base as Word + d as Word;
+            }
+
+            return Self::from_word(w, WORD_BIT_SIZE);
+        }
+
+        let (hi, lo) = digits.split_at(digits.len() - digits.len() / 2);
+
+        let h = Self::conv_int_from_digits(hi, base, rdx, lg)?;
+        let l = Self::conv_int_from_digits(lo, base, rdx, lg)?;
+
+        // the power is computed exactly because the precision is sufficient
+        let pwr = rdx.powi(
+            lo.len(),
+            round_p(bits_for_digits(lo.len(), lg) + WORD_BIT_SIZE),
+            RoundingMode::None,
+        )?;
+
+        h.mul_full_prec(&pwr)?.add_full_prec(&l)
+    }
+
     /// Converts `self` to radix `rdx` using rounding mode `rm`.
     /// The function returns sign, mantissa digits in radix `rdx`, and exponent such that the converted number
     /// can be represented as `mantissa digits` * `rdx` ^ `exponent`.
@@ -377,11 +526,34 @@ impl BigFloatNumber {
         rm: RoundingMode,
         cc: &mut Consts,
     ) -> Result<(Sign, Vec<u8>, Exponent), Error> {
-        match rdx {
-            Radix::Bin => self.conv_to_binary(),
-            Radix::Oct => self.conv_to_commensurable(3),
-            Radix::Dec => self.conv_to_dec(rm, cc),
-            Radix::Hex => self.conv_to_commensurable(4),
+        self.convert_to_base(rdx as u32, rm, cc)
+    }
+
+    /// Converts `self` to radix `base` using rounding mode `rm`.
+    /// `base` can be any number from 2 to 36. The result is the same as in `convert_to_radix`.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///  - InvalidArgument: `base` is not in the range from 2 to 36.
+    pub fn convert_to_base(
+        &self,
+        base: u32,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<(Sign, Vec<u8>, Exponent), Error> {
+        match base {
+            2 => self.conv_to_binary(),
+            4 | 8 | 16 | 32 => self.conv_to_commensurable(base.trailing_zeros() as usize),
+            10 => self.conv_to_dec(rm, cc),
+            3..=36 => {
+                // enough digits for the number to be restored exactly
+                let lg = log2_fixed(base);
+                let n = ((self.mantissa_max_bit_len() as u128) << 32).div_ceil(lg as u128);
+                self.conv_to_base_digits(base, n as usize + 1, rm, cc)
+            }
+            _ => Err(Error::InvalidArgument),
         }
     }
 
@@ -389,6 +561,24 @@ impl BigFloatNumber {
         &self,
         rm: RoundingMode,
         cc: &mut Consts,
+    ) -> Result<(Sign, Vec<u8>, Exponent), Error> {
+        let n = (self.mantissa_max_bit_len() as u64 * 301029996 / 1000000000) as usize + 1;
+        self.conv_to_dec_digits(n, rm, cc)
+    }
+
+    /// Converts `self` to decimal with `n` significant digits using rounding mode `rm`.
+    /// The function returns sign, mantissa digits, and exponent such that the converted number
+    /// can be represented as `0.mantissa digits` * `10` ^ `exponent`. Trailing zeroes are removed from the mantissa.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    pub(crate) fn conv_to_dec_digits(
+        &self,
+        n: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
     ) -> Result<(Sign, Vec<u8>, Exponent), Error> {
         if self.precision() == 0 {
             return Ok((self.sign(), Vec::new(), 0));
@@ -396,7 +586,6 @@ impl BigFloatNumber {
 
         let p = self.mantissa_max_bit_len();
         let subn_e = p - self.precision();
-        let n = (p as u64 * 301029996 / 1000000000) as usize + 1;
 
         let mut err = WORD_BIT_SIZE; // speculative
         let mut p_wrk = round_p((n as u64 * 3321928095 / 1000000000) as usize + 1 + err);
@@ -406,8 +595,10 @@ impl BigFloatNumber {
             let mut x = self.clone()?;
             x.set_inexact(false);
 
-            let n_wrk = ((p_wrk as i64 - self.exponent() as i64 + subn_e as i64) * 301029996
-                / 1000000000) as isize
+            // log10(2) is rounded upwards with the precision sufficient for large exponents
+            let n_wrk = ((p_wrk as i128 - self.exponent() as i128 + subn_e as i128)
+                * 30102999566398119522
+                / 100000000000000000000) as isize
                 + 1;
 
             let mut err_acc = 0;
@@ -514,6 +705,139 @@ impl BigFloatNumber {
         }
     }
 
+    /// Converts `self` to radix `base` with `n` significant digits using rounding mode `rm`.
+    /// The function returns sign, mantissa digits, and exponent such that the converted number
+    /// can be represented as `0.mantissa digits` * `base` ^ `exponent`. Trailing zeroes are removed from the mantissa.
+    /// `base` must be in the range from 3 to 36.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    pub(crate) fn conv_to_base_digits(
+        &self,
+        base: u32,
+        n: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<(Sign, Vec<u8>, Exponent), Error> {
+        debug_assert!((3..=36).contains(&base));
+
+        if base == 10 {
+            return self.conv_to_dec_digits(n, rm, cc);
+        }
+
+        if self.precision() == 0 {
+            return Ok((self.sign(), Vec::new(), 0));
+        }
+
+        let p = self.mantissa_max_bit_len();
+        let subn_e = p - self.precision();
+
+        let lg = log2_fixed(base);
+        let lg_e = log2_fixed_frac(base, 56);
+        let rdx = BigFloatNumber::from_word(base as Word, WORD_BIT_SIZE)?;
+
+        let mut err = WORD_BIT_SIZE; // speculative
+        let mut p_wrk = round_p(bits_for_digits(n, lg) + 1 + err);
+        let mut p_inc = WORD_BIT_SIZE;
+
+        loop {
+            let mut x = self.clone()?;
+            x.set_inexact(false);
+
+            // rdx^n_wrk must make x an integer with at least p_wrk bits;
+            // the exponent can be large, so a more precise logarithm is used
+            let v = p_wrk as i64 - self.exponent() as i64 + subn_e as i64;
+            let n_wrk = if v >= 0 {
+                ((v as u128) << 56).div_ceil(lg_e as u128) as isize + 1
+            } else {
+                -((((v.unsigned_abs() as u128) << 56) / (lg_e as u128 + 2)) as isize) + 1
+            };
+
+            let mut err_acc = 0;
+
+            let mut pwr = n_wrk.unsigned_abs();
+            if pwr > RDX_PWR_MAX {
+                let tp = rdx.powi(RDX_PWR_MAX, p_wrk, RoundingMode::None)?;
+                err_acc += 1;
+
+                while pwr > RDX_PWR_MAX {
+                    if n_wrk < 0 {
+                        x = x.div(&tp, p_wrk, RoundingMode::None)?;
+                    } else {
+                        x = x.mul(&tp, p_wrk, RoundingMode::None)?;
+                    }
+                    err_acc += 2;
+                    pwr -= RDX_PWR_MAX;
+                }
+            }
+
+            if pwr != 0 {
+                let tp = rdx.powi(pwr, p_wrk, RoundingMode::None)?;
+                if n_wrk < 0 {
+                    x = x.div(&tp, p_wrk, RoundingMode::None)?;
+                } else {
+                    x = x.mul(&tp, p_wrk, RoundingMode::None)?;
+                }
+                err_acc += 3;
+            }
+
+            if err_acc > err {
+                err_acc += err_acc / RDX_PWR_MAX + 3;
+                p_wrk += round_p(err_acc - err);
+                err = err_acc;
+                continue;
+            }
+
+            let (mut m, _, e, inexact) = x.into_raw_parts();
+
+            let shift = e as usize - p_wrk;
+            if shift > 0 {
+                m.shift_left_resize(shift)?;
+            }
+
+            let mut digits = words_to_base_digits(m.digits(), base)?;
+
+            let e_out = digits.len() as isize - n_wrk;
+            if e_out > EXPONENT_MAX as isize {
+                return Err(Error::ExponentOverflow(self.sign()));
+            }
+
+            let mut e_out = e_out as Exponent;
+
+            // cut off digits with error
+            let valid = digits.len() as i64
+                - (((shift + err_acc) as u128) << 32).div_ceil(lg as u128) as i64
+                - 1;
+
+            if digits.len() > n
+                && valid > n as i64
+                && Self::try_round_digits(
+                    &mut digits[..valid as usize],
+                    n,
+                    base,
+                    rm,
+                    self.sign(),
+                    &mut e_out,
+                    inexact,
+                )?
+            {
+                digits.truncate(n);
+
+                // remove trailing zeroes
+                let nzr = digits.iter().rev().take_while(|&&x| x == 0).count();
+
+                digits.resize(digits.len() - nzr, 0);
+
+                return Ok((self.sign(), digits, e_out));
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
     // Try to round a decimal mantissa.
     fn try_round_dec(
         digits: &mut [u8],
@@ -522,11 +846,25 @@ impl BigFloatNumber {
         s: Sign,
         e: &mut Exponent,
         inexact: bool,
+    ) -> Result<bool, Error> {
+        Self::try_round_digits(digits, n, 10, rm, s, e, inexact)
+    }
+
+    // Try to round a mantissa in radix `base`.
+    fn try_round_digits(
+        digits: &mut [u8],
+        n: usize,
+        base: u32,
+        rm: RoundingMode,
+        s: Sign,
+        e: &mut Exponent,
+        inexact: bool,
     ) -> Result<bool, Error> {
         let mut check_roundable = inexact;
 
         if n > 0 {
-            let ovf = Self::round_dec(digits, n, rm, s.is_positive(), &mut check_roundable);
+            let ovf =
+                Self::round_digits(digits, n, base, rm, s.is_positive(), &mut check_roundable);
 
             if check_roundable {
                 return Ok(false);
@@ -545,74 +883,103 @@ impl BigFloatNumber {
     }
 
     // Round decimal mantissa.
-    // The function is similar to Mantissa::round_mantissa.
+    #[cfg(test)]
     fn round_dec(
         digits: &mut [u8],
         n: usize,
         rm: RoundingMode,
         is_positive: bool,
         check_roundable: &mut bool,
+    ) -> bool {
+        Self::round_digits(digits, n, 10, rm, is_positive, check_roundable)
+    }
+
+    // Round mantissa in radix `base`.
+    // The function is similar to Mantissa::round_mantissa.
+    fn round_digits(
+        digits: &mut [u8],
+        n: usize,
+        base: u32,
+        rm: RoundingMode,
+        is_positive: bool,
+        check_roundable: &mut bool,
     ) -> bool {
         if rm == RoundingMode::None {
             *check_roundable = false;
             return false;
         }
 
+        let dmax = (base - 1) as u8;
+        let half = (base / 2) as u8;
+
         #[inline]
-        fn get_rem(arr: &[u8]) -> (bool, bool) {
-            let mut rem9 = true;
+        fn get_rem(arr: &[u8], dmax: u8) -> (bool, bool) {
+            let mut remmax = true;
             let mut rem0 = true;
 
             for &d in arr.iter() {
-                if d != 9 {
-                    rem9 = false;
+                if d != dmax {
+                    remmax = false;
                 }
                 if d != 0 {
                     rem0 = false;
                 }
             }
-            (rem0, rem9)
+            (rem0, remmax)
         }
 
         if n > 0 && n < digits.len() {
             let mut c = false;
 
-            if rm == RoundingMode::ToEven || rm == RoundingMode::ToOdd {
-                let is_even = digits[n - 1] % 2 == 0;
+            if (rm == RoundingMode::ToEven || rm == RoundingMode::ToOdd) && base % 2 == 1 {
+                // half of the unit is 0.hhh... in odd radix, where h = (base - 1) / 2,
+                // so the remainder can't be exactly a half.
+                let h = dmax / 2;
+                let last = digits.len() - 1;
+
+                if *check_roundable && digits[n..last].iter().all(|&d| d == h) {
+                    return false;
+                }
+
+                if let Some(&d) = digits[n..].iter().find(|&&d| d != h) {
+                    c = d > h;
+                }
+            } else if rm == RoundingMode::ToEven || rm == RoundingMode::ToOdd {
+                let is_even = digits[n - 1].is_multiple_of(2);
                 let dn = digits[n];
 
-                let (rem0, rem9) = get_rem(&digits[n + 1..]);
+                let (rem0, remmax) = get_rem(&digits[n + 1..], dmax);
 
-                if *check_roundable && (rem0 || rem9) {
+                if *check_roundable && (rem0 || remmax) {
                     return false;
                 }
 
                 // need adding 1?
                 match rm {
                     RoundingMode::ToEven => {
-                        if dn == 5 {
+                        if dn == half {
                             if !is_even || !rem0 {
                                 c = true;
                             }
-                        } else if dn > 5 {
+                        } else if dn > half {
                             c = true;
                         }
                     }
                     RoundingMode::ToOdd => {
-                        if dn == 5 {
+                        if dn == half {
                             if is_even || !rem0 {
                                 c = true;
                             }
-                        } else if dn > 5 {
+                        } else if dn > half {
                             c = true;
                         }
                     }
                     _ => unreachable!(),
                 };
             } else {
-                let (rem0, rem9) = get_rem(&digits[n..]);
+                let (rem0, remmax) = get_rem(&digits[n..], dmax);
 
-                if *check_roundable && (rem0 || rem9) {
+                if *check_roundable && (rem0 || remmax) {
                     return false;
                 }
 
@@ -647,7 +1014,7 @@ impl BigFloatNumber {
 
             if c {
                 for v in digits[..n].iter_mut().rev() {
-                    if *v < 9 {
+                    if *v < dmax {
                         *v += 1;
                         return false;
                     } else {
@@ -750,6 +1117,90 @@ impl BigFloatNumber {
     }
 }
 
+/// Returns log2(`base`) as a fixed point number with 32 fractional bits rounded down.
+pub(crate) fn log2_fixed(base: u32) -> u64 {
+    log2_fixed_frac(base, 32)
+}
+
+/// Returns log2(`base`) as a fixed point number with `frac` fractional bits rounded down.
+/// `frac` must not exceed 58.
+fn log2_fixed_frac(base: u32, frac: usize) -> u64 {
+    let k = 31 - base.leading_zeros();
+
+    // base / 2^k in [1, 2) with 62 fractional bits
+    let mut y = (base as u128) << (62 - k);
+    let mut ret = k as u64;
+
+    for _ in 0..frac {
+        y = (y * y) >> 62;
+        ret <<= 1;
+        if y >= 2 << 62 {
+            y >>= 1;
+            ret |= 1;
+        }
+    }
+
+    ret
+}
+
+/// Returns the number of bits sufficient to hold `n` digits in radix with `lg` = log2_fixed(radix).
+fn bits_for_digits(n: usize, lg: u64) -> usize {
+    ((n as u128 * (lg as u128 + 1)) >> 32) as usize + 1
+}
+
+/// Returns the maximum number of digits in radix `base` which fit in a word, and `base` to the power of that number.
+fn word_chunk(base: u32) -> (usize, Word) {
+    let mut chunk = 0;
+    let mut pwr: Word = 1;
+
+    while let Some(v) = pwr.checked_mul(base as Word) {
+        pwr = v;
+        chunk += 1;
+    }
+
+    (chunk, pwr)
+}
+
+/// Converts the integer number represented by words `m` to digits in radix `base`.
+/// The first word in `m` is the least significant word, and the first digit is the most significant digit.
+fn words_to_base_digits(m: &[Word], base: u32) -> Result<Vec<u8>, Error> {
+    let (chunk, pwr) = word_chunk(base);
+
+    let mut m = m.to_vec();
+    while m.last() == Some(&0) {
+        m.pop();
+    }
+
+    let mut ret = Vec::new();
+    ret.try_reserve_exact(m.len() * chunk + chunk)?;
+
+    while !m.is_empty() {
+        let mut rem: DoubleWord = 0;
+
+        for w in m.iter_mut().rev() {
+            let v = (rem << WORD_BIT_SIZE) | *w as DoubleWord;
+            *w = (v / pwr as DoubleWord) as Word;
+            rem = v % pwr as DoubleWord;
+        }
+
+        while m.last() == Some(&0) {
+            m.pop();
+        }
+
+        let mut r = rem as Word;
+        for _ in 0..chunk {
+            ret.push((r % base as Word) as u8);
+            r /= base as Word;
+        }
+    }
+
+    let nzr = ret.iter().rev().take_while(|&&x| x == 0).count();
+    ret.truncate(ret.len() - nzr);
+    ret.reverse();
+
+    Ok(ret)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1471,6 +1922,124 @@ mod tests {
         // unknown p: decimal
     }
 
+    #[test]
+    fn test_conv_base() {
+        let mut cc = Consts::new().unwrap();
+
+        assert_eq!(log2_fixed(3), 6807362105);
+        assert_eq!(log2_fixed(8), 3 << 32);
+        assert_eq!(log2_fixed(10), 14267572527);
+        assert_eq!(log2_fixed(36), 22204658803);
+        assert_eq!(log2_fixed_frac(3, 56), 114208584442304135);
+
+        assert!(words_to_base_digits(&[0, 0], 3).unwrap().is_empty());
+        assert_eq!(words_to_base_digits(&[36, 0], 3).unwrap(), [1, 1, 0, 0]);
+        assert_eq!(
+            words_to_base_digits(&[1, 1], 36).unwrap().len(),
+            (WORD_BIT_SIZE * 1000 / 5169) + 1
+        );
+
+        // basic tests
+        let n = BigFloatNumber::from_f64(64, -36.0).unwrap();
+        let (s, m, e) = n.convert_to_base(3, RoundingMode::None, &mut cc).unwrap();
+        assert_eq!(s, Sign::Neg);
+        assert_eq!(m, [1, 1]);
+        assert_eq!(e, 4);
+
+        let n = BigFloatNumber::from_f64(64, 0.1).unwrap();
+        let (_, m, e) = n
+            .convert_to_base(36, RoundingMode::ToEven, &mut cc)
+            .unwrap();
+        assert_eq!(&m[..3], [3, 21, 21]);
+        assert_eq!(e, 0);
+
+        let g = BigFloatNumber::convert_from_base(
+            Sign::Pos,
+            &[35, 18],
+            2,
+            36,
+            64,
+            RoundingMode::None,
+            &mut cc,
+        )
+        .unwrap();
+        assert_eq!(g.to_f64(), 1278.0);
+
+        // incorrect base or digit
+        for base in [0, 1, 37] {
+            assert_eq!(
+                BigFloatNumber::convert_from_base(
+                    Sign::Pos,
+                    &[1],
+                    0,
+                    base,
+                    64,
+                    RoundingMode::None,
+                    &mut cc
+                )
+                .unwrap_err(),
+                Error::InvalidArgument
+            );
+            assert_eq!(
+                n.convert_to_base(base, RoundingMode::None, &mut cc)
+                    .unwrap_err(),
+                Error::InvalidArgument
+            );
+        }
+        assert_eq!(
+            BigFloatNumber::convert_from_base(
+                Sign::Pos,
+                &[1, 12],
+                0,
+                12,
+                64,
+                RoundingMode::None,
+                &mut cc
+            )
+            .unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // round trip, decimal conversion is tested in test_conv_num
+        for base in (2..=36).filter(|&b| b != 10) {
+            for _ in 0..3 {
+                let p = (random::<usize>() % 5 + 1) * WORD_BIT_SIZE;
+                let subnormal = random::<u8>() % 8 == 0;
+                let n = if subnormal {
+                    random_subnormal(p)
+                } else {
+                    BigFloatNumber::random_normal(p, EXPONENT_MIN + p as Exponent, EXPONENT_MAX)
+                        .unwrap()
+                };
+                let p = n.mantissa_max_bit_len();
+
+                let (s, m, e) = n
+                    .convert_to_base(base, RoundingMode::ToEven, &mut cc)
+                    .unwrap();
+                let g = BigFloatNumber::convert_from_base(
+                    s,
+                    &m,
+                    e,
+                    base,
+                    p,
+                    RoundingMode::ToEven,
+                    &mut cc,
+                )
+                .unwrap();
+
+                if subnormal && !base.is_power_of_two() {
+                    let mut eps = BigFloatNumber::min_positive(p).unwrap();
+                    eps.set_exponent(eps.exponent() + 1);
+
+                    let d = n.sub(&g, p, RoundingMode::None).unwrap().abs().unwrap();
+                    assert!(d.cmp(&eps) <= 0, "{} {:?} {:?}", base, n, g);
+                } else {
+                    assert_eq!(n.cmp(&g), 0, "{} {:?} {:?}", base, n, g);
+                }
+            }
+        }
+    }
+
     fn random_radix() -> Radix {
         match random::<usize>() % 4 {
             0 => Radix::Bin,
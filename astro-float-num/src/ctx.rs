@@ -1,5 +1,9 @@
 //! Context is used in expressions returning `BigFloat`.
 
+use crate::common::util::round_p;
+use crate::defs::DEFAULT_P;
+use crate::macro_util::round_to_exponent_range;
+use crate::num::BigFloatNumber;
 use crate::BigFloat;
 use crate::Consts;
 use crate::Error;
@@ -7,8 +11,410 @@ use crate::Exponent;
 use crate::RoundingMode;
 use crate::EXPONENT_MAX;
 use crate::EXPONENT_MIN;
+use crate::WORD_BIT_SIZE;
+
+#[cfg(feature = "debug")]
+use crate::tag::Tag;
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+/// Sticky status flags accumulated by a context.
+///
+/// A flag is raised when the corresponding condition occurs in any operation performed through the context,
+/// and stays raised until the status is reset with `Context::reset_status`.
+/// The flags are similar to the status flags of IEEE 754: inexact, invalid, overflow, and underflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Status {
+    inexact: bool,
+    clamped: bool,
+    invalid: bool,
+    overflow: bool,
+    underflow: bool,
+}
+
+impl Status {
+    /// Returns true if the result of an operation was inexact.
+    pub fn inexact(&self) -> bool {
+        self.inexact
+    }
+
+    /// Returns true if a value was replaced with infinity or zero because its exponent was outside of the exponent range of the context.
+    pub fn clamped(&self) -> bool {
+        self.clamped
+    }
+
+    /// Returns true if an operation returned NaN.
+    pub fn invalid(&self) -> bool {
+        self.invalid
+    }
+
+    /// Returns true if a value exceeded the maximum exponent of the context and was replaced with infinity,
+    /// or if an operation returned NaN because of the exponent overflow.
+    pub fn overflow(&self) -> bool {
+        self.overflow
+    }
+
+    /// Returns true if a nonzero value was smaller than the minimum exponent of the context and was replaced with zero,
+    /// or if an operation returned an inexact subnormal number, including the subnormal numbers of `RangeMode::IeeeSubnormal`.
+    pub fn underflow(&self) -> bool {
+        self.underflow
+    }
+
+    /// Returns true if no flag is raised, i.e. all operations were exact, valid, and did not leave the exponent range.
+    pub fn is_clear(&self) -> bool {
+        !(self.inexact || self.clamped || self.invalid || self.overflow || self.underflow)
+    }
+
+    /// Raises the overflow and the clamped flags.
+    pub(crate) fn raise_overflow(&mut self) {
+        self.overflow = true;
+        self.clamped = true;
+    }
+
+    /// Raises the underflow and the clamped flags.
+    pub(crate) fn raise_underflow(&mut self) {
+        self.underflow = true;
+        self.clamped = true;
+    }
+
+    /// Raises the inexact flag.
+    pub(crate) fn raise_inexact(&mut self) {
+        self.inexact = true;
+    }
+
+    /// Raises the underflow flag without the clamped flag.
+    pub(crate) fn raise_subnormal_underflow(&mut self) {
+        self.underflow = true;
+    }
+
+    fn merge(&mut self, other: &Status) {
+        self.inexact |= other.inexact;
+        self.clamped |= other.clamped;
+        self.invalid |= other.invalid;
+        self.overflow |= other.overflow;
+        self.underflow |= other.underflow;
+    }
+
+    fn update(&mut self, n: &BigFloat, clamped: bool) {
+        self.inexact |= n.inexact();
+        self.invalid |= n.is_nan();
+        self.clamped |= clamped;
+        self.overflow |= matches!(n.err(), Some(Error::ExponentOverflow(_)));
+        self.underflow |= n.inexact() && n.is_subnormal();
+    }
+}
+
+/// The way a context brings the results of operations into its exponent range.
+///
+/// The range modes `Ieee` and `IeeeSubnormal` emulate an IEEE 754 binary format with the precision and the exponent range of the context.
+/// For example, a context with the precision 64, `emin` equal to -16381, and `emax` equal to 16384 emulates the x87 extended precision format,
+/// since the exponent of the numbers is defined for the mantissa in the range [0.5, 1), unlike in IEEE 754.
+/// The mode applies to the results of `Context::call`, `Context::sum`, `Context::product`, and `expr!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RangeMode {
+    /// A result whose exponent is larger than `emax` is replaced with infinity of the same sign,
+    /// and a result whose exponent is smaller than `emin` is replaced with positive zero.
+    #[default]
+    Clamp,
+
+    /// A result whose exponent is larger than `emax` is replaced with infinity, or with the largest finite number
+    /// if the rounding mode rounds the result towards zero, in the same way as in IEEE 754.
+    /// A result whose exponent after rounding is smaller than `emin` is flushed to zero of the same sign.
+    Ieee,
+
+    /// Same as `Ieee`, but a result whose exponent after rounding is smaller than `emin` is rounded once to a subnormal number,
+    /// i.e. to a multiple of `2^(emin - p)`, where `p` is the precision of the context rounded upwards to the word size (gradual underflow).
+    IeeeSubnormal,
+}
+
+/// Built-in function whose results can be memoized by the cache of function calls.
+/// The arguments of a function are listed in the order of the arguments of the corresponding function in `expr!`,
+/// except for the Bessel functions, which take the argument first and the order second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    /// Reciprocal `1 / x`.
+    Recip,
+    /// Square root of `x`.
+    Sqrt,
+    /// Cube root of `x`.
+    Cbrt,
+    /// Euclidean distance `sqrt(x^2 + y^2)` of the arguments `x` and `y`.
+    Hypot,
+    /// Natural logarithm of `x`.
+    Ln,
+    /// Logarithm base 2 of `x`.
+    Log2,
+    /// Logarithm base 10 of `x`.
+    Log10,
+    /// Logarithm of `x` base `b` of the arguments `x` and `b`.
+    Log,
+    /// `e` to the power of `x`.
+    Exp,
+    /// `x` to the power of `y` of the arguments `x` and `y`.
+    Pow,
+    /// Sine of `x`.
+    Sin,
+    /// Cosine of `x`.
+    Cos,
+    /// Tangent of `x`.
+    Tan,
+    /// Arcsine of `x`.
+    Asin,
+    /// Arccosine of `x`.
+    Acos,
+    /// Arctangent of `x`.
+    Atan,
+    /// Hyperbolic sine of `x`.
+    Sinh,
+    /// Hyperbolic cosine of `x`.
+    Cosh,
+    /// Hyperbolic tangent of `x`.
+    Tanh,
+    /// Hyperbolic arcsine of `x`.
+    Asinh,
+    /// Hyperbolic arccosine of `x`.
+    Acosh,
+    /// Hyperbolic arctangent of `x`.
+    Atanh,
+    /// Hyperbolic secant of `x`.
+    Sech,
+    /// Hyperbolic cosecant of `x`.
+    Csch,
+    /// Hyperbolic cotangent of `x`.
+    Coth,
+    /// Hyperbolic arcsecant of `x`.
+    Asech,
+    /// Hyperbolic arccosecant of `x`.
+    Acsch,
+    /// Hyperbolic arccotangent of `x`.
+    Acoth,
+    /// Gamma function of `x`.
+    Gamma,
+    /// Bessel function of the first kind of the argument `x` and the order `n`.
+    BesselJ,
+    /// Bessel function of the second kind of the argument `x` and the order `n`.
+    BesselY,
+    /// Modified Bessel function of the first kind of the argument `x` and the order `n`.
+    BesselI,
+    /// Modified Bessel function of the second kind of the argument `x` and the order `n`.
+    BesselK,
+}
+
+impl Func {
+    /// Returns the number of arguments of the function.
+    pub fn arg_num(self) -> usize {
+        match self {
+            Func::Hypot
+            | Func::Log
+            | Func::Pow
+            | Func::BesselJ
+            | Func::BesselY
+            | Func::BesselI
+            | Func::BesselK => 2,
+            _ => 1,
+        }
+    }
+
+    /// Computes the function of the arguments `args` with precision `p` rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// The function returns NaN if the number of arguments is incorrect.
+    pub fn eval(self, args: &[&BigFloat], p: usize, rm: RoundingMode, cc: &mut Consts) -> BigFloat {
+        if args.len() != self.arg_num() {
+            return BigFloat::nan(Some(Error::InvalidArgument));
+        }
+
+        let x = args[0];
+
+        match self {
+            Func::Recip => x.reciprocal(p, rm),
+            Func::Sqrt => x.sqrt(p, rm),
+            Func::Cbrt => x.cbrt(p, rm),
+            Func::Hypot => x.hypot(args[1], p, rm),
+            Func::Ln => x.ln(p, rm, cc),
+            Func::Log2 => x.log2(p, rm, cc),
+            Func::Log10 => x.log10(p, rm, cc),
+            Func::Log => x.log(args[1], p, rm, cc),
+            Func::Exp => x.exp(p, rm, cc),
+            Func::Pow => x.pow(args[1], p, rm, cc),
+            Func::Sin => x.sin(p, rm, cc),
+            Func::Cos => x.cos(p, rm, cc),
+            Func::Tan => x.tan(p, rm, cc),
+            Func::Asin => x.asin(p, rm, cc),
+            Func::Acos => x.acos(p, rm, cc),
+            Func::Atan => x.atan(p, rm, cc),
+            Func::Sinh => x.sinh(p, rm, cc),
+            Func::Cosh => x.cosh(p, rm, cc),
+            Func::Tanh => x.tanh(p, rm, cc),
+            Func::Asinh => x.asinh(p, rm, cc),
+            Func::Acosh => x.acosh(p, rm, cc),
+            Func::Atanh => x.atanh(p, rm, cc),
+            Func::Sech => x.sech(p, rm, cc),
+            Func::Csch => x.csch(p, rm, cc),
+            Func::Coth => x.coth(p, rm, cc),
+            Func::Asech => x.asech(p, rm, cc),
+            Func::Acsch => x.acsch(p, rm, cc),
+            Func::Acoth => x.acoth(p, rm, cc),
+            Func::Gamma => x.gamma(p, rm, cc),
+            Func::BesselJ => x.besselj(args[1], p, rm, cc),
+            Func::BesselY => x.bessely(args[1], p, rm, cc),
+            Func::BesselI => x.besseli(args[1], p, rm, cc),
+            Func::BesselK => x.besselk(args[1], p, rm, cc),
+        }
+    }
+}
+
+/// Cache of the results of recent calls of built-in functions.
+///
+/// An entry is identified by the function, the exact representation of the arguments,
+/// including their precision and the inexact flag, and the precision and the rounding mode of the result.
+/// When the cache is full, the least recently used entry is evicted.
+/// Calls with an infinite or NaN argument are not memoized.
+#[derive(Debug)]
+pub struct CallCache {
+    capacity: usize,
+    entries: BTreeMap<Vec<u64>, (BigFloat, u64)>,
+    lru: BTreeMap<u64, Vec<u64>>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl CallCache {
+    /// Creates an empty cache which holds up to `capacity` results.
+    pub fn new(capacity: usize) -> Self {
+        CallCache {
+            capacity,
+            entries: BTreeMap::new(),
+            lru: BTreeMap::new(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the maximum number of results the cache holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of results in the cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the cache holds no results.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of calls whose result was found in the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of calls whose result was not found in the cache.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Removes all results from the cache, and resets the numbers of hits and misses.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.lru.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// Returns the result of the function `func` of the arguments `args` with precision `p` rounded using the rounding mode `rm`.
+    /// The result is taken from the cache if it is there, otherwise it is computed and put in the cache.
+    /// This function requires constants cache `cc` for computing the result.
+    pub fn eval(
+        &mut self,
+        func: Func,
+        args: &[&BigFloat],
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> BigFloat {
+        let key = match Self::key(func, args, p, rm) {
+            Some(key) => key,
+            None => return func.eval(args, p, rm, cc),
+        };
+
+        if let Some(v) = self.get(&key) {
+            self.hits += 1;
+            return v;
+        }
+
+        self.misses += 1;
+
+        let v = func.eval(args, p, rm, cc);
+        self.insert(key, &v);
+
+        v
+    }
+
+    // Returns the key of the call, or None if the call is not memoized.
+    #[allow(clippy::unnecessary_cast)] // Word can be u32
+    fn key(func: Func, args: &[&BigFloat], p: usize, rm: RoundingMode) -> Option<Vec<u64>> {
+        if args.len() != func.arg_num() {
+            return None;
+        }
+
+        let mut key = Vec::new();
+        key.push(func as u64);
+        key.push(p as u64);
+        key.push(rm as u64);
+
+        for arg in args {
+            let (m, n, s, e, inexact) = arg.as_raw_parts()?;
+            key.push(n as u64);
+            key.push(s.is_negative() as u64);
+            key.push(e as i64 as u64);
+            key.push(inexact as u64);
+            key.push(m.len() as u64);
+            key.extend(m.iter().map(|&w| w as u64));
+        }
+
+        Some(key)
+    }
+
+    fn get(&mut self, key: &Vec<u64>) -> Option<BigFloat> {
+        let tick = self.tick;
+        let (v, used) = self.entries.get_mut(key)?;
+
+        // move the entry to the end of the eviction order
+        let key = self.lru.remove(used)?;
+        *used = tick;
+        self.lru.insert(tick, key);
+        self.tick += 1;
+
+        Some(v.clone())
+    }
+
+    fn insert(&mut self, key: Vec<u64>, val: &BigFloat) {
+        if self.capacity == 0 || val.err() == Some(Error::MemoryAllocation) {
+            return;
+        }
+
+        if self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.lru.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.lru.insert(self.tick, key.clone());
+        self.entries.insert(key, (val.clone(), self.tick));
+        self.tick += 1;
+    }
+}
 
 /// Context contains parameters, like rounding mode and precision, as well as constant values, and is used with `expr!` macro.
+/// Context also accumulates the sticky status of the operations performed through it,
+/// and can memoize the results of the built-in functions called with `Context::call` or in `expr!`.
 #[derive(Debug)]
 pub struct Context {
     cc: Consts,
@@ -16,12 +422,20 @@ pub struct Context {
     rm: RoundingMode,
     emin: Exponent,
     emax: Exponent,
+    range_mode: RangeMode,
+    status: Status,
+    call_cache: Option<CallCache>,
+    #[cfg(feature = "debug")]
+    seq: usize,
+    #[cfg(feature = "debug")]
+    origin: Option<Tag>,
 }
 
 impl Context {
     /// Create a new context.
     /// The value of `emin` will be clamped to a range between EXPONENT_MIN and 0.
     /// The value of `emax` will be clamped to a range between 0 and EXPONENT_MAX.
+    /// See also `Context::builder`.
     pub fn new(p: usize, rm: RoundingMode, cc: Consts, emin: Exponent, emax: Exponent) -> Self {
         Context {
             cc,
@@ -29,9 +443,39 @@ impl Context {
             rm,
             emin: emin.clamp(EXPONENT_MIN, 0),
             emax: emax.clamp(0, EXPONENT_MAX),
+            range_mode: RangeMode::Clamp,
+            status: Status::default(),
+            call_cache: None,
+            #[cfg(feature = "debug")]
+            seq: 0,
+            #[cfg(feature = "debug")]
+            origin: None,
         }
     }
 
+    /// Returns a builder of a context with the precision `DEFAULT_P`, the rounding mode `RoundingMode::ToEven`,
+    /// the full exponent range, a new constants cache, and memoization disabled.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::RoundingMode;
+    /// # use astro_float_num::ctx::Context;
+    /// let ctx = Context::builder()
+    ///     .precision(256)
+    ///     .rounding(RoundingMode::Down)
+    ///     .emin(-1000)
+    ///     .emax(1000)
+    ///     .build()
+    ///     .expect("Context built");
+    ///
+    /// assert_eq!(ctx.precision(), 256);
+    /// assert_eq!(ctx.emax(), 1000);
+    /// ```
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::default()
+    }
+
     /// Destructures the context and returns its parts: target precision, rounding mode,
     /// constant cache, minimum exponent, maximum exponent.
     pub fn to_raw_parts(self) -> (usize, RoundingMode, Consts, Exponent, Exponent) {
@@ -41,6 +485,13 @@ impl Context {
             cc,
             emin,
             emax,
+            range_mode: _,
+            status: _,
+            call_cache: _,
+            #[cfg(feature = "debug")]
+            seq: _,
+            #[cfg(feature = "debug")]
+            origin: _,
         } = self;
         (p, rm, cc, emin, emax)
     }
@@ -55,6 +506,44 @@ impl Context {
         self.rm = rm;
     }
 
+    /// Calls `f` with the precision of the context temporarily set to `p`, and returns the result of `f`.
+    /// The precision of the context is restored after `f` returns.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, RoundingMode};
+    /// # use astro_float_num::ctx::{Context, Func};
+    /// let mut ctx = Context::builder().precision(128).build().expect("Context built");
+    ///
+    /// let x = BigFloat::from_word(2, 64);
+    /// let y = ctx.with_precision(512, |ctx| ctx.call(Func::Ln, &[&x]));
+    ///
+    /// assert_eq!(y.precision(), Some(512));
+    /// assert_eq!(ctx.precision(), 128);
+    /// ```
+    pub fn with_precision<F, R>(&mut self, p: usize, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        let saved = core::mem::replace(&mut self.p, p);
+        let ret = f(self);
+        self.p = saved;
+        ret
+    }
+
+    /// Calls `f` with the rounding mode of the context temporarily set to `rm`, and returns the result of `f`.
+    /// The rounding mode of the context is restored after `f` returns.
+    pub fn with_rounding<F, R>(&mut self, rm: RoundingMode, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        let saved = core::mem::replace(&mut self.rm, rm);
+        let ret = f(self);
+        self.rm = saved;
+        ret
+    }
+
     /// Sets the constant cache of the context.
     pub fn set_consts(&mut self, cc: Consts) {
         self.cc = cc;
@@ -72,6 +561,11 @@ impl Context {
         self.emax = emax.clamp(0, EXPONENT_MAX);
     }
 
+    /// Sets the way the context brings the results of operations into its exponent range.
+    pub fn set_range_mode(&mut self, mode: RangeMode) {
+        self.range_mode = mode;
+    }
+
     /// Returns the precision of the context.
     pub fn precision(&self) -> usize {
         self.p
@@ -89,22 +583,30 @@ impl Context {
 
     /// Returns the value of the pi number.
     pub fn const_pi(&mut self) -> BigFloat {
-        self.cc.pi(self.p, self.rm)
+        let ret = self.cc.pi(self.p, self.rm);
+        self.status.update(&ret, false);
+        ret
     }
 
     /// Returns the value of the Euler number.
     pub fn const_e(&mut self) -> BigFloat {
-        self.cc.e(self.p, self.rm)
+        let ret = self.cc.e(self.p, self.rm);
+        self.status.update(&ret, false);
+        ret
     }
 
     /// Returns the value of the natural logarithm of 2.
     pub fn const_ln2(&mut self) -> BigFloat {
-        self.cc.ln_2(self.p, self.rm)
+        let ret = self.cc.ln_2(self.p, self.rm);
+        self.status.update(&ret, false);
+        ret
     }
 
     /// Returns the value of the natural logarithm of 10.
     pub fn const_ln10(&mut self) -> BigFloat {
-        self.cc.ln_10(self.p, self.rm)
+        let ret = self.cc.ln_10(self.p, self.rm);
+        self.status.update(&ret, false);
+        ret
     }
 
     /// Returns the minimum exponent.
@@ -117,7 +619,210 @@ impl Context {
         self.emax
     }
 
+    /// Returns the way the context brings the results of operations into its exponent range.
+    pub fn range_mode(&self) -> RangeMode {
+        self.range_mode
+    }
+
+    /// Returns the sticky status accumulated by the context since its creation or the last reset.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, Consts, RoundingMode};
+    /// # use astro_float_num::ctx::{Context, Func};
+    /// let cc = Consts::new().expect("Constants cache allocated");
+    /// let mut ctx = Context::new(128, RoundingMode::ToEven, cc, -1000, 1000);
+    ///
+    /// let x = ctx.call(Func::Exp, &[&BigFloat::from_word(1, 64)]);
+    /// assert!(x.inexact());
+    /// assert!(ctx.status().inexact());
+    /// assert!(!ctx.status().overflow());
+    ///
+    /// ctx.reset_status();
+    /// assert!(ctx.status().is_clear());
+    /// ```
+    #[doc(alias = "flags")]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Clears all flags of the sticky status.
+    /// With the `debug` feature enabled, the recorded origin of NaN or Inf is cleared as well.
+    #[doc(alias = "clear_flags")]
+    pub fn reset_status(&mut self) {
+        self.status = Status::default();
+
+        #[cfg(feature = "debug")]
+        {
+            self.origin = None;
+        }
+    }
+
+    /// Returns the provenance tag of the first NaN or Inf produced through the context
+    /// since its creation or the last reset of the status.
+    #[cfg(feature = "debug")]
+    pub fn origin(&self) -> Option<Tag> {
+        self.origin
+    }
+
+    /// Assigns the next sequence number of the context to the provenance tag of the result `n` of an evaluation,
+    /// and returns `n`. A tag which already has a sequence number, e.g. the tag of a NaN propagated from an argument, is kept.
+    /// Without the `debug` feature `n` is returned unchanged.
+    pub fn tag_result(&mut self, n: BigFloat) -> BigFloat {
+        #[cfg(feature = "debug")]
+        {
+            self.seq += 1;
+            n.sequenced(self.seq - 1)
+        }
+
+        #[cfg(not(feature = "debug"))]
+        n
+    }
+
+    /// Updates the sticky status with the result `n` of an operation.
+    /// `clamped` indicates that a value was clamped to the exponent range of the context during the operation.
+    pub fn update_status(&mut self, n: &BigFloat, clamped: bool) {
+        self.status.update(n, clamped);
+        self.record_origin(n);
+    }
+
+    /// Updates the sticky status with the result `n` of an operation,
+    /// and raises the flags raised in `flags` during the operation.
+    pub fn raise_flags(&mut self, n: &BigFloat, flags: Status) {
+        self.status.update(n, false);
+        self.status.merge(&flags);
+        self.record_origin(n);
+    }
+
+    // Records the tag of `n` as the origin if `n` is the first NaN or Inf produced through the context.
+    #[cfg(feature = "debug")]
+    fn record_origin(&mut self, n: &BigFloat) {
+        if self.origin.is_none() && (n.is_nan() || n.is_inf()) {
+            self.origin = n.tag();
+        }
+    }
+
+    #[cfg(not(feature = "debug"))]
+    #[inline]
+    fn record_origin(&mut self, _n: &BigFloat) {}
+
+    /// Enables memoization of the results of the built-in functions called with `Context::call` or in `expr!`.
+    /// Up to `capacity` most recently used results are kept.
+    /// If memoization is already enabled, the cached results are discarded.
+    pub fn enable_call_cache(&mut self, capacity: usize) {
+        self.call_cache = Some(CallCache::new(capacity));
+    }
+
+    /// Disables memoization of the results of function calls, and discards the cached results.
+    pub fn disable_call_cache(&mut self) {
+        self.call_cache = None;
+    }
+
+    /// Returns the cache of function calls, or None if memoization is disabled.
+    pub fn call_cache(&self) -> Option<&CallCache> {
+        self.call_cache.as_ref()
+    }
+
+    /// Computes the function `func` of the arguments `args` using the precision, the rounding mode, and the constants cache of the context,
+    /// and updates the sticky status with the result.
+    /// The result is brought into the exponent range of the context according to the range mode of the context.
+    ///
+    /// If memoization is enabled with `Context::enable_call_cache`, and the result of `func` for the same arguments,
+    /// precision, and rounding mode is in the cache, the cached result is returned without computing the function.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, Consts, RoundingMode};
+    /// # use astro_float_num::ctx::{Context, Func};
+    /// let cc = Consts::new().expect("Constants cache allocated");
+    /// let mut ctx = Context::new(128, RoundingMode::ToEven, cc, -1000, 1000);
+    /// ctx.enable_call_cache(64);
+    ///
+    /// let x = BigFloat::from_word(2, 64);
+    /// let y1 = ctx.call(Func::Sin, &[&x]);
+    /// let y2 = ctx.call(Func::Sin, &[&x]);
+    ///
+    /// assert_eq!(y1, y2);
+    /// assert_eq!(ctx.call_cache().unwrap().hits(), 1);
+    /// ```
+    pub fn call(&mut self, func: Func, args: &[&BigFloat]) -> BigFloat {
+        self.eval_in_range(|ctx, p, rm| match ctx.call_cache.as_mut() {
+            Some(c) => c.eval(func, args, p, rm, &mut ctx.cc),
+            None => func.eval(args, p, rm, &mut ctx.cc),
+        })
+    }
+
+    /// Computes the sum of `values` using the precision and the rounding mode of the context,
+    /// and updates the sticky status with the result.
+    /// The sum is rounded once, as if it was computed exactly and then rounded.
+    /// The result is checked against the exponent range of the context in the same way as the results of `expr!`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, Consts, RoundingMode};
+    /// # use astro_float_num::ctx::Context;
+    /// let cc = Consts::new().expect("Constants cache allocated");
+    /// let mut ctx = Context::new(128, RoundingMode::ToEven, cc, -1000, 1000);
+    ///
+    /// let big = BigFloat::from_word(1, 128).mul(&BigFloat::from_word(1 << 40, 128), 128, RoundingMode::None);
+    /// let values = [big.clone(), BigFloat::from_word(1, 64), big.neg()];
+    ///
+    /// assert_eq!(ctx.sum(&values), BigFloat::from_word(1, 128));
+    /// assert_eq!(ctx.product(values.iter().take(2)), big);
+    /// ```
+    pub fn sum<'a>(&mut self, values: impl IntoIterator<Item = &'a BigFloat>) -> BigFloat {
+        let values: Vec<&BigFloat> = values.into_iter().collect();
+        self.eval_in_range(|_, p, rm| BigFloat::sum_iter(values.iter().copied(), p, rm))
+    }
+
+    /// Computes the product of `values` using the precision and the rounding mode of the context,
+    /// and updates the sticky status with the result.
+    /// The product is rounded once, as if it was computed exactly and then rounded.
+    /// The result is checked against the exponent range of the context in the same way as the results of `expr!`.
+    pub fn product<'a>(&mut self, values: impl IntoIterator<Item = &'a BigFloat>) -> BigFloat {
+        let values: Vec<&BigFloat> = values.into_iter().collect();
+        self.eval_in_range(|_, p, rm| BigFloat::product_iter(values.iter().copied(), p, rm))
+    }
+
+    // Computes the result with `f` using the precision and the rounding mode of the context,
+    // brings it into the exponent range of the context, and updates the sticky status.
+    fn eval_in_range<F>(&mut self, mut f: F) -> BigFloat
+    where
+        F: FnMut(&mut Self, usize, RoundingMode) -> BigFloat,
+    {
+        let (p, rm) = (self.p, self.rm);
+
+        let mut ret = f(self, p, rm);
+
+        if self.range_mode == RangeMode::IeeeSubnormal
+            && !ret.is_zero()
+            && ret.exponent().is_some_and(|e| e < self.emin)
+        {
+            // a subnormal result is rounded from the result rounded to odd with a larger precision,
+            // which is the same as rounding the exact result once
+            ret = f(self, round_p(p) + WORD_BIT_SIZE, RoundingMode::ToOdd);
+        }
+
+        let mut flags = Status::default();
+        let ret = round_to_exponent_range(
+            ret,
+            p,
+            rm,
+            self.emin,
+            self.emax,
+            self.range_mode,
+            &mut flags,
+        );
+        let ret = self.tag_result(ret);
+        self.raise_flags(&ret, flags);
+        ret
+    }
+
     /// Clones `self` and returns the cloned context.
+    /// The cache of function calls of the cloned context is empty.
     ///
     /// # Errors
     ///
@@ -131,10 +836,119 @@ impl Context {
             cc,
             emin: self.emin,
             emax: self.emax,
+            range_mode: self.range_mode,
+            status: self.status,
+            call_cache: self.call_cache.as_ref().map(|c| CallCache::new(c.capacity)),
+            #[cfg(feature = "debug")]
+            seq: self.seq,
+            #[cfg(feature = "debug")]
+            origin: self.origin,
         })
     }
 }
 
+/// Builder of `Context` returned by `Context::builder`.
+#[derive(Debug)]
+pub struct ContextBuilder {
+    p: usize,
+    rm: RoundingMode,
+    cc: Option<Consts>,
+    emin: Exponent,
+    emax: Exponent,
+    range_mode: RangeMode,
+    call_cache: Option<usize>,
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        ContextBuilder {
+            p: DEFAULT_P,
+            rm: RoundingMode::ToEven,
+            cc: None,
+            emin: EXPONENT_MIN,
+            emax: EXPONENT_MAX,
+            range_mode: RangeMode::Clamp,
+            call_cache: None,
+        }
+    }
+}
+
+impl ContextBuilder {
+    /// Sets the precision of the context.
+    pub fn precision(mut self, p: usize) -> Self {
+        self.p = p;
+        self
+    }
+
+    /// Sets the rounding mode of the context.
+    pub fn rounding(mut self, rm: RoundingMode) -> Self {
+        self.rm = rm;
+        self
+    }
+
+    /// Sets the constants cache of the context.
+    /// If the constants cache is not set, a new one is created when the context is built.
+    pub fn consts(mut self, cc: Consts) -> Self {
+        self.cc = Some(cc);
+        self
+    }
+
+    /// Sets the minimum exponent of the context.
+    pub fn emin(mut self, emin: Exponent) -> Self {
+        self.emin = emin;
+        self
+    }
+
+    /// Sets the maximum exponent of the context.
+    pub fn emax(mut self, emax: Exponent) -> Self {
+        self.emax = emax;
+        self
+    }
+
+    /// Sets the way the context brings the results of operations into its exponent range.
+    pub fn range_mode(mut self, mode: RangeMode) -> Self {
+        self.range_mode = mode;
+        self
+    }
+
+    /// Enables memoization of the results of function calls with the cache of `capacity` results.
+    pub fn call_cache(mut self, capacity: usize) -> Self {
+        self.call_cache = Some(capacity);
+        self
+    }
+
+    /// Validates the parameters and builds the context.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: the precision is zero or too large, `emin` is not in the range from EXPONENT_MIN to 0,
+    ///    or `emax` is not in the range from 0 to EXPONENT_MAX.
+    ///  - MemoryAllocation: failed to allocate memory for the constants cache.
+    pub fn build(self) -> Result<Context, Error> {
+        if self.p == 0
+            || !(EXPONENT_MIN..=0).contains(&self.emin)
+            || !(0..=EXPONENT_MAX).contains(&self.emax)
+        {
+            return Err(Error::InvalidArgument);
+        }
+
+        BigFloatNumber::p_assertion(self.p)?;
+
+        let cc = match self.cc {
+            Some(cc) => cc,
+            None => Consts::new()?,
+        };
+
+        let mut ctx = Context::new(self.p, self.rm, cc, self.emin, self.emax);
+        ctx.set_range_mode(self.range_mode);
+        if let Some(capacity) = self.call_cache {
+            ctx.enable_call_cache(capacity);
+        }
+
+        Ok(ctx)
+    }
+}
+
 /// Represents a type that can be used as context in `expr!` macro.
 ///
 /// ## Examples
@@ -165,6 +979,13 @@ pub trait Contextable {
     /// Returns a mutable reference to the constant cache of the context.
     fn consts(&mut self) -> &mut Consts;
 
+    /// Returns a mutable reference to the constant cache of the context
+    /// together with a mutable reference to the cache of function calls of the context, if memoization is enabled.
+    /// The default implementation returns no cache of function calls.
+    fn consts_and_call_cache(&mut self) -> (&mut Consts, Option<&mut CallCache>) {
+        (self.consts(), None)
+    }
+
     /// Returns the value of the pi number.
     fn const_pi(&mut self) -> BigFloat;
 
@@ -182,6 +1003,30 @@ pub trait Contextable {
 
     /// Returns the maximum exponent.
     fn emax(&self) -> Exponent;
+
+    /// Returns the way the results are brought into the exponent range of the context.
+    /// The default implementation returns `RangeMode::Clamp`.
+    fn range_mode(&self) -> RangeMode {
+        RangeMode::Clamp
+    }
+
+    /// Updates the sticky status of the context with the result `n` of an operation.
+    /// `clamped` indicates that a value was clamped to the exponent range of the context during the operation.
+    /// The default implementation does nothing.
+    fn update_status(&mut self, _n: &BigFloat, _clamped: bool) {}
+
+    /// Updates the sticky status of the context with the result `n` of an operation,
+    /// and raises the flags raised in `flags` during the operation.
+    /// The default implementation calls `update_status` with the clamped flag of `flags`.
+    fn raise_flags(&mut self, n: &BigFloat, flags: Status) {
+        self.update_status(n, flags.clamped());
+    }
+
+    /// Assigns the next sequence number of the context to the provenance tag of the result `n` of an evaluation,
+    /// and returns `n`. The default implementation returns `n` unchanged.
+    fn tag_result(&mut self, n: BigFloat) -> BigFloat {
+        n
+    }
 }
 
 impl Contextable for (usize, RoundingMode, &mut Consts) {
@@ -281,6 +1126,10 @@ impl Contextable for Context {
         Context::consts(self)
     }
 
+    fn consts_and_call_cache(&mut self) -> (&mut Consts, Option<&mut CallCache>) {
+        (&mut self.cc, self.call_cache.as_mut())
+    }
+
     fn const_pi(&mut self) -> BigFloat {
         Context::const_pi(self)
     }
@@ -304,4 +1153,393 @@ impl Contextable for Context {
     fn emax(&self) -> Exponent {
         Context::emax(self)
     }
+
+    fn range_mode(&self) -> RangeMode {
+        Context::range_mode(self)
+    }
+
+    fn update_status(&mut self, n: &BigFloat, clamped: bool) {
+        Context::update_status(self, n, clamped)
+    }
+
+    fn raise_flags(&mut self, n: &BigFloat, flags: Status) {
+        Context::raise_flags(self, n, flags)
+    }
+
+    fn tag_result(&mut self, n: BigFloat) -> BigFloat {
+        Context::tag_result(self, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::macro_util::check_exponent_range_sticky;
+    use crate::{Sign, INF_NEG};
+
+    #[test]
+    fn test_sum_product() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+        let mut ctx = Context::new(p, rm, Consts::new().unwrap(), -1000, 1000);
+
+        let x = BigFloat::from_word(3, 64);
+        let big = BigFloat::from_word(2, p).powi(600, p, rm);
+
+        assert_eq!(ctx.sum([&x, &x, &x]), BigFloat::from_word(9, p));
+        assert_eq!(
+            ctx.product(&[x.clone(), x.clone()]),
+            BigFloat::from_word(9, p)
+        );
+        assert!(ctx.status().is_clear());
+
+        // the result is out of the exponent range of the context
+        let d = ctx.product([&big, &big]);
+        assert!(d.is_inf_pos());
+        assert!(ctx.status().overflow());
+
+        ctx.reset_status();
+        let d = ctx.product([&big, &x, &big.neg()]);
+        assert!(d.is_inf_neg());
+        ctx.reset_status();
+        let d = ctx.sum([&big, &big.neg(), &x]);
+        assert_eq!(d, BigFloat::from_word(3, p));
+        assert!(ctx.status().is_clear());
+
+        // rounding mode of the context
+        let third = BigFloat::from_word(1, p).div(&x, 256, RoundingMode::None);
+        ctx.set_rounding_mode(RoundingMode::Up);
+        assert_eq!(
+            ctx.sum([&third, &x]),
+            BigFloat::sum(&[third.clone(), x.clone()], p, RoundingMode::Up)
+        );
+        assert!(ctx.status().inexact());
+    }
+
+    #[test]
+    fn test_flags() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+        let mut ctx = Context::new(p, rm, Consts::new().unwrap(), -1000, 1000);
+        assert!(ctx.status().is_clear());
+
+        // exponent range
+        let mut flags = Status::default();
+        let x = BigFloat::from_word(1, p);
+        let big = x.mul(&BigFloat::from_word(2, p).powi(1500, p, rm), p, rm);
+        let small = x.div(&BigFloat::from_word(2, p).powi(1500, p, rm), p, rm);
+
+        let y = check_exponent_range_sticky(x.clone(), -1000, 1000, &mut flags);
+        assert_eq!(y, x);
+        assert!(flags.is_clear());
+
+        let y = check_exponent_range_sticky(big.neg(), -1000, 1000, &mut flags);
+        assert!(y.is_inf_neg());
+        assert!(flags.overflow() && flags.clamped() && !flags.underflow());
+
+        let mut flags = Status::default();
+        let y = check_exponent_range_sticky(small, -1000, 1000, &mut flags);
+        assert!(y.is_zero());
+        assert!(!flags.overflow() && flags.clamped() && flags.underflow());
+
+        ctx.raise_flags(&x, flags);
+        assert!(ctx.status().underflow());
+        assert!(!ctx.status().inexact());
+        ctx.reset_status();
+        assert!(ctx.status().is_clear());
+
+        // results of operations
+        ctx.update_status(
+            &BigFloat::nan(Some(Error::ExponentOverflow(Sign::Pos))),
+            false,
+        );
+        assert!(ctx.status().overflow() && ctx.status().invalid());
+        assert!(!ctx.status().clamped());
+
+        ctx.reset_status();
+        let mut sub =
+            BigFloat::from_raw_parts(&[3, 1], WORD_BIT_SIZE + 1, Sign::Pos, EXPONENT_MIN, false);
+        assert!(sub.is_subnormal());
+        ctx.update_status(&sub, false);
+        assert!(ctx.status().is_clear());
+        sub.set_inexact(true);
+        ctx.update_status(&sub, false);
+        assert!(ctx.status().underflow() && ctx.status().inexact());
+    }
+
+    #[test]
+    fn test_builder() {
+        let ctx = Context::builder().build().unwrap();
+        assert_eq!(ctx.precision(), DEFAULT_P);
+        assert_eq!(ctx.rounding_mode(), RoundingMode::ToEven);
+        assert_eq!((ctx.emin(), ctx.emax()), (EXPONENT_MIN, EXPONENT_MAX));
+        assert!(ctx.call_cache().is_none());
+
+        let mut ctx = Context::builder()
+            .precision(320)
+            .rounding(RoundingMode::Up)
+            .consts(Consts::empty())
+            .emin(-10)
+            .emax(20)
+            .call_cache(8)
+            .build()
+            .unwrap();
+        assert_eq!(ctx.precision(), 320);
+        assert_eq!(ctx.rounding_mode(), RoundingMode::Up);
+        assert_eq!((ctx.emin(), ctx.emax()), (-10, 20));
+        assert_eq!(ctx.call_cache().unwrap().capacity(), 8);
+        assert_eq!(
+            ctx.const_pi(),
+            Consts::new().unwrap().pi(320, RoundingMode::Up)
+        );
+
+        // validation
+        for b in [
+            Context::builder().precision(0),
+            Context::builder().precision(usize::MAX),
+            Context::builder().emin(1),
+            Context::builder().emax(-1),
+        ] {
+            assert_eq!(b.build().unwrap_err(), Error::InvalidArgument);
+        }
+    }
+
+    #[test]
+    fn test_scoped_overrides() {
+        let mut ctx = Context::builder().precision(128).build().unwrap();
+        let x = BigFloat::from_word(3, 64);
+
+        let (lo, hi) = ctx.with_precision(256, |ctx| {
+            let lo = ctx.with_rounding(RoundingMode::Down, |ctx| {
+                assert_eq!(ctx.precision(), 256);
+                ctx.call(Func::Ln, &[&x])
+            });
+            assert_eq!(ctx.rounding_mode(), RoundingMode::ToEven);
+            let hi = ctx.with_rounding(RoundingMode::Up, |ctx| {
+                ctx.call(Func::Ln, &[&x])
+            });
+            (lo, hi)
+        });
+
+        assert_eq!(lo.mantissa_max_bit_len(), Some(256));
+        assert!(lo < hi);
+        assert_eq!(ctx.precision(), 128);
+        assert_eq!(ctx.rounding_mode(), RoundingMode::ToEven);
+
+        // the status is accumulated in the overridden scope
+        assert!(ctx.status().inexact());
+    }
+
+    #[test]
+    fn test_call_cache() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+        let mut ctx = Context::new(p, rm, Consts::new().unwrap(), -1000, 1000);
+        let mut cc = Consts::new().unwrap();
+
+        let x = BigFloat::from_word(2, 64);
+        let y = BigFloat::from_word(3, 64);
+
+        // memoization is disabled by default
+        assert!(ctx.call_cache().is_none());
+        let d1 = ctx.call(Func::Exp, &[&x]);
+        assert_eq!(d1, x.exp(p, rm, &mut cc));
+        assert!(ctx.status().inexact());
+
+        ctx.enable_call_cache(2);
+        ctx.reset_status();
+
+        let d2 = ctx.call(Func::Exp, &[&x]);
+        assert_eq!(d2, d1);
+        let d2 = ctx.call(Func::Exp, &[&x]);
+        assert_eq!(d2, d1);
+        assert!(ctx.status().inexact());
+
+        let cache = ctx.call_cache().unwrap();
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 1, 1));
+
+        // the function and the arguments are parts of the key
+        let d2 = ctx.call(Func::Ln, &[&x]);
+        assert_eq!(d2, x.ln(p, rm, &mut cc));
+        let _ = ctx.call(Func::Exp, &[&x]);
+        let cache = ctx.call_cache().unwrap();
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (2, 2, 2));
+
+        // the least recently used entry ln(2) is evicted
+        let _ = ctx.call(Func::Exp, &[&y]);
+        let _ = ctx.call(Func::Exp, &[&x]);
+        let _ = ctx.call(Func::Ln, &[&x]);
+        let cache = ctx.call_cache().unwrap();
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (3, 4, 2));
+
+        // the order of the arguments matters
+        let d2 = ctx.call(Func::Pow, &[&x, &y]);
+        assert_eq!(d2, x.pow(&y, p, rm, &mut cc));
+        let d2 = ctx.call(Func::Pow, &[&y, &x]);
+        assert_eq!(d2, y.pow(&x, p, rm, &mut cc));
+        assert_eq!(ctx.call_cache().unwrap().misses(), 6);
+
+        // the same value with a different precision is a different argument
+        let x2 = BigFloat::from_word(2, 128);
+        let _ = ctx.call(Func::Ln, &[&x2]);
+        assert_eq!(ctx.call_cache().unwrap().misses(), 7);
+
+        // precision of the context is a part of the key
+        ctx.set_precision(256);
+        let d2 = ctx.call(Func::Ln, &[&x2]);
+        assert_eq!(d2.mantissa_max_bit_len(), Some(256));
+        assert_eq!(ctx.call_cache().unwrap().misses(), 8);
+
+        // special values and incorrect arguments are not memoized
+        assert!(ctx.call(Func::Exp, &[&INF_NEG]).is_zero());
+        assert!(ctx.call(Func::Sin, &[&x, &y]).is_nan());
+        let cache = ctx.call_cache().unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (3, 8));
+
+        // clone and disable
+        let ctx2 = ctx.clone().unwrap();
+        let cache = ctx2.call_cache().unwrap();
+        assert!(cache.is_empty() && cache.capacity() == 2);
+
+        ctx.disable_call_cache();
+        assert!(ctx.call_cache().is_none());
+
+        // results are not kept with zero capacity
+        let mut cache = CallCache::new(0);
+        assert_eq!(cache.eval(Func::Exp, &[&x], p, rm, &mut cc), d1);
+        assert_eq!(cache.eval(Func::Exp, &[&x], p, rm, &mut cc), d1);
+        assert!(cache.is_empty());
+        assert_eq!(cache.misses(), 2);
+        cache.clear();
+        assert_eq!(cache.misses(), 0);
+    }
+
+    #[test]
+    fn test_range_mode() {
+        let p = 64;
+        let (emin, emax) = (-1021, 1024);
+
+        // 2^k
+        let pow2 = |k: Exponent, p: usize| {
+            let mut d = BigFloat::from_word(1, p);
+            d.set_exponent(k + 1);
+            d
+        };
+
+        let mut ctx = Context::builder()
+            .precision(p)
+            .emin(emin)
+            .emax(emax)
+            .range_mode(RangeMode::Ieee)
+            .build()
+            .unwrap();
+        assert_eq!(ctx.range_mode(), RangeMode::Ieee);
+
+        // overflow gives infinity or the largest finite number depending on the rounding mode
+        let x = pow2(1000, p);
+        let y = pow2(100, p);
+        let mut max = BigFloat::max_value(p);
+        max.set_exponent(emax);
+        for (rm, pos_inf, neg_inf) in [
+            (RoundingMode::ToEven, true, true),
+            (RoundingMode::FromZero, true, true),
+            (RoundingMode::ToZero, false, false),
+            (RoundingMode::Up, true, false),
+            (RoundingMode::Down, false, true),
+        ] {
+            ctx.set_rounding_mode(rm);
+            ctx.reset_status();
+            let d1 = ctx.product([&x, &y]);
+            let d2 = ctx.product([&x, &y.neg()]);
+            assert_eq!(d1.is_inf_pos(), pos_inf);
+            assert_eq!(d2.is_inf_neg(), neg_inf);
+            if !pos_inf {
+                assert_eq!(d1, max);
+            }
+            if !neg_inf {
+                assert_eq!(d2, max.neg());
+            }
+            let status = ctx.status();
+            assert!(status.overflow() && status.inexact() && !status.underflow());
+        }
+
+        // the largest finite number is not changed
+        ctx.set_rounding_mode(RoundingMode::ToEven);
+        ctx.reset_status();
+        assert_eq!(ctx.sum([&max]), max);
+        assert!(ctx.status().is_clear());
+
+        // underflow is flushed to zero of the same sign
+        let x = pow2(-1000, p);
+        let d1 = ctx.product([&x, &pow2(-100, p).neg()]);
+        assert!(d1.is_zero() && d1.is_negative() && d1.inexact());
+        let status = ctx.status();
+        assert!(status.underflow() && status.clamped() && status.inexact());
+
+        // gradual underflow
+        ctx.set_range_mode(RangeMode::IeeeSubnormal);
+        let x = pow2(-1080, p);
+
+        // exact subnormal results do not raise flags
+        ctx.reset_status();
+        let d1 = ctx.product([&x, &BigFloat::from_word(3, p)]);
+        assert_eq!(d1, BigFloat::from_word(3, p).mul(&x, p, RoundingMode::None));
+        assert!(ctx.status().is_clear());
+
+        // subnormal numbers are multiples of 2^(emin - p)
+        let a = BigFloat::from_word(1, p).add(&pow2(-20, p), p, RoundingMode::None);
+        for (rm, d) in [
+            (RoundingMode::ToEven, x.clone()),
+            (RoundingMode::Down, x.clone()),
+            (
+                RoundingMode::Up,
+                x.add(&pow2(-1085, p), p, RoundingMode::None),
+            ),
+        ] {
+            ctx.set_rounding_mode(rm);
+            ctx.reset_status();
+            let d1 = ctx.product([&x, &a]);
+            assert_eq!(d1, d);
+            assert!(d1.inexact());
+            let status = ctx.status();
+            assert!(status.underflow() && !status.clamped() && status.inexact());
+        }
+
+        // a half of 2^(emin - p) above the midpoint is rounded once
+        ctx.set_rounding_mode(RoundingMode::ToEven);
+        let a = BigFloat::from_word(1, 2 * p)
+            .add(&pow2(-6, p), 2 * p, RoundingMode::None)
+            .add(&pow2(-80, p), 2 * p, RoundingMode::None);
+        let d1 = ctx.product([&x, &a]);
+        assert_eq!(d1, x.add(&pow2(-1085, p), p, RoundingMode::None));
+
+        // the smallest subnormal number and zero
+        let d1 = ctx.product([
+            &pow2(-1085, p),
+            &BigFloat::from_word(1, p).add(&pow2(-1, p), p, RoundingMode::None),
+        ]);
+        assert_eq!(d1, pow2(-1084, p));
+        let d1 = ctx.product([&pow2(-1087, p), &BigFloat::from_word(3, p)]);
+        assert_eq!(d1, pow2(-1085, p));
+        let d1 = ctx.product([&pow2(-1088, p), &BigFloat::from_word(3, p)]);
+        assert!(d1.is_zero() && d1.inexact());
+        let d1 = ctx.call(Func::Exp, &[&BigFloat::from_word(800, p).neg()]);
+        assert!(d1.is_zero() && d1.inexact());
+        let d1 = ctx.call(Func::Exp, &[&BigFloat::from_word(740, p).neg()]);
+        let d2 = BigFloat::from_word(740, p)
+            .neg()
+            .exp(p, RoundingMode::ToEven, ctx.consts());
+        assert!(d1.mul(&pow2(1085, p), p, RoundingMode::None).is_int());
+        let eps = pow2(-1086, p);
+        assert!(d1.sub(&d2, p, RoundingMode::None).abs().cmp(&eps) <= Some(0));
+
+        // numbers in the range are not changed in the clamp mode, and replaced with zero and infinity outside of it
+        ctx.set_range_mode(RangeMode::Clamp);
+        ctx.reset_status();
+        let d1 = ctx.product([&x, &a]);
+        assert!(d1.is_zero() && d1.is_positive());
+        assert!(ctx.product([&pow2(1000, p), &pow2(100, p)]).is_inf_pos());
+        assert!(ctx.status().underflow() && ctx.status().overflow());
+    }
 }
@@ -0,0 +1,1013 @@
+//! Decimal floating-point numbers.
+//!
+//! A decimal number is represented as an integer coefficient multiplied by a power of ten.
+//! Decimal fractions, such as `0.1` or `19.99`, are represented exactly,
+//! and the numbers are formatted and parsed without the binary representation error.
+//! The coefficient is stored in a `BigFloat`, so the decimal arithmetic reuses the binary mantissa machinery.
+//!
+//! The arithmetic operations accept a precision `n` in decimal digits and a rounding mode `rm`.
+//! The results of addition, subtraction, multiplication, and division are correctly rounded to `n` significant digits.
+//! The exponent of an exact result is kept as close as possible to the exponents of the arguments,
+//! so, for example, `1.50 + 2.25` is `3.75`, and `1.50 * 2` is `3.00`.
+//!
+//! ```
+//! use astro_float_num::decimal::BigDecimal;
+//! use astro_float_num::{BigFloat, Consts, RoundingMode};
+//!
+//! let mut cc = Consts::new().expect("Constants cache initialized");
+//! let n = 30;
+//! let rm = RoundingMode::ToEven;
+//!
+//! // 0.1 + 0.2 = 0.3 exactly
+//! let a = BigDecimal::parse("0.1", n, rm, &mut cc);
+//! let b = BigDecimal::parse("0.2", n, rm, &mut cc);
+//! let c = a.add(&b, n, rm);
+//! assert_eq!(c, BigDecimal::parse("0.3", n, rm, &mut cc));
+//! assert_eq!(c.format(&mut cc).unwrap(), "0.3");
+//!
+//! // rounding to cents
+//! let price = BigDecimal::parse("19.99", n, rm, &mut cc);
+//! let total = price.mul(&BigDecimal::parse("1.0725", n, rm, &mut cc), n, rm);
+//! assert_eq!(total.format(&mut cc).unwrap(), "21.439275");
+//! assert_eq!(total.quantize(-2, rm).format(&mut cc).unwrap(), "21.44");
+//!
+//! // conversion to and from BigFloat
+//! let x = BigDecimal::from_bigfloat(&BigFloat::from_f64(0.375, 64), n, rm);
+//! assert_eq!(x.format(&mut cc).unwrap(), "0.375");
+//! assert_eq!(x.to_bigfloat(64, rm), BigFloat::from_f64(0.375, 64));
+//! ```
+
+use crate::defs::{SignedWord, EXPONENT_MAX, EXPONENT_MIN};
+use crate::{BigFloat, Consts, Error, Exponent, Radix, RoundingMode, Sign, WORD_BIT_SIZE};
+use core::cmp::Ordering;
+use core::fmt::Write;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Decimal floating-point number `c * 10^e` with the integer coefficient `c` and the exponent `e`.
+#[derive(Debug, Clone)]
+pub struct BigDecimal {
+    c: BigFloat,
+    e: Exponent,
+}
+
+impl BigDecimal {
+    /// Constructs the number `c * 10^e` with the integer coefficient `c` and the exponent `e`.
+    /// The function returns NaN if `c` is not an integer.
+    pub fn from_parts(c: BigFloat, e: Exponent) -> Self {
+        if c.is_nan() || c.is_inf() || c.is_int() {
+            Self::with_exp(c, e as i64)
+        } else {
+            Self::nan(Error::InvalidArgument)
+        }
+    }
+
+    /// Constructs the number `c * 10^e` exactly.
+    pub fn from_i128(c: i128, e: Exponent) -> Self {
+        Self::with_exp(BigFloat::from_i128(c, 128), e as i64)
+    }
+
+    /// Converts the binary number `x` to a decimal number with precision `n` rounded using the rounding mode `rm`.
+    /// The conversion is exact if the decimal representation of `x` fits in `n` digits.
+    /// The function returns NaN if the precision `n` is zero.
+    pub fn from_bigfloat(x: &BigFloat, n: usize, rm: RoundingMode) -> Self {
+        if n == 0 {
+            return Self::nan(Error::InvalidArgument);
+        }
+
+        if x.is_nan() || x.is_inf() || x.is_int() {
+            return Self::with_exp(x.clone(), 0).round(n, rm);
+        }
+
+        // x = m * 2^-k = m * 5^k * 10^-k, where m is an integer
+        let (words, _, _, _, _) = x.as_raw_parts().expect("x is a finite number");
+        let mut tz = 0;
+        for w in words {
+            if *w == 0 {
+                tz += WORD_BIT_SIZE;
+            } else {
+                tz += w.trailing_zeros() as usize;
+                break;
+            }
+        }
+
+        let bits = (x.mantissa_max_bit_len().unwrap_or(0) - tz) as Exponent;
+        let k = (bits - x.exponent().unwrap_or(0)) as usize;
+
+        let mut m = x.clone();
+        m.set_exponent(bits);
+
+        let c = m.mul_full_prec(&Self::pow_exact(5, k));
+
+        Self::round_coef(c, -(k as i64), n, rm)
+    }
+
+    /// Converts `self` to a binary number with precision `p` rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn to_bigfloat(&self, p: usize, rm: RoundingMode) -> BigFloat {
+        if !self.is_finite() || self.c.is_zero() || self.e == 0 {
+            let mut ret = self.c.clone();
+            return match ret.set_precision(p, rm) {
+                Ok(()) => ret,
+                Err(e) => BigFloat::nan(Some(e)),
+            };
+        }
+
+        // avoid computing huge powers of ten when the result is out of range anyway
+        let adj = self.e as i64 + Self::digit_count(&self.c) as i64;
+        if adj > EXPONENT_MAX as i64 / 3 + 1 {
+            return if self.c.is_negative() { crate::INF_NEG } else { crate::INF_POS };
+        } else if 3 * adj < EXPONENT_MIN as i64 - p as i64 - WORD_BIT_SIZE as i64 {
+            let mut ret = BigFloat::new(p);
+            ret.set_sign(self.c.sign().unwrap_or(Sign::Pos));
+            return ret;
+        }
+
+        let t = Self::pow_exact(10, self.e.unsigned_abs() as usize);
+
+        if self.e > 0 {
+            self.c.mul(&t, p, rm)
+        } else {
+            self.c.div(&t, p, rm)
+        }
+    }
+
+    /// Parses the decimal number from the string `s` and rounds it to precision `n` using the rounding mode `rm`.
+    /// The string can contain the sign, the digits with an optional decimal point, and an optional exponent,
+    /// e.g. `-12.5e+3`. The strings "Inf", "-Inf", and "NaN" are parsed as the corresponding special values.
+    /// The function returns NaN if the string is not a valid number or the precision `n` is zero.
+    pub fn parse(s: &str, n: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        if n == 0 {
+            return Self::nan(Error::InvalidArgument);
+        }
+
+        match Self::parse_exact(s, cc) {
+            Ok(v) => v.round(n, rm),
+            Err(e) => Self::nan(e),
+        }
+    }
+
+    fn parse_exact(s: &str, cc: &mut Consts) -> Result<Self, Error> {
+        match s {
+            "NaN" => return Ok(Self::nan(Error::InvalidArgument)),
+            "Inf" | "+Inf" => return Ok(Self::with_exp(crate::INF_POS, 0)),
+            "-Inf" => return Ok(Self::with_exp(crate::INF_NEG, 0)),
+            _ => {}
+        }
+
+        let mut chars = s.bytes().peekable();
+
+        let sign = match chars.peek() {
+            Some(b'-') => {
+                chars.next();
+                Sign::Neg
+            }
+            Some(b'+') => {
+                chars.next();
+                Sign::Pos
+            }
+            _ => Sign::Pos,
+        };
+
+        let mut digits = Vec::new();
+        let mut frac = 0i64;
+        let mut point = false;
+        let mut any = false;
+
+        while let Some(&b) = chars.peek() {
+            match b {
+                b'0'..=b'9' => {
+                    any = true;
+                    if point {
+                        frac += 1;
+                    }
+                    if b != b'0' || !digits.is_empty() {
+                        digits.push(b - b'0');
+                    }
+                }
+                b'.' if !point => point = true,
+                _ => break,
+            }
+            chars.next();
+        }
+
+        if !any {
+            return Err(Error::InvalidArgument);
+        }
+
+        let mut e = 0i64;
+        let has_exp = match chars.next() {
+            Some(b'e' | b'E') => true,
+            Some(_) => return Err(Error::InvalidArgument),
+            None => false,
+        };
+
+        if has_exp {
+            let neg = match chars.peek() {
+                Some(b'-') => {
+                    chars.next();
+                    true
+                }
+                Some(b'+') => {
+                    chars.next();
+                    false
+                }
+                _ => false,
+            };
+
+            let mut any = false;
+            for b in chars.by_ref() {
+                if !b.is_ascii_digit() {
+                    return Err(Error::InvalidArgument);
+                }
+                any = true;
+                e = e * 10 + (b - b'0') as i64;
+                if e > EXPONENT_MAX as i64 * 2 {
+                    return Err(Error::ExponentOverflow(sign));
+                }
+            }
+
+            if !any {
+                return Err(Error::InvalidArgument);
+            }
+
+            if neg {
+                e = -e;
+            }
+        }
+
+        let c = if digits.is_empty() {
+            let mut z = BigFloat::new(WORD_BIT_SIZE);
+            z.set_sign(sign);
+            z
+        } else {
+            if digits.len() > EXPONENT_MAX as usize {
+                return Err(Error::InvalidArgument);
+            }
+
+            let p = (digits.len() as u64 * 3321928095 / 1000000000) as usize + WORD_BIT_SIZE;
+            let c = BigFloat::convert_from_radix(
+                sign,
+                &digits,
+                digits.len() as Exponent,
+                Radix::Dec,
+                p,
+                RoundingMode::None,
+                cc,
+            );
+
+            if let Some(err) = c.err() {
+                return Err(err);
+            }
+
+            c
+        };
+
+        let ret = Self::with_exp(c, e - frac);
+
+        if let Some(err) = ret.c.err() {
+            Err(err)
+        } else {
+            Ok(ret)
+        }
+    }
+
+    /// Formats the number exactly.
+    /// The number is written in positional notation if its exponent is not positive and
+    /// the number is not smaller than `1e-6`, and in scientific notation otherwise, e.g. `1.25e+10`.
+    /// Inf and NaN are formatted as "Inf", "-Inf", and "NaN".
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    pub fn format(&self, cc: &mut Consts) -> Result<String, Error> {
+        if self.c.is_nan() {
+            return Ok(String::from("NaN"));
+        } else if self.c.is_inf_pos() {
+            return Ok(String::from("Inf"));
+        } else if self.c.is_inf_neg() {
+            return Ok(String::from("-Inf"));
+        }
+
+        let mut ds = Vec::new();
+        if self.c.is_zero() {
+            ds.push(b'0');
+        } else {
+            let (_, digits, e) = self
+                .c
+                .convert_to_radix(Radix::Dec, RoundingMode::None, cc)?;
+            ds.try_reserve_exact(e as usize)?;
+            ds.extend(digits.iter().map(|d| d + b'0'));
+            ds.resize(e as usize, b'0');
+        }
+
+        let mut ret = String::new();
+        ret.try_reserve_exact(ds.len() + 24)?;
+
+        if self.c.is_negative() {
+            ret.push('-');
+        }
+
+        let l = ds.len() as i64;
+        let e = self.e as i64;
+        let adj = e + l - 1;
+        let ds = core::str::from_utf8(&ds).expect("Decimal digits");
+
+        if e <= 0 && adj >= -6 {
+            if e == 0 {
+                ret.push_str(ds);
+            } else if l + e > 0 {
+                let (int, frac) = ds.split_at((l + e) as usize);
+                ret.push_str(int);
+                ret.push('.');
+                ret.push_str(frac);
+            } else {
+                ret.push_str("0.");
+                for _ in 0..-(l + e) {
+                    ret.push('0');
+                }
+                ret.push_str(ds);
+            }
+        } else {
+            let (int, frac) = ds.split_at(1);
+            ret.push_str(int);
+            if !frac.is_empty() {
+                ret.push('.');
+                ret.push_str(frac);
+            }
+            write!(ret, "e{:+}", adj).map_err(|_| Error::MemoryAllocation)?;
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the coefficient of the number.
+    pub fn coefficient(&self) -> &BigFloat {
+        &self.c
+    }
+
+    /// Returns the decimal exponent of the number.
+    pub fn exponent(&self) -> Exponent {
+        self.e
+    }
+
+    /// Decomposes the number into the coefficient and the exponent.
+    pub fn into_parts(self) -> (BigFloat, Exponent) {
+        (self.c, self.e)
+    }
+
+    /// Returns the number of decimal digits in the coefficient, or 0 if the number is zero, Inf, or NaN.
+    pub fn digits(&self) -> usize {
+        if !self.is_finite() || self.c.is_zero() {
+            0
+        } else {
+            Self::digit_count(&self.c)
+        }
+    }
+
+    /// Returns true if the number is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.c.is_nan()
+    }
+
+    /// Returns true if the number is Inf.
+    pub fn is_inf(&self) -> bool {
+        self.c.is_inf()
+    }
+
+    /// Returns true if the number is zero.
+    pub fn is_zero(&self) -> bool {
+        self.c.is_zero()
+    }
+
+    /// Returns true if the number is negative.
+    pub fn is_negative(&self) -> bool {
+        self.c.is_negative()
+    }
+
+    /// Returns the negated number.
+    pub fn neg(&self) -> Self {
+        BigDecimal {
+            c: self.c.neg(),
+            e: self.e,
+        }
+    }
+
+    /// Returns the absolute value of the number.
+    pub fn abs(&self) -> Self {
+        BigDecimal {
+            c: self.c.abs(),
+            e: self.e,
+        }
+    }
+
+    /// Adds `d2` to `self` and returns the result of the operation with precision `n` rounded using the rounding mode `rm`.
+    /// The function returns NaN if the precision `n` is zero.
+    pub fn add(&self, d2: &Self, n: usize, rm: RoundingMode) -> Self {
+        if n == 0 {
+            return Self::nan(Error::InvalidArgument);
+        }
+
+        if !self.is_finite() || !d2.is_finite() {
+            return Self::with_exp(self.c.add(&d2.c, WORD_BIT_SIZE, rm), 0);
+        }
+
+        // an argument which is far below the last digit of the result
+        // affects only rounding and can be replaced by a small number of the same sign
+        let (a, b);
+        let (a, b) = if let Some(r) = self.negligible(d2, n) {
+            b = r;
+            (self, &b)
+        } else if let Some(r) = d2.negligible(self, n) {
+            a = r;
+            (&a, d2)
+        } else {
+            (self, d2)
+        };
+
+        let e = a.e.min(b.e);
+        let c = Self::scale(&a.c, (a.e - e) as usize)
+            .add_full_prec(&Self::scale(&b.c, (b.e - e) as usize));
+
+        Self::round_coef(c, e as i64, n, rm)
+    }
+
+    /// Subtracts `d2` from `self` and returns the result of the operation with precision `n` rounded using the rounding mode `rm`.
+    /// The function returns NaN if the precision `n` is zero.
+    pub fn sub(&self, d2: &Self, n: usize, rm: RoundingMode) -> Self {
+        self.add(&d2.neg(), n, rm)
+    }
+
+    /// Multiplies `self` by `d2` and returns the result of the operation with precision `n` rounded using the rounding mode `rm`.
+    /// The function returns NaN if the precision `n` is zero.
+    pub fn mul(&self, d2: &Self, n: usize, rm: RoundingMode) -> Self {
+        if n == 0 {
+            return Self::nan(Error::InvalidArgument);
+        }
+
+        let c = self.c.mul_full_prec(&d2.c);
+
+        Self::round_coef(c, self.e as i64 + d2.e as i64, n, rm)
+    }
+
+    /// Divides `self` by `d2` and returns the result of the operation with precision `n` rounded using the rounding mode `rm`.
+    /// The function returns NaN if the precision `n` is zero.
+    pub fn div(&self, d2: &Self, n: usize, rm: RoundingMode) -> Self {
+        if n == 0 {
+            return Self::nan(Error::InvalidArgument);
+        }
+
+        let e_ideal = self.e as i64 - d2.e as i64;
+
+        if !self.is_finite() || !d2.is_finite() || d2.c.is_zero() {
+            return Self::with_exp(self.c.div(&d2.c, WORD_BIT_SIZE, rm), 0);
+        }
+
+        if self.c.is_zero() {
+            return Self::with_exp(self.c.div(&d2.c, WORD_BIT_SIZE, rm), e_ideal);
+        }
+
+        // scale the dividend so that the quotient has at least n + 1 digits
+        let d1 = Self::digit_count(&self.c) as i64;
+        let d2d = Self::digit_count(&d2.c) as i64;
+        let s = (n as i64 + 1 + d2d - d1).max(0) as usize;
+
+        let a = Self::scale(&self.c.abs(), s);
+        let (mut q, inexact) = Self::div_round(&a, &d2.c.abs(), RoundingMode::None, true);
+        let mut e = e_ideal - s as i64;
+
+        if inexact {
+            // the sticky digit
+            q = q
+                .mul_full_prec(&BigFloat::from_word(10, WORD_BIT_SIZE))
+                .add_full_prec(&BigFloat::from_word(1, WORD_BIT_SIZE));
+            e -= 1;
+        } else {
+            let ten = Self::pow_exact(10, 1);
+            while e < e_ideal {
+                let (q2, inexact) = Self::div_round(&q, &ten, RoundingMode::None, true);
+                if inexact {
+                    break;
+                }
+                q = q2;
+                e += 1;
+            }
+        }
+
+        if self.c.is_negative() != d2.c.is_negative() {
+            q.inv_sign();
+        }
+
+        Self::round_coef(q, e, n, rm)
+    }
+
+    /// Rounds the number to precision `n` using the rounding mode `rm`.
+    /// The function returns NaN if the precision `n` is zero.
+    pub fn round(&self, n: usize, rm: RoundingMode) -> Self {
+        if n == 0 {
+            return Self::nan(Error::InvalidArgument);
+        }
+
+        Self::round_coef(self.c.clone(), self.e as i64, n, rm)
+    }
+
+    /// Returns the number rounded using the rounding mode `rm` to a multiple of `10^e`,
+    /// and represented with the exponent `e`, e.g. `quantize(-2, rm)` rounds the number to cents.
+    pub fn quantize(&self, e: Exponent, rm: RoundingMode) -> Self {
+        if !self.is_finite() {
+            return self.clone();
+        }
+
+        if e <= self.e {
+            return BigDecimal {
+                c: Self::scale(&self.c, (self.e as i64 - e as i64) as usize),
+                e,
+            };
+        }
+
+        let k = (e as i64 - self.e as i64) as usize;
+
+        let c = if self.c.is_zero() || k > Self::digit_count(&self.c) {
+            // |c| < 10^(k - 1), and the result is 0
+            let mut z = BigFloat::new(WORD_BIT_SIZE);
+            z.set_sign(self.c.sign().unwrap_or(Sign::Pos));
+            z
+        } else {
+            let t = Self::pow_exact(10, k);
+            let (mut q, _) = Self::div_round(&self.c.abs(), &t, rm, self.c.is_positive());
+            if self.c.is_negative() {
+                q.inv_sign();
+            }
+            q
+        };
+
+        BigDecimal { c, e }
+    }
+
+    /// Compares `self` to `d2`.
+    /// Returns positive if `self` > `d2`, negative if `self` < `d2`, zero if `self` == `d2`, None if `self` or `d2` is NaN.
+    #[allow(clippy::should_implement_trait)]
+    pub fn cmp(&self, d2: &Self) -> Option<SignedWord> {
+        if self.is_nan() || d2.is_nan() {
+            return None;
+        }
+
+        if !self.is_finite() || !d2.is_finite() || self.c.is_zero() || d2.c.is_zero() {
+            return self.c.cmp(&d2.c);
+        }
+
+        if self.c.is_negative() != d2.c.is_negative() {
+            return self.c.cmp(&d2.c);
+        }
+
+        let adj1 = self.e as i64 + Self::digit_count(&self.c) as i64;
+        let adj2 = d2.e as i64 + Self::digit_count(&d2.c) as i64;
+
+        let ret = if adj1 != adj2 {
+            if adj1 > adj2 {
+                1
+            } else {
+                -1
+            }
+        } else {
+            let e = self.e.min(d2.e);
+            let c1 = Self::scale(&self.c, (self.e - e) as usize);
+            let c2 = Self::scale(&d2.c, (d2.e - e) as usize);
+            c1.abs_cmp(&c2)?
+        };
+
+        Some(if self.c.is_negative() { -ret } else { ret })
+    }
+
+    fn nan(err: Error) -> Self {
+        BigDecimal {
+            c: BigFloat::nan(Some(err)),
+            e: 0,
+        }
+    }
+
+    fn is_finite(&self) -> bool {
+        !self.c.is_nan() && !self.c.is_inf()
+    }
+
+    // Constructs the number checking the range of the exponent `e`.
+    fn with_exp(c: BigFloat, e: i64) -> Self {
+        if c.is_nan() || c.is_inf() {
+            BigDecimal { c, e: 0 }
+        } else if e > Exponent::MAX as i64 {
+            if c.is_zero() {
+                BigDecimal {
+                    c,
+                    e: Exponent::MAX,
+                }
+            } else {
+                Self::nan(Error::ExponentOverflow(c.sign().unwrap_or(Sign::Pos)))
+            }
+        } else if e < Exponent::MIN as i64 {
+            let mut z = BigFloat::new(WORD_BIT_SIZE);
+            z.set_sign(c.sign().unwrap_or(Sign::Pos));
+            BigDecimal {
+                c: z,
+                e: Exponent::MIN,
+            }
+        } else {
+            BigDecimal {
+                c,
+                e: e as Exponent,
+            }
+        }
+    }
+
+    // Rounds the integer coefficient `c` with the exponent `e` to `n` digits.
+    fn round_coef(c: BigFloat, e: i64, n: usize, rm: RoundingMode) -> Self {
+        if c.is_nan() || c.is_inf() || c.is_zero() {
+            return Self::with_exp(c, e);
+        }
+
+        let d = Self::digit_count(&c);
+        if d <= n {
+            return Self::with_exp(c, e);
+        }
+
+        let k = d - n;
+        let (mut q, _) = Self::div_round(&c.abs(), &Self::pow_exact(10, k), rm, c.is_positive());
+        let mut e = e + k as i64;
+
+        if Self::digit_count(&q) > n {
+            // rounding up produced 10^n
+            q = Self::pow_exact(10, n - 1);
+            e += 1;
+        }
+
+        if c.is_negative() {
+            q.inv_sign();
+        }
+
+        Self::with_exp(q, e)
+    }
+
+    // Returns a replacement for `d2` if it is small enough to affect only rounding of `self + d2` to `n` digits.
+    // Both numbers must be finite.
+    fn negligible(&self, d2: &Self, n: usize) -> Option<Self> {
+        if self.c.is_zero() || d2.c.is_zero() {
+            return None;
+        }
+
+        let adj1 = self.e as i64 + Self::digit_count(&self.c) as i64;
+        let adj2 = d2.e as i64 + Self::digit_count(&d2.c) as i64;
+
+        // Neither the last digit of `self`, nor the rounding position of the result are below 10^g.
+        let g = (self.e as i64).min(adj1 - n as i64 - 1) - 1;
+
+        if adj2 <= g {
+            let mut c = BigFloat::from_word(1, WORD_BIT_SIZE);
+            c.set_sign(d2.c.sign().unwrap_or(Sign::Pos));
+            let ret = Self::with_exp(c, g - 1);
+            if ret.is_finite() {
+                return Some(ret);
+            }
+        }
+
+        None
+    }
+
+    // Returns `c * 10^k`.
+    fn scale(c: &BigFloat, k: usize) -> BigFloat {
+        if k == 0 || c.is_zero() {
+            c.clone()
+        } else {
+            c.mul_full_prec(&Self::pow_exact(10, k))
+        }
+    }
+
+    // Returns `b^k` exactly for `b` equal to 5 or 10.
+    fn pow_exact(b: u8, k: usize) -> BigFloat {
+        // 5^k has less than k * log2(5) + 1 bits
+        let p = (k as u64 * 2321928095 / 1000000000) as usize + WORD_BIT_SIZE;
+        BigFloat::from_u8(b, WORD_BIT_SIZE).powi(k, p, RoundingMode::None)
+    }
+
+    // Returns the number of decimal digits of a non-zero integer `c`.
+    fn digit_count(c: &BigFloat) -> usize {
+        // |c| >= 2^b
+        let b = c.exponent().unwrap_or(1) as u64 - 1;
+
+        let mut d = (b * 301029995 / 1000000000) as usize + 1;
+        while c.abs_cmp(&Self::pow_exact(10, d)).is_some_and(|r| r >= 0) {
+            d += 1;
+        }
+
+        d
+    }
+
+    // Divides a non-negative integer `a` by a positive integer `t`, and rounds the quotient
+    // to an integer using the rounding mode `rm` assuming the sign of the result is positive if `is_positive` is true.
+    // Returns the quotient and true if the quotient is inexact.
+    fn div_round(
+        a: &BigFloat,
+        t: &BigFloat,
+        rm: RoundingMode,
+        is_positive: bool,
+    ) -> (BigFloat, bool) {
+        let qb = a.exponent().unwrap_or(0) as i64 - t.exponent().unwrap_or(0) as i64 + 1;
+        let p = qb.max(1) as usize + WORD_BIT_SIZE;
+
+        // the truncated quotient is not smaller than the integer part of the exact quotient
+        let mut q = a.div(t, p, RoundingMode::None).int();
+        let r = a.sub_full_prec(&q.mul_full_prec(t));
+
+        if r.is_zero() {
+            return (q, false);
+        }
+
+        let is_odd = !q.is_zero() && q.as_number().is_some_and(|v| v.is_odd_int());
+
+        let up = match r.add_full_prec(&r).cmp(t).unwrap_or(0) {
+            _ if rm == RoundingMode::None => false,
+            c if c > 0 => true,
+            c if c < 0 => false,
+            _ => match rm {
+                RoundingMode::Up => is_positive,
+                RoundingMode::Down => !is_positive,
+                RoundingMode::ToZero => false,
+                RoundingMode::FromZero => true,
+                RoundingMode::ToEven => is_odd,
+                RoundingMode::ToOdd => !is_odd,
+                RoundingMode::None => false,
+            },
+        };
+
+        if up {
+            q = q.add_full_prec(&BigFloat::from_word(1, WORD_BIT_SIZE));
+        }
+
+        (q, true)
+    }
+}
+
+impl PartialEq for BigDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Some(0)
+    }
+}
+
+impl PartialOrd for BigDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.cmp(other).map(|c| c.cmp(&0))
+    }
+}
+
+impl From<&BigDecimal> for BigFloat {
+    /// Converts the decimal number to `BigFloat` with the precision sufficient to represent its coefficient,
+    /// rounded to nearest, ties to even.
+    fn from(d: &BigDecimal) -> Self {
+        let p = d.c.mantissa_max_bit_len().unwrap_or(WORD_BIT_SIZE);
+        d.to_bigfloat(p, RoundingMode::ToEven)
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for BigDecimal {
+    /// Formats the number exactly in the same way as `format`.
+    /// The implementation is not available in no_std environment.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = crate::common::consts::TENPOWERS
+            .with(|tp| self.format(&mut tp.borrow_mut()))
+            .map_err(|_| core::fmt::Error)?;
+        f.write_str(&s)
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::str::FromStr for BigDecimal {
+    type Err = Error;
+
+    /// Parses the decimal number exactly, keeping all its digits.
+    /// The implementation is not available in no_std environment.
+    fn from_str(src: &str) -> Result<BigDecimal, Self::Err> {
+        crate::common::consts::TENPOWERS.with(|tp| Self::parse_exact(src, &mut tp.borrow_mut()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_decimal() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+        let n = 20;
+
+        let d = |s: &str, cc: &mut Consts| BigDecimal::parse(s, 1000, RoundingMode::None, cc);
+
+        // parsing and formatting are exact
+        for s in [
+            "0",
+            "-0",
+            "1",
+            "0.1",
+            "-19.99",
+            "1.50",
+            "123456789012345678901234567890.123",
+            "0.000001",
+            "1e-7",
+            "1.25e+10",
+            "-5e+3",
+            "1e+100",
+            "7.000e-300",
+            "Inf",
+            "-Inf",
+            "NaN",
+        ] {
+            assert_eq!(d(s, &mut cc).format(&mut cc).unwrap(), s);
+        }
+        assert_eq!(d("+012.50E1", &mut cc).format(&mut cc).unwrap(), "125.0");
+        assert_eq!(d(".5", &mut cc).format(&mut cc).unwrap(), "0.5");
+        assert_eq!(d("5.", &mut cc).format(&mut cc).unwrap(), "5");
+        for s in ["", "-", ".", "e5", "1e", "1e+", "1.2.3", "1x", "1e5x", " 1"] {
+            assert!(d(s, &mut cc).is_nan(), "{}", s);
+        }
+        assert!(BigDecimal::parse("1", 0, rm, &mut cc).is_nan());
+
+        // arithmetic
+        let a = d("0.1", &mut cc);
+        let b = d("0.2", &mut cc);
+        assert_eq!(a.add(&b, n, rm).format(&mut cc).unwrap(), "0.3");
+        assert_eq!(a.sub(&b, n, rm).format(&mut cc).unwrap(), "-0.1");
+        assert_eq!(
+            d("1.50", &mut cc)
+                .add(&d("2.25", &mut cc), n, rm)
+                .format(&mut cc)
+                .unwrap(),
+            "3.75"
+        );
+        assert_eq!(
+            d("1.50", &mut cc)
+                .mul(&d("2", &mut cc), n, rm)
+                .format(&mut cc)
+                .unwrap(),
+            "3.00"
+        );
+        assert_eq!(
+            d("1", &mut cc)
+                .div(&d("4", &mut cc), n, rm)
+                .format(&mut cc)
+                .unwrap(),
+            "0.25"
+        );
+        assert_eq!(
+            d("10", &mut cc)
+                .div(&d("5", &mut cc), n, rm)
+                .format(&mut cc)
+                .unwrap(),
+            "2"
+        );
+        assert_eq!(
+            d("1.00", &mut cc)
+                .div(&d("8", &mut cc), n, rm)
+                .format(&mut cc)
+                .unwrap(),
+            "0.125"
+        );
+        assert_eq!(
+            d("1", &mut cc)
+                .div(&d("3", &mut cc), n, rm)
+                .format(&mut cc)
+                .unwrap(),
+            "0.33333333333333333333"
+        );
+        assert_eq!(
+            d("-2", &mut cc)
+                .div(&d("3", &mut cc), n, rm)
+                .format(&mut cc)
+                .unwrap(),
+            "-0.66666666666666666667"
+        );
+        assert!(d("1", &mut cc).div(&d("0", &mut cc), n, rm).is_inf());
+        assert!(d("0", &mut cc).div(&d("0", &mut cc), n, rm).is_nan());
+        assert_eq!(
+            d("0.00", &mut cc).div(&d("2", &mut cc), n, rm).exponent(),
+            -2
+        );
+
+        // rounding modes
+        let x = d("2.5", &mut cc);
+        let y = d("-2.5", &mut cc);
+        let z = d("2.51", &mut cc);
+        for (rm, rx, ry, rz) in [
+            (RoundingMode::None, "2", "-2", "2"),
+            (RoundingMode::Up, "3", "-2", "3"),
+            (RoundingMode::Down, "2", "-3", "3"),
+            (RoundingMode::ToZero, "2", "-2", "3"),
+            (RoundingMode::FromZero, "3", "-3", "3"),
+            (RoundingMode::ToEven, "2", "-2", "3"),
+            (RoundingMode::ToOdd, "3", "-3", "3"),
+        ] {
+            assert_eq!(x.round(1, rm).format(&mut cc).unwrap(), rx);
+            assert_eq!(y.round(1, rm).format(&mut cc).unwrap(), ry);
+            assert_eq!(z.round(1, rm).format(&mut cc).unwrap(), rz);
+            assert_eq!(x.quantize(0, rm), x.round(1, rm));
+        }
+        assert_eq!(
+            d("9.99", &mut cc).round(2, rm).format(&mut cc).unwrap(),
+            "10"
+        );
+        assert_eq!(
+            d("999.5", &mut cc).round(3, rm).format(&mut cc).unwrap(),
+            "1.00e+3"
+        );
+        assert_eq!(
+            d("1.005", &mut cc)
+                .quantize(-2, rm)
+                .format(&mut cc)
+                .unwrap(),
+            "1.00"
+        );
+        assert_eq!(
+            d("1.015", &mut cc)
+                .quantize(-2, rm)
+                .format(&mut cc)
+                .unwrap(),
+            "1.02"
+        );
+        assert_eq!(
+            d("2", &mut cc).quantize(-2, rm).format(&mut cc).unwrap(),
+            "2.00"
+        );
+        assert_eq!(
+            d("0.004", &mut cc).quantize(3, rm).format(&mut cc).unwrap(),
+            "0e+3"
+        );
+
+        // the far smaller argument affects only rounding
+        let big = d("1e+1000", &mut cc);
+        let tiny = d("1e-1000", &mut cc);
+        assert_eq!(big.add(&tiny, n, rm), big);
+        assert_eq!(big.add(&tiny, n, RoundingMode::None), big);
+        assert_eq!(
+            big.sub(&tiny, n, RoundingMode::None)
+                .format(&mut cc)
+                .unwrap(),
+            "9.9999999999999999999e+999"
+        );
+        assert_eq!(tiny.add(&big, n, rm), big);
+        let h = d("1.5", &mut cc).add(&d("-1e-100", &mut cc), 1, RoundingMode::ToEven);
+        assert_eq!(h.format(&mut cc).unwrap(), "1");
+
+        // comparison
+        assert_eq!(d("1.0", &mut cc), d("1", &mut cc));
+        assert!(d("1e+2", &mut cc) > d("99.9", &mut cc));
+        assert!(d("-1e+2", &mut cc) < d("-99.9", &mut cc));
+        assert!(d("0", &mut cc) < d("1e-100", &mut cc));
+        assert!(d("-Inf", &mut cc) < d("-1e+100", &mut cc));
+        assert!(d("NaN", &mut cc).partial_cmp(&d("1", &mut cc)).is_none());
+
+        // conversion from and to BigFloat
+        let p = 128;
+        let x = BigDecimal::from_bigfloat(&BigFloat::from_f64(-0.375, p), n, rm);
+        assert_eq!(x.format(&mut cc).unwrap(), "-0.375");
+        assert_eq!(x.to_bigfloat(p, rm), BigFloat::from_f64(-0.375, p));
+        let x = BigDecimal::from_bigfloat(&BigFloat::from_f64(0.1, p), 100, rm);
+        assert_eq!(
+            x.format(&mut cc).unwrap(),
+            "0.1000000000000000055511151231257827021181583404541015625"
+        );
+        let x = BigDecimal::from_bigfloat(&BigFloat::from_f64(0.1, p), 5, rm);
+        assert_eq!(x.format(&mut cc).unwrap(), "0.10000");
+        let x = BigDecimal::from_bigfloat(&BigFloat::from_u64(1 << 40, p), 5, rm);
+        assert_eq!(x.format(&mut cc).unwrap(), "1.0995e+12");
+        assert_eq!(
+            d("0.1", &mut cc).to_bigfloat(p, rm),
+            BigFloat::from_u8(1, p).div(&BigFloat::from_u8(10, p), p, rm)
+        );
+        assert_eq!(
+            BigFloat::from(&d("2.5e+3", &mut cc)),
+            BigFloat::from_u16(2500, p)
+        );
+        assert!(BigDecimal::from_i128(1, EXPONENT_MAX)
+            .to_bigfloat(p, rm)
+            .is_inf_pos());
+        assert!(BigDecimal::from_i128(-1, EXPONENT_MIN)
+            .to_bigfloat(p, rm)
+            .is_zero());
+
+        // parts
+        let x = BigDecimal::from_i128(-1234, -2);
+        assert_eq!(x.format(&mut cc).unwrap(), "-12.34");
+        assert_eq!(x.digits(), 4);
+        assert_eq!(x.exponent(), -2);
+        assert_eq!(x.coefficient(), &BigFloat::from_i16(-1234, p));
+        let (c, e) = x.clone().into_parts();
+        assert_eq!(BigDecimal::from_parts(c, e), x);
+        assert!(BigDecimal::from_parts(BigFloat::from_f64(0.5, p), 0).is_nan());
+        assert_eq!(x.neg().abs(), x.abs());
+
+        // std formatting and parsing
+        let x: BigDecimal = "-1234.5600".parse().unwrap();
+        assert_eq!(x.to_string(), "-1234.5600");
+        assert!("1.2.3".parse::<BigDecimal>().is_err());
+    }
+}
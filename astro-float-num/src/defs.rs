@@ -33,22 +33,27 @@ pub type DoubleWord = u64;
 pub type SignedWord = i64;
 
 /// An exponent.
+#[cfg(not(feature = "exp64"))]
 pub type Exponent = i32;
 
+/// An exponent.
+#[cfg(feature = "exp64")]
+pub type Exponent = i64;
+
 /// Maximum exponent value.
-#[cfg(not(target_arch = "x86"))]
+#[cfg(not(any(target_arch = "x86", feature = "exp64")))]
 pub const EXPONENT_MAX: Exponent = Exponent::MAX;
 
 /// Maximum exponent value.
-#[cfg(target_arch = "x86")]
+#[cfg(any(target_arch = "x86", feature = "exp64"))]
 pub const EXPONENT_MAX: Exponent = Exponent::MAX / 4;
 
 /// Minimum exponent value.
-#[cfg(not(target_arch = "x86"))]
+#[cfg(not(any(target_arch = "x86", feature = "exp64")))]
 pub const EXPONENT_MIN: Exponent = Exponent::MIN;
 
 /// Minimum exponent value.
-#[cfg(target_arch = "x86")]
+#[cfg(any(target_arch = "x86", feature = "exp64"))]
 pub const EXPONENT_MIN: Exponent = Exponent::MIN / 4;
 
 /// Maximum value of a word.
@@ -71,6 +76,7 @@ pub const EXPONENT_BIT_SIZE: usize = core::mem::size_of::<Exponent>() * 8;
 
 /// Sign.
 #[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Sign {
     /// Negative.
     Neg = -1,
@@ -160,8 +166,84 @@ impl From<TryReserveError> for Error {
     }
 }
 
+/// Kinds of errors which can occur when a number is parsed from a string.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseErrorKind {
+    /// The string ended where a digit or another part of the number was expected.
+    UnexpectedEnd,
+
+    /// The character can't appear at this position.
+    UnexpectedChar(char),
+
+    /// The character is a digit, but not in the radix of the number, e.g. `9` in a binary number.
+    InvalidDigit(char),
+
+    /// The exponent has no digits.
+    InvalidExponent,
+
+    /// The string is a valid number, but the number could not be constructed from it.
+    Conversion(Error),
+}
+
+/// Error of parsing a number from a string.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    pos: usize,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, pos: usize) -> Self {
+        ParseError { kind, pos }
+    }
+
+    /// Returns the kind of the error.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the position in bytes in the parsed string at which the error was detected.
+    /// For conversion errors, the position is the length of the string.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ParseErrorKind::Conversion(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.kind {
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ParseErrorKind::InvalidDigit(c) => write!(f, "invalid digit {:?} for the radix", c),
+            ParseErrorKind::InvalidExponent => write!(f, "exponent has no digits"),
+            ParseErrorKind::Conversion(e) => write!(f, "{}", e),
+        }?;
+        write!(f, " at position {}", self.pos)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        match e.kind {
+            ParseErrorKind::Conversion(e) => e,
+            _ => Error::InvalidArgument,
+        }
+    }
+}
+
 /// Radix.
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Radix {
     /// Binary.
     Bin = 2,
@@ -176,10 +258,75 @@ pub enum Radix {
     Hex = 16,
 }
 
+/// Options of formatting of a number as a string.
+///
+/// The default options produce the same output as `format`.
+///
+/// ## Examples
+///
+/// The options for rendering numbers as `1 234 567,890 12`:
+///
+/// ```
+/// # use astro_float_num::FormatOptions;
+/// let opts = FormatOptions {
+///     frac_digits: Some(5),
+///     group_size: 3,
+///     group_separator: ' ',
+///     decimal_separator: ',',
+/// };
+/// ```
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct FormatOptions {
+    /// If `Some(n)`, the number is formatted in positional notation with `n` digits after the decimal separator.
+    /// If `None`, the number is formatted in scientific notation with all digits of the mantissa.
+    pub frac_digits: Option<usize>,
+
+    /// Number of digits in a group. The integer part is grouped starting from the decimal separator to the left,
+    /// and the fractional part is grouped starting from the decimal separator to the right.
+    /// The value 0 disables grouping.
+    pub group_size: usize,
+
+    /// Character inserted between groups of digits.
+    pub group_separator: char,
+
+    /// Character separating the integer part from the fractional part.
+    pub decimal_separator: char,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            frac_digits: None,
+            group_size: 0,
+            group_separator: ' ',
+            decimal_separator: '.',
+        }
+    }
+}
+
+/// Range of angles used for normalization of an angle.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum AngleRange {
+    /// The interval [0, 2*pi).
+    ZeroToTwoPi,
+
+    /// The interval (-pi, pi].
+    MinusPiToPi,
+}
+
 /// Rounding modes.
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RoundingMode {
     /// Skip rounding operation.
+    ///
+    /// The result is truncated to the requested precision, i.e. rounded toward zero,
+    /// and the inexact flag of the result is set if any of the discarded bits is nonzero.
+    /// Thus, the inexact flag of the result acts as a sticky bit located below the least significant bit of the mantissa.
+    /// The results of addition, subtraction, multiplication, division, and square root of exact numbers computed
+    /// with this mode can later be correctly rounded using `reround`.
+    /// For other functions the result computed with this mode is an approximation
+    /// which is not necessarily a truncation of the exact result.
     None = 1,
 
     /// Round half toward positive infinity.
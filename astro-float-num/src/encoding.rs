@@ -0,0 +1,287 @@
+//! Canonical binary encoding of BigFloat.
+//!
+//! The encoding does not depend on the word size and on the endianness of the platform.
+//! All multi-byte values are stored in little-endian byte order.
+//!
+//! | Offset | Size  | Description |
+//! |--------|-------|-------------|
+//! | 0      | 1     | Version of the encoding, currently 1. |
+//! | 1      | 1     | Flags: bits 0 and 1 are the kind of the value (0 - finite number, 1 - infinity, 2 - NaN), bit 2 is set if the sign is negative, bit 3 is set if the number is inexact. The other bits are 0. |
+//! | 2      | 4     | Exponent, a signed 32-bit integer. Present only for finite numbers. |
+//! | 6      | 8     | Precision in bits `p`, an unsigned 64-bit integer which is a multiple of 32. Present only for finite numbers. |
+//! | 14     | p / 8 | Mantissa, least significant byte first. Present only for finite numbers. |
+//!
+//! A finite number whose exponent does not fit in a signed 32-bit integer, which is possible with the feature `exp64`,
+//! is encoded with the version 2 of the encoding. In the version 2, the exponent is a signed 64-bit integer of size 8,
+//! and the precision and the mantissa follow it at the offsets 10 and 18. Numbers encoded with the version 2 can be decoded only with the feature `exp64`.
+//!
+//! The sign bit is always 0 for NaN, and the inexact flag is always 0 for infinity and NaN.
+//! The mantissa of a finite number is normalized, i.e. its most significant bit is set
+//! unless the number is zero or subnormal. Zero has the exponent 0.
+//! Therefore, numbers with the same value, precision, and inexact flag have the same encoding.
+
+use crate::defs::Error;
+use crate::defs::Exponent;
+use crate::defs::Sign;
+use crate::defs::Word;
+use crate::defs::WORD_BIT_SIZE;
+use crate::num::BigFloatNumber;
+use crate::BigFloat;
+use crate::INF_NEG;
+use crate::INF_POS;
+use crate::NAN;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Current version of the encoding.
+const VERSION: u8 = 1;
+
+/// Version of the encoding of the numbers whose exponent does not fit in 32 bits.
+const VERSION_EXP64: u8 = 2;
+
+const KIND_FINITE: u8 = 0;
+const KIND_INF: u8 = 1;
+const KIND_NAN: u8 = 2;
+const KIND_MASK: u8 = 0b11;
+const FLAG_NEG: u8 = 0b100;
+const FLAG_INEXACT: u8 = 0b1000;
+
+/// Size of the header of a finite number: version, flags, exponent, and precision.
+const HEADER_LEN: usize = 14;
+
+impl BigFloat {
+    /// Returns the canonical binary encoding of `self`.
+    /// The encoding includes the sign, the exponent, the precision, the inexact flag, and the mantissa of `self`,
+    /// and does not depend on the word size and on the endianness of the platform.
+    /// The format is described in the documentation of the module `encoding`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::BigFloat;
+    /// let n = BigFloat::from_f64(1.5, 64);
+    /// let bytes = n.to_bytes();
+    /// assert_eq!(bytes[..2], [1, 0]);
+    /// assert_eq!(BigFloat::from_bytes(&bytes).unwrap(), n);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        if let Some((m, _, s, e, inexact)) = self.as_raw_parts() {
+            let mut flags = KIND_FINITE;
+            if s == Sign::Neg {
+                flags |= FLAG_NEG;
+            }
+            if inexact {
+                flags |= FLAG_INEXACT;
+            }
+
+            let mut ret = Vec::with_capacity(HEADER_LEN + 4 + m.len() * WORD_BIT_SIZE / 8);
+            match i32::try_from(e as i64) {
+                Ok(e) => {
+                    ret.push(VERSION);
+                    ret.push(flags);
+                    ret.extend_from_slice(&e.to_le_bytes());
+                }
+                Err(_) => {
+                    ret.push(VERSION_EXP64);
+                    ret.push(flags);
+                    ret.extend_from_slice(&(e as i64).to_le_bytes());
+                }
+            }
+            ret.extend_from_slice(&((m.len() * WORD_BIT_SIZE) as u64).to_le_bytes());
+            for w in m {
+                ret.extend_from_slice(&w.to_le_bytes());
+            }
+
+            ret
+        } else if self.is_inf_pos() {
+            [VERSION, KIND_INF].to_vec()
+        } else if self.is_inf_neg() {
+            [VERSION, KIND_INF | FLAG_NEG].to_vec()
+        } else {
+            [VERSION, KIND_NAN].to_vec()
+        }
+    }
+
+    /// Decodes a number from the canonical binary encoding `bytes` produced by `to_bytes`.
+    /// If the precision of the encoded number is not a multiple of the word size,
+    /// it is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: the version of the encoding is not supported, or `bytes` is not a canonical encoding of a number.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 2 || (bytes[0] != VERSION && bytes[0] != VERSION_EXP64) {
+            return Err(Error::InvalidArgument);
+        }
+
+        let flags = bytes[1];
+        if flags & !(KIND_MASK | FLAG_NEG | FLAG_INEXACT) != 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let neg = flags & FLAG_NEG != 0;
+        let inexact = flags & FLAG_INEXACT != 0;
+
+        match flags & KIND_MASK {
+            KIND_FINITE => {}
+            KIND_INF if bytes.len() == 2 && bytes[0] == VERSION && !inexact => {
+                return Ok(if neg { INF_NEG } else { INF_POS });
+            }
+            KIND_NAN if bytes.len() == 2 && bytes[0] == VERSION && !inexact && !neg => {
+                return Ok(NAN)
+            }
+            _ => return Err(Error::InvalidArgument),
+        }
+
+        let (e, header_len) = if bytes[0] == VERSION {
+            if bytes.len() < HEADER_LEN {
+                return Err(Error::InvalidArgument);
+            }
+
+            let mut e = [0u8; 4];
+            e.copy_from_slice(&bytes[2..6]);
+            (i32::from_le_bytes(e) as i64, HEADER_LEN)
+        } else {
+            if bytes.len() < HEADER_LEN + 4 {
+                return Err(Error::InvalidArgument);
+            }
+
+            // the exponent which fits in 32 bits is encoded with the version 1
+            let mut e = [0u8; 8];
+            e.copy_from_slice(&bytes[2..10]);
+            let e = i64::from_le_bytes(e);
+            if i32::try_from(e).is_ok() {
+                return Err(Error::InvalidArgument);
+            }
+
+            (e, HEADER_LEN + 4)
+        };
+
+        let e = Exponent::try_from(e).map_err(|_| Error::InvalidArgument)?;
+
+        let mut p = [0u8; 8];
+        p.copy_from_slice(&bytes[header_len - 8..header_len]);
+        let p = u64::from_le_bytes(p);
+
+        let m = &bytes[header_len..];
+        if p == 0 || p % 32 != 0 || p / 8 != m.len() as u64 {
+            return Err(Error::InvalidArgument);
+        }
+
+        // the mantissa is aligned to the most significant bit, so the least significant word is padded with zeroes
+        let word_len = WORD_BIT_SIZE / 8;
+        let (first, rest) = m.split_at(m.len() % word_len);
+        let mut words = Vec::with_capacity(m.len() / word_len + 1);
+        let mut buf = [0u8; WORD_BIT_SIZE / 8];
+        if !first.is_empty() {
+            buf[word_len - first.len()..].copy_from_slice(first);
+            words.push(Word::from_le_bytes(buf));
+        }
+        for c in rest.chunks(word_len) {
+            buf.copy_from_slice(c);
+            words.push(Word::from_le_bytes(buf));
+        }
+
+        let s = if neg { Sign::Neg } else { Sign::Pos };
+        let mut n = BigFloatNumber::from_words_checked(&words, s, e)?;
+        n.set_inexact(inexact);
+
+        Ok(n.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::RoundingMode;
+
+    #[test]
+    fn test_bytes() {
+        // the encoding of 1.5 with precision 64
+        let bytes = BigFloat::from_f64(1.5, 64).to_bytes();
+        assert_eq!(
+            bytes,
+            [1, 0, 1, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xC0]
+        );
+
+        // the encoding of -0.5 with precision 32
+        let bytes = [1, 4, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80];
+        let d1 = BigFloat::from_bytes(&bytes).unwrap();
+        assert_eq!(d1, BigFloat::from_f64(-0.5, 64));
+        assert_eq!(d1.mantissa_max_bit_len(), Some(WORD_BIT_SIZE));
+
+        // round trip
+        let p = 192;
+        let third =
+            BigFloat::from_word(1, p).div(&BigFloat::from_word(3, p), p, RoundingMode::ToEven);
+        let mut tiny = BigFloat::min_positive(p);
+        tiny.set_inexact(true);
+
+        for d1 in [
+            third.clone(),
+            third.neg(),
+            tiny,
+            BigFloat::new(128).neg(),
+            BigFloat::max_value(256),
+            BigFloat::min_value(p),
+            INF_POS,
+            INF_NEG,
+        ] {
+            let bytes = d1.to_bytes();
+            let d2 = BigFloat::from_bytes(&bytes).unwrap();
+            assert_eq!(d2, d1);
+            assert_eq!(d2.sign(), d1.sign());
+            assert_eq!(d2.inexact(), d1.inexact());
+            assert_eq!(d2.mantissa_max_bit_len(), d1.mantissa_max_bit_len());
+            assert_eq!(d2.to_bytes(), bytes);
+        }
+
+        assert_eq!(NAN.to_bytes(), [1, 2]);
+        assert!(BigFloat::from_bytes(&NAN.to_bytes()).unwrap().is_nan());
+        assert_eq!(INF_NEG.to_bytes(), [1, 5]);
+
+        // incorrect encodings
+        let bytes = third.to_bytes();
+        for b in [
+            &[][..],
+            &[1],
+            &[2, 0],
+            &[1, 0x10],
+            &[1, 3],
+            &[1, 1 | 8],
+            &[1, 2 | 4],
+            &[1, 1, 0],
+            &bytes[..HEADER_LEN],
+            &bytes[..bytes.len() - 1],
+        ] {
+            assert_eq!(BigFloat::from_bytes(b), Err(Error::InvalidArgument));
+        }
+
+        // not normalized mantissa
+        let bytes = [1, 0, 1, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x40];
+        assert_eq!(BigFloat::from_bytes(&bytes), Err(Error::InvalidArgument));
+
+        // zero with a nonzero exponent
+        let bytes = [1, 0, 1, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(BigFloat::from_bytes(&bytes), Err(Error::InvalidArgument));
+
+        // the version 2 with the exponent 2^32
+        let bytes = [2, 0, 0, 0, 0, 0, 1, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80];
+        #[cfg(feature = "exp64")]
+        {
+            let d1 = BigFloat::from_bytes(&bytes).unwrap();
+            assert_eq!(d1.exponent(), Some(1 << 32));
+            assert_eq!(d1.to_bytes()[0], 2);
+            assert_eq!(BigFloat::from_bytes(&d1.to_bytes()).unwrap(), d1);
+        }
+        #[cfg(not(feature = "exp64"))]
+        assert_eq!(BigFloat::from_bytes(&bytes), Err(Error::InvalidArgument));
+
+        // the version 2 with the exponent which fits in 32 bits
+        let bytes = [2, 0, 1, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80];
+        assert_eq!(BigFloat::from_bytes(&bytes), Err(Error::InvalidArgument));
+    }
+}
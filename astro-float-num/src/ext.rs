@@ -1,11 +1,19 @@
 //! BigFloat including finite numbers, NaN, and `Inf`.
 
+use crate::common::util::u64_to_words;
+use crate::common::util::words_to_u64;
 use crate::defs::SignedWord;
 use crate::defs::DEFAULT_P;
 use crate::num::BigFloatNumber;
+#[cfg(feature = "debug")]
+use crate::tag::Tag;
+use crate::AngleRange;
 use crate::Consts;
 use crate::Error;
 use crate::Exponent;
+use crate::FormatOptions;
+use crate::ParseError;
+use crate::ParseErrorKind;
 use crate::Radix;
 use crate::RoundingMode;
 use crate::Sign;
@@ -13,40 +21,47 @@ use crate::Word;
 use core::num::FpCategory;
 use lazy_static::lazy_static;
 
-#[cfg(feature = "std")]
 use core::fmt::Write;
 
 #[cfg(not(feature = "std"))]
-use alloc::{string::String, vec::Vec};
+use alloc::{string::String, vec, vec::Vec};
 
 /// Not a number.
 pub const NAN: BigFloat = BigFloat {
     inner: Flavor::NaN(None),
+    #[cfg(feature = "debug")]
+    tag: None,
 };
 
 /// Positive infinity.
 pub const INF_POS: BigFloat = BigFloat {
     inner: Flavor::Inf(Sign::Pos),
+    #[cfg(feature = "debug")]
+    tag: None,
 };
 
 /// Negative infinity.
 pub const INF_NEG: BigFloat = BigFloat {
     inner: Flavor::Inf(Sign::Neg),
+    #[cfg(feature = "debug")]
+    tag: None,
 };
 
 lazy_static! {
 
     /// 1
-    pub static ref ONE: BigFloat = BigFloat { inner: Flavor::Value(BigFloatNumber::from_word(1, DEFAULT_P).expect("Constant ONE initialized")) };
+    pub static ref ONE: BigFloat = BigFloat { inner: Flavor::Value(BigFloatNumber::from_word(1, DEFAULT_P).expect("Constant ONE initialized")), #[cfg(feature = "debug")] tag: None };
 
     /// 2
-    pub static ref TWO: BigFloat = BigFloat { inner: Flavor::Value(BigFloatNumber::from_word(2, DEFAULT_P).expect("Constant TWO initialized")) };
+    pub static ref TWO: BigFloat = BigFloat { inner: Flavor::Value(BigFloatNumber::from_word(2, DEFAULT_P).expect("Constant TWO initialized")), #[cfg(feature = "debug")] tag: None };
 }
 
 /// A floating point number of arbitrary precision.
 #[derive(Debug)]
 pub struct BigFloat {
     inner: Flavor,
+    #[cfg(feature = "debug")]
+    tag: Option<Tag>,
 }
 
 #[derive(Debug)]
@@ -74,6 +89,8 @@ impl BigFloat {
     pub fn nan(err: Option<Error>) -> Self {
         BigFloat {
             inner: Flavor::NaN(err),
+            #[cfg(feature = "debug")]
+            tag: None,
         }
     }
 
@@ -113,6 +130,12 @@ impl BigFloat {
         }
     }
 
+    /// Return true if `self` is an integer number. The function is the same as `is_int`,
+    /// and `to_i128` and `to_u128` return a value only if it returns true.
+    pub fn is_integer(&self) -> bool {
+        self.is_int()
+    }
+
     /// Returns the associated with NaN error, if any.
     pub fn err(&self) -> Option<Error> {
         match &self.inner {
@@ -121,11 +144,19 @@ impl BigFloat {
         }
     }
 
+    /// Returns the provenance tag of `self`, i.e. the operation which produced `self`.
+    /// For NaN and Inf the tag points to the operation where the NaN or Inf first appeared.
+    /// The function returns None if `self` was not produced by an operation, e.g. if it is a constant.
+    #[cfg(feature = "debug")]
+    pub fn tag(&self) -> Option<Tag> {
+        self.tag
+    }
+
     /// Adds `d2` to `self` and returns the result of the operation with precision `p` rounded according to `rm`.
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn add(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
-        self.add_op(d2, p, rm, false)
+        self.add_op(d2, p, rm, false).tagged("add", &[self, d2])
     }
 
     /// Adds `d2` to `self` and returns the result of the operation.
@@ -133,6 +164,7 @@ impl BigFloat {
     /// This operation can be used to emulate integer addition.
     pub fn add_full_prec(&self, d2: &Self) -> Self {
         self.add_op(d2, 0, RoundingMode::None, true)
+            .tagged("add", &[self, d2])
     }
 
     fn add_op(&self, d2: &Self, p: usize, rm: RoundingMode, full_prec: bool) -> Self {
@@ -145,12 +177,16 @@ impl BigFloat {
                 ),
                 Flavor::Inf(s2) => BigFloat {
                     inner: Flavor::Inf(*s2),
+                    #[cfg(feature = "debug")]
+                    tag: None,
                 },
                 Flavor::NaN(err) => Self::nan(*err),
             },
             Flavor::Inf(s1) => match &d2.inner {
                 Flavor::Value(_) => BigFloat {
                     inner: Flavor::Inf(*s1),
+                    #[cfg(feature = "debug")]
+                    tag: None,
                 },
                 Flavor::Inf(s2) => {
                     if *s1 != *s2 {
@@ -158,6 +194,8 @@ impl BigFloat {
                     } else {
                         BigFloat {
                             inner: Flavor::Inf(*s2),
+                            #[cfg(feature = "debug")]
+                            tag: None,
                         }
                     }
                 }
@@ -171,7 +209,7 @@ impl BigFloat {
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn sub(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
-        self.sub_op(d2, p, rm, false)
+        self.sub_op(d2, p, rm, false).tagged("sub", &[self, d2])
     }
 
     /// Subtracts `d2` from `self` and returns the result of the operation.
@@ -179,6 +217,127 @@ impl BigFloat {
     /// This operation can be used to emulate integer subtraction.
     pub fn sub_full_prec(&self, d2: &Self) -> Self {
         self.sub_op(d2, 0, RoundingMode::None, true)
+            .tagged("sub", &[self, d2])
+    }
+
+    /// Adds `d2` to `self` and returns the sum with precision `p` rounded according to `rm`, and the residual.
+    /// The residual is the exact difference between the exact sum and the rounded sum,
+    /// so that adding the residual to the rounded sum gives `self + d2` exactly.
+    /// The residual has the precision required to represent it exactly, which may be larger than `p`.
+    /// This operation can be used to build floating-point expansions.
+    /// Precision is rounded upwards to the word size.
+    /// If the sum is Inf or NaN, the residual is NaN.
+    /// The function returns NaN for both values if the precision `p` is incorrect.
+    pub fn two_sum(&self, d2: &Self, p: usize, rm: RoundingMode) -> (Self, Self) {
+        let (sum, residual) = match (&self.inner, &d2.inner) {
+            (Flavor::Value(v1), Flavor::Value(v2)) => match v1.two_sum(v2, p, rm) {
+                Ok((sum, residual)) => (sum.into(), residual.into()),
+                Err(Error::ExponentOverflow(_)) => (self.add(d2, p, rm), NAN),
+                Err(e) => (Self::nan(Some(e)), Self::nan(Some(e))),
+            },
+            _ => (self.add(d2, p, rm), NAN),
+        };
+
+        (
+            sum.tagged("two_sum", &[self, d2]),
+            residual.tagged("two_sum", &[self, d2]),
+        )
+    }
+
+    /// Computes the sum of `values` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The sum is rounded once, as if it was computed exactly and then rounded.
+    /// The sum of an empty slice is positive zero.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if any of the values is NaN, if the values include infinities of opposite signs,
+    /// or if the precision `p` is incorrect.
+    pub fn sum(values: &[Self], p: usize, rm: RoundingMode) -> Self {
+        Self::sum_iter(values.iter(), p, rm)
+    }
+
+    // Computes the sum of `values` in the same way as `sum` does.
+    pub(crate) fn sum_iter<'a>(
+        values: impl Iterator<Item = &'a Self>,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Self {
+        let mut nums = Vec::new();
+        if let Err(e) = nums.try_reserve_exact(values.size_hint().0) {
+            return Self::nan(Some(e.into()));
+        }
+
+        let mut inf = None;
+
+        for v in values {
+            match &v.inner {
+                Flavor::Value(v) => nums.push(v),
+                Flavor::Inf(s) => match inf {
+                    Some(s2) if s2 != *s => return NAN.tagged("sum", &[v]),
+                    _ => inf = Some(*s),
+                },
+                Flavor::NaN(err) => return Self::nan(*err).tagged("sum", &[v]),
+            }
+        }
+
+        let ret = match inf {
+            Some(Sign::Pos) => INF_POS,
+            Some(Sign::Neg) => INF_NEG,
+            None => Self::result_to_ext(BigFloatNumber::sum(&nums, p, rm), false, true),
+        };
+
+        ret.tagged("sum", &[])
+    }
+
+    /// Computes the product of `values` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The product is rounded once, as if it was computed exactly and then rounded.
+    /// The product of an empty slice is one.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if any of the values is NaN, if the values include both infinity and zero,
+    /// or if the precision `p` is incorrect.
+    pub fn product(values: &[Self], p: usize, rm: RoundingMode) -> Self {
+        Self::product_iter(values.iter(), p, rm)
+    }
+
+    // Computes the product of `values` in the same way as `product` does.
+    pub(crate) fn product_iter<'a>(
+        values: impl Iterator<Item = &'a Self>,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Self {
+        let mut nums = Vec::new();
+        if let Err(e) = nums.try_reserve_exact(values.size_hint().0) {
+            return Self::nan(Some(e.into()));
+        }
+
+        let mut inf = false;
+        let mut zero = false;
+        let mut neg = false;
+
+        for v in values {
+            match &v.inner {
+                Flavor::Value(n) => {
+                    zero |= n.is_zero();
+                    neg ^= n.is_negative();
+                    nums.push(n);
+                }
+                Flavor::Inf(s) => {
+                    inf = true;
+                    neg ^= s.is_negative();
+                }
+                Flavor::NaN(err) => return Self::nan(*err).tagged("product", &[v]),
+            }
+        }
+
+        let ret = if inf && zero {
+            NAN
+        } else if inf && neg {
+            INF_NEG
+        } else if inf {
+            INF_POS
+        } else {
+            Self::result_to_ext(BigFloatNumber::product(&nums, p, rm), false, true)
+        };
+
+        ret.tagged("product", &[])
     }
 
     fn sub_op(&self, d2: &Self, p: usize, rm: RoundingMode, full_prec: bool) -> Self {
@@ -201,6 +360,8 @@ impl BigFloat {
             Flavor::Inf(s1) => match &d2.inner {
                 Flavor::Value(_) => BigFloat {
                     inner: Flavor::Inf(*s1),
+                    #[cfg(feature = "debug")]
+                    tag: None,
                 },
                 Flavor::Inf(s2) => {
                     if *s1 == *s2 {
@@ -208,6 +369,8 @@ impl BigFloat {
                     } else {
                         BigFloat {
                             inner: Flavor::Inf(*s1),
+                            #[cfg(feature = "debug")]
+                            tag: None,
                         }
                     }
                 }
@@ -221,7 +384,7 @@ impl BigFloat {
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn mul(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
-        self.mul_op(d2, p, rm, false)
+        self.mul_op(d2, p, rm, false).tagged("mul", &[self, d2])
     }
 
     /// Multiplies `d2` by `self` and returns the result of the operation.
@@ -229,6 +392,182 @@ impl BigFloat {
     /// This operation can be used to emulate integer multiplication.
     pub fn mul_full_prec(&self, d2: &Self) -> Self {
         self.mul_op(d2, 0, RoundingMode::None, true)
+            .tagged("mul", &[self, d2])
+    }
+
+    /// Computes `self * b + c * d` with precision `p` using a single rounding.
+    /// The result is rounded using the rounding mode `rm`.
+    /// The partial products are computed with full precision, so the result is correct even if the products cancel each other.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn fused_mul_add_pair(
+        &self,
+        b: &Self,
+        c: &Self,
+        d: &Self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Self {
+        let ret = match (&self.inner, &b.inner, &c.inner, &d.inner) {
+            (Flavor::Value(v1), Flavor::Value(v2), Flavor::Value(v3), Flavor::Value(v4)) => {
+                Self::result_to_ext(v1.fused_mul_add_pair(v2, v3, v4, p, rm), false, true)
+            }
+            _ => {
+                // a finite product does not affect the result when the other product is Inf or NaN
+                let ab = if self.is_inf() || self.is_nan() || b.is_inf() || b.is_nan() {
+                    self.mul(b, p, rm)
+                } else {
+                    Self::new(p)
+                };
+
+                let cd = if c.is_inf() || c.is_nan() || d.is_inf() || d.is_nan() {
+                    c.mul(d, p, rm)
+                } else {
+                    Self::new(p)
+                };
+
+                ab.add(&cd, p, rm)
+            }
+        };
+
+        ret.tagged("fused_mul_add_pair", &[self, b, c, d])
+    }
+
+    /// Computes the discriminant `self^2 - 4 * a * c` of the quadratic equation `a*x^2 + self*x + c = 0`
+    /// with precision `p` using a single rounding. The result is rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn discriminant(&self, a: &Self, c: &Self, p: usize, rm: RoundingMode) -> Self {
+        let ret = match (&self.inner, &a.inner, &c.inner) {
+            (Flavor::Value(v1), Flavor::Value(v2), Flavor::Value(v3)) => {
+                Self::result_to_ext(v1.discriminant(v2, v3, p, rm), false, true)
+            }
+            _ => {
+                // a finite product does not affect the result when the other product is Inf or NaN
+                let bb = if self.is_inf() || self.is_nan() {
+                    self.mul(self, p, rm)
+                } else {
+                    Self::new(p)
+                };
+
+                let ac = if a.is_inf() || a.is_nan() || c.is_inf() || c.is_nan() {
+                    a.mul(c, p, rm)
+                } else {
+                    Self::new(p)
+                };
+
+                bb.sub(&ac, p, rm)
+            }
+        };
+
+        ret.tagged("discriminant", &[self, a, c])
+    }
+
+    /// Computes the real roots of the quadratic equation `self*x^2 + b*x + c = 0` with precision `p`.
+    /// Each root is rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    /// The roots are returned in ascending order, and a double root is returned twice.
+    /// If `self` is zero, the roots of the linear equation `b*x + c = 0` are returned.
+    /// The function returns a single NaN if any of the coefficients is Inf or NaN, if all of the coefficients are zero,
+    /// if the precision `p` is incorrect, or if a root can't be computed.
+    pub fn solve_quadratic(&self, b: &Self, c: &Self, p: usize, rm: RoundingMode) -> Vec<Self> {
+        let args = [self, b, c];
+
+        let res = match (&self.inner, &b.inner, &c.inner) {
+            (Flavor::Value(v1), Flavor::Value(v2), Flavor::Value(v3)) => {
+                v1.solve_quadratic(v2, v3, p, rm)
+            }
+            _ => return vec![Self::nan(Self::coef_error(&args)).tagged("solve_quadratic", &args)],
+        };
+
+        Self::roots_to_ext(res, "solve_quadratic", &args)
+    }
+
+    /// Computes the real roots of the cubic equation `self*x^3 + b*x^2 + c*x + d = 0` with precision `p`.
+    /// Each root is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The roots are returned in ascending order, and a multiple root is returned as many times as its multiplicity.
+    /// If `self` is zero, the roots of the quadratic equation `b*x^2 + c*x + d = 0` are returned.
+    /// The function returns a single NaN if any of the coefficients is Inf or NaN, if all of the coefficients are zero,
+    /// if the precision `p` is incorrect, or if a root can't be computed.
+    pub fn solve_cubic(
+        &self,
+        b: &Self,
+        c: &Self,
+        d: &Self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Vec<Self> {
+        let args = [self, b, c, d];
+
+        let res = match (&self.inner, &b.inner, &c.inner, &d.inner) {
+            (Flavor::Value(v1), Flavor::Value(v2), Flavor::Value(v3), Flavor::Value(v4)) => {
+                v1.solve_cubic(v2, v3, v4, p, rm, cc)
+            }
+            _ => return vec![Self::nan(Self::coef_error(&args)).tagged("solve_cubic", &args)],
+        };
+
+        Self::roots_to_ext(res, "solve_cubic", &args)
+    }
+
+    /// Solves Kepler's equation `M = E - e*sin(E)` for the eccentric anomaly `E`,
+    /// where `self` is the mean anomaly `M`, and `e` is the eccentricity, `0 <= e < 1`.
+    /// The result is computed with precision `p` and rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns infinity with the sign of `self` if `self` is infinity.
+    /// The function returns NaN if any of the arguments is NaN, if `e` is outside of the range [0, 1),
+    /// or if the precision `p` is incorrect.
+    pub fn solve_kepler(&self, e: &Self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        let ret = match (&self.inner, &e.inner) {
+            (Flavor::NaN(err), _) | (_, Flavor::NaN(err)) => Self::nan(*err),
+            (Flavor::Value(v1), Flavor::Value(v2)) => {
+                Self::result_to_ext(v1.solve_kepler(v2, p, rm, cc), false, true)
+            }
+            (Flavor::Inf(s), Flavor::Value(v2)) => {
+                if v2.is_negative() || v2.cmp(&crate::common::consts::ONE) >= 0 {
+                    Self::nan(Some(Error::InvalidArgument))
+                } else if s.is_positive() {
+                    INF_POS
+                } else {
+                    INF_NEG
+                }
+            }
+            (_, Flavor::Inf(_)) => Self::nan(Some(Error::InvalidArgument)),
+        };
+
+        ret.tagged("solve_kepler", &[self, e])
+    }
+
+    // The error of the first NaN coefficient.
+    fn coef_error(args: &[&Self]) -> Option<Error> {
+        args.iter().find_map(|a| match a.inner {
+            Flavor::NaN(err) => err,
+            _ => None,
+        })
+    }
+
+    fn roots_to_ext(
+        res: Result<Vec<BigFloatNumber>, Error>,
+        op: &'static str,
+        args: &[&Self],
+    ) -> Vec<Self> {
+        match res {
+            Ok(roots) => roots
+                .into_iter()
+                .map(|v| {
+                    BigFloat {
+                        inner: Flavor::Value(v),
+                        #[cfg(feature = "debug")]
+                        tag: None,
+                    }
+                    .tagged(op, args)
+                })
+                .collect(),
+            Err(e) => vec![Self::nan(Some(e)).tagged(op, args)],
+        }
     }
 
     fn mul_op(&self, d2: &Self, p: usize, rm: RoundingMode, full_prec: bool) -> Self {
@@ -248,6 +587,8 @@ impl BigFloat {
                             let s = if v1.sign() == *s2 { Sign::Pos } else { Sign::Neg };
                             BigFloat {
                                 inner: Flavor::Inf(s),
+                                #[cfg(feature = "debug")]
+                                tag: None,
                             }
                         }
                     }
@@ -264,6 +605,8 @@ impl BigFloat {
                             let s = if v2.sign() == *s1 { Sign::Pos } else { Sign::Neg };
                             BigFloat {
                                 inner: Flavor::Inf(s),
+                                #[cfg(feature = "debug")]
+                                tag: None,
                             }
                         }
                     }
@@ -271,6 +614,8 @@ impl BigFloat {
                         let s = if s1 == s2 { Sign::Pos } else { Sign::Neg };
                         BigFloat {
                             inner: Flavor::Inf(s),
+                            #[cfg(feature = "debug")]
+                            tag: None,
                         }
                     }
                     Flavor::NaN(err) => Self::nan(*err),
@@ -284,7 +629,7 @@ impl BigFloat {
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn div(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
-        match &self.inner {
+        let ret = match &self.inner {
             Flavor::Value(v1) => match &d2.inner {
                 Flavor::Value(v2) => {
                     Self::result_to_ext(v1.div(v2, p, rm), v1.is_zero(), v1.sign() == v2.sign())
@@ -304,12 +649,156 @@ impl BigFloat {
                 Flavor::NaN(err) => Self::nan(*err),
             },
             Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("div", &[self, d2])
+    }
+
+    /// Adds `d2` to `self` in place with precision `p` rounded according to `rm`.
+    /// The memory of the previous mantissa of `self` is reused by the next operation on the current thread,
+    /// so a loop of in-place operations with the same precision does not allocate memory for the results.
+    /// Precision is rounded upwards to the word size.
+    /// `self` is set to NaN if the precision `p` is incorrect.
+    pub fn add_assign_ext(&mut self, d2: &Self, p: usize, rm: RoundingMode) {
+        let ret = self.add(d2, p, rm);
+        self.replace(ret);
+    }
+
+    /// Subtracts `d2` from `self` in place with precision `p` rounded according to `rm`.
+    /// The memory is reused in the same way as in `add_assign_ext`.
+    /// Precision is rounded upwards to the word size.
+    /// `self` is set to NaN if the precision `p` is incorrect.
+    pub fn sub_assign_ext(&mut self, d2: &Self, p: usize, rm: RoundingMode) {
+        let ret = self.sub(d2, p, rm);
+        self.replace(ret);
+    }
+
+    /// Multiplies `self` by `d2` in place with precision `p` rounded according to `rm`.
+    /// The memory is reused in the same way as in `add_assign_ext`.
+    /// Precision is rounded upwards to the word size.
+    /// `self` is set to NaN if the precision `p` is incorrect.
+    pub fn mul_assign_ext(&mut self, d2: &Self, p: usize, rm: RoundingMode) {
+        let ret = self.mul(d2, p, rm);
+        self.replace(ret);
+    }
+
+    /// Divides `self` by `d2` in place with precision `p` rounded according to `rm`.
+    /// The memory is reused in the same way as in `add_assign_ext`.
+    /// Precision is rounded upwards to the word size.
+    /// `self` is set to NaN if the precision `p` is incorrect.
+    pub fn div_assign_ext(&mut self, d2: &Self, p: usize, rm: RoundingMode) {
+        let ret = self.div(d2, p, rm);
+        self.replace(ret);
+    }
+
+    // Replace `self` with `v` and keep the memory of the previous mantissa for reuse.
+    fn replace(&mut self, v: Self) {
+        let old = core::mem::replace(self, v);
+        if let Flavor::Value(old) = old.inner {
+            old.recycle();
         }
     }
 
+    /// Adds `d2` to `self` and returns the result of the operation with precision `p` rounded according to `rm`,
+    /// and the ordering of the result relative to the exact sum, like the ternary value of MPFR.
+    /// The ordering is `Less` if the result is smaller than the exact value, `Greater` if it is larger, and `Equal` if the result is exact.
+    /// The arguments are treated as exact, i.e. their inexact flags do not affect the ordering.
+    /// If the result is NaN, or an infinity which is not caused by an exponent overflow, the ordering is `Equal`.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn add_ternary(&self, d2: &Self, p: usize, rm: RoundingMode) -> (Self, Ordering) {
+        let overflow = !self.is_inf() && !d2.is_inf();
+        Self::ternary(self, d2, |d1, d2, rm| d1.add(d2, p, rm), rm, overflow)
+    }
+
+    /// Subtracts `d2` from `self` and returns the result of the operation with precision `p` rounded according to `rm`,
+    /// and the ordering of the result relative to the exact difference.
+    /// The ordering is defined in the same way as in `add_ternary`.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn sub_ternary(&self, d2: &Self, p: usize, rm: RoundingMode) -> (Self, Ordering) {
+        let overflow = !self.is_inf() && !d2.is_inf();
+        Self::ternary(self, d2, |d1, d2, rm| d1.sub(d2, p, rm), rm, overflow)
+    }
+
+    /// Multiplies `d2` by `self` and returns the result of the operation with precision `p` rounded according to `rm`,
+    /// and the ordering of the result relative to the exact product.
+    /// The ordering is defined in the same way as in `add_ternary`.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn mul_ternary(&self, d2: &Self, p: usize, rm: RoundingMode) -> (Self, Ordering) {
+        let overflow = !self.is_inf() && !d2.is_inf();
+        Self::ternary(self, d2, |d1, d2, rm| d1.mul(d2, p, rm), rm, overflow)
+    }
+
+    /// Divides `self` by `d2` and returns the result of the operation with precision `p` rounded according to `rm`,
+    /// and the ordering of the result relative to the exact quotient.
+    /// The ordering is defined in the same way as in `add_ternary`; in particular, it is `Equal` for division by zero.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn div_ternary(&self, d2: &Self, p: usize, rm: RoundingMode) -> (Self, Ordering) {
+        let overflow = !self.is_inf() && !d2.is_inf() && !d2.is_zero();
+        Self::ternary(self, d2, |d1, d2, rm| d1.div(d2, p, rm), rm, overflow)
+    }
+
+    /// Computes the square root of `self` with precision `p` rounded according to `rm`,
+    /// and returns the result with the ordering of the result relative to the exact square root.
+    /// The ordering is defined in the same way as in `add_ternary`.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn sqrt_ternary(&self, p: usize, rm: RoundingMode) -> (Self, Ordering) {
+        Self::ternary(self, self, |d1, _, rm| d1.sqrt(p, rm), rm, false)
+    }
+
+    // Computes `f(d1, d2, rm)`, and determines the ordering of the result relative to the exact value
+    // using the truncated result `f(d1, d2, RoundingMode::None)` for the arguments with the inexact flag cleared,
+    // whose inexact flag is set if the exact value is not representable.
+    // `overflow` specifies that an infinite result can only be caused by the exponent overflow.
+    fn ternary<F>(d1: &Self, d2: &Self, f: F, rm: RoundingMode, overflow: bool) -> (Self, Ordering)
+    where
+        F: Fn(&Self, &Self, RoundingMode) -> Self,
+    {
+        let ret = f(d1, d2, rm);
+
+        let ord = match &ret.inner {
+            Flavor::Value(v) => {
+                let t = if d1.inexact() || d2.inexact() {
+                    let mut e1 = d1.clone();
+                    let mut e2 = d2.clone();
+                    e1.set_inexact(false);
+                    e2.set_inexact(false);
+                    f(&e1, &e2, RoundingMode::None)
+                } else if rm == RoundingMode::None {
+                    ret.clone()
+                } else {
+                    f(d1, d2, RoundingMode::None)
+                };
+
+                if !t.inexact() {
+                    Ordering::Equal
+                } else if (ret == t) == v.is_positive() {
+                    // the result is the truncated exact value
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            }
+            Flavor::Inf(s) if overflow => {
+                if s.is_positive() {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                }
+            }
+            _ => Ordering::Equal,
+        };
+
+        (ret, ord)
+    }
+
     /// Returns the remainder of division of `|self|` by `|d2|`. The sign of the result is set to the sign of `self`.
     pub fn rem(&self, d2: &Self) -> Self {
-        match &self.inner {
+        let ret = match &self.inner {
             Flavor::Value(v1) => match &d2.inner {
                 Flavor::Value(v2) => {
                     Self::result_to_ext(v1.rem(v2), v1.is_zero(), v1.sign() == v2.sign())
@@ -319,7 +808,65 @@ impl BigFloat {
             },
             Flavor::Inf(_) => NAN,
             Flavor::NaN(err) => Self::nan(*err),
-        }
+        };
+
+        ret.tagged("rem", &[self, d2])
+    }
+
+    /// Returns the remainder of division of `|self|` by `|d2|` like `rem`, and the low bits of the integer quotient.
+    /// The integer quotient `n` is `|self| / |d2|` rounded toward zero, so that `|self| = n * |d2| + |r|`, where `r` is the remainder.
+    /// The second value holds the 63 least significant bits of `n` with the sign of `self / d2`,
+    /// which can be used to select the branch after a range reduction by a period `d2`.
+    /// The quotient is 0 if the remainder is NaN, or if `d2` is Inf.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::BigFloat;
+    /// let (r, q) = BigFloat::from_f64(-7.5, 64).remquo(&BigFloat::from_f64(2.0, 64));
+    /// assert_eq!(r, BigFloat::from_f64(-1.5, 64));
+    /// assert_eq!(q, -3);
+    /// ```
+    pub fn remquo(&self, d2: &Self) -> (Self, i64) {
+        let (r, q) = match (&self.inner, &d2.inner) {
+            (Flavor::Value(v1), Flavor::Value(v2)) => match v1.remquo(v2) {
+                Ok((r, q)) => (r.into(), q),
+                Err(e) => (Self::result_to_ext(Err(e), v1.is_zero(), true), 0),
+            },
+            _ => (self.rem(d2), 0),
+        };
+
+        (r.tagged("remquo", &[self, d2]), q)
+    }
+
+    /// Rounds `self` to an integer multiple of `step` and returns the result with precision `p`.
+    /// The multiple is chosen according to the rounding mode `rm`, as if `self / step` was rounded to an integer,
+    /// and the result is rounded to precision `p` using the same rounding mode `rm`, so the result is computed using a single rounding.
+    /// `RoundingMode::None` chooses the multiple toward zero. The sign of `step` is ignored.
+    /// The result is Inf if `self` is Inf and `step` is finite.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if `step` is zero or Inf, or if the precision `p` is incorrect.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, RoundingMode};
+    /// let x = BigFloat::from_f64(7.3, 128);
+    /// let step = BigFloat::from_f64(1.5, 128);
+    /// let d = x.round_to_multiple(&step, 128, RoundingMode::ToEven);
+    /// assert_eq!(d, BigFloat::from_f64(7.5, 128));
+    /// ```
+    pub fn round_to_multiple(&self, step: &Self, p: usize, rm: RoundingMode) -> Self {
+        let ret = match (&self.inner, &step.inner) {
+            (Flavor::Value(v1), Flavor::Value(v2)) => {
+                Self::result_to_ext(v1.round_to_multiple(v2, p, rm), false, true)
+            }
+            (Flavor::Inf(_), Flavor::Value(v2)) if !v2.is_zero() => self.clone(),
+            (Flavor::NaN(err), _) | (_, Flavor::NaN(err)) => Self::nan(*err),
+            _ => NAN,
+        };
+
+        ret.tagged("round_to_multiple", &[self, step])
     }
 
     /// Compares `self` to `d2`.
@@ -379,7 +926,7 @@ impl BigFloat {
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn pow(&self, n: &Self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
-        match &self.inner {
+        let ret = match &self.inner {
             Flavor::Value(v1) => {
                 match &n.inner {
                     Flavor::Value(v2) => Self::result_to_ext(
@@ -393,6 +940,8 @@ impl BigFloat {
                         if val > 0 {
                             BigFloat {
                                 inner: Flavor::Inf(*s2),
+                                #[cfg(feature = "debug")]
+                                tag: None,
                             }
                         } else if val < 0 {
                             Self::new(p)
@@ -432,14 +981,16 @@ impl BigFloat {
                 }
             }
             Flavor::NaN(err) => Self::nan(*err),
-        }
+        };
+
+        ret.tagged("pow", &[self, n])
     }
 
     /// Compute the power of `self` to the integer `n` with precision `p`. The result is rounded using the rounding mode `rm`.
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn powi(&self, n: usize, p: usize, rm: RoundingMode) -> Self {
-        match &self.inner {
+        let ret = match &self.inner {
             Flavor::Value(v1) => Self::result_to_ext(v1.powi(n, p, rm), false, true),
             Flavor::Inf(s1) => {
                 // inf ^ v2
@@ -452,7 +1003,9 @@ impl BigFloat {
                 }
             }
             Flavor::NaN(err) => Self::nan(*err),
-        }
+        };
+
+        ret.tagged("powi", &[self])
     }
 
     /// Computes the logarithm base `n` of a number with precision `p`. The result is rounded using the rounding mode `rm`.
@@ -460,12 +1013,12 @@ impl BigFloat {
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn log(&self, n: &Self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
-        match &self.inner {
+        let ret = match &self.inner {
             Flavor::Value(v1) => {
                 match &n.inner {
                     Flavor::Value(v2) => {
                         if v2.is_zero() {
-                            return INF_NEG;
+                            return INF_NEG.tagged("log", &[self, n]);
                         }
                         Self::result_to_ext(v1.log(v2, p, rm, cc), false, true)
                     }
@@ -500,7 +1053,53 @@ impl BigFloat {
                 }
             }
             Flavor::NaN(err) => Self::nan(*err),
-        }
+        };
+
+        ret.tagged("log", &[self, n])
+    }
+
+    /// Computes the natural logarithm of one plus `self` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The result is accurate when `self` is close to zero.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns negative infinity if `self` is -1, and NaN if `self` is less than -1, or if the precision `p` is incorrect.
+    pub fn ln_1p(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        let ret = match &self.inner {
+            Flavor::Value(v) => {
+                if v.is_negative() && v.abs_cmp(&crate::common::consts::ONE) == 0 {
+                    INF_NEG
+                } else {
+                    Self::result_to_ext(v.ln_1p(p, rm, cc), false, true)
+                }
+            }
+            Flavor::Inf(s) => {
+                if s.is_positive() {
+                    INF_POS
+                } else {
+                    NAN
+                }
+            }
+            Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("ln_1p", &[self])
+    }
+
+    /// Computes `sqrt(self^2 + d2^2)` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The result is computed using a single rounding without overflow or underflow of the intermediate results.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns positive infinity if any of the arguments is infinite, even if the other argument is NaN.
+    /// The function returns NaN if any of the arguments is NaN, or if the precision `p` is incorrect.
+    pub fn hypot(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
+        let ret = match (&self.inner, &d2.inner) {
+            (Flavor::Inf(_), _) | (_, Flavor::Inf(_)) => INF_POS,
+            (Flavor::NaN(err), _) | (_, Flavor::NaN(err)) => Self::nan(*err),
+            (Flavor::Value(v1), Flavor::Value(v2)) => {
+                Self::result_to_ext(v1.hypot(v2, p, rm), false, true)
+            }
+        };
+
+        ret.tagged("hypot", &[self, d2])
     }
 
     /// Returns true if `self` is positive.
@@ -600,7 +1199,12 @@ impl BigFloat {
 
     /// Parses a number from the string `s`.
     /// The function expects `s` to be a number in scientific format in radix `rdx`, or +-Inf, or NaN.
+    /// Regardless of `rdx`, `s` can also be a number in C99 hexadecimal floating point notation, e.g. `0x1.8p+3`,
+    /// where the exponent after "p" is a decimal power of 2.
+    /// Digits can be separated with `_` as in Rust literals, e.g. `1_000_000.000_1`: the separator can follow
+    /// any digit of the mantissa or of the exponent. In radixes where "e" is a digit, "_e" always starts the exponent.
     /// if `p` equals to usize::MAX then the precision of the resulting number is determined automatically from the input.
+    /// If `s` is not a valid number, NaN is returned. `try_parse` can be used to find out what is wrong with the input.
     ///
     /// ## Examples
     ///
@@ -622,45 +1226,188 @@ impl BigFloat {
     ///
     /// let n = BigFloat::parse("NaN", Radix::Oct, 2, RoundingMode::None, &mut cc);
     /// assert!(n.is_nan());
+    ///
+    /// let n = BigFloat::parse("-0x1.8p-1", Radix::Dec, 64, RoundingMode::None, &mut cc);
+    /// assert_eq!(n, BigFloat::from_f64(-0.75, 64));
+    ///
+    /// let n = BigFloat::parse("1_000_000.25", Radix::Dec, 64, RoundingMode::None, &mut cc);
+    /// assert_eq!(n, BigFloat::from_f64(1000000.25, 64));
     /// ```
     pub fn parse(s: &str, rdx: Radix, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
-        match crate::parser::parse(s, rdx) {
-            Ok(ps) => {
-                if ps.is_inf() {
-                    if ps.sign() == Sign::Pos {
-                        INF_POS
-                    } else {
-                        INF_NEG
-                    }
-                } else if ps.is_nan() {
-                    NAN
-                } else {
-                    let (m, s, e) = ps.raw_parts();
-                    Self::result_to_ext(
-                        BigFloatNumber::convert_from_radix(s, m, e, rdx, p, rm, cc),
-                        false,
-                        true,
-                    )
-                }
-            }
-            Err(e) => Self::nan(Some(e)),
-        }
+        let ret = match Self::try_parse(s, rdx, p, rm, cc) {
+            Ok(v) => v,
+            Err(e) => Self::nan(Some(e.into())),
+        };
+
+        ret.tagged("parse", &[])
     }
 
-    #[cfg(feature = "std")]
-    pub(crate) fn write_str<T: Write>(
-        &self,
-        w: &mut T,
-        rdx: Radix,
-        rm: RoundingMode,
-        cc: &mut Consts,
-    ) -> Result<(), core::fmt::Error> {
-        match &self.inner {
-            Flavor::Value(v) => match v.format(rdx, rm, cc) {
-                Ok(s) => w.write_str(&s),
-                Err(e) => match e {
-                    Error::ExponentOverflow(s) => {
-                        if s.is_positive() {
+    /// Parses a number from the string `s` in the same way as `parse`.
+    /// Unlike `parse`, the function returns an error describing the problem and its position
+    /// if `s` is not a valid number.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `ParseError` with one of the following kinds:
+    ///
+    ///  - UnexpectedEnd: the string ended where a digit or another part of the number was expected.
+    ///  - UnexpectedChar: the string contains a character which can't appear at its position.
+    ///  - InvalidDigit: the string contains a digit which is not valid in radix `rdx`.
+    ///  - InvalidExponent: the exponent has no digits.
+    ///  - Conversion: the number could not be constructed, e.g. because memory allocation failed or the precision is incorrect.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, Consts, ParseErrorKind, Radix, RoundingMode};
+    /// let mut cc = Consts::new().expect("Constants cache initialized.");
+    ///
+    /// let n = BigFloat::try_parse("1.5e+2", Radix::Dec, 64, RoundingMode::ToEven, &mut cc);
+    /// assert_eq!(n, Ok(BigFloat::from_f64(150.0, 64)));
+    ///
+    /// let err = BigFloat::try_parse("1.5x", Radix::Dec, 64, RoundingMode::ToEven, &mut cc).unwrap_err();
+    /// assert_eq!(err.kind(), ParseErrorKind::UnexpectedChar('x'));
+    /// assert_eq!(err.position(), 3);
+    ///
+    /// let err = BigFloat::try_parse("1012", Radix::Bin, 64, RoundingMode::ToEven, &mut cc).unwrap_err();
+    /// assert_eq!(err.kind(), ParseErrorKind::InvalidDigit('2'));
+    /// assert_eq!(err.to_string(), "invalid digit '2' for the radix at position 3");
+    /// ```
+    pub fn try_parse(
+        s: &str,
+        rdx: Radix,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, ParseError> {
+        Self::try_parse_base(s, rdx as u32, p, rm, cc)
+    }
+
+    /// Parses a number from the string `s` in radix `base` from 2 to 36.
+    /// Letters are used as digits above 9, and they are case insensitive.
+    /// If `e` is a digit in the radix, i.e. `base` is greater than 14, the exponent part is separated
+    /// from the mantissa by "_", e.g. `z.zz_e+1z`. The digits of the exponent are in radix `base`.
+    /// In radixes where "i" or "n" are digits, only the strings "inf" and "nan" themselves are parsed as infinity and NaN.
+    /// Otherwise, the function behaves as `parse`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, Consts, RoundingMode};
+    /// let mut cc = Consts::new().expect("Constants cache initialized.");
+    ///
+    /// let n = BigFloat::parse_base("-1.1e+10", 3, 64, RoundingMode::ToEven, &mut cc);
+    /// assert_eq!(n, BigFloat::from_f64(-36.0, 64));
+    ///
+    /// let n = BigFloat::parse_base("Z.I_e+1", 36, 64, RoundingMode::ToEven, &mut cc);
+    /// assert_eq!(n, BigFloat::from_f64(1278.0, 64));
+    /// ```
+    pub fn parse_base(s: &str, base: u32, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        let ret = match Self::try_parse_base(s, base, p, rm, cc) {
+            Ok(v) => v,
+            Err(e) => Self::nan(Some(e.into())),
+        };
+
+        ret.tagged("parse_base", &[])
+    }
+
+    /// Parses a number from the string `s` in radix `base` in the same way as `parse_base`.
+    /// The function returns an error describing the problem and its position if `s` is not a valid number.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `ParseError` with the same kinds as `try_parse`.
+    /// If `base` is not in the range from 2 to 36, the kind is `Conversion(Error::InvalidArgument)` at position 0.
+    pub fn try_parse_base(
+        s: &str,
+        base: u32,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, ParseError> {
+        if !(2..=36).contains(&base) {
+            return Err(ParseError::new(
+                ParseErrorKind::Conversion(Error::InvalidArgument),
+                0,
+            ));
+        }
+
+        let ps = crate::parser::parse_base(s, base)?;
+
+        let res = if ps.is_inf() {
+            return Ok(if ps.sign() == Sign::Pos { INF_POS } else { INF_NEG });
+        } else if ps.is_nan() {
+            return Ok(NAN);
+        } else if let Some(bin_e) = ps.bin_exp() {
+            let (m, s, e) = ps.raw_parts();
+            BigFloatNumber::convert_from_radix(
+                s,
+                m,
+                e,
+                Radix::Hex,
+                usize::MAX,
+                RoundingMode::None,
+                cc,
+            )
+            .and_then(|v| {
+                let p = if p == usize::MAX { v.mantissa_max_bit_len() } else { p };
+                v.ldexp(bin_e, p, rm)
+            })
+        } else {
+            let (m, s, e) = ps.raw_parts();
+            BigFloatNumber::convert_from_base(s, m, e, base, p, rm, cc)
+        };
+
+        match res {
+            Ok(_) | Err(Error::ExponentOverflow(_)) => Ok(Self::result_to_ext(res, false, true)),
+            Err(e) => Err(ParseError::new(ParseErrorKind::Conversion(e), s.len())),
+        }
+    }
+
+    /// Writes the number formatted using radix `rdx`, rounding mode `rm`, and formatting options `opts` to `w`.
+    /// The output is the same as the output of `format_with`, but the characters are written to `w` directly
+    /// without building an intermediate string.
+    /// The conversion to radix `rdx` still allocates memory for the digits, one byte per digit,
+    /// and for the temporary numbers used in the conversion.
+    /// If the number can't be formatted, "Inf", "-Inf", or "Err" is written instead as in `format_with`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if writing to `w` fails.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, Consts, FormatOptions, Radix, RoundingMode};
+    /// use core::fmt::Write;
+    ///
+    /// let mut cc = Consts::new().unwrap();
+    /// let n = BigFloat::from_f64(-1234.5, 64);
+    /// let opts = FormatOptions {
+    ///     frac_digits: Some(2),
+    ///     group_size: 3,
+    ///     group_separator: ',',
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut s = String::from("x = ");
+    /// n.write_str(&mut s, Radix::Dec, RoundingMode::ToEven, &opts, &mut cc).unwrap();
+    /// assert_eq!(s, "x = -1,234.50");
+    /// ```
+    pub fn write_str<T: Write>(
+        &self,
+        w: &mut T,
+        rdx: Radix,
+        rm: RoundingMode,
+        opts: &FormatOptions,
+        cc: &mut Consts,
+    ) -> Result<(), core::fmt::Error> {
+        match &self.inner {
+            Flavor::Value(v) => match v.write_with(w, rdx as u32, rm, opts, cc) {
+                Ok(r) => r,
+                Err(e) => match e {
+                    Error::ExponentOverflow(s) => {
+                        if s.is_positive() {
                             w.write_str("Inf")
                         } else {
                             w.write_str("-Inf")
@@ -687,8 +1434,87 @@ impl BigFloat {
     ///  - MemoryAllocation: failed to allocate memory for mantissa.
     ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
     pub fn format(&self, rdx: Radix, rm: RoundingMode, cc: &mut Consts) -> Result<String, Error> {
+        self.format_with(rdx, rm, &FormatOptions::default(), cc)
+    }
+
+    /// Formats the number using radix `rdx`, rounding mode `rm`, and formatting options `opts`.
+    /// The options define the notation, the grouping of digits, and the separators.
+    /// Digits of the exponent are not grouped.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, Consts, FormatOptions, Radix, RoundingMode};
+    /// let mut cc = Consts::new().unwrap();
+    /// let n = BigFloat::parse("1234567.89012", Radix::Dec, 128, RoundingMode::ToEven, &mut cc);
+    /// let opts = FormatOptions {
+    ///     frac_digits: Some(5),
+    ///     group_size: 3,
+    ///     group_separator: ' ',
+    ///     decimal_separator: ',',
+    /// };
+    /// let s = n.format_with(Radix::Dec, RoundingMode::ToEven, &opts, &mut cc).unwrap();
+    /// assert_eq!(s, "1 234 567,890 12");
+    /// ```
+    pub fn format_with(
+        &self,
+        rdx: Radix,
+        rm: RoundingMode,
+        opts: &FormatOptions,
+        cc: &mut Consts,
+    ) -> Result<String, Error> {
+        self.format_in_base(rdx as u32, rm, opts, cc)
+    }
+
+    /// Formats the number using radix `base` from 2 to 36 and rounding mode `rm`.
+    /// Digits above 9 are formatted as capital letters, and the exponent is formatted in radix `base` in lower case.
+    /// If `e` is a digit in the radix, i.e. `base` is greater than 14, the exponent part is separated
+    /// from the mantissa by "_". The output for radixes 2, 8, 10, and 16 is the same as the output of `format`.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: `base` is not in the range from 2 to 36.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, Consts, RoundingMode};
+    /// let mut cc = Consts::new().unwrap();
+    /// let n = BigFloat::from_f64(-36.0, 64);
+    /// assert_eq!(n.format_base(3, RoundingMode::ToEven, &mut cc).unwrap(), "-1.1e+10");
+    ///
+    /// let n = BigFloat::from_f64(1278.0, 64);
+    /// assert_eq!(n.format_base(36, RoundingMode::ToEven, &mut cc).unwrap(), "Z.I_e+1");
+    /// ```
+    pub fn format_base(
+        &self,
+        base: u32,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<String, Error> {
+        if !(2..=36).contains(&base) {
+            return Err(Error::InvalidArgument);
+        }
+
+        self.format_in_base(base, rm, &FormatOptions::default(), cc)
+    }
+
+    fn format_in_base(
+        &self,
+        base: u32,
+        rm: RoundingMode,
+        opts: &FormatOptions,
+        cc: &mut Consts,
+    ) -> Result<String, Error> {
         let s = match &self.inner {
-            Flavor::Value(v) => match v.format(rdx, rm, cc) {
+            Flavor::Value(v) => match v.format_with(base, rm, opts, cc) {
                 Ok(s) => return Ok(s),
                 Err(e) => match e {
                     Error::ExponentOverflow(s) => {
@@ -718,6 +1544,42 @@ impl BigFloat {
         Ok(ret)
     }
 
+    /// Formats the number in C99 hexadecimal floating point notation, e.g. `0x1.8p+3`.
+    /// The notation represents the value of the number exactly: the mantissa is normalized to have a single leading digit 1,
+    /// trailing zeroes of the fraction are omitted, and the exponent after "p" is a decimal power of 2.
+    /// Inf and NaN are formatted as "Inf", "-Inf", and "NaN".
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::BigFloat;
+    /// let n = BigFloat::from_f64(-12.5, 64);
+    /// assert_eq!(n.format_hex_float().unwrap(), "-0x1.9p+3");
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    pub fn format_hex_float(&self) -> Result<String, Error> {
+        let s = match &self.inner {
+            Flavor::Value(v) => return v.format_hex_float(),
+            Flavor::Inf(sign) => {
+                if sign.is_negative() {
+                    "-Inf"
+                } else {
+                    "Inf"
+                }
+            }
+            Flavor::NaN(_) => "NaN",
+        };
+
+        let mut ret = String::new();
+        ret.try_reserve_exact(s.len())?;
+        ret.push_str(s);
+
+        Ok(ret)
+    }
+
     /// Returns a random normalized (not subnormal) BigFloat number with exponent in the range
     /// from `exp_from` to `exp_to` inclusive. The sign can be positive and negative. Zero is excluded.
     /// Precision is rounded upwards to the word size.
@@ -755,11 +1617,93 @@ impl BigFloat {
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn atan(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
-        match &self.inner {
+        let ret = match &self.inner {
             Flavor::Value(v) => Self::result_to_ext(v.atan(p, rm, cc), v.is_zero(), true),
             Flavor::Inf(s) => Self::result_to_ext(Self::half_pi(*s, p, rm, cc), false, true),
             Flavor::NaN(err) => Self::nan(*err),
-        }
+        };
+
+        ret.tagged("atan", &[self])
+    }
+
+    /// Computes the arctangent of `self/x` with precision `p`, using the signs of `self` and `x` to determine the quadrant.
+    /// The result is in the range [-pi, pi]. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// Signed zeros and infinities are treated as in the C standard library, e.g. the result is ±pi
+    /// if `self` is ±0 and `x` is -0, and the result is ±3*pi/4 if `self` is ±Inf and `x` is -Inf.
+    /// The function returns NaN if any of the arguments is NaN, or if the precision `p` is incorrect.
+    pub fn atan2(&self, x: &Self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        let ret = match (&self.inner, &x.inner) {
+            (Flavor::NaN(err), _) | (_, Flavor::NaN(err)) => Self::nan(*err),
+            (Flavor::Value(v1), Flavor::Value(v2)) => {
+                Self::result_to_ext(v1.atan2(v2, p, rm, cc), false, true)
+            }
+            (Flavor::Value(v1), Flavor::Inf(s2)) => Self::result_to_ext(
+                if s2.is_positive() {
+                    BigFloatNumber::new2(p, v1.sign(), v1.inexact())
+                } else {
+                    BigFloatNumber::signed_pi(v1.sign(), 0, p, rm, cc)
+                },
+                false,
+                true,
+            ),
+            (Flavor::Inf(s1), Flavor::Value(_)) => {
+                Self::result_to_ext(BigFloatNumber::signed_pi(*s1, -1, p, rm, cc), false, true)
+            }
+            (Flavor::Inf(s1), Flavor::Inf(s2)) => {
+                // ±pi/4 or ±3*pi/4
+                let y = Self::from_i8(s1.to_int(), p);
+                let x = Self::from_i8(s2.to_int(), p);
+                y.atan2(&x, p, rm, cc)
+            }
+        };
+
+        ret.tagged("atan2", &[self, x])
+    }
+
+    /// Normalizes an angle `self` in radians to the range `range` with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// The period 2*pi is taken with the precision sufficient for reducing `self` with a single rounding of the result.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if `self` is Inf or NaN, or if the precision `p` is incorrect.
+    pub fn normalize_angle(
+        &self,
+        range: AngleRange,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Self {
+        let ret = match &self.inner {
+            Flavor::Value(v) => {
+                Self::result_to_ext(v.normalize_angle(range, p, rm, cc), v.is_zero(), true)
+            }
+            Flavor::Inf(_) => Self::nan(Some(Error::InvalidArgument)),
+            Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("normalize_angle", &[self])
+    }
+
+    /// Reduces an angle `self` in radians modulo pi/2 with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// Returns the reduced argument `r` and the quadrant `q` such that `self = k*pi/2 + r`, where `k` is the integer closest to `self / (pi/2)`,
+    /// and `q = k mod 4` is in the range from 0 to 3. The reduced argument is in the range [-pi/4, pi/4] up to the rounding error of `k`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN and the quadrant 0 if `self` is Inf or NaN, or if the precision `p` is incorrect.
+    pub fn rem_pio2(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> (Self, usize) {
+        let (ret, q) = match &self.inner {
+            Flavor::Value(v) => match v.rem_pio2(p, rm, cc) {
+                Ok((r, q)) => (r.into(), q),
+                Err(e) => (Self::nan(Some(e)), 0),
+            },
+            Flavor::Inf(_) => (Self::nan(Some(Error::InvalidArgument)), 0),
+            Flavor::NaN(err) => (Self::nan(*err), 0),
+        };
+
+        (ret.tagged("rem_pio2", &[self]), q)
     }
 
     /// Computes the hyperbolic tangent of a number with precision `p`. The result is rounded using the rounding mode `rm`.
@@ -767,11 +1711,186 @@ impl BigFloat {
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn tanh(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
-        match &self.inner {
+        let ret = match &self.inner {
             Flavor::Value(v) => Self::result_to_ext(v.tanh(p, rm, cc), v.is_zero(), true),
             Flavor::Inf(s) => Self::from_i8(s.to_int(), p),
             Flavor::NaN(err) => Self::nan(*err),
-        }
+        };
+
+        ret.tagged("tanh", &[self])
+    }
+
+    /// Computes the gamma function of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns Inf with the sign of zero if `self` is zero, and NaN if `self` is a negative integer,
+    /// or negative infinity, or if the precision `p` is incorrect.
+    pub fn gamma(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        let ret = match &self.inner {
+            Flavor::Value(v) => Self::result_to_ext(v.gamma(p, rm, cc), false, v.is_positive()),
+            Flavor::Inf(s) => {
+                if s.is_positive() {
+                    INF_POS
+                } else {
+                    NAN
+                }
+            }
+            Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("gamma", &[self])
+    }
+
+    /// Computes the Bessel function of the first kind of order `n` of a number with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns zero if `self` is infinity, unless `self` is negative infinity and `n` is not an integer.
+    /// The function returns NaN if `self` is negative and `n` is not an integer, if `self` is zero and `n` is a negative number which is not an integer,
+    /// if `n` is infinity, or if the precision `p` is incorrect.
+    pub fn besselj(&self, n: &Self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        let ret = match &self.inner {
+            Flavor::Value(v1) => match &n.inner {
+                Flavor::Value(v2) => Self::result_to_ext(v1.besselj(v2, p, rm, cc), false, true),
+                Flavor::Inf(_) => NAN,
+                Flavor::NaN(err) => Self::nan(*err),
+            },
+            Flavor::Inf(s1) => match &n.inner {
+                Flavor::Value(v2) => {
+                    if s1.is_positive() || v2.is_int() {
+                        Self::new(p)
+                    } else {
+                        NAN
+                    }
+                }
+                Flavor::Inf(_) => NAN,
+                Flavor::NaN(err) => Self::nan(*err),
+            },
+            Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("besselj", &[self, n])
+    }
+
+    /// Computes the Bessel function of the second kind of order `n` of a number with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns zero if `self` is positive infinity.
+    /// The function returns negative infinity if `self` is zero and `n` is not negative,
+    /// and infinity with the sign of (-1)^(n+1) if `self` is zero and `n` is a negative integer.
+    /// The function returns NaN if `self` is negative, if `self` is zero and `n` is a negative number which is not an integer,
+    /// if `n` is infinity, or if the precision `p` is incorrect.
+    pub fn bessely(&self, n: &Self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        let ret = match &self.inner {
+            Flavor::Value(v1) => match &n.inner {
+                Flavor::Value(v2) => {
+                    if !v1.is_zero() {
+                        Self::result_to_ext(v1.bessely(v2, p, rm, cc), false, true)
+                    } else if !v2.is_negative() {
+                        INF_NEG
+                    } else if v2.is_int() {
+                        if v2.is_odd_int() {
+                            INF_POS
+                        } else {
+                            INF_NEG
+                        }
+                    } else {
+                        NAN
+                    }
+                }
+                Flavor::Inf(_) => NAN,
+                Flavor::NaN(err) => Self::nan(*err),
+            },
+            Flavor::Inf(s1) => match &n.inner {
+                Flavor::Value(_) => {
+                    if s1.is_positive() {
+                        Self::new(p)
+                    } else {
+                        NAN
+                    }
+                }
+                Flavor::Inf(_) => NAN,
+                Flavor::NaN(err) => Self::nan(*err),
+            },
+            Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("bessely", &[self, n])
+    }
+
+    /// Computes the modified Bessel function of the first kind of order `n` of a number with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns positive infinity if `self` is positive infinity,
+    /// and infinity with the sign of (-1)^n if `self` is negative infinity and `n` is an integer.
+    /// The function returns NaN if `self` is negative and `n` is not an integer, if `self` is zero and `n` is a negative number which is not an integer,
+    /// if `n` is infinity, or if the precision `p` is incorrect.
+    pub fn besseli(&self, n: &Self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        let ret = match &self.inner {
+            Flavor::Value(v1) => match &n.inner {
+                Flavor::Value(v2) => Self::result_to_ext(v1.besseli(v2, p, rm, cc), false, true),
+                Flavor::Inf(_) => NAN,
+                Flavor::NaN(err) => Self::nan(*err),
+            },
+            Flavor::Inf(s1) => match &n.inner {
+                Flavor::Value(v2) => {
+                    if s1.is_positive() {
+                        INF_POS
+                    } else if v2.is_int() {
+                        if !v2.is_zero() && v2.is_odd_int() {
+                            INF_NEG
+                        } else {
+                            INF_POS
+                        }
+                    } else {
+                        NAN
+                    }
+                }
+                Flavor::Inf(_) => NAN,
+                Flavor::NaN(err) => Self::nan(*err),
+            },
+            Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("besseli", &[self, n])
+    }
+
+    /// Computes the modified Bessel function of the second kind of order `n` of a number with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns zero if `self` is positive infinity, and positive infinity if `self` is zero.
+    /// The function returns NaN if `self` is negative, if `n` is infinity, or if the precision `p` is incorrect.
+    pub fn besselk(&self, n: &Self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Self {
+        let ret = match &self.inner {
+            Flavor::Value(v1) => match &n.inner {
+                Flavor::Value(v2) => {
+                    if v1.is_zero() {
+                        INF_POS
+                    } else {
+                        Self::result_to_ext(v1.besselk(v2, p, rm, cc), false, true)
+                    }
+                }
+                Flavor::Inf(_) => NAN,
+                Flavor::NaN(err) => Self::nan(*err),
+            },
+            Flavor::Inf(s1) => match &n.inner {
+                Flavor::Value(_) => {
+                    if s1.is_positive() {
+                        Self::new(p)
+                    } else {
+                        NAN
+                    }
+                }
+                Flavor::Inf(_) => NAN,
+                Flavor::NaN(err) => Self::nan(*err),
+            },
+            Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("besselk", &[self, n])
     }
 
     fn half_pi(
@@ -788,7 +1907,39 @@ impl BigFloat {
         Ok(half_pi)
     }
 
-    fn result_to_ext(
+    // Sets the provenance tag of the result `self` of the operation `op` with arguments `args`.
+    // NaN or Inf keeps the tag of an argument which is also NaN or Inf.
+    #[cfg(feature = "debug")]
+    pub(crate) fn tagged(mut self, op: &'static str, args: &[&Self]) -> Self {
+        let inherited = args.iter().find_map(|a| {
+            if (self.is_nan() && a.is_nan()) || (self.is_inf() && a.is_inf()) {
+                a.tag
+            } else {
+                None
+            }
+        });
+
+        self.tag = Some(inherited.unwrap_or_else(|| Tag::new(op)));
+        self
+    }
+
+    #[cfg(not(feature = "debug"))]
+    #[inline]
+    pub(crate) fn tagged(self, _op: &'static str, _args: &[&Self]) -> Self {
+        self
+    }
+
+    // Assigns the sequence number `seq` of an evaluation of a context to the provenance tag of `self`,
+    // unless the tag already has a sequence number.
+    #[cfg(feature = "debug")]
+    pub(crate) fn sequenced(mut self, seq: usize) -> Self {
+        if let Some(tag) = self.tag.as_mut() {
+            tag.set_seq(seq);
+        }
+        self
+    }
+
+    pub(crate) fn result_to_ext(
         res: Result<BigFloatNumber, Error>,
         is_dividend_zero: bool,
         is_same_sign: bool,
@@ -816,28 +1967,164 @@ impl BigFloat {
             },
             Ok(v) => BigFloat {
                 inner: Flavor::Value(v),
+                #[cfg(feature = "debug")]
+                tag: None,
             },
         }
     }
 
     /// Returns the exponent of `self`, or None if `self` is Inf or NaN.
     pub fn exponent(&self) -> Option<Exponent> {
-        match &self.inner {
-            Flavor::Value(v) => Some(v.exponent()),
-            _ => None,
-        }
+        self.as_number().map(|v| v.exponent())
     }
 
     /// Returns the number of significant bits used in the mantissa, or None if `self` is Inf or NaN.
     /// Normal numbers use all bits of the mantissa.
     /// Subnormal numbers use fewer bits than the mantissa can hold.
     pub fn precision(&self) -> Option<usize> {
-        match &self.inner {
-            Flavor::Value(v) => Some(v.precision()),
+        self.as_number().map(|v| v.precision())
+    }
+
+    /// Returns the unit in the last place of `self` at precision `p`, i.e. `2^(e - p)`, where `e` is the exponent of `self`.
+    /// The exponent of zero is considered to be EXPONENT_MIN, so the result is the same as for subnormal numbers.
+    /// The result is positive and exact, or positive infinity if `self` is Inf.
+    /// The precision of the result is `p` rounded upwards to the word size.
+    /// The function returns NaN if `self` is NaN, or if the precision `p` is incorrect.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::BigFloat;
+    /// let x = BigFloat::from_f64(1.5, 64);
+    /// assert_eq!(x.ulp(53), BigFloat::from_f64(f64::EPSILON, 64));
+    /// ```
+    pub fn ulp(&self, p: usize) -> Self {
+        let ret = match &self.inner {
+            Flavor::Value(v) => Self::result_to_ext(v.ulp(p), false, true),
+            Flavor::Inf(_) => INF_POS,
+            Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("ulp", &[self])
+    }
+
+    /// Returns the distance between `self` and `d2` in units in the last place,
+    /// i.e. the number of steps between `self` and `d2` in the ordered set of numbers
+    /// with precision equal to the larger of the mantissa lengths of `self` and `d2`.
+    /// Positive and negative zero are at the distance 0, and so are infinities of the same sign.
+    /// The distance is saturated at u128::MAX.
+    /// The function returns None if any of the values is NaN, if only one of the values is Inf or the infinities have different signs,
+    /// or if memory allocation fails.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, RoundingMode};
+    /// let x = BigFloat::from_u8(1, 128);
+    /// let up = x.add(&x.ulp(128), 128, RoundingMode::None);
+    /// assert_eq!(x.ulp_diff(&up), Some(1));
+    /// assert_eq!(x.ulp_diff(&BigFloat::from_u8(2, 128)), Some(1 << 127));
+    /// ```
+    pub fn ulp_diff(&self, d2: &Self) -> Option<u128> {
+        match (&self.inner, &d2.inner) {
+            (Flavor::Value(v1), Flavor::Value(v2)) => v1.ulp_diff(v2).ok(),
+            (Flavor::Inf(s1), Flavor::Inf(s2)) if s1 == s2 => Some(0),
             _ => None,
         }
     }
 
+    /// Returns the value of `self` as f64 if the value can be represented in f64 exactly, otherwise returns None.
+    /// Inf is converted to the infinity of the same sign. The function returns None if `self` is NaN.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::BigFloat;
+    /// assert_eq!(BigFloat::from_f64(0.375, 128).to_f64_exact(), Some(0.375));
+    /// assert_eq!(BigFloat::from_u64(u64::MAX, 128).to_f64_exact(), None);
+    /// ```
+    pub fn to_f64_exact(&self) -> Option<f64> {
+        match &self.inner {
+            Flavor::Value(v) => v.to_f64_exact(),
+            Flavor::Inf(Sign::Pos) => Some(f64::INFINITY),
+            Flavor::Inf(Sign::Neg) => Some(f64::NEG_INFINITY),
+            Flavor::NaN(_) => None,
+        }
+    }
+
+    /// Converts `self` to f64 using the rounding mode `rm`. `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the conversion is inexact.
+    /// Inf is converted to the infinity of the same sign, and NaN is converted to NaN.
+    /// The function returns NaN if memory allocation fails.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, RoundingMode};
+    /// let x = BigFloat::from_f64(1.0, 128).div(&BigFloat::from_f64(3.0, 128), 128, RoundingMode::ToEven);
+    /// let (lo, inexact) = x.to_f64_round(RoundingMode::Down);
+    /// let (hi, _) = x.to_f64_round(RoundingMode::Up);
+    /// assert!(inexact && lo < hi);
+    /// assert_eq!(BigFloat::from_f64(0.375, 128).to_f64_round(RoundingMode::Up), (0.375, false));
+    /// ```
+    pub fn to_f64_round(&self, rm: RoundingMode) -> (f64, bool) {
+        match &self.inner {
+            Flavor::Value(v) => v.to_f64_round(rm).unwrap_or((f64::NAN, true)),
+            Flavor::Inf(Sign::Pos) => (f64::INFINITY, false),
+            Flavor::Inf(Sign::Neg) => (f64::NEG_INFINITY, false),
+            Flavor::NaN(_) => (f64::NAN, false),
+        }
+    }
+
+    /// Converts `self` to f32 using the rounding mode `rm`. `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the conversion is inexact.
+    /// Inf is converted to the infinity of the same sign, and NaN is converted to NaN.
+    /// The function returns NaN if memory allocation fails.
+    pub fn to_f32_round(&self, rm: RoundingMode) -> (f32, bool) {
+        match &self.inner {
+            Flavor::Value(v) => v.to_f32_round(rm).unwrap_or((f32::NAN, true)),
+            Flavor::Inf(Sign::Pos) => (f32::INFINITY, false),
+            Flavor::Inf(Sign::Neg) => (f32::NEG_INFINITY, false),
+            Flavor::NaN(_) => (f32::NAN, false),
+        }
+    }
+
+    /// Returns the value of `self` as u128 if `self` is an integer in the range of u128, otherwise returns None.
+    /// The function returns None if `self` is Inf or NaN.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::BigFloat;
+    /// assert_eq!(BigFloat::from_f64(1024.0, 128).to_u128(), Some(1024));
+    /// assert_eq!(BigFloat::from_f64(1.5, 128).to_u128(), None);
+    /// assert_eq!(BigFloat::from_f64(-1.0, 128).to_u128(), None);
+    /// ```
+    pub fn to_u128(&self) -> Option<u128> {
+        match &self.inner {
+            Flavor::Value(v) => v.to_u128(),
+            Flavor::Inf(_) | Flavor::NaN(_) => None,
+        }
+    }
+
+    /// Returns the value of `self` as i128 if `self` is an integer in the range of i128, otherwise returns None.
+    /// The function returns None if `self` is Inf or NaN.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::BigFloat;
+    /// assert_eq!(BigFloat::from_f64(-1024.0, 128).to_i128(), Some(-1024));
+    /// assert_eq!(BigFloat::from_f64(-0.5, 128).to_i128(), None);
+    /// assert_eq!(BigFloat::from_u128(u128::MAX, 128).to_i128(), None);
+    /// ```
+    pub fn to_i128(&self) -> Option<i128> {
+        match &self.inner {
+            Flavor::Value(v) => v.to_i128(),
+            Flavor::Inf(_) | Flavor::NaN(_) => None,
+        }
+    }
+
     /// Returns the maximum value for the specified precision `p`: all bits of the mantissa are set to 1,
     /// the exponent has the maximum possible value, and the sign is positive.
     /// Precision is rounded upwards to the word size.
@@ -888,10 +2175,38 @@ impl BigFloat {
     /// numbers of significant bits in the mantissa, sign, exponent,
     /// and a bool value which specify whether the number is inexact.
     pub fn as_raw_parts(&self) -> Option<(&[Word], usize, Sign, Exponent, bool)> {
-        if let Flavor::Value(v) = &self.inner {
-            Some(v.as_raw_parts())
-        } else {
-            None
+        self.as_number().map(|v| v.as_raw_parts())
+    }
+
+    /// Returns a reference to the underlying number without copying, or None if `self` is Inf or NaN.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use astro_float_num::{BigFloat, BigFloatNumber};
+    ///
+    /// let x = BigFloat::from_word(3, 128);
+    /// let n: &BigFloatNumber = x.as_number().unwrap();
+    /// assert_eq!(n.mantissa_max_bit_len(), 128);
+    ///
+    /// // moving between the layers does not copy the mantissa
+    /// let n = BigFloatNumber::try_from(x).unwrap();
+    /// assert_eq!(BigFloat::from(n), BigFloat::from_word(3, 128));
+    /// ```
+    #[inline]
+    pub fn as_number(&self) -> Option<&BigFloatNumber> {
+        match &self.inner {
+            Flavor::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the underlying number without copying, or None if `self` is Inf or NaN.
+    #[inline]
+    pub fn as_number_mut(&mut self) -> Option<&mut BigFloatNumber> {
+        match &mut self.inner {
+            Flavor::Value(v) => Some(v),
+            _ => None,
         }
     }
 
@@ -929,6 +2244,74 @@ impl BigFloat {
         Self::result_to_ext(BigFloatNumber::from_words(m, s, e), false, true)
     }
 
+    /// Constructs a number from the slice of words like `from_words`, but instead of normalizing the mantissa
+    /// validates that the words and the exponent are the exact representation of the number, as returned by `as_raw_parts`:
+    ///
+    ///  - `m` is the mantissa.
+    ///  - `s` is the sign.
+    ///  - `e` is the exponent.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: `m` is empty, or the size of `m` is larger than isize::MAX / 2 + EXPONENT_MIN;
+    ///    `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX;
+    ///    `m` is zero, but `e` is not 0;
+    ///    the most significant bit of `m` is not set, but `m` is not zero, and `e` is not EXPONENT_MIN.
+    pub fn from_words_checked(m: &[Word], s: Sign, e: Exponent) -> Result<Self, Error> {
+        BigFloatNumber::from_words_checked(m, s, e).map(|v| v.into())
+    }
+
+    /// Returns the mantissa of `self` in 64-bit words with the least significant word first, the sign, and the exponent,
+    /// or None if `self` is Inf or NaN.
+    /// The value of `self` is `0.m * 2^e`, where the most significant bit of `m` is the most significant bit of the last word.
+    /// The result does not depend on the word size, which makes it suitable for exchanging numbers with other libraries,
+    /// e.g. GMP or MPFR with 64-bit limbs.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, Sign};
+    /// let n = BigFloat::from_f64(-1.5, 128);
+    /// let (m, s, e) = n.to_words_le().unwrap();
+    /// assert_eq!(m, [0, 0xC000000000000000]);
+    /// assert_eq!((s, e), (Sign::Neg, 1));
+    /// assert_eq!(BigFloat::from_words_le(&m, s, e), n);
+    /// ```
+    pub fn to_words_le(&self) -> Option<(Vec<u64>, Sign, Exponent)> {
+        self.as_raw_parts()
+            .map(|(m, _, s, e, _)| (words_to_u64(m), s, e))
+    }
+
+    /// Returns the mantissa of `self` in 64-bit words with the most significant word first, the sign, and the exponent,
+    /// or None if `self` is Inf or NaN.
+    /// The value of `self` is `0.m * 2^e`, where the most significant bit of `m` is the most significant bit of the first word.
+    pub fn to_words_be(&self) -> Option<(Vec<u64>, Sign, Exponent)> {
+        self.to_words_le().map(|(mut m, s, e)| {
+            m.reverse();
+            (m, s, e)
+        })
+    }
+
+    /// Constructs a number from the mantissa `m` in 64-bit words with the least significant word first, the sign `s`,
+    /// and the exponent `e`. The value of the number is `0.m * 2^e`.
+    /// The mantissa is normalized, and the precision of the number is the number of bits in `m`.
+    ///
+    /// The function returns NaN if `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
+    pub fn from_words_le(m: &[u64], s: Sign, e: Exponent) -> Self {
+        Self::from_words(&u64_to_words(m), s, e)
+    }
+
+    /// Constructs a number from the mantissa `m` in 64-bit words with the most significant word first, the sign `s`,
+    /// and the exponent `e`. The value of the number is `0.m * 2^e`.
+    /// The mantissa is normalized, and the precision of the number is the number of bits in `m`.
+    ///
+    /// The function returns NaN if `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
+    pub fn from_words_be(m: &[u64], s: Sign, e: Exponent) -> Self {
+        let m: Vec<u64> = m.iter().rev().copied().collect();
+        Self::from_words_le(&m, s, e)
+    }
+
     /// Returns the sign of `self`, or None if `self` is NaN.
     pub fn sign(&self) -> Option<Sign> {
         match &self.inner {
@@ -963,18 +2346,71 @@ impl BigFloat {
     /// assert_eq!(n.precision(), Some(2));
     /// ```
     pub fn set_exponent(&mut self, e: Exponent) {
-        if let Flavor::Value(v) = &mut self.inner {
+        if let Some(v) = self.as_number_mut() {
             v.set_exponent(e)
         }
     }
 
+    /// Decomposes `self` into a fraction and an exponent, such that `self = fraction * 2^exponent`,
+    /// and the absolute value of the fraction is in the range [0.5, 1).
+    /// The fraction of a subnormal number is normalized, so the exponent can be smaller than EXPONENT_MIN.
+    /// For zero, Inf, and NaN the fraction is a copy of `self` and the exponent is 0.
+    /// The fraction is NaN if memory allocation fails.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::BigFloat;
+    /// let (f, e) = BigFloat::from_u8(12, 64).frexp();
+    /// assert_eq!(f, BigFloat::from_f64(0.75, 64));
+    /// assert_eq!(e, 4);
+    /// ```
+    pub fn frexp(&self) -> (Self, isize) {
+        match &self.inner {
+            Flavor::Value(v) => match v.frexp() {
+                Ok((f, e)) => (f.into(), e),
+                Err(e) => (Self::nan(Some(e)), 0),
+            },
+            _ => (self.clone(), 0),
+        }
+    }
+
+    /// Returns `self` multiplied by 2 to the power of `e`, with precision `p` rounded according to `rm`.
+    /// Unlike `set_exponent`, the exponent of the result is not clamped: the result is Inf if it is too large,
+    /// and a subnormal number or zero if it is too small, in the same way as the result of multiplication.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, RoundingMode};
+    /// let (f, e) = BigFloat::min_positive(64).frexp();
+    /// assert_eq!(f.ldexp(e, 64, RoundingMode::None), BigFloat::min_positive(64));
+    /// assert!(f.ldexp(isize::MAX, 64, RoundingMode::None).is_inf_pos());
+    /// ```
+    pub fn ldexp(&self, e: isize, p: usize, rm: RoundingMode) -> Self {
+        let ret = match &self.inner {
+            Flavor::Value(v) => Self::result_to_ext(v.ldexp(e, p, rm), false, true),
+            Flavor::Inf(_) => self.clone(),
+            Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        ret.tagged("ldexp", &[self])
+    }
+
+    /// Returns `self` multiplied by 2 to the power of `n` rounded according to `rm`, keeping the mantissa length of `self`.
+    /// The result is the same as the result of `ldexp` with the precision equal to the mantissa length of `self`.
+    pub fn scalbn(&self, n: isize, rm: RoundingMode) -> Self {
+        match &self.inner {
+            Flavor::Value(v) => self.ldexp(n, v.mantissa_max_bit_len(), rm),
+            _ => self.ldexp(n, 0, rm),
+        }
+    }
+
     /// Returns the maximum mantissa length of `self` in bits regardless of whether `self` is normal or subnormal.
     pub fn mantissa_max_bit_len(&self) -> Option<usize> {
-        if let Flavor::Value(v) = &self.inner {
-            Some(v.mantissa_max_bit_len())
-        } else {
-            None
-        }
+        self.as_number().map(|v| v.mantissa_max_bit_len())
     }
 
     /// Sets the precision of `self` to `p`.
@@ -985,10 +2421,46 @@ impl BigFloat {
     ///  - MemoryAllocation: failed to allocate memory for mantissa.
     ///  - InvalidArgument: the precision is incorrect.
     pub fn set_precision(&mut self, p: usize, rm: RoundingMode) -> Result<(), Error> {
-        if let Flavor::Value(v) = &mut self.inner {
-            v.set_precision(p, rm)
-        } else {
-            Ok(())
+        match self.as_number_mut() {
+            Some(v) => v.set_precision(p, rm),
+            None => Ok(()),
+        }
+    }
+
+    /// Reduces the memory used by `self`. If the precision of `self` is larger than `p`,
+    /// the number is rounded to precision `p` using the rounding mode `rm`.
+    /// Then the least significant words of the mantissa containing zeroes are removed without loss of precision,
+    /// and unused memory is released.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: rounding causes exponent overflow.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn compress_to(&mut self, p: usize, rm: RoundingMode) -> Result<(), Error> {
+        match self.as_number_mut() {
+            Some(v) => v.compress_to(p, rm),
+            None => Ok(()),
+        }
+    }
+
+    /// Rounds `self` to precision `p` using the rounding mode `rm`, given `self` was computed with `RoundingMode::None`.
+    /// The inexact flag of `self` is treated as a sticky bit located below the least significant bit of the mantissa.
+    /// The result of the basic arithmetic operation on exact numbers computed with `RoundingMode::None` and then rounded
+    /// with this function is correctly rounded if `p` is smaller than the precision of `self`, or if `p` equals the precision of `self`
+    /// and `rm` is not rounding to nearest.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: rounding causes exponent overflow.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn reround(&mut self, p: usize, rm: RoundingMode) -> Result<(), Error> {
+        match self.as_number_mut() {
+            Some(v) => v.reround(p, rm),
+            None => Ok(()),
         }
     }
 
@@ -997,7 +2469,7 @@ impl BigFloat {
     /// Precision is rounded upwards to the word size.
     /// The function returns NaN if the precision `p` is incorrect.
     pub fn reciprocal(&self, p: usize, rm: RoundingMode) -> Self {
-        match &self.inner {
+        let ret = match &self.inner {
             Flavor::Value(v) => Self::result_to_ext(v.reciprocal(p, rm), false, v.is_positive()),
             Flavor::Inf(s) => {
                 let mut ret = Self::new(p);
@@ -1005,7 +2477,9 @@ impl BigFloat {
                 ret
             }
             Flavor::NaN(err) => Self::nan(*err),
-        }
+        };
+
+        ret.tagged("reciprocal", &[self])
     }
 
     /// Sets the sign of `self`.
@@ -1062,7 +2536,7 @@ impl BigFloat {
     ///  - MemoryAllocation: failed to allocate memory for mantissa.
     ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
     ///  - InvalidArgument: the precision is incorrect, or `digits` contains unacceptable digits for given radix,
-    /// or when `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
+    ///    or when `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
     pub fn convert_from_radix(
         sign: Sign,
         digits: &[u8],
@@ -1143,6 +2617,10 @@ impl BigFloat {
         if let Flavor::Value(v) = &mut self.inner {
             v.try_set_precision(p, rm, s).unwrap_or_else(|e| {
                 self.inner = Flavor::NaN(Some(e));
+                #[cfg(feature = "debug")]
+                {
+                    self.tag = Some(Tag::new("try_set_precision"));
+                }
                 true
             })
         } else {
@@ -1153,7 +2631,8 @@ impl BigFloat {
 
 impl Clone for BigFloat {
     fn clone(&self) -> Self {
-        match &self.inner {
+        #[allow(unused_mut)]
+        let mut ret = match &self.inner {
             Flavor::Value(v) => Self::result_to_ext(v.clone(), false, true),
             Flavor::Inf(s) => {
                 if s.is_positive() {
@@ -1163,7 +2642,14 @@ impl Clone for BigFloat {
                 }
             }
             Flavor::NaN(err) => Self::nan(*err),
+        };
+
+        #[cfg(feature = "debug")]
+        {
+            ret.tag = self.tag;
         }
+
+        ret
     }
 }
 
@@ -1172,11 +2658,13 @@ macro_rules! gen_wrapper_arg {
     ($comment:literal, $fname:ident, $ret:ty, $pos_inf:block, $neg_inf:block, $($arg:ident, $arg_type:ty),*) => {
         #[doc=$comment]
         pub fn $fname(&self$(,$arg: $arg_type)*) -> $ret {
-            match &self.inner {
+            let ret = match &self.inner {
                 Flavor::Value(v) => Self::result_to_ext(v.$fname($($arg,)*), v.is_zero(), true),
                 Flavor::Inf(s) => if s.is_positive() $pos_inf else $neg_inf,
                 Flavor::NaN(err) => Self::nan(*err),
-            }
+            };
+
+            ret.tagged(stringify!($fname), &[self])
         }
     };
 }
@@ -1186,13 +2674,15 @@ macro_rules! gen_wrapper_arg_rm {
     ($comment:literal, $fname:ident, $ret:ty, $pos_inf:block, $neg_inf:block, $($arg:ident, $arg_type:ty),*) => {
         #[doc=$comment]
         pub fn $fname(&self$(,$arg: $arg_type)*, rm: RoundingMode) -> $ret {
-            match &self.inner {
+            let ret = match &self.inner {
                 Flavor::Value(v) => {
                     Self::result_to_ext(v.$fname($($arg,)* rm), v.is_zero(), true)
                 },
                 Flavor::Inf(s) => if s.is_positive() $pos_inf else $neg_inf,
                 Flavor::NaN(err) => Self::nan(*err),
-            }
+            };
+
+            ret.tagged(stringify!($fname), &[self])
         }
     };
 }
@@ -1202,13 +2692,18 @@ macro_rules! gen_wrapper_arg_rm_cc {
     ($comment:literal, $fname:ident, $ret:ty, $pos_inf:block, $neg_inf:block, $($arg:ident, $arg_type:ty),*) => {
         #[doc=$comment]
         pub fn $fname(&self$(,$arg: $arg_type)*, rm: RoundingMode, cc: &mut Consts) -> $ret {
-            match &self.inner {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::trace_span!(stringify!($fname), $($arg,)* ?rm).entered();
+
+            let ret = match &self.inner {
                 Flavor::Value(v) => {
                     Self::result_to_ext(v.$fname($($arg,)* rm, cc), v.is_zero(), true)
                 },
                 Flavor::Inf(s) => if s.is_positive() $pos_inf else $neg_inf,
                 Flavor::NaN(err) => Self::nan(*err),
-            }
+            };
+
+            ret.tagged(stringify!($fname), &[self])
         }
     };
 }
@@ -1217,16 +2712,21 @@ macro_rules! gen_wrapper_log {
     ($comment:literal, $fname:ident, $ret:ty, $pos_inf:block, $neg_inf:block, $($arg:ident, $arg_type:ty),*) => {
         #[doc=$comment]
         pub fn $fname(&self$(,$arg: $arg_type)*, rm: RoundingMode, cc: &mut Consts) -> $ret {
-            match &self.inner {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::trace_span!(stringify!($fname), $($arg,)* ?rm).entered();
+
+            let ret = match &self.inner {
                 Flavor::Value(v) => {
                     if v.is_zero() {
-                        return INF_NEG;
+                        return INF_NEG.tagged(stringify!($fname), &[self]);
                     }
                     Self::result_to_ext(v.$fname($($arg,)* rm, cc), v.is_zero(), true)
                 },
                 Flavor::Inf(s) => if s.is_positive() $pos_inf else $neg_inf,
                 Flavor::NaN(err) => Self::nan(*err),
-            }
+            };
+
+            ret.tagged(stringify!($fname), &[self])
         }
     };
 }
@@ -1271,6 +2771,21 @@ impl BigFloat {
         n,
         usize
     );
+    gen_wrapper_arg!(
+        "Returns `self` rounded to an integer toward zero. Unlike `int`, the result is Inf if `self` is Inf.",
+        trunc,
+        Self,
+        { INF_POS },
+        { INF_NEG },
+    );
+    gen_wrapper_arg_rm!(
+        "Returns `self` rounded to an integer using rounding mode `rm`. `RoundingMode::None` rounds toward zero.
+        The result has the same precision as `self`, and its inexact flag is set if `self` is not an integer.",
+        round_int,
+        Self,
+        { INF_POS },
+        { INF_NEG },
+    );
     gen_wrapper_arg_rm!(
         "Computes the square root of a number with precision `p`. The result is rounded using the rounding mode `rm`.
         Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
@@ -1335,7 +2850,43 @@ impl BigFloat {
         p,
         usize
     );
+    gen_wrapper_arg_rm_cc!(
+        "Computes `e` to the power of `self` minus one with precision `p`. The result is rounded using the rounding mode `rm`.
+        The result is accurate when `self` is close to zero.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        expm1,
+        Self,
+        { INF_POS },
+        { Self::from_i8(-1, p) },
+        p,
+        usize
+    );
 
+    gen_wrapper_arg_rm_cc!(
+        "Converts an angle `self` in radians to degrees with precision `p`. The result is rounded using the rounding mode `rm`.
+        The result `self * 180 / pi` is computed using a single rounding.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        to_degrees,
+        Self,
+        { INF_POS },
+        { INF_NEG },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Converts an angle `self` in degrees to radians with precision `p`. The result is rounded using the rounding mode `rm`.
+        The result `self * pi / 180` is computed using a single rounding.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        to_radians,
+        Self,
+        { INF_POS },
+        { INF_NEG },
+        p,
+        usize
+    );
     gen_wrapper_arg_rm_cc!(
         "Computes the sine of a number with precision `p`. The result is rounded using the rounding mode `rm`.
         This function requires constants cache `cc` for computing the result.
@@ -1369,6 +2920,42 @@ impl BigFloat {
         p,
         usize
     );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the sine of `pi * self` with precision `p`. The result is rounded using the rounding mode `rm`.
+        The argument is reduced exactly, so the result is accurate for large arguments. Integer and half-integer arguments give exact results.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        sin_pi,
+        Self,
+        { NAN },
+        { NAN },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the cosine of `pi * self` with precision `p`. The result is rounded using the rounding mode `rm`.
+        The argument is reduced exactly, so the result is accurate for large arguments. Integer and half-integer arguments give exact results.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        cos_pi,
+        Self,
+        { NAN },
+        { NAN },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the tangent of `pi * self` with precision `p`. The result is rounded using the rounding mode `rm`.
+        The argument is reduced exactly, so the result is accurate for large arguments. Integer and quarter-integer arguments give exact results, and half-integer arguments give infinity.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        tan_pi,
+        Self,
+        { NAN },
+        { NAN },
+        p,
+        usize
+    );
     gen_wrapper_arg_rm_cc!(
         "Computes the arcsine of a number with precision `p`. The result is rounded using the rounding mode `rm`.
         This function requires constants cache `cc` for computing the result.
@@ -1414,38 +3001,301 @@ impl BigFloat {
         p,
         usize
     );
-    gen_wrapper_arg_rm_cc!(
-        "Computes the hyperbolic arcsine of a number with precision `p`. The result is rounded using the rounding mode `rm`.
-        This function requires constants cache `cc` for computing the result.
-        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
-        asinh,
-        Self,
-        { INF_POS },
-        { INF_NEG },
-        p,
-        usize
+    gen_wrapper_arg_rm_cc!(
+        "Computes the hyperbolic arcsine of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        asinh,
+        Self,
+        { INF_POS },
+        { INF_NEG },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the hyperbolic arccosine of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        acosh,
+        Self,
+        { INF_POS },
+        { NAN },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the hyperbolic arctangent of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        atanh,
+        Self,
+        { NAN },
+        { NAN },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the hyperbolic secant of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        sech,
+        Self,
+        { Self::new(p) },
+        { Self::new(p) },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the hyperbolic cosecant of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        The result is infinity with the sign of zero if `self` is zero.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        csch,
+        Self,
+        { Self::new(p) },
+        { Self::new(p).neg() },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the hyperbolic cotangent of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        The result is infinity with the sign of zero if `self` is zero.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        coth,
+        Self,
+        { Self::from_word(1, p) },
+        { Self::from_i8(-1, p) },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the hyperbolic arcsecant of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        The result is positive infinity if `self` is zero, and NaN if `self` is negative or larger than 1.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        asech,
+        Self,
+        { NAN },
+        { NAN },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the hyperbolic arccosecant of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        The result is infinity with the sign of zero if `self` is zero.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        acsch,
+        Self,
+        { Self::new(p) },
+        { Self::new(p).neg() },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the hyperbolic arccotangent of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        The result is infinity with the sign of `self` if the absolute value of `self` is 1, and NaN if it is less than 1.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        acoth,
+        Self,
+        { Self::new(p) },
+        { Self::new(p).neg() },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the error function of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        erf,
+        Self,
+        { Self::from_word(1, p) },
+        { Self::from_i8(-1, p) },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the complementary error function of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        erfc,
+        Self,
+        { Self::new(p) },
+        { Self::from_word(2, p) },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the inverse error function of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        erfinv,
+        Self,
+        { NAN },
+        { NAN },
+        p,
+        usize
+    );
+    gen_wrapper_arg_rm_cc!(
+        "Computes the inverse complementary error function of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+        This function requires constants cache `cc` for computing the result.
+        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
+        erfcinv,
+        Self,
+        { NAN },
+        { NAN },
+        p,
+        usize
+    );
+}
+
+macro_rules! gen_wrapper_many {
+    ($comment:literal, $fname:ident, $fname_many:ident, $preload:expr) => {
+        #[doc=$comment]
+        #[doc="This function requires constants cache `cc` for computing the results."]
+        #[doc="Precision is rounded upwards to the word size. The function returns NaN in place of each result if the precision `p` is incorrect."]
+        pub fn $fname_many(values: &[Self], p: usize, rm: RoundingMode, cc: &mut Consts) -> Vec<Self> {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::trace_span!(stringify!($fname_many), n = values.len(), p, ?rm).entered();
+
+            Self::eval_many(values, p, cc, $preload, |v, cc| v.$fname(p, rm, cc))
+        }
+    };
+}
+
+// Computes the constant used by a function for the given working precision and the largest exponent of the arguments.
+type Preload = fn(&mut Consts, usize, usize) -> Result<BigFloatNumber, Error>;
+
+// Batch evaluation: the constant used by a function is computed once with the precision sufficient for all of the values,
+// and the memory of the temporary numbers is reused across the evaluations.
+impl BigFloat {
+    fn eval_many(
+        values: &[Self],
+        p: usize,
+        cc: &mut Consts,
+        preload: Option<Preload>,
+        mut f: impl FnMut(&Self, &mut Consts) -> Self,
+    ) -> Vec<Self> {
+        if let Some(preload) = preload {
+            // the working precision of the largest value; the errors are reported by the evaluations.
+            let p_arg = values
+                .iter()
+                .filter_map(|v| v.mantissa_max_bit_len())
+                .max()
+                .unwrap_or(0);
+            let e_arg = values
+                .iter()
+                .filter_map(|v| v.exponent())
+                .max()
+                .unwrap_or(0)
+                .max(0);
+            let p_wrk = p.max(p_arg).saturating_add(3 * crate::WORD_BIT_SIZE);
+            let _ = preload(cc, p_wrk, e_arg as usize);
+        }
+
+        #[cfg(feature = "std")]
+        {
+            crate::common::buf::with_pool(|| values.iter().map(|v| f(v, cc)).collect())
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            values.iter().map(|v| f(v, cc)).collect()
+        }
+    }
+
+    gen_wrapper_many!(
+        "Computes `e` to the power of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `exp` for each of the values.",
+        exp,
+        exp_many,
+        Some(|cc, p, _| cc.e_num(p, RoundingMode::None))
+    );
+    gen_wrapper_many!(
+        "Computes the natural logarithm of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `ln` for each of the values.",
+        ln,
+        ln_many,
+        Some(|cc, p, _| cc.ln_2_num(p, RoundingMode::None))
+    );
+    gen_wrapper_many!(
+        "Computes the logarithm base 2 of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `log2` for each of the values.",
+        log2,
+        log2_many,
+        Some(|cc, p, _| cc.ln_2_num(p, RoundingMode::None))
+    );
+    gen_wrapper_many!(
+        "Computes the logarithm base 10 of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `log10` for each of the values.",
+        log10,
+        log10_many,
+        Some(|cc, p, _| cc.ln_2_num(p, RoundingMode::None))
+    );
+    gen_wrapper_many!(
+        "Computes the sine of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `sin` for each of the values.",
+        sin,
+        sin_many,
+        Some(|cc, p, e| cc.pi_num(p + e, RoundingMode::None))
+    );
+    gen_wrapper_many!(
+        "Computes the cosine of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `cos` for each of the values.",
+        cos,
+        cos_many,
+        Some(|cc, p, e| cc.pi_num(p + e, RoundingMode::None))
+    );
+    gen_wrapper_many!(
+        "Computes the tangent of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `tan` for each of the values.",
+        tan,
+        tan_many,
+        Some(|cc, p, e| cc.pi_num(p + e, RoundingMode::None))
     );
-    gen_wrapper_arg_rm_cc!(
-        "Computes the hyperbolic arccosine of a number with precision `p`. The result is rounded using the rounding mode `rm`.
-        This function requires constants cache `cc` for computing the result.
-        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
-        acosh,
-        Self,
-        { INF_POS },
-        { NAN },
-        p,
-        usize
+    gen_wrapper_many!(
+        "Computes the arcsine of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `asin` for each of the values.",
+        asin,
+        asin_many,
+        Some(|cc, p, e| cc.pi_num(p + e, RoundingMode::None))
     );
-    gen_wrapper_arg_rm_cc!(
-        "Computes the hyperbolic arctangent of a number with precision `p`. The result is rounded using the rounding mode `rm`.
-        This function requires constants cache `cc` for computing the result.
-        Precision is rounded upwards to the word size. The function returns NaN if the precision `p` is incorrect.",
-        atanh,
-        Self,
-        { NAN },
-        { NAN },
-        p,
-        usize
+    gen_wrapper_many!(
+        "Computes the arccosine of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `acos` for each of the values.",
+        acos,
+        acos_many,
+        Some(|cc, p, e| cc.pi_num(p + e, RoundingMode::None))
+    );
+    gen_wrapper_many!(
+        "Computes the arctangent of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `atan` for each of the values.",
+        atan,
+        atan_many,
+        Some(|cc, p, e| cc.pi_num(p + e, RoundingMode::None))
+    );
+    gen_wrapper_many!(
+        "Computes the hyperbolic sine of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `sinh` for each of the values.",
+        sinh,
+        sinh_many,
+        None
+    );
+    gen_wrapper_many!(
+        "Computes the hyperbolic cosine of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `cosh` for each of the values.",
+        cosh,
+        cosh_many,
+        None
+    );
+    gen_wrapper_many!(
+        "Computes the hyperbolic tangent of each of `values` with precision `p`. The results are rounded using the rounding mode `rm`.
+        The results are the same as the results of `tanh` for each of the values.",
+        tanh,
+        tanh_many,
+        None
     );
 }
 
@@ -1486,13 +3336,28 @@ impl From<BigFloatNumber> for BigFloat {
     fn from(x: BigFloatNumber) -> Self {
         BigFloat {
             inner: Flavor::Value(x),
+            #[cfg(feature = "debug")]
+            tag: None,
+        }
+    }
+}
+
+/// Moves the underlying number out of `BigFloat` without copying.
+/// If the value is Inf or NaN, the original value is returned as an error.
+impl TryFrom<BigFloat> for BigFloatNumber {
+    type Error = BigFloat;
+
+    fn try_from(x: BigFloat) -> Result<Self, Self::Error> {
+        match x.inner {
+            Flavor::Value(v) => Ok(v),
+            _ => Err(x),
         }
     }
 }
 
 #[cfg(feature = "std")]
 use core::{
-    fmt::{Binary, Display, Formatter, Octal, UpperHex},
+    fmt::{Alignment, Binary, Display, Formatter, LowerExp, LowerHex, Octal, UpperExp, UpperHex},
     str::FromStr,
 };
 
@@ -1515,6 +3380,209 @@ impl Neg for &BigFloat {
     }
 }
 
+//
+// arithmetic operators
+//
+
+#[cfg(feature = "std")]
+thread_local! {
+    static OP_PARAMS: core::cell::Cell<(usize, RoundingMode)> =
+        const { core::cell::Cell::new((usize::MAX, RoundingMode::ToEven)) };
+}
+
+#[cfg(feature = "std")]
+impl BigFloat {
+    /// Sets the precision `p` and the rounding mode `rm` of the results of the arithmetic operators `+`, `-`, `*`, and `/`
+    /// in the current thread.
+    /// If `p` equals to usize::MAX, the precision of the result is the larger of the precisions of the operands.
+    /// By default, the precision of the result is the larger of the precisions of the operands,
+    /// and the rounding mode is `RoundingMode::ToEven`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, RoundingMode};
+    /// let one = BigFloat::from_word(1, 64);
+    /// let three = BigFloat::from_word(3, 64);
+    ///
+    /// BigFloat::set_op_params(256, RoundingMode::Down);
+    /// let third = &one / &three;
+    /// assert_eq!(third.precision(), Some(256));
+    /// assert_eq!(third, one.div(&three, 256, RoundingMode::Down));
+    ///
+    /// BigFloat::set_op_params(usize::MAX, RoundingMode::ToEven);
+    /// assert_eq!((&one / &three).precision(), Some(64));
+    /// ```
+    pub fn set_op_params(p: usize, rm: RoundingMode) {
+        OP_PARAMS.with(|v| v.set((p, rm)));
+    }
+
+    /// Returns the precision and the rounding mode of the results of the arithmetic operators in the current thread.
+    pub fn op_params() -> (usize, RoundingMode) {
+        OP_PARAMS.with(|v| v.get())
+    }
+}
+
+impl BigFloat {
+    /// Returns the precision and the rounding mode of the result of an arithmetic operator applied to `d1` and `d2`.
+    /// The automatic precision is the larger of the precisions of the operands, or `DEFAULT_P` if both operands are Inf or NaN.
+    fn op_result_params(d1: &Self, d2: &Self) -> (usize, RoundingMode) {
+        #[cfg(feature = "std")]
+        let (p, rm) = Self::op_params();
+
+        #[cfg(not(feature = "std"))]
+        let (p, rm) = (usize::MAX, RoundingMode::ToEven);
+
+        if p != usize::MAX {
+            return (p, rm);
+        }
+
+        let p = match (d1.mantissa_max_bit_len(), d2.mantissa_max_bit_len()) {
+            (Some(p1), Some(p2)) => p1.max(p2),
+            (Some(p), None) | (None, Some(p)) => p,
+            (None, None) => DEFAULT_P,
+        };
+
+        (p, rm)
+    }
+}
+
+macro_rules! impl_arith_op {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $doc:literal) => {
+        impl core::ops::$trait<&BigFloat> for &BigFloat {
+            type Output = BigFloat;
+
+            #[doc = $doc]
+            /// The precision and the rounding mode of the result are set with `BigFloat::set_op_params`.
+            /// By default, and in no_std environment, the result has the precision of the operand with the larger precision
+            /// and is rounded to the nearest, ties to even.
+            fn $fn(self, rhs: &BigFloat) -> Self::Output {
+                let (p, rm) = BigFloat::op_result_params(self, rhs);
+                BigFloat::$fn(self, rhs, p, rm)
+            }
+        }
+
+        impl_arith_op!(@forward $trait, $fn, $assign_trait, $assign_fn);
+    };
+    (@forward $trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident) => {
+        impl core::ops::$trait<BigFloat> for BigFloat {
+            type Output = BigFloat;
+            fn $fn(self, rhs: BigFloat) -> Self::Output {
+                <&BigFloat as core::ops::$trait<&BigFloat>>::$fn(&self, &rhs)
+            }
+        }
+
+        impl core::ops::$trait<&BigFloat> for BigFloat {
+            type Output = BigFloat;
+            fn $fn(self, rhs: &BigFloat) -> Self::Output {
+                <&BigFloat as core::ops::$trait<&BigFloat>>::$fn(&self, rhs)
+            }
+        }
+
+        impl core::ops::$trait<BigFloat> for &BigFloat {
+            type Output = BigFloat;
+            fn $fn(self, rhs: BigFloat) -> Self::Output {
+                <&BigFloat as core::ops::$trait<&BigFloat>>::$fn(self, &rhs)
+            }
+        }
+
+        impl core::ops::$assign_trait<BigFloat> for BigFloat {
+            fn $assign_fn(&mut self, rhs: BigFloat) {
+                let ret = <&BigFloat as core::ops::$trait<&BigFloat>>::$fn(self, &rhs);
+                self.replace(ret);
+            }
+        }
+
+        impl core::ops::$assign_trait<&BigFloat> for BigFloat {
+            fn $assign_fn(&mut self, rhs: &BigFloat) {
+                let ret = <&BigFloat as core::ops::$trait<&BigFloat>>::$fn(self, rhs);
+                self.replace(ret);
+            }
+        }
+    };
+}
+
+impl_arith_op!(Add, add, AddAssign, add_assign, "Computes `self + rhs`.");
+impl_arith_op!(Sub, sub, SubAssign, sub_assign, "Computes `self - rhs`.");
+impl_arith_op!(Mul, mul, MulAssign, mul_assign, "Computes `self * rhs`.");
+impl_arith_op!(Div, div, DivAssign, div_assign, "Computes `self / rhs`.");
+
+impl BigFloat {
+    /// Returns the precision and the rounding mode of the result of `Sum` or `Product` of `values`.
+    fn iter_result_params<'a>(values: impl Iterator<Item = &'a BigFloat>) -> (usize, RoundingMode) {
+        #[cfg(feature = "std")]
+        let (p, rm) = Self::op_params();
+
+        #[cfg(not(feature = "std"))]
+        let (p, rm) = (usize::MAX, RoundingMode::ToEven);
+
+        if p != usize::MAX {
+            return (p, rm);
+        }
+
+        let p = values
+            .filter_map(|v| v.mantissa_max_bit_len())
+            .max()
+            .unwrap_or(DEFAULT_P);
+
+        (p, rm)
+    }
+}
+
+macro_rules! impl_iter_op {
+    ($trait:ident, $fn:ident, $fn_iter:ident, $doc:literal) => {
+        impl core::iter::$trait for BigFloat {
+            #[doc = $doc]
+            /// The result is rounded once, as if it was computed exactly and then rounded.
+            /// The precision and the rounding mode of the result are set with `BigFloat::set_op_params`.
+            /// By default, and in no_std environment, the result has the largest precision of the values
+            /// and is rounded to the nearest, ties to even.
+            fn $fn<I: Iterator<Item = BigFloat>>(iter: I) -> Self {
+                let values: Vec<BigFloat> = iter.collect();
+                values.iter().$fn()
+            }
+        }
+
+        impl<'a> core::iter::$trait<&'a BigFloat> for BigFloat {
+            #[doc = $doc]
+            /// The result is rounded once, as if it was computed exactly and then rounded.
+            /// The precision and the rounding mode of the result are set with `BigFloat::set_op_params`.
+            /// By default, and in no_std environment, the result has the largest precision of the values
+            /// and is rounded to the nearest, ties to even.
+            fn $fn<I: Iterator<Item = &'a BigFloat>>(iter: I) -> Self {
+                let values: Vec<&BigFloat> = iter.collect();
+                let (p, rm) = BigFloat::iter_result_params(values.iter().copied());
+                BigFloat::$fn_iter(values.into_iter(), p, rm)
+            }
+        }
+    };
+}
+
+impl_iter_op!(
+    Sum,
+    sum,
+    sum_iter,
+    "Computes the sum of the elements of `iter`."
+);
+impl_iter_op!(
+    Product,
+    product,
+    product_iter,
+    "Computes the product of the elements of `iter`."
+);
+
+impl core::ops::Rem<&BigFloat> for &BigFloat {
+    type Output = BigFloat;
+
+    /// Returns the remainder of division of `self` by `rhs` with the sign of `self`.
+    /// The remainder is computed exactly, see `BigFloat::rem`.
+    fn rem(self, rhs: &BigFloat) -> Self::Output {
+        BigFloat::rem(self, rhs)
+    }
+}
+
+impl_arith_op!(@forward Rem, rem, RemAssign, rem_assign);
+
 //
 // ordering traits
 //
@@ -1577,25 +3645,51 @@ impl Default for BigFloat {
     }
 }
 
+#[cfg(feature = "std")]
+thread_local! {
+    static FROM_STR_PARAMS: core::cell::Cell<(usize, RoundingMode)> =
+        const { core::cell::Cell::new((usize::MAX, RoundingMode::ToEven)) };
+}
+
+#[cfg(feature = "std")]
+impl BigFloat {
+    /// Sets the precision `p` and the rounding mode `rm` used by the implementation of `FromStr` in the current thread.
+    /// If `p` equals to usize::MAX, the precision of the parsed number is determined automatically from the input.
+    /// By default, the precision is determined automatically, and the rounding mode is `RoundingMode::ToEven`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, RoundingMode};
+    /// BigFloat::set_from_str_params(256, RoundingMode::Down);
+    /// let n: BigFloat = "0.1".parse().unwrap();
+    /// assert_eq!(n.precision(), Some(256));
+    /// ```
+    pub fn set_from_str_params(p: usize, rm: RoundingMode) {
+        FROM_STR_PARAMS.with(|v| v.set((p, rm)));
+    }
+
+    /// Returns the precision and the rounding mode used by the implementation of `FromStr` in the current thread.
+    pub fn from_str_params() -> (usize, RoundingMode) {
+        FROM_STR_PARAMS.with(|v| v.get())
+    }
+}
+
 #[cfg(feature = "std")]
 impl FromStr for BigFloat {
     type Err = Error;
 
-    /// Returns parsed number or NAN in case of error.
+    /// Returns parsed decimal number, or an error if `src` is not a valid number.
+    /// The precision and the rounding mode of the result are set with `set_from_str_params`.
+    /// By default, the precision is determined automatically from the input, and the result is rounded to nearest, ties to even.
     /// The implementation is not available in no_std environment.
     fn from_str(src: &str) -> Result<BigFloat, Self::Err> {
-        let bf = crate::common::consts::TENPOWERS.with(|tp| {
-            let cc = &mut tp.borrow_mut();
-            BigFloat::parse(src, Radix::Dec, usize::MAX, RoundingMode::ToEven, cc)
-        });
-
-        if bf.is_nan() {
-            if let Some(err) = bf.err() {
-                return Err(err);
-            }
-        }
+        let (p, rm) = Self::from_str_params();
 
-        Ok(bf)
+        crate::common::consts::TENPOWERS.with(|tp| {
+            let cc = &mut tp.borrow_mut();
+            BigFloat::try_parse(src, Radix::Dec, p, rm, cc).map_err(Error::from)
+        })
     }
 }
 
@@ -1623,29 +3717,176 @@ impl_from!(u64, from_u64);
 impl_from!(u128, from_u128);
 
 #[cfg(feature = "std")]
-macro_rules! impl_format_rdx {
-    ($trait:ty, $rdx:path) => {
+impl BigFloat {
+    /// Formats the number in decimal respecting the sign, width, fill, and precision flags of `f`.
+    /// If the precision is specified, the number is formatted with the given number of digits after the decimal point
+    /// in positional notation if `exp` is None, or in scientific notation with the exponent character `exp` otherwise.
+    fn fmt_dec(&self, f: &mut Formatter<'_>, exp: Option<char>) -> Result<(), core::fmt::Error> {
+        let mut s = String::new();
+
+        crate::common::consts::TENPOWERS.with(|tp| {
+            let cc = &mut tp.borrow_mut();
+            match (f.precision(), self.as_number()) {
+                (Some(n), Some(v)) => {
+                    let ret = match exp {
+                        Some(c) => v.format_sci(n, c, cc),
+                        None => v.format_fixed(n, cc),
+                    };
+                    match ret {
+                        Ok(d) if v.is_negative() => {
+                            s.push('-');
+                            s.push_str(&d);
+                        }
+                        Ok(d) => s = d,
+                        Err(Error::ExponentOverflow(Sign::Pos)) => s.push_str("Inf"),
+                        Err(Error::ExponentOverflow(Sign::Neg)) => s.push_str("-Inf"),
+                        Err(_) => s.push_str("Err"),
+                    }
+                    Ok(())
+                }
+                _ => self.write_str(
+                    &mut s,
+                    Radix::Dec,
+                    RoundingMode::ToEven,
+                    &FormatOptions::default(),
+                    cc,
+                ),
+            }
+        })?;
+
+        // Inf, NaN, and formatting errors
+        if matches!(s.as_str(), "Inf" | "-Inf" | "NaN" | "Err") {
+            return Self::pad_ignoring_precision(f, &s);
+        }
+
+        if exp == Some('E') {
+            s.make_ascii_uppercase();
+        }
+
+        match s.strip_prefix('-') {
+            Some(digits) => f.pad_integral(false, "", digits),
+            None => f.pad_integral(true, "", &s),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl BigFloat {
+    /// Writes `s` padded according to the width, fill, and alignment flags of `f`.
+    /// Unlike `Formatter::pad`, the string is not truncated to the precision specified in `f`.
+    fn pad_ignoring_precision(f: &mut Formatter<'_>, s: &str) -> Result<(), core::fmt::Error> {
+        let pad = f.width().unwrap_or(0).saturating_sub(s.chars().count());
+        let (pre, post) = match f.align() {
+            Some(Alignment::Right) => (pad, 0),
+            Some(Alignment::Center) => (pad / 2, pad - pad / 2),
+            _ => (0, pad),
+        };
+
+        let fill = f.fill();
+        for _ in 0..pre {
+            f.write_char(fill)?;
+        }
+        f.write_str(s)?;
+        for _ in 0..post {
+            f.write_char(fill)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Display for BigFloat {
+    /// Formats the number.
+    /// If the precision is specified, e.g. `{:.30}`, the number is formatted in positional notation
+    /// with the given number of digits after the decimal point.
+    /// The implementation is not available in no_std environment.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        self.fmt_dec(f, None)
+    }
+}
+
+#[cfg(feature = "std")]
+impl LowerExp for BigFloat {
+    /// Formats the number in scientific notation.
+    /// If the precision is specified, e.g. `{:.30e}`, the mantissa has the given number of digits after the decimal point.
+    /// The implementation is not available in no_std environment.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        self.fmt_dec(f, Some('e'))
+    }
+}
+
+#[cfg(feature = "std")]
+impl UpperExp for BigFloat {
+    /// Formats the number in scientific notation with the exponent character "E".
+    /// If the precision is specified, e.g. `{:.30E}`, the mantissa has the given number of digits after the decimal point.
+    /// The implementation is not available in no_std environment.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        self.fmt_dec(f, Some('E'))
+    }
+}
+
+#[cfg(feature = "std")]
+impl BigFloat {
+    /// Formats the number with radix `rdx` respecting the sign, alternate, width and fill flags of `f`,
+    /// e.g. `{:#x}` adds the prefix "0x" after the sign.
+    fn fmt_integral(
+        &self,
+        f: &mut Formatter<'_>,
+        rdx: Radix,
+        prefix: &str,
+        lowercase: bool,
+    ) -> Result<(), core::fmt::Error> {
+        let mut s = String::new();
+
+        crate::common::consts::TENPOWERS.with(|tp| {
+            let cc = &mut tp.borrow_mut();
+            self.write_str(
+                &mut s,
+                rdx,
+                RoundingMode::ToEven,
+                &FormatOptions::default(),
+                cc,
+            )
+        })?;
+
+        // Inf, NaN, and formatting errors
+        if matches!(s.as_str(), "Inf" | "-Inf" | "NaN" | "Err") {
+            return f.pad(&s);
+        }
+
+        if lowercase {
+            s.make_ascii_lowercase();
+        }
+
+        match s.strip_prefix('-') {
+            Some(digits) => f.pad_integral(false, prefix, digits),
+            None => f.pad_integral(true, prefix, &s),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+macro_rules! impl_format_integral {
+    ($trait:ty, $rdx:path, $prefix:literal, $lowercase:literal) => {
         impl $trait for BigFloat {
             /// Formats the number.
             /// The implementation is not available in no_std environment.
             fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
-                crate::common::consts::TENPOWERS.with(|tp| {
-                    let cc = &mut tp.borrow_mut();
-                    self.write_str(f, $rdx, RoundingMode::ToEven, cc)
-                })
+                self.fmt_integral(f, $rdx, $prefix, $lowercase)
             }
         }
     };
 }
 
 #[cfg(feature = "std")]
-impl_format_rdx!(Binary, Radix::Bin);
+impl_format_integral!(Binary, Radix::Bin, "0b", false);
 #[cfg(feature = "std")]
-impl_format_rdx!(Octal, Radix::Oct);
+impl_format_integral!(Octal, Radix::Oct, "0o", false);
 #[cfg(feature = "std")]
-impl_format_rdx!(Display, Radix::Dec);
+impl_format_integral!(LowerHex, Radix::Hex, "0x", true);
 #[cfg(feature = "std")]
-impl_format_rdx!(UpperHex, Radix::Hex);
+impl_format_integral!(UpperHex, Radix::Hex, "0x", false);
 
 /// A trait for conversion with additional arguments.
 pub trait FromExt<T> {
@@ -1680,12 +3921,19 @@ mod tests {
     use crate::defs::DEFAULT_P;
     use crate::ext::ONE;
     use crate::ext::TWO;
+    use crate::num::BigFloatNumber;
+    use crate::AngleRange;
     use crate::BigFloat;
     use crate::Consts;
     use crate::Error;
+    use crate::Exponent;
+    use crate::FormatOptions;
+    use crate::ParseErrorKind;
     use crate::Radix;
     use crate::Sign;
     use crate::Word;
+    use crate::EXPONENT_MAX;
+    use crate::EXPONENT_MIN;
     use crate::INF_NEG;
     use crate::INF_POS;
     use crate::NAN;
@@ -1764,6 +4012,15 @@ mod tests {
         assert!(INF_NEG.as_raw_parts().is_none());
         assert!(NAN.as_raw_parts().is_none());
 
+        let d1 = BigFloat::from_f64(-0.3, 128);
+        let (m, _, sign, e, _) = d1.as_raw_parts().unwrap();
+        let d2 = BigFloat::from_words_checked(m, sign, e).unwrap();
+        assert_eq!(d2.as_raw_parts(), d1.as_raw_parts());
+        assert_eq!(
+            BigFloat::from_words_checked(&[1, 1], sign, e).unwrap_err(),
+            Error::InvalidArgument
+        );
+
         assert!(ONE.add(&ONE, rand_p(), rm).cmp(&TWO) == Some(0));
         assert!(ONE.add(&INF_POS, rand_p(), rm).is_inf_pos());
         assert!(INF_POS.add(&ONE, rand_p(), rm).is_inf_pos());
@@ -2085,6 +4342,30 @@ mod tests {
         assert!(INF_POS.cbrt(rand_p(), rm).is_inf_pos());
         assert!(NAN.cbrt(rand_p(), rm).is_nan());
 
+        assert!(INF_NEG.expm1(rand_p(), rm, &mut cc).cmp(&ONE.neg()) == Some(0));
+        assert!(INF_POS.expm1(rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(NAN.expm1(rand_p(), rm, &mut cc).is_nan());
+
+        assert!(INF_NEG.ln_1p(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_POS.ln_1p(rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(NAN.ln_1p(rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.neg().ln_1p(rand_p(), rm, &mut cc).is_inf_neg());
+        assert!(TWO.neg().ln_1p(rand_p(), rm, &mut cc).is_nan());
+
+        assert!(INF_NEG.hypot(&ONE, rand_p(), rm).is_inf_pos());
+        assert!(NAN.hypot(&INF_NEG, rand_p(), rm).is_inf_pos());
+        assert!(NAN.hypot(&ONE, rand_p(), rm).is_nan());
+        assert!(ONE.hypot(&NAN, rand_p(), rm).is_nan());
+        let d1 = BigFloat::from_word(3, DEFAULT_P);
+        let d2 = BigFloat::from_word(4, DEFAULT_P).neg();
+        assert!(
+            d1.hypot(&d2, rand_p(), rm)
+                .cmp(&BigFloat::from_word(5, DEFAULT_P))
+                == Some(0)
+        );
+        let d1 = BigFloat::max_value(DEFAULT_P);
+        assert!(d1.hypot(&d1, rand_p(), rm).is_inf_pos());
+
         for op in [BigFloat::ln, BigFloat::log2, BigFloat::log10] {
             assert!(op(&INF_NEG, rand_p(), rm, &mut cc).is_nan());
             assert!(op(&INF_POS, rand_p(), rm, &mut cc).is_inf_pos());
@@ -2107,6 +4388,17 @@ mod tests {
         assert!(INF_POS.tan(rand_p(), rm, &mut cc).is_nan());
         assert!(NAN.tan(rand_p(), rm, &mut cc).is_nan());
 
+        for op in [BigFloat::sin_pi, BigFloat::cos_pi, BigFloat::tan_pi] {
+            assert!(op(&INF_NEG, rand_p(), rm, &mut cc).is_nan());
+            assert!(op(&INF_POS, rand_p(), rm, &mut cc).is_nan());
+            assert!(op(&NAN, rand_p(), rm, &mut cc).is_nan());
+        }
+        let d1 = BigFloat::from_f64(2.5, DEFAULT_P);
+        assert_eq!(d1.sin_pi(rand_p(), rm, &mut cc), *ONE);
+        assert!(d1.cos_pi(rand_p(), rm, &mut cc).is_zero());
+        assert!(d1.tan_pi(rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(d1.neg().tan_pi(rand_p(), rm, &mut cc).is_inf_neg());
+
         assert!(INF_NEG.asin(rand_p(), rm, &mut cc).is_nan());
         assert!(INF_POS.asin(rand_p(), rm, &mut cc).is_nan());
         assert!(NAN.asin(rand_p(), rm, &mut cc).is_nan());
@@ -2122,6 +4414,24 @@ mod tests {
         assert!(INF_POS.atan(p, rm, &mut cc).cmp(&half_pi) == Some(0));
         assert!(NAN.atan(rand_p(), rm, &mut cc).is_nan());
 
+        let mut pi: BigFloat = cc.pi_num(p, rm).unwrap().into();
+        assert!(ONE.atan2(&INF_NEG, p, rm, &mut cc).cmp(&pi) == Some(0));
+        assert!(ONE.neg().atan2(&INF_POS, p, rm, &mut cc).is_zero());
+        assert!(INF_NEG.atan2(&TWO, p, rm, &mut cc).cmp(&half_pi.neg()) == Some(0));
+        pi.set_exponent(0);
+        assert!(INF_POS.atan2(&INF_POS, p, rm, &mut cc).cmp(&pi) == Some(0));
+        assert!(INF_NEG.atan2(&INF_NEG, p, rm, &mut cc).is_negative());
+        assert!(NAN.atan2(&ONE, rand_p(), rm, &mut cc).is_nan());
+
+        assert!(INF_NEG.to_degrees(rand_p(), rm, &mut cc).is_inf_neg());
+        assert!(INF_POS.to_radians(rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(NAN.to_degrees(rand_p(), rm, &mut cc).is_nan());
+        let mut d1 = BigFloat::from_word(180, p).to_radians(p, rm, &mut cc);
+        assert!(d1.cmp(&cc.pi(p, rm)) == Some(0));
+        d1 = d1.to_degrees(p, rm, &mut cc);
+        assert!(d1.cmp(&BigFloat::from_word(180, p)) == Some(0));
+        assert!(ONE.atan2(&NAN, rand_p(), rm, &mut cc).is_nan());
+
         assert!(INF_NEG.sinh(rand_p(), rm, &mut cc).is_inf_neg());
         assert!(INF_POS.sinh(rand_p(), rm, &mut cc).is_inf_pos());
         assert!(NAN.sinh(rand_p(), rm, &mut cc).is_nan());
@@ -2138,13 +4448,242 @@ mod tests {
         assert!(INF_POS.asinh(rand_p(), rm, &mut cc).is_inf_pos());
         assert!(NAN.asinh(rand_p(), rm, &mut cc).is_nan());
 
-        assert!(INF_NEG.acosh(rand_p(), rm, &mut cc).is_nan());
-        assert!(INF_POS.acosh(rand_p(), rm, &mut cc).is_inf_pos());
-        assert!(NAN.acosh(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_NEG.acosh(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_POS.acosh(rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(NAN.acosh(rand_p(), rm, &mut cc).is_nan());
+
+        assert!(INF_NEG.atanh(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_POS.atanh(rand_p(), rm, &mut cc).is_nan());
+        assert!(NAN.atanh(rand_p(), rm, &mut cc).is_nan());
+
+        for range in [AngleRange::ZeroToTwoPi, AngleRange::MinusPiToPi] {
+            assert!(INF_POS
+                .normalize_angle(range, rand_p(), rm, &mut cc)
+                .is_nan());
+            assert!(INF_NEG
+                .normalize_angle(range, rand_p(), rm, &mut cc)
+                .is_nan());
+            assert!(NAN.normalize_angle(range, rand_p(), rm, &mut cc).is_nan());
+        }
+
+        assert!(INF_POS.rem_pio2(rand_p(), rm, &mut cc).0.is_nan());
+        assert!(INF_NEG.rem_pio2(rand_p(), rm, &mut cc).0.is_nan());
+        assert!(NAN.rem_pio2(rand_p(), rm, &mut cc).0.is_nan());
+
+        let half = BigFloat::from_f64(0.5, rand_p());
+        assert!(INF_NEG
+            .solve_kepler(&half, rand_p(), rm, &mut cc)
+            .is_inf_neg());
+        assert!(INF_POS
+            .solve_kepler(&half, rand_p(), rm, &mut cc)
+            .is_inf_pos());
+        assert!(INF_POS.solve_kepler(&ONE, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.solve_kepler(&INF_POS, rand_p(), rm, &mut cc).is_nan());
+        assert!(NAN.solve_kepler(&half, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.solve_kepler(&NAN, rand_p(), rm, &mut cc).is_nan());
+
+        let (s, r) = INF_POS.two_sum(&ONE, rand_p(), rm);
+        assert!(s.is_inf_pos() && r.is_nan());
+        let (s, r) = ONE.two_sum(&NAN, rand_p(), rm);
+        assert!(s.is_nan() && r.is_nan());
+        let d1 = BigFloat::max_value(rand_p());
+        let (s, r) = d1.two_sum(&d1, rand_p(), rm);
+        assert!(s.is_inf_pos() && r.is_nan());
+        assert!(BigFloat::sum(&[ONE.clone(), INF_NEG, ONE.clone()], rand_p(), rm).is_inf_neg());
+        assert!(BigFloat::sum(&[INF_POS, INF_NEG], rand_p(), rm).is_nan());
+        assert!(BigFloat::sum(&[ONE.clone(), NAN], rand_p(), rm).is_nan());
+        assert!(BigFloat::sum(&[], rand_p(), rm).is_zero());
+        let d1 = BigFloat::max_value(rand_p());
+        assert!(BigFloat::sum(&[d1.clone(), d1], rand_p(), rm).is_inf_pos());
+
+        let (s, r) = ONE.two_sum(&ONE, rand_p(), rm);
+        assert!(s.cmp(&BigFloat::from_word(2, 1)) == Some(0) && r.is_zero());
+
+        let d = INF_NEG.sech(rand_p(), rm, &mut cc);
+        assert!(d.is_zero() && d.is_positive());
+        assert!(INF_POS.sech(rand_p(), rm, &mut cc).is_zero());
+        assert!(NAN.sech(rand_p(), rm, &mut cc).is_nan());
+
+        let d = INF_NEG.csch(rand_p(), rm, &mut cc);
+        assert!(d.is_zero() && d.is_negative());
+        assert!(INF_POS.csch(rand_p(), rm, &mut cc).is_zero());
+        assert!(NAN.csch(rand_p(), rm, &mut cc).is_nan());
+        assert!(BigFloat::new(1)
+            .neg()
+            .csch(rand_p(), rm, &mut cc)
+            .is_inf_neg());
+
+        assert!(INF_NEG.coth(rand_p(), rm, &mut cc).cmp(&ONE.neg()) == Some(0));
+        assert!(INF_POS.coth(rand_p(), rm, &mut cc).cmp(&ONE) == Some(0));
+        assert!(NAN.coth(rand_p(), rm, &mut cc).is_nan());
+        assert!(BigFloat::new(1).coth(rand_p(), rm, &mut cc).is_inf_pos());
+
+        assert!(INF_NEG.asech(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_POS.asech(rand_p(), rm, &mut cc).is_nan());
+        assert!(NAN.asech(rand_p(), rm, &mut cc).is_nan());
+        assert!(BigFloat::new(1).asech(rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(ONE.neg().asech(rand_p(), rm, &mut cc).is_nan());
+
+        let d = INF_NEG.acsch(rand_p(), rm, &mut cc);
+        assert!(d.is_zero() && d.is_negative());
+        assert!(INF_POS.acsch(rand_p(), rm, &mut cc).is_zero());
+        assert!(NAN.acsch(rand_p(), rm, &mut cc).is_nan());
+        assert!(BigFloat::new(1)
+            .neg()
+            .acsch(rand_p(), rm, &mut cc)
+            .is_inf_neg());
+
+        let d = INF_NEG.acoth(rand_p(), rm, &mut cc);
+        assert!(d.is_zero() && d.is_negative());
+        assert!(INF_POS.acoth(rand_p(), rm, &mut cc).is_zero());
+        assert!(NAN.acoth(rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.neg().acoth(rand_p(), rm, &mut cc).is_inf_neg());
+        assert!(BigFloat::new(1).acoth(rand_p(), rm, &mut cc).is_nan());
+
+        assert!(INF_NEG.gamma(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_POS.gamma(rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(NAN.gamma(rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.neg().gamma(rand_p(), rm, &mut cc).is_nan());
+        assert!(BigFloat::new(rand_p())
+            .gamma(rand_p(), rm, &mut cc)
+            .is_inf_pos());
+        assert!(BigFloat::new(rand_p())
+            .neg()
+            .gamma(rand_p(), rm, &mut cc)
+            .is_inf_neg());
+
+        assert!(INF_POS.erf(rand_p(), rm, &mut cc).cmp(&ONE) == Some(0));
+        assert!(INF_NEG.erf(rand_p(), rm, &mut cc).cmp(&ONE.neg()) == Some(0));
+        assert!(NAN.erf(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_POS.erfc(rand_p(), rm, &mut cc).is_zero());
+        assert!(INF_NEG.erfc(rand_p(), rm, &mut cc).cmp(&TWO) == Some(0));
+        assert!(NAN.erfc(rand_p(), rm, &mut cc).is_nan());
+
+        assert!(INF_POS.erfinv(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_NEG.erfinv(rand_p(), rm, &mut cc).is_nan());
+        assert!(NAN.erfinv(rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.erfinv(rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(ONE.neg().erfinv(rand_p(), rm, &mut cc).is_inf_neg());
+        assert!(TWO.erfinv(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_POS.erfcinv(rand_p(), rm, &mut cc).is_nan());
+        assert!(INF_NEG.erfcinv(rand_p(), rm, &mut cc).is_nan());
+        assert!(NAN.erfcinv(rand_p(), rm, &mut cc).is_nan());
+        assert!(BigFloat::new(rand_p())
+            .erfcinv(rand_p(), rm, &mut cc)
+            .is_inf_pos());
+        assert!(TWO.erfcinv(rand_p(), rm, &mut cc).is_inf_neg());
+        assert!(ONE.neg().erfcinv(rand_p(), rm, &mut cc).is_nan());
+
+        assert!(INF_POS.besselj(&ONE, rand_p(), rm, &mut cc).is_zero());
+        assert!(INF_NEG.besselj(&TWO, rand_p(), rm, &mut cc).is_zero());
+        assert!(INF_NEG
+            .besselj(&ONE.div(&TWO, rand_p(), rm), rand_p(), rm, &mut cc)
+            .is_nan());
+        assert!(ONE.besselj(&INF_POS, rand_p(), rm, &mut cc).is_nan());
+        assert!(NAN.besselj(&ONE, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.besselj(&NAN, rand_p(), rm, &mut cc).is_nan());
+        assert!(
+            BigFloat::new(rand_p())
+                .besselj(&BigFloat::new(rand_p()), rand_p(), rm, &mut cc)
+                .cmp(&ONE)
+                == Some(0)
+        );
+        assert!(ONE
+            .neg()
+            .besselj(&ONE.div(&TWO, rand_p(), rm), rand_p(), rm, &mut cc)
+            .is_nan());
+
+        assert!(INF_POS.bessely(&ONE, rand_p(), rm, &mut cc).is_zero());
+        assert!(INF_NEG.bessely(&ONE, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.bessely(&INF_POS, rand_p(), rm, &mut cc).is_nan());
+        assert!(NAN.bessely(&ONE, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.bessely(&NAN, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.neg().bessely(&ONE, rand_p(), rm, &mut cc).is_nan());
+        let zero = BigFloat::new(rand_p());
+        assert!(zero.bessely(&ONE, rand_p(), rm, &mut cc).is_inf_neg());
+        assert!(zero.bessely(&ONE.neg(), rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(zero.bessely(&TWO.neg(), rand_p(), rm, &mut cc).is_inf_neg());
+        assert!(zero
+            .bessely(&ONE.div(&TWO, rand_p(), rm).neg(), rand_p(), rm, &mut cc)
+            .is_nan());
+
+        assert!(INF_POS.besseli(&ONE, rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(INF_NEG.besseli(&ONE, rand_p(), rm, &mut cc).is_inf_neg());
+        assert!(INF_NEG.besseli(&TWO, rand_p(), rm, &mut cc).is_inf_pos());
+        assert!(INF_NEG
+            .besseli(&ONE.div(&TWO, rand_p(), rm), rand_p(), rm, &mut cc)
+            .is_nan());
+        assert!(ONE.besseli(&INF_POS, rand_p(), rm, &mut cc).is_nan());
+        assert!(NAN.besseli(&ONE, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.besseli(&NAN, rand_p(), rm, &mut cc).is_nan());
+        assert!(zero.besseli(&zero, rand_p(), rm, &mut cc).cmp(&ONE) == Some(0));
+        assert!(ONE
+            .neg()
+            .besseli(&ONE.div(&TWO, rand_p(), rm), rand_p(), rm, &mut cc)
+            .is_nan());
+
+        assert!(INF_POS.besselk(&ONE, rand_p(), rm, &mut cc).is_zero());
+        assert!(INF_NEG.besselk(&ONE, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.besselk(&INF_POS, rand_p(), rm, &mut cc).is_nan());
+        assert!(NAN.besselk(&ONE, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.besselk(&NAN, rand_p(), rm, &mut cc).is_nan());
+        assert!(ONE.neg().besselk(&ONE, rand_p(), rm, &mut cc).is_nan());
+        assert!(zero.besselk(&ONE, rand_p(), rm, &mut cc).is_inf_pos());
+
+        assert!(
+            TWO.fused_mul_add_pair(&TWO, &ONE, &ONE, rand_p(), rm)
+                .cmp(&BigFloat::from_u8(5, p))
+                == Some(0)
+        );
+        assert!(INF_POS
+            .fused_mul_add_pair(&TWO, &ONE, &ONE, rand_p(), rm)
+            .is_inf_pos());
+        assert!(INF_POS
+            .fused_mul_add_pair(&TWO, &TWO, &INF_NEG, rand_p(), rm)
+            .is_nan());
+        assert!(INF_POS
+            .fused_mul_add_pair(&TWO, &TWO, &INF_POS, rand_p(), rm)
+            .is_inf_pos());
+        assert!(INF_POS
+            .fused_mul_add_pair(&zero, &ONE, &ONE, rand_p(), rm)
+            .is_nan());
+        assert!(ONE
+            .fused_mul_add_pair(&ONE, &NAN, &ONE, rand_p(), rm)
+            .is_nan());
+        assert!(BigFloat::max_value(p)
+            .fused_mul_add_pair(&TWO, &ONE, &INF_NEG, rand_p(), rm)
+            .is_inf_neg());
+        assert!(BigFloat::max_value(p)
+            .fused_mul_add_pair(&TWO, &BigFloat::max_value(p), &TWO.neg(), rand_p(), rm)
+            .is_zero());
+
+        assert!(
+            ONE.discriminant(&TWO, &TWO, rand_p(), rm)
+                .cmp(&BigFloat::from_i8(-15, p))
+                == Some(0)
+        );
+        assert!(INF_NEG.discriminant(&TWO, &TWO, rand_p(), rm).is_inf_pos());
+        assert!(ONE.discriminant(&INF_NEG, &TWO, rand_p(), rm).is_inf_pos());
+        assert!(INF_POS.discriminant(&INF_POS, &TWO, rand_p(), rm).is_nan());
+        assert!(INF_POS
+            .discriminant(&INF_POS, &TWO.neg(), rand_p(), rm)
+            .is_inf_pos());
+        assert!(ONE.discriminant(&INF_POS, &zero, rand_p(), rm).is_nan());
+        assert!(NAN.discriminant(&ONE, &ONE, rand_p(), rm).is_nan());
+        assert!(BigFloat::max_value(p)
+            .discriminant(&ONE, &ONE, rand_p(), rm)
+            .is_inf_pos());
 
-        assert!(INF_NEG.atanh(rand_p(), rm, &mut cc).is_nan());
-        assert!(INF_POS.atanh(rand_p(), rm, &mut cc).is_nan());
-        assert!(NAN.atanh(rand_p(), rm, &mut cc).is_nan());
+        let roots = ONE.solve_quadratic(&TWO.neg(), &zero, rand_p(), rm);
+        assert!(roots.len() == 2 && roots[0].is_zero() && roots[1].cmp(&TWO) == Some(0));
+        assert!(ONE.solve_quadratic(&ONE, &ONE, rand_p(), rm).is_empty());
+        let roots = INF_POS.solve_quadratic(&ONE, &ONE, rand_p(), rm);
+        assert!(roots.len() == 1 && roots[0].is_nan());
+        let roots = zero.solve_quadratic(&zero, &zero, rand_p(), rm);
+        assert!(roots.len() == 1 && roots[0].err() == Some(Error::InvalidArgument));
+        let roots = ONE.solve_cubic(&zero, &ONE.neg(), &zero, rand_p(), rm, &mut cc);
+        assert!(roots.len() == 3 && roots[0].cmp(&ONE.neg()) == Some(0));
+        assert!(ONE.solve_cubic(&ONE, &ONE, &NAN, rand_p(), rm, &mut cc)[0].is_nan());
 
         assert!(INF_NEG.reciprocal(rand_p(), rm).is_zero());
         assert!(INF_POS.reciprocal(rand_p(), rm).is_zero());
@@ -2204,6 +4743,18 @@ mod tests {
         NAN.clone().set_precision(1, rm).unwrap();
         NAN.clone().set_sign(Sign::Pos);
 
+        let mut d1 = INF_NEG.clone();
+        d1.reround(1, rm).unwrap();
+        assert!(d1.is_inf_neg());
+        let mut d1 = NAN.clone();
+        d1.reround(1, rm).unwrap();
+        assert!(d1.is_nan());
+        let three = BigFloat::from_word(3, 1);
+        let mut d1 = ONE.div(&three, WORD_BIT_SIZE * 2, RoundingMode::None);
+        d1.reround(WORD_BIT_SIZE, RoundingMode::FromZero).unwrap();
+        let d2 = ONE.div(&three, WORD_BIT_SIZE, RoundingMode::FromZero);
+        assert!(d1.cmp(&d2) == Some(0) && d1.inexact());
+
         assert!(INF_POS.min(&ONE).cmp(&ONE) == Some(0));
         assert!(INF_NEG.min(&ONE).is_inf_neg());
         assert!(NAN.min(&ONE).is_nan());
@@ -2296,7 +4847,988 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ternary() {
+        use crate::defs::SignedWord;
+        use core::cmp::Ordering;
+
+        let to_ord = |c: Option<SignedWord>| c.unwrap().cmp(&0);
+
+        let third = ONE.div(&BigFloat::from_u8(3, 128), 128, RoundingMode::None);
+        let nums = [
+            BigFloat::from_f64(1.5, 64),
+            BigFloat::from_f64(-0.1, 64),
+            BigFloat::from_u8(3, 64),
+            BigFloat::from_u8(25, 64),
+            third.clone(),
+            third.neg(),
+            BigFloat::from_words(&[1, 0x8000], Sign::Pos, 40),
+        ];
+        let rms = [
+            RoundingMode::None,
+            RoundingMode::Up,
+            RoundingMode::Down,
+            RoundingMode::ToZero,
+            RoundingMode::FromZero,
+            RoundingMode::ToEven,
+            RoundingMode::ToOdd,
+        ];
+        let p = 64;
+
+        for d1 in nums.iter() {
+            for d2 in nums.iter() {
+                for rm in rms {
+                    let (r, ord) = d1.add_ternary(d2, p, rm);
+                    assert_eq!(r, d1.add(d2, p, rm));
+                    assert_eq!(ord, to_ord(r.cmp(&d1.add_full_prec(d2))));
+
+                    let (r, ord) = d1.sub_ternary(d2, p, rm);
+                    assert_eq!(r, d1.sub(d2, p, rm));
+                    assert_eq!(ord, to_ord(r.cmp(&d1.sub_full_prec(d2))));
+
+                    let (r, ord) = d1.mul_ternary(d2, p, rm);
+                    assert_eq!(r, d1.mul(d2, p, rm));
+                    assert_eq!(ord, to_ord(r.cmp(&d1.mul_full_prec(d2))));
+
+                    // r * d2 <=> d1 is reversed if d2 is negative
+                    let (r, ord) = d1.div_ternary(d2, p, rm);
+                    assert_eq!(r, d1.div(d2, p, rm));
+                    let mut c = to_ord(r.mul_full_prec(d2).cmp(d1));
+                    if d2.is_negative() {
+                        c = c.reverse();
+                    }
+                    assert_eq!(ord, c);
+                }
+            }
+
+            for rm in rms {
+                let (r, ord) = d1.abs().sqrt_ternary(p, rm);
+                assert_eq!(r, d1.abs().sqrt(p, rm));
+                assert_eq!(ord, to_ord(r.mul_full_prec(&r).cmp(&d1.abs())));
+            }
+        }
+
+        // exact results
+        let (r, ord) = nums[0].add_ternary(&nums[2], p, RoundingMode::ToEven);
+        assert_eq!(r, BigFloat::from_f64(4.5, p));
+        assert_eq!(ord, Ordering::Equal);
+        assert_eq!(nums[3].sqrt_ternary(p, RoundingMode::Up).1, Ordering::Equal);
+
+        // truncation
+        assert_eq!(
+            third.mul_ternary(&ONE, p, RoundingMode::None).1,
+            Ordering::Less
+        );
+        assert_eq!(
+            third.neg().mul_ternary(&ONE, p, RoundingMode::None).1,
+            Ordering::Greater
+        );
+
+        // special values
+        let max = BigFloat::max_value(p);
+        assert_eq!(
+            max.add_ternary(&max, p, RoundingMode::ToEven),
+            (INF_POS, Ordering::Greater)
+        );
+        assert_eq!(
+            max.neg().mul_ternary(&max, p, RoundingMode::ToEven),
+            (INF_NEG, Ordering::Less)
+        );
+        assert_eq!(
+            INF_POS.add_ternary(&ONE, p, RoundingMode::ToEven),
+            (INF_POS, Ordering::Equal)
+        );
+        assert_eq!(
+            ONE.div_ternary(&BigFloat::new(p), p, RoundingMode::ToEven),
+            (INF_POS, Ordering::Equal)
+        );
+        let (r, ord) = INF_NEG.add_ternary(&INF_POS, p, RoundingMode::ToEven);
+        assert!(r.is_nan());
+        assert_eq!(ord, Ordering::Equal);
+    }
+
+    #[test]
+    fn test_sum_product_iter() {
+        let p = 128;
+
+        // the sum and the product are rounded once
+        let mut big = BigFloat::from_word(1, p);
+        big.set_exponent(200);
+        let small = BigFloat::from_word(3, 64);
+        let values = [big.clone(), small.clone(), big.neg()];
+        let s: BigFloat = values.iter().sum();
+        assert_eq!(s, small);
+        assert_eq!(s.precision(), Some(p));
+        assert_eq!(
+            values.iter().fold(BigFloat::new(p), |a, b| &a + b),
+            BigFloat::new(p)
+        );
+
+        let third =
+            BigFloat::from_word(1, p).div(&BigFloat::from_word(3, p), p, RoundingMode::ToEven);
+        let values = [third.clone(), small.clone(), small.clone(), third.clone()];
+        let d: BigFloat = values.iter().product();
+        let mut exact = third
+            .mul_full_prec(&third)
+            .mul_full_prec(&small)
+            .mul_full_prec(&small);
+        exact.set_precision(p, RoundingMode::ToEven).unwrap();
+        assert_eq!(d, exact);
+        let d2: BigFloat = values.into_iter().product();
+        assert_eq!(d2, d);
+
+        // empty iterators, NaN, and infinity
+        let s: BigFloat = core::iter::empty::<BigFloat>().sum();
+        assert!(s.is_zero() && s.mantissa_max_bit_len() == Some(DEFAULT_P));
+        let d: BigFloat = core::iter::empty::<BigFloat>().product();
+        assert_eq!(d, *ONE);
+        assert!([small.clone(), NAN].iter().sum::<BigFloat>().is_nan());
+        assert!([INF_POS, INF_NEG].iter().sum::<BigFloat>().is_nan());
+        assert!([INF_POS, BigFloat::new(p)]
+            .iter()
+            .product::<BigFloat>()
+            .is_nan());
+        assert!([INF_POS, small.neg()]
+            .iter()
+            .product::<BigFloat>()
+            .is_inf_neg());
+
+        // precision and rounding mode of the operators
+        #[cfg(feature = "std")]
+        {
+            BigFloat::set_op_params(256, RoundingMode::Up);
+            let values = [third.clone(), small.clone()];
+            let s: BigFloat = values.iter().sum();
+            assert_eq!(s, BigFloat::sum(&values, 256, RoundingMode::Up));
+            assert_eq!(s.precision(), Some(256));
+            let d: BigFloat = values.iter().product();
+            assert_eq!(d, third.mul(&small, 256, RoundingMode::Up));
+            BigFloat::set_op_params(usize::MAX, RoundingMode::ToEven);
+        }
+    }
+
+    #[test]
+    fn test_eval_many() {
+        let p = 192;
+        let rm = RoundingMode::ToEven;
+        let mut cc = Consts::new().unwrap();
+
+        let mut values = vec![
+            BigFloat::new(p),
+            NAN,
+            INF_POS,
+            INF_NEG,
+            BigFloat::from_i8(-3, p),
+            BigFloat::from_f64(1.0e+30, p),
+        ];
+        for _ in 0..20 {
+            values.push(BigFloat::random_normal(p, -20, 20));
+        }
+
+        let same = |d1: &[BigFloat], d2: Vec<BigFloat>| {
+            assert_eq!(d1.len(), d2.len());
+            for (a, b) in d1.iter().zip(d2) {
+                assert!(a.is_nan() && b.is_nan() || *a == b && a.inexact() == b.inexact());
+            }
+        };
+
+        let batch = BigFloat::exp_many(&values, p, rm, &mut cc);
+        same(
+            &batch,
+            values.iter().map(|v| v.exp(p, rm, &mut cc)).collect(),
+        );
+
+        let batch = BigFloat::ln_many(&values, p, rm, &mut cc);
+        same(
+            &batch,
+            values.iter().map(|v| v.ln(p, rm, &mut cc)).collect(),
+        );
+
+        let batch = BigFloat::sin_many(&values, p, rm, &mut cc);
+        same(
+            &batch,
+            values.iter().map(|v| v.sin(p, rm, &mut cc)).collect(),
+        );
+
+        let batch = BigFloat::tanh_many(&values, p, rm, &mut cc);
+        same(
+            &batch,
+            values.iter().map(|v| v.tanh(p, rm, &mut cc)).collect(),
+        );
+
+        assert!(BigFloat::cos_many(&[], p, rm, &mut cc).is_empty());
+    }
+
+    #[test]
+    fn test_ulp() {
+        let p = 128;
+
+        // normal numbers
+        let x = BigFloat::from_u8(3, p);
+        let u = x.ulp(p);
+        assert_eq!(u.exponent(), Some(3 - p as crate::Exponent));
+        assert_eq!(x.ulp(64), BigFloat::from_f64(2f64.powi(-62), p));
+        assert_eq!(x.neg().ulp(p), u);
+
+        let up = x.add(&u, p, RoundingMode::None);
+        let dn = x.sub(&u, p, RoundingMode::None);
+        assert_eq!(x.ulp_diff(&up), Some(1));
+        assert_eq!(up.ulp_diff(&dn), Some(2));
+        assert_eq!(dn.ulp_diff(&up), Some(2));
+        assert_eq!(x.ulp_diff(&x), Some(0));
+
+        // across the binade boundary
+        let two = BigFloat::from_u8(2, p);
+        let below = two.sub(&two.ulp(p + 1), p, RoundingMode::None);
+        assert_eq!(below.ulp_diff(&two), Some(1));
+        assert_eq!(
+            below.ulp_diff(&BigFloat::from_u8(1, p)),
+            Some((1 << (p - 1)) - 1)
+        );
+
+        // different precisions
+        let y = BigFloat::from_u8(3, 64);
+        let y = y.add(&y.ulp(64), 64, RoundingMode::None);
+        assert_eq!(x.ulp_diff(&y), Some(1 << 64));
+
+        // subnormal numbers and zero
+        let mp = BigFloat::min_positive(p);
+        assert_eq!(mp.ulp(p), mp);
+        assert_eq!(BigFloat::new(p).ulp(p), mp);
+        assert_eq!(BigFloat::min_positive_normal(p).ulp(p), mp);
+        assert_eq!(mp.ulp_diff(&BigFloat::new(p)), Some(1));
+        assert_eq!(mp.ulp_diff(&mp.neg()), Some(2));
+        assert_eq!(BigFloat::new(p).ulp_diff(&BigFloat::new(p).neg()), Some(0));
+        assert_eq!(
+            BigFloat::min_positive_normal(p).ulp_diff(&BigFloat::new(p)),
+            Some(1 << (p - 1))
+        );
+
+        // far apart
+        assert_eq!(ONE.ulp_diff(&ONE.neg()), Some(u128::MAX));
+
+        // special values
+        assert!(INF_NEG.ulp(p).is_inf_pos());
+        assert!(NAN.ulp(p).is_nan());
+        assert!(ONE.ulp(0).is_nan());
+        assert_eq!(INF_POS.ulp_diff(&INF_POS), Some(0));
+        assert_eq!(INF_POS.ulp_diff(&INF_NEG), None);
+        assert_eq!(INF_POS.ulp_diff(&ONE), None);
+        assert_eq!(NAN.ulp_diff(&ONE), None);
+    }
+
+    #[test]
+    fn test_frexp_ldexp() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // normal numbers
+        for x in [
+            BigFloat::from_f64(1.0, p),
+            BigFloat::from_f64(-0.375, p),
+            BigFloat::from_f64(12345.678, p),
+            BigFloat::max_value(p),
+            BigFloat::min_positive_normal(p),
+        ] {
+            let (f, e) = x.frexp();
+            assert!(f.abs() >= BigFloat::from_f64(0.5, p) && f.abs() < *ONE);
+            assert_eq!(f.sign(), x.sign());
+            assert_eq!(f.ldexp(e, p, rm), x);
+            assert_eq!(x.ldexp(-e, p, rm), f);
+            assert_eq!(x.scalbn(3, rm), x.mul(&BigFloat::from_u8(8, p), p, rm));
+        }
+
+        // subnormal numbers
+        let mp = BigFloat::min_positive(p);
+        let (f, e) = mp.frexp();
+        assert_eq!(f, BigFloat::from_f64(0.5, p));
+        assert_eq!(e, EXPONENT_MIN as isize - p as isize + 1);
+        assert_eq!(f.ldexp(e, p, rm), mp);
+        assert_eq!(
+            mp.scalbn(p as isize - 1, rm),
+            BigFloat::min_positive_normal(p)
+        );
+        assert_eq!(
+            BigFloat::min_positive_normal(p).scalbn(1 - p as isize, rm),
+            mp
+        );
+
+        // rounding in the subnormal range is the same as for multiplication
+        let x = BigFloat::from_f64(1.75, p);
+        let mut two = BigFloat::from_u8(2, p);
+        two.set_exponent(EXPONENT_MIN + 1);
+        for rm in [
+            RoundingMode::ToEven,
+            RoundingMode::Up,
+            RoundingMode::Down,
+            RoundingMode::ToZero,
+            RoundingMode::FromZero,
+        ] {
+            for k in [0, 1, p as isize - 2, p as isize - 1, p as isize, p as isize + 5] {
+                let y = x.ldexp(EXPONENT_MIN as isize - k, p, rm);
+                let mut d = BigFloat::from_u8(1, p);
+                d.set_exponent(1 - k as Exponent);
+                assert_eq!(y, x.mul(&two, p, rm).mul(&d, p, rm));
+            }
+        }
+
+        // precision and rounding
+        let x = BigFloat::from_u64(u64::MAX, 64);
+        assert_eq!(
+            x.ldexp(-64, 64, rm),
+            x.mul(&BigFloat::from_f64(2f64.powi(-64), 64), 64, rm)
+        );
+        assert_eq!(x.ldexp(1, 128, rm).precision(), Some(128));
+
+        // overflow
+        assert!(BigFloat::max_value(p).scalbn(1, rm).is_inf_pos());
+        assert!(BigFloat::min_value(p).ldexp(isize::MAX, p, rm).is_inf_neg());
+        assert!(ONE.ldexp(isize::MIN, p, rm).is_zero());
+        assert_eq!(
+            ONE.ldexp(isize::MIN, p, RoundingMode::Up),
+            BigFloat::min_positive(p)
+        );
+
+        // special values
+        assert_eq!(BigFloat::new(p).frexp(), (BigFloat::new(p), 0));
+        assert!(BigFloat::new(p).ldexp(10, p, rm).is_zero());
+        assert!(INF_NEG.frexp().0.is_inf_neg());
+        assert!(INF_POS.ldexp(-10, p, rm).is_inf_pos());
+        assert!(NAN.frexp().0.is_nan());
+        assert!(NAN.scalbn(1, rm).is_nan());
+    }
+
+    #[test]
+    fn test_int_rounding() {
+        let p = 128;
+
+        // x, floor, ceil, trunc, round to even
+        let vals = [
+            (2.5, 2.0, 3.0, 2.0, 2.0),
+            (3.5, 3.0, 4.0, 3.0, 4.0),
+            (-2.5, -3.0, -2.0, -2.0, -2.0),
+            (-2.7, -3.0, -2.0, -2.0, -3.0),
+            (0.3, 0.0, 1.0, 0.0, 0.0),
+            (-0.3, -1.0, -0.0, -0.0, -0.0),
+            (0.5, 0.0, 1.0, 0.0, 0.0),
+            (0.75, 0.0, 1.0, 0.0, 1.0),
+            (7.0, 7.0, 7.0, 7.0, 7.0),
+            (1e300, 1e300, 1e300, 1e300, 1e300),
+        ];
+
+        for (x, fl, cl, tr, re) in vals {
+            let x = BigFloat::from_f64(x, p);
+            assert_eq!(x.floor(), BigFloat::from_f64(fl, p));
+            assert_eq!(x.ceil(), BigFloat::from_f64(cl, p));
+            assert_eq!(x.trunc(), BigFloat::from_f64(tr, p));
+            assert_eq!(x.round_int(RoundingMode::ToEven), BigFloat::from_f64(re, p));
+            assert_eq!(x.round_int(RoundingMode::None), x.trunc());
+            assert_eq!(x.round_int(RoundingMode::Down), x.floor());
+            assert_eq!(x.round_int(RoundingMode::Up), x.ceil());
+            assert_eq!(x.round_int(RoundingMode::ToZero), x.trunc());
+            assert_eq!(x.round_int(RoundingMode::ToEven).inexact(), !x.is_int());
+            assert_eq!(x.trunc().mantissa_max_bit_len(), x.mantissa_max_bit_len());
+        }
+
+        // exact results at high precision
+        let p = 512;
+        let x = BigFloat::from_u8(1, p).div(&BigFloat::from_u8(3, p), p, RoundingMode::ToEven);
+        let big = BigFloat::from_u8(1, p).ldexp(400, p, RoundingMode::ToEven);
+        let y = big.add(&x, p, RoundingMode::ToEven);
+        assert_eq!(y.floor(), big);
+        assert_eq!(y.trunc(), big);
+        assert_eq!(y.ceil(), big.add(&ONE, p, RoundingMode::ToEven));
+        assert_eq!(y.round_int(RoundingMode::ToOdd), big);
+        assert_eq!(y.neg().round_int(RoundingMode::FromZero), y.ceil().neg());
+
+        // special values
+        assert!(INF_POS.trunc().is_inf_pos());
+        assert!(INF_NEG.round_int(RoundingMode::ToEven).is_inf_neg());
+        assert!(NAN.trunc().is_nan());
+        assert!(NAN.round_int(RoundingMode::ToEven).is_nan());
+    }
+
+    #[test]
+    fn test_round_to_multiple() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+        let x = BigFloat::from_f64(-7.3, p);
+        let step = BigFloat::from_f64(1.5, p);
+
+        assert_eq!(
+            x.round_to_multiple(&step, p, rm),
+            BigFloat::from_f64(-7.5, p)
+        );
+        assert_eq!(
+            x.round_to_multiple(&step.neg(), p, RoundingMode::Up),
+            BigFloat::from_f64(-6.0, p)
+        );
+
+        // special values
+        assert!(INF_NEG.round_to_multiple(&step, p, rm).is_inf_neg());
+        assert!(x.round_to_multiple(&INF_POS, p, rm).is_nan());
+        assert!(x.round_to_multiple(&BigFloat::new(p), p, rm).is_nan());
+        assert!(INF_POS.round_to_multiple(&BigFloat::new(p), p, rm).is_nan());
+        assert!(NAN.round_to_multiple(&step, p, rm).is_nan());
+        assert!(x.round_to_multiple(&NAN, p, rm).is_nan());
+    }
+
+    #[test]
+    fn test_remquo() {
+        let p = 128;
+        let x = BigFloat::from_f64(-10.25, p);
+        let y = BigFloat::from_f64(3.0, p);
+
+        assert_eq!(x.remquo(&y), (BigFloat::from_f64(-1.25, p), -3));
+        assert_eq!(x.remquo(&y).0, x.rem(&y));
+
+        // special values
+        assert_eq!(x.remquo(&INF_POS), (x.clone(), 0));
+        let (r, q) = INF_POS.remquo(&y);
+        assert!(r.is_nan() && q == 0);
+        let (r, q) = x.remquo(&BigFloat::new(p));
+        assert!(r.is_nan() && q == 0);
+        assert!(NAN.remquo(&y).0.is_nan());
+        assert!(x.remquo(&NAN).0.is_nan());
+    }
+
+    #[test]
+    fn test_float_conv_round() {
+        let p = 128;
+
+        let d1 = BigFloat::from_f64(-0.1, p);
+        assert_eq!(d1.to_f64_round(RoundingMode::ToEven), (-0.1, false));
+        assert_eq!(
+            d1.to_f32_round(RoundingMode::ToEven),
+            (-0.1f64 as f32, true)
+        );
+
+        assert_eq!(
+            INF_NEG.to_f64_round(RoundingMode::Up),
+            (f64::NEG_INFINITY, false)
+        );
+        assert_eq!(
+            INF_POS.to_f32_round(RoundingMode::Down),
+            (f32::INFINITY, false)
+        );
+        assert!(NAN.to_f64_round(RoundingMode::Up).0.is_nan());
+        assert!(NAN.to_f32_round(RoundingMode::Up).0.is_nan());
+    }
+
+    #[test]
+    fn test_int_conv() {
+        let p = 192;
+
+        assert_eq!(BigFloat::from_u128(u128::MAX, p).to_u128(), Some(u128::MAX));
+        assert_eq!(BigFloat::from_i128(i128::MIN, p).to_i128(), Some(i128::MIN));
+        assert_eq!(BigFloat::from_i128(i128::MAX, p).to_i128(), Some(i128::MAX));
+        assert_eq!(BigFloat::new(p).neg().to_u128(), Some(0));
+
+        // out of range
+        let d1 = BigFloat::from_u128(u128::MAX, p).add(&ONE, p, RoundingMode::None);
+        assert_eq!(d1.to_u128(), None);
+        let d1 = BigFloat::from_i128(i128::MIN, p).sub(&ONE, p, RoundingMode::None);
+        assert_eq!(d1.to_i128(), None);
+
+        // fractional part
+        let d1 = BigFloat::from_u128(u128::MAX, p).sub(
+            &BigFloat::from_f64(0.5, p),
+            p,
+            RoundingMode::None,
+        );
+        assert_eq!(d1.to_u128(), None);
+        assert_eq!(d1.to_i128(), None);
+        assert!(!d1.is_integer());
+        assert!(BigFloat::from_u128(u128::MAX, p).is_integer());
+
+        for d1 in [INF_POS, INF_NEG, NAN] {
+            assert_eq!(d1.to_u128(), None);
+            assert_eq!(d1.to_i128(), None);
+            assert!(!d1.is_integer());
+        }
+    }
+
+    #[test]
+    fn test_words_conv() {
+        let p = 192;
+        let d1 = BigFloat::from_word(1, p)
+            .div(&BigFloat::from_word(3, p), p, RoundingMode::ToEven)
+            .neg();
+
+        let (m, s, e) = d1.to_words_le().unwrap();
+        assert_eq!(
+            m,
+            [0xaaaaaaaaaaaaaaab, 0xaaaaaaaaaaaaaaaa, 0xaaaaaaaaaaaaaaaa]
+        );
+        assert_eq!((s, e), (Sign::Neg, -1));
+        let d2 = BigFloat::from_words_le(&m, s, e);
+        assert_eq!(d2, d1);
+        assert_eq!(d2.mantissa_max_bit_len(), Some(p));
+
+        let (mut m2, s, e) = d1.to_words_be().unwrap();
+        assert_eq!(BigFloat::from_words_be(&m2, s, e), d1);
+        m2.reverse();
+        assert_eq!(m2, m);
+
+        // the mantissa is normalized
+        let d2 = BigFloat::from_words_be(&[0, 3], Sign::Pos, 128);
+        assert_eq!(d2, BigFloat::from_word(3, p));
+        assert_eq!(
+            d2.to_words_be().unwrap(),
+            (vec![0xC000000000000000, 0], Sign::Pos, 2)
+        );
+
+        for d1 in [INF_POS, INF_NEG, NAN] {
+            assert_eq!(d1.to_words_le(), None);
+            assert_eq!(d1.to_words_be(), None);
+        }
+    }
+
+    #[test]
+    fn test_try_parse() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        let d1 = BigFloat::try_parse("-1.25e-1", Radix::Dec, 64, rm, &mut cc).unwrap();
+        assert_eq!(d1, BigFloat::from_f64(-0.125, 64));
+        let d1 = BigFloat::try_parse("-inf", Radix::Bin, 64, rm, &mut cc).unwrap();
+        assert!(d1.is_inf_neg());
+        let d1 = BigFloat::try_parse("NaN", Radix::Hex, 64, rm, &mut cc).unwrap();
+        assert!(d1.is_nan());
+        let d1 = BigFloat::try_parse(&format!("1e+{}", EXPONENT_MAX), Radix::Dec, 64, rm, &mut cc)
+            .unwrap();
+        assert!(d1.is_inf_pos());
+
+        let err = BigFloat::try_parse("12.3.4", Radix::Dec, 64, rm, &mut cc).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::UnexpectedChar('.'));
+        assert_eq!(err.position(), 4);
+        assert_eq!(Error::from(err), Error::InvalidArgument);
+
+        let err = BigFloat::try_parse("1.0e", Radix::Dec, 64, rm, &mut cc).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidExponent);
+        assert_eq!(err.position(), 4);
+
+        let err = BigFloat::try_parse("0.18", Radix::Oct, 64, rm, &mut cc).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidDigit('8'));
+        assert_eq!(err.position(), 3);
+
+        // parse returns NaN for invalid input
+        let d1 = BigFloat::parse("1.5 ", Radix::Dec, 64, rm, &mut cc);
+        assert!(d1.is_nan());
+        assert_eq!(d1.err(), Some(Error::InvalidArgument));
+    }
+
+    #[test]
+    fn test_parse_base() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        let d1 = BigFloat::parse_base("-1.1e+10", 3, 64, rm, &mut cc);
+        assert_eq!(d1, BigFloat::from_f64(-36.0, 64));
+        let d1 = BigFloat::parse_base("b.6E+1", 12, 64, rm, &mut cc);
+        assert_eq!(d1, BigFloat::from_f64(138.0, 64));
+        let d1 = BigFloat::parse_base("z.i_E+1", 36, 64, rm, &mut cc);
+        assert_eq!(d1, BigFloat::from_f64(1278.0, 64));
+        let d1 = BigFloat::parse_base("1e1", 36, 64, rm, &mut cc);
+        assert_eq!(d1, BigFloat::from_f64(1801.0, 64));
+
+        // inf and nan
+        for base in [3, 20, 36] {
+            assert!(BigFloat::parse_base("-Inf", base, 64, rm, &mut cc).is_inf_neg());
+            assert!(BigFloat::parse_base("NaN", base, 64, rm, &mut cc).is_nan());
+        }
+        let d1 = BigFloat::parse_base("nan1", 36, 64, rm, &mut cc);
+        assert!(!d1.is_nan());
+
+        // errors
+        let err = BigFloat::try_parse_base("1.2", 2, 64, rm, &mut cc).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidDigit('2'));
+        assert_eq!(err.position(), 2);
+        let err = BigFloat::try_parse_base("1.c", 12, 64, rm, &mut cc).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidDigit('c'));
+        let err = BigFloat::try_parse_base("1.g", 12, 64, rm, &mut cc).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::UnexpectedChar('g'));
+        let err = BigFloat::try_parse_base("1.u", 30, 64, rm, &mut cc).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidDigit('u'));
+        let err = BigFloat::try_parse_base("1_e", 20, 64, rm, &mut cc).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidExponent);
+        for base in [0, 1, 37] {
+            let err = BigFloat::try_parse_base("1", base, 64, rm, &mut cc).unwrap_err();
+            assert_eq!(
+                err.kind(),
+                ParseErrorKind::Conversion(Error::InvalidArgument)
+            );
+            assert_eq!(err.position(), 0);
+            assert_eq!(
+                BigFloat::from_u8(1, 64).format_base(base, rm, &mut cc),
+                Err(Error::InvalidArgument)
+            );
+        }
+
+        // the same output as format
+        let d1 = BigFloat::from_f64(-123.456, 64);
+        for rdx in [Radix::Bin, Radix::Oct, Radix::Dec, Radix::Hex] {
+            assert_eq!(
+                d1.format_base(rdx as u32, rm, &mut cc),
+                d1.format(rdx, rm, &mut cc)
+            );
+        }
+
+        assert_eq!(
+            BigFloat::from_f64(1278.0, 64).format_base(36, rm, &mut cc),
+            Ok("Z.I_e+1".into())
+        );
+        assert_eq!(
+            BigFloat::from_f64(-0.75, 64).format_base(3, rm, &mut cc),
+            Ok("-2.02020202020202020202020202020202020202021e-1".into())
+        );
+        assert_eq!(INF_NEG.format_base(5, rm, &mut cc), Ok("-Inf".into()));
+
+        // round trip
+        #[cfg(feature = "random")]
+        for base in [3, 4, 12, 32, 36] {
+            for _ in 0..10 {
+                let d1 = BigFloat::random_normal(128, -1000, 1000);
+                let s = d1.format_base(base, rm, &mut cc).unwrap();
+                let d2 = BigFloat::parse_base(&s, base, 128, rm, &mut cc);
+                assert_eq!(d1, d2, "{} {}", base, s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_with() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        let mut opts = FormatOptions {
+            frac_digits: Some(5),
+            group_size: 3,
+            group_separator: ' ',
+            decimal_separator: ',',
+        };
+
+        let d1 = BigFloat::parse("1234567.89012", Radix::Dec, 128, rm, &mut cc);
+        let s = d1.format_with(Radix::Dec, rm, &opts, &mut cc).unwrap();
+        assert_eq!(s, "1 234 567,890 12");
+        let s = d1
+            .neg()
+            .format_with(Radix::Dec, rm, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(s, "-1 234 567,890 12");
+
+        // rounding
+        opts.frac_digits = Some(2);
+        let s = d1.format_with(Radix::Dec, rm, &opts, &mut cc).unwrap();
+        assert_eq!(s, "1 234 567,89");
+        let s = d1
+            .format_with(Radix::Dec, RoundingMode::Up, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(s, "1 234 567,90");
+        let d1 = BigFloat::from_f64(0.125, 64);
+        let s = d1.format_with(Radix::Dec, rm, &opts, &mut cc).unwrap();
+        assert_eq!(s, "0,12");
+        let s = d1
+            .neg()
+            .format_with(Radix::Dec, RoundingMode::Down, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(s, "-0,13");
+        let s = BigFloat::from_f64(999.999, 64)
+            .format_with(Radix::Dec, rm, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(s, "1 000,00");
+
+        // no fractional part
+        opts.frac_digits = Some(0);
+        let s = BigFloat::from_f64(-1234.5, 64)
+            .format_with(Radix::Dec, rm, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(s, "-1 234");
+        let s = BigFloat::new(64)
+            .format_with(Radix::Dec, rm, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(s, "0");
+
+        // other radices
+        opts.frac_digits = Some(4);
+        opts.group_size = 4;
+        opts.group_separator = '_';
+        opts.decimal_separator = '.';
+        let d1 = BigFloat::from_f64(-166.8125, 64);
+        let s = d1.format_with(Radix::Bin, rm, &opts, &mut cc).unwrap();
+        assert_eq!(s, "-1010_0110.1101");
+        let s = d1.format_with(Radix::Hex, rm, &opts, &mut cc).unwrap();
+        assert_eq!(s, "-A6.D000");
+        opts.frac_digits = Some(1);
+        let s = d1.format_with(Radix::Oct, rm, &opts, &mut cc).unwrap();
+        assert_eq!(s, "-246.6");
+
+        // scientific notation
+        opts.frac_digits = None;
+        opts.group_size = 3;
+        opts.group_separator = '\u{202F}';
+        opts.decimal_separator = ',';
+        let s = BigFloat::from_f64(1234.5, 64)
+            .format_with(Radix::Dec, rm, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(s, "1,234\u{202F}5e+3");
+        let s = BigFloat::new(64)
+            .format_with(Radix::Dec, rm, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(s, "0,0");
+
+        // the default options
+        let d1 = BigFloat::from_word(1, 128).div(&BigFloat::from_word(3, 128), 128, rm);
+        for rdx in [Radix::Bin, Radix::Oct, Radix::Dec, Radix::Hex] {
+            for d1 in [d1.clone(), BigFloat::min_positive(128), BigFloat::new(64)] {
+                assert_eq!(
+                    d1.format_with(rdx, rm, &FormatOptions::default(), &mut cc),
+                    d1.format(rdx, rm, &mut cc)
+                );
+            }
+        }
+
+        // special values
+        let s = INF_NEG.format_with(Radix::Dec, rm, &opts, &mut cc).unwrap();
+        assert_eq!(s, "-Inf");
+        let s = NAN.format_with(Radix::Dec, rm, &opts, &mut cc).unwrap();
+        assert_eq!(s, "NaN");
+    }
+
+    #[test]
+    fn test_write_str() {
+        use core::fmt::Write;
+
+        // writer with a fixed-size buffer
+        struct Buf {
+            b: [u8; 32],
+            l: usize,
+        }
+
+        impl Write for Buf {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let b = s.as_bytes();
+                if self.l + b.len() > self.b.len() {
+                    return Err(core::fmt::Error);
+                }
+                self.b[self.l..self.l + b.len()].copy_from_slice(b);
+                self.l += b.len();
+                Ok(())
+            }
+        }
+
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+        let opts = FormatOptions {
+            frac_digits: Some(3),
+            group_size: 3,
+            group_separator: '.',
+            decimal_separator: ',',
+        };
+
+        let mut w = Buf { b: [0; 32], l: 0 };
+        let d1 = BigFloat::from_f64(-1234567.25, 64);
+        d1.write_str(&mut w, Radix::Dec, rm, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(&w.b[..w.l], b"-1.234.567,250");
+
+        INF_POS
+            .write_str(&mut w, Radix::Dec, rm, &opts, &mut cc)
+            .unwrap();
+        assert_eq!(&w.b[..w.l], b"-1.234.567,250Inf");
+
+        // the output is the same as the output of format_with
+        let d1 = BigFloat::from_word(1, 128).div(&BigFloat::from_word(3, 128), 128, rm);
+        for opts in [FormatOptions::default(), opts] {
+            for rdx in [Radix::Bin, Radix::Oct, Radix::Dec, Radix::Hex] {
+                let mut s = String::new();
+                d1.write_str(&mut s, rdx, rm, &opts, &mut cc).unwrap();
+                assert_eq!(s, d1.format_with(rdx, rm, &opts, &mut cc).unwrap());
+            }
+        }
+
+        // errors of the writer are propagated
+        let mut w = Buf { b: [0; 32], l: 0 };
+        assert!(d1
+            .write_str(&mut w, Radix::Bin, rm, &FormatOptions::default(), &mut cc)
+            .is_err());
+    }
+
+    #[test]
+    fn test_hex_float() {
+        let mut cc = Consts::new().unwrap();
+
+        let d1 = BigFloat::from_f64(-12.5, 64);
+        assert_eq!(d1.format_hex_float().unwrap(), "-0x1.9p+3");
+        assert_eq!(ONE.format_hex_float().unwrap(), "0x1p+0");
+        assert_eq!(
+            BigFloat::from_f64(0.1, 64).format_hex_float().unwrap(),
+            "0x1.999999999999ap-4"
+        );
+        assert_eq!(
+            BigFloat::new(64).neg().format_hex_float().unwrap(),
+            "-0x0p+0"
+        );
+        assert_eq!(INF_NEG.format_hex_float().unwrap(), "-Inf");
+        assert_eq!(NAN.format_hex_float().unwrap(), "NaN");
+
+        // subnormal
+        let d1 = BigFloat::min_positive(128);
+        let s = d1.format_hex_float().unwrap();
+        assert_eq!(
+            s,
+            format!("0x1p-{}", EXPONENT_MIN.unsigned_abs() as usize + 128)
+        );
+
+        // round trip
+        let p = 192;
+        let d1 = BigFloat::from_word(1, p).div(&BigFloat::from_word(3, p), p, RoundingMode::ToEven);
+        for d1 in [d1.clone(), d1.neg(), BigFloat::max_value(p)] {
+            let s = d1.format_hex_float().unwrap();
+            let d2 = BigFloat::parse(&s, Radix::Dec, p, RoundingMode::None, &mut cc);
+            assert_eq!(d2, d1);
+            let d2 = BigFloat::parse(&s, Radix::Hex, usize::MAX, RoundingMode::None, &mut cc);
+            assert_eq!(d2, d1);
+        }
+
+        // subnormal numbers are rounded to the precision of the result
+        for p in [64, 192] {
+            let d1 = BigFloat::min_positive(p).mul(&TWO, p, RoundingMode::None);
+            let s = d1.format_hex_float().unwrap();
+            let d2 = BigFloat::parse(&s, Radix::Dec, p, RoundingMode::None, &mut cc);
+            assert_eq!(d2, d1);
+        }
+        let s = BigFloat::min_positive(192).format_hex_float().unwrap();
+        let d2 = BigFloat::parse(&s, Radix::Dec, usize::MAX, RoundingMode::Down, &mut cc);
+        assert!(d2.is_zero());
+
+        // parsing with rounding
+        let d1 = BigFloat::parse(
+            "0x1.00000000000000018p+0",
+            Radix::Dec,
+            64,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        assert_eq!(d1.format_hex_float().unwrap(), "0x1.0000000000000002p+0");
+        assert!(d1.inexact());
+        let d1 = BigFloat::parse(
+            "0x1.00000000000000008p+0",
+            Radix::Dec,
+            64,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        assert_eq!(d1, *ONE);
+        let d1 = BigFloat::parse(
+            "0x1.8p-1",
+            Radix::Dec,
+            usize::MAX,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        assert_eq!(d1, BigFloat::from_f64(0.75, 64));
+        let d1 = BigFloat::parse("0x3p-2", Radix::Dec, 64, RoundingMode::ToEven, &mut cc);
+        assert_eq!(d1, BigFloat::from_f64(0.75, 64));
+        let d1 = BigFloat::parse(
+            &format!("0x1p+{}", EXPONENT_MAX),
+            Radix::Dec,
+            64,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        assert!(d1.is_inf_pos());
+
+        #[cfg(feature = "std")]
+        assert_eq!(
+            BigFloat::from_str("-0x1.8p+1").unwrap(),
+            BigFloat::from_f64(-3.0, 64)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_str() {
+        fn parse_all<T: FromStr>(v: &[&str]) -> Result<Vec<T>, T::Err> {
+            v.iter().map(|s| s.parse()).collect()
+        }
+
+        // the precision is determined from the input by default
+        assert_eq!(
+            BigFloat::from_str_params(),
+            (usize::MAX, RoundingMode::ToEven)
+        );
+        let v: Vec<BigFloat> = parse_all(&["1.25", "-Inf", "1e+2"]).unwrap();
+        assert_eq!(v[0], BigFloat::from_f64(1.25, 64));
+        assert!(v[1].is_inf_neg());
+        assert_eq!(v[2], BigFloat::from_u8(100, 64));
+        assert!(parse_all::<BigFloat>(&["1", "1..0"]).is_err());
+
+        // configured precision and rounding mode
+        BigFloat::set_from_str_params(64, RoundingMode::Down);
+        let d1: BigFloat = "0.1".parse().unwrap();
+        BigFloat::set_from_str_params(64, RoundingMode::Up);
+        let d2: BigFloat = "0.1".parse().unwrap();
+        assert_eq!(d1.mantissa_max_bit_len(), Some(64));
+        assert!(d1 < d2);
+        assert!(d1.inexact() && d2.inexact());
+
+        // the parameters are local to a thread
+        std::thread::spawn(|| {
+            assert_eq!(
+                BigFloat::from_str_params(),
+                (usize::MAX, RoundingMode::ToEven)
+            );
+        })
+        .join()
+        .unwrap();
+
+        BigFloat::set_from_str_params(usize::MAX, RoundingMode::ToEven);
+    }
+
     #[cfg(feature = "std")]
+    #[test]
+    fn test_op_params() {
+        let d1 = BigFloat::from_word(1, 64);
+        let d2 = BigFloat::from_word(3, 128);
+
+        // the larger precision of the operands by default
+        assert_eq!(BigFloat::op_params(), (usize::MAX, RoundingMode::ToEven));
+        let d3 = &d1 / &d2;
+        assert_eq!(d3.mantissa_max_bit_len(), Some(128));
+        assert_eq!(d3, d1.div(&d2, 128, RoundingMode::ToEven));
+        assert!((INF_POS * NAN).is_nan());
+        assert_eq!((&d1 + INF_NEG), INF_NEG);
+
+        // configured precision and rounding mode
+        BigFloat::set_op_params(320, RoundingMode::Down);
+        let lo = &d1 / &d2;
+        assert_eq!(lo.mantissa_max_bit_len(), Some(320));
+        assert_eq!(lo, d1.div(&d2, 320, RoundingMode::Down));
+
+        BigFloat::set_op_params(64, RoundingMode::Up);
+        let mut hi = d1.clone();
+        hi /= &d2;
+        assert_eq!(hi.mantissa_max_bit_len(), Some(64));
+        assert!(lo < hi);
+        assert_eq!(&d2 - &d1, BigFloat::from_word(2, 64));
+
+        // the remainder is exact
+        let r = BigFloat::from_word(7, 128) % &d2;
+        assert_eq!(r.mantissa_max_bit_len(), Some(128));
+        assert_eq!(r, d1);
+
+        // the parameters are local to a thread
+        std::thread::spawn(|| {
+            assert_eq!(BigFloat::op_params(), (usize::MAX, RoundingMode::ToEven));
+        })
+        .join()
+        .unwrap();
+
+        BigFloat::set_op_params(usize::MAX, RoundingMode::ToEven);
+    }
+
     #[test]
     fn test_ops_std() {
         let mut cc = Consts::new().unwrap();
@@ -2337,8 +5869,101 @@ mod tests {
         let d1str = format!("{}", NAN);
         assert_eq!(d1str, "NaN");
 
-        assert!(BigFloat::from_str("abc").is_ok());
-        assert!(BigFloat::from_str("abc").unwrap().is_nan());
+        let d1 = BigFloat::from_f64(-26.75, 64);
+        assert_eq!(format!("{:b}", d1), "-1.101011e+100");
+        assert_eq!(format!("{:o}", d1), "-3.26e+1");
+        assert_eq!(format!("{:x}", d1), "-1.ac_e+1");
+        assert_eq!(format!("{:X}", d1), "-1.AC_e+1");
+        assert_eq!(format!("{:#x}", d1), "-0x1.ac_e+1");
+        assert_eq!(format!("{:+#14x}", d1.abs()), "   +0x1.ac_e+1");
+        assert_eq!(format!("{:#o}", d1.abs()), "0o3.26e+1");
+        assert_eq!(format!("{:#b}", *ONE), "0b1.e+0");
+        assert_eq!(format!("{:>6x}", INF_NEG), "  -Inf");
+        assert_eq!(format!("{:#x}", NAN), "NaN");
+
+        // precision
+        let d1 = BigFloat::from_f64(-26.75, 64);
+        assert_eq!(format!("{:.1}", d1), "-26.8");
+        assert_eq!(format!("{:.0}", d1), "-27");
+        assert_eq!(format!("{:.4}", d1), "-26.7500");
+        assert_eq!(format!("{:+10.2}", d1.abs()), "    +26.75");
+        assert_eq!(format!("{:010.2}", d1), "-000026.75");
+        assert_eq!(format!("{:.3e}", d1), "-2.675e+1");
+        assert_eq!(format!("{:.2e}", d1), "-2.68e+1");
+        assert_eq!(format!("{:.0E}", d1), "-3E+1");
+        assert_eq!(format!("{:E}", d1), "-2.675E+1");
+        assert_eq!(format!("{:e}", d1), format!("{}", d1));
+        assert_eq!(format!("{:>8.2}", INF_NEG), "    -Inf");
+        assert_eq!(format!("{:.2e}", NAN), "NaN");
+
+        // ties are rounded to even
+        assert_eq!(format!("{:.2}", BigFloat::from_f64(0.125, 64)), "0.12");
+        assert_eq!(format!("{:.2}", BigFloat::from_f64(0.375, 64)), "0.38");
+        assert_eq!(format!("{:.1e}", BigFloat::from_f64(0.125, 64)), "1.2e-1");
+        assert_eq!(format!("{:.0}", BigFloat::from_f64(0.5, 64)), "0");
+        assert_eq!(format!("{:.0}", BigFloat::from_f64(2.5, 64)), "2");
+        assert_eq!(format!("{:.1e}", BigFloat::from_f64(2.5e20, 64)), "2.5e+20");
+
+        // carry to the next decimal order
+        assert_eq!(format!("{:.2}", BigFloat::from_f64(9.999, 64)), "10.00");
+        assert_eq!(format!("{:.2e}", BigFloat::from_f64(9.999, 64)), "1.00e+1");
+
+        // small and large numbers
+        assert_eq!(format!("{:.3}", BigFloat::from_f64(0.0004, 64)), "0.000");
+        assert_eq!(format!("{:.3}", BigFloat::from_f64(-0.0006, 64)), "-0.001");
+        assert_eq!(format!("{:.2}", BigFloat::new(64)), "0.00");
+        assert_eq!(format!("{:.2e}", BigFloat::new(64)), "0.00e+0");
+        assert_eq!(
+            format!("{:.1}", BigFloat::from_u128(1 << 100, 128)),
+            "1267650600228229401496703205376.0"
+        );
+        assert_eq!(
+            format!("{:.3e}", BigFloat::from_u128(1 << 100, 128)),
+            "1.268e+30"
+        );
+
+        // correct rounding
+        let d1 = BigFloat::from_word(1, 256).div(
+            &BigFloat::from_word(3, 256),
+            256,
+            RoundingMode::ToEven,
+        );
+        assert_eq!(format!("{:.30}", d1), "0.333333333333333333333333333333");
+        assert_eq!(
+            format!("{:.30}", d1.mul(&TWO, 256, RoundingMode::ToEven)),
+            "0.666666666666666666666666666667"
+        );
+        assert_eq!(format!("{:.5e}", d1.neg()), "-3.33333e-1");
+        let d1 = BigFloat::from_f64(0.1, 64);
+        assert_eq!(format!("{:.20}", d1), "0.10000000000000000555");
+        assert_eq!(format!("{:.25e}", d1), "1.0000000000000000555111512e-1");
+
+        assert_eq!(BigFloat::from_str("abc"), Err(Error::InvalidArgument));
+        assert!(BigFloat::from_str("nan").unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_number_conv() {
+        // conversions in both directions move the mantissa without copying
+        let n = BigFloatNumber::from_word(123, DEFAULT_P).unwrap();
+        let ptr = n.mantissa().digits().as_ptr();
+
+        let mut d1: BigFloat = n.into();
+        assert_eq!(d1.as_number().unwrap().mantissa().digits().as_ptr(), ptr);
+
+        d1.as_number_mut().unwrap().set_exponent(10);
+        assert_eq!(d1.exponent(), Some(10));
+
+        let n = BigFloatNumber::try_from(d1).unwrap();
+        assert_eq!(n.mantissa().digits().as_ptr(), ptr);
+        assert_eq!(n.exponent(), 10);
+
+        // Inf and NaN are returned back
+        for d1 in [INF_POS, INF_NEG, NAN] {
+            assert!(d1.as_number().is_none());
+            let d2 = BigFloatNumber::try_from(d1.clone()).unwrap_err();
+            assert!(d2.is_nan() && d1.is_nan() || d2 == d1);
+        }
     }
 
     #[test]
@@ -2479,11 +6104,11 @@ mod rand_tests {
         for _ in 0..1000 {
             let p = rand::random::<usize>() % 1000 + DEFAULT_P;
             let exp_from;
-            #[cfg(not(target_arch = "x86"))]
+            #[cfg(not(any(target_arch = "x86", feature = "exp64")))]
             {
                 exp_from = rand::random::<Exponent>().abs();
             }
-            #[cfg(target_arch = "x86")]
+            #[cfg(any(target_arch = "x86", feature = "exp64"))]
             {
                 use crate::defs::EXPONENT_MIN;
                 exp_from =
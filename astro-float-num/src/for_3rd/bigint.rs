@@ -0,0 +1,214 @@
+//! Conversion between BigFloat and integers of num-bigint.
+
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::defs::EXPONENT_MAX;
+use crate::defs::WORD_BIT_SIZE;
+use crate::ext::BigFloat;
+use crate::ext::FromExt;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::Exponent;
+use crate::Sign;
+use num_bigint::BigInt;
+use num_bigint::BigUint;
+
+impl BigFloatNumber {
+    /// Constructs a number with precision `p` from the unsigned integer `v` using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: `v` is too large for the range of exponent values.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn from_biguint(v: &BigUint, p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        Self::from_big_parts(v, Sign::Pos, p, rm)
+    }
+
+    /// Constructs a number with precision `p` from the signed integer `v` using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: `v` is too large for the range of exponent values.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn from_bigint(v: &BigInt, p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        let s = if v.sign() == num_bigint::Sign::Minus { Sign::Neg } else { Sign::Pos };
+        Self::from_big_parts(v.magnitude(), s, p, rm)
+    }
+
+    /// Returns the value of `self` as BigUint if `self` is a non-negative integer, otherwise returns None.
+    /// The value of `self` is considered regardless of the inexact flag.
+    pub fn to_biguint(&self) -> Option<BigUint> {
+        if !self.is_int() || (self.is_negative() && !self.is_zero()) {
+            return None;
+        }
+
+        Some(self.int_abs_as_biguint())
+    }
+
+    /// Returns the value of `self` as BigInt if `self` is an integer, otherwise returns None.
+    /// The value of `self` is considered regardless of the inexact flag.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        if !self.is_int() {
+            return None;
+        }
+
+        let s = if self.is_negative() {
+            num_bigint::Sign::Minus
+        } else {
+            num_bigint::Sign::Plus
+        };
+
+        Some(BigInt::from_biguint(s, self.int_abs_as_biguint()))
+    }
+
+    fn from_big_parts(v: &BigUint, s: Sign, p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        #[cfg(not(target_arch = "x86"))]
+        let words = v.to_u64_digits();
+
+        #[cfg(target_arch = "x86")]
+        let words = v.to_u32_digits();
+
+        if words.is_empty() {
+            let mut ret = Self::new(p)?;
+            ret.set_sign(s);
+            return Ok(ret);
+        }
+
+        // the value of the words as a fraction is v * 2^-(words.len() * WORD_BIT_SIZE)
+        let e = words.len() * WORD_BIT_SIZE;
+        if e > EXPONENT_MAX as usize {
+            return Err(Error::ExponentOverflow(s));
+        }
+
+        let mut ret = Self::from_words(&words, s, e as Exponent)?;
+        ret.set_precision(p, rm)?;
+
+        Ok(ret)
+    }
+
+    // Returns the mantissa of `self` as an integer.
+    #[allow(clippy::unnecessary_cast)] // Word can be u32
+    pub(crate) fn mantissa_as_biguint(&self) -> BigUint {
+        let mut digits = Vec::with_capacity(self.mantissa().len() * WORD_BIT_SIZE / 32);
+        for w in self.mantissa().digits() {
+            for i in 0..WORD_BIT_SIZE / 32 {
+                digits.push((*w >> (i * 32)) as u32);
+            }
+        }
+
+        BigUint::new(digits)
+    }
+
+    // Returns the integer part of |self|.
+    fn int_abs_as_biguint(&self) -> BigUint {
+        if self.exponent() <= 0 {
+            return BigUint::default();
+        }
+
+        let m = self.mantissa_as_biguint();
+        let e = self.exponent() as usize;
+        let n = self.mantissa_max_bit_len();
+
+        if e < n {
+            m >> (n - e)
+        } else {
+            m << (e - n)
+        }
+    }
+}
+
+impl BigFloat {
+    /// Constructs a number with precision `p` from the unsigned integer `v` using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn from_biguint(v: &BigUint, p: usize, rm: RoundingMode) -> Self {
+        Self::result_to_ext(BigFloatNumber::from_biguint(v, p, rm), false, true)
+    }
+
+    /// Constructs a number with precision `p` from the signed integer `v` using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn from_bigint(v: &BigInt, p: usize, rm: RoundingMode) -> Self {
+        Self::result_to_ext(BigFloatNumber::from_bigint(v, p, rm), false, true)
+    }
+
+    /// Returns the value of `self` as BigUint if `self` is a non-negative integer, otherwise returns None.
+    /// The function returns None if `self` is Inf or NaN.
+    pub fn to_biguint(&self) -> Option<BigUint> {
+        self.as_number().and_then(|v| v.to_biguint())
+    }
+
+    /// Returns the value of `self` as BigInt if `self` is an integer, otherwise returns None.
+    /// The function returns None if `self` is Inf or NaN.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        self.as_number().and_then(|v| v.to_bigint())
+    }
+}
+
+impl FromExt<&BigUint> for BigFloat {
+    fn from_ext(v: &BigUint, p: usize, rm: RoundingMode, _cc: &mut Consts) -> Self {
+        BigFloat::from_biguint(v, p, rm)
+    }
+}
+
+impl FromExt<&BigInt> for BigFloat {
+    fn from_ext(v: &BigInt, p: usize, rm: RoundingMode, _cc: &mut Consts) -> Self {
+        BigFloat::from_bigint(v, p, rm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::INF_POS;
+    use crate::NAN;
+
+    #[test]
+    fn test_bigint() {
+        let p = 128;
+        let mut cc = Consts::new().unwrap();
+
+        for v in [0i128, 1, -1, 12345, i128::MAX, i128::MIN, 1 << 100] {
+            let b = BigInt::from(v);
+            let d1 = BigFloat::from_bigint(&b, p, RoundingMode::ToEven);
+            assert_eq!(d1, BigFloat::from_i128(v, p));
+            assert_eq!(d1.to_bigint(), Some(b.clone()));
+            assert_eq!(d1.to_biguint(), u128::try_from(v).ok().map(BigUint::from));
+            assert_eq!(BigFloat::from_ext(&b, p, RoundingMode::ToEven, &mut cc), d1);
+        }
+
+        // rounding of a large integer
+        let b = (BigUint::from(1u8) << 200usize) - 1u8;
+        let d1 = BigFloat::from_biguint(&b, p, RoundingMode::Down);
+        assert!(d1.inexact());
+        assert_eq!(
+            d1.to_biguint(),
+            Some(((BigUint::from(1u8) << 128usize) - 1u8) << 72usize)
+        );
+        let d1 = BigFloat::from_biguint(&b, p, RoundingMode::Up);
+        assert_eq!(d1.to_biguint(), Some(BigUint::from(1u8) << 200usize));
+        let d1 = BigFloat::from_biguint(&b, 256, RoundingMode::Up);
+        assert!(!d1.inexact());
+        assert_eq!(d1.to_biguint(), Some(b));
+
+        // negative zero
+        let d1 = BigFloat::new(p).neg();
+        assert_eq!(d1.to_biguint(), Some(BigUint::default()));
+        assert_eq!(d1.to_bigint(), Some(BigInt::default()));
+
+        // not an integer
+        let d1 = BigFloat::from_f64(-2.5, p);
+        assert_eq!(d1.to_bigint(), None);
+        assert_eq!(d1.to_biguint(), None);
+        assert_eq!(INF_POS.to_bigint(), None);
+        assert_eq!(NAN.to_biguint(), None);
+
+        // incorrect precision
+        assert!(BigFloat::from_bigint(&BigInt::from(1), usize::MAX, RoundingMode::ToEven).is_nan());
+    }
+}
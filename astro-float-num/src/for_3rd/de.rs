@@ -13,7 +13,12 @@ pub struct BigFloatVisitor {}
 
 impl<'de> Deserialize<'de> for BigFloat {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        deserializer.deserialize_any(BigFloatVisitor {})
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BigFloatVisitor {})
+        } else {
+            // binary formats don't describe the type of the data
+            deserializer.deserialize_str(BigFloatVisitor {})
+        }
     }
 }
 
@@ -55,13 +60,6 @@ impl<'de> Visitor<'de> for BigFloatVisitor {
     fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
         self.visit_str(&v)
     }
-
-    // lossless conversion
-    // (&[Word], usize, Sign, Exponent)
-    // (s * len, s    , 1   , 1       )
-    // fn visit_bytes<E: Error>(self, _: &[u8]) -> Result<Self::Value, E> {
-    //     todo!()
-    // }
 }
 
 #[cfg(test)]
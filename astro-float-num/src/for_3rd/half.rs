@@ -0,0 +1,114 @@
+//! Conversion between BigFloat and half-precision floating point numbers of the crate half.
+
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::defs::DEFAULT_P;
+use crate::ext::BigFloat;
+use crate::num::BigFloatNumber;
+use half::f16;
+
+impl BigFloatNumber {
+    /// Converts `self` to f16 using the rounding mode `rm`. `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the conversion is inexact.
+    /// The value of `self` is considered regardless of the inexact flag.
+    /// Zero is converted to zero of the same sign, and values outside of the range of f16
+    /// are converted to the infinity or to the largest finite f16 depending on the rounding mode.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    pub fn to_f16_round(&self, rm: RoundingMode) -> Result<(f16, bool), Error> {
+        // the result is representable in f16 exactly
+        self.to_binary_float(11, 16, f16::MAX.to_f64(), rm)
+            .map(|(f, inexact)| (f16::from_f64(f), inexact))
+    }
+}
+
+impl BigFloat {
+    /// Constructs a number with precision `p` from f16 value.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn from_f16(f: f16, p: usize) -> Self {
+        Self::result_to_ext(BigFloatNumber::from_f64(p, f.to_f64()), false, true)
+    }
+
+    /// Converts `self` to f16 using the rounding mode `rm`. `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the conversion is inexact.
+    /// Inf is converted to the infinity of the same sign, and NaN is converted to NaN.
+    /// The function returns NaN if memory allocation fails.
+    pub fn to_f16_round(&self, rm: RoundingMode) -> (f16, bool) {
+        if let Some(v) = self.as_number() {
+            v.to_f16_round(rm).unwrap_or((f16::NAN, true))
+        } else if self.is_inf_pos() {
+            (f16::INFINITY, false)
+        } else if self.is_inf_neg() {
+            (f16::NEG_INFINITY, false)
+        } else {
+            (f16::NAN, false)
+        }
+    }
+}
+
+impl From<f16> for BigFloat {
+    fn from(v: f16) -> Self {
+        BigFloat::from_f16(v, DEFAULT_P)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::INF_NEG;
+    use crate::NAN;
+
+    #[test]
+    fn test_f16() {
+        let p = 64;
+
+        // all f16 values are converted exactly in both directions
+        for bits in 0..=u16::MAX {
+            let f = f16::from_bits(bits);
+            let d1 = BigFloat::from_f16(f, p);
+            if f.is_nan() {
+                assert!(d1.is_nan());
+                continue;
+            }
+            let (f2, inexact) = d1.to_f16_round(RoundingMode::ToEven);
+            assert!(!inexact);
+            if f == f16::ZERO {
+                // the sign of zero is not preserved by the conversion from f16
+                assert_eq!(f2, f16::ZERO);
+            } else {
+                assert_eq!(f2.to_bits(), bits);
+            }
+        }
+
+        // rounding
+        let d1 = BigFloat::from_f64(0.1, p);
+        let (lo, inexact) = d1.to_f16_round(RoundingMode::Down);
+        let (hi, _) = d1.to_f16_round(RoundingMode::Up);
+        assert!(inexact);
+        assert_eq!(lo.to_bits() + 1, hi.to_bits());
+        assert_eq!(d1.to_f16_round(RoundingMode::ToEven).0, f16::from_f64(0.1));
+
+        // overflow and underflow
+        let d1 = BigFloat::from_f64(65520.0, p);
+        assert_eq!(d1.to_f16_round(RoundingMode::ToEven), (f16::INFINITY, true));
+        assert_eq!(d1.to_f16_round(RoundingMode::ToZero), (f16::MAX, true));
+        let d1 = BigFloat::from_f64(-1.0e-10, p);
+        let (f, inexact) = d1.to_f16_round(RoundingMode::ToEven);
+        assert!(f == f16::ZERO && f.is_sign_negative() && inexact);
+        assert_eq!(
+            d1.to_f16_round(RoundingMode::Down),
+            (-f16::from_bits(1), true)
+        );
+
+        assert_eq!(
+            INF_NEG.to_f16_round(RoundingMode::Up),
+            (f16::NEG_INFINITY, false)
+        );
+        assert!(NAN.to_f16_round(RoundingMode::Up).0.is_nan());
+        assert_eq!(BigFloat::from(f16::ONE), BigFloat::from_word(1, DEFAULT_P));
+    }
+}
@@ -1,4 +1,16 @@
+#[cfg(feature = "num-bigint")]
+mod bigint;
 #[cfg(feature = "serde")]
 mod de;
+#[cfg(feature = "half")]
+mod half;
+#[cfg(feature = "num-traits")]
+mod num_traits;
+#[cfg(feature = "num-rational")]
+mod rational;
+#[cfg(feature = "rkyv")]
+pub(crate) mod rkyv;
 #[cfg(feature = "serde")]
 mod ser;
+#[cfg(feature = "serde")]
+pub mod serialization;
@@ -0,0 +1,192 @@
+//! Implementation of the core traits of the crate num-traits for BigFloat.
+//!
+//! The trait methods do not accept a precision and a rounding mode, so the following policy applies.
+//! Constants returned by `Zero::zero`, `One::one`, and `Signed::signum`, and numbers constructed with `FromPrimitive`
+//! have the precision `DEFAULT_P`; conversion from primitive types is exact.
+//! The precision and the rounding mode of the results of the arithmetic operators required by `Num`
+//! are set with `BigFloat::set_op_params`.
+//! `Num::from_str_radix` uses the precision and the rounding mode set with `BigFloat::set_from_str_params`.
+
+use crate::common::consts::TENPOWERS;
+use crate::defs::Error;
+use crate::defs::DEFAULT_P;
+use crate::ext::BigFloat;
+use num_traits::FromPrimitive;
+use num_traits::Num;
+use num_traits::One;
+use num_traits::Signed;
+use num_traits::Zero;
+
+impl Zero for BigFloat {
+    fn zero() -> Self {
+        BigFloat::new(DEFAULT_P)
+    }
+
+    fn is_zero(&self) -> bool {
+        BigFloat::is_zero(self)
+    }
+}
+
+impl One for BigFloat {
+    fn one() -> Self {
+        BigFloat::from_u8(1, DEFAULT_P)
+    }
+}
+
+impl Num for BigFloat {
+    type FromStrRadixErr = Error;
+
+    /// Parses a number in radix `radix` from the string `str`.
+    /// Radix can be any value from 2 to 36. Digits above 9 are letters of either case,
+    /// and the exponent is written in `radix` and separated with "e", or with "_e" if "e" is a digit in `radix`.
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        let (p, rm) = BigFloat::from_str_params();
+
+        TENPOWERS.with(|tp| {
+            let cc = &mut tp.borrow_mut();
+            BigFloat::try_parse_base(str, radix, p, rm, cc).map_err(Error::from)
+        })
+    }
+}
+
+impl Signed for BigFloat {
+    fn abs(&self) -> Self {
+        BigFloat::abs(self)
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        if self <= other {
+            BigFloat::new(DEFAULT_P)
+        } else {
+            self - other
+        }
+    }
+
+    fn signum(&self) -> Self {
+        BigFloat::signum(self)
+    }
+
+    fn is_positive(&self) -> bool {
+        BigFloat::is_positive(self)
+    }
+
+    fn is_negative(&self) -> bool {
+        BigFloat::is_negative(self)
+    }
+}
+
+impl FromPrimitive for BigFloat {
+    fn from_i64(n: i64) -> Option<Self> {
+        Some(BigFloat::from_i64(n, DEFAULT_P))
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(BigFloat::from_u64(n, DEFAULT_P))
+    }
+
+    fn from_i128(n: i128) -> Option<Self> {
+        Some(BigFloat::from_i128(n, DEFAULT_P))
+    }
+
+    fn from_u128(n: u128) -> Option<Self> {
+        Some(BigFloat::from_u128(n, DEFAULT_P))
+    }
+
+    fn from_f32(n: f32) -> Option<Self> {
+        Some(BigFloat::from_f32(n, DEFAULT_P))
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        Some(BigFloat::from_f64(n, DEFAULT_P))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::RoundingMode;
+    use crate::INF_NEG;
+    use crate::INF_POS;
+    use crate::NAN;
+
+    fn sum_of_squares<T: Num + Clone>(v: &[T]) -> T {
+        v.iter()
+            .fold(T::zero(), |acc, x| acc + x.clone() * x.clone())
+    }
+
+    #[test]
+    fn test_num_traits() {
+        let zero = <BigFloat as Zero>::zero();
+        assert!(Zero::is_zero(&zero));
+        assert_eq!(zero.mantissa_max_bit_len(), Some(DEFAULT_P));
+        assert!(One::is_one(&<BigFloat as One>::one()));
+
+        // generic code
+        let v = [1.5f64, -2.0, 3.25].map(|f| BigFloat::from_f64(f, 64));
+        assert_eq!(sum_of_squares(&v), BigFloat::from_f64(16.8125, 64));
+
+        // operators use the larger precision of the operands
+        let d1 = BigFloat::from_u8(1, 64);
+        let d2 = BigFloat::from_u8(3, 256);
+        let q = &d1 / &d2;
+        assert_eq!(q.mantissa_max_bit_len(), Some(256));
+        assert_eq!(q, d1.div(&d2, 256, RoundingMode::ToEven));
+        assert_eq!((&d2 / &d1).mantissa_max_bit_len(), Some(256));
+        assert_eq!(
+            (BigFloat::from_u8(7, 64) % BigFloat::from_i8(-3, 64)),
+            BigFloat::from_u8(1, 64)
+        );
+        assert!((d1.clone() / BigFloat::new(64)).is_inf_pos());
+        assert!((INF_POS + INF_NEG).is_nan());
+
+        let mut d3 = d1.clone();
+        d3 += &d2;
+        d3 *= d2.clone();
+        d3 -= BigFloat::from_u8(2, 64);
+        d3 /= &d2;
+        assert_eq!(
+            d3,
+            BigFloat::from_u8(10, 256).div(&d2, 256, RoundingMode::ToEven)
+        );
+
+        // parsing
+        let n = BigFloat::from_str_radix("-1.1e+10", 3).unwrap();
+        assert_eq!(n, BigFloat::from_i8(-36, 64));
+        let n = BigFloat::from_str_radix("ff.8", 16).unwrap();
+        assert_eq!(n, BigFloat::from_f64(255.5, 64));
+        assert_eq!(
+            BigFloat::from_str_radix("12", 37),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(
+            BigFloat::from_str_radix("12", 2),
+            Err(Error::InvalidArgument)
+        );
+
+        // signed
+        let d4 = BigFloat::from_f64(-2.5, 64);
+        assert_eq!(Signed::abs(&d4), BigFloat::from_f64(2.5, 64));
+        assert_eq!(Signed::signum(&d4), BigFloat::from_i8(-1, 64));
+        assert!(Signed::is_negative(&d4) && !Signed::is_positive(&d4));
+        assert!(Signed::abs_sub(&d4, &d1).is_zero());
+        assert_eq!(Signed::abs_sub(&d1, &d4), BigFloat::from_f64(3.5, 64));
+        assert!(Signed::signum(&NAN).is_nan());
+
+        // conversion from primitives
+        assert_eq!(
+            <BigFloat as FromPrimitive>::from_u128(u128::MAX),
+            Some(BigFloat::from_u128(u128::MAX, DEFAULT_P))
+        );
+        assert_eq!(
+            <BigFloat as FromPrimitive>::from_i32(-5),
+            Some(BigFloat::from_i8(-5, 64))
+        );
+        let d5 = <BigFloat as FromPrimitive>::from_f64(0.1).unwrap();
+        assert_eq!(d5.to_f64_exact(), Some(0.1));
+        assert_eq!(d5.mantissa_max_bit_len(), Some(DEFAULT_P));
+        assert!(<BigFloat as FromPrimitive>::from_f64(f64::NAN)
+            .unwrap()
+            .is_nan());
+    }
+}
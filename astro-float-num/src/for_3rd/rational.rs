@@ -0,0 +1,135 @@
+//! Conversion between BigFloat and rational numbers of num-rational.
+
+use crate::defs::RoundingMode;
+use crate::defs::WORD_BIT_SIZE;
+use crate::ext::BigFloat;
+use crate::ext::FromExt;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+impl BigFloatNumber {
+    /// Returns the exact value of `self` as BigRational.
+    /// The value of `self` is considered regardless of the inexact flag.
+    pub fn to_rational_exact(&self) -> BigRational {
+        let s = if self.is_negative() {
+            num_bigint::Sign::Minus
+        } else {
+            num_bigint::Sign::Plus
+        };
+
+        // the value is m * 2^(e - n)
+        let m = BigInt::from_biguint(s, self.mantissa_as_biguint());
+        let shift = self.exponent() as isize - self.mantissa_max_bit_len() as isize;
+
+        if shift >= 0 {
+            BigRational::from_integer(m << shift as usize)
+        } else {
+            BigRational::new(m, BigInt::from(1) << shift.unsigned_abs())
+        }
+    }
+}
+
+impl BigFloat {
+    /// Constructs a number with precision `p` from the rational number `v` using the rounding mode `rm`.
+    /// The numerator and the denominator are converted exactly, so the result is rounded only once.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn from_rational(v: &BigRational, p: usize, rm: RoundingMode) -> Self {
+        let n = Self::from_bigint_exact(v.numer());
+        let d = Self::from_bigint_exact(v.denom());
+
+        n.div(&d, p, rm)
+    }
+
+    /// Returns the exact value of `self` as BigRational, or None if `self` is Inf or NaN.
+    /// The value of `self` is considered regardless of the inexact flag.
+    /// Numbers with large exponents are represented by large integers,
+    /// e.g. the denominator of a number with exponent -1000000 has about 1000000 bits.
+    pub fn to_rational_exact(&self) -> Option<BigRational> {
+        self.as_number().map(|v| v.to_rational_exact())
+    }
+
+    // Converts an integer to a number with the precision sufficient for exact representation.
+    fn from_bigint_exact(v: &BigInt) -> Self {
+        let p = (v.bits() as usize).max(WORD_BIT_SIZE);
+        BigFloat::from_bigint(v, p, RoundingMode::None)
+    }
+}
+
+impl FromExt<&BigRational> for BigFloat {
+    fn from_ext(v: &BigRational, p: usize, rm: RoundingMode, _cc: &mut Consts) -> Self {
+        BigFloat::from_rational(v, p, rm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::INF_NEG;
+    use crate::NAN;
+
+    #[test]
+    fn test_rational() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+        let mut cc = Consts::new().unwrap();
+
+        let r = |n: i64, d: i64| BigRational::new(BigInt::from(n), BigInt::from(d));
+
+        // exact conversion both ways
+        for (n, d) in [(0, 1), (1, 1), (-3, 4), (5, 1024), (1 << 62, 3 << 10), (-7, 1 << 40)] {
+            let v = r(n, d);
+            let d1 = BigFloat::from_rational(&v, p, rm);
+            assert_eq!(
+                d1,
+                BigFloat::from_i64(n, p).div(&BigFloat::from_i64(d, p), p, rm)
+            );
+            assert_eq!(BigFloat::from_ext(&v, p, rm, &mut cc), d1);
+            if d1.inexact() {
+                assert_ne!(d1.to_rational_exact(), Some(v));
+            } else {
+                assert_eq!(d1.to_rational_exact(), Some(v));
+            }
+        }
+
+        // rounding
+        let v = r(1, 3);
+        let d1 = BigFloat::from_rational(&v, p, RoundingMode::Down);
+        let d2 = BigFloat::from_rational(&v, p, RoundingMode::Up);
+        assert!(d1.inexact() && d2.inexact());
+        let (r1, r2) = (
+            d1.to_rational_exact().unwrap(),
+            d2.to_rational_exact().unwrap(),
+        );
+        assert!(r1 < v && v < r2);
+        assert_eq!(
+            r2 - r1,
+            BigRational::new(BigInt::from(1), BigInt::from(1) << 129usize)
+        );
+
+        // large numerators and denominators
+        let big = BigInt::from(1) << 300usize;
+        let v = BigRational::new(big.clone() + 1, big.clone());
+        let d1 = BigFloat::from_rational(&v, 512, rm);
+        assert!(!d1.inexact());
+        assert_eq!(d1.to_rational_exact(), Some(v));
+        let d1 = BigFloat::from_f64(1.0e300, p);
+        let v = d1.to_rational_exact().unwrap();
+        assert!(v.is_integer());
+        assert_eq!(BigFloat::from_rational(&v, p, rm), d1);
+        let d1 = BigFloat::from_f64(-1.0e-300, p);
+        assert_eq!(
+            BigFloat::from_rational(&d1.to_rational_exact().unwrap(), p, rm),
+            d1
+        );
+
+        // special values
+        assert_eq!(NAN.to_rational_exact(), None);
+        assert_eq!(INF_NEG.to_rational_exact(), None);
+        assert_eq!(BigFloat::new(p).neg().to_rational_exact(), Some(r(0, 1)));
+        assert!(BigFloat::from_rational(&r(1, 2), usize::MAX, rm).is_nan());
+    }
+}
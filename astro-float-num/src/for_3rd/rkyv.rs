@@ -0,0 +1,222 @@
+//! Zero-copy archiving of BigFloat with rkyv.
+//!
+//! The archived representation consists of the kind of the value, the sign, the exponent, the inexact flag,
+//! and the mantissa stored in 64-bit words, so it does not depend on the word size.
+//! The archived values can be inspected in place through `ArchivedBigFloat`,
+//! e.g. when an array of numbers is memory-mapped from a file, and converted to `BigFloat` when needed.
+
+use crate::common::util::u64_to_words;
+use crate::common::util::words_to_u64;
+use crate::defs::Error;
+use crate::num::BigFloatNumber;
+use crate::BigFloat;
+use crate::Exponent;
+use crate::Sign;
+use crate::INF_NEG;
+use crate::INF_POS;
+use crate::NAN;
+use rkyv::ser::ScratchSpace;
+use rkyv::ser::Serializer;
+use rkyv::Archive;
+use rkyv::Deserialize;
+use rkyv::Fallible;
+use rkyv::Serialize;
+
+const KIND_VALUE: u8 = 0;
+const KIND_INF_POS: u8 = 1;
+const KIND_INF_NEG: u8 = 2;
+const KIND_NAN: u8 = 3;
+
+/// Intermediate representation of BigFloat used for archiving.
+#[doc(hidden)]
+#[derive(Archive, Serialize)]
+#[archive(
+    archived = "ArchivedBigFloat",
+    resolver = "BigFloatResolver",
+    check_bytes
+)]
+#[archive_attr(doc = "Archived BigFloat which can be accessed without deserialization.")]
+pub struct BigFloatRepr {
+    kind: u8,
+    s: i8,
+    e: Exponent,
+    inexact: bool,
+    m: Vec<u64>,
+}
+
+impl From<&BigFloat> for BigFloatRepr {
+    fn from(v: &BigFloat) -> Self {
+        let mut ret = BigFloatRepr {
+            kind: KIND_NAN,
+            s: Sign::Pos as i8,
+            e: 0,
+            inexact: false,
+            m: Vec::new(),
+        };
+
+        if let Some((m, _, s, e, inexact)) = v.as_raw_parts() {
+            ret.kind = KIND_VALUE;
+            ret.s = s as i8;
+            ret.e = e;
+            ret.inexact = inexact;
+            ret.m = words_to_u64(m);
+        } else if v.is_inf_pos() {
+            ret.kind = KIND_INF_POS;
+        } else if v.is_inf_neg() {
+            ret.kind = KIND_INF_NEG;
+            ret.s = Sign::Neg as i8;
+        }
+
+        ret
+    }
+}
+
+impl Archive for BigFloat {
+    type Archived = ArchivedBigFloat;
+    type Resolver = BigFloatResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        BigFloatRepr::from(self).resolve(pos, resolver, out);
+    }
+}
+
+impl<S: ScratchSpace + Serializer + ?Sized> Serialize<S> for BigFloat {
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        BigFloatRepr::from(self).serialize(serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<BigFloat, D> for ArchivedBigFloat {
+    fn deserialize(&self, _deserializer: &mut D) -> Result<BigFloat, D::Error> {
+        Ok(self.to_big_float())
+    }
+}
+
+impl ArchivedBigFloat {
+    /// Returns true if the archived value is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.kind != KIND_VALUE && self.kind != KIND_INF_POS && self.kind != KIND_INF_NEG
+    }
+
+    /// Returns true if the archived value is positive or negative infinity.
+    pub fn is_inf(&self) -> bool {
+        self.kind == KIND_INF_POS || self.kind == KIND_INF_NEG
+    }
+
+    /// Returns the sign of the archived value, or None if the value is NaN.
+    pub fn sign(&self) -> Option<Sign> {
+        if self.is_nan() {
+            None
+        } else if self.s < 0 {
+            Some(Sign::Neg)
+        } else {
+            Some(Sign::Pos)
+        }
+    }
+
+    /// Returns the exponent of the archived value, or None if the value is Inf or NaN.
+    pub fn exponent(&self) -> Option<Exponent> {
+        if self.kind == KIND_VALUE {
+            Some(self.e)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the mantissa of the archived value in 64-bit words, or None if the value is Inf or NaN.
+    /// The most significant word is the last one.
+    pub fn mantissa(&self) -> Option<&[u64]> {
+        if self.kind == KIND_VALUE {
+            Some(self.m.as_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the inexact flag of the archived value.
+    pub fn inexact(&self) -> bool {
+        self.inexact
+    }
+
+    /// Constructs BigFloat from the archived value.
+    /// The function returns NaN if the archived value is incorrect.
+    pub fn to_big_float(&self) -> BigFloat {
+        match self.kind {
+            KIND_VALUE => {
+                if self.m.is_empty() {
+                    return BigFloat::nan(Some(Error::InvalidArgument));
+                }
+
+                let s = if self.s < 0 { Sign::Neg } else { Sign::Pos };
+                let words = u64_to_words(&self.m);
+
+                match BigFloatNumber::from_words(&words, s, self.e) {
+                    Ok(mut n) => {
+                        n.set_inexact(self.inexact);
+                        n.into()
+                    }
+                    Err(e) => BigFloat::nan(Some(e)),
+                }
+            }
+            KIND_INF_POS => INF_POS,
+            KIND_INF_NEG => INF_NEG,
+            _ => NAN,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::RoundingMode;
+    use rkyv::Infallible;
+
+    #[test]
+    fn test_rkyv() {
+        let p = 192;
+        let third =
+            BigFloat::from_word(1, p).div(&BigFloat::from_word(3, p), p, RoundingMode::ToEven);
+        let mut tiny = BigFloat::min_positive(p);
+        tiny.set_inexact(true);
+
+        let v = vec![
+            third.clone(),
+            third.neg(),
+            tiny,
+            BigFloat::new(128),
+            BigFloat::max_value(256),
+            INF_POS,
+            INF_NEG,
+            NAN,
+        ];
+
+        let bytes = rkyv::to_bytes::<_, 256>(&v).unwrap();
+        let archived = rkyv::check_archived_root::<Vec<BigFloat>>(&bytes).unwrap();
+        assert_eq!(archived.len(), v.len());
+
+        // access without deserialization
+        assert_eq!(archived[0].sign(), Some(Sign::Pos));
+        assert_eq!(archived[1].sign(), Some(Sign::Neg));
+        assert_eq!(archived[0].exponent(), third.exponent());
+        assert_eq!(archived[0].mantissa().unwrap().len(), p / 64);
+        assert!(archived[0].mantissa().unwrap()[p / 64 - 1] >> 63 == 1);
+        assert!(archived[0].inexact());
+        assert!(archived[5].is_inf() && archived[6].is_inf());
+        assert_eq!(archived[6].sign(), Some(Sign::Neg));
+        assert!(archived[7].is_nan());
+        assert_eq!(archived[7].mantissa(), None);
+
+        let d: Vec<BigFloat> = archived.deserialize(&mut Infallible).unwrap();
+        for (d1, d2) in d.iter().zip(v.iter()) {
+            if d2.is_nan() {
+                assert!(d1.is_nan());
+            } else {
+                assert_eq!(d1, d2);
+                assert_eq!(d1.inexact(), d2.inexact());
+                assert_eq!(d1.mantissa_max_bit_len(), d2.mantissa_max_bit_len());
+            }
+        }
+    }
+}
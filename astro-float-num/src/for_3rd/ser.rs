@@ -1,5 +1,6 @@
 //! Serialization of BigFloat.
 //! Serialization to a string uses decimal radix.
+//! The exact representation can be selected with `serialization::exact`.
 
 use crate::BigFloat;
 use serde::{Serialize, Serializer};
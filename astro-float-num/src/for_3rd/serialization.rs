@@ -0,0 +1,210 @@
+//! Selection of the serialized representation of BigFloat.
+//!
+//! By default, `BigFloat` is serialized as a decimal string.
+//! The module `exact` can be used with the attribute `#[serde(with = "...")]`
+//! to select the exact representation consisting of the sign, the exponent, and the words of the mantissa.
+//! The module `decimal` selects the default representation explicitly.
+//!
+//! ## Examples
+//!
+//! ```
+//! use astro_float_num::BigFloat;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Checkpoint {
+//!     #[serde(with = "astro_float_num::serialization::exact")]
+//!     x: BigFloat,
+//! }
+//!
+//! let c = Checkpoint { x: BigFloat::from_word(1, 128).div(&BigFloat::from_word(3, 128), 128, astro_float_num::RoundingMode::ToEven) };
+//! let json = serde_json::to_string(&c).unwrap();
+//! let c2: Checkpoint = serde_json::from_str(&json).unwrap();
+//! assert_eq!(c.x.cmp(&c2.x), Some(0));
+//! ```
+
+use crate::common::util::u64_to_words;
+use crate::common::util::words_to_u64;
+use crate::num::BigFloatNumber;
+use crate::BigFloat;
+use crate::Error;
+use crate::Exponent;
+use crate::Sign;
+use crate::INF_NEG;
+use crate::INF_POS;
+use crate::NAN;
+use serde::{Deserialize, Serialize};
+
+/// The exact representation of BigFloat.
+/// The mantissa is stored in 64-bit words, the most significant word is the last one,
+/// so the representation does not depend on the word size.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "BigFloat")]
+pub(crate) enum Exact {
+    Value {
+        s: Sign,
+        e: Exponent,
+        m: Vec<u64>,
+        inexact: bool,
+    },
+    Inf(Sign),
+    NaN,
+}
+
+impl From<&BigFloat> for Exact {
+    fn from(v: &BigFloat) -> Self {
+        if let Some((m, _, s, e, inexact)) = v.as_raw_parts() {
+            let words = words_to_u64(m);
+
+            Exact::Value {
+                s,
+                e,
+                m: words,
+                inexact,
+            }
+        } else if v.is_inf_pos() {
+            Exact::Inf(Sign::Pos)
+        } else if v.is_inf_neg() {
+            Exact::Inf(Sign::Neg)
+        } else {
+            Exact::NaN
+        }
+    }
+}
+
+impl TryFrom<Exact> for BigFloat {
+    type Error = Error;
+
+    fn try_from(v: Exact) -> Result<Self, Self::Error> {
+        match v {
+            Exact::Value { s, e, m, inexact } => {
+                if m.is_empty() {
+                    return Err(Error::InvalidArgument);
+                }
+
+                let words = u64_to_words(&m);
+
+                let mut n = BigFloatNumber::from_words(&words, s, e)?;
+                n.set_inexact(inexact);
+
+                Ok(n.into())
+            }
+            Exact::Inf(Sign::Pos) => Ok(INF_POS),
+            Exact::Inf(Sign::Neg) => Ok(INF_NEG),
+            Exact::NaN => Ok(NAN),
+        }
+    }
+}
+
+/// Serialization of BigFloat as a decimal string.
+pub mod decimal {
+
+    use crate::for_3rd::de::BigFloatVisitor;
+    use crate::BigFloat;
+    use serde::{Deserializer, Serializer};
+
+    /// Serializes `v` as a decimal string.
+    pub fn serialize<S: Serializer>(v: &BigFloat, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&v.to_string())
+    }
+
+    /// Deserializes BigFloat from a decimal string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigFloat, D::Error> {
+        deserializer.deserialize_str(BigFloatVisitor {})
+    }
+}
+
+/// Serialization of BigFloat using the exact representation consisting of the sign, the exponent, and the words of the mantissa.
+pub mod exact {
+
+    use super::Exact;
+    use crate::BigFloat;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes `v` using the exact representation.
+    pub fn serialize<S: Serializer>(v: &BigFloat, serializer: S) -> Result<S::Ok, S::Error> {
+        Exact::from(v).serialize(serializer)
+    }
+
+    /// Deserializes BigFloat from the exact representation.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigFloat, D::Error> {
+        BigFloat::try_from(Exact::deserialize(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{e:?}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::BigFloat;
+    use crate::RoundingMode;
+    use crate::INF_NEG;
+    use crate::NAN;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Repr {
+        #[serde(with = "super::exact")]
+        x: BigFloat,
+        #[serde(with = "super::decimal")]
+        y: BigFloat,
+    }
+
+    #[test]
+    fn test_exact() {
+        let p = 192;
+        let x = BigFloat::from_word(1, p).div(&BigFloat::from_word(3, p), p, RoundingMode::ToEven);
+
+        // the exact representation in a human-readable format
+        let r = Repr {
+            x: x.clone(),
+            y: BigFloat::from_f64(0.5, p),
+        };
+        let json = serde_json::to_string(&r).unwrap();
+        assert!(json.contains("\"y\":\""));
+        let r2: Repr = serde_json::from_str(&json).unwrap();
+        assert_eq!(r2.x.cmp(&x), Some(0));
+        assert_eq!(r2.x.inexact(), x.inexact());
+        assert_eq!(r2.x.precision(), x.precision());
+        assert_eq!(r2.y, r.y);
+
+        // the exact representation in a binary format
+        #[derive(Serialize, Deserialize)]
+        struct ExactOnly(#[serde(with = "super::exact")] BigFloat);
+
+        let mut tiny = BigFloat::min_positive(p);
+        tiny.set_inexact(true);
+        for v in [x.clone(), tiny, BigFloat::new(p), INF_NEG] {
+            let bytes = bincode::serialize(&ExactOnly(v.clone())).unwrap();
+            let v2 = bincode::deserialize::<ExactOnly>(&bytes).unwrap().0;
+            assert_eq!(v2, v);
+            assert_eq!(v2.inexact(), v.inexact());
+            assert_eq!(v2.mantissa_max_bit_len(), v.mantissa_max_bit_len());
+        }
+
+        let bytes = bincode::serialize(&ExactOnly(NAN)).unwrap();
+        assert!(bincode::deserialize::<ExactOnly>(&bytes)
+            .unwrap()
+            .0
+            .is_nan());
+
+        // binary formats use the decimal string by default
+        let bytes = bincode::serialize(&x).unwrap();
+        assert_eq!(bytes, bincode::serialize(&x.to_string()).unwrap());
+        let v2: BigFloat = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(v2, x.to_string().parse::<BigFloat>().unwrap());
+
+        // the other public types
+        let bytes = bincode::serialize(&(RoundingMode::ToOdd, crate::Sign::Neg, crate::Radix::Hex))
+            .unwrap();
+        assert_eq!(
+            bincode::deserialize::<(RoundingMode, crate::Sign, crate::Radix)>(&bytes).unwrap(),
+            (RoundingMode::ToOdd, crate::Sign::Neg, crate::Radix::Hex)
+        );
+        assert_eq!(
+            serde_json::to_string(&RoundingMode::ToEven).unwrap(),
+            "\"ToEven\""
+        );
+    }
+}
@@ -0,0 +1,608 @@
+//! Great-circle distance and bearing on a sphere.
+//!
+//! A point is given by its latitude and longitude in radians, e.g. `(&lat, &lon)`.
+//!
+//! The central angle between two points is computed with the special case of Vincenty's formula for a sphere:
+//! `atan2(sqrt(n1^2 + n2^2), d)`, where `n1 = cos(lat2)*sin(dlon)`, `n2 = cos(lat1)*sin(lat2) - sin(lat1)*cos(lat2)*cos(dlon)`,
+//! and `d = sin(lat1)*sin(lat2) + cos(lat1)*cos(lat2)*cos(dlon)`.
+//! Unlike the haversine formula, which loses accuracy for nearly antipodal points, and the spherical law of cosines,
+//! which loses accuracy for nearly identical points, the formula is well conditioned for any pair of points.
+//! Still, `n2` cancels for nearly identical and for nearly antipodal points if it is computed as written.
+//! The functions of this module compute it as `sin(dlat) + 2*sin(lat1)*cos(lat2)*sin(dlon/2)^2` if `cos(dlon) >= 0`,
+//! and as `sin(lat1 + lat2) - 2*sin(lat1)*cos(lat2)*cos(dlon/2)^2` otherwise, where the differences
+//! and the sum of the coordinates are computed exactly.
+//! The error of the result is estimated from the magnitudes of the terms, and the working precision
+//! is increased until the result can be rounded with a single rounding.
+
+use crate::common::util::round_p;
+use crate::AngleRange;
+use crate::BigFloat;
+use crate::Consts;
+use crate::Error;
+use crate::RoundingMode;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+/// Computes the central angle in radians between the points `a` and `b` with precision `p`.
+/// The result is rounded using the rounding mode `rm`.
+/// This function requires constants cache `cc` for computing the result.
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if any of the coordinates is Inf or NaN, or if the precision `p` is incorrect.
+pub fn central_angle(
+    a: (&BigFloat, &BigFloat),
+    b: (&BigFloat, &BigFloat),
+    p: usize,
+    rm: RoundingMode,
+    cc: &mut Consts,
+) -> BigFloat {
+    match central_angle_internal(a, b, None, p, rm, cc) {
+        Ok(v) => v,
+        Err(e) => BigFloat::nan(Some(e)),
+    }
+}
+
+/// Computes the great-circle distance between the points `a` and `b` on a sphere with the radius `radius`
+/// with precision `p`. The result is rounded using the rounding mode `rm` with a single rounding,
+/// and has the same units as `radius`.
+/// This function requires constants cache `cc` for computing the result.
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if any of the coordinates is Inf or NaN, `radius` is negative, Inf or NaN,
+/// or if the precision `p` is incorrect.
+pub fn distance(
+    a: (&BigFloat, &BigFloat),
+    b: (&BigFloat, &BigFloat),
+    radius: &BigFloat,
+    p: usize,
+    rm: RoundingMode,
+    cc: &mut Consts,
+) -> BigFloat {
+    match central_angle_internal(a, b, Some(radius), p, rm, cc) {
+        Ok(v) => v,
+        Err(e) => BigFloat::nan(Some(e)),
+    }
+}
+
+/// Computes the initial bearing in radians from the point `a` to the point `b` in the range `range` with precision `p`.
+/// The bearing is measured clockwise from the north, e.g. the bearing to the east is pi/2.
+/// The result is rounded using the rounding mode `rm`.
+/// This function requires constants cache `cc` for computing the result.
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if any of the coordinates is Inf or NaN, the points are equal,
+/// or if the precision `p` is incorrect.
+pub fn bearing(
+    a: (&BigFloat, &BigFloat),
+    b: (&BigFloat, &BigFloat),
+    range: AngleRange,
+    p: usize,
+    rm: RoundingMode,
+    cc: &mut Consts,
+) -> BigFloat {
+    match bearing_internal(a, b, range, p, rm, cc) {
+        Ok(v) => v,
+        Err(e) => BigFloat::nan(Some(e)),
+    }
+}
+
+// Terms of Vincenty's formula computed with precision `p`, and the exponents of their absolute errors.
+struct Terms {
+    n1: BigFloat,
+    n2: BigFloat,
+    d: BigFloat,
+    e1: Option<isize>,
+    e2: Option<isize>,
+    ed: Option<isize>,
+}
+
+impl Terms {
+    fn new(
+        a: (&BigFloat, &BigFloat),
+        b: (&BigFloat, &BigFloat),
+        p: usize,
+        cc: &mut Consts,
+    ) -> Self {
+        let rm = RoundingMode::None;
+        let (lat1, lon1) = a;
+        let (lat2, lon2) = b;
+
+        let dlon = lon2.sub_full_prec(lon1);
+
+        let s1 = lat1.sin(p, rm, cc);
+        let c1 = lat1.cos(p, rm, cc);
+        let s2 = lat2.sin(p, rm, cc);
+        let c2 = lat2.cos(p, rm, cc);
+        let sdl = dlon.sin(p, rm, cc);
+        let cdl = dlon.cos(p, rm, cc);
+
+        let n1 = c2.mul(&sdl, p, rm);
+
+        // 2*sin(lat1)*cos(lat2)*h, where h is sin(dlon/2)^2 or cos(dlon/2)^2
+        let half = dlon.mul_full_prec(&BigFloat::from_f64(0.5, 64));
+        let (u, h) = if cdl.is_negative() {
+            let d1 = half.cos(p, rm, cc);
+            (lat1.add_full_prec(lat2).sin(p, rm, cc), d1.mul(&d1, p, rm))
+        } else {
+            let d1 = half.sin(p, rm, cc);
+            (lat2.sub_full_prec(lat1).sin(p, rm, cc), d1.mul(&d1, p, rm))
+        };
+        let mut t = s1.mul(&c2, p, rm).mul(&h, p, rm);
+        t = t.mul(&BigFloat::from_word(2, 64), p, rm);
+
+        let n2 = if cdl.is_negative() { u.sub(&t, p, rm) } else { u.add(&t, p, rm) };
+
+        let d1 = s1.mul(&s2, p, rm);
+        let d2 = c1.mul(&c2, p, rm).mul(&cdl, p, rm);
+        let d = d1.add(&d2, p, rm);
+
+        Terms {
+            e1: err_exp(&[&n1], p),
+            e2: err_exp(&[&u, &t], p),
+            ed: err_exp(&[&d1, &d2], p),
+            n1,
+            n2,
+            d,
+        }
+    }
+}
+
+fn central_angle_internal(
+    a: (&BigFloat, &BigFloat),
+    b: (&BigFloat, &BigFloat),
+    radius: Option<&BigFloat>,
+    p: usize,
+    rm: RoundingMode,
+    cc: &mut Consts,
+) -> Result<BigFloat, Error> {
+    let p = round_p(p);
+
+    check_point(a)?;
+    check_point(b)?;
+
+    if let Some(r) = radius {
+        if r.is_nan() || r.is_inf() || r.is_negative() {
+            return Err(Error::InvalidArgument);
+        }
+    }
+
+    if is_same_point(a, b) {
+        let mut ret = BigFloat::new(p);
+        ret.set_sign(Sign::Pos);
+        return Ok(ret);
+    }
+
+    // along a meridian and along the equator the central angle can be equal to the difference of the coordinates
+    let diff = if a.1.cmp(b.1) == Some(0) {
+        Some(b.0.sub_full_prec(a.0))
+    } else if a.0.is_zero() && b.0.is_zero() {
+        Some(b.1.sub_full_prec(a.1))
+    } else {
+        None
+    };
+
+    if let Some(ret) = diff.and_then(|d| exact_angle(d, cc)) {
+        return match radius {
+            Some(r) => Ok(ret.mul(r, p, rm)),
+            None => {
+                let mut ret = ret;
+                ret.set_precision(p, rm)?;
+                Ok(ret)
+            }
+        };
+    }
+
+    let mut p_inc = WORD_BIT_SIZE;
+    let mut p_wrk = p + p_inc;
+
+    loop {
+        let p_x = p_wrk + WORD_BIT_SIZE;
+
+        let t = Terms::new(a, b, p_x, cc);
+
+        let n = t.n1.hypot(&t.n2, p_x, RoundingMode::None);
+        let mut ret = n.atan2(&t.d, p_x, RoundingMode::None, cc);
+
+        // n^2 + d^2 is close to 1, so the error of the angle does not exceed |d|*|dn| + |n|*|dd|
+        let err = add_exp(t.e1.max(t.e2), &t.d).max(add_exp(t.ed, &n));
+        let s = err.map_or(p_x as isize, |e| exp(&ret) - e - 3);
+
+        if let Some(r) = radius {
+            ret = ret.mul(r, p_x, RoundingMode::None);
+        }
+
+        if ret.is_nan() || try_round(&mut ret, s, p, rm) {
+            return Ok(ret);
+        }
+
+        p_wrk += p_inc;
+        p_inc = round_p(p_wrk / 5);
+    }
+}
+
+fn bearing_internal(
+    a: (&BigFloat, &BigFloat),
+    b: (&BigFloat, &BigFloat),
+    range: AngleRange,
+    p: usize,
+    rm: RoundingMode,
+    cc: &mut Consts,
+) -> Result<BigFloat, Error> {
+    let p = round_p(p);
+
+    check_point(a)?;
+    check_point(b)?;
+
+    if is_same_point(a, b) {
+        return Err(Error::InvalidArgument);
+    }
+
+    let mut p_inc = WORD_BIT_SIZE;
+    let mut p_wrk = p + p_inc;
+
+    loop {
+        let p_x = p_wrk + WORD_BIT_SIZE;
+
+        let mut t = Terms::new(a, b, p_x, cc);
+
+        // due south is pi rather than -pi
+        if t.n1.is_zero() {
+            t.n1.set_sign(Sign::Pos);
+        }
+
+        let n = t.n1.hypot(&t.n2, p_x, RoundingMode::None);
+        let mut ret = t.n1.atan2(&t.n2, p_x, RoundingMode::None, cc);
+
+        // the bearing along a meridian to the north is exactly 0
+        if ret.is_zero() {
+            ret.set_inexact(false);
+            ret.set_precision(p, rm)?;
+            return Ok(ret);
+        }
+
+        // the error of the angle does not exceed (|n2|*|dn1| + |n1|*|dn2|) / n^2
+        let mut err = add_exp(t.e1, &t.n2)
+            .max(add_exp(t.e2, &t.n1))
+            .map(|e| e - 2 * exp(&n) + 3);
+
+        if range == AngleRange::ZeroToTwoPi && ret.is_negative() {
+            let pi2 = cc.pi(p_x, RoundingMode::None).mul(
+                &BigFloat::from_word(2, 64),
+                p_x,
+                RoundingMode::None,
+            );
+            ret = ret.add(&pi2, p_x, RoundingMode::None);
+            err = err.max(Some(4 - p_x as isize));
+        }
+
+        let s = err.map_or(p_x as isize - 2, |e| exp(&ret) - e - 2);
+
+        if ret.is_nan() || try_round(&mut ret, s, p, rm) {
+            return Ok(ret);
+        }
+
+        p_wrk += p_inc;
+        p_inc = round_p(p_wrk / 5);
+    }
+}
+
+fn check_point(a: (&BigFloat, &BigFloat)) -> Result<(), Error> {
+    let (lat, lon) = a;
+
+    if lat.is_nan() || lat.is_inf() || lon.is_nan() || lon.is_inf() {
+        Err(Error::InvalidArgument)
+    } else {
+        Ok(())
+    }
+}
+
+fn is_same_point(a: (&BigFloat, &BigFloat), b: (&BigFloat, &BigFloat)) -> bool {
+    a.0.cmp(b.0) == Some(0) && a.1.cmp(b.1) == Some(0)
+}
+
+// Returns |x| if it does not exceed pi, or None otherwise.
+fn exact_angle(x: BigFloat, cc: &mut Consts) -> Option<BigFloat> {
+    let x = x.abs();
+    let q = x.mantissa_max_bit_len()?;
+
+    // pi is irrational, so x <= pi if and only if x does not exceed pi rounded down to the precision of x
+    if matches!(x.cmp(&cc.pi(q, RoundingMode::Down)), Some(c) if c <= 0) {
+        Some(x)
+    } else {
+        None
+    }
+}
+
+// Rounds `ret` to precision `p` if it has `s` correct bits, and the rounding is possible.
+// The roundability check requires the number of correct bits to be aligned to the word size,
+// and not to exceed the precision of `ret`.
+fn try_round(ret: &mut BigFloat, s: isize, p: usize, rm: RoundingMode) -> bool {
+    let s = s.min(ret.mantissa_max_bit_len().unwrap_or(0) as isize);
+    let s = s / WORD_BIT_SIZE as isize * WORD_BIT_SIZE as isize;
+    s >= (p + WORD_BIT_SIZE) as isize && ret.try_set_precision(p, rm, s as usize)
+}
+
+fn exp(x: &BigFloat) -> isize {
+    x.exponent().unwrap_or(0) as isize
+}
+
+// Exponent of the absolute error of a sum of `terms`, each computed with precision `p` and a few roundings.
+// Returns None if all terms are zero, and the sum is exact.
+fn err_exp(terms: &[&BigFloat], p: usize) -> Option<isize> {
+    terms
+        .iter()
+        .filter(|t| !t.is_zero())
+        .map(|t| exp(t) - p as isize + 4)
+        .max()
+}
+
+// Exponent of the product of an error with exponent `e` and `x`, or None if the product is zero.
+fn add_exp(e: Option<isize>, x: &BigFloat) -> Option<isize> {
+    if x.is_zero() {
+        None
+    } else {
+        e.map(|e| e + exp(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_geodesy() {
+        let mut cc = Consts::new().unwrap();
+        let p = 256;
+        let zero = BigFloat::from_word(0, p);
+
+        // along the equator and along a meridian the central angle is the difference of the coordinates
+        let lon1 = BigFloat::from_f64(0.25, p);
+        let lon2 = BigFloat::from_f64(1.0, p);
+        let lat1 = BigFloat::from_f64(0.1, p);
+        let lat2 = BigFloat::from_f64(0.3, p);
+        for rm in [RoundingMode::Up, RoundingMode::Down, RoundingMode::ToEven] {
+            let d1 = central_angle((&zero, &lon1), (&zero, &lon2), p, rm, &mut cc);
+            assert_eq!(d1, BigFloat::from_f64(0.75, p));
+
+            let d1 = central_angle((&lat1, &lon1), (&lat2, &lon1), p, rm, &mut cc);
+            assert_eq!(d1, lat2.sub(&lat1, p, rm));
+        }
+
+        // directed rounding
+        let a = (&lat1, &lon1);
+        let b = (&lat2, &lon2);
+        let d1 = central_angle(a, b, p, RoundingMode::Down, &mut cc);
+        let d2 = central_angle(a, b, p, RoundingMode::Up, &mut cc);
+        assert!(d1 < d2);
+        assert!(d1.inexact());
+
+        // nearly identical points
+        let mut eps = BigFloat::from_word(1, 64);
+        eps.set_exponent(-300);
+        let lat3 = lat1.add(&eps, 1024, RoundingMode::None);
+        let d1 = central_angle(
+            (&lat1, &lon1),
+            (&lat3, &lon1),
+            p,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        assert_eq!(d1, eps);
+        let lon3 = lon1.add(&eps, 1024, RoundingMode::None);
+        let d1 = central_angle(
+            (&zero, &lon1),
+            (&zero, &lon3),
+            p,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        assert_eq!(d1, eps);
+
+        // nearly identical points off the equator and the meridian
+        let d1 = central_angle(
+            (&lat1, &lon1),
+            (&lat3, &lon3),
+            p,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        let d2 = naive_central_angle((&lat1, &lon1), (&lat3, &lon3), 4 * p, &mut cc);
+        assert_close(&d1, &d2, p);
+
+        // nearly antipodal points
+        let pi = cc.pi(p, RoundingMode::ToEven);
+        let lat4 = lat1.neg().add(&eps, 1024, RoundingMode::None);
+        let lon4 = lon1.add_full_prec(&pi);
+        let d1 = central_angle(
+            (&lat1, &lon1),
+            (&lat4, &lon4),
+            p,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        let d2 = naive_central_angle((&lat1, &lon1), (&lat4, &lon4), 4 * p, &mut cc);
+        assert_close(&d1, &d2, p);
+        assert!(d1 < cc.pi(p, RoundingMode::Up));
+
+        // random points
+        for _ in 0..20 {
+            let lat1 = BigFloat::random_normal(p, -3, 1);
+            let lon1 = BigFloat::random_normal(p, -3, 3);
+            let lat2 = BigFloat::random_normal(p, -3, 1);
+            let lon2 = BigFloat::random_normal(p, -3, 3);
+            let d1 = central_angle(
+                (&lat1, &lon1),
+                (&lat2, &lon2),
+                p,
+                RoundingMode::ToEven,
+                &mut cc,
+            );
+            let d2 = naive_central_angle((&lat1, &lon1), (&lat2, &lon2), 4 * p, &mut cc);
+            assert_close(&d1, &d2, p);
+        }
+
+        // distance
+        let r = BigFloat::from_f64(6371008.8, p);
+        let d1 = distance(
+            (&lat1, &lon1),
+            (&lat2, &lon2),
+            &r,
+            p,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        let d2 = naive_central_angle((&lat1, &lon1), (&lat2, &lon2), 4 * p, &mut cc).mul(
+            &r,
+            4 * p,
+            RoundingMode::None,
+        );
+        assert_close(&d1, &d2, p);
+        let d1 = distance(
+            (&lat1, &lon1),
+            (&lat1, &lon1),
+            &r,
+            p,
+            RoundingMode::ToEven,
+            &mut cc,
+        );
+        assert!(d1.is_zero() && !d1.inexact());
+
+        // bearing
+        let pi = cc.pi(p, RoundingMode::ToEven);
+        let mut pi_2 = pi.clone();
+        pi_2.set_exponent(1);
+        let rm = RoundingMode::ToEven;
+        let east = bearing(
+            (&zero, &lon1),
+            (&zero, &lon2),
+            AngleRange::MinusPiToPi,
+            p,
+            rm,
+            &mut cc,
+        );
+        assert_eq!(east, pi_2);
+        let west = bearing(
+            (&zero, &lon2),
+            (&zero, &lon1),
+            AngleRange::MinusPiToPi,
+            p,
+            rm,
+            &mut cc,
+        );
+        assert_eq!(west, pi_2.neg());
+        let west = bearing(
+            (&zero, &lon2),
+            (&zero, &lon1),
+            AngleRange::ZeroToTwoPi,
+            p,
+            rm,
+            &mut cc,
+        );
+        assert_eq!(
+            west,
+            BigFloat::from_word(3, p)
+                .mul(&cc.pi(2 * p, RoundingMode::None), 2 * p, RoundingMode::None)
+                .div(&BigFloat::from_word(2, p), p, rm)
+        );
+        let north = bearing(
+            (&lat1, &lon1),
+            (&lat2, &lon1),
+            AngleRange::ZeroToTwoPi,
+            p,
+            rm,
+            &mut cc,
+        );
+        assert!(north.is_zero() && !north.inexact());
+        for range in [AngleRange::ZeroToTwoPi, AngleRange::MinusPiToPi] {
+            let south = bearing((&lat2, &lon1), (&lat1, &lon1), range, p, rm, &mut cc);
+            assert_eq!(south, pi);
+        }
+
+        // nearly identical points: the bearing is not lost
+        let d1 = bearing(
+            (&lat1, &lon1),
+            (&lat3, &lon3),
+            AngleRange::MinusPiToPi,
+            p,
+            rm,
+            &mut cc,
+        );
+        let d2 = naive_bearing((&lat1, &lon1), (&lat3, &lon3), 4 * p, &mut cc);
+        assert_close(&d1, &d2, p);
+
+        // random points
+        for _ in 0..20 {
+            let lat1 = BigFloat::random_normal(p, -3, 1);
+            let lon1 = BigFloat::random_normal(p, -3, 3);
+            let lat2 = BigFloat::random_normal(p, -3, 1);
+            let lon2 = BigFloat::random_normal(p, -3, 3);
+            let d1 = bearing(
+                (&lat1, &lon1),
+                (&lat2, &lon2),
+                AngleRange::MinusPiToPi,
+                p,
+                rm,
+                &mut cc,
+            );
+            let d2 = naive_bearing((&lat1, &lon1), (&lat2, &lon2), 4 * p, &mut cc);
+            assert_close(&d1, &d2, p);
+        }
+
+        // special values
+        assert!(central_angle((&crate::NAN, &lon1), b, p, rm, &mut cc).is_nan());
+        assert!(central_angle(a, (&lat2, &crate::INF_POS), p, rm, &mut cc).is_nan());
+        assert!(distance(a, b, &r.neg(), p, rm, &mut cc).is_nan());
+        assert!(distance(a, b, &crate::INF_POS, p, rm, &mut cc).is_nan());
+        assert!(bearing(a, a, AngleRange::ZeroToTwoPi, p, rm, &mut cc).is_nan());
+    }
+
+    // Vincenty's formula without rewriting the terms.
+    fn naive_central_angle(
+        a: (&BigFloat, &BigFloat),
+        b: (&BigFloat, &BigFloat),
+        p: usize,
+        cc: &mut Consts,
+    ) -> BigFloat {
+        let rm = RoundingMode::None;
+        let (n1, n2, d) = naive_terms(a, b, p, cc);
+        n1.hypot(&n2, p, rm).atan2(&d, p, rm, cc)
+    }
+
+    fn naive_bearing(
+        a: (&BigFloat, &BigFloat),
+        b: (&BigFloat, &BigFloat),
+        p: usize,
+        cc: &mut Consts,
+    ) -> BigFloat {
+        let (n1, n2, _) = naive_terms(a, b, p, cc);
+        n1.atan2(&n2, p, RoundingMode::None, cc)
+    }
+
+    fn naive_terms(
+        a: (&BigFloat, &BigFloat),
+        b: (&BigFloat, &BigFloat),
+        p: usize,
+        cc: &mut Consts,
+    ) -> (BigFloat, BigFloat, BigFloat) {
+        let rm = RoundingMode::None;
+        let dlon = b.1.sub(a.1, p, rm);
+        let (s1, c1) = (a.0.sin(p, rm, cc), a.0.cos(p, rm, cc));
+        let (s2, c2) = (b.0.sin(p, rm, cc), b.0.cos(p, rm, cc));
+        let cdl = dlon.cos(p, rm, cc);
+        let n1 = c2.mul(&dlon.sin(p, rm, cc), p, rm);
+        let n2 = c1
+            .mul(&s2, p, rm)
+            .sub(&s1.mul(&c2, p, rm).mul(&cdl, p, rm), p, rm);
+        let d = s1
+            .mul(&s2, p, rm)
+            .add(&c1.mul(&c2, p, rm).mul(&cdl, p, rm), p, rm);
+        (n1, n2, d)
+    }
+
+    // `d1` differs from `d2` by at most one unit in the last place of precision `p`.
+    fn assert_close(d1: &BigFloat, d2: &BigFloat, p: usize) {
+        let d = d1.sub(d2, 4 * p, RoundingMode::None);
+        assert!(
+            d.is_zero()
+                || d.exponent().unwrap() as isize <= d1.exponent().unwrap() as isize - p as isize
+        );
+    }
+}
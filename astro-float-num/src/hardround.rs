@@ -0,0 +1,273 @@
+//! Search for hard-to-round cases.
+//!
+//! The search enumerates consecutive floating point numbers of a target precision,
+//! evaluates a function for each of them with extra precision,
+//! and reports arguments for which the exact result lies unusually close
+//! to a rounding boundary of the target precision.
+//! A case is characterized by the length of the run of identical bits which follows the rounding position:
+//! to round such a result correctly, an implementation needs at least that many extra bits of precision.
+
+use crate::common::util::round_p;
+use crate::BigFloat;
+use crate::Consts;
+use crate::Error;
+use crate::Exponent;
+use crate::RoundingMode;
+use crate::Sign;
+use crate::Word;
+use crate::WORD_BIT_SIZE;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// A hard-to-round case found by the search.
+#[derive(Debug)]
+pub struct HardCase {
+    /// Argument of the function.
+    pub x: BigFloat,
+
+    /// Value of the function truncated to the working precision which was used to find the case.
+    pub y: BigFloat,
+
+    /// The number of identical bits which follow the rounding position of the target precision.
+    /// If the run spans all computed bits, the value is a lower bound of the actual run length.
+    pub run: usize,
+}
+
+/// Searches for hard-to-round cases of the function `f`.
+///
+/// `count` consecutive numbers of precision `p`, starting with `from` truncated to `p` bits, are checked in increasing order.
+/// For each number `x` the function is evaluated as `f(x, p_wrk, RoundingMode::ToZero, cc)`,
+/// where `p_wrk` is the working precision chosen by the search; the result of `f` is expected to be correctly rounded,
+/// e.g. `f` can be `BigFloat::exp`, `BigFloat::sin`, or a closure calling one of the other functions.
+/// A case is reported when the run of identical bits following the rounding position is at least `min_run` bits long.
+///
+/// The rounding position depends on `rm`: for directed rounding modes it is the bit `p` bits after the most significant bit,
+/// for rounding to nearest modes it is the bit after the round bit, i.e. the run distance to the midpoint is measured.
+///
+/// Arguments for which `f` returns an exact result, NaN, infinity, or a subnormal value are skipped.
+/// The search stops early if an argument reaches zero.
+///
+/// ## Errors
+///
+///  - InvalidArgument: `from` is zero, subnormal, NaN or infinity, or `p` is zero.
+///  - MemoryAllocation: failed to allocate memory.
+pub fn search<F>(
+    mut f: F,
+    from: &BigFloat,
+    count: usize,
+    p: usize,
+    rm: RoundingMode,
+    min_run: usize,
+    cc: &mut Consts,
+) -> Result<Vec<HardCase>, Error>
+where
+    F: FnMut(&BigFloat, usize, RoundingMode, &mut Consts) -> BigFloat,
+{
+    if p == 0 || from.is_zero() || from.is_subnormal() {
+        return Err(Error::InvalidArgument);
+    }
+
+    let mut x = truncate(from, p)?;
+
+    // position of the first bit of the run
+    let start = match rm {
+        RoundingMode::Up | RoundingMode::Down | RoundingMode::ToZero | RoundingMode::FromZero => p,
+        _ => p + 1,
+    };
+
+    let p_max = 4 * round_p(p) + min_run;
+
+    let mut ret = Vec::new();
+
+    for _ in 0..count {
+        let mut p_wrk = round_p(start + min_run + 1);
+
+        loop {
+            let y = f(&x, p_wrk, RoundingMode::ToZero, cc);
+
+            if let Some(Error::MemoryAllocation) = y.err() {
+                return Err(Error::MemoryAllocation);
+            }
+
+            if y.is_nan() || y.is_inf() || y.is_zero() || y.is_subnormal() || !y.inexact() {
+                break;
+            }
+
+            let (m, _, _, _, _) = y.as_raw_parts().ok_or(Error::InvalidArgument)?;
+            let run = run_len(m, start);
+
+            if start + run < m.len() * WORD_BIT_SIZE || p_wrk >= p_max {
+                if run >= min_run {
+                    ret.try_reserve(1)?;
+                    ret.push(HardCase {
+                        x: x.clone(),
+                        y,
+                        run,
+                    });
+                }
+                break;
+            }
+
+            // the run reaches the end of the computed bits
+            p_wrk *= 2;
+        }
+
+        x = next_up(&x, p)?;
+
+        if x.is_zero() {
+            break;
+        }
+    }
+
+    Ok(ret)
+}
+
+// Returns `x` truncated to `p` bits.
+fn truncate(x: &BigFloat, p: usize) -> Result<BigFloat, Error> {
+    let (m, _, s, e, _) = x.as_raw_parts().ok_or(Error::InvalidArgument)?;
+
+    let mut buf = Vec::new();
+    buf.try_reserve_exact(round_p(p) / WORD_BIT_SIZE)?;
+    buf.resize(round_p(p) / WORD_BIT_SIZE, 0);
+
+    let l = buf.len().min(m.len());
+    let dst = buf.len() - l;
+    buf[dst..].copy_from_slice(&m[m.len() - l..]);
+
+    let rem = buf.len() * WORD_BIT_SIZE - p;
+    if rem > 0 {
+        buf[0] &= Word::MAX << rem;
+    }
+
+    let n = buf.len() * WORD_BIT_SIZE;
+    let ret = BigFloat::from_raw_parts(&buf, n, s, e, false);
+
+    ret.err().map_or(Ok(ret), Err)
+}
+
+// Returns the number following `x` in the set of numbers of precision `p`.
+fn next_up(x: &BigFloat, p: usize) -> Result<BigFloat, Error> {
+    let e = x.exponent().ok_or(Error::InvalidArgument)?;
+
+    let mut ulp = BigFloat::from_word(1, 1);
+    ulp.set_exponent(e - p as Exponent + 1);
+
+    if ulp.is_zero() || ulp.sign() != Some(Sign::Pos) {
+        return Err(Error::InvalidArgument);
+    }
+
+    let ret = x.add(&ulp, round_p(p), RoundingMode::ToZero);
+
+    ret.err().map_or(Ok(ret), Err)
+}
+
+// Length of the run of identical bits starting from the bit `start` counting from the most significant bit.
+fn run_len(m: &[Word], start: usize) -> usize {
+    let bit = |i: usize| -> bool {
+        let w = m[m.len() - 1 - i / WORD_BIT_SIZE];
+        (w >> (WORD_BIT_SIZE - 1 - i % WORD_BIT_SIZE)) & 1 != 0
+    };
+
+    let n = m.len() * WORD_BIT_SIZE;
+
+    if start >= n {
+        return 0;
+    }
+
+    let b = bit(start);
+    let mut i = start + 1;
+
+    while i < n && bit(i) == b {
+        i += 1;
+    }
+
+    i - start - 1
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_hardround() {
+        let mut cc = Consts::new().unwrap();
+
+        let p = 16;
+        let min_run = 8;
+        let one = BigFloat::from_word(1, 1);
+
+        for rm in [RoundingMode::ToEven, RoundingMode::Down] {
+            let cases = search(
+                |x, p, rm, _| x.sqrt(p, rm),
+                &one,
+                2000,
+                p,
+                rm,
+                min_run,
+                &mut cc,
+            )
+            .unwrap();
+
+            assert!(!cases.is_empty());
+
+            let start = if rm == RoundingMode::Down { p } else { p + 1 };
+
+            for c in cases.iter() {
+                assert!(c.run >= min_run);
+
+                // exact squares are never reported
+                assert!(c.y.inexact());
+
+                // verify with larger precision
+                let y = c.x.sqrt(512, RoundingMode::ToZero);
+                let (m, _, _, _, _) = y.as_raw_parts().unwrap();
+                assert_eq!(run_len(m, start), c.run);
+            }
+        }
+
+        // all arguments are checked
+        let cases = search(
+            |x, p, rm, _| x.sqrt(p, rm),
+            &one,
+            100,
+            p,
+            RoundingMode::ToEven,
+            0,
+            &mut cc,
+        )
+        .unwrap();
+
+        // 1 is the only exact square among the first 100 numbers
+        assert_eq!(cases.len(), 99);
+
+        for (i, c) in cases.iter().enumerate() {
+            let x = one.add(
+                &BigFloat::from_word(i as Word + 1, 64).mul(
+                    &{
+                        let mut u = BigFloat::from_word(1, 1);
+                        u.set_exponent(2 - p as Exponent);
+                        u
+                    },
+                    64,
+                    RoundingMode::None,
+                ),
+                64,
+                RoundingMode::None,
+            );
+            assert_eq!(c.x.cmp(&x), Some(0));
+        }
+
+        assert!(search(
+            |x, p, rm, _| x.sqrt(p, rm),
+            &BigFloat::new(64),
+            1,
+            p,
+            RoundingMode::ToEven,
+            0,
+            &mut cc,
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,527 @@
+//! Encoding and decoding of IEEE 754 binary128 and binary256 interchange formats.
+
+use crate::common::util::round_p;
+use crate::common::util::u64_to_words;
+use crate::defs::Error;
+use crate::defs::Exponent;
+use crate::defs::RoundingMode;
+use crate::defs::Sign;
+use crate::defs::WORD_BIT_SIZE;
+use crate::num::BigFloatNumber;
+use crate::BigFloat;
+use crate::INF_NEG;
+use crate::INF_POS;
+use crate::NAN;
+
+/// Precision of binary128 including the implicit bit.
+const BINARY128_P: usize = 113;
+
+/// Precision of binary256 including the implicit bit.
+const BINARY256_P: usize = 237;
+
+impl BigFloatNumber {
+    /// Encodes `self` as IEEE 754 binary128 bit pattern using the rounding mode `rm`.
+    /// `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the encoding is inexact.
+    /// The value of `self` is considered regardless of the inexact flag.
+    /// Values outside of the range of binary128 are encoded as the infinity or as the largest finite number depending on the rounding mode.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    pub fn to_binary128_bits(&self, rm: RoundingMode) -> Result<(u128, bool), Error> {
+        let (w, inexact) = self.to_ieee_words::<2>(BINARY128_P, rm)?;
+        Ok((w[0] as u128 | (w[1] as u128) << 64, inexact))
+    }
+
+    /// Decodes IEEE 754 binary128 bit pattern `bits` into a number with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect or smaller than the precision of binary128, or `bits` is infinity or NaN.
+    pub fn from_binary128_bits(bits: u128, p: usize) -> Result<Self, Error> {
+        Self::from_ieee_words(&[bits as u64, (bits >> 64) as u64], BINARY128_P, p)
+    }
+
+    /// Encodes `self` as IEEE 754 binary256 bit pattern using the rounding mode `rm`.
+    /// The bit pattern is returned as an array of bytes in little-endian byte order.
+    /// `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the encoding is inexact.
+    /// The value of `self` is considered regardless of the inexact flag.
+    /// Values outside of the range of binary256 are encoded as the infinity or as the largest finite number depending on the rounding mode.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    pub fn to_binary256_bytes(&self, rm: RoundingMode) -> Result<([u8; 32], bool), Error> {
+        let (w, inexact) = self.to_ieee_words::<4>(BINARY256_P, rm)?;
+        Ok((words_to_bytes(&w), inexact))
+    }
+
+    /// Decodes IEEE 754 binary256 bit pattern given as an array of bytes `bytes` in little-endian byte order into a number with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect or smaller than the precision of binary256, or `bytes` is infinity or NaN.
+    pub fn from_binary256_bytes(bytes: &[u8; 32], p: usize) -> Result<Self, Error> {
+        Self::from_ieee_words(&bytes_to_words(bytes), BINARY256_P, p)
+    }
+
+    // Encodes `self` in the interchange format of N 64-bit words with precision `pm`.
+    // Words are in little-endian order.
+    fn to_ieee_words<const N: usize>(
+        &self,
+        pm: usize,
+        rm: RoundingMode,
+    ) -> Result<([u64; N], bool), Error> {
+        let ew = N * 64 - pm;
+        let emax = 1isize << (ew - 1);
+        let emin = 3 - emax;
+        let inf_biased = (1u64 << ew) - 1;
+
+        let is_neg = self.is_negative();
+        let mut ret = [0u64; N];
+        let mut inexact = false;
+
+        if !self.is_zero() {
+            let e = self.exponent() as isize;
+
+            // the biased exponent and the significand including the implicit bit
+            let mut biased = inf_biased;
+
+            if e <= emax {
+                let k = e.max(emin);
+
+                let mut x = self.clone()?;
+                x.set_exponent((e - k + pm as isize) as Exponent);
+                x.set_inexact(false);
+
+                let s = x.round_int(rm)?;
+                inexact = s.cmp(&x) != 0;
+
+                s.int_abs_to_u64_words(&mut ret);
+
+                // the significand is 2^pm if rounding carried over to the next binade
+                let top = get_bit(&ret, pm - 1) as u64 + 2 * get_bit(&ret, pm) as u64;
+                biased = (k - emin) as u64 + top;
+
+                clear_bits_from(&mut ret, pm - 1);
+            }
+
+            if biased >= inf_biased {
+                let is_inf = match rm {
+                    RoundingMode::Up => !is_neg,
+                    RoundingMode::Down => is_neg,
+                    RoundingMode::ToZero | RoundingMode::None => false,
+                    RoundingMode::FromZero | RoundingMode::ToEven | RoundingMode::ToOdd => true,
+                };
+
+                if is_inf {
+                    ret = [0; N];
+                    biased = inf_biased;
+                } else {
+                    ret = [u64::MAX; N];
+                    clear_bits_from(&mut ret, pm - 1);
+                    biased = inf_biased - 1;
+                }
+
+                inexact = true;
+            }
+
+            set_field(&mut ret, pm - 1, biased);
+        }
+
+        if is_neg {
+            set_field(&mut ret, N * 64 - 1, 1);
+        }
+
+        Ok((ret, inexact))
+    }
+
+    // Decodes the interchange format of N 64-bit words with precision `pm`.
+    fn from_ieee_words<const N: usize>(w: &[u64; N], pm: usize, p: usize) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if p < pm {
+            return Err(Error::InvalidArgument);
+        }
+
+        let ew = N * 64 - pm;
+        let emax = 1isize << (ew - 1);
+        let emin = 3 - emax;
+        let inf_biased = (1u64 << ew) - 1;
+
+        let s = if get_bit(w, N * 64 - 1) { Sign::Neg } else { Sign::Pos };
+
+        let mut biased = 0;
+        for i in 0..ew {
+            biased |= (get_bit(w, pm - 1 + i) as u64) << i;
+        }
+
+        if biased == inf_biased {
+            return Err(Error::InvalidArgument);
+        }
+
+        let mut m = *w;
+        clear_bits_from(&mut m, pm - 1);
+
+        let k = if biased == 0 {
+            emin
+        } else {
+            set_field(&mut m, pm - 1, 1);
+            biased as isize + emin - 1
+        };
+
+        // value = m * 2^(k - pm), and m as a fraction of N words is m * 2^-(N * 64)
+        let words = u64_to_words(&m);
+        let mut ret = Self::from_words(&words, s, (N as isize * 64 + k - pm as isize) as Exponent)?;
+        ret.set_precision(p, RoundingMode::None)?;
+
+        Ok(ret)
+    }
+
+    // Writes the integer part of |self| to `out` as little-endian 64-bit words.
+    // The integer part must fit in `out`.
+    #[allow(clippy::unnecessary_cast)] // Word can be u32
+    fn int_abs_to_u64_words(&self, out: &mut [u64]) {
+        if self.exponent() <= 0 {
+            return;
+        }
+
+        let shift = self.mantissa_max_bit_len() as isize - self.exponent() as isize;
+
+        for (i, d) in self.mantissa().digits().iter().enumerate() {
+            let mut w = *d as u64;
+            let mut pos = (i * WORD_BIT_SIZE) as isize - shift;
+
+            if pos + WORD_BIT_SIZE as isize <= 0 {
+                continue;
+            }
+
+            if pos < 0 {
+                w >>= -pos;
+                pos = 0;
+            }
+
+            set_field(out, pos as usize, w);
+        }
+    }
+}
+
+impl BigFloat {
+    /// Encodes `self` as IEEE 754 binary128 bit pattern using the rounding mode `rm`.
+    /// `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the encoding is inexact.
+    /// Inf is encoded as the infinity of the same sign, and NaN is encoded as a quiet NaN.
+    /// The function returns the encoding of NaN if memory allocation fails.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use astro_float_num::{BigFloat, RoundingMode};
+    /// let (bits, inexact) = BigFloat::from_f64(1.0, 128).to_binary128_bits(RoundingMode::ToEven);
+    /// assert_eq!(bits, 0x3fff_0000_0000_0000_0000_0000_0000_0000);
+    /// assert!(!inexact);
+    /// assert_eq!(BigFloat::from_binary128_bits(bits, 128), BigFloat::from_f64(1.0, 128));
+    /// ```
+    pub fn to_binary128_bits(&self, rm: RoundingMode) -> (u128, bool) {
+        if let Some(Ok(r)) = self.as_number().map(|v| v.to_binary128_bits(rm)) {
+            return r;
+        }
+
+        let w = self.ieee_special::<2>(BINARY128_P);
+        (w[0] as u128 | (w[1] as u128) << 64, false)
+    }
+
+    /// Decodes IEEE 754 binary128 bit pattern `bits` into a number with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect or smaller than the precision of binary128.
+    pub fn from_binary128_bits(bits: u128, p: usize) -> Self {
+        if let Some(v) = Self::from_ieee_special(&[bits as u64, (bits >> 64) as u64], BINARY128_P) {
+            return v;
+        }
+
+        Self::result_to_ext(BigFloatNumber::from_binary128_bits(bits, p), false, true)
+    }
+
+    /// Encodes `self` as IEEE 754 binary256 bit pattern using the rounding mode `rm`.
+    /// The bit pattern is returned as an array of bytes in little-endian byte order.
+    /// `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the encoding is inexact.
+    /// Inf is encoded as the infinity of the same sign, and NaN is encoded as a quiet NaN.
+    /// The function returns the encoding of NaN if memory allocation fails.
+    pub fn to_binary256_bytes(&self, rm: RoundingMode) -> ([u8; 32], bool) {
+        if let Some(Ok(r)) = self.as_number().map(|v| v.to_binary256_bytes(rm)) {
+            return r;
+        }
+
+        (words_to_bytes(&self.ieee_special::<4>(BINARY256_P)), false)
+    }
+
+    /// Decodes IEEE 754 binary256 bit pattern given as an array of bytes `bytes` in little-endian byte order into a number with precision `p`.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect or smaller than the precision of binary256.
+    pub fn from_binary256_bytes(bytes: &[u8; 32], p: usize) -> Self {
+        if let Some(v) = Self::from_ieee_special(&bytes_to_words(bytes), BINARY256_P) {
+            return v;
+        }
+
+        Self::result_to_ext(BigFloatNumber::from_binary256_bytes(bytes, p), false, true)
+    }
+
+    // Returns the encoding of Inf if `self` is Inf, and the encoding of quiet NaN otherwise.
+    fn ieee_special<const N: usize>(&self, pm: usize) -> [u64; N] {
+        let ew = N * 64 - pm;
+        let mut ret = [0u64; N];
+
+        if self.is_inf() {
+            if self.is_inf_neg() {
+                set_field(&mut ret, N * 64 - 1, 1);
+            }
+            set_field(&mut ret, pm - 1, (1u64 << ew) - 1);
+        } else {
+            set_field(&mut ret, pm - 2, (1u64 << (ew + 1)) - 1);
+        }
+
+        ret
+    }
+
+    // Returns Inf or NaN if `w` is the encoding of Inf or NaN, otherwise returns None.
+    fn from_ieee_special<const N: usize>(w: &[u64; N], pm: usize) -> Option<Self> {
+        let ew = N * 64 - pm;
+        let mut biased = 0;
+        for i in 0..ew {
+            biased |= (get_bit(w, pm - 1 + i) as u64) << i;
+        }
+
+        if biased == (1u64 << ew) - 1 {
+            let mut m = *w;
+            clear_bits_from(&mut m, pm - 1);
+            if m.iter().any(|v| *v != 0) {
+                Some(NAN)
+            } else if get_bit(w, N * 64 - 1) {
+                Some(INF_NEG)
+            } else {
+                Some(INF_POS)
+            }
+        } else {
+            None
+        }
+    }
+}
+
+fn get_bit(w: &[u64], i: usize) -> bool {
+    w[i / 64] & (1 << (i % 64)) != 0
+}
+
+// Adds `v` shifted left by `i` bits to `w`, the bits must be zero. The bits which do not fit in `w` are dropped.
+fn set_field(w: &mut [u64], i: usize, v: u64) {
+    let (idx, shift) = (i / 64, i % 64);
+
+    if idx < w.len() {
+        w[idx] |= v << shift;
+    }
+
+    if shift > 0 && idx + 1 < w.len() {
+        w[idx + 1] |= v >> (64 - shift);
+    }
+}
+
+// Sets all bits starting from the bit `i` to zero.
+fn clear_bits_from(w: &mut [u64], i: usize) {
+    let (idx, shift) = (i / 64, i % 64);
+
+    w[idx] &= (1 << shift) - 1;
+
+    for v in w[idx + 1..].iter_mut() {
+        *v = 0;
+    }
+}
+
+fn words_to_bytes(w: &[u64; 4]) -> [u8; 32] {
+    let mut ret = [0u8; 32];
+    for (c, v) in ret.chunks_mut(8).zip(w.iter()) {
+        c.copy_from_slice(&v.to_le_bytes());
+    }
+    ret
+}
+
+fn bytes_to_words(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut ret = [0u64; 4];
+    for (c, v) in bytes.chunks(8).zip(ret.iter_mut()) {
+        let mut b = [0u8; 8];
+        b.copy_from_slice(c);
+        *v = u64::from_le_bytes(b);
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::random;
+
+    #[test]
+    fn test_binary128() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // known patterns
+        let vals = [
+            (1.0, 0x3fff_0000_0000_0000_0000_0000_0000_0000u128),
+            (-2.0, 0xc000_0000_0000_0000_0000_0000_0000_0000),
+            (0.0, 0),
+            (1.5, 0x3fff_8000_0000_0000_0000_0000_0000_0000),
+        ];
+        for (f, bits) in vals {
+            let d1 = BigFloat::from_f64(f, p);
+            assert_eq!(d1.to_binary128_bits(rm), (bits, false));
+            assert_eq!(BigFloat::from_binary128_bits(bits, p), d1);
+        }
+
+        // f64 values are exact
+        for _ in 0..1000 {
+            let f = f64::from_bits(random::<u64>());
+            if !f.is_finite() {
+                continue;
+            }
+            let d1 = BigFloat::from_f64(f, p);
+            let (bits, inexact) = d1.to_binary128_bits(rm);
+            assert!(!inexact);
+            let d2 = BigFloat::from_binary128_bits(bits, p);
+            assert_eq!(d2.to_f64_exact().unwrap().to_bits(), f.to_bits());
+        }
+
+        // random patterns round trip
+        for _ in 0..1000 {
+            let bits = random::<u128>();
+            let d1 = BigFloat::from_binary128_bits(bits, p);
+            if d1.is_nan() {
+                continue;
+            }
+            assert_eq!(d1.to_binary128_bits(RoundingMode::None), (bits, false));
+        }
+
+        // the smallest subnormal, the largest subnormal, and the smallest normal number
+        for bits in [1u128, (1 << 112) - 1, 1 << 112] {
+            let d1 = BigFloatNumber::from_binary128_bits(bits, p).unwrap();
+            assert_eq!(d1.to_binary128_bits(rm).unwrap(), (bits, false));
+        }
+
+        // rounding
+        let third = BigFloat::from_word(1, 256).div(&BigFloat::from_word(3, 256), 256, rm);
+        let (lo, inexact) = third.to_binary128_bits(RoundingMode::Down);
+        assert!(inexact);
+        assert_eq!(third.to_binary128_bits(RoundingMode::Up).0, lo + 1);
+        assert_eq!(lo, 0x3ffd_5555_5555_5555_5555_5555_5555_5555);
+
+        // carry to the next binade
+        let d1 = BigFloat::from_binary128_bits((1 << 112) - 1, p);
+        let d2 = BigFloat::from_binary128_bits(1, p);
+        let mut tiny = d2.clone();
+        tiny.set_exponent(d2.exponent().unwrap() - 10);
+        let d3 = d1.add(&tiny, 256, RoundingMode::None);
+        assert_eq!(d3.to_binary128_bits(RoundingMode::Up), (1 << 112, true));
+        assert_eq!(
+            BigFloat::max_value(256).to_binary128_bits(RoundingMode::Up),
+            (0x7fff << 112, true)
+        );
+
+        // overflow
+        let max = 0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffffu128;
+        let d1 = BigFloat::from_binary128_bits(max, p);
+        let mut tiny = d1.clone();
+        tiny.set_exponent(d1.exponent().unwrap() - 120);
+        let d1 = d1.add(&tiny, 256, RoundingMode::None);
+        assert_eq!(d1.to_binary128_bits(RoundingMode::ToZero), (max, true));
+        assert_eq!(
+            d1.to_binary128_bits(RoundingMode::Up),
+            (0x7fff << 112, true)
+        );
+        let d1 = d1.neg();
+        assert_eq!(
+            d1.to_binary128_bits(RoundingMode::Up),
+            (max | 1 << 127, true)
+        );
+
+        // underflow
+        let min_positive = BigFloat::min_positive(p);
+        assert_eq!(min_positive.to_binary128_bits(RoundingMode::Up), (1, true));
+        assert_eq!(
+            min_positive.to_binary128_bits(RoundingMode::ToEven),
+            (0, true)
+        );
+        assert_eq!(
+            min_positive.neg().to_binary128_bits(RoundingMode::Up),
+            (1 << 127, true)
+        );
+
+        // special values
+        assert_eq!(INF_POS.to_binary128_bits(rm), (0x7fff << 112, false));
+        assert_eq!(INF_NEG.to_binary128_bits(rm), (0xffff << 112, false));
+        assert_eq!(NAN.to_binary128_bits(rm), (0x7fff8 << 108, false));
+        assert!(BigFloat::from_binary128_bits(0x7fff8 << 108, p).is_nan());
+        assert!(BigFloat::from_binary128_bits(0x7fff << 112, p).is_inf_pos());
+        assert!(BigFloat::from_binary128_bits(0xffff << 112, p).is_inf_neg());
+        assert_eq!(
+            BigFloatNumber::from_binary128_bits(0x7fff << 112, p).unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // insufficient precision
+        assert!(BigFloat::from_binary128_bits(1, 64).is_nan());
+    }
+
+    #[test]
+    fn test_binary256() {
+        let p = 256;
+        let rm = RoundingMode::ToEven;
+
+        // 1.0 has the biased exponent 2^18 - 1
+        let mut one = [0u8; 32];
+        one[31] = 0x3f;
+        one[30] = 0xff;
+        one[29] = 0xf0;
+        let d1 = BigFloat::from_f64(1.0, p);
+        assert_eq!(d1.to_binary256_bytes(rm), (one, false));
+        assert_eq!(BigFloat::from_binary256_bytes(&one, p), d1);
+
+        let third = BigFloat::from_word(1, 512).div(&BigFloat::from_word(3, 512), 512, rm);
+        let (bytes, inexact) = third.to_binary256_bytes(rm);
+        assert!(inexact);
+        let d2 = BigFloat::from_binary256_bytes(&bytes, p);
+        let err = d2.sub(&third, 512, rm).abs();
+        assert!(err.exponent().unwrap() <= third.exponent().unwrap() - 237);
+
+        // random patterns round trip
+        for _ in 0..1000 {
+            let mut bytes = [0u8; 32];
+            for b in bytes.iter_mut() {
+                *b = random();
+            }
+            let d1 = BigFloat::from_binary256_bytes(&bytes, p);
+            if d1.is_nan() {
+                continue;
+            }
+            assert_eq!(d1.to_binary256_bytes(RoundingMode::None), (bytes, false));
+        }
+
+        // the smallest subnormal
+        let mut bytes = [0u8; 32];
+        bytes[0] = 1;
+        let d1 = BigFloatNumber::from_binary256_bytes(&bytes, p).unwrap();
+        assert_eq!(d1.to_binary256_bytes(rm).unwrap(), (bytes, false));
+        assert_eq!(d1.exponent() as isize, 3 - (1 << 18) - 236);
+
+        let (bytes, _) = INF_NEG.to_binary256_bytes(rm);
+        assert!(BigFloat::from_binary256_bytes(&bytes, p).is_inf_neg());
+        let (bytes, _) = NAN.to_binary256_bytes(rm);
+        assert!(BigFloat::from_binary256_bytes(&bytes, p).is_nan());
+    }
+}
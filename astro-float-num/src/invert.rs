@@ -0,0 +1,359 @@
+//! Inversion of functions.
+
+use crate::common::util::round_p;
+use crate::BigFloat;
+use crate::Error;
+use crate::Exponent;
+use crate::RoundingMode;
+use crate::WORD_BIT_SIZE;
+
+/// Finds `x` in the interval `bracket` such that `f(x) = y`, and returns `x` with precision `p` rounded using the rounding mode `rm`.
+///
+/// The function `f` is called as `f(x, p_wrk, rm_wrk)`, where `p_wrk` is the working precision chosen by the search,
+/// and `rm_wrk` is either `RoundingMode::Down`, or `RoundingMode::Up`.
+/// `f` is expected to be continuous in `bracket` and to return the result rounded according to `rm_wrk`,
+/// as the built-in functions do, e.g. `|x, p, rm| x.exp(p, rm, &mut cc)`.
+///
+/// The sign of `f(x) - y` is certified at each point of the search: it is determined only if the values of `f(x)`
+/// rounded down and rounded up are both on the same side of `y`; otherwise the working precision is increased.
+/// The search maintains an enclosure of `x` with certified sign change and stops when all numbers in the enclosure
+/// round to the same value. If the sign can't be certified with precision up to `8*p`, `f(x)` is considered equal to `y`.
+/// If there is more than one solution in `bracket`, any of them can be returned.
+///
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if `y` or any end of `bracket` is not finite, `f` has no sign change at the ends of `bracket`,
+/// `f` returns NaN, or the precision `p` is incorrect.
+pub fn invert<F>(
+    f: F,
+    y: &BigFloat,
+    bracket: (&BigFloat, &BigFloat),
+    p: usize,
+    rm: RoundingMode,
+) -> BigFloat
+where
+    F: FnMut(&BigFloat, usize, RoundingMode) -> BigFloat,
+{
+    match invert_internal(f, y, bracket, p, rm) {
+        Ok(v) => v,
+        Err(e) => BigFloat::nan(Some(e)),
+    }
+}
+
+// Side of the enclosure updated on the previous step.
+#[derive(PartialEq)]
+enum Side {
+    None,
+    Lo,
+    Hi,
+}
+
+fn invert_internal<F>(
+    mut f: F,
+    y: &BigFloat,
+    bracket: (&BigFloat, &BigFloat),
+    p: usize,
+    rm: RoundingMode,
+) -> Result<BigFloat, Error>
+where
+    F: FnMut(&BigFloat, usize, RoundingMode) -> BigFloat,
+{
+    let p = round_p(p);
+
+    let (a, b) = bracket;
+
+    if y.is_nan() || y.is_inf() || a.is_nan() || a.is_inf() || b.is_nan() || b.is_inf() {
+        return Err(Error::InvalidArgument);
+    }
+
+    let (mut lo, mut hi) = if a > b { (b.clone(), a.clone()) } else { (a.clone(), b.clone()) };
+
+    let p_max = 8 * p;
+
+    let (slo, mut glo) = eval(&mut f, &lo, y, p, p_max)?;
+    if slo == 0 {
+        return round(&lo, p, rm);
+    }
+
+    let (shi, mut ghi) = eval(&mut f, &hi, y, p, p_max)?;
+    if shi == 0 {
+        return round(&hi, p, rm);
+    }
+
+    if slo == shi {
+        return Err(Error::InvalidArgument);
+    }
+
+    // precision of the points inside the enclosure
+    let mut p_wrk = p
+        .max(lo.mantissa_max_bit_len().unwrap_or(p))
+        .max(hi.mantissa_max_bit_len().unwrap_or(p))
+        + 2 * WORD_BIT_SIZE;
+
+    let mut side = Side::None;
+    let mut steps = 0;
+    let mut ref_width = hi.sub(&lo, p_wrk, RoundingMode::Up);
+
+    loop {
+        let rlo = round(&lo, p, rm)?;
+        let rhi = round(&hi, p, rm)?;
+
+        if rlo == rhi {
+            return Ok(rlo);
+        }
+
+        let mut x = None;
+
+        if next_up(&rlo, p)? == rhi {
+            // the enclosure contains at most one point where the rounded value changes.
+            let mut m = rlo.add_full_prec(&rhi);
+            if let Some(e) = m.exponent() {
+                if !m.is_zero() {
+                    m.set_exponent(e - 1);
+                }
+            }
+
+            for c in [m, rlo, rhi] {
+                if lo < c && c < hi {
+                    x = Some(c);
+                    break;
+                }
+            }
+
+            if x.is_none() {
+                return round(&mid(&lo, &hi, p_wrk + WORD_BIT_SIZE), p, rm);
+            }
+        }
+
+        let x = match x {
+            Some(x) => x,
+            None => {
+                // Illinois method with fallback to bisection
+                let c = if steps < 3 && !glo.is_zero() && !ghi.is_zero() {
+                    let d1 = lo.mul(&ghi, p_wrk, RoundingMode::None);
+                    let d2 = hi.mul(&glo, p_wrk, RoundingMode::None);
+                    let d3 = d1.sub(&d2, p_wrk, RoundingMode::None);
+                    let d4 = ghi.sub(&glo, p_wrk, RoundingMode::None);
+                    d3.div(&d4, p_wrk, RoundingMode::None)
+                } else {
+                    mid(&lo, &hi, p_wrk)
+                };
+
+                if lo < c && c < hi {
+                    c
+                } else {
+                    let c = mid(&lo, &hi, p_wrk);
+
+                    if lo < c && c < hi {
+                        c
+                    } else {
+                        p_wrk += WORD_BIT_SIZE;
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let (s, g) = eval(&mut f, &x, y, p, p_max)?;
+
+        if s == 0 {
+            return round(&x, p, rm);
+        }
+
+        if s == slo {
+            lo = x;
+            glo = g;
+            if side == Side::Lo {
+                if let Some(e) = ghi.exponent() {
+                    ghi.set_exponent(e - 1);
+                }
+            }
+            side = Side::Lo;
+        } else {
+            hi = x;
+            ghi = g;
+            if side == Side::Hi {
+                if let Some(e) = glo.exponent() {
+                    glo.set_exponent(e - 1);
+                }
+            }
+            side = Side::Hi;
+        }
+
+        let width = hi.sub(&lo, p_wrk, RoundingMode::Up);
+        let mut half = ref_width.clone();
+        if let Some(e) = half.exponent() {
+            half.set_exponent(e - 1);
+        }
+
+        if width <= half {
+            ref_width = width;
+            steps = 0;
+        } else {
+            steps += 1;
+        }
+    }
+}
+
+// Returns the certified sign of f(x) - y, and an approximation of f(x) - y.
+fn eval<F>(
+    f: &mut F,
+    x: &BigFloat,
+    y: &BigFloat,
+    p: usize,
+    p_max: usize,
+) -> Result<(i8, BigFloat), Error>
+where
+    F: FnMut(&BigFloat, usize, RoundingMode) -> BigFloat,
+{
+    let mut p_wrk = p + WORD_BIT_SIZE;
+
+    loop {
+        let d1 = f(x, p_wrk, RoundingMode::Down);
+        let d2 = f(x, p_wrk, RoundingMode::Up);
+
+        if d1.is_nan() {
+            return Err(d1.err().unwrap_or(Error::InvalidArgument));
+        }
+
+        if d2.is_nan() {
+            return Err(d2.err().unwrap_or(Error::InvalidArgument));
+        }
+
+        if d1 > y {
+            return Ok((1, d1.sub(y, p_wrk, RoundingMode::None)));
+        }
+
+        if d2 < y {
+            return Ok((-1, d2.sub(y, p_wrk, RoundingMode::None)));
+        }
+
+        if (d1 == y && d2 == y) || p_wrk >= p_max {
+            return Ok((0, BigFloat::new(p_wrk)));
+        }
+
+        p_wrk *= 2;
+    }
+}
+
+// Returns `x` rounded to precision `p` using rounding mode `rm`.
+fn round(x: &BigFloat, p: usize, rm: RoundingMode) -> Result<BigFloat, Error> {
+    let mut ret = x.clone();
+    ret.set_precision(p, rm)?;
+    Ok(ret)
+}
+
+// Returns the smallest number of precision `p` which is larger than `x` of precision `p`.
+fn next_up(x: &BigFloat, p: usize) -> Result<BigFloat, Error> {
+    if x.is_zero() {
+        return Ok(BigFloat::min_positive(p));
+    }
+
+    let e = x.exponent().ok_or(Error::InvalidArgument)?;
+
+    // the step is smaller if a negative x is a power of 2
+    for shift in [1, 0] {
+        let mut ulp = BigFloat::from_word(1, p);
+        ulp.set_exponent(e - p as Exponent + 1 - shift);
+
+        let ret = x.add(&ulp, p + WORD_BIT_SIZE, RoundingMode::None);
+        let rounded = round(&ret, p, RoundingMode::ToZero)?;
+
+        if ret == rounded {
+            return Ok(rounded);
+        }
+    }
+
+    Err(Error::InvalidArgument)
+}
+
+// Returns (a + b) / 2.
+fn mid(a: &BigFloat, b: &BigFloat, p: usize) -> BigFloat {
+    let mut ret = a.add(b, p, RoundingMode::None);
+    if let Some(e) = ret.exponent() {
+        if !ret.is_zero() {
+            ret.set_exponent(e - 1);
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Consts;
+
+    #[test]
+    fn test_invert() {
+        let mut cc = Consts::new().unwrap();
+
+        for rm in [
+            RoundingMode::ToEven,
+            RoundingMode::ToOdd,
+            RoundingMode::Up,
+            RoundingMode::Down,
+            RoundingMode::ToZero,
+            RoundingMode::FromZero,
+        ] {
+            for p in [64, 128, 320] {
+                // ln(x) = 2  ->  x = e^2
+                let y = BigFloat::from_word(2, p);
+                let x = invert(
+                    |x, p, rm| x.ln(p, rm, &mut cc),
+                    &y,
+                    (&BigFloat::from_word(1, p), &BigFloat::from_word(100, p)),
+                    p,
+                    rm,
+                );
+                assert_eq!(x, y.exp(p, rm, &mut cc));
+
+                // decreasing function: 1/x = -3  ->  x = -1/3
+                let y = BigFloat::from_i8(-3, p);
+                let x = invert(
+                    |x, p, rm| x.reciprocal(p, rm),
+                    &y,
+                    (&BigFloat::from_f64(-0.001, p), &BigFloat::from_i8(-100, p)),
+                    p,
+                    rm,
+                );
+                assert_eq!(x, y.reciprocal(p, rm));
+            }
+        }
+
+        // exact result
+        let p = 128;
+        let y = BigFloat::from_word(49, p);
+        let x = invert(
+            |x, p, rm| x.mul(x, p, rm),
+            &y,
+            (&BigFloat::from_word(0, p), &BigFloat::from_word(10, p)),
+            p,
+            RoundingMode::Up,
+        );
+        assert_eq!(x, BigFloat::from_word(7, p));
+
+        // the solution is close to a number of precision p: sqrt(x) = 1 + 2^(-128)  ->  x = 1 + 2^(-127) + 2^(-256)
+        let mut d = BigFloat::from_word(1, p);
+        d.set_exponent(-127);
+        let y = BigFloat::from_word(1, p).add(&d, p * 2, RoundingMode::None);
+        let x = invert(
+            |x, p, rm| x.sqrt(p, rm),
+            &y,
+            (&BigFloat::from_word(1, p), &BigFloat::from_word(2, p)),
+            p,
+            RoundingMode::ToEven,
+        );
+        assert_eq!(x, y.mul(&y, p, RoundingMode::ToEven));
+
+        // no sign change
+        let y = BigFloat::from_word(10, p);
+        let x = invert(
+            |x, p, rm| x.mul(x, p, rm),
+            &y,
+            (&BigFloat::from_word(4, p), &BigFloat::from_word(10, p)),
+            p,
+            RoundingMode::Up,
+        );
+        assert!(x.is_nan());
+    }
+}
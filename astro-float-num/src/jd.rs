@@ -0,0 +1,147 @@
+//! Conversion between Julian dates, Modified Julian dates, and seconds.
+//!
+//! A Julian date of the current epoch stored in a double precision number has a resolution of about 40 microseconds,
+//! and the conversion of a Julian date to seconds past an epoch loses even more if the intermediate results are rounded.
+//! The functions of this module compute each conversion with a single rounding,
+//! so the result is exact whenever it fits into the requested precision and the arguments are exact.
+//!
+//! The epochs are given as Julian dates, e.g. `BigFloat::from_f64(J2000, 64)`.
+
+use crate::defs::RoundingMode;
+use crate::BigFloat;
+
+/// Julian date of the epoch of Modified Julian dates, 1858-11-17 00:00.
+pub const MJD_EPOCH: f64 = 2400000.5;
+
+/// Julian date of the epoch J2000.0, 2000-01-01 12:00.
+pub const J2000: f64 = 2451545.0;
+
+/// Julian date of the Unix epoch, 1970-01-01 00:00.
+pub const UNIX_EPOCH: f64 = 2440587.5;
+
+/// Number of seconds in a day.
+pub const SECONDS_PER_DAY: u32 = 86400;
+
+/// Converts the Julian date `jd` to the Modified Julian date with precision `p`.
+/// The result is rounded using the rounding mode `rm`.
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if the precision `p` is incorrect.
+pub fn mjd_from_jd(jd: &BigFloat, p: usize, rm: RoundingMode) -> BigFloat {
+    jd.sub(&mjd_epoch(), p, rm)
+}
+
+/// Converts the Modified Julian date `mjd` to the Julian date with precision `p`.
+/// The result is rounded using the rounding mode `rm`.
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if the precision `p` is incorrect.
+pub fn jd_from_mjd(mjd: &BigFloat, p: usize, rm: RoundingMode) -> BigFloat {
+    mjd.add(&mjd_epoch(), p, rm)
+}
+
+/// Converts the number of days `days` to seconds with precision `p`.
+/// The result is rounded using the rounding mode `rm`.
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if the precision `p` is incorrect.
+pub fn seconds_from_days(days: &BigFloat, p: usize, rm: RoundingMode) -> BigFloat {
+    days.mul(&seconds_per_day(), p, rm)
+}
+
+/// Converts the number of seconds `secs` to days with precision `p`.
+/// The result is rounded using the rounding mode `rm`.
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if the precision `p` is incorrect.
+pub fn days_from_seconds(secs: &BigFloat, p: usize, rm: RoundingMode) -> BigFloat {
+    secs.div(&seconds_per_day(), p, rm)
+}
+
+/// Computes the number of seconds elapsed from the Julian date `epoch` to the Julian date `jd` with precision `p`.
+/// The result is rounded using the rounding mode `rm` with a single rounding.
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if the precision `p` is incorrect.
+///
+/// The difference of the dates is computed exactly, so the memory use grows with the difference
+/// of the exponents of `jd` and `epoch`.
+pub fn seconds_from_jd(jd: &BigFloat, epoch: &BigFloat, p: usize, rm: RoundingMode) -> BigFloat {
+    jd.sub_full_prec(epoch).mul(&seconds_per_day(), p, rm)
+}
+
+/// Computes the Julian date which is `secs` seconds past the Julian date `epoch` with precision `p`.
+/// The result is rounded using the rounding mode `rm` with a single rounding.
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if the precision `p` is incorrect.
+///
+/// The sum of the epoch and the seconds is computed exactly, so the memory use grows with the difference
+/// of the exponents of `secs` and `epoch`.
+pub fn jd_from_seconds(secs: &BigFloat, epoch: &BigFloat, p: usize, rm: RoundingMode) -> BigFloat {
+    let spd = seconds_per_day();
+    epoch
+        .mul_full_prec(&spd)
+        .add_full_prec(secs)
+        .div(&spd, p, rm)
+}
+
+fn mjd_epoch() -> BigFloat {
+    BigFloat::from_f64(MJD_EPOCH, 64)
+}
+
+fn seconds_per_day() -> BigFloat {
+    BigFloat::from_u32(SECONDS_PER_DAY, 64)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_jd() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // exact conversions
+        let jd = BigFloat::from_f64(2460000.25, 64);
+        let mjd = mjd_from_jd(&jd, p, rm);
+        assert_eq!(mjd, BigFloat::from_f64(59999.75, p));
+        assert!(!mjd.inexact());
+        assert_eq!(jd_from_mjd(&mjd, p, rm), jd);
+
+        let days = BigFloat::from_f64(1.5, 64);
+        let secs = seconds_from_days(&days, p, rm);
+        assert_eq!(secs, BigFloat::from_word(129600, p));
+        assert_eq!(days_from_seconds(&secs, p, rm), days);
+
+        let j2000 = BigFloat::from_f64(J2000, 64);
+        let unix = BigFloat::from_f64(UNIX_EPOCH, 64);
+        let secs = seconds_from_jd(&j2000, &unix, p, rm);
+        assert_eq!(secs, BigFloat::from_word(946728000, p));
+        assert!(!secs.inexact());
+        assert_eq!(jd_from_seconds(&secs, &unix, p, rm), j2000);
+
+        // single rounding: one second past J2000
+        for rm in [RoundingMode::Up, RoundingMode::Down, RoundingMode::ToEven] {
+            let one = BigFloat::from_word(1, 64);
+            let d1 = jd_from_seconds(&one, &j2000, p, rm);
+            let d2 = BigFloat::from_u64(2451545 * 86400 + 1, p).div(
+                &BigFloat::from_word(86400, 64),
+                p,
+                rm,
+            );
+            assert_eq!(d1, d2);
+            assert!(d1.inexact());
+        }
+
+        // small differences are not lost
+        let mut eps = BigFloat::from_word(1, 64);
+        eps.set_exponent(-89);
+        let jd = j2000.add(&eps, p, rm);
+        let secs = seconds_from_jd(&jd, &j2000, p, rm);
+        assert_eq!(secs, eps.mul(&BigFloat::from_word(86400, 64), p, rm));
+        assert!(!secs.inexact());
+
+        // special values
+        assert!(mjd_from_jd(&crate::NAN, p, rm).is_nan());
+        assert!(jd_from_mjd(&crate::INF_POS, p, rm).is_inf_pos());
+        assert!(seconds_from_jd(&crate::INF_NEG, &j2000, p, rm).is_inf_neg());
+        assert!(jd_from_seconds(&crate::NAN, &j2000, p, rm).is_nan());
+    }
+}
@@ -0,0 +1,341 @@
+//! Stable memory layout of numbers for shared memory.
+//!
+//! A number is represented by a fixed-size `#[repr(C)]` header followed by the words of the mantissa.
+//! The layout does not depend on the compiler version, so the header and the words can be placed
+//! into a shared memory segment by one process, and the number can be reconstructed by another process
+//! without intermediate serialization. The header records the word size and the version of the layout,
+//! and the reconstruction validates the header and the words before constructing the number.
+//!
+//! ```
+//! use astro_float_num::layout::{self, RawHeader};
+//! use astro_float_num::{BigFloat, Word};
+//!
+//! let x = BigFloat::from_f64(-1.5, 128);
+//!
+//! // e.g. memory mapped by another process
+//! let mut header = RawHeader::default();
+//! let mut words: [Word; 4] = [0; 4];
+//!
+//! let n = layout::write(&x, &mut header, &mut words).unwrap();
+//!
+//! let y = layout::from_parts(&header, &words[..n]).unwrap();
+//! assert_eq!(x, y);
+//! ```
+
+use crate::defs::{Error, Exponent, Sign, Word, WORD_BIT_SIZE};
+use crate::num::BigFloatNumber;
+use crate::BigFloat;
+
+/// Value of the `magic` field of a valid header.
+pub const MAGIC: u32 = u32::from_le_bytes(*b"ASTF");
+
+/// Version of the layout.
+pub const VERSION: u16 = 1;
+
+/// The header contains a finite number.
+pub const KIND_VALUE: u8 = 0;
+
+/// The header contains an infinity.
+pub const KIND_INF: u8 = 1;
+
+/// The header contains NaN.
+pub const KIND_NAN: u8 = 2;
+
+/// Header of a number. The size of the header is 32 bytes, and the alignment is 8 bytes.
+/// The words of the mantissa follow the header; they are ordered from the least significant to the most significant.
+#[repr(C, align(8))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawHeader {
+    /// Equal to `MAGIC`.
+    pub magic: u32,
+
+    /// Version of the layout, equal to `VERSION`.
+    pub version: u16,
+
+    /// Size of a word in bits, 32 or 64.
+    pub word_bits: u8,
+
+    /// One of `KIND_VALUE`, `KIND_INF`, `KIND_NAN`.
+    pub kind: u8,
+
+    /// Sign of a finite number or an infinity: -1 or 1.
+    pub sign: i8,
+
+    /// 1 if the number is inexact, 0 otherwise.
+    pub inexact: u8,
+
+    /// Error which produced NaN: 0 - none, 1 - positive overflow, 2 - negative overflow,
+    /// 3 - division by zero, 4 - invalid argument, 5 - memory allocation.
+    pub err: u8,
+
+    /// Reserved, equal to 0.
+    pub reserved: u8,
+
+    /// Exponent of a finite number.
+    pub exponent: i32,
+
+    /// Number of significant bits in the mantissa.
+    pub bit_len: u64,
+
+    /// Number of words in the mantissa.
+    pub word_len: u64,
+}
+
+impl Default for RawHeader {
+    fn default() -> Self {
+        RawHeader {
+            magic: MAGIC,
+            version: VERSION,
+            word_bits: WORD_BIT_SIZE as u8,
+            kind: KIND_VALUE,
+            sign: Sign::Pos as i8,
+            inexact: 0,
+            err: 0,
+            reserved: 0,
+            exponent: 0,
+            bit_len: 0,
+            word_len: 0,
+        }
+    }
+}
+
+impl RawHeader {
+    /// Returns the header of `x`.
+    /// If the exponent of `x` does not fit in the header, which is possible with the feature `exp64`,
+    /// the header of NaN with the error `InvalidArgument` is returned.
+    pub fn new(x: &BigFloat) -> Self {
+        let mut ret = RawHeader::default();
+
+        if let Some((m, n, s, e, inexact)) = x.as_raw_parts() {
+            if let Ok(e) = i32::try_from(e as i64) {
+                ret.sign = s as i8;
+                ret.inexact = inexact as u8;
+                ret.exponent = e;
+                ret.bit_len = n as u64;
+                ret.word_len = m.len() as u64;
+            } else {
+                ret.kind = KIND_NAN;
+                ret.err = 4;
+            }
+        } else if x.is_inf() {
+            ret.kind = KIND_INF;
+            ret.sign = if x.is_inf_neg() { Sign::Neg as i8 } else { Sign::Pos as i8 };
+        } else {
+            ret.kind = KIND_NAN;
+            ret.err = match x.err() {
+                None => 0,
+                Some(Error::ExponentOverflow(Sign::Pos)) => 1,
+                Some(Error::ExponentOverflow(Sign::Neg)) => 2,
+                Some(Error::DivisionByZero) => 3,
+                Some(Error::InvalidArgument) => 4,
+                Some(Error::MemoryAllocation) => 5,
+            };
+        }
+
+        ret
+    }
+}
+
+/// Returns the header of `x`, and the words of the mantissa of `x` which follow the header.
+pub fn to_parts(x: &BigFloat) -> (RawHeader, &[Word]) {
+    let h = RawHeader::new(x);
+    let m = match x.as_raw_parts() {
+        Some((m, ..)) if h.kind == KIND_VALUE => m,
+        _ => &[],
+    };
+    (h, m)
+}
+
+/// Writes the header of `x` to `h`, and the words of the mantissa of `x` to the beginning of `m`.
+/// The function does not allocate memory, and returns the number of words written to `m`.
+///
+/// ## Errors
+///
+///  - InvalidArgument: `m` is shorter than the mantissa of `x`, or the exponent of `x` does not fit in the header.
+pub fn write(x: &BigFloat, h: &mut RawHeader, m: &mut [Word]) -> Result<usize, Error> {
+    let (header, words) = to_parts(x);
+
+    if m.len() < words.len() || (header.kind == KIND_NAN && !x.is_nan()) {
+        return Err(Error::InvalidArgument);
+    }
+
+    m[..words.len()].copy_from_slice(words);
+    *h = header;
+
+    Ok(words.len())
+}
+
+/// Reconstructs a number from the header `h` and the words of the mantissa `m`.
+///
+/// ## Errors
+///
+///  - MemoryAllocation: failed to allocate memory for mantissa.
+///  - InvalidArgument: `magic` or `version` of the header is not valid;
+///    the header was written on a platform with a different word size;
+///    `kind`, `sign`, `inexact`, `err`, or `reserved` has an invalid value;
+///    the length of `m` is not equal to `word_len`;
+///    the mantissa, the number of significant bits, and the exponent are not a valid representation of a number.
+pub fn from_parts(h: &RawHeader, m: &[Word]) -> Result<BigFloat, Error> {
+    if h.magic != MAGIC
+        || h.version != VERSION
+        || h.word_bits as usize != WORD_BIT_SIZE
+        || h.inexact > 1
+        || h.reserved != 0
+    {
+        return Err(Error::InvalidArgument);
+    }
+
+    let s = match h.sign {
+        -1 => Sign::Neg,
+        1 => Sign::Pos,
+        _ => return Err(Error::InvalidArgument),
+    };
+
+    match h.kind {
+        KIND_VALUE if h.err == 0 => {
+            if h.word_len != m.len() as u64 {
+                return Err(Error::InvalidArgument);
+            }
+
+            let n = usize::try_from(h.bit_len).map_err(|_| Error::InvalidArgument)?;
+
+            BigFloatNumber::from_raw_parts(m, n, s, h.exponent as Exponent, h.inexact != 0)
+                .map(|v| v.into())
+        }
+        KIND_INF if h.err == 0 && m.is_empty() => {
+            Ok(if s.is_positive() { crate::INF_POS } else { crate::INF_NEG })
+        }
+        KIND_NAN if m.is_empty() => {
+            let err = match h.err {
+                0 => None,
+                1 => Some(Error::ExponentOverflow(Sign::Pos)),
+                2 => Some(Error::ExponentOverflow(Sign::Neg)),
+                3 => Some(Error::DivisionByZero),
+                4 => Some(Error::InvalidArgument),
+                5 => Some(Error::MemoryAllocation),
+                _ => return Err(Error::InvalidArgument),
+            };
+            Ok(BigFloat::nan(err))
+        }
+        _ => Err(Error::InvalidArgument),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{RoundingMode, EXPONENT_MIN, INF_NEG, INF_POS, NAN};
+
+    #[cfg(feature = "exp64")]
+    use crate::defs::WORD_SIGNIFICANT_BIT;
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(core::mem::size_of::<RawHeader>(), 32);
+        assert_eq!(core::mem::align_of::<RawHeader>(), 8);
+
+        let rm = RoundingMode::ToEven;
+        let three = BigFloat::from_word(3, 64);
+        let subn =
+            BigFloat::from_raw_parts(&[3, 1], WORD_BIT_SIZE + 1, Sign::Neg, EXPONENT_MIN, true);
+
+        // the smallest exponent which fits in the header
+        #[cfg(not(feature = "exp64"))]
+        let tiny = subn.clone();
+        #[cfg(feature = "exp64")]
+        let tiny = BigFloat::from_raw_parts(
+            &[3, WORD_SIGNIFICANT_BIT],
+            2 * WORD_BIT_SIZE,
+            Sign::Neg,
+            i32::MIN as Exponent,
+            true,
+        );
+
+        for x in [
+            BigFloat::from_f64(-1.5, 128),
+            BigFloat::from_word(1, 64).div(&three, 320, rm),
+            BigFloat::new(256),
+            tiny,
+        ] {
+            let (h, m) = to_parts(&x);
+            let y = from_parts(&h, m).unwrap();
+            assert_eq!(y.as_raw_parts(), x.as_raw_parts());
+            assert_eq!(y.inexact(), x.inexact());
+        }
+
+        for x in [
+            INF_POS,
+            INF_NEG,
+            NAN,
+            BigFloat::nan(Some(Error::ExponentOverflow(Sign::Neg))),
+            BigFloat::nan(Some(Error::DivisionByZero)),
+        ] {
+            let (h, m) = to_parts(&x);
+            assert!(m.is_empty());
+            let y = from_parts(&h, m).unwrap();
+            assert_eq!(y.is_inf_pos(), x.is_inf_pos());
+            assert_eq!(y.is_inf_neg(), x.is_inf_neg());
+            assert_eq!(y.is_nan(), x.is_nan());
+            assert_eq!(y.err(), x.err());
+        }
+
+        // write to a preallocated buffer
+        let x = BigFloat::from_word(1, 64).div(&three, 192, rm);
+        let mut h = RawHeader::default();
+        let mut m = [0; 8];
+
+        // the exponent does not fit in the header
+        #[cfg(feature = "exp64")]
+        {
+            let (h, w) = to_parts(&subn);
+            assert!(w.is_empty());
+            assert_eq!(
+                from_parts(&h, w).unwrap().err(),
+                Some(Error::InvalidArgument)
+            );
+            assert_eq!(
+                write(&subn, &mut RawHeader::default(), &mut m).unwrap_err(),
+                Error::InvalidArgument
+            );
+        }
+        #[cfg(not(feature = "exp64"))]
+        assert!(write(&subn, &mut h, &mut m).is_ok());
+
+        let n = write(&x, &mut h, &mut m).unwrap();
+        assert_eq!(n, 192 / WORD_BIT_SIZE);
+        assert_eq!(from_parts(&h, &m[..n]).unwrap(), x);
+        assert_eq!(
+            write(&x, &mut h, &mut m[..1]).unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // validation
+        let (h, m) = to_parts(&x);
+        let invalid = [
+            RawHeader { magic: 0, ..h },
+            RawHeader { version: 2, ..h },
+            RawHeader { word_bits: 16, ..h },
+            RawHeader { kind: 3, ..h },
+            RawHeader { sign: 0, ..h },
+            RawHeader { inexact: 2, ..h },
+            RawHeader { err: 1, ..h },
+            RawHeader { reserved: 1, ..h },
+            RawHeader { word_len: 1, ..h },
+            RawHeader { bit_len: 1, ..h },
+            RawHeader {
+                kind: KIND_INF,
+                ..h
+            },
+        ];
+        for h in invalid {
+            assert_eq!(from_parts(&h, m).unwrap_err(), Error::InvalidArgument);
+        }
+
+        let (h, _) = to_parts(&NAN);
+        assert_eq!(
+            from_parts(&RawHeader { err: 6, ..h }, &[]).unwrap_err(),
+            Error::InvalidArgument
+        );
+    }
+}
@@ -8,29 +8,70 @@
 #![allow(clippy::comparison_chain)]
 #![allow(clippy::collapsible_else_if)]
 #![allow(clippy::collapsible_if)]
+#![cfg_attr(feature = "exp64", allow(clippy::unnecessary_cast))] // Exponent can be i64
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+#[cfg(all(feature = "exp64", target_pointer_width = "32"))]
+compile_error!("The feature `exp64` requires a 64-bit target.");
+
+pub mod abserr;
+pub mod ball;
 mod common;
+pub mod complex;
+pub mod cond;
 mod conv;
 pub mod ctx;
+pub mod decimal;
 mod defs;
+pub mod encoding;
 mod ext;
+pub mod geodesy;
+mod ieee;
+pub mod invert;
+pub mod jd;
+pub mod layout;
+pub mod linalg;
 mod mantissa;
+pub mod measure;
 mod num;
 mod ops;
 mod parser;
+pub mod polynomial;
+pub mod quadrature;
+pub mod range;
+pub mod sexagesimal;
 mod strop;
+pub mod tuning;
 
 #[cfg(feature = "std")]
 mod for_3rd;
 
+#[cfg(feature = "std")]
+pub mod pool;
+
+#[cfg(feature = "bench")]
+pub mod bench;
+
+#[cfg(feature = "hardround")]
+pub mod hardround;
+
+#[cfg(feature = "tablegen")]
+pub mod tablegen;
+
+#[cfg(feature = "debug")]
+pub mod tag;
+
 #[doc(hidden)]
 pub mod macro_util;
 
+pub use crate::defs::AngleRange;
 pub use crate::defs::Error;
 pub use crate::defs::Exponent;
+pub use crate::defs::FormatOptions;
+pub use crate::defs::ParseError;
+pub use crate::defs::ParseErrorKind;
 pub use crate::defs::Radix;
 pub use crate::defs::RoundingMode;
 pub use crate::defs::Sign;
@@ -40,7 +81,17 @@ pub use crate::ext::FromExt;
 pub use crate::ext::INF_NEG;
 pub use crate::ext::INF_POS;
 pub use crate::ext::NAN;
+pub use crate::num::BigFloatNumber;
 pub use crate::ops::consts::Consts;
+pub use crate::ops::solve;
+#[cfg(feature = "std")]
+pub use crate::ops::consts::SharedConsts;
+
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use crate::for_3rd::serialization;
+
+#[cfg(all(feature = "std", feature = "rkyv"))]
+pub use crate::for_3rd::rkyv::ArchivedBigFloat;
 
 pub use crate::defs::EXPONENT_BIT_SIZE;
 pub use crate::defs::EXPONENT_MAX;
@@ -0,0 +1,455 @@
+//! Linear algebra.
+//!
+//! Vectors are represented by slices of `BigFloat`, and matrices by `Matrix` which stores its elements in row-major order.
+//! The functions accept a precision `p` and a rounding mode `rm` which are applied to each element of the result.
+//! Norms, dot products, and the elements of matrix-vector products are computed from exact products
+//! and rounded once, so they are correctly rounded.
+//!
+//! Linear systems are solved using LU decomposition with partial pivoting.
+//! `Matrix::solve_refined` improves the solution with iterative refinement,
+//! where the residual is computed with twice the precision.
+//!
+//! ```
+//! use astro_float_num::linalg::Matrix;
+//! use astro_float_num::{BigFloat, RoundingMode};
+//!
+//! let p = 128;
+//! let rm = RoundingMode::ToEven;
+//! let n = |v: i8| BigFloat::from_i8(v, p);
+//!
+//! // 2x + y = 3, x + 3y = 5
+//! let a = Matrix::new(2, 2, vec![n(2), n(1), n(1), n(3)]).unwrap();
+//! let b = [n(3), n(5)];
+//!
+//! let x = a.solve_refined(&b, p, rm, 10).unwrap();
+//! assert_eq!(x[0], n(4).div(&n(5), p, rm));
+//! assert_eq!(x[1], n(7).div(&n(5), p, rm));
+//! ```
+
+use crate::common::util::round_p;
+use crate::{BigFloat, Error, RoundingMode};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Returns the sum of the absolute values of the elements of `v` with precision `p` rounded using the rounding mode `rm`.
+/// Precision is rounded upwards to the word size.
+pub fn norm1(v: &[BigFloat], p: usize, rm: RoundingMode) -> BigFloat {
+    let abs: Vec<BigFloat> = v.iter().map(|x| x.abs()).collect();
+    BigFloat::sum(&abs, p, rm)
+}
+
+/// Returns the Euclidean norm of `v` with precision `p` rounded using the rounding mode `rm`.
+/// Precision is rounded upwards to the word size.
+pub fn norm2(v: &[BigFloat], p: usize, rm: RoundingMode) -> BigFloat {
+    let sq: Vec<BigFloat> = v.iter().map(|x| x.mul_full_prec(x)).collect();
+    let p_wrk = round_p(p).saturating_add(crate::WORD_BIT_SIZE);
+    BigFloat::sum(&sq, p_wrk, RoundingMode::None).sqrt(p, rm)
+}
+
+/// Returns the maximum of the absolute values of the elements of `v`, or zero if `v` is empty.
+pub fn norm_inf(v: &[BigFloat]) -> BigFloat {
+    v.iter().fold(BigFloat::new(1), |m, x| {
+        if x.is_nan() || x.abs() > m {
+            x.abs()
+        } else {
+            m
+        }
+    })
+}
+
+/// Returns the dot product of `v1` and `v2` with precision `p` rounded using the rounding mode `rm`.
+/// Precision is rounded upwards to the word size.
+///
+/// ## Errors
+///
+///  - InvalidArgument: the lengths of `v1` and `v2` differ.
+pub fn dot(
+    v1: &[BigFloat],
+    v2: &[BigFloat],
+    p: usize,
+    rm: RoundingMode,
+) -> Result<BigFloat, Error> {
+    if v1.len() != v2.len() {
+        return Err(Error::InvalidArgument);
+    }
+
+    let prods: Vec<BigFloat> = v1.iter().zip(v2).map(|(x, y)| x.mul_full_prec(y)).collect();
+
+    Ok(BigFloat::sum(&prods, p, rm))
+}
+
+/// Matrix with `BigFloat` elements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<BigFloat>,
+}
+
+/// LU decomposition of a square matrix with partial pivoting.
+#[derive(Debug, Clone)]
+pub struct Lu {
+    lu: Matrix,
+    perm: Vec<usize>,
+}
+
+impl Matrix {
+    /// Constructs a matrix with `rows` rows and `cols` columns from the elements `data` given in row-major order.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: the length of `data` is not equal to `rows * cols`.
+    pub fn new(rows: usize, cols: usize, data: Vec<BigFloat>) -> Result<Self, Error> {
+        if rows.checked_mul(cols) != Some(data.len()) {
+            return Err(Error::InvalidArgument);
+        }
+
+        Ok(Matrix { rows, cols, data })
+    }
+
+    /// Constructs the identity matrix of size `n` with the elements of precision `p`.
+    pub fn identity(n: usize, p: usize) -> Self {
+        let data = (0..n * n)
+            .map(|k| if k % (n + 1) == 0 { BigFloat::from_word(1, p) } else { BigFloat::new(p) })
+            .collect();
+
+        Matrix {
+            rows: n,
+            cols: n,
+            data,
+        }
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the element in the row `i` and the column `j`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `i` or `j` is out of bounds.
+    pub fn get(&self, i: usize, j: usize) -> &BigFloat {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &self.data[i * self.cols + j]
+    }
+
+    /// Returns the row `i` of the matrix.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn row(&self, i: usize) -> &[BigFloat] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// Multiplies the matrix by the vector `v` and returns the result with precision `p` rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: the length of `v` is not equal to the number of columns.
+    pub fn mul_vec(
+        &self,
+        v: &[BigFloat],
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Vec<BigFloat>, Error> {
+        if v.len() != self.cols {
+            return Err(Error::InvalidArgument);
+        }
+
+        (0..self.rows).map(|i| dot(self.row(i), v, p, rm)).collect()
+    }
+
+    /// Computes the LU decomposition of the matrix with partial pivoting using precision `p`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: the matrix is not square.
+    ///  - DivisionByZero: the matrix is singular.
+    pub fn lu(&self, p: usize, rm: RoundingMode) -> Result<Lu, Error> {
+        if self.rows != self.cols {
+            return Err(Error::InvalidArgument);
+        }
+
+        let n = self.rows;
+        let mut lu = self.clone();
+        let mut perm: Vec<usize> = (0..n).collect();
+
+        for k in 0..n {
+            // pivot with the largest absolute value
+            let mut piv = k;
+            for i in k + 1..n {
+                if lu.data[i * n + k].abs() > lu.data[piv * n + k].abs() {
+                    piv = i;
+                }
+            }
+
+            if lu.data[piv * n + k].is_zero() {
+                return Err(Error::DivisionByZero);
+            }
+
+            if piv != k {
+                for j in 0..n {
+                    lu.data.swap(k * n + j, piv * n + j);
+                }
+                perm.swap(k, piv);
+            }
+
+            for i in k + 1..n {
+                let l = lu.data[i * n + k].div(&lu.data[k * n + k], p, rm);
+                for j in k + 1..n {
+                    let t = l.mul(&lu.data[k * n + j], p, rm);
+                    lu.data[i * n + j] = lu.data[i * n + j].sub(&t, p, rm);
+                }
+                lu.data[i * n + k] = l;
+            }
+        }
+
+        if let Some(e) = lu.data.iter().find_map(|x| x.err()) {
+            return Err(e);
+        }
+
+        Ok(Lu { lu, perm })
+    }
+
+    /// Solves the system of linear equations `self * x = b` with precision `p` using LU decomposition.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: the matrix is not square, or the length of `b` is not equal to the size of the matrix.
+    ///  - DivisionByZero: the matrix is singular.
+    pub fn solve(
+        &self,
+        b: &[BigFloat],
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Vec<BigFloat>, Error> {
+        self.lu(p, rm)?.solve(b, p, rm)
+    }
+
+    /// Solves the system of linear equations `self * x = b` using LU decomposition followed by iterative refinement.
+    /// The decomposition is computed with precision `p`, and the residual is computed with twice the precision `p`.
+    /// The refinement stops when the correction becomes smaller than the unit in the last place of the solution,
+    /// or after `max_iter` iterations. Each element of the solution is rounded to precision `p` using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: the matrix is not square, or the length of `b` is not equal to the size of the matrix.
+    ///  - DivisionByZero: the matrix is singular.
+    pub fn solve_refined(
+        &self,
+        b: &[BigFloat],
+        p: usize,
+        rm: RoundingMode,
+        max_iter: usize,
+    ) -> Result<Vec<BigFloat>, Error> {
+        let p = round_p(p);
+        let p_wrk = p + crate::WORD_BIT_SIZE;
+        let p_res = p_wrk * 2;
+
+        let lu = self.lu(p_wrk, RoundingMode::ToEven)?;
+        let mut x = lu.solve(b, p_wrk, RoundingMode::ToEven)?;
+
+        for _ in 0..max_iter {
+            // r = b - A * x
+            let ax = self.mul_vec(&x, p_res, RoundingMode::ToEven)?;
+            let r: Vec<BigFloat> = b
+                .iter()
+                .zip(ax.iter())
+                .map(|(b, ax)| b.sub(ax, p_res, RoundingMode::ToEven))
+                .collect();
+
+            let d = lu.solve(&r, p_wrk, RoundingMode::ToEven)?;
+
+            let mut small = true;
+            for (x, d) in x.iter_mut().zip(d.iter()) {
+                let xn = x.add(d, p_res, RoundingMode::ToEven);
+                if !d.is_zero() && d.abs() >= xn.ulp(p) {
+                    small = false;
+                }
+                *x = xn;
+            }
+
+            if small {
+                break;
+            }
+        }
+
+        for x in x.iter_mut() {
+            x.set_precision(p, rm)?;
+        }
+
+        Ok(x)
+    }
+}
+
+impl Lu {
+    /// Solves the system of linear equations `A * x = b` with precision `p`, where `A` is the decomposed matrix.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: the length of `b` is not equal to the size of the matrix.
+    pub fn solve(
+        &self,
+        b: &[BigFloat],
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Vec<BigFloat>, Error> {
+        let n = self.lu.rows;
+        if b.len() != n {
+            return Err(Error::InvalidArgument);
+        }
+
+        let a = &self.lu.data;
+
+        // forward substitution with the unit lower triangular matrix
+        let mut y: Vec<BigFloat> = self.perm.iter().map(|&i| b[i].clone()).collect();
+        for i in 0..n {
+            for j in 0..i {
+                let t = a[i * n + j].mul(&y[j], p, rm);
+                y[i] = y[i].sub(&t, p, rm);
+            }
+        }
+
+        // back substitution with the upper triangular matrix
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                let t = a[i * n + j].mul(&y[j], p, rm);
+                y[i] = y[i].sub(&t, p, rm);
+            }
+            y[i] = y[i].div(&a[i * n + i], p, rm);
+        }
+
+        Ok(y)
+    }
+
+    /// Returns the permutation of rows: the row `i` of the decomposed matrix is the row `perm()[i]` of the original matrix.
+    pub fn perm(&self) -> &[usize] {
+        &self.perm
+    }
+
+    /// Returns the combined factors: the elements below the diagonal belong to the unit lower triangular factor,
+    /// and the other elements belong to the upper triangular factor.
+    pub fn factors(&self) -> &Matrix {
+        &self.lu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_linalg() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+        let n = |v: i64| BigFloat::from_i64(v, p);
+
+        // norms and the dot product
+        let v = [n(3), n(-4), n(0)];
+        assert_eq!(norm1(&v, p, rm), n(7));
+        assert_eq!(norm2(&v, p, rm), n(5));
+        assert_eq!(norm_inf(&v), n(4));
+        assert!(norm_inf(&[]).is_zero());
+        assert_eq!(dot(&v, &[n(1), n(2), n(3)], p, rm).unwrap(), n(-5));
+        assert_eq!(dot(&v, &[n(1)], p, rm).unwrap_err(), Error::InvalidArgument);
+        assert_eq!(
+            norm2(&[n(1), n(1)], p, rm),
+            BigFloat::from_word(2, p).sqrt(p, rm)
+        );
+
+        // the dot product is rounded once
+        let mut e = BigFloat::from_word(1, p);
+        e.set_exponent(-200);
+        let d = dot(&[n(1), e.clone(), n(-1)], &[n(1), n(1), n(1)], p, rm).unwrap();
+        assert_eq!(d, e);
+
+        // matrix construction and matrix-vector product
+        assert_eq!(
+            Matrix::new(2, 2, vec![n(1)]).unwrap_err(),
+            Error::InvalidArgument
+        );
+        let a = Matrix::new(2, 3, vec![n(1), n(2), n(3), n(4), n(5), n(6)]).unwrap();
+        assert_eq!((a.rows(), a.cols()), (2, 3));
+        assert_eq!(a.get(1, 2), &n(6));
+        assert_eq!(
+            a.mul_vec(&[n(1), n(0), n(-1)], p, rm).unwrap(),
+            [n(-2), n(-2)]
+        );
+        assert_eq!(
+            a.mul_vec(&[n(1)], p, rm).unwrap_err(),
+            Error::InvalidArgument
+        );
+        assert_eq!(a.lu(p, rm).unwrap_err(), Error::InvalidArgument);
+        let id = Matrix::identity(3, p);
+        assert_eq!(id.mul_vec(&v, p, rm).unwrap(), v);
+
+        // LU decomposition requires pivoting
+        let a = Matrix::new(
+            3,
+            3,
+            vec![n(0), n(2), n(1), n(1), n(1), n(1), n(2), n(1), n(0)],
+        )
+        .unwrap();
+        let lu = a.lu(p, rm).unwrap();
+        assert_eq!(lu.perm()[0], 2);
+        let b = [n(5), n(4), n(4)];
+        let x = a.solve(&b, p, rm).unwrap();
+        assert_eq!(x, [n(1), n(2), n(1)]);
+        assert_eq!(
+            lu.solve(&b[..2], p, rm).unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // singular matrix
+        let s = Matrix::new(2, 2, vec![n(1), n(2), n(2), n(4)]).unwrap();
+        assert_eq!(
+            s.solve(&[n(1), n(2)], p, rm).unwrap_err(),
+            Error::DivisionByZero
+        );
+
+        // ill-conditioned Hilbert matrix: refinement gives a more accurate solution
+        let sz = 8;
+        let data = (0..sz * sz)
+            .map(|k| BigFloat::from_word(1, p).div(&n((k / sz + k % sz + 1) as i64), p, rm))
+            .collect();
+        let h = Matrix::new(sz, sz, data).unwrap();
+        let x_exact: Vec<BigFloat> = (0..sz).map(|i| n(i as i64 + 1)).collect();
+        let p_exact = 1024;
+        let hb: Vec<BigFloat> = (0..sz)
+            .map(|i| dot(h.row(i), &x_exact, p_exact, rm).unwrap())
+            .collect();
+
+        let err = |x: &[BigFloat]| {
+            let diff: Vec<BigFloat> = x
+                .iter()
+                .zip(x_exact.iter())
+                .map(|(a, b)| a.sub(b, p, rm))
+                .collect();
+            norm_inf(&diff)
+        };
+
+        let x1 = h.solve(&hb, p, rm).unwrap();
+        let x2 = h.solve_refined(&hb, p, rm, 10).unwrap();
+        let e1 = err(&x1);
+        let e2 = err(&x2);
+        assert!(e2 < e1);
+        let mut tol = BigFloat::from_word(1, p);
+        tol.set_exponent(-115);
+        assert!(e2 < tol);
+    }
+}
@@ -1,11 +1,18 @@
 //! Functons used by macros
 
 use crate::{
-    common::util::{count_leading_ones, count_leading_zeroes_skip_first},
+    common::util::{count_leading_ones, count_leading_zeroes_skip_first, log2_ceil, round_p},
+    ctx::{CallCache, Func, RangeMode, Status},
     defs::DEFAULT_P,
-    BigFloat, Consts, Exponent, RoundingMode, Sign, EXPONENT_BIT_SIZE, INF_NEG, INF_POS,
+    BigFloat, Consts, Exponent, RoundingMode, Sign, EXPONENT_BIT_SIZE, INF_NEG, INF_POS, NAN,
 };
 
+#[cfg(target_arch = "x86")]
+use crate::{Error, Word};
+
+#[cfg(all(not(feature = "std"), target_arch = "x86"))]
+use alloc::vec::Vec;
+
 /// Computes error for BigFloat values near 1. This function is for internal use by macro `expr`.
 pub fn compute_added_err_near_one(arg: &BigFloat, emin: Exponent) -> usize {
     if arg.is_zero() {
@@ -32,6 +39,14 @@ pub enum TrigFun {
     Tan,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum BesselFun {
+    J,
+    Y,
+    I,
+    K,
+}
+
 /// Algorithm of error computation.
 #[derive(Debug)]
 pub enum ErrAlgo<'a> {
@@ -43,6 +58,16 @@ pub enum ErrAlgo<'a> {
     Acos(&'a BigFloat, Exponent),
     Acosh(&'a BigFloat, Exponent),
     Atanh(&'a BigFloat, Exponent),
+    Asech(&'a BigFloat, Exponent),
+    Acoth(&'a BigFloat, Exponent),
+    Gamma(&'a BigFloat, Exponent),
+    Bessel(
+        &'a BigFloat,
+        &'a BigFloat,
+        BesselFun,
+        &'a mut Consts,
+        Exponent,
+    ),
 }
 
 /// Computes the precision increment of an arguments to cover the error for a given algorithm.
@@ -182,7 +207,7 @@ pub fn compute_added_err(algo: ErrAlgo<'_>) -> usize {
         ErrAlgo::Asin(arg, emin) => {
             if arg.inexact() && arg.exponent().unwrap_or(1) < 1 {
                 let n = compute_added_err_near_one(arg, emin);
-                2 + (n + 1) / 2
+                2 + n.div_ceil(2)
             } else {
                 0
             }
@@ -209,6 +234,115 @@ pub fn compute_added_err(algo: ErrAlgo<'_>) -> usize {
                 0
             }
         }
+        ErrAlgo::Asech(arg, emin) => {
+            if arg.inexact() && arg.is_positive() && arg.exponent().unwrap_or(1) < 1 {
+                2 + compute_added_err_near_one(arg, emin)
+            } else {
+                0
+            }
+        }
+        ErrAlgo::Acoth(arg, emin) => {
+            if arg.inexact() && arg.exponent().unwrap_or(0) >= 1 {
+                2 + compute_added_err_near_one(arg, emin)
+            } else {
+                0
+            }
+        }
+        ErrAlgo::Gamma(arg, emin) => {
+            if arg.inexact() {
+                if let Some(e) = arg.exponent() {
+                    let err = if e > 0 { e as usize + log2_ceil(e as usize + 1) } else { 0 };
+
+                    // proximity to a pole
+                    let pole = if arg.is_negative() {
+                        let argrem = arg.sub(
+                            &arg.round(0, RoundingMode::ToEven),
+                            DEFAULT_P,
+                            RoundingMode::None,
+                        );
+                        argrem.exponent().map_or(0, |er| {
+                            if argrem.is_zero() || er < emin {
+                                0
+                            } else {
+                                (e as isize - er as isize).max(0) as usize
+                            }
+                        })
+                    } else {
+                        0
+                    };
+
+                    3 + err + pole
+                } else {
+                    0
+                }
+            } else {
+                0
+            }
+        }
+        ErrAlgo::Bessel(arg, n, f, cc, emin) => {
+            if arg.inexact() || n.inexact() {
+                if let (Some(e1), Some(e2)) = (arg.exponent(), n.exponent()) {
+                    let e = e1.max(e2).max(0) as usize;
+                    let err = e + log2_ceil(e.max(e1.max(emin).unsigned_abs() as usize) + 1);
+
+                    let fun = match f {
+                        BesselFun::J => BigFloat::besselj,
+                        BesselFun::Y => BigFloat::bessely,
+                        BesselFun::I => BigFloat::besseli,
+                        BesselFun::K => BigFloat::besselk,
+                    };
+
+                    // proximity to a zero: the function of order n is small compared to the function of order n+1
+                    let mut x = arg.clone();
+                    let mut n1 = n.clone();
+                    let (d1, d2) = if x.set_precision(DEFAULT_P, RoundingMode::ToEven).is_ok()
+                        && n1.set_precision(DEFAULT_P, RoundingMode::ToEven).is_ok()
+                    {
+                        let n2 = n1.add(&BigFloat::from_word(1, 1), DEFAULT_P, RoundingMode::None);
+                        (
+                            fun(&x, &n1, DEFAULT_P, RoundingMode::None, cc),
+                            fun(&x, &n2, DEFAULT_P, RoundingMode::None, cc),
+                        )
+                    } else {
+                        (NAN, NAN)
+                    };
+
+                    let zero = match (d1.exponent(), d2.exponent()) {
+                        (Some(e1), Some(e2)) if !d1.is_zero() && !d2.is_zero() && e1 >= emin => {
+                            (e2 as isize - e1 as isize).max(0) as usize
+                        }
+                        _ => 0,
+                    };
+
+                    // sensitivity to the order: relative change of the function when the order is changed by 2^(-32) relatively
+                    let order = match (d1.exponent(), n1.exponent()) {
+                        (Some(e1), Some(en))
+                            if n.inexact() && !d1.is_zero() && !n1.is_zero() && e1 >= emin =>
+                        {
+                            let mut h = n1.clone();
+                            h.set_exponent(en - 32);
+                            let n3 = n1.add(&h, DEFAULT_P, RoundingMode::None);
+                            let d3 = fun(&x, &n3, DEFAULT_P, RoundingMode::None, cc);
+                            let diff = d3.sub(&d1, DEFAULT_P, RoundingMode::None);
+
+                            match diff.exponent() {
+                                Some(ed) if !diff.is_zero() => {
+                                    (ed as isize - e1 as isize + 32).max(0) as usize
+                                }
+                                _ => 0,
+                            }
+                        }
+                        _ => 0,
+                    };
+
+                    3 + err + zero.max(order)
+                } else {
+                    0
+                }
+            } else {
+                0
+            }
+        }
     }
 }
 
@@ -219,14 +353,26 @@ pub fn compute_added_err(algo: ErrAlgo<'_>) -> usize {
 /// Return `n` itself otherwise.
 #[inline]
 pub fn check_exponent_range(n: BigFloat, emin: Exponent, emax: Exponent) -> BigFloat {
+    let mut flags = Status::default();
+    check_exponent_range_sticky(n, emin, emax, &mut flags)
+}
+
+/// Checks if the number's exponent is in the given exponent range in the same way as `check_exponent_range` does.
+/// Raises the overflow flag in `flags` if `n` was replaced with Inf, and the underflow flag if `n` was replaced with 0.
+#[inline]
+pub fn check_exponent_range_sticky(
+    n: BigFloat,
+    emin: Exponent,
+    emax: Exponent,
+    flags: &mut Status,
+) -> BigFloat {
     if let Some(e) = n.exponent() {
         if e > emax {
-            if n.is_positive() {
-                INF_POS
-            } else {
-                INF_NEG
-            }
+            flags.raise_overflow();
+            let inf = if n.is_positive() { INF_POS } else { INF_NEG };
+            inf.tagged("clamp", &[&n])
         } else if e < emin {
+            flags.raise_underflow();
             BigFloat::new(n.mantissa_max_bit_len().unwrap_or(DEFAULT_P))
         } else {
             n
@@ -236,6 +382,142 @@ pub fn check_exponent_range(n: BigFloat, emin: Exponent, emax: Exponent) -> BigF
     }
 }
 
+/// Rounds `n` to precision `p` using rounding mode `rm`, and brings the result into the exponent range from `emin` to `emax` according to `mode`.
+/// With `RangeMode::Clamp`, the rounded result is checked in the same way as `check_exponent_range_sticky` does.
+/// With `RangeMode::Ieee` and `RangeMode::IeeeSubnormal`, overflow and underflow are handled like in IEEE 754,
+/// and the overflow, the underflow, and the inexact flags are raised in `flags` accordingly.
+/// This function is for internal use by macro `expr`.
+pub fn round_to_exponent_range(
+    n: BigFloat,
+    p: usize,
+    rm: RoundingMode,
+    emin: Exponent,
+    emax: Exponent,
+    mode: RangeMode,
+    flags: &mut Status,
+) -> BigFloat {
+    // subnormal numbers are rounded from the unrounded value
+    let unrounded = if mode == RangeMode::IeeeSubnormal { Some(n.clone()) } else { None };
+
+    let mut ret = n;
+    if let Err(err) = ret.set_precision(p, rm) {
+        ret = BigFloat::nan(Some(err));
+    }
+
+    if mode == RangeMode::Clamp || ret.is_zero() {
+        return check_exponent_range_sticky(ret, emin, emax, flags);
+    }
+
+    let e = match ret.exponent() {
+        Some(e) => e,
+        None => return ret,
+    };
+
+    if e > emax {
+        flags.raise_overflow();
+        flags.raise_inexact();
+
+        let is_positive = ret.is_positive();
+        let to_inf = match rm {
+            RoundingMode::ToEven | RoundingMode::FromZero => true,
+            RoundingMode::Up => is_positive,
+            RoundingMode::Down => !is_positive,
+            RoundingMode::ToZero | RoundingMode::ToOdd | RoundingMode::None => false,
+        };
+
+        if to_inf {
+            let inf = if is_positive { INF_POS } else { INF_NEG };
+            inf.tagged("clamp", &[&ret])
+        } else {
+            // the largest finite number
+            let mut max = BigFloat::max_value(p);
+            max.set_exponent(emax);
+            if !is_positive {
+                max.set_sign(Sign::Neg);
+            }
+            max.set_inexact(true);
+            max
+        }
+    } else if e < emin {
+        match unrounded.as_ref().and_then(|n| n.as_number()) {
+            Some(v) => {
+                // the subnormal numbers are multiples of 2^(emin - p)
+                let n = (round_p(p) as isize - emin as isize) as usize;
+
+                let mut ret = match v.round(n, rm) {
+                    Ok(x) => BigFloat::from(x),
+                    Err(err) => BigFloat::nan(Some(err)),
+                };
+
+                if let Err(err) = ret.set_precision(p, rm) {
+                    ret = BigFloat::nan(Some(err));
+                }
+
+                if ret.inexact() {
+                    flags.raise_subnormal_underflow();
+                }
+
+                ret
+            }
+            None => {
+                flags.raise_underflow();
+                flags.raise_inexact();
+
+                let mut zero = BigFloat::new(p);
+                if ret.is_negative() {
+                    zero.set_sign(Sign::Neg);
+                }
+                zero.set_inexact(true);
+                zero
+            }
+        }
+    } else {
+        ret
+    }
+}
+
+/// Computes the function `func` of the arguments `args` with precision `p` rounded using the rounding mode `rm`.
+/// If `cache` is given, the result is taken from the cache or memoized in it.
+/// This function is for internal use by macro `expr`.
+#[inline]
+pub fn call(
+    cache: Option<&mut CallCache>,
+    func: Func,
+    args: &[&BigFloat],
+    p: usize,
+    rm: RoundingMode,
+    cc: &mut Consts,
+) -> BigFloat {
+    match cache {
+        Some(c) => c.eval(func, args, p, rm, cc),
+        None => func.eval(args, p, rm, cc),
+    }
+}
+
+/// Constructs a number from the mantissa `m` given as 64-bit words in the same way as `BigFloat::from_raw_parts` does.
+/// This function is for internal use by macro `bigfloat`.
+pub fn from_u64_words(m: &[u64], n: usize, s: Sign, e: Exponent, inexact: bool) -> BigFloat {
+    #[cfg(not(target_arch = "x86"))]
+    {
+        BigFloat::from_raw_parts(m, n, s, e, inexact)
+    }
+
+    #[cfg(target_arch = "x86")]
+    {
+        let mut words = Vec::new();
+        if words.try_reserve_exact(m.len() * 2).is_err() {
+            return BigFloat::nan(Some(Error::MemoryAllocation));
+        }
+
+        for w in m {
+            words.push(*w as Word);
+            words.push((*w >> 32) as Word);
+        }
+
+        BigFloat::from_raw_parts(&words, n, s, e, inexact)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -651,6 +933,16 @@ mod tests {
 
                         //println!("atan {:?}", err);
                         assert!(err <= 2);
+
+                        // gamma
+                        let d1 = BigFloat::gamma(&n1, p, RoundingMode::None, &mut cc);
+                        let d2 = BigFloat::gamma(&n2, p, RoundingMode::None, &mut cc);
+
+                        let err_estimate = compute_added_err(ErrAlgo::Gamma(&n1, emin));
+                        let err = calc_err(d1, d2, p);
+
+                        //println!("gamma {:?} {:?}", err, err_estimate);
+                        assert!(err <= err_estimate);
                     }
                 }
             }
@@ -746,5 +1038,53 @@ mod tests {
                 }
             }
         }
+
+        // bessel functions
+        type BesselFn = fn(&BigFloat, &BigFloat, usize, RoundingMode, &mut Consts) -> BigFloat;
+
+        for (f, fun) in [
+            (BesselFun::J, BigFloat::besselj as BesselFn),
+            (BesselFun::Y, BigFloat::bessely),
+            (BesselFun::I, BigFloat::besseli),
+            (BesselFun::K, BigFloat::besselk),
+        ] {
+            for _ in 0..10 {
+                for e in [-10, 0, 1, 3, 6, 10] {
+                    for n in [0.0, 1.0, 2.5, -3.0, -0.75] {
+                        let p = (rand::random::<usize>() % 10 + 1) * WORD_BIT_SIZE;
+
+                        let (mut x1, mut x2) = gen_num_pair(p, e, 0);
+                        x1.set_sign(Sign::Pos);
+                        x2.set_sign(Sign::Pos);
+                        let n = BigFloat::from_f64(n, p);
+
+                        let d1 = fun(&x1, &n, p, RoundingMode::None, &mut cc);
+                        let d2 = fun(&x2, &n, p, RoundingMode::None, &mut cc);
+
+                        let err_estimate =
+                            compute_added_err(ErrAlgo::Bessel(&x1, &n, f, &mut cc, emin));
+                        let err = calc_err(d1, d2, p);
+
+                        //println!("{:?} x {:?} {:?}", f, err, err_estimate);
+                        assert!(err <= err_estimate);
+
+                        // order
+                        let (n1, n2) = gen_num_pair(p, e.min(3), 0);
+                        let mut x = x1.clone();
+                        x.set_inexact(false);
+
+                        let d1 = fun(&x, &n1, p, RoundingMode::None, &mut cc);
+                        let d2 = fun(&x, &n2, p, RoundingMode::None, &mut cc);
+
+                        let err_estimate =
+                            compute_added_err(ErrAlgo::Bessel(&x, &n1, f, &mut cc, emin));
+                        let err = calc_err(d1, d2, p);
+
+                        //println!("{:?} n {:?} {:?}", f, err, err_estimate);
+                        assert!(err <= err_estimate);
+                    }
+                }
+            }
+        }
     }
 }
@@ -134,7 +134,7 @@ impl Mantissa {
         debug_assert!(input[0] != 0);
 
         let mut chunks = Vec::new();
-        chunks.try_reserve_exact((input.len() + WORD_TENPOWER_LEN - 1) / WORD_TENPOWER_LEN)?;
+        chunks.try_reserve_exact(input.len().div_ceil(WORD_TENPOWER_LEN))?;
 
         let mut word: Word = 0;
         let mut i = 0;
@@ -173,7 +173,7 @@ impl Mantissa {
             let (_, tenpower, _) = &tenpowers[p];
 
             let mut newchunks = Vec::new();
-            newchunks.try_reserve_exact((chunks.len() + 1) / 2)?;
+            newchunks.try_reserve_exact(chunks.len().div_ceil(2))?;
 
             for pair in chunks.chunks(2) {
                 if pair.len() == 2 {
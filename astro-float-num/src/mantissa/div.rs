@@ -379,7 +379,7 @@ impl Mantissa {
             let (q1, _r1) = Self::div_basic(m1, m2)?;
             Ok(q1)
         } else {
-            let m2l = (m2.len() + 1) / 2;
+            let m2l = m2.len().div_ceil(2);
             let k = m2.len() - m2l;
 
             let a1 = SliceWithSign::new(&m1[2 * k..], 1); // m1 div 2^(2*k)
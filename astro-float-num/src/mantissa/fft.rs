@@ -12,6 +12,11 @@ use crate::defs::Word;
 use crate::defs::WORD_BIT_SIZE;
 use crate::defs::WORD_MAX;
 use crate::mantissa::Mantissa;
+
+#[cfg(feature = "rayon")]
+use crate::tuning;
+
+#[cfg(not(feature = "rayon"))]
 use itertools::izip;
 
 #[cfg(not(feature = "std"))]
@@ -26,7 +31,7 @@ impl Mantissa {
         j = ((j >> 4) & 0x0F0F0F0F0F0F0F0F) | ((j & 0x0F0F0F0F0F0F0F0F) << 4);
         j = ((j >> 8) & 0x00FF00FF00FF00FF) | ((j & 0x00FF00FF00FF00FF) << 8);
         j = ((j >> 16) & 0x0000FFFF0000FFFF) | ((j & 0x0000FFFF0000FFFF) << 16);
-        j = (j >> 32) | (j << 32);
+        j = j.rotate_left(32);
 
         j >>= core::mem::size_of::<u64>() * 8 - k;
 
@@ -187,10 +192,10 @@ impl Mantissa {
         }
         let k = log2_ceil(k1).max(6);
         let k1 = 1 << k;
-        let m = (n + k1 - 1) / k1;
+        let m = n.div_ceil(k1);
         let n = m * k1;
-        let n1 = (2 * n + k1 - 1) / k1 + k;
-        let n1 = ((n1 + k1 - 1) / k1) * k1;
+        let n1 = (2 * n).div_ceil(k1) + k;
+        let n1 = n1.div_ceil(k1) * k1;
         let t = n1 / k1;
         (n, k1, k, m, n1, t)
     }
@@ -201,13 +206,13 @@ impl Mantissa {
 
         let mut parts_iter = parts.iter_mut();
 
-        if m % WORD_BIT_SIZE == 0 {
+        if m.is_multiple_of(WORD_BIT_SIZE) {
             for (chunk, part) in d.chunks(m / WORD_BIT_SIZE).zip(parts_iter.by_ref()) {
                 part[..chunk.len()].copy_from_slice(chunk);
                 part[chunk.len()..].fill(0);
             }
         } else {
-            let chunk_sz = (m + WORD_BIT_SIZE - 1) / WORD_BIT_SIZE + 1;
+            let chunk_sz = m.div_ceil(WORD_BIT_SIZE) + 1;
             let mask = WORD_MAX >> (WORD_BIT_SIZE - (m % WORD_BIT_SIZE));
             let mut s = 0;
             let mut idx;
@@ -321,7 +326,7 @@ impl Mantissa {
         modulus: &SliceWithSign,
         tmp_buf: &mut [Word],
     ) {
-        debug_assert!(n % WORD_BIT_SIZE == 0);
+        debug_assert!(n.is_multiple_of(WORD_BIT_SIZE));
 
         let (work_buf, ext_buf) = tmp_buf.split_at_mut(num.len());
 
@@ -357,10 +362,10 @@ impl Mantissa {
         modulus: &SliceWithSign,
         tmp_buf: &mut [Word],
     ) {
-        debug_assert!(n % WORD_BIT_SIZE == 0);
+        debug_assert!(n.is_multiple_of(WORD_BIT_SIZE));
 
         let n2 = n * 2;
-        let mut shift = ((j + n2 - 1) / n2) * n2 - j;
+        let mut shift = j.div_ceil(n2) * n2 - j;
 
         let mut s = 1;
         if shift >= n {
@@ -383,7 +388,7 @@ impl Mantissa {
         buf: &mut [Word],
         k1: usize,
         part_len: usize,
-    ) -> Result<Vec<SliceWithSign>, Error> {
+    ) -> Result<Vec<SliceWithSign<'_>>, Error> {
         let mut parts = Vec::<SliceWithSign>::new();
         parts.try_reserve_exact(k1)?;
 
@@ -400,6 +405,72 @@ impl Mantissa {
         Ok(parts)
     }
 
+    // pointwise product of a pair of parts modulo 2^n1 + 1.
+    fn fft_mul_part(
+        part1: &SliceWithSign,
+        part2: &SliceWithSign,
+        part3: &mut SliceWithSign,
+        n1: usize,
+        modulus: &SliceWithSign,
+        tmp_buf: &mut [Word],
+        tmp_buf2: &mut [Word],
+    ) -> Result<(), Error> {
+        Self::mul_unbalanced(part1, part2, tmp_buf2)?;
+
+        part3.set_sign(part1.sign() * part2.sign());
+
+        let mut t0 = SliceWithSign::new_mut(tmp_buf2, part3.sign());
+        Self::fft_mul_mod(&mut t0, 0, n1, modulus, tmp_buf);
+
+        let part_len = part3.len();
+        part3.copy_from_slice(&tmp_buf2[..part_len]);
+
+        Ok(())
+    }
+
+    // pointwise products of the transformed parts.
+    fn fft_pointwise_mul(
+        parts1: &[SliceWithSign],
+        parts2: &[SliceWithSign],
+        parts3: &mut [SliceWithSign],
+        n1: usize,
+        modulus: &SliceWithSign,
+        tmp_buf: &mut [Word],
+        tmp_buf2: &mut [Word],
+    ) -> Result<(), Error> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            // each task multiplies a group of parts with its own temporary buffers
+            let (l1, l2) = (tmp_buf.len(), tmp_buf2.len());
+            let min_len = tuning::mul_par() / modulus.len() + 1;
+
+            parts1
+                .par_iter()
+                .zip(parts2.par_iter())
+                .zip(parts3.par_iter_mut())
+                .with_min_len(min_len)
+                .try_for_each_init(
+                    || WordBuf::new(l1 + l2),
+                    |buf, ((part1, part2), part3)| {
+                        let buf = buf.as_mut().map_err(|e| *e)?;
+                        let (tmp_buf, tmp_buf2) = buf.split_at_mut(l1);
+                        Self::fft_mul_part(part1, part2, part3, n1, modulus, tmp_buf, tmp_buf2)
+                    },
+                )
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        {
+            for (part1, part2, part3) in izip!(parts1.iter(), parts2.iter(), parts3.iter_mut()) {
+                Self::fft_mul_part(part1, part2, part3, n1, modulus, tmp_buf, tmp_buf2)?;
+            }
+
+            Ok(())
+        }
+    }
+
     // multiply two integer numbers.
     pub(super) fn fft_mul(d1: &[Word], d2: &[Word], d3: &mut [Word]) -> Result<(), Error> {
         let l: usize = (d1.len() + d2.len()) * WORD_BIT_SIZE;
@@ -440,16 +511,15 @@ impl Mantissa {
         Self::fft_forward(&mut parts1, w, k1, k, 1, n1, &modulus, tmp_buf);
         Self::fft_forward(&mut parts2, w, k1, k, 1, n1, &modulus, tmp_buf);
 
-        for (part1, part2, part3) in izip!(parts1.iter(), parts2.iter(), parts3.iter_mut()) {
-            Self::mul_unbalanced(part1, part2, tmp_buf2)?;
-
-            part3.set_sign(part1.sign() * part2.sign());
-
-            let mut t0 = SliceWithSign::new_mut(tmp_buf2, part3.sign());
-            Self::fft_mul_mod(&mut t0, 0, n1, &modulus, tmp_buf);
-
-            part3.copy_from_slice(&tmp_buf2[..part_len]);
-        }
+        Self::fft_pointwise_mul(
+            &parts1,
+            &parts2,
+            &mut parts3,
+            n1,
+            &modulus,
+            tmp_buf,
+            tmp_buf2,
+        )?;
 
         Self::fft_reverse(&mut parts3, w, k1, n1, &modulus, tmp_buf);
 
@@ -30,6 +30,11 @@ pub struct Mantissa {
 }
 
 impl Mantissa {
+    /// Keep the memory of the mantissa for reuse by the next allocation on the current thread.
+    pub fn recycle(self) {
+        self.m.recycle();
+    }
+
     // bit lenth to length in words.
     #[inline]
     fn bit_len_to_word_len(p: usize) -> usize {
@@ -76,7 +81,7 @@ impl Mantissa {
     pub fn from_word(p: usize, mut d: Word) -> Result<Self, Error> {
         let mut m = Self::reserve_new(Self::bit_len_to_word_len(p))?;
 
-        if m.len() == 0 {
+        if m.is_empty() {
             return Err(Error::InvalidArgument);
         }
 
@@ -286,9 +291,7 @@ impl Mantissa {
             // m1 1XXXXXX00000   - m1 and trailing zeroes
             // m2 0000001XXXXX   - m2_shift, m2
 
-            let l = self
-                .len()
-                .max((m2_shift + WORD_BIT_SIZE - 1) / WORD_BIT_SIZE + m2.len());
+            let l = self.len().max(m2_shift.div_ceil(WORD_BIT_SIZE) + m2.len());
 
             let mut m3 = Mantissa::new(l * WORD_BIT_SIZE)?;
 
@@ -397,10 +400,7 @@ impl Mantissa {
                 // m1 1XXXXXX00000   - m1 and trailing zeroes
                 // m2 0000001XXXXX   - m2_shift, m2
 
-                let l = self
-                    .len()
-                    .max((m2_shift + WORD_BIT_SIZE - 1) / WORD_BIT_SIZE + m2.len())
-                    + 1;
+                let l = self.len().max(m2_shift.div_ceil(WORD_BIT_SIZE) + m2.len()) + 1;
 
                 let mut m3 = Mantissa::new(l * WORD_BIT_SIZE)?;
 
@@ -751,8 +751,14 @@ impl Mantissa {
             return Ok((0, ret));
         }
 
-        m = Self::reserve_new(1)?;
-        m[0] = u as Word;
+        // usize can be wider than a word
+        m = Self::reserve_new(size_of::<usize>().div_ceil(size_of::<Word>()))?;
+
+        let mut u = u;
+        for v in m.iter_mut() {
+            *v = u as Word;
+            u = u.checked_shr(WORD_BIT_SIZE as u32).unwrap_or(0);
+        }
 
         let shift = Self::maximize(&mut m);
         let mut ret = Mantissa { m, n: 0 };
@@ -874,7 +880,7 @@ impl Mantissa {
         s: usize,
         inexact: &mut bool,
     ) -> bool {
-        debug_assert!(s % WORD_BIT_SIZE == 0); // assume s is aligned to the word size.
+        debug_assert!(s.is_multiple_of(WORD_BIT_SIZE)); // assume s is aligned to the word size.
 
         // This function is so complex, because it combines rounding for all rounding modes
         // and checks for roundability at the same time.
@@ -1099,7 +1105,6 @@ impl Mantissa {
                     } else {
                         *v = 0;
                     }
-                    i += 1;
                 }
                 self.m[self_len - 1] = WORD_SIGNIFICANT_BIT;
                 return true;
@@ -1177,6 +1182,11 @@ impl Mantissa {
         Ok(Mantissa { m, n: self.n })
     }
 
+    /// Release unused memory.
+    pub fn shrink_to_fit(&mut self) {
+        self.m.shrink_to_fit();
+    }
+
     pub fn digits(&self) -> &[Word] {
         &self.m
     }
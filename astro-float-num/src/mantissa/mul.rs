@@ -7,6 +7,7 @@ use crate::defs::Error;
 use crate::defs::Word;
 use crate::defs::WORD_BIT_SIZE;
 use crate::mantissa::Mantissa;
+use crate::tuning;
 
 impl Mantissa {
     pub(super) fn mul_basic(m1: &[Word], m2: &[Word], m3: &mut [Word]) {
@@ -32,11 +33,17 @@ impl Mantissa {
     fn mul_slices(m1: &[Word], m2: &[Word], m3: &mut [Word]) -> Result<(), Error> {
         debug_assert!(m1.len() <= m2.len());
 
-        if m1.len() <= 32 || m2.len() <= 32 {
+        let (basic, toom2, toom3) = (
+            tuning::mul_basic(),
+            tuning::mul_toom2(),
+            tuning::mul_toom3(),
+        );
+
+        if m1.len() <= basic || m2.len() <= basic {
             Self::mul_basic(m1, m2, m3);
-        } else if m1.len() <= 220 || m2.len() <= 220 {
+        } else if m1.len() <= toom2 || m2.len() <= toom2 {
             Self::toom2(m1, m2, m3)?;
-        } else if m1.len() <= 5400 && m2.len() <= 5400 {
+        } else if m1.len() <= toom3 && m2.len() <= toom3 {
             Self::toom3(m1, m2, m3)?;
         } else {
             Mantissa::fft_mul(m1, m2, m3)?;
@@ -44,6 +51,25 @@ impl Mantissa {
         Ok(())
     }
 
+    // independent products a * b -> c, computed in parallel if the operands are large enough
+    pub(super) fn mul_batch(prods: &mut [(&[Word], &[Word], &mut [Word])]) -> Result<(), Error> {
+        #[cfg(feature = "rayon")]
+        if prods
+            .iter()
+            .all(|(a, b, _)| a.len().min(b.len()) >= tuning::mul_par())
+        {
+            use rayon::prelude::*;
+
+            return prods
+                .par_iter_mut()
+                .try_for_each(|(a, b, c)| Self::mul_unbalanced(a, b, c));
+        }
+
+        prods
+            .iter_mut()
+            .try_for_each(|(a, b, c)| Self::mul_unbalanced(a, b, c))
+    }
+
     // general case multiplication
     pub(super) fn mul_unbalanced(m1: &[Word], m2: &[Word], m3: &mut [Word]) -> Result<(), Error> {
         let (sm, lg) = if m1.len() < m2.len() { (m1, m2) } else { (m2, m1) };
@@ -164,6 +190,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mul_large() {
+        // toom-3 and fft with sub-products large enough to be computed in parallel
+        for sz in [tuning::mul_toom3() - 1, tuning::mul_toom3() + 1] {
+            let m1 = random_slice(sz, sz);
+            let m2 = random_slice(sz, sz);
+            let mut ret1 = WordBuf::new(2 * sz).unwrap();
+            let mut ret2 = WordBuf::new(2 * sz).unwrap();
+            Mantissa::mul_slices(&m1, &m2, &mut ret1).unwrap();
+            Mantissa::mul_basic(&m1, &m2, &mut ret2);
+            assert!(ret1[..] == ret2[..]);
+        }
+    }
+
     #[ignore]
     #[test]
     fn test_mul_short() {
@@ -156,7 +156,10 @@ impl Mantissa {
         }
     }
 
-    fn sqrt_rem_split(m: &[Word], l: usize) -> (SliceWithSign, SliceWithSign, SliceWithSign) {
+    fn sqrt_rem_split(
+        m: &[Word],
+        l: usize,
+    ) -> (SliceWithSign<'_>, SliceWithSign<'_>, SliceWithSign<'_>) {
         let (m, m2) = m.split_at(2 * l);
         let (m0, m1) = m.split_at(l);
 
@@ -8,7 +8,10 @@ use crate::defs::Word;
 use crate::mantissa::Mantissa;
 
 impl Mantissa {
-    fn toom3_get_splits(m: &[Word], l: usize) -> (SliceWithSign, SliceWithSign, SliceWithSign) {
+    fn toom3_get_splits(
+        m: &[Word],
+        l: usize,
+    ) -> (SliceWithSign<'_>, SliceWithSign<'_>, SliceWithSign<'_>) {
         let b11 = l.min(m.len());
         let b12 = l.min(m.len() - b11) + b11;
         let b13 = l.min(m.len() - b12) + b12;
@@ -58,7 +61,7 @@ impl Mantissa {
     // d1 must contain input number + have reserve of d2.len() positions in addition for the output.
     // The result is placed in d1, and the sign is returned.
     pub(super) fn toom3(d1: &[Word], d2: &[Word], d3: &mut [Word]) -> Result<(), Error> {
-        let l = (d1.len().max(d2.len()) + 2) / 3;
+        let l = d1.len().max(d2.len()).div_ceil(3);
 
         let mut buf = WordBuf::new(25 * (l + 1))?;
 
@@ -107,11 +110,13 @@ impl Mantissa {
         debug_assert!(p2.len() + q2.len() == s2.len());
         debug_assert!(p3.len() + q3.len() == s3.len());
 
-        Self::mul_unbalanced(&p0, &q0, &mut s0)?;
-        Self::mul_unbalanced(&p1, &q1, &mut s1)?;
-        Self::mul_unbalanced(&p2, &q2, &mut s2)?;
-        Self::mul_unbalanced(&p3, &q3, &mut s3)?;
-        Self::mul_unbalanced(&p4, &q4, &mut s4)?;
+        Self::mul_batch(&mut [
+            (&p0, &q0, &mut s0),
+            (&p1, &q1, &mut s1),
+            (&p2, &q2, &mut s2),
+            (&p3, &q3, &mut s3),
+            (&p4, &q4, &mut s4),
+        ])?;
 
         s1.set_sign(p1.sign() * q1.sign());
         s2.set_sign(p2.sign() * q2.sign());
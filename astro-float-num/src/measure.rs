@@ -0,0 +1,304 @@
+//! Numbers tagged with units of measurement.
+//!
+//! `Measure<U>` wraps a `BigFloat` with a unit `U` known at compile time, so that lengths can't be added to times,
+//! and meters can't be added to kilometers without a conversion.
+//! Each unit defines its dimension and its size in the base unit of the dimension as an exact ratio of integers.
+//! Conversions and operations on measures given in different units of the same dimension are done
+//! with a single rounding of the result.
+//!
+//! ```
+//! use astro_float_num::measure::{Kilometer, Measure, Meter};
+//! use astro_float_num::{BigFloat, RoundingMode};
+//!
+//! let d: Measure<Kilometer> = Measure::new(BigFloat::from_f64(1.5, 64));
+//! let m: Measure<Meter> = d.convert(128, RoundingMode::ToEven);
+//! assert_eq!(m.value(), &BigFloat::from_word(1500, 128));
+//! ```
+//!
+//! Custom units are defined by implementing `Unit`:
+//!
+//! ```
+//! use astro_float_num::measure::{Length, Unit};
+//!
+//! pub enum Inch {}
+//!
+//! impl Unit for Inch {
+//!     type Dimension = Length;
+//!     const NUM: u64 = 127;
+//!     const DEN: u64 = 5000;
+//! }
+//! ```
+
+use crate::defs::RoundingMode;
+use crate::BigFloat;
+
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+/// Unit of measurement.
+pub trait Unit {
+    /// Dimension of the unit. Measures can be converted only between units of the same dimension.
+    type Dimension;
+
+    /// Numerator of the size of the unit in the base unit of the dimension.
+    const NUM: u64;
+
+    /// Denominator of the size of the unit in the base unit of the dimension.
+    const DEN: u64;
+}
+
+/// Dimension of length with the base unit of meter.
+pub enum Length {}
+
+/// Dimension of time with the base unit of second.
+pub enum Time {}
+
+/// Dimension of mass with the base unit of kilogram.
+pub enum Mass {}
+
+macro_rules! impl_unit {
+    ($name:ident, $dim:ty, $num:expr, $den:expr, $doc:expr) => {
+        #[doc = $doc]
+        pub enum $name {}
+
+        impl Unit for $name {
+            type Dimension = $dim;
+            const NUM: u64 = $num;
+            const DEN: u64 = $den;
+        }
+    };
+}
+
+impl_unit!(Meter, Length, 1, 1, "Meter.");
+impl_unit!(Kilometer, Length, 1000, 1, "Kilometer.");
+impl_unit!(
+    AstronomicalUnit,
+    Length,
+    149597870700,
+    1,
+    "Astronomical unit, 149597870700 meters."
+);
+impl_unit!(
+    LightYear,
+    Length,
+    9460730472580800,
+    1,
+    "Light-year, the distance travelled by light in a Julian year."
+);
+
+impl_unit!(Second, Time, 1, 1, "Second.");
+impl_unit!(Minute, Time, 60, 1, "Minute.");
+impl_unit!(Hour, Time, 3600, 1, "Hour.");
+impl_unit!(Day, Time, 86400, 1, "Day of 86400 seconds.");
+impl_unit!(JulianYear, Time, 31557600, 1, "Julian year of 365.25 days.");
+
+impl_unit!(Kilogram, Mass, 1, 1, "Kilogram.");
+impl_unit!(Gram, Mass, 1, 1000, "Gram.");
+
+/// Number tagged with the unit `U`.
+pub struct Measure<U: Unit> {
+    value: BigFloat,
+    unit: PhantomData<U>,
+}
+
+impl<U: Unit> Measure<U> {
+    /// Constructs a measure of `value` units `U`.
+    pub fn new(value: BigFloat) -> Self {
+        Measure {
+            value,
+            unit: PhantomData,
+        }
+    }
+
+    /// Returns the value of the measure in units `U`.
+    pub fn value(&self) -> &BigFloat {
+        &self.value
+    }
+
+    /// Returns the value of the measure in units `U` consuming the measure.
+    pub fn into_value(self) -> BigFloat {
+        self.value
+    }
+
+    /// Converts the measure to units `V` with precision `p`.
+    /// The result is rounded using the rounding mode `rm` with a single rounding.
+    /// Precision is rounded upwards to the word size.
+    /// The value of the result is NaN if the precision `p` is incorrect.
+    pub fn convert<V: Unit<Dimension = U::Dimension>>(
+        &self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Measure<V> {
+        let (n, d) = ratio::<U, V>();
+        Measure::new(self.value.mul_full_prec(&n).div(&d, p, rm))
+    }
+
+    /// Adds `d2` given in units `V` to the measure with precision `p`, and returns the result in units `U`.
+    /// The result is rounded using the rounding mode `rm` with a single rounding.
+    /// Precision is rounded upwards to the word size.
+    /// The value of the result is NaN if the precision `p` is incorrect.
+    ///
+    /// The sum is computed exactly before rounding, so the memory use grows with the difference
+    /// of the exponents of the values.
+    pub fn add<V: Unit<Dimension = U::Dimension>>(
+        &self,
+        d2: &Measure<V>,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Self {
+        let (n, d) = ratio::<V, U>();
+        let d1 = self.value.mul_full_prec(&d);
+        let d2 = d2.value.mul_full_prec(&n);
+        Measure::new(d1.add_full_prec(&d2).div(&d, p, rm))
+    }
+
+    /// Subtracts `d2` given in units `V` from the measure with precision `p`, and returns the result in units `U`.
+    /// The result is rounded using the rounding mode `rm` with a single rounding.
+    /// Precision is rounded upwards to the word size.
+    /// The value of the result is NaN if the precision `p` is incorrect.
+    ///
+    /// The difference is computed exactly before rounding, so the memory use grows with the difference
+    /// of the exponents of the values.
+    pub fn sub<V: Unit<Dimension = U::Dimension>>(
+        &self,
+        d2: &Measure<V>,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Self {
+        let (n, d) = ratio::<V, U>();
+        let d1 = self.value.mul_full_prec(&d);
+        let d2 = d2.value.mul_full_prec(&n);
+        Measure::new(d1.sub_full_prec(&d2).div(&d, p, rm))
+    }
+
+    /// Multiplies the measure by a dimensionless number `k` with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    /// The value of the result is NaN if the precision `p` is incorrect.
+    pub fn scale(&self, k: &BigFloat, p: usize, rm: RoundingMode) -> Self {
+        Measure::new(self.value.mul(k, p, rm))
+    }
+
+    /// Computes the ratio of the measure to `d2` given in units `V` with precision `p`.
+    /// The result is rounded using the rounding mode `rm` with a single rounding.
+    /// Precision is rounded upwards to the word size.
+    /// The function returns NaN if the precision `p` is incorrect.
+    pub fn ratio<V: Unit<Dimension = U::Dimension>>(
+        &self,
+        d2: &Measure<V>,
+        p: usize,
+        rm: RoundingMode,
+    ) -> BigFloat {
+        let (n, d) = ratio::<U, V>();
+        let d1 = self.value.mul_full_prec(&n);
+        let d2 = d2.value.mul_full_prec(&d);
+        d1.div(&d2, p, rm)
+    }
+}
+
+impl<U: Unit> Clone for Measure<U> {
+    fn clone(&self) -> Self {
+        Measure::new(self.value.clone())
+    }
+}
+
+impl<U: Unit> Debug for Measure<U> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Measure")
+            .field("value", &self.value)
+            .field("unit", &core::any::type_name::<U>())
+            .finish()
+    }
+}
+
+impl<U: Unit> PartialEq for Measure<U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<U: Unit> PartialOrd for Measure<U> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+// Returns the numerator and the denominator of the ratio of the size of the unit `U` to the size of the unit `V`.
+fn ratio<U: Unit, V: Unit>() -> (BigFloat, BigFloat) {
+    let n = U::NUM as u128 * V::DEN as u128;
+    let d = U::DEN as u128 * V::NUM as u128;
+    (BigFloat::from_u128(n, 128), BigFloat::from_u128(d, 128))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // exact conversions
+        let d: Measure<Kilometer> = Measure::new(BigFloat::from_f64(1.5, 64));
+        let m: Measure<Meter> = d.convert(p, rm);
+        assert_eq!(m.value(), &BigFloat::from_word(1500, p));
+        assert!(!m.value().inexact());
+        let d2: Measure<Kilometer> = m.convert(p, rm);
+        assert_eq!(d2, d);
+
+        let g: Measure<Gram> = Measure::new(BigFloat::from_word(250, 64));
+        let kg: Measure<Kilogram> = g.convert(p, rm);
+        assert_eq!(kg.value(), &BigFloat::from_f64(0.25, p));
+
+        // single rounding
+        let one = BigFloat::from_word(1, 64);
+        let ly: Measure<LightYear> = Measure::new(one.clone());
+        for rm in [RoundingMode::Up, RoundingMode::Down, RoundingMode::ToEven] {
+            let au: Measure<AstronomicalUnit> = ly.convert(p, rm);
+            let expected = BigFloat::from_u64(9460730472580800, 64).div(
+                &BigFloat::from_u64(149597870700, 64),
+                p,
+                rm,
+            );
+            assert_eq!(au.value(), &expected);
+            assert!(au.value().inexact());
+        }
+
+        // operations on different units
+        let h: Measure<Hour> = Measure::new(one.clone());
+        let s: Measure<Second> = Measure::new(BigFloat::from_word(1, 64));
+        let d1 = h.add(&s, p, rm);
+        let expected = BigFloat::from_word(3601, 64).div(&BigFloat::from_word(3600, 64), p, rm);
+        assert_eq!(d1.value(), &expected);
+        let d1 = h.sub(&s, p, rm);
+        let expected = BigFloat::from_word(3599, 64).div(&BigFloat::from_word(3600, 64), p, rm);
+        assert_eq!(d1.value(), &expected);
+        let d1 = s.add(&h, p, rm);
+        assert_eq!(d1.value(), &BigFloat::from_word(3601, p));
+
+        let y: Measure<JulianYear> = Measure::new(one.clone());
+        let d: Measure<Day> = Measure::new(BigFloat::from_word(365, 64));
+        let r = y.ratio(&d, p, rm);
+        assert_eq!(
+            r,
+            BigFloat::from_f64(365.25, 64).div(&BigFloat::from_word(365, 64), p, rm)
+        );
+
+        let m: Measure<Minute> = Measure::new(one.clone());
+        let d1 = m.scale(&BigFloat::from_word(3, 64), p, rm);
+        assert_eq!(d1.value(), &BigFloat::from_word(3, p));
+        assert!(d1 > m);
+
+        let d1: Measure<Second> = m.convert(p, rm);
+        assert_eq!(d1.into_value(), BigFloat::from_word(60, p));
+
+        // special values
+        let d: Measure<Meter> = Measure::new(crate::INF_POS);
+        assert!(d.convert::<Kilometer>(p, rm).value().is_inf_pos());
+        let d: Measure<Meter> = Measure::new(crate::NAN);
+        assert!(d.convert::<Kilometer>(p, rm).value().is_nan());
+    }
+}
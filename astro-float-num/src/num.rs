@@ -9,6 +9,7 @@ use crate::defs::RoundingMode;
 use crate::defs::Sign;
 use crate::defs::SignedWord;
 use crate::defs::Word;
+use crate::defs::EXPONENT_BIT_SIZE;
 use crate::defs::EXPONENT_MAX;
 use crate::defs::EXPONENT_MIN;
 use crate::defs::WORD_BIT_SIZE;
@@ -17,7 +18,7 @@ use crate::mantissa::Mantissa;
 
 /// A finite floating point number with mantissa of an arbitrary size, an exponent, and the sign.
 #[derive(Debug, Hash)]
-pub(crate) struct BigFloatNumber {
+pub struct BigFloatNumber {
     e: Exponent,
     s: Sign,
     m: Mantissa,
@@ -229,6 +230,10 @@ impl BigFloatNumber {
     ///  - InvalidArgument: the precision is incorrect.
     #[inline]
     pub fn add(&self, d2: &Self, p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        if let Some(ret) = self.add_sub_fast(d2, p, 1)? {
+            return Ok(ret);
+        }
+
         self.add_sub(d2, p, 1, rm, false)
     }
 
@@ -242,6 +247,10 @@ impl BigFloatNumber {
     ///  - InvalidArgument: the precision is incorrect.
     #[inline]
     pub fn sub(&self, d2: &Self, p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        if let Some(ret) = self.add_sub_fast(d2, p, -1)? {
+            return Ok(ret);
+        }
+
         self.add_sub(d2, p, -1, rm, false)
     }
 
@@ -271,6 +280,33 @@ impl BigFloatNumber {
         self.add_sub(d2, 0, -1, RoundingMode::None, true)
     }
 
+    /// Adds `d2` to `self` and returns the sum with precision `p` rounded according to `rm`, and the residual.
+    /// The residual is the exact difference between the exact sum and the rounded sum,
+    /// so that adding the residual to the rounded sum gives `self + d2` exactly.
+    /// The residual has the precision required to represent it exactly, which may be larger than `p`
+    /// if the arguments have larger precision or if their exponents are far apart.
+    /// This operation can be used to build floating-point expansions.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn two_sum(&self, d2: &Self, p: usize, rm: RoundingMode) -> Result<(Self, Self), Error> {
+        let sum = self.add(d2, p, rm)?;
+
+        let mut residual = if sum.inexact() {
+            self.add_full_prec(d2)?.sub_full_prec(&sum)?
+        } else {
+            Self::new2(sum.mantissa_max_bit_len(), sum.sign(), false)?
+        };
+
+        residual.set_inexact(self.inexact() || d2.inexact());
+
+        Ok((sum, residual))
+    }
+
     /// Multiplies `d2` by `self` and returns the result of the operation with precision `p` rounded according to `rm`.
     /// Precision is rounded upwards to the word size.
     ///
@@ -281,6 +317,10 @@ impl BigFloatNumber {
     ///  - InvalidArgument: the precision is incorrect.
     #[inline]
     pub fn mul(&self, d2: &Self, p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        if let Some(ret) = self.mul_fast(d2, p)? {
+            return Ok(ret);
+        }
+
         self.mul_general_case(d2, p, rm, false)
     }
 
@@ -382,6 +422,10 @@ impl BigFloatNumber {
         let p = round_p(p);
         Self::p_assertion(p)?;
 
+        if let Some(ret) = self.div_fast(d2, p)? {
+            return Ok(ret);
+        }
+
         let s = if self.s == d2.s { Sign::Pos } else { Sign::Neg };
 
         if self.m.is_zero() {
@@ -427,6 +471,121 @@ impl BigFloatNumber {
         }
     }
 
+    /// Adds `d2` to `self` in place with precision `p` rounded according to `rm`.
+    /// The memory of the previous mantissa of `self` is reused by the next operation on the current thread,
+    /// so a loop of in-place operations with the same precision does not allocate memory for the results.
+    /// If an error occurs, `self` is left unchanged.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn add_assign_ext(&mut self, d2: &Self, p: usize, rm: RoundingMode) -> Result<(), Error> {
+        let ret = self.add(d2, p, rm)?;
+        self.replace(ret);
+        Ok(())
+    }
+
+    /// Subtracts `d2` from `self` in place with precision `p` rounded according to `rm`.
+    /// The memory is reused in the same way as in `add_assign_ext`.
+    /// If an error occurs, `self` is left unchanged.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn sub_assign_ext(&mut self, d2: &Self, p: usize, rm: RoundingMode) -> Result<(), Error> {
+        let ret = self.sub(d2, p, rm)?;
+        self.replace(ret);
+        Ok(())
+    }
+
+    /// Multiplies `self` by `d2` in place with precision `p` rounded according to `rm`.
+    /// The memory is reused in the same way as in `add_assign_ext`.
+    /// If an error occurs, `self` is left unchanged.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn mul_assign_ext(&mut self, d2: &Self, p: usize, rm: RoundingMode) -> Result<(), Error> {
+        let ret = self.mul(d2, p, rm)?;
+        self.replace(ret);
+        Ok(())
+    }
+
+    /// Divides `self` by `d2` in place with precision `p` rounded according to `rm`.
+    /// The memory is reused in the same way as in `add_assign_ext`.
+    /// If an error occurs, `self` is left unchanged.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - DivisionByZero: `d2` is zero.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: both `self` and `d2` are zero or precision is incorrect.
+    pub fn div_assign_ext(&mut self, d2: &Self, p: usize, rm: RoundingMode) -> Result<(), Error> {
+        let ret = self.div(d2, p, rm)?;
+        self.replace(ret);
+        Ok(())
+    }
+
+    // Replace `self` with `v` and keep the memory of the previous mantissa for reuse.
+    fn replace(&mut self, v: Self) {
+        let old = core::mem::replace(self, v);
+        old.recycle();
+    }
+
+    // Keep the memory of the mantissa for reuse by the next allocation on the current thread.
+    pub(crate) fn recycle(self) {
+        self.m.recycle();
+    }
+
+    /// Returns the remainder of division of `|self|` by `|d2|` like `rem`, and the low bits of the integer quotient.
+    /// The integer quotient `n` is `|self| / |d2|` rounded toward zero, so that `|self| = n * |d2| + |r|`, where `r` is the remainder.
+    /// The second value holds the 63 least significant bits of `n` with the sign of `self / d2`.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: `d2` is zero.
+    pub fn remquo(&self, d2: &Self) -> Result<(Self, i64), Error> {
+        let r = self.rem(d2)?;
+
+        if self.is_zero() {
+            return Ok((r, 0));
+        }
+
+        let mut x = self.clone()?;
+        x.s = Sign::Pos;
+        let mut s = d2.clone()?;
+        s.s = Sign::Pos;
+        let mut ra = r.clone()?;
+        ra.s = Sign::Pos;
+
+        // |self| rem (2^63 * |d2|) = |r| + (n mod 2^63) * |d2|
+        let rk = match s.ldexp(63, s.mantissa_max_bit_len(), RoundingMode::None) {
+            Ok(m) => x.rem(&m)?,
+            Err(Error::ExponentOverflow(_)) => x, // n < 2^63
+            Err(e) => return Err(e),
+        };
+
+        // the quotient is an integer smaller than 2^63, so it is computed exactly
+        let q = rk.sub_full_prec(&ra)?.div(&s, 64, RoundingMode::ToEven)?;
+
+        let n = q.int_abs_as_u128().ok_or(Error::InvalidArgument)? as i64;
+
+        Ok((r, if self.s == d2.s { n } else { -n }))
+    }
+
     /// Returns the remainder of division of `|self|` by `|d2|`. The sign of the result is set to the sign of `self`.
     ///
     /// ## Errors
@@ -460,7 +619,7 @@ impl BigFloatNumber {
                     // first, see if m3 is already subnormal, then shift accordingly
                     let mut excess = m3.max_bit_len() - m3.bit_len();
 
-                    if EXPONENT_MIN as isize - e > usize::MAX as isize {
+                    if (EXPONENT_MIN as isize - e) as usize > usize::MAX / 2 {
                         m3.set_zero();
                         e = 0;
                     } else {
@@ -551,7 +710,7 @@ impl BigFloatNumber {
         if self.is_subnormal() {
             let (shift, mantissa) = self.m.normilize()?;
 
-            #[cfg(not(target_arch = "x86"))]
+            #[cfg(not(any(target_arch = "x86", feature = "exp64")))]
             {
                 // checks for the case when usize is larger than exponent
                 debug_assert!((shift as isize) < (isize::MAX / 2 + EXPONENT_MIN as isize));
@@ -590,7 +749,9 @@ impl BigFloatNumber {
         if self.m.is_zero() {
             let mut ret = if op < 0 { d2.neg() } else { d2.clone() }?;
 
-            ret.set_precision(p, rm)?;
+            if !full_prec {
+                ret.set_precision(p, rm)?;
+            }
 
             return Ok(ret);
         }
@@ -598,7 +759,9 @@ impl BigFloatNumber {
         if d2.m.is_zero() {
             let mut ret = self.clone()?;
 
-            ret.set_precision(p, rm)?;
+            if !full_prec {
+                ret.set_precision(p, rm)?;
+            }
 
             return Ok(ret);
         }
@@ -673,7 +836,7 @@ impl BigFloatNumber {
 
         d3.inexact |= inexact;
 
-        #[cfg(not(target_arch = "x86"))]
+        #[cfg(not(any(target_arch = "x86", feature = "exp64")))]
         {
             debug_assert!(shift <= isize::MAX / 2 && e >= isize::MIN / 2);
         }
@@ -687,6 +850,11 @@ impl BigFloatNumber {
 
         if e < EXPONENT_MIN as isize {
             d3.e = EXPONENT_MIN;
+            if full_prec {
+                // keep the bits shifted out during subnormalization
+                let p_ext = d3.m.max_bit_len() + (EXPONENT_MIN as isize - e) as usize;
+                d3.set_precision(p_ext, RoundingMode::None)?;
+            }
             d3.subnormalize(e, rm);
         } else {
             d3.e = e as Exponent;
@@ -748,6 +916,7 @@ impl BigFloatNumber {
 
     /// Compares `self` to `d2`.
     /// Returns positive if `self` is greater than `d2`, negative if `self` is smaller than `d2`, 0 otherwise.
+    #[allow(clippy::should_implement_trait)]
     pub fn cmp(&self, d2: &Self) -> SignedWord {
         if self.is_zero() && d2.is_zero() {
             return 0;
@@ -769,6 +938,8 @@ impl BigFloatNumber {
             } else {
                 return -1;
             }
+        } else if d2.m.is_zero() {
+            return 1;
         }
 
         let n1 = self.mantissa_max_bit_len() as isize - self.precision() as isize;
@@ -838,6 +1009,9 @@ impl BigFloatNumber {
             mantissa >>= 1;
             mantissa |= 0x8000000000000000u64;
             exponent += 1;
+        } else {
+            // subnormal f64 has the same exponent as the minimum normal f64
+            exponent = 1;
         }
 
         let (shift, m) = Mantissa::from_u64(p, mantissa)?;
@@ -845,7 +1019,7 @@ impl BigFloatNumber {
         ret.m = m;
         ret.e = exponent - 0b1111111111 - shift as Exponent;
 
-        #[cfg(target_arch = "x86")]
+        #[cfg(any(target_arch = "x86", feature = "exp64"))]
         debug_assert!(ret.e <= EXPONENT_MAX && ret.e >= EXPONENT_MIN);
 
         Ok(ret)
@@ -868,10 +1042,10 @@ impl BigFloatNumber {
                 Sign::Pos => f64::INFINITY,
                 Sign::Neg => f64::NEG_INFINITY,
             }
-        } else if e <= 0 {
-            let shift = -e;
-            if shift < 52 {
-                ret |= mantissa >> (shift + 12);
+        } else if e <= 1 {
+            let shift = 13 - e;
+            if shift < 64 {
+                ret |= mantissa >> shift;
                 if self.s == Sign::Neg {
                     ret |= 0x8000000000000000u64;
                 }
@@ -893,6 +1067,176 @@ impl BigFloatNumber {
         }
     }
 
+    /// Returns the value of `self` as f64 if the value can be represented in f64 exactly, otherwise returns None.
+    /// The value of `self` is considered regardless of the inexact flag.
+    /// Zero is converted to zero of the same sign.
+    #[allow(clippy::unnecessary_cast)] // Word can be u32
+    pub fn to_f64_exact(&self) -> Option<f64> {
+        if self.m.is_zero() {
+            return Some(if self.s == Sign::Neg { -0.0 } else { 0.0 });
+        }
+
+        let d = self.m.digits();
+
+        // positions of the lowest and the highest set bits of the mantissa
+        let j = d.iter().position(|w| *w != 0)?;
+        let i = d.iter().rposition(|w| *w != 0)?;
+        let lo = j * WORD_BIT_SIZE + d[j].trailing_zeros() as usize;
+        let hi = (i + 1) * WORD_BIT_SIZE - 1 - d[i].leading_zeros() as usize;
+
+        let bits = hi - lo + 1;
+        if bits > 53 {
+            return None;
+        }
+
+        let mut m: u64 = 0;
+        for (k, w) in d.iter().enumerate().take(i + 1).skip(j) {
+            let pos = k * WORD_BIT_SIZE;
+            if pos >= lo {
+                m |= (*w as u64) << (pos - lo);
+            } else {
+                m |= (*w as u64) >> (lo - pos);
+            }
+        }
+
+        // value = m * 2^e2, the mantissa is normalized to 53 bits
+        let shift = 53 - bits;
+        m <<= shift;
+        let e2 =
+            self.e as isize - (d.len() * WORD_BIT_SIZE) as isize + lo as isize - shift as isize;
+        let be = e2 + 52 + 0b1111111111;
+
+        let u = if be >= 0b11111111111 {
+            return None;
+        } else if be >= 1 {
+            ((be as u64) << 52) | (m & 0xFFFFFFFFFFFFF)
+        } else {
+            // f64 subnormal
+            let rs = (1 - be) as usize;
+            if rs > 52 || m.trailing_zeros() < rs as u32 {
+                return None;
+            }
+            m >> rs
+        };
+
+        let sign = if self.s == Sign::Neg { 0x8000000000000000u64 } else { 0 };
+
+        Some(f64::from_bits(u | sign))
+    }
+
+    /// Converts `self` to f64 using the rounding mode `rm`. `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the conversion is inexact.
+    /// The value of `self` is considered regardless of the inexact flag.
+    /// Zero is converted to zero of the same sign, and values outside of the range of f64
+    /// are converted to the infinity or to the largest finite f64 depending on the rounding mode.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    pub fn to_f64_round(&self, rm: RoundingMode) -> Result<(f64, bool), Error> {
+        self.to_binary_float(53, 1024, f64::MAX, rm)
+    }
+
+    /// Converts `self` to f32 using the rounding mode `rm`. `RoundingMode::None` rounds toward zero.
+    /// The second value of the result is true if the conversion is inexact.
+    /// The value of `self` is considered regardless of the inexact flag.
+    /// Zero is converted to zero of the same sign, and values outside of the range of f32
+    /// are converted to the infinity or to the largest finite f32 depending on the rounding mode.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    pub fn to_f32_round(&self, rm: RoundingMode) -> Result<(f32, bool), Error> {
+        // the result is representable in f32 exactly
+        self.to_binary_float(24, 128, f32::MAX as f64, rm)
+            .map(|(f, inexact)| (f as f32, inexact))
+    }
+
+    // Rounds `self` to the binary format with precision `pm` and the maximum exponent `emax`,
+    // and returns the result as f64, which represents it exactly.
+    pub(crate) fn to_binary_float(
+        &self,
+        pm: isize,
+        emax: isize,
+        max: f64,
+        rm: RoundingMode,
+    ) -> Result<(f64, bool), Error> {
+        let is_neg = self.s == Sign::Neg;
+
+        if self.m.is_zero() {
+            return Ok((if is_neg { -0.0 } else { 0.0 }, false));
+        }
+
+        let e = self.e as isize;
+
+        if e <= emax {
+            // number of bits of the format available at the exponent e, including the subnormal range
+            let emin = 3 - emax;
+            let bits = pm.min(e - emin + pm);
+
+            let mut x = self.clone()?;
+            x.set_exponent(bits as Exponent);
+            x.set_inexact(false);
+
+            let mut r = x.round_int(rm)?;
+            let inexact = r.cmp(&x) != 0;
+
+            if r.is_zero() {
+                return Ok((if is_neg { -0.0 } else { 0.0 }, inexact));
+            }
+
+            let re = r.e as isize + e - bits;
+            if re <= emax {
+                r.set_exponent(re as Exponent);
+                if let Some(f) = r.to_f64_exact() {
+                    return Ok((f, inexact));
+                }
+            }
+        }
+
+        // overflow
+        let is_inf = match rm {
+            RoundingMode::Up => !is_neg,
+            RoundingMode::Down => is_neg,
+            RoundingMode::ToZero | RoundingMode::None => false,
+            RoundingMode::FromZero | RoundingMode::ToEven | RoundingMode::ToOdd => true,
+        };
+
+        let f = if is_inf { f64::INFINITY } else { max };
+
+        Ok((if is_neg { -f } else { f }, true))
+    }
+
+    /// Returns the value of `self` as u128 if `self` is an integer in the range of u128, otherwise returns None.
+    /// The value of `self` is considered regardless of the inexact flag.
+    pub fn to_u128(&self) -> Option<u128> {
+        if !self.is_int() || (self.s == Sign::Neg && !self.m.is_zero()) {
+            return None;
+        }
+
+        self.int_abs_as_u128()
+    }
+
+    /// Returns the value of `self` as i128 if `self` is an integer in the range of i128, otherwise returns None.
+    /// The value of `self` is considered regardless of the inexact flag.
+    pub fn to_i128(&self) -> Option<i128> {
+        if !self.is_int() {
+            return None;
+        }
+
+        let u = self.int_abs_as_u128()?;
+
+        if self.s == Sign::Neg {
+            if u > i128::MIN.unsigned_abs() {
+                None
+            } else {
+                Some(0u128.wrapping_sub(u) as i128)
+            }
+        } else {
+            i128::try_from(u).ok()
+        }
+    }
+
     /// Returns true if `self` is subnormal. A number is subnormal if the most significant bit of the mantissa is not equal to 1.
     #[inline]
     pub fn is_subnormal(&self) -> bool {
@@ -929,10 +1273,10 @@ impl BigFloatNumber {
     ///
     ///  - MemoryAllocation: failed to allocate memory for mantissa.
     ///  - InvalidArgument: `n` is larger than the number of bits in `m`;
-    /// `n` is smaller than the number of bits in `m`, but `m` does not represent corresponding subnormal number mantissa;
-    /// `n` is smaller than the number of bits in `m`, but `e` is not the minimum possible exponent;
-    /// `n` or the size of `m` is too large (larger than isize::MAX / 2 + EXPONENT_MIN);
-    /// `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
+    ///    `n` is smaller than the number of bits in `m`, but `m` does not represent corresponding subnormal number mantissa;
+    ///    `n` is smaller than the number of bits in `m`, but `e` is not the minimum possible exponent;
+    ///    `n` or the size of `m` is too large (larger than isize::MAX / 2 + EXPONENT_MIN);
+    ///    `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
     pub fn from_raw_parts(
         m: &[Word],
         n: usize,
@@ -943,8 +1287,8 @@ impl BigFloatNumber {
         let p = m.len() * WORD_BIT_SIZE;
         Self::p_assertion(p)?;
 
-        #[cfg(target_arch = "x86")]
-        if e < EXPONENT_MIN || e > EXPONENT_MAX {
+        #[cfg(any(target_arch = "x86", feature = "exp64"))]
+        if !(EXPONENT_MIN..=EXPONENT_MAX).contains(&e) {
             return Err(Error::InvalidArgument);
         }
 
@@ -984,13 +1328,13 @@ impl BigFloatNumber {
     ///
     ///  - MemoryAllocation: failed to allocate memory for mantissa.
     ///  - InvalidArgument: size of `m` is larger than isize::MAX / 2 + EXPONENT_MIN or
-    /// when `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
+    ///    when `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX.
     pub fn from_words(m: &[Word], s: Sign, mut e: Exponent) -> Result<Self, Error> {
         let p = m.len() * WORD_BIT_SIZE;
         Self::p_assertion(p)?;
 
-        #[cfg(target_arch = "x86")]
-        if e < EXPONENT_MIN || e > EXPONENT_MAX {
+        #[cfg(any(target_arch = "x86", feature = "exp64"))]
+        if !(EXPONENT_MIN..=EXPONENT_MAX).contains(&e) {
             return Err(Error::InvalidArgument);
         }
 
@@ -1019,6 +1363,51 @@ impl BigFloatNumber {
         })
     }
 
+    /// Constructs a number from the slice of words like `from_words`, but instead of normalizing the mantissa
+    /// validates that the words and the exponent are the exact representation of the number:
+    ///
+    ///  - `m` is the mantissa.
+    ///  - `s` is the sign.
+    ///  - `e` is the exponent.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: `m` is empty, or the size of `m` is larger than isize::MAX / 2 + EXPONENT_MIN;
+    ///    `e` is less than EXPONENT_MIN or greater than EXPONENT_MAX;
+    ///    `m` is zero, but `e` is not 0;
+    ///    the most significant bit of `m` is not set, but `m` is not zero, and `e` is not EXPONENT_MIN.
+    pub fn from_words_checked(m: &[Word], s: Sign, e: Exponent) -> Result<Self, Error> {
+        if m.is_empty() {
+            return Err(Error::InvalidArgument);
+        }
+
+        let p = m.len() * WORD_BIT_SIZE;
+        Self::p_assertion(p)?;
+
+        #[cfg(any(target_arch = "x86", feature = "exp64"))]
+        if !(EXPONENT_MIN..=EXPONENT_MAX).contains(&e) {
+            return Err(Error::InvalidArgument);
+        }
+
+        let m = Mantissa::from_words(p, m)?;
+
+        if m.is_zero() {
+            if e != 0 {
+                return Err(Error::InvalidArgument);
+            }
+        } else if m.bit_len() < p && e != EXPONENT_MIN {
+            return Err(Error::InvalidArgument);
+        }
+
+        Ok(BigFloatNumber {
+            e,
+            s,
+            m,
+            inexact: false,
+        })
+    }
+
     /// Returns the sign of a number.
     #[inline]
     pub fn sign(&self) -> Sign {
@@ -1129,6 +1518,32 @@ impl BigFloatNumber {
         Ok(ret)
     }
 
+    /// Returns `self` rounded to an integer toward zero, i.e. the integer part of `self`.
+    /// The result has the same precision as `self` and is exact.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    pub fn trunc(&self) -> Result<Self, Error> {
+        self.int()
+    }
+
+    /// Returns `self` rounded to an integer using rounding mode `rm`.
+    /// `RoundingMode::None` rounds toward zero like `trunc`.
+    /// The result has the same precision as `self`, and its inexact flag is set if `self` is not an integer.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: rounding causes exponent overflow.
+    pub fn round_int(&self, rm: RoundingMode) -> Result<Self, Error> {
+        if rm == RoundingMode::None {
+            self.round(0, RoundingMode::ToZero)
+        } else {
+            self.round(0, rm)
+        }
+    }
+
     /// Returns true if `self` is odd integer number.
     pub(crate) fn is_odd_int(&self) -> bool {
         if self.e > 0 {
@@ -1155,6 +1570,11 @@ impl BigFloatNumber {
         }
     }
 
+    /// Returns true if `self` is an integer number. The function is the same as `is_int`.
+    pub fn is_integer(&self) -> bool {
+        self.is_int()
+    }
+
     /// Returns integer part of a number as built-in integer.
     pub(super) fn int_as_usize(&self) -> Result<usize, Error> {
         if self.e > 0 {
@@ -1171,18 +1591,40 @@ impl BigFloatNumber {
         }
     }
 
+    /// Returns the integer part of the absolute value of `self` as u128, or None if it does not fit.
+    pub(crate) fn int_abs_as_u128(&self) -> Option<u128> {
+        if self.e <= 0 {
+            return Some(0);
+        }
+
+        let e = self.e as usize;
+        if e > 128 {
+            return None;
+        }
+
+        let mut ret: u128 = 0;
+        let mut bits = 0;
+        for w in self.m.digits().iter().rev() {
+            if bits >= e {
+                break;
+            }
+            ret = (ret << WORD_BIT_SIZE) | *w as u128;
+            bits += WORD_BIT_SIZE;
+        }
+
+        if bits > e {
+            Some(ret >> (bits - e))
+        } else {
+            Some(ret << (e - bits))
+        }
+    }
+
     /// Sets the exponent of `self`.
     /// Note that if `self` is subnormal, the exponent may not change, but the mantissa will shift instead.
     /// `e` will be clamped to the range from EXPONENT_MIN to EXPONENT_MAX if it's outside of the range.
     pub fn set_exponent(&mut self, e: Exponent) {
-        #[cfg(target_arch = "x86")]
-        let e = if e < EXPONENT_MIN {
-            EXPONENT_MIN
-        } else if e > EXPONENT_MAX {
-            EXPONENT_MAX
-        } else {
-            e
-        };
+        #[cfg(any(target_arch = "x86", feature = "exp64"))]
+        let e = e.clamp(EXPONENT_MIN, EXPONENT_MAX);
 
         if !self.is_zero() {
             if self.is_subnormal() && e > EXPONENT_MIN {
@@ -1203,6 +1645,73 @@ impl BigFloatNumber {
         }
     }
 
+    /// Decomposes `self` into a fraction and an exponent, such that `self = fraction * 2^exponent`,
+    /// and the absolute value of the fraction is in the range [0.5, 1).
+    /// The fraction of a subnormal number is normalized, so the exponent can be smaller than EXPONENT_MIN.
+    /// The fraction has the same sign, precision, and inexact flag as `self`.
+    /// For zero, the fraction is zero and the exponent is 0.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    pub fn frexp(&self) -> Result<(Self, isize), Error> {
+        let mut ret = self.clone()?;
+
+        if self.is_zero() {
+            return Ok((ret, 0));
+        }
+
+        let mut e = self.e as isize;
+
+        if ret.is_subnormal() {
+            let shift = ret.m.max_bit_len() - ret.m.bit_len();
+            ret.m.shift_left(shift);
+            ret.m.set_bit_len(ret.m.max_bit_len());
+            e -= shift as isize;
+        }
+
+        ret.e = 0;
+
+        Ok((ret, e))
+    }
+
+    /// Returns `self` multiplied by 2 to the power of `e`, with precision `p` rounded according to `rm`.
+    /// The exponent is not limited to the range of exponent values, so values can be moved from and to the subnormal range.
+    /// If the result is smaller than the minimum positive normal number, it becomes subnormal
+    /// in the same way as the result of multiplication.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn ldexp(&self, e: isize, p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        let (mut ret, e0) = self.frexp()?;
+
+        ret.set_precision(p, rm)?;
+
+        if ret.is_zero() {
+            return Ok(ret);
+        }
+
+        // rounding may carry into the exponent of the fraction.
+        let e = (ret.e as isize).saturating_add(e0).saturating_add(e);
+
+        if e > EXPONENT_MAX as isize {
+            return Err(Error::ExponentOverflow(ret.s));
+        }
+
+        if e < EXPONENT_MIN as isize {
+            ret.e = EXPONENT_MIN;
+            ret.subnormalize(e, rm);
+        } else {
+            ret.e = e as Exponent;
+        }
+
+        Ok(ret)
+    }
+
     /// Returns the maximum mantissa length of `self` in bits regardless of whether `self` is normal or subnormal.
     #[inline]
     pub fn mantissa_max_bit_len(&self) -> usize {
@@ -1216,6 +1725,87 @@ impl BigFloatNumber {
         self.m.bit_len()
     }
 
+    /// Returns the unit in the last place of `self` at precision `p`, i.e. `2^(e - p)`, where `e` is the exponent of `self`.
+    /// The exponent of zero is considered to be EXPONENT_MIN, so the result is the same as for subnormal numbers.
+    /// The result is positive and exact. The precision of the result is `p` rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn ulp(&self, p: usize) -> Result<Self, Error> {
+        if p == 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let e = if self.is_zero() { EXPONENT_MIN } else { self.e };
+
+        let mut ret = Self::from_word(1, p)?;
+
+        // 2^(e - p) = 0.1 * 2^(e - p + 1)
+        let t = e as isize - p as isize + 1;
+        if t >= EXPONENT_MIN as isize {
+            ret.e = t as Exponent;
+        } else {
+            // subnormal: the shift is smaller than p.
+            let shift = (EXPONENT_MIN as isize - t) as usize;
+            ret.m.shift_right(shift);
+            ret.m.set_bit_len(ret.m.max_bit_len() - shift);
+            ret.e = EXPONENT_MIN;
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the distance between `self` and `d2` in units in the last place,
+    /// i.e. the number of steps between `self` and `d2` in the ordered set of numbers
+    /// with precision equal to the larger of the mantissa lengths of `self` and `d2`.
+    /// Positive and negative zero are at the distance 0. The inexact flags are ignored.
+    /// The distance is saturated at u128::MAX.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is too large.
+    pub fn ulp_diff(&self, d2: &Self) -> Result<u128, Error> {
+        let n = self.mantissa_max_bit_len().max(d2.mantissa_max_bit_len());
+
+        let o1 = self.ulp_ordinal(n)?;
+        let o2 = d2.ulp_ordinal(n)?;
+        let d = o1.sub_full_prec(&o2)?;
+
+        Ok(d.int_abs_as_u128().unwrap_or(u128::MAX))
+    }
+
+    // Returns the position of `|self|` in the ordered set of non-negative numbers with precision `n`
+    // as an integer with the sign of `self`. `n` must not be smaller than the mantissa length of `self`.
+    fn ulp_ordinal(&self, n: usize) -> Result<Self, Error> {
+        if n + EXPONENT_BIT_SIZE + 2 > EXPONENT_MAX as usize {
+            return Err(Error::InvalidArgument);
+        }
+
+        if self.is_zero() {
+            return Self::new(n);
+        }
+
+        let mut x = self.clone()?;
+        x.set_precision(n, RoundingMode::None)?;
+
+        // the mantissa as an integer
+        let mut ret = Self::from_words(x.m.digits(), self.s, n as Exponent)?;
+
+        // each binade above the minimum exponent contains 2^(n-1) numbers.
+        let k = self.e as i64 - EXPONENT_MIN as i64;
+        if k > 0 {
+            let mut b = Self::from_u64(k as u64, 64)?;
+            b.e += (n - 1) as Exponent;
+            b.s = self.s;
+            ret = ret.add_full_prec(&b)?;
+        }
+
+        Ok(ret)
+    }
+
     /// Returns the rounded number with `n` binary positions in the fractional part of the number using rounding mode `rm`.
     ///
     /// ## Errors
@@ -1314,7 +1904,7 @@ impl BigFloatNumber {
     pub fn random_normal(p: usize, exp_from: Exponent, exp_to: Exponent) -> Result<Self, Error> {
         Self::p_assertion(p)?;
 
-        #[cfg(target_arch = "x86")]
+        #[cfg(any(target_arch = "x86", feature = "exp64"))]
         if exp_from < EXPONENT_MIN || exp_to > EXPONENT_MAX {
             return Err(Error::InvalidArgument);
         }
@@ -1340,6 +1930,7 @@ impl BigFloatNumber {
     /// ## Errors
     ///
     ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    #[allow(clippy::should_implement_trait)]
     pub fn clone(&self) -> Result<Self, Error> {
         Ok(BigFloatNumber {
             e: self.e,
@@ -1361,6 +1952,68 @@ impl BigFloatNumber {
             .map(|_| {})
     }
 
+    /// Rounds `self` to precision `p` using the rounding mode `rm`, given `self` was computed with `RoundingMode::None`.
+    /// The inexact flag of `self` is treated as a sticky bit located below the least significant bit of the mantissa,
+    /// i.e. the exact value is assumed to be strictly greater in magnitude than `self` if `self` is inexact.
+    /// The result of the basic arithmetic operation on exact numbers computed with `RoundingMode::None` and then rounded with this function
+    /// is correctly rounded if `p` is smaller than the precision of `self`, or if `p` equals the precision of `self` and `rm`
+    /// is not rounding to nearest. A zero is not modified.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: rounding causes exponent overflow.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn reround(&mut self, p: usize, rm: RoundingMode) -> Result<(), Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.inexact && !self.is_zero() {
+            self.m
+                .set_length(self.mantissa_max_bit_len().max(p) + WORD_BIT_SIZE)?;
+            self.m.digits_mut()[0] |= 1;
+        }
+
+        self.set_precision(p, rm)
+    }
+
+    /// Reduces the memory used by `self`. If the precision of `self` is larger than `p`,
+    /// the number is rounded to precision `p` using the rounding mode `rm`.
+    /// Then the least significant words of the mantissa containing zeroes are removed without loss of precision,
+    /// and unused memory is released.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: rounding causes exponent overflow.
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn compress_to(&mut self, p: usize, rm: RoundingMode) -> Result<(), Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if p == 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        if self.mantissa_max_bit_len() > p {
+            self.set_precision(p, rm)?;
+        }
+
+        let tz = self.m.digits().iter().take_while(|v| **v == 0).count();
+        let p_used = (self.m.len() - tz).max(1) * WORD_BIT_SIZE;
+
+        if p_used < self.mantissa_max_bit_len() {
+            // the discarded words are zero
+            self.set_precision(p_used, RoundingMode::None)?;
+        }
+
+        self.m.shrink_to_fit();
+
+        Ok(())
+    }
+
     /// Try to round and then set the precision to `p`, given `self` has `s` correct digits in mantissa.
     /// Returns true if rounding succeeded. If the fuction returns `false`, `self` is still modified, and should be discarded.
     ///
@@ -1401,6 +2054,13 @@ impl BigFloatNumber {
             self.inexact |= inexact;
 
             if check_roundable && self.inexact {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    p,
+                    p_wrk = s,
+                    "rounding is not possible, precision escalation required"
+                );
+
                 return Ok(false);
             }
 
@@ -1520,7 +2180,7 @@ impl BigFloatNumber {
                 shift += 1;
             }
 
-            let mut words = [0; (SZ + WORD_BIT_SIZE - 1) / WORD_BIT_SIZE];
+            let mut words = [0; SZ.div_ceil(WORD_BIT_SIZE)];
             for w in &mut words {
                 *w = v as Word;
                 v >>= WORD_BIT_SIZE;
@@ -1749,6 +2409,13 @@ mod tests {
         d3.inv_sign();
         assert!(d3.abs_cmp(&d1) == 0);
 
+        // abs cmp with zero
+        d3 = BigFloatNumber::new(1).unwrap();
+        d1.set_exponent(-123);
+        assert!(d1.abs_cmp(&d3) > 0);
+        assert!(d3.abs_cmp(&d1) < 0);
+        assert!(d1.cmp(&d3) != 0);
+
         // abs cmp subnormal
         d1 = BigFloatNumber::from_raw_parts(
             &[1, WORD_MAX, 1],
@@ -1791,6 +2458,72 @@ mod tests {
             }
         }
 
+        // exact conversion to f64
+        for _ in 0..10000 {
+            let p = (random::<usize>() % p_rng + p_min) * WORD_BIT_SIZE;
+
+            let f: f64 = f64::from_bits(random::<u64>());
+            if f.is_finite() {
+                d1 = BigFloatNumber::from_f64(p, f).unwrap();
+                assert!(d1.to_f64_exact().unwrap().to_bits() == f.to_bits());
+
+                d1.set_exponent(d1.exponent() + 1);
+                assert!(d1.to_f64_exact() == Some(f * 2.0) || !(f * 2.0).is_finite());
+            }
+        }
+
+        assert!(
+            BigFloatNumber::from_f64(p, -0.0)
+                .unwrap()
+                .to_f64_exact()
+                .unwrap()
+                == 0.0
+        );
+        assert!(BigFloatNumber::new2(p, Sign::Neg, false)
+            .unwrap()
+            .to_f64_exact()
+            .unwrap()
+            .is_sign_negative());
+        assert!(BigFloatNumber::max_value(p)
+            .unwrap()
+            .to_f64_exact()
+            .is_none());
+        assert!(BigFloatNumber::min_positive(p)
+            .unwrap()
+            .to_f64_exact()
+            .is_none());
+
+        // mantissa wider than 53 bits
+        d1 = BigFloatNumber::from_u64_internal((1 << 53) + 1, p).unwrap();
+        assert!(d1.to_f64_exact().is_none());
+        d1 = BigFloatNumber::from_u64_internal((1 << 54) + 4, p).unwrap();
+        assert!(d1.to_f64_exact() == Some(((1u64 << 54) + 4) as f64));
+        d1 = d1
+            .div(
+                &BigFloatNumber::from_word(3, p).unwrap(),
+                p,
+                RoundingMode::ToEven,
+            )
+            .unwrap();
+        assert!(d1.to_f64_exact().is_none());
+
+        // f64 subnormals
+        d1 = BigFloatNumber::from_f64(p, f64::from_bits(1)).unwrap();
+        d2 = BigFloatNumber::from_word(1, p).unwrap();
+        d2.set_exponent(-1073);
+        assert!(d1.cmp(&d2) == 0);
+        assert!(d1.to_f64() == f64::from_bits(1));
+        d1 = BigFloatNumber::from_f64(p, f64::MIN_POSITIVE).unwrap();
+        d1.set_exponent(d1.exponent() - 52);
+        assert!(d1.to_f64_exact() == Some(f64::from_bits(1)));
+        d1.set_exponent(d1.exponent() - 1);
+        assert!(d1.to_f64_exact().is_none());
+        d1 = BigFloatNumber::from_u64_internal(3, p).unwrap();
+        d1.set_exponent(-1072);
+        assert!(d1.to_f64_exact() == Some(f64::from_bits(3)));
+        d1.set_exponent(-1073);
+        assert!(d1.to_f64_exact().is_none());
+
         for _ in 0..1000 {
             let p = (random::<usize>() % p_rng + p_min) * WORD_BIT_SIZE;
 
@@ -2117,6 +2850,12 @@ mod tests {
             assert!(d1.cmp(&d4) == 0);
         }
 
+        // full prec with zero argument
+        let zero = BigFloatNumber::new(WORD_BIT_SIZE).unwrap();
+        assert!(zero.add_full_prec(&d1).unwrap().cmp(&d1) == 0);
+        assert!(d1.sub_full_prec(&zero).unwrap().cmp(&d1) == 0);
+        assert!(zero.sub_full_prec(&d1).unwrap().cmp(&d1.neg().unwrap()) == 0);
+
         // mul & div
         for i in 0..10000 {
             let p1 = (random::<usize>() % p_rng + p_min) * WORD_BIT_SIZE;
@@ -2907,6 +3646,36 @@ mod tests {
         assert_eq!(d1.precision(), WORD_BIT_SIZE * 2);
         assert_eq!(d1.mantissa_max_bit_len(), WORD_BIT_SIZE * 2);
         assert_eq!(d1.sign(), Sign::Pos);
+
+        // checked build from words
+        let d2 = BigFloatNumber::from_words_checked(&words, Sign::Pos, EXPONENT_MIN + 5).unwrap();
+        assert!(d2.cmp(&d1) == 0);
+        assert_eq!(d2.as_raw_parts(), d1.as_raw_parts());
+
+        let d1 = BigFloatNumber::from_words_checked(&[0, 0], Sign::Neg, 0).unwrap();
+        assert!(d1.is_zero());
+        assert_eq!(d1.mantissa_max_bit_len(), WORD_BIT_SIZE * 2);
+        assert_eq!(d1.sign(), Sign::Neg);
+
+        let d1 = BigFloatNumber::from_words_checked(&[3, 1], Sign::Neg, EXPONENT_MIN).unwrap();
+        assert_eq!(d1.mantissa().digits(), [3, 1]);
+        assert_eq!(d1.exponent(), EXPONENT_MIN);
+        assert_eq!(d1.precision(), WORD_BIT_SIZE + 1);
+        assert!(d1.is_subnormal());
+
+        for (m, e) in [
+            (&[][..], 0),
+            (&[0, 0][..], 1),
+            (&[0][..], EXPONENT_MIN),
+            (&[3, 1][..], 0),
+            (&[3, 1][..], EXPONENT_MIN + 5),
+            (&[3, 1][..], EXPONENT_MAX),
+        ] {
+            assert_eq!(
+                BigFloatNumber::from_words_checked(m, Sign::Pos, e).unwrap_err(),
+                Error::InvalidArgument
+            );
+        }
     }
 
     fn random_f64() -> f64 {
@@ -3719,4 +4488,481 @@ mod tests {
             println!("rem {}", time.as_millis());
         }
     }
+
+    #[test]
+    fn test_reround() {
+        let rms = [
+            RoundingMode::Up,
+            RoundingMode::Down,
+            RoundingMode::ToZero,
+            RoundingMode::FromZero,
+            RoundingMode::ToEven,
+            RoundingMode::ToOdd,
+        ];
+
+        fn sqrt(
+            d1: &BigFloatNumber,
+            _d2: &BigFloatNumber,
+            p: usize,
+            rm: RoundingMode,
+        ) -> Result<BigFloatNumber, Error> {
+            d1.abs()?.sqrt(p, rm)
+        }
+
+        // the result of the basic operation with RoundingMode::None is truncated,
+        // and the discarded bits are retained in the inexact flag
+        for _ in 0..1000 {
+            let p1 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+            let p2 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+            let p = (random::<usize>() % 3 + 2) * WORD_BIT_SIZE;
+
+            let d1 = BigFloatNumber::random_normal(p1, -100, 100).unwrap();
+            let d2 = if random::<u8>() & 1 == 0 {
+                BigFloatNumber::random_normal(p2, -100, 100).unwrap()
+            } else {
+                // cancellation
+                let mut d2 = d1.clone().unwrap();
+                d2.set_precision(p2, RoundingMode::ToEven).unwrap();
+                d2.set_inexact(false);
+                d2
+            };
+
+            for op in [
+                BigFloatNumber::add,
+                BigFloatNumber::sub,
+                BigFloatNumber::mul,
+                BigFloatNumber::div,
+                sqrt,
+            ] {
+                let d3 = op(&d1, &d2, p, RoundingMode::None).unwrap();
+                assert!(d3.is_zero() || d3.precision() == p);
+
+                for rm in rms {
+                    let mut d4 = d3.clone().unwrap();
+                    d4.reround(p - WORD_BIT_SIZE, rm).unwrap();
+                    let d5 = op(&d1, &d2, p - WORD_BIT_SIZE, rm).unwrap();
+                    assert!(d4.cmp(&d5) == 0);
+                    assert!(d4.inexact() == d5.inexact());
+
+                    if rm != RoundingMode::ToEven && rm != RoundingMode::ToOdd {
+                        let mut d4 = d3.clone().unwrap();
+                        d4.reround(p, rm).unwrap();
+                        let d5 = op(&d1, &d2, p, rm).unwrap();
+                        assert!(d4.cmp(&d5) == 0);
+                        assert!(d4.inexact() == d5.inexact());
+                    }
+                }
+            }
+        }
+
+        // tie is resolved using the inexact flag
+        let mut d1 = BigFloatNumber::from_words(
+            &[WORD_SIGNIFICANT_BIT, 2, WORD_SIGNIFICANT_BIT],
+            Sign::Pos,
+            1,
+        )
+        .unwrap();
+        let mut d2 = d1.clone().unwrap();
+        d2.set_precision(WORD_BIT_SIZE * 2, RoundingMode::ToEven)
+            .unwrap();
+        assert!(d2.cmp(&d1) < 0);
+
+        d1.set_inexact(true);
+        let mut d2 = d1.clone().unwrap();
+        d2.reround(WORD_BIT_SIZE * 2, RoundingMode::ToEven).unwrap();
+        assert!(d2.cmp(&d1) > 0);
+        assert!(d2.inexact());
+
+        let mut d2 = d1.clone().unwrap();
+        d2.reround(WORD_BIT_SIZE * 3, RoundingMode::Up).unwrap();
+        assert!(d2.cmp(&d1) > 0);
+        let mut d2 = d1.clone().unwrap();
+        d2.reround(WORD_BIT_SIZE * 3, RoundingMode::Down).unwrap();
+        assert!(d2.cmp(&d1) == 0);
+
+        // subnormal and zero
+        let d1 = random_subnormal(WORD_BIT_SIZE * 2);
+        let d2 = BigFloatNumber::from_word(3, WORD_BIT_SIZE).unwrap();
+        let d3 = d1.div(&d2, WORD_BIT_SIZE * 2, RoundingMode::None).unwrap();
+        for rm in rms {
+            let mut d4 = d3.clone().unwrap();
+            d4.reround(WORD_BIT_SIZE, rm).unwrap();
+            let d5 = d1.div(&d2, WORD_BIT_SIZE, rm).unwrap();
+            assert!(d4.cmp(&d5) == 0);
+        }
+
+        let mut d1 = BigFloatNumber::new2(WORD_BIT_SIZE * 2, Sign::Neg, true).unwrap();
+        d1.reround(WORD_BIT_SIZE, RoundingMode::Down).unwrap();
+        assert!(d1.is_zero() && d1.is_negative() && d1.inexact());
+    }
+
+    #[test]
+    fn test_compress_to() {
+        let rm = RoundingMode::ToEven;
+
+        // rounding
+        let d1 = BigFloatNumber::random_normal(WORD_BIT_SIZE * 4, -10, 10).unwrap();
+        let mut d2 = d1.clone().unwrap();
+        d2.compress_to(WORD_BIT_SIZE * 2, rm).unwrap();
+        let mut d3 = d1.clone().unwrap();
+        d3.set_precision(WORD_BIT_SIZE * 2, rm).unwrap();
+        assert!(d2.cmp(&d3) == 0);
+        assert!(d2.mantissa_max_bit_len() <= WORD_BIT_SIZE * 2);
+
+        // trailing zero words are removed
+        let mut d1 = BigFloatNumber::from_word(3, WORD_BIT_SIZE * 4).unwrap();
+        d1.compress_to(WORD_BIT_SIZE * 8, rm).unwrap();
+        assert_eq!(d1.mantissa_max_bit_len(), WORD_BIT_SIZE);
+        assert!(d1.cmp(&BigFloatNumber::from_word(3, 1).unwrap()) == 0);
+        assert!(!d1.inexact());
+
+        let mut d1 =
+            BigFloatNumber::from_words(&[0, 1, 0, WORD_SIGNIFICANT_BIT], Sign::Neg, 3).unwrap();
+        let d2 = d1.clone().unwrap();
+        d1.compress_to(WORD_BIT_SIZE * 4, rm).unwrap();
+        assert_eq!(d1.mantissa_max_bit_len(), WORD_BIT_SIZE * 3);
+        assert!(d1.cmp(&d2) == 0);
+
+        // subnormal
+        let d1 = random_subnormal(WORD_BIT_SIZE * 3);
+        let mut d2 = d1.clone().unwrap();
+        d2.compress_to(WORD_BIT_SIZE * 3, rm).unwrap();
+        assert!(d2.cmp(&d1) == 0);
+        assert!(d2.precision() <= d1.precision());
+
+        // zero
+        let mut d1 = BigFloatNumber::new2(WORD_BIT_SIZE * 4, Sign::Neg, false).unwrap();
+        d1.compress_to(WORD_BIT_SIZE * 2, rm).unwrap();
+        assert!(d1.is_zero() && d1.is_negative());
+        assert_eq!(d1.mantissa_max_bit_len(), WORD_BIT_SIZE);
+
+        // overflow
+        let mut d1 = BigFloatNumber::max_value(WORD_BIT_SIZE * 2).unwrap();
+        assert_eq!(
+            d1.compress_to(WORD_BIT_SIZE, RoundingMode::Up).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+    }
+
+    #[test]
+    fn test_assign_ext() {
+        let p = 256;
+        let rm = RoundingMode::ToEven;
+
+        for _ in 0..100 {
+            let d1 = BigFloatNumber::random_normal(p, -100, 100).unwrap();
+            let d2 = BigFloatNumber::random_normal(p, -100, 100).unwrap();
+
+            let mut d3 = d1.clone().unwrap();
+            d3.add_assign_ext(&d2, p, rm).unwrap();
+            assert!(d3.cmp(&d1.add(&d2, p, rm).unwrap()) == 0);
+
+            let mut d3 = d1.clone().unwrap();
+            d3.sub_assign_ext(&d2, p, rm).unwrap();
+            assert!(d3.cmp(&d1.sub(&d2, p, rm).unwrap()) == 0);
+
+            let mut d3 = d1.clone().unwrap();
+            d3.mul_assign_ext(&d2, p, rm).unwrap();
+            assert!(d3.cmp(&d1.mul(&d2, p, rm).unwrap()) == 0);
+
+            let mut d3 = d1.clone().unwrap();
+            d3.div_assign_ext(&d2, p, rm).unwrap();
+            assert!(d3.cmp(&d1.div(&d2, p, rm).unwrap()) == 0);
+        }
+
+        // self is unchanged on error
+        let d1 = BigFloatNumber::from_word(3, p).unwrap();
+        let mut d3 = d1.clone().unwrap();
+        assert!(d3
+            .div_assign_ext(&BigFloatNumber::new(p).unwrap(), p, rm)
+            .is_err());
+        assert!(d3.cmp(&d1) == 0);
+
+        // in the steady state the mantissas of the results are reused
+        #[cfg(feature = "std")]
+        {
+            let d2 = BigFloatNumber::from_word(1, p).unwrap();
+            let mut d3 = BigFloatNumber::from_word(1, p).unwrap();
+            d3.add_assign_ext(&d2, p, rm).unwrap();
+            let m1 = d3.mantissa().digits().as_ptr();
+            d3.add_assign_ext(&d2, p, rm).unwrap();
+            d3.add_assign_ext(&d2, p, rm).unwrap();
+            assert!(d3.mantissa().digits().as_ptr() == m1);
+            assert!(d3.cmp(&BigFloatNumber::from_word(4, p).unwrap()) == 0);
+        }
+    }
+
+    #[test]
+    fn test_two_sum() {
+        for rm in [
+            RoundingMode::ToEven,
+            RoundingMode::ToOdd,
+            RoundingMode::Up,
+            RoundingMode::Down,
+            RoundingMode::ToZero,
+            RoundingMode::FromZero,
+            RoundingMode::None,
+        ] {
+            for _ in 0..100 {
+                let p = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+                let p1 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+                let p2 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+                let d1 = BigFloatNumber::random_normal(p1, -300, 300).unwrap();
+                let d2 = BigFloatNumber::random_normal(p2, -300, 300).unwrap();
+
+                let (s, r) = d1.two_sum(&d2, p, rm).unwrap();
+
+                // the sum is rounded as in add, and the residual recovers the exact sum
+                assert!(s.cmp(&d1.add(&d2, p, rm).unwrap()) == 0);
+                let d3 = d1.add_full_prec(&d2).unwrap();
+                assert!(s.add_full_prec(&r).unwrap().cmp(&d3) == 0);
+                assert!(!r.inexact());
+
+                // the residual is smaller than a unit in the last place of the sum
+                if !r.is_zero() && !s.is_zero() {
+                    assert!((r.exponent() as isize) <= s.exponent() as isize - p as isize);
+                }
+            }
+        }
+
+        let p = WORD_BIT_SIZE;
+        let rm = RoundingMode::ToEven;
+
+        // exact sum
+        let d1 = BigFloatNumber::from_word(3, p).unwrap();
+        let d2 = BigFloatNumber::from_word(5, p).unwrap();
+        let (s, r) = d1.two_sum(&d2, p, rm).unwrap();
+        assert!(s.cmp(&BigFloatNumber::from_word(8, p).unwrap()) == 0);
+        assert!(r.is_zero() && !s.inexact());
+
+        // exponents are far apart
+        let mut d2 = BigFloatNumber::from_word(1, p).unwrap();
+        d2.set_exponent(-100000);
+        let d2 = d2.neg().unwrap();
+        let (s, r) = d1.two_sum(&d2, p, rm).unwrap();
+        assert!(s.cmp(&d1) == 0 && s.inexact());
+        assert!(r.cmp(&d2) == 0);
+
+        // subnormal
+        let d1 = random_subnormal(p);
+        let d2 = random_subnormal(p);
+        let (s, r) = d1.two_sum(&d2, p, rm).unwrap();
+        let d3 = d1.add_full_prec(&d2).unwrap();
+        assert!(s.add_full_prec(&r).unwrap().cmp(&d3) == 0);
+
+        // overflow
+        let d1 = BigFloatNumber::max_value(p).unwrap();
+        assert_eq!(
+            d1.two_sum(&d1, p, rm).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+    }
+
+    #[test]
+    fn test_remquo() {
+        let p = 128;
+        let num = |f: f64| BigFloatNumber::from_f64(p, f).unwrap();
+
+        // x, y, r, q
+        let vals = [
+            (7.5, 2.0, 1.5, 3),
+            (-7.5, 2.0, -1.5, -3),
+            (7.5, -2.0, 1.5, -3),
+            (-7.5, -2.0, -1.5, 3),
+            (1.0, 3.0, 1.0, 0),
+            (6.0, 1.5, 0.0, 4),
+            (0.0, 5.0, 0.0, 0),
+        ];
+
+        for (x, y, r, q) in vals {
+            let (r2, q2) = num(x).remquo(&num(y)).unwrap();
+            assert!(r2.cmp(&num(r)) == 0);
+            assert_eq!(q2, q);
+        }
+
+        for _ in 0..100 {
+            let p1 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+            let p2 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+            let x = BigFloatNumber::random_normal(p1, -20, 40).unwrap();
+            let y = BigFloatNumber::random_normal(p2, -20, 20).unwrap();
+
+            let (r, q) = x.remquo(&y).unwrap();
+            assert!(r.cmp(&x.rem(&y).unwrap()) == 0);
+
+            // the quotient is exact, since it is smaller than 2^61
+            let n = x
+                .abs()
+                .unwrap()
+                .sub_full_prec(&r.abs().unwrap())
+                .unwrap()
+                .div(&y.abs().unwrap(), 128, RoundingMode::ToEven)
+                .unwrap();
+            assert!(n.is_int());
+            assert_eq!(n.int_abs_as_u128().unwrap() as i64, q.abs());
+            assert!(q == 0 || (q > 0) == (x.sign() == y.sign()));
+        }
+
+        // only the low bits of a large quotient
+        let mut x = num(1.0);
+        x.set_exponent(101);
+        let x = x.add(&num(13.0), p, RoundingMode::None).unwrap();
+        let (r, q) = x.remquo(&num(1.0)).unwrap();
+        assert!(r.is_zero());
+        assert_eq!(q, 13);
+
+        let (r, q) = x.remquo(&num(-4.0)).unwrap();
+        assert!(r.cmp(&num(1.0)) == 0);
+        assert_eq!(q, -3);
+
+        // the divisor is close to the maximum value
+        let y = BigFloatNumber::max_value(p).unwrap();
+        let mut x = y.clone().unwrap();
+        x.set_exponent(EXPONENT_MAX - 1);
+        let (_, q) = y.remquo(&x).unwrap();
+        assert_eq!(q, 2);
+
+        // subnormal divisor
+        let y = BigFloatNumber::min_positive(p).unwrap();
+        let x = y.mul(&num(5.0), p, RoundingMode::ToEven).unwrap();
+        let (r, q) = x.remquo(&y).unwrap();
+        assert!(r.is_zero());
+        assert_eq!(q, 5);
+
+        assert_eq!(
+            x.remquo(&BigFloatNumber::new(p).unwrap()).unwrap_err(),
+            Error::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_to_float_round() {
+        let p = 128;
+        let num = |f: f64| BigFloatNumber::from_f64(p, f).unwrap();
+        let tiny = |f: f64| {
+            let mut d = num(f);
+            d.set_exponent(d.exponent() - 100);
+            d
+        };
+
+        let rms = [
+            RoundingMode::ToEven,
+            RoundingMode::ToOdd,
+            RoundingMode::Up,
+            RoundingMode::Down,
+            RoundingMode::ToZero,
+            RoundingMode::FromZero,
+            RoundingMode::None,
+        ];
+
+        for _ in 0..1000 {
+            let f = f64::from_bits(random::<u64>());
+            if !f.is_finite() {
+                continue;
+            }
+
+            // exact values
+            for rm in rms {
+                let (f2, inexact) = num(f).to_f64_round(rm).unwrap();
+                assert_eq!(f2.to_bits(), f.to_bits());
+                assert!(!inexact);
+            }
+
+            if f == 0.0 || f.abs() == f64::MAX {
+                continue;
+            }
+
+            // values between f and the next f64 away from zero
+            let next = f64::from_bits(f.to_bits() + 1);
+            let d1 = num(f).add(&tiny(f), p, RoundingMode::None).unwrap();
+            for (rm, f3) in [
+                (RoundingMode::ToEven, f),
+                (RoundingMode::ToZero, f),
+                (RoundingMode::None, f),
+                (RoundingMode::FromZero, next),
+                (RoundingMode::Up, if f > 0.0 { next } else { f }),
+                (RoundingMode::Down, if f > 0.0 { f } else { next }),
+            ] {
+                let (f2, inexact) = d1.to_f64_round(rm).unwrap();
+                assert_eq!(f2.to_bits(), f3.to_bits());
+                assert!(inexact);
+            }
+        }
+
+        // ties
+        let half_ulp = |f: f64| {
+            let mut d = num(f64::EPSILON);
+            d.set_exponent(d.exponent() - 1);
+            num(f).mul(&d, p, RoundingMode::None).unwrap()
+        };
+        let d1 = num(1.0).add(&half_ulp(1.0), p, RoundingMode::None).unwrap();
+        assert_eq!(d1.to_f64_round(RoundingMode::ToEven).unwrap(), (1.0, true));
+        assert_eq!(
+            d1.to_f64_round(RoundingMode::ToOdd).unwrap(),
+            (1.0 + f64::EPSILON, true)
+        );
+
+        // subnormal f64
+        let mut d1 = num(f64::from_bits(1));
+        d1.set_exponent(d1.exponent() - 1);
+        assert_eq!(d1.to_f64_round(RoundingMode::ToEven).unwrap(), (0.0, true));
+        assert_eq!(
+            d1.to_f64_round(RoundingMode::Up).unwrap(),
+            (f64::from_bits(1), true)
+        );
+        d1.set_sign(Sign::Neg);
+        let (f, inexact) = d1.to_f64_round(RoundingMode::Up).unwrap();
+        assert!(f == 0.0 && f.is_sign_negative() && inexact);
+
+        let d1 = BigFloatNumber::min_positive(p).unwrap();
+        assert_eq!(d1.to_f64_round(RoundingMode::Down).unwrap(), (0.0, true));
+        assert_eq!(
+            d1.to_f64_round(RoundingMode::FromZero).unwrap(),
+            (f64::from_bits(1), true)
+        );
+
+        // overflow
+        let d1 = num(f64::MAX)
+            .add(&half_ulp(f64::MAX), p, RoundingMode::None)
+            .unwrap();
+        assert_eq!(
+            d1.to_f64_round(RoundingMode::ToEven).unwrap(),
+            (f64::INFINITY, true)
+        );
+        assert_eq!(
+            d1.to_f64_round(RoundingMode::ToZero).unwrap(),
+            (f64::MAX, true)
+        );
+        let d1 = BigFloatNumber::min_value(p).unwrap();
+        assert_eq!(
+            d1.to_f64_round(RoundingMode::Up).unwrap(),
+            (-f64::MAX, true)
+        );
+        assert_eq!(
+            d1.to_f64_round(RoundingMode::Down).unwrap(),
+            (f64::NEG_INFINITY, true)
+        );
+
+        // f32
+        let d1 = num(f32::MAX as f64)
+            .add(&num(1.0), p, RoundingMode::None)
+            .unwrap();
+        assert_eq!(
+            d1.to_f32_round(RoundingMode::Up).unwrap(),
+            (f32::INFINITY, true)
+        );
+        assert_eq!(
+            d1.to_f32_round(RoundingMode::ToEven).unwrap(),
+            (f32::MAX, true)
+        );
+        let d1 = num(f32::from_bits(3) as f64);
+        assert_eq!(
+            d1.to_f32_round(RoundingMode::Down).unwrap(),
+            (f32::from_bits(3), false)
+        );
+        let d1 = num(0.1);
+        let (f, inexact) = d1.to_f32_round(RoundingMode::Down).unwrap();
+        assert!((f as f64) < 0.1 && inexact);
+        let (f2, _) = d1.to_f32_round(RoundingMode::Up).unwrap();
+        assert_eq!(f32::from_bits(f.to_bits() + 1), f2);
+    }
 }
@@ -0,0 +1,624 @@
+//! Conversion between radians and degrees, normalization of angles, and reduction of angles modulo pi/2.
+
+use crate::common::util::round_p;
+use crate::defs::AngleRange;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Converts an angle `self` in radians to degrees with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// The result `self * 180 / pi` is computed using a single rounding.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn to_degrees(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        self.convert_angle(true, p, rm, cc)
+    }
+
+    /// Converts an angle `self` in degrees to radians with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// The result `self * pi / 180` is computed using a single rounding.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn to_radians(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        self.convert_angle(false, p, rm, cc)
+    }
+
+    /// Normalizes an angle `self` in radians to the range `range` with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// The period 2*pi is taken with the precision sufficient for reducing `self` with a single rounding of the result.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn normalize_angle(
+        &self,
+        range: AngleRange,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            return Self::new2(p, self.sign(), self.inexact());
+        }
+
+        if self.is_in_angle_range(range, cc)? {
+            let mut ret = self.clone()?;
+            ret.set_precision(p, rm)?;
+            return Ok(ret);
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut x = self.clone()?;
+            x.set_precision(p_x.max(self.mantissa_max_bit_len()), RoundingMode::None)?;
+
+            // the reduction error is less than 2^(1 - p_x)
+            let mut ret = x.reduce_trig_arg(cc, RoundingMode::None)?;
+
+            let mut pi = cc.pi_num(p_x, RoundingMode::None)?;
+            let mut two_pi = pi.clone()?;
+            two_pi.set_exponent(pi.exponent() + 1);
+
+            match range {
+                AngleRange::ZeroToTwoPi => {
+                    if ret.is_negative() {
+                        ret = ret.add(&two_pi, p_x, RoundingMode::None)?;
+                    }
+                }
+                AngleRange::MinusPiToPi => {
+                    if ret.abs_cmp(&pi) > 0 {
+                        two_pi.set_sign(ret.sign());
+                        ret = ret.sub(&two_pi, p_x, RoundingMode::None)?;
+                    }
+                }
+            }
+
+            // the error of the result is less than 2^(5 - p_x)
+            if !ret.is_zero() {
+                let bnd = match range {
+                    AngleRange::ZeroToTwoPi => two_pi.abs()?,
+                    AngleRange::MinusPiToPi => {
+                        pi.set_sign(ret.sign());
+                        pi
+                    }
+                };
+
+                // the result is not too close to the boundary of the range
+                let dist = bnd.sub(&ret, p_x, RoundingMode::None)?;
+                let s = p_x as isize + ret.exponent() as isize - 6;
+
+                // the roundability check requires the number of correct bits to be aligned to the word size
+                let s = s / WORD_BIT_SIZE as isize * WORD_BIT_SIZE as isize;
+
+                if !dist.is_zero() && dist.exponent() as isize > 7 - p_x as isize {
+                    if s >= (p + WORD_BIT_SIZE) as isize {
+                        ret.set_inexact(true);
+                        if ret.try_set_precision(p, rm, s as usize)? {
+                            ret.set_inexact(ret.inexact() | self.inexact());
+                            break Ok(ret);
+                        }
+                    }
+
+                    p_wrk += p_inc.max((p as isize - s).max(0) as usize);
+                    p_inc = round_p(p_wrk / 5);
+                    continue;
+                }
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    /// Reduces an angle `self` in radians modulo pi/2 with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// Returns the reduced argument `r` and the quadrant `q` such that `self = k*pi/2 + r`, where `k` is the integer closest to `self / (pi/2)`,
+    /// and `q = k mod 4` is in the range from 0 to 3. The reduced argument is in the range [-pi/4, pi/4] up to the rounding error of `k`.
+    /// The value pi/2 is taken with the precision sufficient for reducing `self` with a single rounding of the result.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn rem_pio2(
+        &self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<(Self, usize), Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() || self.exponent() < 0 {
+            let mut ret = self.clone()?;
+            ret.set_precision(p, rm)?;
+            return Ok((ret, 0));
+        }
+
+        let four = Self::from_word(4, WORD_BIT_SIZE)?;
+
+        // the integer part of self / (pi/2) has at most e + 1 bits
+        let e = self.exponent() as usize;
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p + p_inc;
+
+        loop {
+            let p_x = p_wrk + e + WORD_BIT_SIZE;
+
+            let mut pio2 = cc.pi_num(p_x, RoundingMode::None)?;
+            pio2.set_exponent(pio2.exponent() - 1);
+
+            let k = self
+                .div(&pio2, p_x, RoundingMode::None)?
+                .round_int(RoundingMode::ToEven)?;
+
+            if k.is_zero() {
+                let mut ret = self.clone()?;
+                ret.set_precision(p, rm)?;
+                return Ok((ret, 0));
+            }
+
+            // the remainder is an integer in the range (-4, 4)
+            let q = match k.rem(&four)?.to_i128() {
+                Some(q) => q.rem_euclid(4) as usize,
+                None => unreachable!(),
+            };
+
+            // the error of k * pi/2 is less than 2^(e + 2 - p_x)
+            let mut ret = self.sub(&k.mul_full_prec(&pio2)?, p_x, RoundingMode::None)?;
+
+            if !ret.is_zero() {
+                let s = p_x as isize - e as isize + ret.exponent() as isize - 3;
+
+                // the roundability check requires the number of correct bits to be aligned to the word size
+                let s = s / WORD_BIT_SIZE as isize * WORD_BIT_SIZE as isize;
+
+                if s >= (p + WORD_BIT_SIZE) as isize {
+                    ret.set_inexact(true);
+                    if ret.try_set_precision(p, rm, s as usize)? {
+                        break Ok((ret, q));
+                    }
+                }
+
+                p_wrk += p_inc.max((p as isize - s).max(0) as usize);
+                p_inc = round_p(p_wrk / 5);
+                continue;
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // Returns true if the angle `self` is certainly in the range `range`.
+    fn is_in_angle_range(&self, range: AngleRange, cc: &mut Consts) -> Result<bool, Error> {
+        if self.exponent() > 3 {
+            return Ok(false);
+        }
+
+        let mut pi = cc.pi_num(
+            self.mantissa_max_bit_len() + WORD_BIT_SIZE,
+            RoundingMode::Down,
+        )?;
+
+        Ok(match range {
+            AngleRange::ZeroToTwoPi => {
+                pi.set_exponent(pi.exponent() + 1);
+                self.is_positive() && self.cmp(&pi) < 0
+            }
+            AngleRange::MinusPiToPi => self.abs_cmp(&pi) <= 0,
+        })
+    }
+
+    // Computes self * 180 / pi if `to_deg` is true, or self * pi / 180 otherwise.
+    fn convert_angle(
+        &self,
+        to_deg: bool,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            return Self::new2(p, self.sign(), self.inexact());
+        }
+
+        let d180 = Self::from_word(180, WORD_BIT_SIZE)?;
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len()) + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let pi = cc.pi_num(p_x, RoundingMode::None)?;
+
+            let factor = if to_deg {
+                d180.div(&pi, p_x, RoundingMode::None)?
+            } else {
+                pi.div(&d180, p_x, RoundingMode::None)?
+            };
+
+            let mut ret = self.mul(&factor, p_x, RoundingMode::None)?;
+
+            if ret.is_zero() || ret.is_subnormal() {
+                // the result is too close to zero
+                let mut ret = self.mul(&factor, p, rm)?;
+                ret.set_inexact(true);
+                return Ok(ret);
+            }
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(true);
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::common::util::invert_rm_for_sign;
+    use crate::common::util::random_subnormal;
+    use crate::defs::EXPONENT_MAX;
+    use crate::Exponent;
+    use crate::Sign;
+
+    #[test]
+    fn test_angle() {
+        let p = 320;
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        // pi radians = 180 degrees
+        let pi = cc.pi_num(p, rm).unwrap();
+        let d1 = pi.to_degrees(p, rm, &mut cc).unwrap();
+        assert!(d1.cmp(&BigFloatNumber::from_word(180, p).unwrap()) == 0);
+        assert!(d1.inexact());
+
+        let d1 = BigFloatNumber::from_word(90, p).unwrap();
+        let mut d2 = pi.clone().unwrap();
+        d2.set_exponent(1);
+        assert!(d1.to_radians(p, rm, &mut cc).unwrap().cmp(&d2) == 0);
+
+        // directed rounding
+        for rm in [RoundingMode::Up, RoundingMode::Down] {
+            let d1 = BigFloatNumber::from_word(180, p).unwrap().neg().unwrap();
+            let d2 = d1.to_radians(p, rm, &mut cc).unwrap();
+            let d3 = cc.pi_num(p, invert_rm_for_sign(rm)).unwrap();
+            assert!(d2.cmp(&d3.neg().unwrap()) == 0);
+        }
+
+        // single rounding
+        for _ in 0..100 {
+            let d1 = BigFloatNumber::random_normal(p, -10, 10).unwrap();
+            let d2 = d1.to_degrees(p, rm, &mut cc).unwrap();
+
+            let p_x = p * 2;
+            let mut d3 = d1
+                .mul(
+                    &BigFloatNumber::from_word(180, p).unwrap(),
+                    p_x,
+                    RoundingMode::None,
+                )
+                .unwrap()
+                .div(
+                    &cc.pi_num(p_x, RoundingMode::None).unwrap(),
+                    p_x,
+                    RoundingMode::None,
+                )
+                .unwrap();
+            d3.set_precision(p, rm).unwrap();
+            assert!(d2.cmp(&d3) == 0);
+
+            let d4 = d2.to_radians(p * 2, rm, &mut cc).unwrap();
+            let mut eps = d1.clone().unwrap();
+            eps.set_exponent(d1.exponent() - p as Exponent + 2);
+            assert!(d4.sub(&d1, p, rm).unwrap().abs_cmp(&eps) <= 0);
+        }
+
+        // zero, overflow, subnormal
+        let zero = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let d1 = zero.to_degrees(p, rm, &mut cc).unwrap();
+        assert!(d1.is_zero() && d1.is_negative() && !d1.inexact());
+
+        let d1 = BigFloatNumber::max_value(p).unwrap();
+        assert_eq!(
+            d1.to_degrees(p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(EXPONENT_MAX);
+        assert!(d1.to_radians(p, rm, &mut cc).unwrap().exponent() == EXPONENT_MAX - 6);
+
+        let d1 = random_subnormal(p);
+        let d2 = d1.to_radians(p, rm, &mut cc).unwrap();
+        assert!(d2.is_subnormal() || d2.is_zero());
+        assert!(d2.inexact());
+
+        let d2 = d1.to_degrees(p, rm, &mut cc).unwrap();
+        assert!(d2.abs_cmp(&d1) > 0);
+    }
+
+    // x - 2*pi*floor(x / (2*pi)) computed with a large precision
+    fn normalize_angle_ref(
+        x: &BigFloatNumber,
+        range: AngleRange,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> BigFloatNumber {
+        let p_x = p + x.mantissa_max_bit_len() + x.exponent().max(0) as usize + 256;
+        let mut two_pi = cc.pi_num(p_x, RoundingMode::None).unwrap();
+        two_pi.set_exponent(two_pi.exponent() + 1);
+
+        let mut y = x.clone().unwrap();
+        if range == AngleRange::MinusPiToPi {
+            // k = floor((x + pi) / (2*pi))
+            let mut pi = two_pi.clone().unwrap();
+            pi.set_exponent(pi.exponent() - 1);
+            y = y.add(&pi, p_x, RoundingMode::None).unwrap();
+        }
+
+        let k = y
+            .div(&two_pi, p_x, RoundingMode::None)
+            .unwrap()
+            .floor()
+            .unwrap();
+        let mut ret = x
+            .sub(
+                &k.mul(&two_pi, p_x, RoundingMode::None).unwrap(),
+                p_x,
+                RoundingMode::None,
+            )
+            .unwrap();
+
+        ret.set_precision(p, rm).unwrap();
+        ret
+    }
+
+    #[test]
+    fn test_normalize_angle() {
+        let p = 320;
+        let mut cc = Consts::new().unwrap();
+
+        for _ in 0..100 {
+            let x = BigFloatNumber::random_normal(p, -5, 200).unwrap();
+
+            for range in [AngleRange::ZeroToTwoPi, AngleRange::MinusPiToPi] {
+                for rm in [RoundingMode::Up, RoundingMode::Down, RoundingMode::ToEven] {
+                    let d1 = x.normalize_angle(range, p, rm, &mut cc).unwrap();
+                    let d2 = normalize_angle_ref(&x, range, p, rm, &mut cc);
+                    assert!(d1.cmp(&d2) == 0);
+                }
+            }
+        }
+
+        // angles in the range are not changed
+        let rm = RoundingMode::ToEven;
+        let d1 = BigFloatNumber::from_word(3, p).unwrap().neg().unwrap();
+        let d2 = d1
+            .normalize_angle(AngleRange::MinusPiToPi, p, rm, &mut cc)
+            .unwrap();
+        assert!(d1.cmp(&d2) == 0 && !d2.inexact());
+        let d2 = d1
+            .normalize_angle(AngleRange::ZeroToTwoPi, p, rm, &mut cc)
+            .unwrap();
+        assert!(
+            d2.cmp(&normalize_angle_ref(
+                &d1,
+                AngleRange::ZeroToTwoPi,
+                p,
+                rm,
+                &mut cc
+            )) == 0
+                && d2.inexact()
+        );
+
+        // boundaries of the range
+        let pi_up = cc.pi_num(p, RoundingMode::Up).unwrap();
+        let pi_down = cc.pi_num(p, RoundingMode::Down).unwrap();
+
+        let d1 = pi_up
+            .normalize_angle(AngleRange::MinusPiToPi, p, rm, &mut cc)
+            .unwrap();
+        assert!(d1.is_negative());
+        assert!(
+            d1.cmp(&normalize_angle_ref(
+                &pi_up,
+                AngleRange::MinusPiToPi,
+                p,
+                rm,
+                &mut cc
+            )) == 0
+        );
+        let d1 = pi_down
+            .normalize_angle(AngleRange::MinusPiToPi, p, rm, &mut cc)
+            .unwrap();
+        assert!(d1.cmp(&pi_down) == 0);
+
+        let mut two_pi_up = pi_up.clone().unwrap();
+        two_pi_up.set_exponent(pi_up.exponent() + 1);
+        let d1 = two_pi_up
+            .normalize_angle(AngleRange::ZeroToTwoPi, p, rm, &mut cc)
+            .unwrap();
+        assert!(d1.is_positive() && d1.exponent() < -(p as Exponent) + 10);
+        assert!(
+            d1.cmp(&normalize_angle_ref(
+                &two_pi_up,
+                AngleRange::ZeroToTwoPi,
+                p,
+                rm,
+                &mut cc
+            )) == 0
+        );
+
+        // close to a multiple of 2*pi
+        let mut d1 = cc.pi_num(p, RoundingMode::Down).unwrap();
+        d1.set_exponent(100);
+        let d2 = d1
+            .normalize_angle(AngleRange::MinusPiToPi, p, rm, &mut cc)
+            .unwrap();
+        assert!(
+            d2.cmp(&normalize_angle_ref(
+                &d1,
+                AngleRange::MinusPiToPi,
+                p,
+                rm,
+                &mut cc
+            )) == 0
+        );
+
+        // zero and large arguments
+        let zero = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let d1 = zero
+            .normalize_angle(AngleRange::ZeroToTwoPi, p, rm, &mut cc)
+            .unwrap();
+        assert!(d1.is_zero() && !d1.inexact());
+
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(10000);
+        let d2 = d1
+            .normalize_angle(AngleRange::ZeroToTwoPi, p, rm, &mut cc)
+            .unwrap();
+        assert!(
+            d2.cmp(&normalize_angle_ref(
+                &d1,
+                AngleRange::ZeroToTwoPi,
+                p,
+                rm,
+                &mut cc
+            )) == 0
+        );
+    }
+
+    // x - k*pi/2, where k = round(x / (pi/2)), computed with a large precision
+    fn rem_pio2_ref(
+        x: &BigFloatNumber,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> (BigFloatNumber, usize) {
+        let p_x = p + x.mantissa_max_bit_len() + x.exponent().max(0) as usize + 256;
+        let mut pio2 = cc.pi_num(p_x, RoundingMode::None).unwrap();
+        pio2.set_exponent(pio2.exponent() - 1);
+
+        let k = x
+            .div(&pio2, p_x, RoundingMode::None)
+            .unwrap()
+            .round_int(RoundingMode::ToEven)
+            .unwrap();
+        let mut ret = x
+            .sub(&k.mul_full_prec(&pio2).unwrap(), p_x, RoundingMode::None)
+            .unwrap();
+        ret.set_precision(p, rm).unwrap();
+
+        let four = BigFloatNumber::from_word(4, p).unwrap();
+        let q = k.rem(&four).unwrap().to_i128().unwrap().rem_euclid(4) as usize;
+
+        (ret, q)
+    }
+
+    #[test]
+    fn test_rem_pio2() {
+        let p = 320;
+        let mut cc = Consts::new().unwrap();
+
+        for _ in 0..100 {
+            let x = BigFloatNumber::random_normal(p, -5, 200).unwrap();
+
+            for rm in [RoundingMode::Up, RoundingMode::Down, RoundingMode::ToEven] {
+                let (d1, q1) = x.rem_pio2(p, rm, &mut cc).unwrap();
+                let (d2, q2) = rem_pio2_ref(&x, p, rm, &mut cc);
+                assert!(d1.cmp(&d2) == 0);
+                assert_eq!(q1, q2);
+            }
+        }
+
+        let rm = RoundingMode::ToEven;
+
+        // quadrants of small multiples of pi/2
+        let pi = cc.pi_num(p, rm).unwrap();
+        for (i, q) in [(1, 1), (2, 2), (3, 3), (4, 0), (5, 1)] {
+            let mut d1 = pi
+                .mul(&BigFloatNumber::from_word(i, p).unwrap(), p, rm)
+                .unwrap();
+            d1.set_exponent(d1.exponent() - 1);
+            for s in [Sign::Pos, Sign::Neg] {
+                d1.set_sign(s);
+                let (d2, q2) = d1.rem_pio2(p, rm, &mut cc).unwrap();
+                assert_eq!(q2, if s == Sign::Neg { (4 - q) % 4 } else { q });
+                assert!(d2.exponent() < -(p as Exponent) + 10);
+            }
+        }
+
+        // sin of the argument from sin and cos of the reduced argument
+        let d1 = BigFloatNumber::from_word(1000, p).unwrap();
+        let (r, q) = d1.rem_pio2(p, rm, &mut cc).unwrap();
+        let d2 = match q {
+            0 => r.sin(p, rm, &mut cc).unwrap(),
+            1 => r.cos(p, rm, &mut cc).unwrap(),
+            2 => r.sin(p, rm, &mut cc).unwrap().neg().unwrap(),
+            _ => r.cos(p, rm, &mut cc).unwrap().neg().unwrap(),
+        };
+        assert!(d2.cmp(&d1.sin(p, rm, &mut cc).unwrap()) == 0);
+
+        // small arguments are not changed
+        let d1 = BigFloatNumber::from_word(3, p)
+            .unwrap()
+            .div(&BigFloatNumber::from_word(4, p).unwrap(), p, rm)
+            .unwrap();
+        let (d2, q) = d1.rem_pio2(p, rm, &mut cc).unwrap();
+        assert!(d1.cmp(&d2) == 0 && !d2.inexact() && q == 0);
+
+        // zero, subnormal, and large arguments
+        let zero = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let (d1, q) = zero.rem_pio2(p, rm, &mut cc).unwrap();
+        assert!(d1.is_zero() && d1.is_negative() && !d1.inexact() && q == 0);
+
+        let d1 = random_subnormal(p);
+        let (d2, q) = d1.rem_pio2(p, rm, &mut cc).unwrap();
+        assert!(d1.cmp(&d2) == 0 && q == 0);
+
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(10000);
+        let (d2, q2) = d1.rem_pio2(p, rm, &mut cc).unwrap();
+        let (d3, q3) = rem_pio2_ref(&d1, p, rm, &mut cc);
+        assert!(d2.cmp(&d3) == 0 && q2 == q3);
+    }
+}
@@ -5,6 +5,7 @@ use crate::common::consts::TWO;
 use crate::common::util::calc_add_cost;
 use crate::common::util::calc_mul_cost;
 use crate::common::util::calc_sqrt_cost;
+use crate::common::util::invert_rm_for_sign;
 use crate::common::util::round_p;
 use crate::defs::Error;
 use crate::defs::RoundingMode;
@@ -16,6 +17,7 @@ use crate::ops::series::ArgReductionEstimator;
 use crate::ops::series::PolycoeffGen;
 use crate::ops::util::compute_small_exp;
 use crate::Exponent;
+use crate::Sign;
 use crate::WORD_BIT_SIZE;
 
 // Polynomial coefficient generator.
@@ -132,6 +134,117 @@ impl BigFloatNumber {
         }
     }
 
+    /// Computes the arctangent of `self/x` with precision `p`, using the signs of `self` and `x` to determine the quadrant.
+    /// The result is in the range [-pi, pi]. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// Signed zeros are treated as in the C standard library: the result is ±0 if `self` is ±0 and `x` is +0 or positive,
+    /// and the result is ±pi if `self` is ±0 and `x` is -0 or negative.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn atan2(
+        &self,
+        x: &Self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            return if x.is_positive() {
+                Self::new2(p, self.sign(), self.inexact() | x.inexact())
+            } else {
+                Self::signed_pi(self.sign(), 0, p, rm, cc)
+            };
+        }
+
+        if x.is_zero() {
+            return Self::signed_pi(self.sign(), -1, p, rm, cc);
+        }
+
+        let inexact = self.inexact() | x.inexact();
+        let y_le_x = self.abs_cmp(x) <= 0;
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p
+            .max(self.mantissa_max_bit_len())
+            .max(x.mantissa_max_bit_len())
+            + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = if y_le_x {
+                let r = self.div(x, p_x, RoundingMode::None)?;
+
+                if x.is_positive() {
+                    if r.is_zero() || r.is_subnormal() {
+                        // atan(r) = r with the precision of the subnormal number
+                        let mut ret = self.div(x, p, rm)?;
+                        ret.set_inexact(true);
+                        return Ok(ret);
+                    }
+
+                    r.atan(p_x, RoundingMode::None, cc)?
+                } else {
+                    // atan(y/x) + pi * sign(y)
+                    let mut pi = cc.pi_num(p_x, RoundingMode::None)?;
+                    pi.set_sign(self.sign());
+
+                    pi.add(
+                        &r.atan(p_x, RoundingMode::None, cc)?,
+                        p_x,
+                        RoundingMode::None,
+                    )?
+                }
+            } else {
+                // pi/2 * sign(y) - atan(x/y)
+                let r = x.div(self, p_x, RoundingMode::None)?;
+
+                let mut half_pi = cc.pi_num(p_x, RoundingMode::None)?;
+                half_pi.set_exponent(1);
+                half_pi.set_sign(self.sign());
+
+                half_pi.sub(
+                    &r.atan(p_x, RoundingMode::None, cc)?,
+                    p_x,
+                    RoundingMode::None,
+                )?
+            };
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | inexact);
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // pi * 2^e with sign `s`.
+    pub(crate) fn signed_pi(
+        s: Sign,
+        e: Exponent,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let rm = if s == Sign::Neg { invert_rm_for_sign(rm) } else { rm };
+
+        let mut pi = cc.pi_num(p, rm)?;
+        pi.set_exponent(pi.exponent() + e);
+        pi.set_sign(s);
+
+        Ok(pi)
+    }
+
     /// arctan using series
     pub(super) fn atan_series(mut self, rm: RoundingMode) -> Result<Self, Error> {
         // atan:  x - x^3/3 + x^5/5 - x^7/7 + ...
@@ -196,6 +309,7 @@ mod tests {
     use crate::common::util::random_subnormal;
 
     use super::*;
+    use rand::random;
 
     #[test]
     fn test_arctan() {
@@ -273,6 +387,103 @@ mod tests {
         assert!(n1.atan(p, rm, &mut cc).unwrap().cmp(&n1) == 0);
     }
 
+    #[test]
+    fn test_atan2() {
+        let p = 320;
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        let mone = one.neg().unwrap();
+        let zero = BigFloatNumber::new(p).unwrap();
+        let mzero = zero.neg().unwrap();
+
+        let pi = cc.pi_num(p, rm).unwrap();
+        let mut half_pi = pi.clone().unwrap();
+        half_pi.set_exponent(1);
+        let mut quarter_pi = pi.clone().unwrap();
+        quarter_pi.set_exponent(0);
+        let mut three_quarter_pi = cc
+            .pi_num(p + WORD_BIT_SIZE, RoundingMode::None)
+            .unwrap()
+            .mul(
+                &BigFloatNumber::from_word(3, p).unwrap(),
+                p + WORD_BIT_SIZE,
+                RoundingMode::None,
+            )
+            .unwrap();
+        three_quarter_pi.set_exponent(three_quarter_pi.exponent() - 2);
+        three_quarter_pi.set_precision(p, rm).unwrap();
+
+        // quadrants
+        for (y, x, r) in [
+            (&one, &one, &quarter_pi),
+            (&one, &mone, &three_quarter_pi),
+            (&mone, &mone, &three_quarter_pi.neg().unwrap()),
+            (&mone, &one, &quarter_pi.neg().unwrap()),
+        ] {
+            let d1 = y.atan2(x, p, rm, &mut cc).unwrap();
+            assert!(d1.cmp(r) == 0);
+            assert!(d1.inexact());
+        }
+
+        // zeros
+        let d1 = zero.atan2(&zero, p, rm, &mut cc).unwrap();
+        assert!(d1.is_zero() && d1.is_positive());
+        let d1 = mzero.atan2(&one, p, rm, &mut cc).unwrap();
+        assert!(d1.is_zero() && d1.is_negative());
+        assert!(zero.atan2(&mzero, p, rm, &mut cc).unwrap().cmp(&pi) == 0);
+        assert!(zero.atan2(&mone, p, rm, &mut cc).unwrap().cmp(&pi) == 0);
+        assert!(
+            mzero
+                .atan2(&mzero, p, rm, &mut cc)
+                .unwrap()
+                .cmp(&pi.neg().unwrap())
+                == 0
+        );
+        assert!(one.atan2(&mzero, p, rm, &mut cc).unwrap().cmp(&half_pi) == 0);
+        assert!(
+            mone.atan2(&zero, p, rm, &mut cc)
+                .unwrap()
+                .cmp(&half_pi.neg().unwrap())
+                == 0
+        );
+
+        // directed rounding of a negative result
+        let d1 = mzero.atan2(&mone, p, RoundingMode::Up, &mut cc).unwrap();
+        let d2 = cc.pi_num(p, RoundingMode::Down).unwrap();
+        assert!(d1.cmp(&d2.neg().unwrap()) == 0);
+
+        // large and small ratio
+        let mut d1 = one.clone().unwrap();
+        d1.set_exponent(-1000);
+        let d2 = one.atan2(&d1, p, rm, &mut cc).unwrap();
+        assert!(d2.cmp(&half_pi) == 0);
+        let d2 = d1.atan2(&one, p, rm, &mut cc).unwrap();
+        assert!(d2.cmp(&d1) == 0);
+        let d2 = d1.atan2(&mone, p, rm, &mut cc).unwrap();
+        assert!(d2.cmp(&pi) == 0);
+
+        let d1 = BigFloatNumber::min_positive(p).unwrap();
+        let d2 = d1.atan2(&one, p, rm, &mut cc).unwrap();
+        assert!(d2.cmp(&d1) == 0);
+        assert!(d2.inexact());
+
+        // consistency with atan
+        for _ in 0..100 {
+            let y = BigFloatNumber::random_normal(p, -10, 10).unwrap();
+            let mut x = one.clone().unwrap();
+            x.set_exponent(random::<Exponent>() % 10);
+
+            let d1 = y.atan2(&x, p, rm, &mut cc).unwrap();
+            let d2 = y.div(&x, p, rm).unwrap().atan(p, rm, &mut cc).unwrap();
+            assert!(d1.cmp(&d2) == 0);
+
+            let d3 = y.neg().unwrap().atan2(&x, p, rm, &mut cc).unwrap();
+            assert!(d3.cmp(&d1.neg().unwrap()) == 0);
+        }
+    }
+
     #[ignore]
     #[test]
     #[cfg(feature = "std")]
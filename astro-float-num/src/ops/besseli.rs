@@ -0,0 +1,307 @@
+//! Modified Bessel function of the first kind.
+
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes the modified Bessel function of the first kind of order `n` of a number with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// The order `n` can be any real number. If `n` is not an integer, `self` must not be negative.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: `self` is negative and `n` is not an integer; `self` is zero and `n` is a negative number which is not an integer;
+    ///    the precision is incorrect.
+    pub fn besseli(
+        &self,
+        n: &Self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+
+        let nint = n.is_int();
+
+        if self.is_negative() && !nint {
+            return Err(Error::InvalidArgument);
+        }
+
+        if self.is_zero() {
+            return if n.is_zero() {
+                let mut ret = Self::from_word(1, p)?;
+                ret.set_inexact(self.inexact() || n.inexact());
+                Ok(ret)
+            } else if n.is_positive() || nint {
+                Self::new2(p, Sign::Pos, self.inexact() || n.inexact())
+            } else {
+                Err(Error::InvalidArgument)
+            };
+        }
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+        x.set_sign(Sign::Pos);
+
+        let mut nu = n.clone()?;
+        nu.set_inexact(false);
+
+        // I(-n, x) = I(n, x), I(n, -x) = (-1)^n * I(n, x) for integer n
+        let mut inv_sign = false;
+        if nint {
+            if !nu.is_zero() && nu.is_odd_int() {
+                inv_sign = self.is_negative();
+            }
+            nu.set_sign(Sign::Pos);
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p
+            .max(self.mantissa_max_bit_len())
+            .max(n.mantissa_max_bit_len())
+            + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = match x.besseli_positive_arg(&nu, p_x, cc) {
+                Ok(v) => v,
+                Err(Error::ExponentOverflow(s)) => {
+                    return Err(Error::ExponentOverflow(if inv_sign {
+                        s.invert()
+                    } else {
+                        s
+                    }));
+                }
+                Err(e) => return Err(e),
+            };
+
+            if inv_sign {
+                ret.inv_sign();
+            }
+
+            if ret.is_zero() {
+                // the result is too close to zero
+                return Self::new2(p, ret.sign(), true);
+            }
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact() | n.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // I(ν, x) for positive x, the result has relative error not exceeding 2^(-p) approximately.
+    // ν is non-negative, or ν is not an integer.
+    pub(super) fn besseli_positive_arg(
+        &self,
+        nu: &Self,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        if let Some(ret) = self.besseli_asymptotic(nu, false, p, cc)? {
+            return Ok(ret);
+        }
+
+        self.besselj_series(nu, true, p, cc)
+    }
+
+    // I(ν, x) = e^x / sqrt(2*π*x) * (1 - a(1)/x + a(2)/x^2 - ...),
+    // a(k) = (4*ν^2 - 1^2) * (4*ν^2 - 3^2) * ... * (4*ν^2 - (2*k-1)^2) / (k! * 8^k).
+    // If `second_kind` is true, the function computes K(ν, x) = sqrt(π / (2*x)) * e^(-x) * (1 + a(1)/x + a(2)/x^2 + ...).
+    pub(super) fn besseli_asymptotic(
+        &self,
+        nu: &Self,
+        second_kind: bool,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<Option<Self>, Error> {
+        let p_x = p + WORD_BIT_SIZE;
+
+        let mu = match self.bessel_asymptotic_mu(nu, p_x)? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let sum = match self.besseli_asymptotic_sum(&mu, !second_kind, p_x)? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let pi = cc.pi_num(p_x, RoundingMode::None)?;
+
+        let ret = if second_kind {
+            // sqrt(π / (2*x)) * e^(-x)
+            let mut d1 = pi.div(self, p_x, RoundingMode::None)?;
+            d1.set_exponent(d1.exponent() - 1);
+            let d2 = d1.sqrt(p_x, RoundingMode::None)?;
+            let d3 = self.neg()?.exp(p_x, RoundingMode::None, cc)?;
+
+            d2.mul(&d3, p_x, RoundingMode::None)?
+        } else {
+            // e^x / sqrt(2*π*x)
+            let mut d1 = self.mul(&pi, p_x, RoundingMode::None)?;
+            d1.set_exponent(d1.exponent() + 1);
+            let d2 = d1.sqrt(p_x, RoundingMode::None)?;
+            let d3 = self.exp(p_x, RoundingMode::None, cc)?;
+
+            d3.div(&d2, p_x, RoundingMode::None)?
+        };
+
+        ret.mul(&sum, p_x, RoundingMode::None).map(Some)
+    }
+
+    // Computes the sum 1 + a(1)/x + a(2)/x^2 + ... for μ = 4*ν^2, or the alternating sum if `alt` is true.
+    // Returns None if the terms start to grow before the required precision is reached.
+    fn besseli_asymptotic_sum(
+        &self,
+        mu: &Self,
+        alt: bool,
+        p: usize,
+    ) -> Result<Option<Self>, Error> {
+        let mut x8 = self.clone()?;
+        x8.set_exponent(x8.exponent() + 3);
+
+        let mut sum = Self::from_word(1, p)?;
+        let mut term = Self::from_word(1, p)?;
+        let mut k: usize = 1;
+
+        loop {
+            // (4*ν^2 - (2*k-1)^2) / (8*k*x)
+            let d1 = Self::from_usize(2 * k - 1)?;
+            let d2 = d1.mul_full_prec(&d1)?;
+            let d3 = mu.sub(&d2, p, RoundingMode::None)?;
+            let d4 = Self::from_usize(k)?;
+            let d5 = x8.mul(&d4, p, RoundingMode::None)?;
+
+            let prev_e = term.exponent();
+
+            term = term.mul(&d3, p, RoundingMode::None)?;
+            term = term.div(&d5, p, RoundingMode::None)?;
+
+            if term.is_zero() {
+                // the series terminates for half-integer ν
+                break;
+            }
+
+            if k > 1 && term.exponent() > prev_e {
+                return Ok(None);
+            }
+
+            if alt && k & 1 == 1 {
+                sum = sum.sub(&term, p, RoundingMode::None)?;
+            } else {
+                sum = sum.add(&term, p, RoundingMode::None)?;
+            }
+
+            if (term.exponent() as isize) < sum.exponent() as isize - p as isize {
+                break;
+            }
+
+            k += 1;
+        }
+
+        Ok(Some(sum))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::common::consts::ONE;
+    use crate::Exponent;
+    use crate::Radix;
+
+    #[test]
+    fn test_besseli() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+        let p = 320;
+
+        // reference values
+        for (n, x, y) in [
+            ("0", "0.75", "1.1456467780440013276475708003815183884962797819343354555070703582733562067723482962808911495463861904300625757"),
+            ("1", "2.5", "2.5167162452886984415281917481223776723889473033969492189945395536862123621998489805478611328623654879854309634"),
+            ("5", "0.125", "7.9524614282328797585062826115881319934643382785544335805316759538457928348029115456779853940098289606334611458e-9"),
+            ("-3", "7.25", "1.1005724973158790201538919099623039418023279653778777654405430260802151855668294885355096426148052496423677826e+2"),
+            ("0.5", "10.0", "2.778784603874571023997600323151739720814471787665070624341403748109323000247168253895230752260730136151014272e+3"),
+            ("-0.25", "1.5", "1.6477592959146952218312345336262396830845814000864114592507073494984393390368075341373174610434822688526674703"),
+            ("-5.5", "3.0", "-1.0733878759184452023123220816277235630530562275899342531051219734342465261270982858630819409443944921627699451"),
+            ("2.75", "150.5", "7.2924856881766213269739030341593174284497741310198957016045115387359453057930374439515664895595130023543325991e+63"),
+            ("100", "20.0", "2.8703193216428772482632607904320400793577144000463007639788701383818334470981195576410708840433253612646901207e-58"),
+            ("1.5", "1234567.25", "1.9923508085813123698233050663711621846667072625592584552737129157843057926635045202289571732986037275312587583e+536162"),
+        ] {
+            let d1 = BigFloatNumber::parse(n, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+            let d2 = BigFloatNumber::parse(x, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+
+            let d3 = d2.besseli(&d1, p, rm, &mut cc).unwrap();
+            let d4 = BigFloatNumber::parse(y, Radix::Dec, p, rm, &mut cc).unwrap();
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d4.exponent() - p as Exponent + 2);
+
+            assert!(
+                d3.sub(&d4, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0,
+                "{} {} {:?} {:?}",
+                n,
+                x,
+                d3,
+                d4
+            );
+            assert!(d3.inexact());
+        }
+
+        // negative argument
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        let three = BigFloatNumber::from_word(3, p).unwrap();
+        let d1 = one.besseli(&three, p, rm, &mut cc).unwrap();
+        let d2 = one.neg().unwrap().besseli(&three, p, rm, &mut cc).unwrap();
+        assert!(d1.cmp(&d2.neg().unwrap()) == 0);
+
+        let half = BigFloatNumber::from_f64(p, 0.5).unwrap();
+        assert_eq!(
+            one.neg()
+                .unwrap()
+                .besseli(&half, p, rm, &mut cc)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // zero argument
+        let zero = BigFloatNumber::new(p).unwrap();
+        assert!(zero.besseli(&zero, p, rm, &mut cc).unwrap().cmp(&one) == 0);
+        assert!(zero.besseli(&one, p, rm, &mut cc).unwrap().is_zero());
+        assert_eq!(
+            zero.besseli(&half.neg().unwrap(), p, rm, &mut cc)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // overflow
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(63);
+        assert_eq!(
+            d1.besseli(&one, p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+        assert_eq!(
+            d1.neg().unwrap().besseli(&one, p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Neg)
+        );
+    }
+}
@@ -0,0 +1,609 @@
+//! Bessel function of the first kind.
+
+use crate::common::consts::ONE;
+use crate::common::util::calc_add_cost;
+use crate::common::util::calc_mul_cost;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::ops::series::series_run;
+use crate::ops::series::PolycoeffGen;
+use crate::Exponent;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+// Polynomial coefficient generator: (-1)^k * k! * (ν + 1) * (ν + 2) * ... * (ν + k),
+// or k! * (ν + 1) * (ν + 2) * ... * (ν + k) for the modified Bessel function.
+struct BesseljPolycoeffGen {
+    nu: BigFloatNumber,
+    inc: BigFloatNumber,
+    fct: BigFloatNumber,
+    modified: bool,
+    iter_cost: usize,
+}
+
+impl BesseljPolycoeffGen {
+    fn new(p: usize, nu: &BigFloatNumber, modified: bool) -> Result<Self, Error> {
+        let inc = BigFloatNumber::new(1)?;
+        let fct = BigFloatNumber::from_word(1, p)?;
+
+        let iter_cost = calc_mul_cost(p) * 2 + calc_add_cost(p) + calc_add_cost(1);
+
+        Ok(BesseljPolycoeffGen {
+            nu: nu.clone()?,
+            inc,
+            fct,
+            modified,
+            iter_cost,
+        })
+    }
+}
+
+impl PolycoeffGen for BesseljPolycoeffGen {
+    fn next(&mut self, rm: RoundingMode) -> Result<&BigFloatNumber, Error> {
+        let p_inc = self.inc.mantissa_max_bit_len();
+        let p_fct = self.fct.mantissa_max_bit_len();
+
+        self.inc = self.inc.add(&ONE, p_inc, rm)?;
+
+        let d = self.nu.add(&self.inc, p_fct, rm)?;
+        self.fct = self.fct.mul(&d, p_fct, rm)?;
+        self.fct = self.fct.mul(&self.inc, p_fct, rm)?;
+
+        if !self.modified {
+            self.fct.inv_sign();
+        }
+
+        Ok(&self.fct)
+    }
+
+    #[inline]
+    fn iter_cost(&self) -> usize {
+        self.iter_cost
+    }
+
+    #[inline]
+    fn is_div(&self) -> bool {
+        true
+    }
+}
+
+impl BigFloatNumber {
+    /// Computes the Bessel function of the first kind of order `n` of a number with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// The order `n` can be any real number. If `n` is not an integer, `self` must not be negative.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: `self` is negative and `n` is not an integer; `self` is zero and `n` is a negative number which is not an integer;
+    ///    the precision is incorrect.
+    pub fn besselj(
+        &self,
+        n: &Self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+
+        let nint = n.is_int();
+
+        if self.is_negative() && !nint {
+            return Err(Error::InvalidArgument);
+        }
+
+        if self.is_zero() {
+            return if n.is_zero() {
+                let mut ret = Self::from_word(1, p)?;
+                ret.set_inexact(self.inexact() || n.inexact());
+                Ok(ret)
+            } else if n.is_positive() || nint {
+                Self::new2(p, Sign::Pos, self.inexact() || n.inexact())
+            } else {
+                Err(Error::InvalidArgument)
+            };
+        }
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+        x.set_sign(Sign::Pos);
+
+        let mut nu = n.clone()?;
+        nu.set_inexact(false);
+
+        // J(-n, x) = (-1)^n * J(n, x), J(n, -x) = (-1)^n * J(n, x) for integer n
+        let mut inv_sign = false;
+        if nint {
+            if !nu.is_zero() && nu.is_odd_int() {
+                inv_sign = self.is_negative() != n.is_negative();
+            }
+            nu.set_sign(Sign::Pos);
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p
+            .max(self.mantissa_max_bit_len())
+            .max(n.mantissa_max_bit_len())
+            + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = x.besselj_positive_arg(&nu, p_x, cc)?;
+
+            if inv_sign {
+                ret.inv_sign();
+            }
+
+            if ret.is_zero() {
+                // the result is too close to zero
+                return Self::new2(p, ret.sign(), true);
+            }
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact() | n.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // J(ν, x) for positive x, the result has relative error not exceeding 2^(-p) approximately.
+    // ν is non-negative, or ν is not an integer.
+    pub(super) fn besselj_positive_arg(
+        &self,
+        nu: &Self,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        if let Some(ret) = self.besselj_asymptotic(nu, false, p, cc)? {
+            return Ok(ret);
+        }
+
+        self.besselj_series(nu, false, p, cc)
+    }
+
+    // J(ν, x) = (x/2)^ν / Γ(ν + 1) * (1 - y/(1*(ν+1)) + y^2/(1*2*(ν+1)*(ν+2)) - ...), where y = x^2/4.
+    // Terms of the series can be much larger than the sum, so the precision is increased
+    // by the number of bits lost in cancellation.
+    // If `modified` is true, the function computes I(ν, x), and all signs in the series are positive.
+    pub(super) fn besselj_series(
+        &self,
+        nu: &Self,
+        modified: bool,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p_x = p + WORD_BIT_SIZE;
+
+        // for negative ν the terms with negative ν + k are summed separately
+        let k0 = if nu.is_negative() { nu.int_as_usize()? } else { 0 };
+
+        let sum = if (self.exponent() as isize) * 2 < -(p_x as isize) && k0 == 0 {
+            // the series is too close to 1
+            Self::from_word(1, p_x)?.add_correction(!modified)?
+        } else {
+            let mut y = self.mul_full_prec(self)?;
+            y.set_exponent(y.exponent() - 2);
+
+            let (loss, niter) = Self::besselj_series_estimate(&y, nu, k0, p_x)?;
+
+            // the terms of the modified series are positive unless ν < -1
+            let mut p_sum = if modified { p_x + WORD_BIT_SIZE } else { p_x + loss };
+
+            loop {
+                let mut polycoeff_gen = BesseljPolycoeffGen::new(p_sum, nu, modified)?;
+
+                let mut acc = Self::from_word(1, p_sum)?;
+                let mut x_pow = Self::from_word(1, p_sum)?;
+
+                for _ in 0..k0 {
+                    x_pow = x_pow.mul(&y, p_sum, RoundingMode::None)?;
+                    let coeff = polycoeff_gen.next(RoundingMode::None)?;
+                    let part = x_pow.div(coeff, p_sum, RoundingMode::None)?;
+                    acc = acc.add(&part, p_sum, RoundingMode::None)?;
+                }
+
+                let x_first = x_pow.mul(&y, p_sum, RoundingMode::None)?;
+                let x_step = y.clone()?;
+
+                let ret = series_run(
+                    acc,
+                    x_first,
+                    x_step,
+                    niter.saturating_sub(k0),
+                    &mut polycoeff_gen,
+                )?;
+
+                // the number of bits lost in cancellation
+                let p_req = if ret.is_zero() {
+                    p_sum * 2
+                } else {
+                    p_x + (loss as isize - ret.exponent() as isize).max(0) as usize
+                };
+
+                if p_sum >= p_req {
+                    break ret;
+                }
+
+                p_sum = round_p(p_req + WORD_BIT_SIZE);
+            }
+        };
+
+        // (x/2)^ν / Γ(ν + 1)
+        let mut x_half = self.clone()?;
+        x_half.set_exponent(x_half.exponent() - 1);
+
+        let d1 = x_half.pow(nu, p_x, RoundingMode::None, cc)?;
+
+        if d1.is_zero() {
+            return Self::new2(p, Sign::Pos, true);
+        }
+
+        let p_nu = nu.mantissa_max_bit_len().max(p_x) + WORD_BIT_SIZE;
+        let nu1 = nu.add(&ONE, p_nu, RoundingMode::None)?;
+
+        let d2 = match nu1.gamma(p_x, RoundingMode::None, cc) {
+            Ok(v) => v,
+            Err(Error::ExponentOverflow(_)) => {
+                // Γ(ν + 1) is too large
+                return Self::new2(p, Sign::Pos, true);
+            }
+            Err(e) => return Err(e),
+        };
+
+        if d2.is_zero() {
+            return Err(Error::ExponentOverflow(d2.sign()));
+        }
+
+        let d3 = d1.mul(&sum, p_x, RoundingMode::None)?;
+
+        d3.div(&d2, p_x, RoundingMode::None)
+    }
+
+    // Estimates the number of bits lost in cancellation, and the number of terms of the series
+    // for the given y = x^2/4 and ν. The terms are computed with low precision.
+    fn besselj_series_estimate(
+        y: &Self,
+        nu: &Self,
+        k0: usize,
+        p: usize,
+    ) -> Result<(usize, usize), Error> {
+        let p_est = WORD_BIT_SIZE;
+        let p_nu = nu.mantissa_max_bit_len() + WORD_BIT_SIZE;
+
+        let mut y = y.clone()?;
+        y.set_precision(p_est, RoundingMode::None)?;
+
+        let mut term = Self::from_word(1, p_est)?;
+        let mut sum = Self::from_word(1, p_est)?;
+        let mut k = 0;
+
+        loop {
+            k += 1;
+
+            let inc = Self::from_usize(k)?;
+            let mut d = nu.add(&inc, p_nu, RoundingMode::None)?;
+            d.set_sign(Sign::Pos);
+            d = d.mul(&inc, p_est, RoundingMode::None)?;
+
+            term = term.mul(&y, p_est, RoundingMode::None)?;
+            term = term.div(&d, p_est, RoundingMode::None)?;
+            sum = sum.add(&term, p_est, RoundingMode::None)?;
+
+            // the terms are decreasing and small enough
+            if k > k0
+                && d.cmp(&y) > 0
+                && (term.exponent() as isize) < sum.exponent() as isize - p as isize
+            {
+                break;
+            }
+        }
+
+        Ok((sum.exponent().max(0) as usize + 1, k))
+    }
+
+    // J(ν, x) = sqrt(2 / (π*x)) * (P * cos(χ) - Q * sin(χ)), where χ = x - (ν/2 + 1/4)*π,
+    // P = 1 - a(2)/x^2 + a(4)/x^4 - ..., Q = a(1)/x - a(3)/x^3 + ...,
+    // a(k) = (4*ν^2 - 1^2) * (4*ν^2 - 3^2) * ... * (4*ν^2 - (2*k-1)^2) / (k! * 8^k).
+    // If `second_kind` is true, the function computes Y(ν, x) = sqrt(2 / (π*x)) * (P * sin(χ) + Q * cos(χ)).
+    pub(super) fn besselj_asymptotic(
+        &self,
+        nu: &Self,
+        second_kind: bool,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<Option<Self>, Error> {
+        let p_x = p + WORD_BIT_SIZE;
+
+        let mu = match self.bessel_asymptotic_mu(nu, p_x)? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let mut p_sum = p_x;
+
+        let ret = loop {
+            let (pp, qq) = match self.besselj_asymptotic_pq(&mu, p_sum)? {
+                Some(v) => v,
+                None => return Ok(None),
+            };
+
+            // χ must have absolute error not exceeding 2^(-p_sum)
+            let p_chi = p_sum
+                + (self.exponent() as isize).max(0) as usize
+                + (nu.exponent() as isize).max(0) as usize
+                + WORD_BIT_SIZE;
+
+            let pi = cc.pi_num(p_chi, RoundingMode::None)?;
+
+            let mut d1 = nu.clone()?;
+            d1.set_exponent(d1.exponent() + 1);
+            let d2 = d1.add(&ONE, p_chi, RoundingMode::None)?;
+            let mut d3 = d2.mul(&pi, p_chi, RoundingMode::None)?;
+            d3.set_exponent(d3.exponent() - 2);
+
+            let chi = self.sub(&d3, p_chi, RoundingMode::None)?;
+
+            let cos = chi.cos(p_sum, RoundingMode::None, cc)?;
+            let sin = chi.sin(p_sum, RoundingMode::None, cc)?;
+
+            let (d4, d5, ret) = if second_kind {
+                let d4 = pp.mul(&sin, p_sum, RoundingMode::None)?;
+                let d5 = qq.mul(&cos, p_sum, RoundingMode::None)?;
+                let ret = d4.add(&d5, p_sum, RoundingMode::None)?;
+                (d4, d5, ret)
+            } else {
+                let d4 = pp.mul(&cos, p_sum, RoundingMode::None)?;
+                let d5 = qq.mul(&sin, p_sum, RoundingMode::None)?;
+                let ret = d4.sub(&d5, p_sum, RoundingMode::None)?;
+                (d4, d5, ret)
+            };
+
+            // the number of bits lost in cancellation
+            let p_req = if ret.is_zero() {
+                p_sum * 2
+            } else {
+                let e = d4.exponent().max(d5.exponent());
+                p_x + (e as isize - ret.exponent() as isize).max(0) as usize
+            };
+
+            if p_sum >= p_req {
+                break ret;
+            }
+
+            p_sum = round_p(p_req + WORD_BIT_SIZE);
+        };
+
+        // sqrt(2 / (π*x))
+        let pi = cc.pi_num(p_x, RoundingMode::None)?;
+        let d1 = self.mul(&pi, p_x, RoundingMode::None)?;
+        let mut d2 = d1.reciprocal(p_x, RoundingMode::None)?;
+        d2.set_exponent(d2.exponent() + 1);
+        let d3 = d2.sqrt(p_x, RoundingMode::None)?;
+
+        ret.mul(&d3, p_x, RoundingMode::None).map(Some)
+    }
+
+    // Returns μ = 4*ν^2 if the asymptotic expansion can be used for computing the Bessel functions
+    // of x with precision p. The smallest term of the expansion is about e^(-2*x),
+    // so it is used only if x is large enough.
+    pub(super) fn bessel_asymptotic_mu(&self, nu: &Self, p: usize) -> Result<Option<Self>, Error> {
+        // ln(2) / 2 < 0.35
+        let thres = Self::from_usize(p * 7 / 20 + 4)?;
+
+        if self.cmp(&thres) < 0 {
+            return Ok(None);
+        }
+
+        // the terms of the series are decreasing from the beginning if 2*x >= ν^2
+        let nu2 = nu.mul_full_prec(nu)?;
+        let mut x2 = self.clone()?;
+        x2.set_exponent(x2.exponent() + 1);
+
+        if x2.cmp(&nu2) < 0 {
+            return Ok(None);
+        }
+
+        let mut mu = nu2;
+        mu.set_exponent(mu.exponent() + 2);
+
+        Ok(Some(mu))
+    }
+
+    // Computes P and Q of the asymptotic expansion for μ = 4*ν^2.
+    // Returns None if the terms start to grow before the required precision is reached.
+    fn besselj_asymptotic_pq(&self, mu: &Self, p: usize) -> Result<Option<(Self, Self)>, Error> {
+        let mut x8 = self.clone()?;
+        x8.set_exponent(x8.exponent() + 3);
+
+        let mut pp = Self::from_word(1, p)?;
+        let mut qq = Self::new(p)?;
+        let mut term = Self::from_word(1, p)?;
+        let mut k: usize = 1;
+
+        loop {
+            // (4*ν^2 - (2*k-1)^2) / (8*k*x)
+            let d1 = Self::from_usize(2 * k - 1)?;
+            let d2 = d1.mul_full_prec(&d1)?;
+            let d3 = mu.sub(&d2, p, RoundingMode::None)?;
+            let d4 = Self::from_usize(k)?;
+            let d5 = x8.mul(&d4, p, RoundingMode::None)?;
+
+            let prev_e = term.exponent();
+
+            term = term.mul(&d3, p, RoundingMode::None)?;
+            term = term.div(&d5, p, RoundingMode::None)?;
+
+            if term.is_zero() {
+                // the series terminates for half-integer ν
+                break;
+            }
+
+            if k > 1 && term.exponent() > prev_e {
+                return Ok(None);
+            }
+
+            match k % 4 {
+                1 => qq = qq.add(&term, p, RoundingMode::None)?,
+                2 => pp = pp.sub(&term, p, RoundingMode::None)?,
+                3 => qq = qq.sub(&term, p, RoundingMode::None)?,
+                _ => pp = pp.add(&term, p, RoundingMode::None)?,
+            }
+
+            let e: Exponent =
+                if qq.is_zero() { pp.exponent() } else { pp.exponent().min(qq.exponent()) };
+
+            if (term.exponent() as isize) < e as isize - p as isize {
+                break;
+            }
+
+            k += 1;
+        }
+
+        Ok(Some((pp, qq)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Radix;
+    use crate::EXPONENT_MIN;
+
+    #[test]
+    fn test_besselj() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+        let p = 320;
+
+        // reference values
+        for (n, x, y) in [
+            ("0", "0.75", "8.642422751666486235557311038209232106109374442348021057137224318911992941143551615376552475431902793265212733e-1"),
+            ("1", "2.5", "4.9709410246427403801081627626442224252123496951900681887987242891872417576705474610168170808820818494995036768e-1"),
+            ("5", "0.125", "7.9421133991561054325499693806711572378486915255800046345824403068437903624158046013953086183078068652781904255e-9"),
+            ("-3", "7.25", "2.1924533340150819107341135793179250007492817517500477514752621902204964515331512125175939373743528981279607921e-1"),
+            ("2", "-3.5", "4.5862918419430748350225324563494563703387901684709158287938299235493590745635623729258536272209822842131320492e-1"),
+            ("0.5", "10.0", "-1.3726373575505048121295879506618160373234447262901051745081123153263617499590614927710348546317600594323167419e-1"),
+            ("-0.25", "1.5", "3.1798065366591983528661755639564229691063161651365821487521610979799606289776483235498831099234851424990724958e-1"),
+            ("-5.5", "3.0", "-3.1053336548415969704262264311487312525152299256507783660589296419911790018171824578588982980990735232465811149"),
+            ("2.75", "150.5", "4.0128400195511217866203597706529921728783399881619052057446587343409198585570191531080876757242201563537787629e-2"),
+            ("0", "500.0", "-3.4100556880731998265125060451894558131442791531683870482603019747336570817645593100634772869328923527088111417e-2"),
+            ("100", "20.0", "3.9617550943362517738349131484878066990733929347561686976864939297498393444048007156071941672321338567156910178e-59"),
+            ("0", "2.404825557695772886290797032415866851806640625", "-6.1087652597367303970819790742353884786309438907861803547372232693967259997383327712726362892697551764365605149e-17"),
+            ("1", "0.000000000000000000013552527156068805425093160010874271392822265625", "6.7762635780344027125465800054371356964109772371180534714573213983986554968939852448863938367407089118927381203e-21"),
+            ("1.5", "1234567.25", "7.12667254276587658945630458288276784467807568504534006108050175333846087207063187131657134105577738961588336e-4"),
+        ] {
+            let d1 = BigFloatNumber::parse(n, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+            let d2 = BigFloatNumber::parse(x, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+
+            let d3 = d2.besselj(&d1, p, rm, &mut cc).unwrap();
+            let d4 = BigFloatNumber::parse(y, Radix::Dec, p, rm, &mut cc).unwrap();
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d4.exponent() - p as Exponent + 2);
+
+            assert!(
+                d3.sub(&d4, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0,
+                "{} {} {:?} {:?}",
+                n,
+                x,
+                d3,
+                d4
+            );
+            assert!(d3.inexact());
+        }
+
+        // zero argument
+        let zero = BigFloatNumber::new(p).unwrap();
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        let half = BigFloatNumber::from_f64(p, 0.5).unwrap();
+        assert!(zero.besselj(&zero, p, rm, &mut cc).unwrap().cmp(&one) == 0);
+        assert!(zero.besselj(&one, p, rm, &mut cc).unwrap().is_zero());
+        assert!(zero.besselj(&half, p, rm, &mut cc).unwrap().is_zero());
+        assert!(zero
+            .besselj(&one.neg().unwrap(), p, rm, &mut cc)
+            .unwrap()
+            .is_zero());
+        assert_eq!(
+            zero.besselj(&half.neg().unwrap(), p, rm, &mut cc)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // negative argument and non-integer order
+        assert_eq!(
+            one.neg()
+                .unwrap()
+                .besselj(&half, p, rm, &mut cc)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // underflow
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(EXPONENT_MIN / 2);
+        let d2 = BigFloatNumber::from_word(1000000, p).unwrap();
+        assert!(d1.besselj(&d2, p, rm, &mut cc).unwrap().is_zero());
+
+        // J(n-1, x) + J(n+1, x) = 2*n/x * J(n, x)
+        let p = 1024;
+        for (n, x) in [(3.0, 300.5), (0.75, 1000.25)] {
+            let n1 = BigFloatNumber::from_f64(p, n - 1.0).unwrap();
+            let n2 = BigFloatNumber::from_f64(p, n).unwrap();
+            let n3 = BigFloatNumber::from_f64(p, n + 1.0).unwrap();
+            let x = BigFloatNumber::from_f64(p, x).unwrap();
+
+            let d1 = x.besselj(&n1, p, rm, &mut cc).unwrap();
+            let d2 = x.besselj(&n2, p, rm, &mut cc).unwrap();
+            let d3 = x.besselj(&n3, p, rm, &mut cc).unwrap();
+
+            let d4 = d1.add(&d3, p, RoundingMode::None).unwrap();
+            let mut d5 = d2.mul(&n2, p, RoundingMode::None).unwrap();
+            d5 = d5.div(&x, p, RoundingMode::None).unwrap();
+            d5.set_exponent(d5.exponent() + 1);
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d1.exponent().max(d3.exponent()) - p as Exponent + 4);
+
+            assert!(d4.sub(&d5, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0);
+        }
+    }
+
+    #[ignore]
+    #[test]
+    #[cfg(feature = "std")]
+    fn besselj_perf() {
+        let mut cc = Consts::new().unwrap();
+        let mut n = vec![];
+        let p = 160;
+        let nu = BigFloatNumber::from_word(2, p).unwrap();
+        for _ in 0..10000 {
+            n.push(
+                BigFloatNumber::random_normal(p, -5, 5)
+                    .unwrap()
+                    .abs()
+                    .unwrap(),
+            );
+        }
+
+        for _ in 0..5 {
+            let start_time = std::time::Instant::now();
+            for ni in n.iter() {
+                let _f = ni.besselj(&nu, p, RoundingMode::ToEven, &mut cc).unwrap();
+            }
+            let time = start_time.elapsed();
+            println!("{}", time.as_millis());
+        }
+    }
+}
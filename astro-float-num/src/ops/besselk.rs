@@ -0,0 +1,222 @@
+//! Modified Bessel function of the second kind.
+
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes the modified Bessel function of the second kind of order `n` of a number with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// The order `n` can be any real number. `self` must be positive.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: `self` is zero or negative; `n` is an integer which is too large;
+    ///    the precision is incorrect.
+    pub fn besselk(
+        &self,
+        n: &Self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+
+        if self.is_zero() || self.is_negative() {
+            return Err(Error::InvalidArgument);
+        }
+
+        let nint = n.is_int();
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+
+        // K(-ν, x) = K(ν, x)
+        let mut nu = n.clone()?;
+        nu.set_inexact(false);
+        nu.set_sign(Sign::Pos);
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p
+            .max(self.mantissa_max_bit_len())
+            .max(n.mantissa_max_bit_len())
+            + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = x.besselk_positive_arg(&nu, nint, p_x, cc)?;
+
+            if ret.is_zero() {
+                // the result is too close to zero
+                return Self::new2(p, Sign::Pos, true);
+            }
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact() | n.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // K(ν, x) for positive x and non-negative ν,
+    // the result has relative error not exceeding 2^(-p) approximately.
+    fn besselk_positive_arg(
+        &self,
+        nu: &Self,
+        nint: bool,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        if let Some(ret) = self.besseli_asymptotic(nu, true, p, cc)? {
+            return Ok(ret);
+        }
+
+        if nint {
+            let n = nu.int_as_usize()?;
+            self.bessel_int_order(n, true, p, cc)
+        } else {
+            self.besselk_non_int(nu, p, cc)
+        }
+    }
+
+    // K(ν, x) = π/2 * (I(-ν, x) - I(ν, x)) / sin(ν*π) for non-integer ν.
+    fn besselk_non_int(&self, nu: &Self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        let p_x = p + WORD_BIT_SIZE;
+        let nu_neg = nu.neg()?;
+
+        let mut p_wrk = p_x;
+
+        loop {
+            let d1 = self.besseli_positive_arg(&nu_neg, p_wrk, cc)?;
+            let d2 = self.besseli_positive_arg(nu, p_wrk, cc)?;
+
+            let ret = d1.sub(&d2, p_wrk, RoundingMode::None)?;
+
+            // the number of bits lost in cancellation
+            let p_req = if ret.is_zero() {
+                p_wrk * 2
+            } else {
+                let e = (d1.exponent() as isize).max(d2.exponent() as isize);
+                p_x + (e - ret.exponent() as isize).max(0) as usize
+            };
+
+            if p_wrk >= p_req {
+                let (sin, _) = nu.bessel_order_sin_cos(p_x, cc)?;
+                let pi = cc.pi_num(p_x, RoundingMode::None)?;
+
+                let mut ret = ret.mul(&pi, p_x, RoundingMode::None)?;
+                ret.set_exponent(ret.exponent() - 1);
+
+                break ret.div(&sin, p_x, RoundingMode::None);
+            }
+
+            p_wrk = round_p(p_req + WORD_BIT_SIZE);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::common::consts::ONE;
+    use crate::Exponent;
+    use crate::Radix;
+
+    #[test]
+    fn test_besselk() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+        let p = 320;
+
+        // reference values
+        for (n, x, y) in [
+            ("0", "0.75", "6.1058242211646411935094509113329144824191010291490041480292431319627732039556096975920294669021727597801669894e-1"),
+            ("1", "2.5", "7.3890816347747063648993540591217582101975744210962005306723010118640815423264789079998669903769446192709979004e-2"),
+            ("5", "0.125", "1.2570631994796726388988328940587296971445219366259376560181271530229458737775653256468735764461403558880520633e+7"),
+            ("-3", "7.25", "5.7926461366853301695334133380504717966081014945350604707170381478913067053876711788384885773929552334834042139e-4"),
+            ("0.5", "10.0", "1.7993478093705179608115879664146210265129409416158103858482967103598087405279724479081077272570934699730733413e-5"),
+            ("-0.25", "1.5", "2.1735815698180042599235348194179207047114081323029585857081335427656333351666645864168952189127574862119422767e-1"),
+            ("2.75", "150.5", "4.5549941222640329621423393630243996029778491393173982939679841239502714388621404475735097652057524484462445327e-67"),
+            ("0", "50.0", "3.410167749789495513920675512352952231845025377623348089932764359061957818822755254927689247198262509705989725e-23"),
+            ("100", "20.0", "1.7081356456876032997781520549338961193064854303896257836906442667612170066904685145998198912116815709988188568e+55"),
+            ("1.5", "1234567.25", "2.032775613074558564510861576672211509593850581599329347655048499770249311765625800886339576289405223555561928e-536169"),
+        ] {
+            let d1 = BigFloatNumber::parse(n, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+            let d2 = BigFloatNumber::parse(x, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+
+            let d3 = d2.besselk(&d1, p, rm, &mut cc).unwrap();
+            let d4 = BigFloatNumber::parse(y, Radix::Dec, p, rm, &mut cc).unwrap();
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d4.exponent() - p as Exponent + 2);
+
+            assert!(
+                d3.sub(&d4, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0,
+                "{} {} {:?} {:?}",
+                n,
+                x,
+                d3,
+                d4
+            );
+            assert!(d3.inexact());
+        }
+
+        // invalid argument
+        let zero = BigFloatNumber::new(p).unwrap();
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        assert_eq!(
+            zero.besselk(&one, p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+        assert_eq!(
+            one.neg()
+                .unwrap()
+                .besselk(&one, p, rm, &mut cc)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // underflow
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(63);
+        assert!(d1.besselk(&one, p, rm, &mut cc).unwrap().is_zero());
+
+        // I(ν, x) * K(ν+1, x) + I(ν+1, x) * K(ν, x) = 1 / x
+        let p = 1024;
+        for (n, x) in [(3.0, 300.5), (0.75, 1000.25), (2.0, 0.5), (-1.5, 12.5)] {
+            let n1 = BigFloatNumber::from_f64(p, n).unwrap();
+            let n2 = BigFloatNumber::from_f64(p, n + 1.0).unwrap();
+            let x = BigFloatNumber::from_f64(p, x).unwrap();
+
+            let d1 = x.besseli(&n1, p, rm, &mut cc).unwrap();
+            let d2 = x.besselk(&n2, p, rm, &mut cc).unwrap();
+            let d3 = x.besseli(&n2, p, rm, &mut cc).unwrap();
+            let d4 = x.besselk(&n1, p, rm, &mut cc).unwrap();
+
+            let d5 = d1.mul(&d2, p, RoundingMode::None).unwrap();
+            let d6 = d3.mul(&d4, p, RoundingMode::None).unwrap();
+            let d7 = d5.add(&d6, p, RoundingMode::None).unwrap();
+
+            let d8 = x.reciprocal(p, RoundingMode::None).unwrap();
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d8.exponent() - p as Exponent + 4);
+
+            assert!(d7.sub(&d8, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0);
+        }
+    }
+}
@@ -0,0 +1,453 @@
+//! Bessel function of the second kind.
+
+use crate::common::consts::ONE;
+use crate::common::util::log2_ceil;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes the Bessel function of the second kind of order `n` of a number with precision `p`.
+    /// The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// The order `n` can be any real number. `self` must be positive.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: `self` is zero or negative; `n` is an integer which is too large;
+    ///    the precision is incorrect.
+    pub fn bessely(
+        &self,
+        n: &Self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+
+        if self.is_zero() || self.is_negative() {
+            return Err(Error::InvalidArgument);
+        }
+
+        let nint = n.is_int();
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+
+        let mut nu = n.clone()?;
+        nu.set_inexact(false);
+
+        // Y(-n, x) = (-1)^n * Y(n, x) for integer n
+        let mut inv_sign = false;
+        if nint {
+            inv_sign = n.is_negative() && n.is_odd_int();
+            nu.set_sign(Sign::Pos);
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p
+            .max(self.mantissa_max_bit_len())
+            .max(n.mantissa_max_bit_len())
+            + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = match x.bessely_positive_arg(&nu, nint, p_x, cc) {
+                Ok(v) => v,
+                Err(Error::ExponentOverflow(s)) => {
+                    return Err(Error::ExponentOverflow(if inv_sign {
+                        s.invert()
+                    } else {
+                        s
+                    }));
+                }
+                Err(e) => return Err(e),
+            };
+
+            if inv_sign {
+                ret.inv_sign();
+            }
+
+            if ret.is_zero() {
+                // the result is too close to zero
+                return Self::new2(p, ret.sign(), true);
+            }
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact() | n.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // Y(ν, x) for positive x, the result has relative error not exceeding 2^(-p) approximately.
+    // ν is non-negative if ν is an integer.
+    fn bessely_positive_arg(
+        &self,
+        nu: &Self,
+        nint: bool,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        if let Some(ret) = self.besselj_asymptotic(nu, true, p, cc)? {
+            return Ok(ret);
+        }
+
+        if nint {
+            let n = nu.int_as_usize()?;
+            self.bessel_int_order(n, false, p, cc)
+        } else {
+            self.bessely_non_int(nu, p, cc)
+        }
+    }
+
+    // Y(ν, x) = (J(ν, x) * cos(ν*π) - J(-ν, x)) / sin(ν*π) for non-integer ν.
+    fn bessely_non_int(&self, nu: &Self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        let p_x = p + WORD_BIT_SIZE;
+        let nu_neg = nu.neg()?;
+
+        let mut p_wrk = p_x;
+
+        loop {
+            let (sin, cos) = nu.bessel_order_sin_cos(p_wrk, cc)?;
+
+            let d1 = self.besselj_positive_arg(nu, p_wrk, cc)?;
+            let d2 = self.besselj_positive_arg(&nu_neg, p_wrk, cc)?;
+            let d3 = d1.mul(&cos, p_wrk, RoundingMode::None)?;
+
+            let ret = d3.sub(&d2, p_wrk, RoundingMode::None)?;
+
+            // the number of bits lost in cancellation
+            let p_req = if ret.is_zero() {
+                p_wrk * 2
+            } else {
+                let e = (d3.exponent() as isize).max(d2.exponent() as isize);
+                p_x + (e - ret.exponent() as isize).max(0) as usize
+            };
+
+            if p_wrk >= p_req {
+                break ret.div(&sin, p_x, RoundingMode::None);
+            }
+
+            p_wrk = round_p(p_req + WORD_BIT_SIZE);
+        }
+    }
+
+    // Returns sin(ν*π) and cos(ν*π) for the order ν.
+    // ν = m + δ, where m is an integer, and |δ| <= 1/2, so sin(ν*π) = (-1)^m * sin(δ*π),
+    // and the result does not depend on the precision of π if ν is close to an integer.
+    pub(super) fn bessel_order_sin_cos(
+        &self,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<(Self, Self), Error> {
+        let m = self.round(0, RoundingMode::ToEven)?;
+
+        // exact subtraction
+        let delta = self.sub(&m, self.mantissa_max_bit_len(), RoundingMode::None)?;
+
+        let pi = cc.pi_num(p, RoundingMode::None)?;
+        let d = delta.mul(&pi, p, RoundingMode::None)?;
+
+        let mut sin = d.sin(p, RoundingMode::None, cc)?;
+        let mut cos = d.cos(p, RoundingMode::None, cc)?;
+
+        if !m.is_zero() && m.is_odd_int() {
+            sin.inv_sign();
+            cos.inv_sign();
+        }
+
+        Ok((sin, cos))
+    }
+
+    // Bessel functions of the second kind of integer order n >= 0 for positive x:
+    // Y(n, x) = ((x/2)^n * (2*L*S1 - S2) - F) / π,
+    // K(n, x) = (F - (-1)^n * (x/2)^n * (2*L*S1 - S2)) / 2, where
+    // L = ln(x/2) + γ, y = x^2/4, s = -1 for Y, and s = 1 for K,
+    // S1 = sum((s*y)^k / (k! * (n+k)!)), S2 = sum((H(k) + H(n+k)) * (s*y)^k / (k! * (n+k)!)),
+    // F = (x/2)^(-n) * sum((n-k-1)! / k! * (-s*y)^k) for k = 0..n-1, H(k) = 1 + 1/2 + ... + 1/k.
+    // Terms can be much larger than the result, so the precision is increased
+    // by the number of bits lost in cancellation.
+    // If `modified` is true, the function computes K(n, x), otherwise it computes Y(n, x).
+    pub(super) fn bessel_int_order(
+        &self,
+        n: usize,
+        modified: bool,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p_x = p + WORD_BIT_SIZE;
+
+        // the sign of the result if F is too large
+        let s_ovf = if modified { Sign::Pos } else { Sign::Neg };
+
+        let mut p_wrk = p_x + WORD_BIT_SIZE;
+
+        loop {
+            let mut x_half = self.clone()?;
+            x_half.set_exponent(x_half.exponent() - 1);
+
+            let mut y = x_half.mul(&x_half, p_wrk, RoundingMode::None)?;
+            if !modified {
+                y.inv_sign();
+            }
+
+            let xn = x_half.powi(n, p_wrk, RoundingMode::None)?;
+
+            // S1 and S2, and the maximum exponents of their terms
+            let mut t = Self::from_word(1, p_wrk)?;
+            let mut hn = Self::new(p_wrk)?;
+            for i in 1..=n {
+                let d = Self::from_usize(i)?;
+                t = t.div(&d, p_wrk, RoundingMode::None)?;
+                let r = ONE.div(&d, p_wrk, RoundingMode::None)?;
+                hn = hn.add(&r, p_wrk, RoundingMode::None)?;
+            }
+
+            let mut hk = Self::new(p_wrk)?;
+            let mut s1 = t.clone()?;
+            let mut s2 = t.mul(&hn, p_wrk, RoundingMode::None)?;
+            let mut e1 = t.exponent() as isize;
+            let mut e2 = e1;
+            let mut k = 0;
+
+            loop {
+                k += 1;
+
+                let dk = Self::from_usize(k)?;
+                let dnk = Self::from_usize(n + k)?;
+
+                t = t.mul(&y, p_wrk, RoundingMode::None)?;
+                t = t.div(&dk, p_wrk, RoundingMode::None)?;
+                t = t.div(&dnk, p_wrk, RoundingMode::None)?;
+
+                if t.is_zero() {
+                    break;
+                }
+
+                let r = ONE.div(&dk, p_wrk, RoundingMode::None)?;
+                hk = hk.add(&r, p_wrk, RoundingMode::None)?;
+                let r = ONE.div(&dnk, p_wrk, RoundingMode::None)?;
+                hn = hn.add(&r, p_wrk, RoundingMode::None)?;
+
+                let h = hk.add(&hn, p_wrk, RoundingMode::None)?;
+                let u = t.mul(&h, p_wrk, RoundingMode::None)?;
+
+                s1 = s1.add(&t, p_wrk, RoundingMode::None)?;
+                s2 = s2.add(&u, p_wrk, RoundingMode::None)?;
+
+                e1 = e1.max(t.exponent() as isize);
+                e2 = e2.max(u.exponent() as isize);
+
+                // the terms are decreasing and small enough
+                if y.abs_cmp(&dk.mul_full_prec(&dnk)?) < 0
+                    && (t.exponent() as isize) < e1 - p_wrk as isize
+                    && (u.exponent() as isize) < e2 - p_wrk as isize
+                {
+                    break;
+                }
+            }
+
+            // (x/2)^n * (2*L*S1 - S2)
+            let ln = x_half.ln(p_wrk, RoundingMode::None, cc)?;
+            let el = (ln.exponent() as isize).max(0);
+            let gamma = cc.euler_gamma_num(p_wrk, RoundingMode::None)?;
+            let l = ln.add(&gamma, p_wrk, RoundingMode::None)?;
+
+            let mut d1 = l.mul(&s1, p_wrk, RoundingMode::None)?;
+            d1.set_exponent(d1.exponent() + 1);
+            let d2 = d1.sub(&s2, p_wrk, RoundingMode::None)?;
+            let g = d2.mul(&xn, p_wrk, RoundingMode::None)?;
+
+            let mut e_max = if xn.is_zero() {
+                isize::MIN / 2
+            } else {
+                xn.exponent() as isize + (el + 1 + e1).max(e2) + 1
+            };
+
+            // F
+            let f = if n > 0 {
+                let mut c = Self::from_word(1, p_wrk)?;
+                for i in 2..n {
+                    c = c.mul(&Self::from_usize(i)?, p_wrk, RoundingMode::None)?;
+                }
+
+                let z = y.neg()?;
+                let mut fs = c.clone()?;
+                let mut e3 = c.exponent() as isize;
+
+                for k in 1..n {
+                    c = c.mul(&z, p_wrk, RoundingMode::None)?;
+                    c = c.div(&Self::from_usize(k)?, p_wrk, RoundingMode::None)?;
+                    c = c.div(&Self::from_usize(n - k)?, p_wrk, RoundingMode::None)?;
+                    fs = fs.add(&c, p_wrk, RoundingMode::None)?;
+                    e3 = e3.max(c.exponent() as isize);
+                }
+
+                if xn.is_zero() {
+                    return Err(Error::ExponentOverflow(s_ovf));
+                }
+
+                let f = match fs.div(&xn, p_wrk, RoundingMode::None) {
+                    Ok(v) => v,
+                    Err(Error::ExponentOverflow(_)) => return Err(Error::ExponentOverflow(s_ovf)),
+                    Err(e) => return Err(e),
+                };
+
+                e_max = e_max.max(e3 - xn.exponent() as isize + 1);
+
+                f
+            } else {
+                Self::new(p_wrk)?
+            };
+
+            let mut ret = if modified {
+                if n & 1 == 0 {
+                    f.sub(&g, p_wrk, RoundingMode::None)
+                } else {
+                    f.add(&g, p_wrk, RoundingMode::None)
+                }
+            } else {
+                g.sub(&f, p_wrk, RoundingMode::None)
+            }?;
+
+            // the number of bits lost in cancellation, and rounding errors of the sums
+            let p_req = if ret.is_zero() {
+                p_wrk * 2
+            } else {
+                p_x + (e_max - ret.exponent() as isize).max(0) as usize + log2_ceil(n + k + 1)
+            };
+
+            if p_wrk >= p_req {
+                break if modified {
+                    ret.set_exponent(ret.exponent() - 1);
+                    Ok(ret)
+                } else {
+                    let pi = cc.pi_num(p_x, RoundingMode::None)?;
+                    ret.div(&pi, p_x, RoundingMode::None)
+                };
+            }
+
+            p_wrk = round_p(p_req + WORD_BIT_SIZE);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Exponent;
+    use crate::Radix;
+    use crate::EXPONENT_MIN;
+
+    #[test]
+    fn test_bessely() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+        let p = 320;
+
+        // reference values
+        for (n, x, y) in [
+            ("0", "0.75", "-1.3717276938577239752281437939658185520084264033268876646345384085839441949683593023097608340733077176673570238e-1"),
+            ("1", "2.5", "1.4591813796678579887875994053587757127608019654670099984510336876847982786986827339694694495998904089288127699e-1"),
+            ("5", "0.125", "-8.0183584532013223401507892132112780134322752956316076715194560269789703426831888800934584041754451512295538977e+6"),
+            ("-3", "7.25", "-2.185934197448578487687531447012242411717184150079906422121475615487672074094207122299215669889225194016564975e-1"),
+            ("0.5", "10.0", "2.1170886633139815291899827942773548370862153669803135525494150072579129207329312546533131345416334509624500813e-1"),
+            ("-0.25", "1.5", "5.5772005420704313480867950473469229855621828884054669207441573993641770615580991131550312798103755846791773514e-1"),
+            ("2.75", "150.5", "5.1190098147806970295745138937687749370412373521106899685553802591489210711006813673174545540322247856656365129e-2"),
+            ("0", "500.0", "1.0506708739831374099740660626553125825873868927222316347004348599031887576749718186227200688684427110647040251e-2"),
+            ("30", "20.0", "-1.1497814626308341439597216056297386931238869553508183474292244591976414842521262835077403741199727130753090654e+2"),
+            ("0", "0.8935769662791674949886555623379535973072052001953125", "-2.33892792840621031186921539341542974885024047252032385133422345080788606398661162161524825599004774422125947e-17"),
+        ] {
+            let d1 = BigFloatNumber::parse(n, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+            let d2 = BigFloatNumber::parse(x, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+
+            let d3 = d2.bessely(&d1, p, rm, &mut cc).unwrap();
+            let d4 = BigFloatNumber::parse(y, Radix::Dec, p, rm, &mut cc).unwrap();
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d4.exponent() - p as Exponent + 2);
+
+            assert!(
+                d3.sub(&d4, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0,
+                "{} {} {:?} {:?}",
+                n,
+                x,
+                d3,
+                d4
+            );
+            assert!(d3.inexact());
+        }
+
+        // invalid argument
+        let zero = BigFloatNumber::new(p).unwrap();
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        assert_eq!(
+            zero.bessely(&one, p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+        assert_eq!(
+            one.neg()
+                .unwrap()
+                .bessely(&one, p, rm, &mut cc)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // overflow
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(EXPONENT_MIN / 2);
+        let d2 = BigFloatNumber::from_word(1000001, p).unwrap();
+        assert_eq!(
+            d1.bessely(&d2, p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Neg)
+        );
+        assert_eq!(
+            d1.bessely(&d2.neg().unwrap(), p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+
+        // J(n+1, x) * Y(n, x) - J(n, x) * Y(n+1, x) = 2 / (π * x)
+        let p = 1024;
+        for (n, x) in [(3.0, 300.5), (0.75, 1000.25), (2.0, 0.5), (-1.5, 12.5)] {
+            let n1 = BigFloatNumber::from_f64(p, n).unwrap();
+            let n2 = BigFloatNumber::from_f64(p, n + 1.0).unwrap();
+            let x = BigFloatNumber::from_f64(p, x).unwrap();
+
+            let d1 = x.besselj(&n2, p, rm, &mut cc).unwrap();
+            let d2 = x.bessely(&n1, p, rm, &mut cc).unwrap();
+            let d3 = x.besselj(&n1, p, rm, &mut cc).unwrap();
+            let d4 = x.bessely(&n2, p, rm, &mut cc).unwrap();
+
+            let d5 = d1.mul(&d2, p, RoundingMode::None).unwrap();
+            let d6 = d3.mul(&d4, p, RoundingMode::None).unwrap();
+            let d7 = d5.sub(&d6, p, RoundingMode::None).unwrap();
+
+            let pi = cc.pi_num(p, RoundingMode::None).unwrap();
+            let mut d8 = pi
+                .mul(&x, p, RoundingMode::None)
+                .unwrap()
+                .reciprocal(p, RoundingMode::None)
+                .unwrap();
+            d8.set_exponent(d8.exponent() + 1);
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d5.exponent().max(d6.exponent()) - p as Exponent + 4);
+
+            assert!(d7.sub(&d8, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0);
+        }
+    }
+}
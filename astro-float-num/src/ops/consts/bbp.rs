@@ -0,0 +1,190 @@
+//! Hexadecimal digits of pi at a given position.
+
+use crate::common::consts::ONE;
+use crate::common::util::{log2_ceil, round_p};
+use crate::defs::Error;
+use crate::num::BigFloatNumber;
+use crate::{Exponent, RoundingMode, Sign, WORD_BIT_SIZE};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Computes `count` hexadecimal digits of pi starting from the position `start` after the point
+/// using the Bailey–Borwein–Plouffe formula:
+/// pi = sum(1/16^k * (4/(8k+1) - 2/(8k+4) - 1/(8k+5) - 1/(8k+6))), k = 0, 1, ...
+/// The fractional part of 16^start * pi is computed with the absolute error less than 2^(-q),
+/// and q is increased until the digits can be determined.
+pub(crate) fn pi_hex_digits(start: usize, count: usize) -> Result<Vec<u8>, Error> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let d = start as u64;
+    if d > (u64::MAX - 6) / 8 {
+        return Err(Error::InvalidArgument);
+    }
+
+    let mut q = 4 * count + WORD_BIT_SIZE;
+
+    loop {
+        // each of the n operations adds an error not larger than 2^(4 - p)
+        let n = 8 * (start + q / 4 + 2);
+        let p = round_p(q + log2_ceil(n) + 8);
+
+        let x = bbp_frac(d, p)?;
+
+        let mut eps = ONE.clone()?;
+        eps.set_exponent(1 - q as Exponent);
+
+        let lo = x.sub(&eps, p, RoundingMode::None)?;
+        let hi = x.add(&eps, p, RoundingMode::None)?;
+
+        if !lo.is_negative() && hi.cmp(&ONE) < 0 {
+            let d1 = hex_digits(&lo, count)?;
+            let d2 = hex_digits(&hi, count)?;
+
+            if d1 == d2 {
+                return Ok(d1);
+            }
+        }
+
+        q = round_p(q + q / 2);
+    }
+}
+
+// Computes the fractional part of 16^d * pi with precision `p`.
+fn bbp_frac(d: u64, p: usize) -> Result<BigFloatNumber, Error> {
+    let mut sum = BigFloatNumber::new(p)?;
+
+    // j, exponent of the coefficient, sign of the coefficient
+    let terms = [(1, 2, Sign::Pos), (4, 1, Sign::Neg), (5, 0, Sign::Neg), (6, 0, Sign::Neg)];
+
+    // sum((16^(d-k) mod (8k+j)) / (8k+j)), k = 0, 1, ..., d
+    for k in 0..=d {
+        for (j, c, s) in terms {
+            let m = 8 * k + j;
+            let r = pow_mod(16, d - k, m);
+
+            if r != 0 {
+                let d1 = BigFloatNumber::from_u64(r, 64)?;
+                let d2 = BigFloatNumber::from_u64(m, 64)?;
+                let mut t = d1.div(&d2, p, RoundingMode::None)?;
+                t.set_exponent(t.exponent() + c);
+                t.set_sign(s);
+
+                sum = sum.add(&t, p, RoundingMode::None)?;
+            }
+        }
+
+        // the integer part does not affect the result, and removing it keeps the sum small
+        sum = sum.fract()?;
+    }
+
+    // sum(16^(d-k) / (8k+j)), k = d + 1, d + 2, ...
+    let mut k = d + 1;
+    while 4 * (k - d) < p as u64 + 8 {
+        for (j, c, s) in terms {
+            let d1 = BigFloatNumber::from_u64(8 * k + j, 64)?;
+            let mut t = d1.reciprocal(p, RoundingMode::None)?;
+            t.set_exponent(t.exponent() + c - 4 * (k - d) as Exponent);
+            t.set_sign(s);
+
+            sum = sum.add(&t, p, RoundingMode::None)?;
+        }
+
+        k += 1;
+    }
+
+    let ret = sum.fract()?;
+
+    if ret.is_negative() {
+        ret.add(&ONE, p, RoundingMode::None)
+    } else {
+        Ok(ret)
+    }
+}
+
+// Returns first `count` hexadecimal digits of `x`, where 0 <= x < 1.
+fn hex_digits(x: &BigFloatNumber, count: usize) -> Result<Vec<u8>, Error> {
+    let mut ret = Vec::new();
+    ret.try_reserve_exact(count)?;
+
+    let mut x = x.clone()?;
+
+    for _ in 0..count {
+        if !x.is_zero() {
+            x.set_exponent(x.exponent() + 4);
+        }
+
+        ret.push(x.int_as_usize()? as u8);
+        x = x.fract()?;
+    }
+
+    Ok(ret)
+}
+
+// Computes b^e mod m.
+fn pow_mod(b: u64, mut e: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+
+    let m = m as u128;
+    let mut b = b as u128 % m;
+    let mut ret = 1;
+
+    while e > 0 {
+        if e & 1 == 1 {
+            ret = ret * b % m;
+        }
+
+        b = b * b % m;
+        e >>= 1;
+    }
+
+    ret as u64
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::ops::consts::Consts;
+
+    #[test]
+    fn test_pi_hex_digits() {
+        let cc = Consts::new().unwrap();
+
+        // pi = 3.243F6A8885A308D313198A2E03707344A...
+        let digits = cc.pi_hex_digits(0, 33).unwrap();
+        let expected = "243F6A8885A308D313198A2E03707344A";
+        assert_eq!(digits.len(), expected.len());
+        for (d, c) in digits.iter().zip(expected.chars()) {
+            assert_eq!(*d as u32, c.to_digit(16).unwrap());
+        }
+
+        assert!(cc.pi_hex_digits(10, 0).unwrap().is_empty());
+
+        // compare with the digits of pi computed directly
+        let mut cc = Consts::new().unwrap();
+        let p = 4 * 5000;
+        let mut pi = cc.pi_num(p, RoundingMode::ToZero).unwrap();
+        pi = pi.fract().unwrap();
+        let expected = hex_digits(&pi, 4900).unwrap();
+
+        for (start, count) in [(1, 10), (100, 40), (1000, 100), (4000, 16), (4871, 29)] {
+            let digits = cc.pi_hex_digits(start, count).unwrap();
+            assert_eq!(digits, &expected[start..start + count]);
+        }
+
+        assert_eq!(pow_mod(16, 0, 1), 0);
+        assert_eq!(pow_mod(16, 10, 1000007), 1099511627776 % 1000007);
+
+        // errors
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(
+            cc.pi_hex_digits(usize::MAX, 1).unwrap_err(),
+            Error::InvalidArgument
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! Bernoulli numbers.
+
+use crate::common::consts::ONE;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::mem_size;
+use crate::{Exponent, RoundingMode, Sign};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Holds exact tangent numbers T(1), T(2), ..., T(n) for the largest n requested so far.
+/// Bernoulli numbers are derived from the tangent numbers: B(2*k) = (-1)^(k-1) * 2*k * T(k) / (4^k * (4^k - 1)).
+#[derive(Debug, Default)]
+pub struct BernoulliCache {
+    t: Vec<BigFloatNumber>,
+}
+
+impl BernoulliCache {
+    pub(crate) const fn new() -> Self {
+        BernoulliCache { t: Vec::new() }
+    }
+
+    /// Returns the number of bytes used by the cached values.
+    pub(crate) fn memory_used(&self) -> usize {
+        self.t.iter().map(|v| mem_size(&[v])).sum()
+    }
+
+    /// Returns exact tangent numbers T(1), T(2), ..., T(n).
+    pub(crate) fn tangent_numbers(&mut self, n: usize) -> Result<&[BigFloatNumber], Error> {
+        if n > self.t.len() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(constant = "bernoulli", n, "constant cache miss");
+
+            // the tangent numbers are not computed incrementally, so the cache grows at least twice
+            self.t = tangent_numbers(n.max(2 * self.t.len()))?;
+        }
+
+        Ok(&self.t[..n])
+    }
+
+    /// Return the Bernoulli number B(n) with precision k using a single rounding with the rounding mode `rm`.
+    /// B(1) is -1/2.
+    pub(crate) fn for_prec(
+        &mut self,
+        n: usize,
+        k: usize,
+        rm: RoundingMode,
+    ) -> Result<BigFloatNumber, Error> {
+        let p = round_p(k);
+
+        if n == 0 {
+            return BigFloatNumber::from_word(1, p);
+        }
+
+        if n == 1 {
+            let mut ret = BigFloatNumber::from_word(1, p)?;
+            ret.set_exponent(0);
+            ret.set_sign(Sign::Neg);
+            return Ok(ret);
+        }
+
+        if n & 1 == 1 {
+            return BigFloatNumber::new2(p, Sign::Pos, false);
+        }
+
+        let k = n / 2;
+        let t = &self.tangent_numbers(k)?[k - 1];
+
+        // B(2*k) = (-1)^(k-1) * 2*k * T(k) / (4^k * (4^k - 1))
+        let mut d1 = t.mul_full_prec(&BigFloatNumber::from_usize(n)?)?;
+        d1.set_exponent(d1.exponent() - n as Exponent);
+
+        if k & 1 == 0 {
+            d1.inv_sign();
+        }
+
+        let mut d2 = ONE.clone()?;
+        d2.set_exponent(n as Exponent + 1);
+        let d2 = d2.sub_full_prec(&ONE)?;
+
+        d1.div(&d2, p, rm)
+    }
+}
+
+// Computes tangent numbers T(1), T(2), ..., T(n) exactly (R. P. Brent, D. Harvey).
+fn tangent_numbers(n: usize) -> Result<Vec<BigFloatNumber>, Error> {
+    let mut t = Vec::new();
+    t.try_reserve_exact(n)?;
+
+    t.push(BigFloatNumber::from_word(1, 1)?);
+
+    for k in 1..n {
+        let d = BigFloatNumber::from_usize(k)?;
+        t.push(t[k - 1].mul_full_prec(&d)?);
+    }
+
+    for k in 2..=n {
+        for j in k..=n {
+            let d1 = BigFloatNumber::from_usize(j - k + 2)?;
+            let mut v = t[j - 1].mul_full_prec(&d1)?;
+
+            if j > k {
+                let d2 = BigFloatNumber::from_usize(j - k)?;
+                let d3 = t[j - 2].mul_full_prec(&d2)?;
+                v = v.add_full_prec(&d3)?;
+            }
+
+            t[j - 1] = v;
+        }
+    }
+
+    Ok(t)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::ops::consts::Consts;
+
+    #[test]
+    fn test_bernoulli() {
+        let mut cc = Consts::new().unwrap();
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // tangent numbers
+        let t = cc.tangent_numbers(6).unwrap();
+        for (v, e) in t.iter().zip([1, 2, 16, 272, 7936, 353792]) {
+            assert!(v.cmp(&BigFloatNumber::from_word(e, 128).unwrap()) == 0);
+        }
+
+        // the cache grows, and the values stay exact
+        let t = cc.tangent_numbers(100).unwrap();
+        assert_eq!(t.len(), 100);
+        assert!(t.iter().all(|v| !v.inexact() && v.is_int()));
+        let t = cc.tangent_numbers(3).unwrap();
+        assert_eq!(t.len(), 3);
+
+        // B(n) = num / den
+        for (n, num, den) in [
+            (0, 1, 1),
+            (2, 1, 6),
+            (4, -1, 30),
+            (6, 1, 42),
+            (8, -1, 30),
+            (10, 5, 66),
+            (12, -691, 2730),
+            (14, 7, 6),
+            (20, -174611, 330),
+        ] {
+            for rm in [RoundingMode::Up, RoundingMode::Down, RoundingMode::ToEven] {
+                let d1 = cc.bernoulli_num(n, p, rm).unwrap();
+                let d2 = BigFloatNumber::from_i64(num, p)
+                    .unwrap()
+                    .div(&BigFloatNumber::from_word(den, p).unwrap(), p, rm)
+                    .unwrap();
+                assert!(d1.cmp(&d2) == 0);
+                assert_eq!(d1.inexact(), d2.inexact());
+            }
+        }
+
+        let d1 = cc.bernoulli_num(1, p, rm).unwrap();
+        assert!(d1.cmp(&BigFloatNumber::from_f64(p, -0.5).unwrap()) == 0);
+
+        for n in [3, 5, 101] {
+            assert!(cc.bernoulli_num(n, p, rm).unwrap().is_zero());
+        }
+
+        // B(200)
+        let d1 = cc.bernoulli(200, p, rm);
+        let d2 = crate::BigFloat::parse(
+            "-3.64707726451913543621383088655499449048682346861910587376827e+215",
+            crate::Radix::Dec,
+            p,
+            rm,
+            &mut cc,
+        );
+        let d3 = d1.sub(&d2, p, RoundingMode::None);
+        assert!(d3.is_zero() || d3.exponent().unwrap() < d1.exponent().unwrap() - 126);
+    }
+}
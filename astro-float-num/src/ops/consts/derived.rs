@@ -0,0 +1,132 @@
+//! Constants derived from elementary functions: sqrt(2), golden ratio, ln(pi).
+
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::{mem_size, Consts};
+use crate::{RoundingMode, WORD_BIT_SIZE};
+
+/// Holds value of a derived constant computed with the largest precision requested so far.
+#[derive(Debug, Default)]
+pub struct DerivedCache {
+    val: Option<BigFloatNumber>,
+}
+
+impl DerivedCache {
+    pub(crate) const fn new() -> Self {
+        DerivedCache { val: None }
+    }
+
+    /// Returns the number of bytes used by the cached value.
+    pub(crate) fn memory_used(&self) -> usize {
+        self.val.as_ref().map_or(0, |v| mem_size(&[v]))
+    }
+
+    /// Return value of the constant with precision k (calculate with `f` if needed).
+    /// `f` computes the constant with the given precision, and has access to the constants cache `cc`.
+    pub(crate) fn for_prec<F>(
+        &mut self,
+        k: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+        f: F,
+    ) -> Result<BigFloatNumber, Error>
+    where
+        F: Fn(usize, &mut Consts) -> Result<BigFloatNumber, Error>,
+    {
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = round_p(k) + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            match &self.val {
+                Some(val) if val.mantissa_max_bit_len() >= p_x => {
+                    let mut ret = val.clone()?;
+
+                    if ret.try_set_precision(k, rm, p_wrk)? {
+                        return Ok(ret);
+                    }
+
+                    p_wrk += p_inc;
+                    p_inc = round_p(p_wrk / 5);
+                }
+                _ => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(constant = "derived", p = k, "constant cache miss");
+
+                    self.val = Some(f(p_x, cc)?);
+                }
+            }
+        }
+    }
+}
+
+// sqrt(2)
+pub(super) fn sqrt_2(p: usize, _cc: &mut Consts) -> Result<BigFloatNumber, Error> {
+    BigFloatNumber::from_word(2, 1)?.sqrt(p, RoundingMode::None)
+}
+
+// (1 + sqrt(5)) / 2
+pub(super) fn phi(p: usize, _cc: &mut Consts) -> Result<BigFloatNumber, Error> {
+    let d1 = BigFloatNumber::from_word(5, 1)?.sqrt(p, RoundingMode::None)?;
+    let mut ret = d1.add(&BigFloatNumber::from_word(1, 1)?, p, RoundingMode::None)?;
+    ret.set_exponent(ret.exponent() - 1);
+    Ok(ret)
+}
+
+// ln(pi)
+pub(super) fn ln_pi(p: usize, cc: &mut Consts) -> Result<BigFloatNumber, Error> {
+    let pi = cc.pi_num(p + WORD_BIT_SIZE, RoundingMode::None)?;
+    pi.ln(p, RoundingMode::None, cc)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_derived_const() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        for p in [64, 320, 128, 1024] {
+            // sqrt(2)^2 = 2
+            let d1 = cc.sqrt_2_num(p, rm).unwrap();
+            let d2 = BigFloatNumber::from_word(2, 1)
+                .unwrap()
+                .sqrt(p, rm)
+                .unwrap();
+            assert!(d1.cmp(&d2) == 0);
+            assert_eq!(d1.mantissa_max_bit_len(), round_p(p));
+
+            // phi^2 = phi + 1
+            let d1 = cc.phi_num(p, rm).unwrap();
+            let d2 = d1.mul(&d1, 2 * p, RoundingMode::None).unwrap();
+            let d3 = d1
+                .add(
+                    &BigFloatNumber::from_word(1, 1).unwrap(),
+                    2 * p,
+                    RoundingMode::None,
+                )
+                .unwrap();
+            let d4 = d2.sub(&d3, 2 * p, RoundingMode::None).unwrap();
+            assert!(d4.exponent() < 3 - p as crate::Exponent);
+
+            // ln(pi)
+            let d1 = cc.ln_pi_num(p, rm).unwrap();
+            let d2 = cc
+                .pi_num(2 * p, RoundingMode::None)
+                .unwrap()
+                .ln(p, rm, &mut cc)
+                .unwrap();
+            assert!(d1.cmp(&d2) == 0);
+        }
+
+        // directed rounding
+        let d1 = cc.ln_pi_num(256, RoundingMode::Down).unwrap();
+        let d2 = cc.ln_pi_num(256, RoundingMode::Up).unwrap();
+        assert!(d1.cmp(&d2) < 0);
+    }
+}
@@ -4,6 +4,7 @@ use crate::common::consts::ONE;
 use crate::common::util::{log2_floor, round_p};
 use crate::defs::Error;
 use crate::num::BigFloatNumber;
+use crate::ops::consts::mem_size;
 use crate::{RoundingMode, WORD_BIT_SIZE};
 
 fn pq(a: usize, b: usize) -> Result<(BigFloatNumber, BigFloatNumber), Error> {
@@ -59,6 +60,11 @@ impl ECache {
         f0.add(&ONE, prec, RoundingMode::None)
     }
 
+    /// Returns the number of bytes used by the cached values.
+    pub(crate) fn memory_used(&self) -> usize {
+        mem_size(&[&self.pk, &self.qk, &self.val])
+    }
+
     pub fn new() -> Result<Self, Error> {
         // initial precision is large enough, as b_factor() requires it.
         let (p01, q01) = pq(0, 64)?;
@@ -110,6 +116,9 @@ impl ECache {
                 p_inc = round_p(p_wrk / 5);
             }
 
+            #[cfg(feature = "tracing")]
+            tracing::debug!(constant = "e", p = k, "constant cache miss");
+
             let mut pk;
             let mut qk;
             let mut bb;
@@ -0,0 +1,148 @@
+//! Euler–Mascheroni constant
+
+use crate::common::consts::ONE;
+use crate::common::util::{log2_ceil, round_p};
+use crate::defs::Error;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::ln2::Ln2Cache;
+use crate::ops::consts::mem_size;
+use crate::{Exponent, RoundingMode, WORD_BIT_SIZE};
+
+/// Holds value of currently computed Euler–Mascheroni constant.
+#[derive(Debug)]
+pub struct EulerGammaCache {
+    val: BigFloatNumber,
+}
+
+impl EulerGammaCache {
+    /// Returns the number of bytes used by the cached values.
+    pub(crate) fn memory_used(&self) -> usize {
+        mem_size(&[&self.val])
+    }
+
+    pub fn new() -> Result<Self, Error> {
+        Ok(EulerGammaCache {
+            val: BigFloatNumber::new(1)?,
+        })
+    }
+
+    /// Return value of the Euler–Mascheroni constant with precision k (calculate if needed).
+    pub(crate) fn for_prec(
+        &mut self,
+        k: usize,
+        rm: RoundingMode,
+        ln2: &mut Ln2Cache,
+    ) -> Result<BigFloatNumber, Error> {
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = round_p(k) + p_inc;
+
+        loop {
+            if !self.val.is_zero() && self.val.mantissa_max_bit_len() >= p_wrk {
+                let mut ret = self.val.clone()?;
+
+                if ret.try_set_precision(k, rm, p_wrk)? {
+                    return Ok(ret);
+                }
+
+                p_wrk += p_inc;
+                p_inc = round_p(p_wrk / 5);
+            }
+
+            if self.val.is_zero() || self.val.mantissa_max_bit_len() < p_wrk {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(constant = "euler_gamma", p = k, "constant cache miss");
+
+                self.val = Self::compute(p_wrk, ln2)?;
+            }
+        }
+    }
+
+    // Brent-McMillan: γ = U / V - O(e^(-4*n)),
+    // U = sum((n^k / k!)^2 * (H(k) - ln(n))), V = sum((n^k / k!)^2), H(k) = 1 + 1/2 + ... + 1/k.
+    // n is a power of 2, so that ln(n) = m * ln(2), and the multiplication by n^2 is exact.
+    fn compute(p: usize, ln2: &mut Ln2Cache) -> Result<BigFloatNumber, Error> {
+        // π * e^(-4*n) < 2^(-p - 2), 1/(4*log2(e)) < 0.1733
+        let m = log2_ceil((p + 2) * 1733 / 10000 + 2);
+        let n = 1usize << m;
+
+        // rounding errors of about 4*n terms are accumulated
+        let p_wrk = round_p(p + log2_ceil(4 * n) + WORD_BIT_SIZE);
+
+        let mut ln_n = ln2.for_prec(p_wrk, RoundingMode::None)?;
+        ln_n = ln_n.mul(&BigFloatNumber::from_usize(m)?, p_wrk, RoundingMode::None)?;
+
+        let mut u = BigFloatNumber::from_word(1, p_wrk)?;
+        let mut h = BigFloatNumber::new(p_wrk)?;
+        let mut uu = ln_n.neg()?;
+        let mut vv = u.clone()?;
+        let mut k = 0;
+
+        loop {
+            k += 1;
+
+            let kk = BigFloatNumber::from_usize(k)?;
+
+            u.set_exponent(u.exponent() + 2 * m as Exponent);
+            u = u.div(&kk, p_wrk, RoundingMode::None)?;
+            u = u.div(&kk, p_wrk, RoundingMode::None)?;
+
+            let r = ONE.div(&kk, p_wrk, RoundingMode::None)?;
+            h = h.add(&r, p_wrk, RoundingMode::None)?;
+
+            let d = h.sub(&ln_n, p_wrk, RoundingMode::None)?;
+            let t = u.mul(&d, p_wrk, RoundingMode::None)?;
+
+            uu = uu.add(&t, p_wrk, RoundingMode::None)?;
+            vv = vv.add(&u, p_wrk, RoundingMode::None)?;
+
+            // |H(k) - ln(n)| < 2 for k < 4*n
+            if k > n && (u.exponent() as isize) + 1 < vv.exponent() as isize - p_wrk as isize {
+                break;
+            }
+        }
+
+        let mut ret = uu.div(&vv, p_wrk, RoundingMode::None)?;
+        ret.set_precision(p, RoundingMode::None)?;
+
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{Consts, Radix};
+
+    #[test]
+    fn test_euler_gamma_const() {
+        let mut cc = Consts::new().unwrap();
+        let p = 640;
+
+        let c = cc.euler_gamma_num(p, RoundingMode::ToEven).unwrap();
+
+        let r = BigFloatNumber::parse(
+            "5.772156649015328606065120900824024310421593359399235988057672348848677267776646709369470632917467495e-1",
+            Radix::Dec,
+            p,
+            RoundingMode::ToEven,
+            &mut cc,
+        )
+        .unwrap();
+
+        let mut eps = ONE.clone().unwrap();
+        eps.set_exponent(r.exponent() - 320);
+
+        assert!(c.sub(&r, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0);
+
+        // cached value with lower and higher precision
+        let c1 = cc.euler_gamma_num(128, RoundingMode::ToEven).unwrap();
+        let c2 = cc.euler_gamma_num(1280, RoundingMode::ToEven).unwrap();
+        assert_eq!(c1.mantissa_max_bit_len(), 128);
+        assert!(c2.sub(&r, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0);
+
+        let mut c3 = c2.clone().unwrap();
+        c3.set_precision(128, RoundingMode::ToEven).unwrap();
+        assert!(c1.cmp(&c3) == 0);
+    }
+}
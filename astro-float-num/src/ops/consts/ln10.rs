@@ -4,6 +4,7 @@ use crate::common::consts::ONE;
 use crate::common::util::round_p;
 use crate::defs::Error;
 use crate::num::BigFloatNumber;
+use crate::ops::consts::mem_size;
 use crate::{RoundingMode, WORD_BIT_SIZE};
 
 fn pqr(a: usize, b: usize) -> Result<(BigFloatNumber, BigFloatNumber, BigFloatNumber), Error> {
@@ -61,6 +62,11 @@ pub struct Ln10Cache {
 }
 
 impl Ln10Cache {
+    /// Returns the number of bytes used by the cached values.
+    pub(crate) fn memory_used(&self) -> usize {
+        mem_size(&[&self.pk, &self.qk, &self.rk, &self.val])
+    }
+
     pub fn new() -> Result<Self, Error> {
         let (p01, q01, r01) = pqr(0, 1)?;
 
@@ -105,6 +111,9 @@ impl Ln10Cache {
                 p_inc = round_p(p_wrk / 5);
             }
 
+            #[cfg(feature = "tracing")]
+            tracing::debug!(constant = "ln10", p = k, "constant cache miss");
+
             let mut pk;
             let mut qk;
             let mut rk;
@@ -5,6 +5,7 @@ use crate::common::consts::THREE;
 use crate::common::util::round_p;
 use crate::defs::Error;
 use crate::num::BigFloatNumber;
+use crate::ops::consts::mem_size;
 use crate::RoundingMode;
 use crate::WORD_BIT_SIZE;
 
@@ -63,6 +64,11 @@ pub struct Ln2Cache {
 }
 
 impl Ln2Cache {
+    /// Returns the number of bytes used by the cached values.
+    pub(crate) fn memory_used(&self) -> usize {
+        mem_size(&[&self.pk, &self.qk, &self.rk, &self.val])
+    }
+
     pub fn new() -> Result<Self, Error> {
         let (p01, q01, r01) = pqr(0, 1)?;
 
@@ -101,6 +107,9 @@ impl Ln2Cache {
                 p_inc = round_p(p_wrk / 5);
             }
 
+            #[cfg(feature = "tracing")]
+            tracing::debug!(constant = "ln2", p = k, "constant cache miss");
+
             let mut pk;
             let mut qk;
             let mut rk;
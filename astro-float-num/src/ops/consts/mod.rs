@@ -1,31 +1,96 @@
+mod bbp;
+mod bernoulli;
+mod derived;
 mod e;
+mod euler_gamma;
 mod ln10;
 mod ln2;
 mod pi;
 
+#[cfg(feature = "std")]
+mod shared;
+
 use crate::common::buf::WordBuf;
 use crate::common::util::round_p;
 use crate::mantissa::Mantissa;
 use crate::num::BigFloatNumber;
+use crate::ops::consts::bernoulli::BernoulliCache;
+use crate::ops::consts::derived::DerivedCache;
 use crate::ops::consts::e::ECache;
+use crate::ops::consts::euler_gamma::EulerGammaCache;
 use crate::ops::consts::ln10::Ln10Cache;
 use crate::ops::consts::ln2::Ln2Cache;
 use crate::ops::consts::pi::PiCache;
 use crate::BigFloat;
 use crate::Error;
 use crate::RoundingMode;
+use crate::Word;
+
+#[cfg(feature = "std")]
+pub use shared::SharedConsts;
 
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
 /// Constants cache contains arbitrary-precision mathematical constants.
+/// To share a constants cache between threads, use `SharedConsts`.
 #[derive(Debug)]
 pub struct Consts {
-    pi: PiCache,
-    e: ECache,
-    ln2: Ln2Cache,
-    ln10: Ln10Cache,
+    pi: Option<PiCache>,
+    e: Option<ECache>,
+    ln2: Option<Ln2Cache>,
+    ln10: Option<Ln10Cache>,
+    euler_gamma: Option<EulerGammaCache>,
+    sqrt_2: DerivedCache,
+    phi: DerivedCache,
+    ln_pi: DerivedCache,
+    bernoulli: BernoulliCache,
     tenpowers: Vec<(WordBuf, WordBuf, usize)>,
+    max_memory: Option<usize>,
+}
+
+// Caches of the constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cache {
+    Pi,
+    E,
+    Ln2,
+    Ln10,
+    EulerGamma,
+    Sqrt2,
+    Phi,
+    LnPi,
+    Bernoulli,
+    TenPowers,
+}
+
+impl Cache {
+    const ALL: [Cache; 10] = [
+        Cache::Pi,
+        Cache::E,
+        Cache::Ln2,
+        Cache::Ln10,
+        Cache::EulerGamma,
+        Cache::Sqrt2,
+        Cache::Phi,
+        Cache::LnPi,
+        Cache::Bernoulli,
+        Cache::TenPowers,
+    ];
+}
+
+// Returns the cache `c` initializing it with `f` if it is not initialized yet.
+fn get_or_init<T>(c: &mut Option<T>, f: fn() -> Result<T, Error>) -> Result<&mut T, Error> {
+    let v = match c.take() {
+        Some(v) => v,
+        None => f()?,
+    };
+    Ok(c.insert(v))
+}
+
+// Returns the number of bytes used by the mantissas of the numbers.
+fn mem_size(nums: &[&BigFloatNumber]) -> usize {
+    nums.iter().map(|v| v.mantissa_max_bit_len() / 8).sum()
 }
 
 /// In an ideal situation, the `Consts` structure is initialized with `Consts::new` only once,
@@ -38,14 +103,119 @@ impl Consts {
     ///  - MemoryAllocation: failed to allocate memory for mantissa.
     pub fn new() -> Result<Self, Error> {
         Ok(Consts {
-            pi: PiCache::new()?,
-            e: ECache::new()?,
-            ln2: Ln2Cache::new()?,
-            ln10: Ln10Cache::new()?,
-            tenpowers: Vec::new(),
+            pi: Some(PiCache::new()?),
+            e: Some(ECache::new()?),
+            ln2: Some(Ln2Cache::new()?),
+            ln10: Some(Ln10Cache::new()?),
+            euler_gamma: Some(EulerGammaCache::new()?),
+            ..Self::empty()
         })
     }
 
+    /// Returns an empty constants cache. The function does not allocate memory,
+    /// and can be used in constant expressions, e.g. for initializing a static variable.
+    /// The constants are computed on the first use.
+    pub const fn empty() -> Self {
+        Consts {
+            pi: None,
+            e: None,
+            ln2: None,
+            ln10: None,
+            euler_gamma: None,
+            sqrt_2: DerivedCache::new(),
+            phi: DerivedCache::new(),
+            ln_pi: DerivedCache::new(),
+            bernoulli: BernoulliCache::new(),
+            tenpowers: Vec::new(),
+            max_memory: None,
+        }
+    }
+
+    /// Initializes the constants cache which uses not more than `bytes` bytes of memory for the cached values.
+    /// When a lookup makes the cache exceed the limit, the largest cached values are evicted,
+    /// so the constants computed with a very high precision are not kept alive after they are returned.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    pub fn with_max_memory(bytes: usize) -> Result<Self, Error> {
+        let mut ret = Self::new()?;
+        ret.max_memory = Some(bytes);
+        Ok(ret)
+    }
+
+    /// Returns the memory limit of the cache in bytes, or None if the cache is not limited.
+    pub fn max_memory(&self) -> Option<usize> {
+        self.max_memory
+    }
+
+    /// Returns the number of bytes used by the cached values.
+    pub fn memory_used(&self) -> usize {
+        Cache::ALL.iter().map(|c| self.cache_memory_used(*c)).sum()
+    }
+
+    /// Evicts all cached values.
+    pub fn clear(&mut self) {
+        for c in Cache::ALL {
+            self.evict(c);
+        }
+    }
+
+    fn cache_memory_used(&self, c: Cache) -> usize {
+        match c {
+            Cache::Pi => self.pi.as_ref().map_or(0, |c| c.memory_used()),
+            Cache::E => self.e.as_ref().map_or(0, |c| c.memory_used()),
+            Cache::Ln2 => self.ln2.as_ref().map_or(0, |c| c.memory_used()),
+            Cache::Ln10 => self.ln10.as_ref().map_or(0, |c| c.memory_used()),
+            Cache::EulerGamma => self.euler_gamma.as_ref().map_or(0, |c| c.memory_used()),
+            Cache::Sqrt2 => self.sqrt_2.memory_used(),
+            Cache::Phi => self.phi.memory_used(),
+            Cache::LnPi => self.ln_pi.memory_used(),
+            Cache::Bernoulli => self.bernoulli.memory_used(),
+            Cache::TenPowers => self
+                .tenpowers
+                .iter()
+                .map(|(m1, m2, _)| (m1.len() + m2.len()) * core::mem::size_of::<Word>())
+                .sum(),
+        }
+    }
+
+    fn evict(&mut self, c: Cache) {
+        match c {
+            Cache::Pi => self.pi = None,
+            Cache::E => self.e = None,
+            Cache::Ln2 => self.ln2 = None,
+            Cache::Ln10 => self.ln10 = None,
+            Cache::EulerGamma => self.euler_gamma = None,
+            Cache::Sqrt2 => self.sqrt_2 = DerivedCache::new(),
+            Cache::Phi => self.phi = DerivedCache::new(),
+            Cache::LnPi => self.ln_pi = DerivedCache::new(),
+            Cache::Bernoulli => self.bernoulli = BernoulliCache::new(),
+            Cache::TenPowers => self.tenpowers = Vec::new(),
+        }
+    }
+
+    // Evicts the largest caches except `keep` until the memory limit is satisfied.
+    fn evict_to_max_memory(&mut self, keep: Option<Cache>) {
+        if let Some(max) = self.max_memory {
+            let mut caches = Cache::ALL;
+            caches.sort_by_key(|c| core::cmp::Reverse(self.cache_memory_used(*c)));
+
+            for c in caches {
+                if self.memory_used() <= max {
+                    break;
+                }
+
+                if Some(c) != keep {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(cache = ?c, "constant cache eviction");
+
+                    self.evict(c);
+                }
+            }
+        }
+    }
+
     /// Returns the value of the pi number with precision `p` using rounding mode `rm`.
     /// Precision is rounded upwards to the word size.
     ///
@@ -55,7 +225,9 @@ impl Consts {
     ///  - InvalidArgument: the precision is incorrect.
     pub(crate) fn pi_num(&mut self, p: usize, rm: RoundingMode) -> Result<BigFloatNumber, Error> {
         let p = round_p(p);
-        self.pi.for_prec(p, rm)
+        let ret = get_or_init(&mut self.pi, PiCache::new)?.for_prec(p, rm)?;
+        self.evict_to_max_memory(None);
+        Ok(ret)
     }
 
     /// Returns the value of the Euler number with precision `p` using rounding mode `rm`.
@@ -67,7 +239,9 @@ impl Consts {
     ///  - InvalidArgument: the precision is incorrect.
     pub(crate) fn e_num(&mut self, p: usize, rm: RoundingMode) -> Result<BigFloatNumber, Error> {
         let p = round_p(p);
-        self.e.for_prec(p, rm)
+        let ret = get_or_init(&mut self.e, ECache::new)?.for_prec(p, rm)?;
+        self.evict_to_max_memory(None);
+        Ok(ret)
     }
 
     /// Returns the value of the natural logarithm of 2 with precision `p` using rounding mode `rm`.
@@ -79,7 +253,9 @@ impl Consts {
     ///  - InvalidArgument: the precision is incorrect.
     pub(crate) fn ln_2_num(&mut self, p: usize, rm: RoundingMode) -> Result<BigFloatNumber, Error> {
         let p = round_p(p);
-        self.ln2.for_prec(p, rm)
+        let ret = get_or_init(&mut self.ln2, Ln2Cache::new)?.for_prec(p, rm)?;
+        self.evict_to_max_memory(None);
+        Ok(ret)
     }
 
     /// Returns the value of the natural logarithm of 10 with precision `p` using rounding mode `rm`.
@@ -95,7 +271,116 @@ impl Consts {
         rm: RoundingMode,
     ) -> Result<BigFloatNumber, Error> {
         let p = round_p(p);
-        self.ln10.for_prec(p, rm)
+        let ret = get_or_init(&mut self.ln10, Ln10Cache::new)?.for_prec(p, rm)?;
+        self.evict_to_max_memory(None);
+        Ok(ret)
+    }
+
+    /// Returns the value of the Euler–Mascheroni constant with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub(crate) fn euler_gamma_num(
+        &mut self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<BigFloatNumber, Error> {
+        let p = round_p(p);
+        let ln2 = get_or_init(&mut self.ln2, Ln2Cache::new)?;
+        let ret = get_or_init(&mut self.euler_gamma, EulerGammaCache::new)?.for_prec(p, rm, ln2)?;
+        self.evict_to_max_memory(None);
+        Ok(ret)
+    }
+
+    /// Returns the value of the square root of 2 with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub(crate) fn sqrt_2_num(
+        &mut self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<BigFloatNumber, Error> {
+        let p = round_p(p);
+        let mut cache = core::mem::take(&mut self.sqrt_2);
+        let ret = cache.for_prec(p, rm, self, derived::sqrt_2);
+        self.sqrt_2 = cache;
+        let ret = ret?;
+        self.evict_to_max_memory(None);
+        Ok(ret)
+    }
+
+    /// Returns the value of the golden ratio with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub(crate) fn phi_num(&mut self, p: usize, rm: RoundingMode) -> Result<BigFloatNumber, Error> {
+        let p = round_p(p);
+        let mut cache = core::mem::take(&mut self.phi);
+        let ret = cache.for_prec(p, rm, self, derived::phi);
+        self.phi = cache;
+        let ret = ret?;
+        self.evict_to_max_memory(None);
+        Ok(ret)
+    }
+
+    /// Returns the value of the natural logarithm of the pi number with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub(crate) fn ln_pi_num(
+        &mut self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<BigFloatNumber, Error> {
+        let p = round_p(p);
+        let mut cache = core::mem::take(&mut self.ln_pi);
+        let ret = cache.for_prec(p, rm, self, derived::ln_pi);
+        self.ln_pi = cache;
+        let ret = ret?;
+        self.evict_to_max_memory(None);
+        Ok(ret)
+    }
+
+    /// Returns the Bernoulli number B(n) with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub(crate) fn bernoulli_num(
+        &mut self,
+        n: usize,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<BigFloatNumber, Error> {
+        let ret = self.bernoulli.for_prec(n, p, rm)?;
+        self.evict_to_max_memory(None);
+        Ok(ret)
+    }
+
+    /// Returns exact tangent numbers T(1), T(2), ..., T(n).
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    pub(crate) fn tangent_numbers(&mut self, n: usize) -> Result<&[BigFloatNumber], Error> {
+        self.bernoulli.tangent_numbers(n)?;
+        self.evict_to_max_memory(Some(Cache::Bernoulli));
+        self.bernoulli.tangent_numbers(n)
     }
 
     /// Returns the value of the pi number with precision `p` using rounding mode `rm`.
@@ -134,12 +419,155 @@ impl Consts {
         }
     }
 
+    /// Returns the value of the Euler–Mascheroni constant with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn euler_gamma(&mut self, p: usize, rm: RoundingMode) -> BigFloat {
+        match self.euler_gamma_num(p, rm) {
+            Ok(v) => v.into(),
+            Err(e) => BigFloat::nan(Some(e)),
+        }
+    }
+
+    /// Returns the value of the square root of 2 with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn sqrt_2(&mut self, p: usize, rm: RoundingMode) -> BigFloat {
+        match self.sqrt_2_num(p, rm) {
+            Ok(v) => v.into(),
+            Err(e) => BigFloat::nan(Some(e)),
+        }
+    }
+
+    /// Returns the value of the golden ratio `(1 + sqrt(5)) / 2` with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn phi(&mut self, p: usize, rm: RoundingMode) -> BigFloat {
+        match self.phi_num(p, rm) {
+            Ok(v) => v.into(),
+            Err(e) => BigFloat::nan(Some(e)),
+        }
+    }
+
+    /// Returns the value of the natural logarithm of the pi number with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn ln_pi(&mut self, p: usize, rm: RoundingMode) -> BigFloat {
+        match self.ln_pi_num(p, rm) {
+            Ok(v) => v.into(),
+            Err(e) => BigFloat::nan(Some(e)),
+        }
+    }
+
+    /// Returns the Bernoulli number B(n) with precision `p` using rounding mode `rm`, e.g. B(2) = 1/6, B(4) = -1/30.
+    /// B(1) is -1/2, and B(n) is 0 for odd n larger than 1.
+    /// The result is computed with a single rounding of an exact rational number
+    /// derived from the tangent numbers, which are cached, so subsequent calls with `n` not larger than before are fast.
+    /// Precision is rounded upwards to the word size.
+    pub fn bernoulli(&mut self, n: usize, p: usize, rm: RoundingMode) -> BigFloat {
+        match self.bernoulli_num(n, p, rm) {
+            Ok(v) => v.into(),
+            Err(e) => BigFloat::nan(Some(e)),
+        }
+    }
+
+    /// Returns `count` hexadecimal digits of the pi number starting from the position `start` after the hexadecimal point,
+    /// e.g. the digits at the positions 0, 1, 2 are 2, 4, 3, since pi = 3.243F6A88...
+    /// The digits are computed using the Bailey–Borwein–Plouffe formula without computing the preceding digits,
+    /// which allows verifying the last digits of a computation of pi with a very high precision.
+    /// The time of computation grows almost linearly with `start`, and the working precision grows linearly with `count`.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: `start` is too large, so that `8 * start + 6` does not fit into 64 bits.
+    pub fn pi_hex_digits(&self, start: usize, count: usize) -> Result<Vec<u8>, Error> {
+        bbp::pi_hex_digits(start, count)
+    }
+
     /// Return powers of 10: 100, 10000, 100000000, ...
     pub(crate) fn tenpowers(&mut self, p: usize) -> Result<&[(WordBuf, WordBuf, usize)], Error> {
         if p >= self.tenpowers.len() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(constant = "tenpowers", p, "constant cache miss");
+
             Mantissa::compute_tenpowers(&mut self.tenpowers, p)?;
+            self.evict_to_max_memory(Some(Cache::TenPowers));
         }
 
         Ok(&self.tenpowers)
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_max_memory() {
+        let rm = RoundingMode::ToEven;
+        let p = 10000;
+
+        let mut cc = Consts::new().unwrap();
+        assert!(cc.max_memory().is_none());
+        let initial = cc.memory_used();
+        let pi = cc.pi(p, rm);
+        let e = cc.e(p, rm);
+        assert!(cc.memory_used() > initial + 2 * p / 8);
+
+        cc.clear();
+        assert_eq!(cc.memory_used(), 0);
+        assert_eq!(cc.pi(p, rm), pi);
+
+        // the caches are initialized on the first use
+        const EMPTY: Consts = Consts::empty();
+        let mut cc = EMPTY;
+        assert_eq!(cc.memory_used(), 0);
+        assert_eq!(cc.e(p, rm), e);
+        assert_eq!(
+            cc.euler_gamma(p, rm),
+            Consts::new().unwrap().euler_gamma(p, rm)
+        );
+
+        // values which don't fit in the limit are not retained
+        let mut cc = Consts::with_max_memory(initial + 1000).unwrap();
+        assert_eq!(cc.max_memory(), Some(initial + 1000));
+        assert_eq!(cc.pi(p, rm), pi);
+        assert!(cc.memory_used() <= initial + 1000);
+        assert_eq!(cc.e(p, rm), e);
+        assert_eq!(cc.sqrt_2(p, rm), Consts::new().unwrap().sqrt_2(p, rm));
+        assert_eq!(
+            cc.bernoulli(100, p, rm),
+            Consts::new().unwrap().bernoulli(100, p, rm)
+        );
+        assert!(cc.memory_used() <= initial + 1000);
+
+        // the largest values are evicted first
+        let mut cc = Consts::new().unwrap();
+        cc.pi(p, rm);
+        let max = cc.memory_used() + cc.memory_used() / 2;
+        let mut cc = Consts::with_max_memory(max).unwrap();
+        cc.pi(p, rm);
+        let used = cc.memory_used();
+        assert!(used > initial + p / 8);
+        cc.e(256, rm);
+        assert!(cc.memory_used() > used);
+        let used = cc.memory_used();
+        cc.ln_2(2 * p, rm);
+        assert!(cc.memory_used() < used);
+        assert!(cc.memory_used() > used - initial);
+
+        // tenpowers stay cached until the next lookup
+        let mut cc = Consts::with_max_memory(0).unwrap();
+        let d1 = BigFloat::parse("1.0e+1000", crate::Radix::Dec, p, rm, &mut cc);
+        assert_eq!(
+            d1,
+            BigFloat::parse(
+                "1.0e+1000",
+                crate::Radix::Dec,
+                p,
+                rm,
+                &mut Consts::new().unwrap()
+            )
+        );
+        assert!(cc.pi(p, rm).is_positive());
+        assert_eq!(cc.memory_used(), 0);
+    }
+}
@@ -3,6 +3,7 @@
 use crate::common::util::round_p;
 use crate::defs::{Error, WORD_BIT_SIZE};
 use crate::num::BigFloatNumber;
+use crate::ops::consts::mem_size;
 use crate::RoundingMode;
 
 fn pqr(a: u64, b: u64) -> Result<(BigFloatNumber, BigFloatNumber, BigFloatNumber), Error> {
@@ -94,6 +95,11 @@ impl PiCache {
         Ok(ret)
     }
 
+    /// Returns the number of bytes used by the cached values.
+    pub(crate) fn memory_used(&self) -> usize {
+        mem_size(&[&self.pk, &self.qk, &self.rk, &self.val])
+    }
+
     pub fn new() -> Result<Self, Error> {
         let (p01, q01, r01) = pqr(0, 1)?;
 
@@ -126,6 +132,9 @@ impl PiCache {
                 p_inc = round_p(p_wrk / 5);
             }
 
+            #[cfg(feature = "tracing")]
+            tracing::debug!(constant = "pi", p = k, "constant cache miss");
+
             let mut pk;
             let mut qk;
             let mut rk;
@@ -0,0 +1,184 @@
+//! Constants cache shared between threads.
+
+use crate::ops::consts::Consts;
+use crate::BigFloat;
+use crate::Error;
+use crate::RoundingMode;
+
+use std::sync::Mutex;
+
+/// Constants cache which can be shared between threads, e.g. using a reference or an `Arc`.
+///
+/// `SharedConsts` keeps a pool of `Consts` protected by a mutex.
+/// A thread borrows a constants cache from the pool for the duration of a computation,
+/// and then returns it to the pool with all of the constants computed in the meantime,
+/// so that other threads can reuse them. The mutex is not held during the computation,
+/// so threads don't wait for each other, and the number of caches in the pool
+/// does not exceed the largest number of concurrent computations.
+///
+/// ```
+/// use astro_float_num::{BigFloat, RoundingMode, SharedConsts};
+///
+/// let cc = SharedConsts::new();
+/// let x = BigFloat::from_word(1, 128);
+///
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             let y = cc.with(|cc| x.sin(128, RoundingMode::ToEven, cc));
+///             assert!(y.is_positive());
+///         });
+///     }
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct SharedConsts {
+    pool: Mutex<Vec<Consts>>,
+}
+
+impl SharedConsts {
+    /// Initializes an empty constants cache. The constants caches are created on demand.
+    /// The function can be used in constant expressions, e.g. for initializing a static variable.
+    pub const fn new() -> Self {
+        SharedConsts {
+            pool: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Calls `f` with a constants cache borrowed from the pool, and returns the result of `f`.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for a new constants cache.
+    pub fn try_with<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Consts) -> T,
+    {
+        let mut cc = match self.pool().pop() {
+            Some(cc) => cc,
+            None => Consts::new()?,
+        };
+
+        let ret = f(&mut cc);
+
+        self.pool().push(cc);
+
+        Ok(ret)
+    }
+
+    /// Calls `f` with a constants cache borrowed from the pool, and returns the result of `f`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a new constants cache can't be created because of memory allocation failure.
+    pub fn with<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce(&mut Consts) -> T,
+    {
+        self.try_with(f)
+            .expect("Failed to initialize constants cache")
+    }
+
+    /// Returns the number of constants caches in the pool.
+    pub fn pool_size(&self) -> usize {
+        self.pool().len()
+    }
+
+    /// Returns the value of the pi number with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn pi(&self, p: usize, rm: RoundingMode) -> BigFloat {
+        self.consts(|cc| cc.pi(p, rm))
+    }
+
+    /// Returns the value of the Euler number with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn e(&self, p: usize, rm: RoundingMode) -> BigFloat {
+        self.consts(|cc| cc.e(p, rm))
+    }
+
+    /// Returns the value of the natural logarithm of 2 with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn ln_2(&self, p: usize, rm: RoundingMode) -> BigFloat {
+        self.consts(|cc| cc.ln_2(p, rm))
+    }
+
+    /// Returns the value of the natural logarithm of 10 with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn ln_10(&self, p: usize, rm: RoundingMode) -> BigFloat {
+        self.consts(|cc| cc.ln_10(p, rm))
+    }
+
+    /// Returns the value of the Euler–Mascheroni constant with precision `p` using rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn euler_gamma(&self, p: usize, rm: RoundingMode) -> BigFloat {
+        self.consts(|cc| cc.euler_gamma(p, rm))
+    }
+
+    fn consts<F>(&self, f: F) -> BigFloat
+    where
+        F: FnOnce(&mut Consts) -> BigFloat,
+    {
+        match self.try_with(f) {
+            Ok(v) => v,
+            Err(e) => BigFloat::nan(Some(e)),
+        }
+    }
+
+    fn pool(&self) -> std::sync::MutexGuard<'_, Vec<Consts>> {
+        // a panic in another thread can't leave the pool in an inconsistent state
+        self.pool.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_shared_consts() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Consts>();
+        assert_send_sync::<SharedConsts>();
+
+        let p = 320;
+        let rm = RoundingMode::ToEven;
+        let shared = SharedConsts::new();
+        assert_eq!(shared.pool_size(), 0);
+
+        let mut cc = Consts::new().unwrap();
+        let pi = cc.pi(p, rm);
+        let x = BigFloat::from_word(3, 64).div(&BigFloat::from_word(7, 64), p, rm);
+        let sin = x.sin(p, rm, &mut cc);
+
+        assert_eq!(shared.pi(p, rm), pi);
+        assert_eq!(shared.e(p, rm), cc.e(p, rm));
+        assert_eq!(shared.ln_2(p, rm), cc.ln_2(p, rm));
+        assert_eq!(shared.ln_10(p, rm), cc.ln_10(p, rm));
+        assert_eq!(shared.euler_gamma(p, rm), cc.euler_gamma(p, rm));
+        assert_eq!(shared.pool_size(), 1);
+
+        // the caches are reused by the threads
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for _ in 0..10 {
+                        assert_eq!(shared.with(|cc| x.sin(p, rm, cc)), sin);
+                        assert_eq!(shared.pi(p, rm), pi);
+                    }
+                });
+            }
+        });
+
+        let n = shared.pool_size();
+        assert!((1..=8).contains(&n));
+
+        // nested use borrows another cache
+        let v = shared.with(|cc1| shared.with(|cc2| cc1.pi(p, rm).sub(&cc2.pi(p, rm), p, rm)));
+        assert!(v.is_zero());
+        assert_eq!(shared.pool_size(), n.max(2));
+
+        static STATIC: SharedConsts = SharedConsts::new();
+        assert_eq!(STATIC.pi(p, rm), pi);
+    }
+}
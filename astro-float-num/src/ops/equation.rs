@@ -0,0 +1,707 @@
+//! Quadratic and cubic equations.
+
+use crate::common::consts::ONE;
+use crate::common::consts::THREE;
+use crate::common::consts::TWO;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::defs::EXPONENT_MIN;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::Exponent;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+// The maximum number of Newton's iterations used to polish an approximation of a root.
+const NEWTON_ITER_MAX: usize = 16;
+
+impl BigFloatNumber {
+    /// Computes the real roots of the quadratic equation `self*x^2 + b*x + c = 0` with precision `p`.
+    /// Each root is rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// The roots are returned in ascending order. A double root is returned twice.
+    /// If `self` is zero, the roots of the linear equation `b*x + c = 0` are returned.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: a root or an intermediate result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: all of the coefficients are zero; the precision is incorrect.
+    pub fn solve_quadratic(
+        &self,
+        b: &Self,
+        c: &Self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Vec<Self>, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        let inexact = self.inexact() | b.inexact() | c.inexact();
+
+        let roots = if self.is_zero() {
+            Self::solve_linear(b, c, p, rm)?
+        } else if c.is_zero() {
+            // x * (a*x + b) = 0
+            let mut roots = Self::solve_linear(self, b, p, rm)?;
+            roots.push(Self::new(p)?);
+            roots
+        } else {
+            Self::solve_quadratic_nonzero(self, b, c, p, rm)?
+        };
+
+        Ok(Self::finalize_roots(roots, inexact))
+    }
+
+    /// Computes the real roots of the cubic equation `self*x^3 + b*x^2 + c*x + d = 0` with precision `p`.
+    /// Each root is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// The roots are returned in ascending order. A multiple root is returned as many times as its multiplicity.
+    /// If `self` is zero, the roots of the quadratic equation `b*x^2 + c*x + d = 0` are returned.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: a root or an intermediate result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: all of the coefficients are zero; the precision is incorrect.
+    pub fn solve_cubic(
+        &self,
+        b: &Self,
+        c: &Self,
+        d: &Self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Vec<Self>, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            let mut roots = b.solve_quadratic(c, d, p, rm)?;
+            for r in roots.iter_mut() {
+                r.set_inexact(r.inexact() | self.inexact());
+            }
+            return Ok(roots);
+        }
+
+        let inexact = self.inexact() | b.inexact() | c.inexact() | d.inexact();
+
+        let roots = if d.is_zero() {
+            // x * (a*x^2 + b*x + c) = 0
+            let mut roots = self.solve_quadratic(b, c, p, rm)?;
+            roots.push(Self::new(p)?);
+            roots
+        } else {
+            Self::solve_cubic_nonzero(self, b, c, d, p, rm, cc)?
+        };
+
+        Ok(Self::finalize_roots(roots, inexact))
+    }
+
+    // Roots of a*x + b = 0.
+    fn solve_linear(a: &Self, b: &Self, p: usize, rm: RoundingMode) -> Result<Vec<Self>, Error> {
+        if a.is_zero() {
+            if b.is_zero() {
+                // any number is a root
+                Err(Error::InvalidArgument)
+            } else {
+                Ok(Vec::new())
+            }
+        } else if b.is_zero() {
+            Ok(vec![Self::new(p)?])
+        } else {
+            Ok(vec![b.neg()?.div(a, p, rm)?])
+        }
+    }
+
+    // Sorts the roots in ascending order and sets the inexact flag.
+    fn finalize_roots(mut roots: Vec<Self>, inexact: bool) -> Vec<Self> {
+        for r in roots.iter_mut() {
+            if r.is_zero() {
+                r.set_sign(Sign::Pos);
+            }
+            r.set_inexact(r.inexact() | inexact);
+        }
+
+        roots.sort_by(|x, y| x.cmp(y).cmp(&0));
+
+        roots
+    }
+
+    // Quadratic equation with non-zero coefficients a and c.
+    fn solve_quadratic_nonzero(
+        a: &Self,
+        b: &Self,
+        c: &Self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Vec<Self>, Error> {
+        // the sign of the discriminant is exact, since a non-zero result is never rounded to zero
+        let disc = b.discriminant(a, c, WORD_BIT_SIZE, RoundingMode::FromZero)?;
+
+        if disc.is_negative() {
+            return Ok(Vec::new());
+        }
+
+        if disc.is_zero() {
+            // -b / (2*a)
+            let a2 = a.mul_full_prec(&TWO)?;
+            let root = b.neg()?.div(&a2, p, rm)?;
+            return Ok(vec![root.clone()?, root]);
+        }
+
+        let coef = [a, b, c];
+
+        Self::isolate_simple_roots(&coef, p, rm, &mut |p_x| {
+            // q = -(b + sign(b)*sqrt(D)) / 2, x1 = q / a, x2 = c / q
+            let d = b.discriminant(a, c, p_x, RoundingMode::None)?;
+            let mut sd = d.sqrt(p_x, RoundingMode::None)?;
+
+            if b.is_negative() {
+                sd.inv_sign();
+            }
+
+            let mut q = b.add(&sd, p_x, RoundingMode::None)?;
+            q.set_exponent(q.exponent() - 1);
+            q.inv_sign();
+
+            let x1 = q.div(a, p_x, RoundingMode::None)?;
+            let x2 = c.div(&q, p_x, RoundingMode::None)?;
+
+            Ok(vec![x1, x2])
+        })
+    }
+
+    // Cubic equation with non-zero coefficients a and d.
+    fn solve_cubic_nonzero(
+        a: &Self,
+        b: &Self,
+        c: &Self,
+        d: &Self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Vec<Self>, Error> {
+        let a3 = a.mul_full_prec(&THREE)?;
+
+        // Δ0 = b^2 - 3*a*c
+        let bb = b.mul_full_prec(b)?;
+        let d0 = bb.sub_full_prec(&a3.mul_full_prec(c)?)?;
+
+        // Δ = 18*a*b*c*d - 4*b^3*d + b^2*c^2 - 4*a*c^3 - 27*a^2*d^2
+        let ad = a.mul_full_prec(d)?;
+        let bc = b.mul_full_prec(c)?;
+        let cc3 = c.mul_full_prec(c)?.mul_full_prec(c)?;
+        let bbb = bb.mul_full_prec(b)?;
+
+        let t1 = ad
+            .mul_full_prec(&bc)?
+            .mul_full_prec(&Self::from_usize(18)?)?;
+        let t2 = bbb.mul_full_prec(d)?.mul_full_prec(&Self::from_usize(4)?)?;
+        let t3 = bc.mul_full_prec(&bc)?;
+        let t4 = a
+            .mul_full_prec(&cc3)?
+            .mul_full_prec(&Self::from_usize(4)?)?;
+        let t5 = ad
+            .mul_full_prec(&ad)?
+            .mul_full_prec(&Self::from_usize(27)?)?;
+
+        let disc = t1
+            .sub_full_prec(&t2)?
+            .add_full_prec(&t3)?
+            .sub_full_prec(&t4)?
+            .sub_full_prec(&t5)?;
+
+        if disc.is_zero() {
+            if d0.is_zero() {
+                // triple root -b / (3*a)
+                let root = b.neg()?.div(&a3, p, rm)?;
+                return Ok(vec![root.clone()?, root.clone()?, root]);
+            }
+
+            // double root (9*a*d - b*c) / (2*Δ0)
+            let n1 = ad
+                .mul_full_prec(&Self::from_usize(9)?)?
+                .sub_full_prec(&bc)?;
+            let x1 = n1.div(&d0.mul_full_prec(&TWO)?, p, rm)?;
+
+            // simple root (4*a*b*c - 9*a^2*d - b^3) / (a*Δ0)
+            let n2 = a
+                .mul_full_prec(&bc)?
+                .mul_full_prec(&Self::from_usize(4)?)?
+                .sub_full_prec(&a.mul_full_prec(&ad)?.mul_full_prec(&Self::from_usize(9)?)?)?
+                .sub_full_prec(&bbb)?;
+            let x2 = n2.div(&a.mul_full_prec(&d0)?, p, rm)?;
+
+            return Ok(vec![x1.clone()?, x1, x2]);
+        }
+
+        // depressed cubic t^3 + P*t + Q = 0, x = t - b / (3*a),
+        // P = -Δ0 / (3*a^2), Q = (2*b^3 - 9*a*b*c + 27*a^2*d) / (27*a^3)
+        let p_den = a3.mul_full_prec(a)?;
+        let q_num = bbb
+            .mul_full_prec(&TWO)?
+            .sub_full_prec(&a.mul_full_prec(&bc)?.mul_full_prec(&Self::from_usize(9)?)?)?
+            .add_full_prec(
+                &a.mul_full_prec(&ad)?
+                    .mul_full_prec(&Self::from_usize(27)?)?,
+            )?;
+        let q_den = a3.mul_full_prec(&a3)?.mul_full_prec(&a3)?;
+
+        let three_real = disc.is_positive();
+        let coef = [a, b, c, d];
+
+        Self::isolate_simple_roots(&coef, p, rm, &mut |p_x| {
+            let rm = RoundingMode::None;
+
+            let pp = d0.neg()?.div(&p_den, p_x, rm)?;
+            let qq = q_num.div(&q_den, p_x, rm)?;
+            let shift = b.div(&a3, p_x, rm)?;
+
+            let ts = if three_real {
+                // t(k) = m * cos(φ - 2*π*k/3), m = 2*sqrt(-P/3), φ = acos(3*Q / (P*m)) / 3
+                let mut m = pp.neg()?.div(&THREE, p_x, rm)?.sqrt(p_x, rm)?;
+                m.set_exponent(m.exponent() + 1);
+
+                let mut arg = qq
+                    .mul(&THREE, p_x, rm)?
+                    .div(&pp.mul(&m, p_x, rm)?, p_x, rm)?;
+                if arg.abs_cmp(&ONE) > 0 {
+                    arg = ONE.clone()?;
+                    if qq.is_positive() == pp.is_positive() {
+                        arg.set_sign(Sign::Pos);
+                    } else {
+                        arg.set_sign(Sign::Neg);
+                    }
+                }
+
+                let phi = arg.acos(p_x, rm, cc)?.div(&THREE, p_x, rm)?;
+                let mut pi2_3 = cc.pi_num(p_x, rm)?.div(&THREE, p_x, rm)?;
+                pi2_3.set_exponent(pi2_3.exponent() + 1);
+
+                let mut ts = Vec::new();
+                let mut angle = phi;
+                for _ in 0..3 {
+                    ts.push(m.mul(&angle.cos(p_x, rm, cc)?, p_x, rm)?);
+                    angle = angle.sub(&pi2_3, p_x, rm)?;
+                }
+                ts
+            } else {
+                // t = u - P / (3*u), u = cbrt(-Q/2 - sign(Q)*sqrt(Q^2/4 + P^3/27))
+                let mut q2 = qq.clone()?;
+                q2.set_exponent(q2.exponent() - 1);
+
+                let d1 = q2.mul(&q2, p_x, rm)?;
+                let d2 = pp.mul(&pp, p_x, rm)?.mul(&pp, p_x, rm)?.div(
+                    &Self::from_usize(27)?,
+                    p_x,
+                    rm,
+                )?;
+                let mut s = d1.add(&d2, p_x, rm)?;
+                if s.is_negative() {
+                    s = Self::new(p_x)?;
+                }
+
+                let mut sq = s.sqrt(p_x, rm)?;
+                if qq.is_negative() {
+                    sq.inv_sign();
+                }
+
+                let u = q2.add(&sq, p_x, rm)?.neg()?.cbrt(p_x, rm)?;
+
+                if u.is_zero() {
+                    vec![u]
+                } else {
+                    let v = pp.div(&u.mul(&THREE, p_x, rm)?, p_x, rm)?;
+                    vec![u.sub(&v, p_x, rm)?]
+                }
+            };
+
+            let mut xs = Vec::new();
+            for t in ts {
+                xs.push(t.sub(&shift, p_x, rm)?);
+            }
+
+            Ok(xs)
+        })
+    }
+
+    // Finds all roots of the polynomial with coefficients `coef`, given all of its real roots are simple and non-zero.
+    // `seeds` computes the approximations of all real roots with precision `p_x`.
+    // The approximations are refined, and then each root is enclosed in an interval with the exact sign check.
+    // The precision is increased until all intervals are disjoint and the endpoints of each interval round to the same number.
+    fn isolate_simple_roots(
+        coef: &[&Self],
+        p: usize,
+        rm: RoundingMode,
+        seeds: &mut dyn FnMut(usize) -> Result<Vec<Self>, Error>,
+    ) -> Result<Vec<Self>, Error> {
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = coef.iter().fold(p, |p, c| p.max(c.mantissa_max_bit_len())) + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut xs = Vec::new();
+            for x in seeds(p_x)? {
+                xs.push(Self::newton_polish(coef, x, p_x)?);
+            }
+
+            xs.sort_by(|x, y| x.cmp(y).cmp(&0));
+
+            if let Some(roots) = Self::enclose_roots(coef, &xs, p, rm, p_wrk)? {
+                return Ok(roots);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // Rounds the roots enclosed in the intervals around the approximations `xs`, or returns None if
+    // a root can't be enclosed, or can't be rounded correctly.
+    fn enclose_roots(
+        coef: &[&Self],
+        xs: &[Self],
+        p: usize,
+        rm: RoundingMode,
+        p_wrk: usize,
+    ) -> Result<Option<Vec<Self>>, Error> {
+        let mut roots = Vec::new();
+        let mut prev_hi: Option<Self> = None;
+
+        for x in xs {
+            if x.is_zero() {
+                return Ok(None);
+            }
+
+            // the root is exact if it is representable with a little bit more than p bits
+            let mut y = x.clone()?;
+            y.set_precision(p + WORD_BIT_SIZE, RoundingMode::ToEven)?;
+            y.set_inexact(false);
+
+            let (lo, hi, exact) = if Self::poly_sign(coef, &y)? == 0 {
+                (y.clone()?, y, true)
+            } else {
+                let e = (x.exponent() as isize - p_wrk as isize + 1).max(EXPONENT_MIN as isize);
+                let mut delta = ONE.clone()?;
+                delta.set_exponent(e as Exponent);
+
+                let mut lo = x.sub_full_prec(&delta)?;
+                let mut hi = x.add_full_prec(&delta)?;
+
+                let s_lo = Self::poly_sign(coef, &lo)?;
+                let s_hi = Self::poly_sign(coef, &hi)?;
+
+                if s_lo == 0 {
+                    lo.set_inexact(false);
+                    (lo.clone()?, lo, true)
+                } else if s_hi == 0 {
+                    hi.set_inexact(false);
+                    (hi.clone()?, hi, true)
+                } else if s_lo == s_hi {
+                    return Ok(None);
+                } else {
+                    (lo, hi, false)
+                }
+            };
+
+            if let Some(prev_hi) = &prev_hi {
+                if prev_hi.cmp(&lo) >= 0 {
+                    return Ok(None);
+                }
+            }
+
+            let mut r_lo = lo.clone()?;
+            r_lo.set_precision(p, rm)?;
+
+            if !exact {
+                let mut r_hi = hi.clone()?;
+                r_hi.set_precision(p, rm)?;
+
+                if r_lo.cmp(&r_hi) != 0 {
+                    return Ok(None);
+                }
+
+                r_lo.set_inexact(true);
+            }
+
+            roots.push(r_lo);
+            prev_hi = Some(hi);
+        }
+
+        Ok(Some(roots))
+    }
+
+    // Refines the approximation `x` of a root using Newton's method with precision `p`.
+    fn newton_polish(coef: &[&Self], mut x: Self, p: usize) -> Result<Self, Error> {
+        let rm = RoundingMode::None;
+
+        for _ in 0..NEWTON_ITER_MAX {
+            // Horner's scheme for the polynomial and its derivative
+            let mut f = coef[0].clone()?;
+            let mut df = Self::new(p)?;
+
+            for c in &coef[1..] {
+                df = df.mul(&x, p, rm)?.add(&f, p, rm)?;
+                f = f.mul(&x, p, rm)?.add(c, p, rm)?;
+            }
+
+            if f.is_zero() || df.is_zero() {
+                break;
+            }
+
+            let dx = f.div(&df, p, rm)?;
+            x = x.sub(&dx, p, rm)?;
+
+            if x.is_zero() || (dx.exponent() as isize) < x.exponent() as isize - p as isize {
+                break;
+            }
+        }
+
+        Ok(x)
+    }
+
+    // Exact sign of the polynomial with coefficients `coef` at `x`.
+    fn poly_sign(coef: &[&Self], x: &Self) -> Result<i8, Error> {
+        let mut f = coef[0].clone()?;
+
+        for c in &coef[1..] {
+            f = f.mul_full_prec(x)?.add_full_prec(c)?;
+        }
+
+        Ok(if f.is_zero() {
+            0
+        } else if f.is_positive() {
+            1
+        } else {
+            -1
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::random;
+
+    fn num(f: f64, p: usize) -> BigFloatNumber {
+        BigFloatNumber::from_f64(p, f).unwrap()
+    }
+
+    fn check_roots(roots: &[BigFloatNumber], expected: &[f64]) {
+        assert_eq!(roots.len(), expected.len(), "{:?}", roots);
+        for (r, e) in roots.iter().zip(expected.iter()) {
+            assert_eq!(r.to_f64(), *e, "{:?}", roots);
+        }
+    }
+
+    // Checks that the exact root lies within half ulp of each of the roots.
+    fn check_rounding(coef: &[&BigFloatNumber], roots: &[BigFloatNumber], p: usize) {
+        for r in roots {
+            let mut half_ulp = ONE.clone().unwrap();
+            half_ulp.set_exponent(r.exponent() - p as Exponent);
+
+            let lo = r.sub_full_prec(&half_ulp).unwrap();
+            let hi = r.add_full_prec(&half_ulp).unwrap();
+
+            let s_lo = BigFloatNumber::poly_sign(coef, &lo).unwrap();
+            let s_hi = BigFloatNumber::poly_sign(coef, &hi).unwrap();
+
+            assert!(s_lo != s_hi || s_lo == 0, "{:?}", r);
+        }
+    }
+
+    #[test]
+    fn test_solve_quadratic() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // exact roots
+        let one = num(1.0, p);
+        let d1 = one
+            .solve_quadratic(&num(-3.0, p), &num(2.0, p), p, rm)
+            .unwrap();
+        check_roots(&d1, &[1.0, 2.0]);
+        assert!(d1.iter().all(|r| !r.inexact()));
+
+        let d1 = num(-4.0, p)
+            .solve_quadratic(&num(-4.0, p), &num(3.0, p), p, rm)
+            .unwrap();
+        check_roots(&d1, &[-1.5, 0.5]);
+
+        // double root
+        let d1 = one.solve_quadratic(&num(-2.0, p), &one, p, rm).unwrap();
+        check_roots(&d1, &[1.0, 1.0]);
+
+        // no real roots
+        assert!(one.solve_quadratic(&one, &one, p, rm).unwrap().is_empty());
+
+        // irrational roots
+        let two = num(2.0, p);
+        let zero = BigFloatNumber::new(p).unwrap();
+        for rm in [RoundingMode::ToEven, RoundingMode::Up, RoundingMode::Down] {
+            let d1 = one
+                .solve_quadratic(&zero, &two.neg().unwrap(), p, rm)
+                .unwrap();
+            let rm_neg = match rm {
+                RoundingMode::Up => RoundingMode::Down,
+                RoundingMode::Down => RoundingMode::Up,
+                _ => rm,
+            };
+            let d2 = two.sqrt(p, rm).unwrap();
+            let d3 = two.sqrt(p, rm_neg).unwrap().neg().unwrap();
+            assert_eq!(d1.len(), 2);
+            assert!(d1[0].cmp(&d3) == 0);
+            assert!(d1[1].cmp(&d2) == 0);
+            assert!(d1[0].inexact() && d1[1].inexact());
+        }
+
+        // close roots 1 +- 2^-100
+        let mut eps = ONE.clone().unwrap();
+        eps.set_exponent(-199);
+        let c = one.sub_full_prec(&eps).unwrap();
+        let d1 = one.solve_quadratic(&two.neg().unwrap(), &c, p, rm).unwrap();
+        eps.set_exponent(-99);
+        assert!(d1[0].cmp(&one.sub_full_prec(&eps).unwrap()) == 0);
+        assert!(d1[1].cmp(&one.add_full_prec(&eps).unwrap()) == 0);
+        assert!(d1.iter().all(|r| !r.inexact()));
+
+        // cancellation in the classic formula
+        let b = num(-1.0e30, p);
+        let d1 = one.solve_quadratic(&b, &one, p, rm).unwrap();
+        check_rounding(&[&one, &b, &one], &d1, p);
+        assert!((d1[0].to_f64() - 1.0e-30).abs() < 1.0e-45);
+
+        // degenerate cases
+        check_roots(
+            &one.solve_quadratic(&num(3.0, p), &zero, p, rm).unwrap(),
+            &[-3.0, 0.0],
+        );
+        check_roots(
+            &one.solve_quadratic(&zero, &zero, p, rm).unwrap(),
+            &[0.0, 0.0],
+        );
+        check_roots(
+            &zero
+                .solve_quadratic(&two, &one.neg().unwrap(), p, rm)
+                .unwrap(),
+            &[0.5],
+        );
+        assert!(zero.solve_quadratic(&zero, &one, p, rm).unwrap().is_empty());
+        assert_eq!(
+            zero.solve_quadratic(&zero, &zero, p, rm).unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // inexact flag
+        let mut d2 = one.clone().unwrap();
+        d2.set_inexact(true);
+        let d1 = d2
+            .solve_quadratic(&num(-3.0, p), &num(2.0, p), p, rm)
+            .unwrap();
+        assert!(d1.iter().all(|r| r.inexact()));
+
+        // random coefficients
+        for _ in 0..1000 {
+            let p = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let a = BigFloatNumber::random_normal(p, -20, 20).unwrap();
+            let b = BigFloatNumber::random_normal(p, -20, 20).unwrap();
+            let c = BigFloatNumber::random_normal(p, -20, 20).unwrap();
+
+            let d1 = a.solve_quadratic(&b, &c, p, rm).unwrap();
+            check_rounding(&[&a, &b, &c], &d1, p);
+
+            let disc = b.discriminant(&a, &c, p, rm).unwrap();
+            assert_eq!(d1.len(), if disc.is_negative() { 0 } else { 2 });
+        }
+    }
+
+    #[test]
+    fn test_solve_cubic() {
+        let mut cc = Consts::new().unwrap();
+        let p = 192;
+        let rm = RoundingMode::ToEven;
+
+        let one = num(1.0, p);
+        let zero = BigFloatNumber::new(p).unwrap();
+
+        // (x - 1) * (x - 2) * (x - 3)
+        let d1 = one
+            .solve_cubic(&num(-6.0, p), &num(11.0, p), &num(-6.0, p), p, rm, &mut cc)
+            .unwrap();
+        check_roots(&d1, &[1.0, 2.0, 3.0]);
+        assert!(d1.iter().all(|r| !r.inexact()));
+
+        // (x - 1)^2 * (x + 2)
+        let d1 = one
+            .solve_cubic(&zero, &num(-3.0, p), &num(2.0, p), p, rm, &mut cc)
+            .unwrap();
+        check_roots(&d1, &[-2.0, 1.0, 1.0]);
+
+        // (2*x - 1)^3
+        let d1 = num(8.0, p)
+            .solve_cubic(&num(-12.0, p), &num(6.0, p), &num(-1.0, p), p, rm, &mut cc)
+            .unwrap();
+        check_roots(&d1, &[0.5, 0.5, 0.5]);
+
+        // x^3 - 2
+        let two = num(2.0, p);
+        for rm in [RoundingMode::ToEven, RoundingMode::Up, RoundingMode::Down] {
+            let d1 = one
+                .solve_cubic(&zero, &zero, &two.neg().unwrap(), p, rm, &mut cc)
+                .unwrap();
+            assert_eq!(d1.len(), 1);
+            assert!(d1[0].cmp(&two.cbrt(p, rm).unwrap()) == 0);
+            assert!(d1[0].inexact());
+        }
+
+        // x^3 - 3*x + 1, the roots are 2*cos(2*π/9), 2*cos(4*π/9), 2*cos(8*π/9)
+        let b = num(-3.0, p);
+        let d1 = one.solve_cubic(&zero, &b, &one, p, rm, &mut cc).unwrap();
+        check_rounding(&[&one, &zero, &b, &one], &d1, p);
+        let expected = [-1.8793852415718169, 0.34729635533386066, 1.532088886237956];
+        for (r, e) in d1.iter().zip(expected.iter()) {
+            assert!((r.to_f64() - e).abs() < 1.0e-15);
+        }
+
+        // degenerate cases
+        check_roots(
+            &one.solve_cubic(&zero, &one.neg().unwrap(), &zero, p, rm, &mut cc)
+                .unwrap(),
+            &[-1.0, 0.0, 1.0],
+        );
+        check_roots(
+            &zero
+                .solve_cubic(&one, &num(-3.0, p), &num(2.0, p), p, rm, &mut cc)
+                .unwrap(),
+            &[1.0, 2.0],
+        );
+        assert_eq!(
+            zero.solve_cubic(&zero, &zero, &zero, p, rm, &mut cc)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // random coefficients
+        for _ in 0..500 {
+            let p = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let a = BigFloatNumber::random_normal(p, -20, 20).unwrap();
+            let b = BigFloatNumber::random_normal(p, -20, 20).unwrap();
+            let c = BigFloatNumber::random_normal(p, -20, 20).unwrap();
+            let d = BigFloatNumber::random_normal(p, -20, 20).unwrap();
+
+            let d1 = a.solve_cubic(&b, &c, &d, p, rm, &mut cc).unwrap();
+            check_rounding(&[&a, &b, &c, &d], &d1, p);
+            assert!(d1.len() == 1 || d1.len() == 3);
+        }
+    }
+}
@@ -0,0 +1,430 @@
+//! Error function.
+
+use crate::common::consts::ONE;
+use crate::common::util::log2_ceil;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::defs::EXPONENT_BIT_SIZE;
+use crate::num::BigFloatNumber;
+use crate::Consts;
+use crate::Exponent;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes the error function of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn erf(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+
+        if self.is_zero() {
+            return Self::new2(p, self.sign(), self.inexact());
+        }
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+        x.set_sign(Sign::Pos);
+
+        // erfc(|x|) is too small to affect the result
+        if x.erf_is_one(p)? {
+            let mut ret = Self::from_i8(self.sign().to_int(), p)?;
+            ret = ret.add_correction(true)?;
+            ret.set_precision(p, rm)?;
+            ret.set_inexact(true);
+            return Ok(ret);
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len()) + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = x.erf_positive_arg(p_x, cc)?;
+            ret.set_sign(self.sign());
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    /// Computes the complementary error function `1 - erf(x)` of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// For large positive arguments the result is computed directly, without computing `1 - erf(x)`.
+    /// If the result is too small to be represented, zero is returned.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn erfc(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+
+        if self.is_zero() {
+            let mut ret = Self::from_word(1, p)?;
+            ret.set_inexact(self.inexact());
+            return Ok(ret);
+        }
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+        x.set_sign(Sign::Pos);
+
+        if self.is_negative() {
+            // 1 + erf(|x|) is too close to 2
+            if x.erf_is_one(p)? {
+                let mut ret = Self::from_word(2, p)?;
+                ret = ret.add_correction(true)?;
+                ret.set_precision(p, rm)?;
+                ret.set_inexact(true);
+                return Ok(ret);
+            }
+        } else if self.exponent() > EXPONENT_BIT_SIZE as Exponent {
+            // erfc(x) < e^(-x^2) is too small
+            return Self::new2(p, Sign::Pos, true);
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len()) + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = if self.is_negative() {
+                let d1 = x.erf_positive_arg(p_x, cc)?;
+                d1.add(&ONE, p_x, RoundingMode::None)
+            } else {
+                match x.erfc_positive_arg(p_x, cc) {
+                    Ok(v) => Ok(v),
+                    Err(e) => match e {
+                        Error::ExponentOverflow(_) => {
+                            return Self::new2(p, Sign::Pos, true);
+                        }
+                        Error::DivisionByZero => Err(Error::DivisionByZero),
+                        Error::InvalidArgument => Err(Error::InvalidArgument),
+                        Error::MemoryAllocation => Err(Error::MemoryAllocation),
+                    },
+                }
+            }?;
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // Returns true if erfc(x) < 2^(-p-2) for positive x.
+    // erfc(x) < e^(-x^2) for x >= 1, so it is sufficient to check x^2 >= p + 2.
+    fn erf_is_one(&self, p: usize) -> Result<bool, Error> {
+        let e = self.exponent() as isize;
+
+        if e < 1 {
+            return Ok(false);
+        }
+
+        if 2 * (e - 1) > log2_ceil(p + 2) as isize {
+            return Ok(true);
+        }
+
+        let x2 = self.mul_full_prec(self)?;
+        let thres = Self::from_usize(p + 2)?;
+
+        Ok(x2.cmp(&thres) >= 0)
+    }
+
+    // erf(x) for positive x
+    fn erf_positive_arg(&self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        if self.erf_is_one(p)? {
+            return Self::from_word(1, p)?.add_correction(true);
+        }
+
+        if let Some(d) = self.erfc_asymptotic(p, cc)? {
+            return ONE.sub(&d, p, RoundingMode::None);
+        }
+
+        self.erf_series(p, cc)
+    }
+
+    // erfc(x) for positive x
+    fn erfc_positive_arg(&self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        if let Some(ret) = self.erfc_asymptotic(p, cc)? {
+            return Ok(ret);
+        }
+
+        // 1 - erf(x) loses about x^2*log2(e) bits, log2(e) < 1.5
+        let x2 = self.mul_full_prec(self)?;
+        let n = x2.int_as_usize()?;
+        let p_x = p + n + n / 2 + WORD_BIT_SIZE;
+
+        let d1 = self.erf_series(p_x, cc)?;
+
+        ONE.sub(&d1, p, RoundingMode::None)
+    }
+
+    // erf(x) = 2 / sqrt(π) * e^(-x^2) * (x + 2*x^3/3 + 4*x^5/(3*5) + 8*x^7/(3*5*7) + ...)
+    // All terms are positive, so there is no cancellation.
+    fn erf_series(&self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        let p_x = p + WORD_BIT_SIZE;
+
+        let mut sum = self.clone()?;
+        let mut d2 = ONE.clone()?;
+
+        if (self.exponent() as isize) * 2 >= -(p_x as isize) {
+            let x2 = self.mul_full_prec(self)?;
+
+            let mut d1 = x2.clone()?;
+            d1.set_exponent(d1.exponent() + 1);
+
+            let mut term = self.clone()?;
+            let mut k = 3;
+
+            loop {
+                let d = Self::from_usize(k)?;
+                term = term.mul(&d1, p_x, RoundingMode::None)?;
+                term = term.div(&d, p_x, RoundingMode::None)?;
+
+                sum = sum.add(&term, p_x, RoundingMode::None)?;
+
+                if (term.exponent() as isize) < sum.exponent() as isize - p_x as isize {
+                    break;
+                }
+
+                k += 2;
+            }
+
+            d2 = x2.neg()?.exp(p_x, RoundingMode::None, cc)?;
+        }
+
+        let sqrt_pi = cc
+            .pi_num(p_x, RoundingMode::None)?
+            .sqrt(p_x, RoundingMode::None)?;
+
+        let d3 = sum.mul(&d2, p_x, RoundingMode::None)?;
+        let mut ret = d3.div(&sqrt_pi, p_x, RoundingMode::None)?;
+        ret.set_exponent(ret.exponent() + 1);
+
+        Ok(ret)
+    }
+
+    // erfc(x) = e^(-x^2) / (x * sqrt(π)) * (1 - 1/(2*x^2) + 1*3/(2*x^2)^2 - 1*3*5/(2*x^2)^3 + ...)
+    // The series is asymptotic: its smallest term is about e^(-x^2), so it is used only if x^2 is large enough.
+    fn erfc_asymptotic(&self, p: usize, cc: &mut Consts) -> Result<Option<Self>, Error> {
+        let p_x = p + WORD_BIT_SIZE;
+
+        // ln(2) < 0.7
+        let x2 = self.mul_full_prec(self)?;
+        let thres = Self::from_usize(p_x * 7 / 10 + 4)?;
+
+        if x2.cmp(&thres) < 0 {
+            return Ok(None);
+        }
+
+        let mut d1 = x2.clone()?;
+        d1.set_exponent(d1.exponent() + 1);
+
+        let mut term = ONE.clone()?;
+        let mut sum = ONE.clone()?;
+        let mut k = 1;
+
+        loop {
+            let d = Self::from_usize(k)?;
+
+            if d.cmp(&d1) >= 0 {
+                // terms started to grow
+                return Ok(None);
+            }
+
+            term = term.mul(&d, p_x, RoundingMode::None)?;
+            term = term.div(&d1, p_x, RoundingMode::None)?;
+            term.inv_sign();
+
+            sum = sum.add(&term, p_x, RoundingMode::None)?;
+
+            if (term.exponent() as isize) < -(p_x as isize) {
+                break;
+            }
+
+            k += 2;
+        }
+
+        let d2 = x2.neg()?.exp(p_x, RoundingMode::None, cc)?;
+
+        if d2.is_zero() {
+            return Err(Error::ExponentOverflow(Sign::Neg));
+        }
+
+        let sqrt_pi = cc
+            .pi_num(p_x, RoundingMode::None)?
+            .sqrt(p_x, RoundingMode::None)?;
+
+        let d3 = self.mul(&sqrt_pi, p_x, RoundingMode::None)?;
+        let d4 = d2.mul(&sum, p_x, RoundingMode::None)?;
+
+        d4.div(&d3, p_x, RoundingMode::None).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Radix;
+
+    #[test]
+    fn test_erf() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+        let p = 320;
+
+        // reference values
+        for (x, erf, erfc) in [
+            (
+                "0.75",
+                "7.1115563365351513159893783459141077737420595409653723227813339712503636876404956111079325389009890004353034537e-1",
+                "2.8884436634648486840106216540858922262579404590346276772186660287496363123595043888920674610990109995646965463e-1",
+            ),
+            (
+                "0.000000000000000000013552527156068805425093160010874271392822265625",
+                "1.5292389304404234411725422832362602475567063834507712126944752348101778976855429242220063737221042334032123369e-20",
+                "9.9999999999999999998470761069559576558827457716763739752443293616549228787305524765189822102314457075777993626e-1",
+            ),
+            (
+                "2.5",
+                "9.9959304798255504106043578426002508727965132259628657986087922123090299397015033435803845592123677574143880757e-1",
+                "4.069520174449589395642157399749127203486774037134201391207787690970060298496656419615440787632242585611924286e-4",
+            ),
+            (
+                "5.125",
+                "9.9999999999957654366236637763259198058357197389270378328974186922874116046142659590731382222107799398287771013e-1",
+                "4.2345633763362236740801941642802610729621671025813077125883953857340409268617777892200601712228986753663725458e-13",
+            ),
+            (
+                "-3.25",
+                "-9.9999569722053632487816952410486976340243286493035396282287260698786614182651674756648194345144672183896231887e-1",
+                "1.9999956972205363248781695241048697634024328649303539628228726069878661418265167475664819434514467218389623189",
+            ),
+            (
+                "17.5",
+                "1.0",
+                "3.198863812343480988193469195296936871692130350973013323802561852144915434709125998818864697307562342816344899e-135",
+            ),
+            (
+                "27.0",
+                "1.0",
+                "5.237048923789255685016067682849547090933912547968670799599215945246772879265297700695213606312883941601527057e-319",
+            ),
+        ] {
+            let d1 = BigFloatNumber::parse(x, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+
+            for (f, y) in [
+                (BigFloatNumber::erf as fn(&BigFloatNumber, usize, RoundingMode, &mut Consts) -> Result<BigFloatNumber, Error>, erf),
+                (BigFloatNumber::erfc, erfc),
+            ] {
+                let d2 = f(&d1, p, rm, &mut cc).unwrap();
+                let d3 = BigFloatNumber::parse(y, Radix::Dec, p, rm, &mut cc).unwrap();
+
+                let mut eps = ONE.clone().unwrap();
+                eps.set_exponent(d3.exponent() - p as Exponent + 2);
+
+                assert!(
+                    d2.sub(&d3, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0,
+                    "{} {:?} {:?}",
+                    x,
+                    d2,
+                    d3
+                );
+                assert!(d2.inexact());
+            }
+        }
+
+        // values close to 1 and 2
+        let d1 = BigFloatNumber::from_word(20, p).unwrap();
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        let d2 = d1.erf(p, RoundingMode::ToEven, &mut cc).unwrap();
+        assert!(d2.cmp(&one) == 0);
+        let d2 = d1.erf(p, RoundingMode::Down, &mut cc).unwrap();
+        assert!(d2.cmp(&one) < 0);
+        let d2 = d1
+            .neg()
+            .unwrap()
+            .erf(p, RoundingMode::Down, &mut cc)
+            .unwrap();
+        assert!(d2.cmp(&one.neg().unwrap()) == 0);
+        let d2 = d1
+            .neg()
+            .unwrap()
+            .erfc(p, RoundingMode::Up, &mut cc)
+            .unwrap();
+        assert!(d2.cmp(&BigFloatNumber::from_word(2, p).unwrap()) == 0);
+        let d2 = d1
+            .neg()
+            .unwrap()
+            .erfc(p, RoundingMode::ToZero, &mut cc)
+            .unwrap();
+        assert!(d2.cmp(&BigFloatNumber::from_word(2, p).unwrap()) < 0);
+
+        // underflow
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(40);
+        assert!(d1.erfc(p, rm, &mut cc).unwrap().is_zero());
+        #[cfg(not(feature = "exp64"))]
+        d1.set_exponent(17);
+        #[cfg(feature = "exp64")]
+        d1.set_exponent(32);
+        assert!(d1.erfc(p, rm, &mut cc).unwrap().is_zero());
+
+        // zero
+        let d1 = BigFloatNumber::new(p).unwrap();
+        assert!(d1.erf(p, rm, &mut cc).unwrap().is_zero());
+        assert!(d1.erfc(p, rm, &mut cc).unwrap().cmp(&one) == 0);
+
+        // large precision
+        let p = 3200;
+        let d1 = BigFloatNumber::from_f64(p, 4.75).unwrap();
+        let d2 = d1.erf(p, rm, &mut cc).unwrap();
+        let d3 = d1.erfc(p, rm, &mut cc).unwrap();
+        let d4 = d2.add(&d3, p, RoundingMode::None).unwrap();
+        let mut eps = ONE.clone().unwrap();
+        eps.set_exponent(-(p as Exponent) + 2);
+        assert!(d4.sub(&one, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0);
+    }
+
+    #[ignore]
+    #[test]
+    #[cfg(feature = "std")]
+    fn erf_perf() {
+        let mut cc = Consts::new().unwrap();
+        let mut n = vec![];
+        let p = 160;
+        for _ in 0..10000 {
+            n.push(BigFloatNumber::random_normal(p, -5, 3).unwrap());
+        }
+
+        for _ in 0..5 {
+            let start_time = std::time::Instant::now();
+            for ni in n.iter() {
+                let _f = ni.erf(p, RoundingMode::ToEven, &mut cc).unwrap();
+            }
+            let time = start_time.elapsed();
+            println!("{}", time.as_millis());
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Inverse error function.
+
+use crate::common::consts::ONE;
+use crate::common::consts::TWO;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::Consts;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes the inverse error function of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: |`self`| = 1.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: when |`self`| > 1, or the precision is incorrect.
+    pub fn erfinv(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+
+        if self.is_zero() {
+            return Self::new2(p, self.sign(), self.inexact());
+        }
+
+        let c = self.abs_cmp(&ONE);
+        if c == 0 {
+            return Err(Error::ExponentOverflow(self.sign()));
+        } else if c > 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let mut y = self.clone()?;
+        y.set_inexact(false);
+        y.set_sign(Sign::Pos);
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len()) + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = y.erfinv_positive_arg(p_x, cc)?;
+            ret.set_sign(self.sign());
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    /// Computes the inverse complementary error function of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: `self` is 0 or 2.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: when `self` < 0 or `self` > 2, or the precision is incorrect.
+    pub fn erfcinv(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+
+        if self.is_zero() {
+            return Err(Error::ExponentOverflow(Sign::Pos));
+        }
+
+        if self.is_negative() {
+            return Err(Error::InvalidArgument);
+        }
+
+        let c = self.cmp(&TWO);
+        if c == 0 {
+            return Err(Error::ExponentOverflow(Sign::Neg));
+        } else if c > 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        if self.cmp(&ONE) == 0 {
+            return Self::new2(p, Sign::Pos, self.inexact());
+        }
+
+        let mut q = self.clone()?;
+        q.set_inexact(false);
+
+        // erfcinv(q) = -erfcinv(2 - q), subtraction is exact
+        let sign = if q.cmp(&ONE) > 0 {
+            q = TWO.sub(&q, q.mantissa_max_bit_len(), RoundingMode::None)?;
+            Sign::Neg
+        } else {
+            Sign::Pos
+        };
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len()) + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = q.erfcinv_small_arg(p_x, cc)?;
+            ret.set_sign(sign);
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // erfinv(y) for 0 < y < 1
+    fn erfinv_positive_arg(&self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        if self.exponent() < 0 {
+            Self::erfinv_newton(self, false, p, cc)
+        } else {
+            // erfinv(y) = erfcinv(1 - y), subtraction is exact
+            let q = ONE.sub(self, self.mantissa_max_bit_len(), RoundingMode::None)?;
+            Self::erfinv_newton(&q, true, p, cc)
+        }
+    }
+
+    // erfcinv(q) for 0 < q < 1
+    fn erfcinv_small_arg(&self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        if self.exponent() < 0 {
+            Self::erfinv_newton(self, true, p, cc)
+        } else {
+            // erfcinv(q) = erfinv(1 - q), subtraction is exact
+            let y = ONE.sub(self, self.mantissa_max_bit_len(), RoundingMode::None)?;
+            Self::erfinv_newton(&y, false, p, cc)
+        }
+    }
+
+    // Solves erf(x) = v (or erfc(x) = v if `compl` is true) for 0 < v < 1/2 with Newton's method.
+    // erf is concave and erfc is convex for positive x, therefore the iteration converges monotonically after the first step.
+    fn erfinv_newton(v: &Self, compl: bool, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        let mut p_cur = 2 * WORD_BIT_SIZE;
+
+        let mut x = Self::erfinv_initial(v, compl, p_cur, cc)?;
+
+        // low precision until the convergence becomes quadratic
+        for _ in 0..WORD_BIT_SIZE {
+            let (x1, corr) = x.erfinv_newton_step(v, compl, p_cur, cc)?;
+            x = x1;
+
+            if corr.is_zero()
+                || (corr.exponent() as isize) < x.exponent() as isize - (p_cur / 2) as isize
+            {
+                break;
+            }
+        }
+
+        // each step doubles the number of correct bits
+        while p_cur < p {
+            p_cur = (p_cur * 2).min(p);
+            x.set_precision(p_cur, RoundingMode::None)?;
+            (x, _) = x.erfinv_newton_step(v, compl, p_cur, cc)?;
+        }
+
+        let (ret, _) = x.erfinv_newton_step(v, compl, p, cc)?;
+
+        Ok(ret)
+    }
+
+    // Initial approximation:
+    // erfinv(v) ~ sqrt(π)/2 * (v + π*v^3/12) for small v,
+    // erfcinv(v) ~ sqrt(t - ln(sqrt(π*t))), where t = -ln(v), for small v.
+    fn erfinv_initial(v: &Self, compl: bool, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        let pi = cc.pi_num(p, RoundingMode::None)?;
+
+        if compl {
+            let t = v.ln(p, RoundingMode::None, cc)?.neg()?;
+            let d1 = pi.mul(&t, p, RoundingMode::None)?;
+            let d2 = d1.sqrt(p, RoundingMode::None)?;
+            let d3 = d2.ln(p, RoundingMode::None, cc)?;
+            let d4 = t.sub(&d3, p, RoundingMode::None)?;
+            d4.sqrt(p, RoundingMode::None)
+        } else {
+            let d1 = v.mul(v, p, RoundingMode::None)?;
+            let d2 = d1.mul(&pi, p, RoundingMode::None)?;
+            let d3 = d2.div(&Self::from_word(12, p)?, p, RoundingMode::None)?;
+            let d4 = d3.add(&ONE, p, RoundingMode::None)?;
+            let d5 = d4.mul(v, p, RoundingMode::None)?;
+            let mut ret = d5.mul(&pi.sqrt(p, RoundingMode::None)?, p, RoundingMode::None)?;
+            ret.set_exponent(ret.exponent() - 1);
+            Ok(ret)
+        }
+    }
+
+    // Newton's step: x - (f(x) - v) / f'(x), where f is erf or erfc, and f'(x) = ±2/sqrt(π)*e^(-x^2).
+    // Returns the new value and the correction.
+    fn erfinv_newton_step(
+        &self,
+        v: &Self,
+        compl: bool,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<(Self, Self), Error> {
+        let f = if compl {
+            self.erfc(p, RoundingMode::None, cc)?
+        } else {
+            self.erf(p, RoundingMode::None, cc)?
+        };
+
+        let r = f.sub(v, p, RoundingMode::None)?;
+
+        if r.is_zero() {
+            return Ok((self.clone()?, r));
+        }
+
+        let d1 = self.mul(self, p, RoundingMode::None)?;
+        let d2 = d1.neg()?.exp(p, RoundingMode::None, cc)?;
+        let sqrt_pi = cc
+            .pi_num(p, RoundingMode::None)?
+            .sqrt(p, RoundingMode::None)?;
+        let mut d3 = d2.div(&sqrt_pi, p, RoundingMode::None)?;
+        d3.set_exponent(d3.exponent() + 1);
+
+        if compl {
+            d3.inv_sign();
+        }
+
+        let corr = r.div(&d3, p, RoundingMode::None)?;
+        let ret = self.sub(&corr, p, RoundingMode::None)?;
+
+        Ok((ret, corr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Exponent;
+    use crate::Radix;
+
+    #[test]
+    fn test_erfinv() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+        let p = 320;
+
+        let pow2 = |e: Exponent| {
+            let mut ret = BigFloatNumber::from_word(1, p).unwrap();
+            ret.set_exponent(e + 1);
+            ret
+        };
+
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+
+        // reference values
+        for (x, y, compl) in [
+            (
+                BigFloatNumber::from_f64(p, 0.25).unwrap(),
+                "2.2531205501217810472501401395227755478211844780724675760078289495773822517213895640156465729305040748863646453e-1",
+                false,
+            ),
+            (
+                BigFloatNumber::from_f64(p, -0.875).unwrap(),
+                "-1.0847870400692831413069919378366733233693456237577206415937794500381930738940681326669422093099299984147163288",
+                false,
+            ),
+            (
+                one.sub(&pow2(-200), p, RoundingMode::None).unwrap(),
+                "1.1644679806531725341438916274181958977767558944547609590517975282251448422935806579966350729417109918130716959e+1",
+                false,
+            ),
+            (
+                pow2(-100),
+                "6.9910977464389683419201798423147154379745049971194430980348329613340747862732015429690421887818899000672850424e-31",
+                false,
+            ),
+            (
+                pow2(-1000),
+                "2.6254642422496409747118137884742422890429873251153681411181205384557077857182310642290058676498710479164617606e+1",
+                true,
+            ),
+            (
+                BigFloatNumber::from_f64(p, 1.5).unwrap(),
+                "-4.7693627620446987338141835364313055980896974905947064470388269591938344777464673348869591586998900994803303867e-1",
+                true,
+            ),
+            (
+                BigFloatNumber::from_f64(p, 0.75).unwrap(),
+                "2.2531205501217810472501401395227755478211844780724675760078289495773822517213895640156465729305040748863646453e-1",
+                true,
+            ),
+            (
+                BigFloatNumber::from_f64(p, 0.375).unwrap(),
+                "6.2730734778855896104667247778217734097009726226652949639361264599380897488932220912315661742576854729062595184e-1",
+                true,
+            ),
+        ] {
+            let d2 = if compl {
+                x.erfcinv(p, rm, &mut cc).unwrap()
+            } else {
+                x.erfinv(p, rm, &mut cc).unwrap()
+            };
+            let d3 = BigFloatNumber::parse(y, Radix::Dec, p, rm, &mut cc).unwrap();
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d3.exponent() - p as Exponent + 2);
+
+            assert!(
+                d2.sub(&d3, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0,
+                "{:?} {:?}",
+                d2,
+                d3
+            );
+        }
+
+        // round trip
+        for _ in 0..10 {
+            let mut d1 = BigFloatNumber::random_normal(p, -20, 0).unwrap();
+            if d1.abs_cmp(&ONE) == 0 {
+                continue;
+            }
+            d1.set_precision(p + WORD_BIT_SIZE, RoundingMode::None)
+                .unwrap();
+
+            let d2 = d1.erfinv(p + WORD_BIT_SIZE, rm, &mut cc).unwrap();
+            let d3 = d2.erf(p, rm, &mut cc).unwrap();
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d1.exponent() - p as Exponent + 2);
+
+            assert!(d3.sub(&d1, p, RoundingMode::None).unwrap().abs_cmp(&eps) <= 0);
+        }
+
+        // special values
+        let two = BigFloatNumber::from_word(2, p).unwrap();
+        let zero = BigFloatNumber::new(p).unwrap();
+
+        assert!(zero.erfinv(p, rm, &mut cc).unwrap().is_zero());
+        assert!(one.erfcinv(p, rm, &mut cc).unwrap().is_zero());
+        assert!(one.erfinv(p, rm, &mut cc).unwrap_err() == Error::ExponentOverflow(Sign::Pos));
+        assert!(
+            one.neg().unwrap().erfinv(p, rm, &mut cc).unwrap_err()
+                == Error::ExponentOverflow(Sign::Neg)
+        );
+        assert!(zero.erfcinv(p, rm, &mut cc).unwrap_err() == Error::ExponentOverflow(Sign::Pos));
+        assert!(two.erfcinv(p, rm, &mut cc).unwrap_err() == Error::ExponentOverflow(Sign::Neg));
+        assert!(two.erfinv(p, rm, &mut cc).unwrap_err() == Error::InvalidArgument);
+        assert!(one.neg().unwrap().erfcinv(p, rm, &mut cc).unwrap_err() == Error::InvalidArgument);
+        assert!(
+            two.add(&one, p, rm)
+                .unwrap()
+                .erfcinv(p, rm, &mut cc)
+                .unwrap_err()
+                == Error::InvalidArgument
+        );
+    }
+
+    #[ignore]
+    #[test]
+    #[cfg(feature = "std")]
+    fn erfinv_perf() {
+        let mut cc = Consts::new().unwrap();
+        let mut n = vec![];
+        let p = 160;
+        for _ in 0..10000 {
+            n.push(BigFloatNumber::random_normal(p, -5, 0).unwrap());
+        }
+
+        for _ in 0..5 {
+            let start_time = std::time::Instant::now();
+            for ni in n.iter() {
+                let _f = ni.erfinv(p, RoundingMode::ToEven, &mut cc).unwrap();
+            }
+            let time = start_time.elapsed();
+            println!("{}", time.as_millis());
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! Exponent minus one and logarithm of one plus argument.
+
+use crate::common::consts::ONE;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::defs::Sign;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::ops::util::compute_small_exp;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes `e^self - 1` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// Unlike computing `exp(self) - 1` directly, the result is accurate when `self` is close to zero.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn expm1(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            return Self::new2(p, self.sign(), self.inexact());
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len());
+
+        // e^x - 1 = x + x^2/2! + x^3/3! + ...
+        compute_small_exp!(
+            self,
+            self.exponent() as isize - 1,
+            self.is_negative(),
+            p_wrk,
+            p,
+            rm
+        );
+
+        // e^x - 1 is close to -1 if e^x < 2^(-p_wrk - 2)
+        if self.is_negative() && self.abs_cmp(&Self::from_usize(p_wrk + 2)?) >= 0 {
+            let mut ret = Self::from_word(1, p)?;
+            ret.set_sign(Sign::Neg);
+            let mut ret = ret.add_correction(true)?;
+            ret.set_precision(p, rm)?;
+            return Ok(ret);
+        }
+
+        p_wrk += p_inc;
+
+        loop {
+            let p_x = p_wrk + 6;
+
+            let mut ret = if self.exponent() <= 0 {
+                // e^x - 1 = sh + sh^2 / (1 + sqrt(1 + sh^2)), where sh = sinh(x)
+                let mut x = self.clone()?;
+                x.set_precision(p_x, RoundingMode::None)?;
+
+                let sh = x.sinh_series(p_x, RoundingMode::None)?;
+                let sq = sh.mul(&sh, p_x, RoundingMode::None)?;
+                let d1 = sq.add(&ONE, p_x, RoundingMode::None)?;
+                let d2 = d1.sqrt(p_x, RoundingMode::None)?;
+                let d3 = d2.add(&ONE, p_x, RoundingMode::None)?;
+                let d4 = sq.div(&d3, p_x, RoundingMode::None)?;
+
+                sh.add(&d4, p_x, RoundingMode::None)
+            } else {
+                // no cancellation for |x| >= 1
+                let ex = self.exp(p_x, RoundingMode::None, cc)?;
+
+                ex.sub(&ONE, p_x, RoundingMode::None)
+            }?;
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                return Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    /// Computes `ln(1 + self)` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// Unlike computing `ln(1 + self)` directly, the result is accurate when `self` is close to zero.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - InvalidArgument: `self` is less than or equal to -1, or the precision is incorrect.
+    ///  - MemoryAllocation: failed to allocate memory.
+    pub fn ln_1p(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            return Self::new2(p, self.sign(), self.inexact());
+        }
+
+        if self.is_negative() && self.exponent() > 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len());
+
+        // ln(1 + x) = x - x^2/2 + x^3/3 - ...
+        compute_small_exp!(
+            self,
+            self.exponent() as isize - 1,
+            self.is_positive(),
+            p_wrk,
+            p,
+            rm
+        );
+
+        if self.exponent() <= 0 {
+            // 1 + x is computed exactly for |x| < 1, and the logarithm takes care of cancellation near 1
+            let x1 = self.add_full_prec(&ONE)?;
+            let mut ret = x1.ln(p, rm, cc)?;
+            ret.set_inexact(ret.inexact() | self.inexact());
+            return Ok(ret);
+        }
+
+        p_wrk += p_inc;
+
+        loop {
+            // no cancellation for x >= 1
+            let p_x = p_wrk + 4;
+
+            let x1 = self.add(&ONE, p_x, RoundingMode::None)?;
+            let mut ret = x1.ln(p_x, RoundingMode::None, cc)?;
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                return Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::common::util::random_subnormal;
+    use crate::defs::EXPONENT_MAX;
+    use rand::random;
+
+    #[test]
+    fn test_expm1() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        // compare with e^x - 1 computed with large precision
+        for _ in 0..300 {
+            let p = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let p1 = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let d1 = BigFloatNumber::random_normal(p1, -200, 8).unwrap();
+
+            let d2 = d1.expm1(p, rm, &mut cc).unwrap();
+
+            let p_x = p + 1024;
+            let mut d3 = d1
+                .exp(p_x, RoundingMode::None, &mut cc)
+                .unwrap()
+                .sub(&ONE, p_x, RoundingMode::None)
+                .unwrap();
+            d3.set_precision(p, rm).unwrap();
+
+            assert!(d2.cmp(&d3) == 0);
+            assert!(d2.inexact());
+        }
+
+        let p = 320;
+
+        // small argument
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(-1000);
+        assert!(d1.expm1(p, rm, &mut cc).unwrap().cmp(&d1) == 0);
+        assert!(d1.expm1(p, RoundingMode::Up, &mut cc).unwrap().cmp(&d1) > 0);
+        assert!(d1.expm1(p, RoundingMode::Down, &mut cc).unwrap().cmp(&d1) == 0);
+        let d1 = d1.neg().unwrap();
+        assert!(d1.expm1(p, RoundingMode::Up, &mut cc).unwrap().cmp(&d1) > 0);
+        assert!(d1.expm1(p, RoundingMode::Down, &mut cc).unwrap().cmp(&d1) == 0);
+
+        let d1 = random_subnormal(p);
+        let d2 = d1.expm1(p, rm, &mut cc).unwrap();
+        assert!(d2.cmp(&d1) == 0);
+
+        // large negative argument
+        let d1 = BigFloatNumber::from_word(1000, p).unwrap().neg().unwrap();
+        let d2 = d1.expm1(p, rm, &mut cc).unwrap();
+        let m1 = ONE.neg().unwrap();
+        assert!(d2.cmp(&m1) == 0 && d2.inexact());
+        let d2 = d1.expm1(p, RoundingMode::Up, &mut cc).unwrap();
+        assert!(d2.cmp(&m1) > 0);
+        let d2 = d1.expm1(p, RoundingMode::Down, &mut cc).unwrap();
+        assert!(d2.cmp(&m1) == 0);
+
+        // zero, overflow
+        let zero = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let d2 = zero.expm1(p, rm, &mut cc).unwrap();
+        assert!(d2.is_zero() && d2.is_negative() && !d2.inexact());
+
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(EXPONENT_MAX);
+        assert_eq!(
+            d1.expm1(p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+    }
+
+    #[test]
+    fn test_ln_1p() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        // compare with ln(1 + x) computed with large precision
+        for _ in 0..300 {
+            let p = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let p1 = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let mut d1 = BigFloatNumber::random_normal(p1, -200, 8).unwrap();
+            if d1.is_negative() && d1.exponent() > 0 {
+                d1.set_exponent(0);
+            }
+
+            let d2 = d1.ln_1p(p, rm, &mut cc).unwrap();
+
+            let p_x = p + 1024;
+            let mut d3 = d1
+                .add_full_prec(&ONE)
+                .unwrap()
+                .ln(p_x, RoundingMode::None, &mut cc)
+                .unwrap();
+            d3.set_precision(p, rm).unwrap();
+
+            assert!(d2.cmp(&d3) == 0);
+            assert!(d2.inexact());
+        }
+
+        let p = 320;
+
+        // small argument
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(-1000);
+        assert!(d1.ln_1p(p, rm, &mut cc).unwrap().cmp(&d1) == 0);
+        assert!(d1.ln_1p(p, RoundingMode::Up, &mut cc).unwrap().cmp(&d1) == 0);
+        assert!(d1.ln_1p(p, RoundingMode::Down, &mut cc).unwrap().cmp(&d1) < 0);
+        let d1 = d1.neg().unwrap();
+        assert!(d1.ln_1p(p, RoundingMode::Up, &mut cc).unwrap().cmp(&d1) == 0);
+        assert!(d1.ln_1p(p, RoundingMode::Down, &mut cc).unwrap().cmp(&d1) < 0);
+
+        let d1 = random_subnormal(p);
+        let d2 = d1.ln_1p(p, rm, &mut cc).unwrap();
+        assert!(d2.cmp(&d1) == 0);
+
+        let d2 = ONE.ln_1p(p, rm, &mut cc).unwrap();
+        assert!(d2.cmp(&cc.ln_2_num(p, rm).unwrap()) == 0);
+
+        // domain
+        let zero = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let d2 = zero.ln_1p(p, rm, &mut cc).unwrap();
+        assert!(d2.is_zero() && d2.is_negative() && !d2.inexact());
+
+        let d1 = ONE.neg().unwrap();
+        assert_eq!(
+            d1.ln_1p(p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+        let d1 = BigFloatNumber::from_word(2, p).unwrap().neg().unwrap();
+        assert_eq!(
+            d1.ln_1p(p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        let d1 = BigFloatNumber::max_value(p).unwrap();
+        assert!(d1.ln_1p(p, rm, &mut cc).unwrap().exponent() > 0);
+    }
+}
@@ -0,0 +1,232 @@
+//! Fast paths for the arguments exactly representable in f64.
+//!
+//! The operation is performed using hardware floating point arithmetic, and the result is used only
+//! if it is certified to be exact with an error-free transformation, and the precision of the result is at least 64 bits.
+//! In this case the exact result does not depend on the rounding mode.
+//! Otherwise, the general case algorithm is used.
+
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::num::BigFloatNumber;
+
+// The exponent of a normal f64 number.
+#[cfg(feature = "std")]
+fn f64_exponent(f: f64) -> isize {
+    ((f.to_bits() >> 52) & 0b11111111111) as isize - 0b1111111111
+}
+
+// The error of the product a*b can be computed exactly with fma if e(a) + e(b) >= e_min + 52.
+#[cfg(feature = "std")]
+fn fma_residual_exact(a: f64, b: f64) -> bool {
+    a.is_normal() && b.is_normal() && f64_exponent(a) + f64_exponent(b) >= -1022 + 52
+}
+
+impl BigFloatNumber {
+    // Both arguments as f64, or None if any of the arguments is zero or is not representable in f64.
+    fn f64_args(&self, d2: &Self) -> Option<(f64, f64)> {
+        if self.is_zero() || d2.is_zero() {
+            return None;
+        }
+
+        Some((self.to_f64_exact()?, d2.to_f64_exact()?))
+    }
+
+    // Converts the exact result `f` of an operation to a number with precision `p`.
+    fn from_f64_exact(f: f64, p: usize, inexact: bool) -> Result<Option<Self>, Error> {
+        if round_p(p) < 64 {
+            // the result may not fit in a single 32-bit word
+            return Ok(None);
+        }
+
+        let mut ret = Self::from_f64(round_p(p), f)?;
+        ret.set_inexact(inexact);
+        Ok(Some(ret))
+    }
+
+    /// Computes `self + d2` if `op` is positive, or `self - d2` otherwise, if the result is exact in f64.
+    pub(crate) fn add_sub_fast(&self, d2: &Self, p: usize, op: i8) -> Result<Option<Self>, Error> {
+        if let Some((a, mut b)) = self.f64_args(d2) {
+            if op < 0 {
+                b = -b;
+            }
+
+            let s = a + b;
+
+            if s.is_finite() && s != 0.0 {
+                // two-sum
+                let bb = s - a;
+                let err = (a - (s - bb)) + (b - bb);
+
+                if err == 0.0 {
+                    return Self::from_f64_exact(s, p, self.inexact() || d2.inexact());
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Computes `self * d2` if the result is exact in f64.
+    pub(crate) fn mul_fast(&self, d2: &Self, p: usize) -> Result<Option<Self>, Error> {
+        #[cfg(feature = "std")]
+        if let Some((a, b)) = self.f64_args(d2) {
+            let r = a * b;
+
+            if r.is_normal() && fma_residual_exact(a, b) && a.mul_add(b, -r) == 0.0 {
+                return Self::from_f64_exact(r, p, self.inexact() || d2.inexact());
+            }
+        }
+
+        #[cfg(not(feature = "std"))]
+        let _ = (d2, p);
+
+        Ok(None)
+    }
+
+    /// Computes `self / d2` if the result is exact in f64.
+    pub(crate) fn div_fast(&self, d2: &Self, p: usize) -> Result<Option<Self>, Error> {
+        #[cfg(feature = "std")]
+        if let Some((a, b)) = self.f64_args(d2) {
+            let q = a / b;
+
+            if q.is_normal() && fma_residual_exact(q, b) && q.mul_add(b, -a) == 0.0 {
+                return Self::from_f64_exact(q, p, self.inexact() || d2.inexact());
+            }
+        }
+
+        #[cfg(not(feature = "std"))]
+        let _ = (d2, p);
+
+        Ok(None)
+    }
+
+    /// Computes the square root of `self` if the result is exact in f64.
+    pub(crate) fn sqrt_fast(&self, p: usize) -> Result<Option<Self>, Error> {
+        #[cfg(feature = "std")]
+        if self.is_positive() {
+            if let Some(a) = self.to_f64_exact() {
+                let s = a.sqrt();
+
+                if fma_residual_exact(s, s) && s.mul_add(s, -a) == 0.0 {
+                    return Self::from_f64_exact(s, p, self.inexact());
+                }
+            }
+        }
+
+        #[cfg(not(feature = "std"))]
+        let _ = p;
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{RoundingMode, Sign, WORD_BIT_SIZE};
+    use rand::random;
+
+    fn check_same(d1: &BigFloatNumber, d2: &BigFloatNumber) {
+        assert!(d1.cmp(d2) == 0, "{:?} {:?}", d1, d2);
+        assert_eq!(d1.mantissa_max_bit_len(), d2.mantissa_max_bit_len());
+        assert_eq!(d1.inexact(), d2.inexact());
+        assert_eq!(d1.sign(), d2.sign());
+    }
+
+    fn random_small_f64() -> f64 {
+        // numbers with short mantissas, so that the results are often exact
+        let m = (random::<u32>() >> (random::<u32>() % 32)) as f64;
+        let e = (random::<i32>() % 64) as f64;
+        let s = if random::<bool>() { 1.0 } else { -1.0 };
+        s * m * e.exp2()
+    }
+
+    #[test]
+    fn test_fast_path() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // known results
+        let d1 = BigFloatNumber::from_f64(p, 1.5).unwrap();
+        let d2 = BigFloatNumber::from_f64(p, 0.25).unwrap();
+        let d3 = BigFloatNumber::from_f64(p, 3.0).unwrap();
+
+        assert_eq!(d1.add_sub_fast(&d2, p, 1).unwrap().unwrap().to_f64(), 1.75);
+        assert_eq!(d1.add_sub_fast(&d2, p, -1).unwrap().unwrap().to_f64(), 1.25);
+        assert_eq!(d1.mul_fast(&d2, p).unwrap().unwrap().to_f64(), 0.375);
+        assert_eq!(d1.div_fast(&d2, p).unwrap().unwrap().to_f64(), 6.0);
+        assert_eq!(d2.sqrt_fast(p).unwrap().unwrap().to_f64(), 0.5);
+
+        // inexact results are not computed
+        assert!(d2.div_fast(&d3, p).unwrap().is_none());
+        assert!(d3.sqrt_fast(p).unwrap().is_none());
+        let d4 = BigFloatNumber::from_f64(p, 1.0 + f64::EPSILON).unwrap();
+        assert!(d4.mul_fast(&d4, p).unwrap().is_none());
+        let d5 = BigFloatNumber::from_f64(p, 1.0e-30).unwrap();
+        assert!(d1.add_sub_fast(&d5, p, 1).unwrap().is_none());
+        assert!(d1.add_sub_fast(&d1, p, -1).unwrap().is_none());
+
+        // f64 overflow and underflow
+        let d6 = BigFloatNumber::from_f64(p, 1.0e300).unwrap();
+        assert!(d6.mul_fast(&d6, p).unwrap().is_none());
+        let d7 = BigFloatNumber::from_f64(p, 1.0e-300).unwrap();
+        assert!(d7.mul_fast(&d7, p).unwrap().is_none());
+        assert!(d7.div_fast(&d6, p).unwrap().is_none());
+
+        // not representable in f64
+        let mut d8 = d1.clone().unwrap();
+        d8.set_exponent(2000);
+        assert!(d8.mul_fast(&d1, p).unwrap().is_none());
+        let d9 = d3.reciprocal(p, rm).unwrap();
+        assert!(d9.add_sub_fast(&d1, p, 1).unwrap().is_none());
+
+        // inexact flag and sign
+        let mut d10 = d2.neg().unwrap();
+        d10.set_inexact(true);
+        let d11 = d1.mul_fast(&d10, p).unwrap().unwrap();
+        assert!(d11.inexact());
+        assert_eq!(d11.sign(), Sign::Neg);
+
+        // fast paths give the same results as the general case
+        for _ in 0..10000 {
+            let p = ((random::<usize>() % 5 + 1) * WORD_BIT_SIZE).max(64);
+            let f1 = random_small_f64();
+            let f2 = random_small_f64();
+
+            let mut d1 = BigFloatNumber::from_f64(p, f1).unwrap();
+            let d2 = BigFloatNumber::from_f64(p, f2).unwrap();
+            d1.set_inexact(random());
+
+            for op in [1, -1] {
+                if let Some(d3) = d1.add_sub_fast(&d2, p, op).unwrap() {
+                    let mut d4 = if op > 0 {
+                        d1.add_full_prec(&d2).unwrap()
+                    } else {
+                        d1.sub_full_prec(&d2).unwrap()
+                    };
+                    d4.set_precision(p, rm).unwrap();
+                    check_same(&d3, &d4);
+                }
+            }
+
+            if let Some(d3) = d1.mul_fast(&d2, p).unwrap() {
+                let mut d4 = d1.mul_full_prec(&d2).unwrap();
+                d4.set_precision(p, rm).unwrap();
+                check_same(&d3, &d4);
+            }
+
+            if let Some(d3) = d1.div_fast(&d2, p).unwrap() {
+                let d4 = d3.mul_full_prec(&d2).unwrap();
+                assert!(d4.cmp(&d1) == 0);
+                assert_eq!(d3.inexact(), d1.inexact());
+            }
+
+            let d5 = d1.abs().unwrap();
+            if let Some(d3) = d5.sqrt_fast(p).unwrap() {
+                let d4 = d3.mul_full_prec(&d3).unwrap();
+                assert!(d4.cmp(&d5) == 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Fused operations.
+
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::EXPONENT_MAX;
+use crate::defs::EXPONENT_MIN;
+use crate::num::BigFloatNumber;
+use crate::Exponent;
+use crate::RoundingMode;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes `self * b + c * d` with precision `p` using a single rounding.
+    /// The result is rounded using the rounding mode `rm`.
+    /// The partial products are computed with full precision, so the result is correct even if the products cancel each other.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn fused_mul_add_pair(
+        &self,
+        b: &Self,
+        c: &Self,
+        d: &Self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        let inexact = self.inexact() | b.inexact() | c.inexact() | d.inexact();
+
+        let mut ret = if self.is_zero() || b.is_zero() {
+            c.mul(d, p, rm)?
+        } else if c.is_zero() || d.is_zero() {
+            self.mul(b, p, rm)?
+        } else {
+            Self::fused_mul_add_pair_nonzero(self, b, c, d, 0, p, rm)?
+        };
+
+        ret.set_inexact(ret.inexact() | inexact);
+
+        Ok(ret)
+    }
+
+    /// Computes the discriminant `self^2 - 4 * a * c` of the quadratic equation `a*x^2 + self*x + c = 0`
+    /// with precision `p` using a single rounding. The result is rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn discriminant(
+        &self,
+        a: &Self,
+        c: &Self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        let inexact = self.inexact() | a.inexact() | c.inexact();
+
+        let mut ret = if a.is_zero() || c.is_zero() {
+            self.mul(self, p, rm)?
+        } else if self.is_zero() {
+            let (mut pr, e) = Self::scaled_product(a, &c.neg()?)?;
+            pr.set_precision(p, rm)?;
+            Self::scale_result(pr, e + 2, rm)?
+        } else {
+            Self::fused_mul_add_pair_nonzero(self, self, a, &c.neg()?, 2, p, rm)?
+        };
+
+        ret.set_inexact(ret.inexact() | inexact);
+
+        Ok(ret)
+    }
+
+    // a*b + c*d*2^shift for non-zero arguments.
+    fn fused_mul_add_pair_nonzero(
+        a: &Self,
+        b: &Self,
+        c: &Self,
+        d: &Self,
+        shift: isize,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Self, Error> {
+        // a*b = p1 * 2^e1, c*d*2^shift = p2 * 2^e2, the partial products p1 and p2 are exact
+        let (p1, e1) = Self::scaled_product(a, b)?;
+        let (p2, mut e2) = Self::scaled_product(c, d)?;
+        e2 += shift;
+
+        let (mut hi, mut lo, e_hi, e_lo) =
+            if e1 >= e2 { (p1, p2, e1, e2) } else { (p2, p1, e2, e1) };
+
+        // lo is aligned with hi
+        let e = lo.exponent() as isize - (e_hi - e_lo);
+
+        if e < EXPONENT_MIN as isize {
+            // lo is smaller than the half of the least significant bit of hi and of the result,
+            // so only the sign of lo affects rounding
+            let s = lo.sign();
+            let n = hi.mantissa_max_bit_len().max(p) + 2 * WORD_BIT_SIZE;
+
+            lo = Self::from_word(1, WORD_BIT_SIZE)?;
+            lo.set_sign(s);
+            lo.set_exponent(
+                (hi.exponent() as isize - n as isize).max(EXPONENT_MIN as isize) as Exponent,
+            );
+        } else {
+            lo.set_exponent(e as Exponent);
+        }
+
+        hi = hi.add(&lo, p, rm)?;
+
+        Self::scale_result(hi, e_hi, rm)
+    }
+
+    // Multiplies `x` by 2^e.
+    pub(super) fn scale_result(mut x: Self, e: isize, rm: RoundingMode) -> Result<Self, Error> {
+        if x.is_zero() {
+            return Ok(x);
+        }
+
+        let e = x.exponent() as isize + e;
+
+        if e > EXPONENT_MAX as isize {
+            Err(Error::ExponentOverflow(x.sign()))
+        } else if e < EXPONENT_MIN as isize {
+            x.set_exponent(EXPONENT_MIN);
+            x.subnormalize(e, rm);
+            Ok(x)
+        } else {
+            x.set_exponent(e as Exponent);
+            Ok(x)
+        }
+    }
+
+    // Computes the product of `a` and `b` with full precision, and with the exponents of `a` and `b` removed.
+    // Returns the scaled product and the sum of the exponents.
+    fn scaled_product(a: &Self, b: &Self) -> Result<(Self, isize), Error> {
+        let mut a = a.clone()?;
+        let mut b = b.clone()?;
+
+        let e = a.exponent() as isize + b.exponent() as isize;
+
+        a.set_exponent(0);
+        b.set_exponent(0);
+
+        Ok((a.mul_full_prec(&b)?, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Sign;
+
+    #[test]
+    fn test_fused_mul_add_pair() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // exact cancellation of the products which are not representable with precision p
+        let mut a = BigFloatNumber::from_word(3, p).unwrap();
+        a = a.reciprocal(p, rm).unwrap();
+        let b = a.clone().unwrap();
+        let c = a.neg().unwrap();
+        let d = b.clone().unwrap();
+
+        let ret = a.fused_mul_add_pair(&b, &c, &d, p, rm).unwrap();
+        assert!(ret.is_zero());
+
+        // a*b - (a*b rounded)
+        let ab = a.mul(&b, p, rm).unwrap();
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        let ret = a
+            .fused_mul_add_pair(&b, &ab.neg().unwrap(), &one, p, rm)
+            .unwrap();
+        let ab_full = a.mul_full_prec(&b).unwrap();
+        let mut err = ab_full.sub(&ab, 2 * p, RoundingMode::None).unwrap();
+        err.set_precision(p, rm).unwrap();
+        assert!(!ret.is_zero());
+        assert!(ret.cmp(&err) == 0);
+
+        // determinant of a nearly singular matrix, the products are not representable with precision of one word
+        // | w+1  w   |
+        // | w    w-1 |
+        let p1 = WORD_BIT_SIZE;
+        let w = BigFloatNumber::from_word(crate::WORD_MAX, p1).unwrap();
+        let d1 = w.add(&one, p1, rm).unwrap();
+        let d2 = w.clone().unwrap();
+        let d3 = w.sub(&one, p1, rm).unwrap();
+        let ret = d1
+            .fused_mul_add_pair(&d3, &d2.neg().unwrap(), &d2, p1, rm)
+            .unwrap();
+        assert!(ret.cmp(&one.neg().unwrap()) == 0);
+        assert!(!ret.inexact());
+
+        // products with exponents outside of the exponent range
+        let mut a = BigFloatNumber::from_word(3, p).unwrap();
+        a.set_exponent(EXPONENT_MAX);
+        let mut b = BigFloatNumber::from_word(5, p).unwrap();
+        b.set_exponent(EXPONENT_MAX);
+        let ret = a
+            .fused_mul_add_pair(&b, &a.neg().unwrap(), &b, p, rm)
+            .unwrap();
+        assert!(ret.is_zero());
+
+        assert_eq!(
+            a.fused_mul_add_pair(&b, &one, &one, p, rm).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+
+        // a*b is negligible compared to c*d, but its sign affects rounding
+        let mut tiny = BigFloatNumber::from_word(1, p).unwrap();
+        tiny.set_exponent(EXPONENT_MIN);
+        let ret = tiny
+            .fused_mul_add_pair(&tiny, &one, &one, p, RoundingMode::Up)
+            .unwrap();
+        assert!(ret.cmp(&one) > 0);
+        let ret = tiny
+            .fused_mul_add_pair(&tiny.neg().unwrap(), &one, &one, p, RoundingMode::Down)
+            .unwrap();
+        assert!(ret.cmp(&one) < 0);
+        let ret = tiny
+            .fused_mul_add_pair(&tiny, &one, &one, p, RoundingMode::ToEven)
+            .unwrap();
+        assert!(ret.cmp(&one) == 0);
+        assert!(ret.inexact());
+
+        // zero arguments
+        let zero = BigFloatNumber::new(p).unwrap();
+        let ret = zero.fused_mul_add_pair(&one, &ab, &one, p, rm).unwrap();
+        assert!(ret.cmp(&ab) == 0);
+        let ret = ab.fused_mul_add_pair(&one, &one, &zero, p, rm).unwrap();
+        assert!(ret.cmp(&ab) == 0);
+
+        // random arguments compared to the sum of full precision products
+        for _ in 0..1000 {
+            let p = (rand::random::<usize>() % 10 + 1) * WORD_BIT_SIZE;
+            let a = BigFloatNumber::random_normal(p, -100, 100).unwrap();
+            let b = BigFloatNumber::random_normal(p, -100, 100).unwrap();
+            let c = BigFloatNumber::random_normal(p, -100, 100).unwrap();
+            let d = BigFloatNumber::random_normal(p, -100, 100).unwrap();
+
+            let ret = a.fused_mul_add_pair(&b, &c, &d, p, rm).unwrap();
+
+            let ab = a.mul_full_prec(&b).unwrap();
+            let cd = c.mul_full_prec(&d).unwrap();
+            let r = ab.add(&cd, p, rm).unwrap();
+
+            assert!(ret.cmp(&r) == 0);
+        }
+    }
+
+    #[test]
+    fn test_discriminant() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        let two = BigFloatNumber::from_word(2, p).unwrap();
+        let zero = BigFloatNumber::new(p).unwrap();
+
+        // x^2 - 2x + 1 = 0
+        let ret = two.neg().unwrap().discriminant(&one, &one, p, rm).unwrap();
+        assert!(ret.is_zero());
+
+        // nearly double root: a = 1, b = 2w, c = w^2 - 1, the discriminant is 4
+        let p1 = WORD_BIT_SIZE;
+        let w = BigFloatNumber::from_word(crate::WORD_MAX, p1).unwrap();
+        let b = w.mul(&two, p1, rm).unwrap();
+        let c = w.mul_full_prec(&w).unwrap().sub(&one, 2 * p1, rm).unwrap();
+        let ret = b.discriminant(&one, &c, p1, rm).unwrap();
+        assert!(ret.cmp(&BigFloatNumber::from_word(4, p1).unwrap()) == 0);
+        assert!(!ret.inexact());
+
+        // zero arguments
+        let ret = zero.discriminant(&one, &two, p, rm).unwrap();
+        assert!(ret.cmp(&BigFloatNumber::from_word(8, p).unwrap().neg().unwrap()) == 0);
+        let ret = two.discriminant(&zero, &two, p, rm).unwrap();
+        assert!(ret.cmp(&BigFloatNumber::from_word(4, p).unwrap()) == 0);
+        let ret = zero.discriminant(&zero, &two, p, rm).unwrap();
+        assert!(ret.is_zero());
+
+        // b^2 and 4*a*c are out of the exponent range while b^2 - 4*a*c is not
+        let eb = EXPONENT_MAX / 2 + 50;
+        let mut b = one.clone().unwrap();
+        b.set_exponent(eb);
+        let a = b.clone().unwrap();
+        let mut u = one.clone().unwrap();
+        u.set_exponent(-126);
+        let mut c = one.add(&u, p, rm).unwrap();
+        c.set_exponent(eb - 2);
+        let d = b.discriminant(&a, &c, p, rm).unwrap();
+        let mut r = one.neg().unwrap();
+        r.set_exponent((2 * eb as isize - 128) as Exponent);
+        assert!(d.cmp(&r) == 0);
+
+        assert_eq!(
+            zero.discriminant(&a, &c, p, rm).unwrap_err(),
+            Error::ExponentOverflow(Sign::Neg)
+        );
+
+        // random arguments compared to the full precision computation
+        for _ in 0..1000 {
+            let p = (rand::random::<usize>() % 10 + 1) * WORD_BIT_SIZE;
+            let a = BigFloatNumber::random_normal(p, -100, 100).unwrap();
+            let b = BigFloatNumber::random_normal(p, -100, 100).unwrap();
+            let c = BigFloatNumber::random_normal(p, -100, 100).unwrap();
+
+            let ret = b.discriminant(&a, &c, p, rm).unwrap();
+
+            let bb = b.mul_full_prec(&b).unwrap();
+            let mut ac = a.mul_full_prec(&c).unwrap();
+            ac.set_exponent(ac.exponent() + 2);
+            let r = bb.sub(&ac, p, rm).unwrap();
+
+            assert!(ret.cmp(&r) == 0);
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! Gamma function.
+
+use crate::common::consts::ONE;
+use crate::common::util::log2_ceil;
+use crate::common::util::log2_floor;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::Consts;
+use crate::Exponent;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes the gamma function of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - DivisionByZero: `self` is zero.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: `self` is a negative integer, or the precision is incorrect.
+    pub fn gamma(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+
+        if self.is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+
+        if self.is_int() {
+            if self.is_negative() {
+                return Err(Error::InvalidArgument);
+            }
+
+            if let Some(ret) = self.gamma_int(p, rm)? {
+                return Ok(ret);
+            }
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len()) + p_inc;
+
+        loop {
+            let p_x = p_wrk + WORD_BIT_SIZE;
+
+            let mut ret = if self.is_negative() {
+                match self.gamma_negative_arg(p_x, cc) {
+                    Ok(v) => Ok(v),
+                    Err(e) => match e {
+                        Error::ExponentOverflow(_) => {
+                            // Γ(1 - x) is too large, the result is too close to zero
+                            let n = self.abs()?.int()?;
+                            let sign =
+                                if !n.is_zero() && n.is_odd_int() { Sign::Pos } else { Sign::Neg };
+                            return Self::new2(p, sign, true);
+                        }
+                        Error::DivisionByZero => Err(Error::DivisionByZero),
+                        Error::InvalidArgument => Err(Error::InvalidArgument),
+                        Error::MemoryAllocation => Err(Error::MemoryAllocation),
+                    },
+                }
+            } else {
+                self.gamma_positive_arg(p_x, cc)
+            }?;
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // Computes (n-1)! for a positive integer n if the result fits precision `p`, otherwise returns None.
+    fn gamma_int(&self, p: usize, rm: RoundingMode) -> Result<Option<Self>, Error> {
+        let n = match self.int_as_usize() {
+            Ok(n) => n,
+            Err(_) => return Ok(None),
+        };
+
+        let mut ret = Self::from_word(1, p)?;
+
+        for k in 2..n {
+            let d = Self::from_usize(k)?;
+
+            ret = ret.mul(&d, p, RoundingMode::None)?;
+
+            if ret.inexact() {
+                return Ok(None);
+            }
+        }
+
+        ret.set_precision(p, rm)?;
+        ret.set_inexact(self.inexact());
+
+        Ok(Some(ret))
+    }
+
+    // Γ(x) = π / (sin(π*x) * Γ(1 - x))
+    fn gamma_negative_arg(&self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+        x.set_sign(Sign::Pos);
+
+        let p_x = p + 4;
+
+        // sin(π*x) = -(-1)^n * sin(π*f), where n and f are the integer and the fractional parts of |x|.
+        let n = x.int()?;
+        let mut f = x.fract()?;
+
+        let mut half = ONE.clone()?;
+        half.set_exponent(0);
+
+        if f.cmp(&half) > 0 {
+            // sin(π*f) = sin(π*(1 - f)), subtraction is exact
+            f = ONE.sub(&f, f.mantissa_max_bit_len(), RoundingMode::None)?;
+        }
+
+        let pi = cc.pi_num(p_x, RoundingMode::None)?;
+        let d1 = pi.mul(&f, p_x, RoundingMode::None)?;
+        let d2 = d1.sin(p_x, RoundingMode::None, cc)?;
+
+        let y = x.add(&ONE, x.mantissa_max_bit_len().max(p_x), RoundingMode::None)?;
+        let d3 = y.gamma_positive_arg(p_x, cc)?;
+
+        let d4 = d2.mul(&d3, p_x, RoundingMode::None)?;
+        let mut ret = pi.div(&d4, p_x, RoundingMode::None)?;
+
+        if n.is_zero() || !n.is_odd_int() {
+            ret.inv_sign();
+        }
+
+        Ok(ret)
+    }
+
+    // Γ(x) = Γ(x + n) / (x * (x + 1) * ... * (x + n - 1)), where Γ(x + n) is computed using Stirling's series.
+    fn gamma_positive_arg(&self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        debug_assert!(self.is_positive());
+
+        // Γ(x) > 2^(EXPONENT_MAX) for any x >= 2^32
+        if self.exponent() > 32 {
+            return Err(Error::ExponentOverflow(Sign::Pos));
+        }
+
+        let p_x = p + 4;
+
+        // the series gives the precision p when the argument is larger than p/2
+        let thres = p / 2;
+        let x_int = self.int_as_usize()?;
+
+        let (y, d) = if x_int < thres {
+            let n = thres - x_int;
+
+            let mut prod = self.clone()?;
+            let mut y = self.clone()?;
+
+            for _ in 1..n {
+                y = y.add(&ONE, p_x, RoundingMode::None)?;
+                prod = prod.mul(&y, p_x, RoundingMode::None)?;
+            }
+
+            y = y.add(&ONE, p_x, RoundingMode::None)?;
+
+            (y, Some(prod))
+        } else {
+            (self.clone()?, None)
+        };
+
+        let lg = Self::ln_gamma_stirling(&y, p_x, cc)?;
+        let ret = lg.exp(p_x, RoundingMode::None, cc)?;
+
+        if let Some(d) = d {
+            ret.div(&d, p_x, RoundingMode::None)
+        } else {
+            Ok(ret)
+        }
+    }
+
+    // Computes ln(Γ(y)) with the absolute error less than 2^(-p) using Stirling's series:
+    // ln(Γ(y)) = (y - 1/2)*ln(y) - y + ln(2*π)/2 + sum(B(2*k) / (2*k*(2*k - 1)*y^(2*k-1))), k = 1, 2, ...
+    // `y` must not be less than p/2.
+    fn ln_gamma_stirling(y: &Self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        let ye = y.exponent() as usize;
+        let yi = y.int_as_usize()?;
+
+        // |B(2*k) / (2*k*(2*k - 1)*y^(2*k-1))| < 4 * (2*k - 2)! * y / (2*π*y)^(2*k) < 4 * (2*k - 2)! * y / (6*y)^(2*k)
+        let lg = log2_floor(6 * yi) as isize;
+        let mut l = 2 + ye as isize - 2 * lg;
+        let mut n = 1;
+
+        while l > -(p as isize) {
+            l += log2_ceil(2 * n * (2 * n - 1)) as isize - 2 * lg;
+            n += 1;
+        }
+
+        // y*ln(y) requires additional precision to preserve the absolute error.
+        let p_x = p + ye + 2 * log2_ceil(n) + WORD_BIT_SIZE;
+
+        let t = cc.tangent_numbers(n)?;
+
+        // B(2*k) / (2*k*(2*k - 1)) = (-1)^(k-1) * T(k) / ((2*k - 1)*4^k*(4^k - 1))
+        let y2 = y.mul(y, p_x, RoundingMode::None)?;
+        let mut r = y.reciprocal(p_x, RoundingMode::None)?;
+        let mut sum = Self::new(p_x)?;
+
+        for (i, tk) in t.iter().enumerate() {
+            let k = i + 1;
+
+            let mut d1 = ONE.clone()?;
+            d1.set_exponent((2 * k + 1) as Exponent);
+            let d1 = d1.sub(&ONE, p_x, RoundingMode::None)?;
+            let d2 = Self::from_usize(2 * k - 1)?;
+            let d3 = d1.mul(&d2, p_x, RoundingMode::None)?;
+
+            let d4 = tk.mul(&r, p_x, RoundingMode::None)?;
+            let mut d5 = d4.div(&d3, p_x, RoundingMode::None)?;
+            d5.set_exponent(d5.exponent() - 2 * k as Exponent);
+
+            if k & 1 == 0 {
+                d5.inv_sign();
+            }
+
+            sum = sum.add(&d5, p_x, RoundingMode::None)?;
+
+            r = r.div(&y2, p_x, RoundingMode::None)?;
+        }
+
+        // (y - 1/2)*ln(y) - y + ln(2*π)/2
+        let mut half = ONE.clone()?;
+        half.set_exponent(0);
+
+        let d1 = y.sub(&half, p_x, RoundingMode::None)?;
+        let d2 = y.ln(p_x, RoundingMode::None, cc)?;
+        let d3 = d1.mul(&d2, p_x, RoundingMode::None)?;
+        let d4 = d3.sub(y, p_x, RoundingMode::None)?;
+
+        let mut pi = cc.pi_num(p_x, RoundingMode::None)?;
+        pi.set_exponent(pi.exponent() + 1);
+        let mut d5 = pi.ln(p_x, RoundingMode::None, cc)?;
+        d5.set_exponent(d5.exponent() - 1);
+
+        let d6 = d4.add(&d5, p_x, RoundingMode::None)?;
+
+        d6.add(&sum, p_x, RoundingMode::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Radix;
+
+    #[test]
+    fn test_gamma() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        // integers
+        let p = 128;
+        for (n, f) in [(1, 1), (2, 1), (3, 2), (4, 6), (5, 24), (11, 3628800)] {
+            let d1 = BigFloatNumber::from_word(n, p).unwrap();
+            let d2 = d1.gamma(p, RoundingMode::Up, &mut cc).unwrap();
+            assert!(d2.cmp(&BigFloatNumber::from_word(f, p).unwrap()) == 0);
+            assert!(!d2.inexact());
+        }
+
+        let d1 = BigFloatNumber::from_word(100, p).unwrap();
+        let d2 = d1.gamma(p, rm, &mut cc).unwrap();
+        let d3 = BigFloatNumber::parse(
+            "933262154439441526816992388562667004907159682643816214685929638952175999932299156089414639761565182862536979208272237582511852109168640000000000000000000000",
+            Radix::Dec,
+            p,
+            rm,
+            &mut cc,
+        )
+        .unwrap();
+        assert!(d2.cmp(&d3) == 0);
+
+        // Γ(1/2) = sqrt(π)
+        let p = 320;
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(0);
+        let d2 = d1.gamma(p, rm, &mut cc).unwrap();
+        let d3 = cc
+            .pi_num(p + WORD_BIT_SIZE, RoundingMode::None)
+            .unwrap()
+            .sqrt(p, rm)
+            .unwrap();
+        assert!(d2.cmp(&d3) == 0);
+
+        // reference values
+        for (x, y) in [
+            (
+                "1.00000000000000000000000000000000000000000071746481373430634031294954664443705921549411424077607513961896135157303433516062796115875244140625",
+                "9.9999999999999999999999999999999999999999958586807049689794028644330079808990313428385555048464990673634582216e-1",
+            ),
+            (
+                "0.000000000000000000013552527156068805425093160010874271392822265625",
+                "7.3786976294838206463422784335098467139406892118153152578559292279995335631410483022750866179127187935587279914e+19",
+            ),
+            (
+                "0.75",
+                "1.2254167024651776451290983033628905268512392481080706112301189382898228884267983572371723762149150665821733802",
+            ),
+            (
+                "7.125",
+                "9.1099848872243460769908738898998589787902759406130851521351146491231464128066697672722983894183484237239633246e+2",
+            ),
+            (
+                "123.375",
+                "5.9956953069537656279991615487477067199283493055998300397718205434394338623979442772869059680711456907444607996e+203",
+            ),
+            (
+                "-0.5",
+                "-3.5449077018110320545963349666822903655950989122447742564276155797058225691820643627499013134770893308324536472",
+            ),
+            (
+                "-20.75",
+                "-1.8698500011951957270210633929904142802653736132810828183186475524656178612136691413368060159785180290532090461e-19",
+            ),
+            (
+                "-3.0000000000000000000000000000007888609052210118054117285652827862296732064351090230047702789306640625",
+                "2.1127510003803823358278386756245731372192803325454552979279181285304997715250179972635132530386681131284363055e+29",
+            ),
+        ] {
+            let d1 = BigFloatNumber::parse(x, Radix::Dec, p, RoundingMode::None, &mut cc).unwrap();
+            let d2 = d1.gamma(p, rm, &mut cc).unwrap();
+            let d3 = BigFloatNumber::parse(y, Radix::Dec, p, rm, &mut cc).unwrap();
+
+            let mut eps = ONE.clone().unwrap();
+            eps.set_exponent(d3.exponent() - p as Exponent + 2);
+
+            assert!(
+                d2.sub(&d3, p, RoundingMode::None)
+                    .unwrap()
+                    .abs()
+                    .unwrap()
+                    .cmp(&eps)
+                    < 0,
+                "{}",
+                x
+            );
+        }
+
+        // poles
+        let d1 = BigFloatNumber::new(p).unwrap();
+        assert!(matches!(
+            d1.gamma(p, rm, &mut cc),
+            Err(Error::DivisionByZero)
+        ));
+
+        let d1 = BigFloatNumber::from_i8(-3, p).unwrap();
+        assert!(matches!(
+            d1.gamma(p, rm, &mut cc),
+            Err(Error::InvalidArgument)
+        ));
+
+        // overflow and underflow
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(63);
+        assert!(matches!(
+            d1.gamma(p, rm, &mut cc),
+            Err(Error::ExponentOverflow(Sign::Pos))
+        ));
+
+        let d1 = BigFloatNumber::parse(
+            "-4611686018427387904.5",
+            Radix::Dec,
+            p,
+            RoundingMode::None,
+            &mut cc,
+        )
+        .unwrap();
+        assert!(d1.gamma(p, rm, &mut cc).unwrap().is_zero());
+    }
+
+    #[ignore]
+    #[test]
+    #[cfg(feature = "std")]
+    fn gamma_perf() {
+        let p = 160;
+        let mut cc = Consts::new().unwrap();
+        let mut n = vec![];
+        for _ in 0..1000 {
+            n.push(BigFloatNumber::random_normal(p, 0, 5).unwrap());
+        }
+
+        for _ in 0..5 {
+            let start_time = std::time::Instant::now();
+            for ni in n.iter() {
+                let _f = ni.gamma(p, RoundingMode::ToEven, &mut cc).unwrap();
+            }
+            let time = start_time.elapsed();
+            println!("{}", time.as_millis());
+        }
+    }
+}
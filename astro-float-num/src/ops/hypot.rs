@@ -0,0 +1,145 @@
+//! Euclidean norm.
+
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::Exponent;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes `sqrt(self^2 + d2^2)` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The arguments are scaled internally, so the intermediate results never overflow or underflow,
+    /// and the result is computed using a single rounding.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn hypot(&self, d2: &Self, p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        let inexact = self.inexact() | d2.inexact();
+
+        let (x, y) = if self.abs_cmp(d2) >= 0 { (self, d2) } else { (d2, self) };
+
+        if y.is_zero() {
+            let mut ret = x.clone()?;
+            ret.set_sign(Sign::Pos);
+            ret.set_precision(p, rm)?;
+            ret.set_inexact(ret.inexact() | inexact);
+            return Ok(ret);
+        }
+
+        // |x| >= |y|, scale x to the exponent 0
+        let e = x.exponent() as isize;
+
+        let mut xs = x.clone()?;
+        xs.set_exponent(0);
+        xs.set_sign(Sign::Pos);
+
+        let mut ys = y.clone()?;
+        ys.set_sign(Sign::Pos);
+
+        // if y is negligible, then it only contributes to the rounding as a small positive value
+        let thres = p.max(x.mantissa_max_bit_len()) as isize + 2 * WORD_BIT_SIZE as isize;
+        let ey = (y.exponent() as isize - e).max(-thres);
+        ys.set_exponent(ey as Exponent);
+
+        let xx = xs.mul_full_prec(&xs)?;
+        let yy = ys.mul_full_prec(&ys)?;
+        let s = xx.add_full_prec(&yy)?;
+
+        let mut ret = Self::scale_result(s.sqrt(p, rm)?, e, rm)?;
+
+        ret.set_inexact(ret.inexact() | inexact);
+
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::defs::{EXPONENT_MAX, EXPONENT_MIN};
+    use rand::random;
+
+    #[test]
+    fn test_hypot() {
+        let p = 192;
+        let rm = RoundingMode::ToEven;
+
+        // exact results
+        let d1 = BigFloatNumber::from_word(3, p).unwrap();
+        let d2 = BigFloatNumber::from_word(4, p).unwrap().neg().unwrap();
+        let d3 = d1.hypot(&d2, p, rm).unwrap();
+        assert!(d3.cmp(&BigFloatNumber::from_word(5, p).unwrap()) == 0);
+        assert!(!d3.inexact());
+
+        let zero = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let d3 = zero.hypot(&d2, p, rm).unwrap();
+        assert!(d3.cmp(&BigFloatNumber::from_word(4, p).unwrap()) == 0);
+        let d3 = zero.hypot(&zero, p, rm).unwrap();
+        assert!(d3.is_zero() && d3.is_positive());
+
+        // no overflow or underflow of the intermediate results
+        let mut d1 = BigFloatNumber::from_word(3, p).unwrap();
+        let mut d2 = BigFloatNumber::from_word(4, p).unwrap();
+        for e in [EXPONENT_MAX - 3, EXPONENT_MIN + 3] {
+            d1.set_exponent(e - 1);
+            d2.set_exponent(e);
+            let d3 = d1.hypot(&d2, p, rm).unwrap();
+            let mut d4 = BigFloatNumber::from_word(5, p).unwrap();
+            d4.set_exponent(e);
+            assert!(d3.cmp(&d4) == 0);
+        }
+
+        let d1 = BigFloatNumber::min_positive(p).unwrap();
+        let d3 = d1.hypot(&d1, p, rm).unwrap();
+        assert!(d3.cmp(&d1) == 0 && d3.inexact());
+        let d3 = d1.hypot(&d1, p, RoundingMode::Up).unwrap();
+        assert!(d3.is_subnormal() && d3.cmp(&d1) > 0);
+
+        let d1 = BigFloatNumber::max_value(p).unwrap();
+        assert_eq!(
+            d1.hypot(&d1, p, rm).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+
+        // negligible argument
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        let d1 = BigFloatNumber::min_positive(p).unwrap();
+        let d3 = one.hypot(&d1, p, rm).unwrap();
+        assert!(d3.cmp(&one) == 0);
+        assert!(d3.inexact());
+        let d3 = d1.hypot(&one, p, RoundingMode::Up).unwrap();
+        assert!(d3.cmp(&one) > 0);
+        let d3 = d1.hypot(&one, p, RoundingMode::Down).unwrap();
+        assert!(d3.cmp(&one) == 0);
+
+        // single rounding
+        for _ in 0..1000 {
+            let p1 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+            let p2 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+            let d1 = BigFloatNumber::random_normal(p1, -100, 100).unwrap();
+            let d2 = BigFloatNumber::random_normal(p2, -100, 100).unwrap();
+
+            let d3 = d1.hypot(&d2, p, rm).unwrap();
+            let d4 = d1
+                .mul_full_prec(&d1)
+                .unwrap()
+                .add_full_prec(&d2.mul_full_prec(&d2).unwrap())
+                .unwrap()
+                .sqrt(p, rm)
+                .unwrap();
+
+            assert!(d3.cmp(&d4) == 0);
+            assert!(d3.inexact() == d4.inexact());
+        }
+    }
+}
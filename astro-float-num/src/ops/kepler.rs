@@ -0,0 +1,286 @@
+//! Kepler's equation.
+
+use crate::common::consts::ONE;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Solves Kepler's equation `M = E - e*sin(E)` for the eccentric anomaly `E`,
+    /// where `self` is the mean anomaly `M`, and `e` is the eccentricity, `0 <= e < 1`.
+    /// The result is computed with precision `p` and rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// The error of an approximation `x` is certified by the residual: since the derivative `1 - e*cos(x)`
+    /// is not smaller than `1 - e`, the distance to the solution does not exceed `|x - e*sin(x) - M| / (1 - e)`.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: `e` is negative, or `e` >= 1; the precision is incorrect.
+    pub fn solve_kepler(
+        &self,
+        e: &Self,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if e.is_negative() || e.cmp(&ONE) >= 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        if self.is_zero() {
+            return Self::new2(p, self.sign(), self.inexact() | e.inexact());
+        }
+
+        if e.is_zero() {
+            let mut ret = self.clone()?;
+            ret.set_precision(p, rm)?;
+            ret.set_inexact(ret.inexact() | e.inexact());
+            return Ok(ret);
+        }
+
+        // lower bound of 1 - e
+        let de = ONE.sub(e, WORD_BIT_SIZE, RoundingMode::Down)?;
+        let de_exp = de.exponent() as isize - 1;
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len()) + p_inc;
+
+        loop {
+            // the condition number of the solution is at most 1 / (1 - e)
+            let p_x = p_wrk + (-de_exp) as usize + WORD_BIT_SIZE;
+
+            let mut ret = self.kepler_newton(e, p_x, cc)?;
+
+            if !ret.is_zero() {
+                let s = ret.kepler_certified_bits(self, e, de_exp, p_x, cc)?;
+
+                // the roundability check requires the number of correct bits to be aligned to the word size
+                let s = s / WORD_BIT_SIZE as isize * WORD_BIT_SIZE as isize;
+
+                if s >= (p + WORD_BIT_SIZE) as isize {
+                    ret.set_inexact(true);
+                    if ret.try_set_precision(p, rm, s as usize)? {
+                        ret.set_inexact(ret.inexact() | self.inexact() | e.inexact());
+                        return Ok(ret);
+                    }
+                }
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // Approximates the solution of Kepler's equation with precision `p` using Newton's method.
+    // f(x) = x - e*sin(x) - m is increasing, and the solution is in [m - e, m + e], since |e*sin(x)| <= e.
+    // The enclosure of the solution is narrowed on each step, and the step falls back to bisection
+    // if Newton's step leaves the enclosure.
+    fn kepler_newton(&self, e: &Self, p: usize, cc: &mut Consts) -> Result<Self, Error> {
+        let mut lo = self.sub(e, p, RoundingMode::Down)?;
+        let mut hi = self.add(e, p, RoundingMode::Up)?;
+
+        // initial approximation: m + e*sin(m)
+        let d1 = self.sin(p, RoundingMode::None, cc)?;
+        let d2 = d1.mul(e, p, RoundingMode::None)?;
+        let mut x = self.add(&d2, p, RoundingMode::None)?;
+
+        let mut close = false;
+
+        // the enclosure is at least halved on every step which falls back to bisection
+        for _ in 0..2 * p + WORD_BIT_SIZE {
+            let d1 = x.sin(p, RoundingMode::None, cc)?;
+            let d2 = d1.mul(e, p, RoundingMode::None)?;
+            let d3 = x.sub(&d2, p, RoundingMode::None)?;
+            let r = d3.sub(self, p, RoundingMode::None)?;
+
+            if r.is_zero() {
+                break;
+            }
+
+            if r.is_positive() {
+                hi = x.clone()?;
+            } else {
+                lo = x.clone()?;
+            }
+
+            let d1 = x.cos(p, RoundingMode::None, cc)?;
+            let d2 = d1.mul(e, p, RoundingMode::None)?;
+            let d3 = ONE.sub(&d2, p, RoundingMode::None)?;
+            let corr = r.div(&d3, p, RoundingMode::None)?;
+
+            let mut x1 = x.sub(&corr, p, RoundingMode::None)?;
+
+            if x1.cmp(&lo) <= 0 || x1.cmp(&hi) >= 0 {
+                x1 = lo.add(&hi, p, RoundingMode::None)?;
+                if !x1.is_zero() {
+                    x1.set_exponent(x1.exponent() - 1);
+                }
+
+                if x1.cmp(&lo) <= 0 || x1.cmp(&hi) >= 0 {
+                    break;
+                }
+
+                close = false;
+            } else if close {
+                // one more step after the convergence became quadratic
+                x = x1;
+                break;
+            } else if x1.is_zero()
+                || corr.is_zero()
+                || (corr.exponent() as isize) < x1.exponent() as isize - (p / 2) as isize
+            {
+                close = true;
+            }
+
+            x = x1;
+        }
+
+        Ok(x)
+    }
+
+    // Returns the number of correct bits of the approximation `self` of the solution of Kepler's equation
+    // certified by the residual computed with precision `p`.
+    fn kepler_certified_bits(
+        &self,
+        m: &Self,
+        e: &Self,
+        de_exp: isize,
+        p: usize,
+        cc: &mut Consts,
+    ) -> Result<isize, Error> {
+        let d1 = self.sin(p, RoundingMode::None, cc)?;
+        let d2 = d1.mul(e, p, RoundingMode::None)?;
+        let d3 = self.sub(&d2, p, RoundingMode::None)?;
+        let r = d3.sub(m, p, RoundingMode::None)?;
+
+        // the error of the residual is a few units of the last place of the largest term
+        let emax = (self.exponent() as isize).max(m.exponent() as isize).max(1);
+        let mut err = emax - p as isize + 3;
+
+        if !r.is_zero() {
+            err = err.max(r.exponent() as isize) + 1;
+        }
+
+        // |x - E| <= |r| / (1 - e)
+        Ok(self.exponent() as isize - (err - de_exp) - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Exponent;
+    use crate::Sign;
+
+    #[test]
+    fn test_solve_kepler() {
+        let mut cc = Consts::new().unwrap();
+        let p = 320;
+
+        // the solution satisfies the equation
+        for _ in 0..20 {
+            let m = BigFloatNumber::random_normal(p, -10, 10).unwrap();
+            let mut e = BigFloatNumber::random_normal(p, -5, 0).unwrap();
+            e.set_sign(Sign::Pos);
+
+            for rm in [RoundingMode::Up, RoundingMode::Down, RoundingMode::ToEven] {
+                let x = m.solve_kepler(&e, p, rm, &mut cc).unwrap();
+                assert!(x.inexact());
+
+                let p2 = 2 * p;
+                let d1 = x.sin(p2, RoundingMode::None, &mut cc).unwrap();
+                let d2 = d1.mul(&e, p2, RoundingMode::None).unwrap();
+                let d3 = x.sub(&d2, p2, RoundingMode::None).unwrap();
+                let r = d3.sub(&m, p2, RoundingMode::None).unwrap();
+                assert!(
+                    r.is_zero() || (r.exponent() as isize) < x.exponent() as isize - p as isize + 4
+                );
+            }
+        }
+
+        // directed rounding brackets the solution
+        let m = BigFloatNumber::from_f64(p, 1.0).unwrap();
+        let e = BigFloatNumber::from_f64(p, 0.5).unwrap();
+        let x1 = m.solve_kepler(&e, p, RoundingMode::Down, &mut cc).unwrap();
+        let x2 = m.solve_kepler(&e, p, RoundingMode::Up, &mut cc).unwrap();
+        assert!(x1.cmp(&x2) < 0);
+        let mut d = x2.sub(&x1, p, RoundingMode::None).unwrap();
+        d.set_exponent(d.exponent() + p as Exponent);
+        assert!(d.cmp(&BigFloatNumber::from_word(4, p).unwrap()) <= 0);
+
+        // reference value: E for M = 1, e = 0.5
+        let expected = BigFloatNumber::from_f64(p, 1.4987011335178482).unwrap();
+        let d = x1.sub(&expected, p, RoundingMode::None).unwrap();
+        assert!(d.exponent() < -48);
+
+        // symmetry
+        let x3 = m
+            .neg()
+            .unwrap()
+            .solve_kepler(&e, p, RoundingMode::ToEven, &mut cc)
+            .unwrap();
+        let x4 = m
+            .solve_kepler(&e, p, RoundingMode::ToEven, &mut cc)
+            .unwrap();
+        assert!(x3.neg().unwrap().cmp(&x4) == 0);
+
+        // high eccentricity and small mean anomaly
+        let mut m = BigFloatNumber::from_word(1, p).unwrap();
+        m.set_exponent(-20);
+        let mut e = BigFloatNumber::from_word(1, p).unwrap();
+        e.set_exponent(-30);
+        let e = ONE.sub(&e, p, RoundingMode::None).unwrap();
+        let x = m
+            .solve_kepler(&e, p, RoundingMode::ToEven, &mut cc)
+            .unwrap();
+        let d1 = x.sin(2 * p, RoundingMode::None, &mut cc).unwrap();
+        let d2 = d1.mul(&e, 2 * p, RoundingMode::None).unwrap();
+        let d3 = x.sub(&d2, 2 * p, RoundingMode::None).unwrap();
+        let r = d3.sub(&m, 2 * p, RoundingMode::None).unwrap();
+        assert!(r.is_zero() || (r.exponent() as isize) < x.exponent() as isize - p as isize);
+
+        // large mean anomaly
+        let m = BigFloatNumber::from_f64(p, 1.0e20).unwrap();
+        let e = BigFloatNumber::from_f64(p, 0.9).unwrap();
+        let x = m
+            .solve_kepler(&e, p, RoundingMode::ToEven, &mut cc)
+            .unwrap();
+        let d = x.sub(&m, p, RoundingMode::None).unwrap();
+        assert!(d.abs_cmp(&e) <= 0);
+
+        // special cases
+        let zero = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let x = zero
+            .solve_kepler(&e, p, RoundingMode::ToEven, &mut cc)
+            .unwrap();
+        assert!(x.is_zero() && x.is_negative() && !x.inexact());
+
+        let zero = BigFloatNumber::new(p).unwrap();
+        let x = m
+            .solve_kepler(&zero, p, RoundingMode::ToEven, &mut cc)
+            .unwrap();
+        assert!(x.cmp(&m) == 0 && !x.inexact());
+
+        // errors
+        assert_eq!(
+            m.solve_kepler(&ONE, p, RoundingMode::ToEven, &mut cc)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+        assert_eq!(
+            m.solve_kepler(&e.neg().unwrap(), p, RoundingMode::ToEven, &mut cc)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+    }
+}
@@ -8,12 +8,15 @@ use crate::common::util::calc_mul_cost;
 use crate::common::util::calc_sqrt_cost;
 use crate::common::util::count_leading_ones;
 use crate::common::util::count_leading_zeroes_skip_first;
+use crate::common::util::log2_ceil;
 use crate::common::util::round_p;
 use crate::defs::Error;
 use crate::defs::RoundingMode;
 use crate::defs::Sign;
+use crate::defs::EXPONENT_MIN;
 use crate::num::BigFloatNumber;
 use crate::ops::consts::Consts;
+use crate::ops::series::series_cost;
 use crate::ops::series::series_cost_optimize;
 use crate::ops::series::series_run;
 use crate::ops::series::ArgReductionEstimator;
@@ -21,6 +24,9 @@ use crate::ops::series::PolycoeffGen;
 use crate::Exponent;
 use crate::WORD_BIT_SIZE;
 
+// Minimum precision for which the AGM method is considered.
+const LN_AGM_MIN_PREC: usize = 1024;
+
 // Polynomial coefficient generator.
 struct AtanhPolycoeffGen {
     acc: BigFloatNumber,
@@ -132,7 +138,7 @@ impl BigFloatNumber {
             let p_x = p_wrk + additional_prec;
             x.set_precision(p_x, RoundingMode::None)?;
 
-            let p1 = Self::ln_series(x, RoundingMode::None)?;
+            let p1 = Self::ln_reduced(x, cc)?;
 
             let mut ret = if e == 0 {
                 p1
@@ -158,6 +164,91 @@ impl BigFloatNumber {
         }
     }
 
+    // ln(x) for 0.5 <= x < 1 using the method with the lowest estimated cost.
+    fn ln_reduced(x: Self, cc: &mut Consts) -> Result<Self, Error> {
+        let p = x.mantissa_max_bit_len();
+
+        if p >= LN_AGM_MIN_PREC && Self::ln_agm_cost(p) < Self::ln_series_cost(p)? {
+            Self::ln_agm(x, cc)
+        } else {
+            Self::ln_series(x, RoundingMode::None)
+        }
+    }
+
+    // Estimates cost of ln_series for precision p.
+    fn ln_series_cost(p: usize) -> Result<u64, Error> {
+        let polycoeff_gen = AtanhPolycoeffGen::new(p)?;
+        let (reduction_times, niter, _) =
+            series_cost_optimize::<LnArgReductionEstimator>(p, &polycoeff_gen, 0, 2, false);
+
+        Ok(series_cost(niter, p, &polycoeff_gen)
+            + LnArgReductionEstimator::reduction_cost(reduction_times, p))
+    }
+
+    // Estimates cost of ln_agm for precision p.
+    fn ln_agm_cost(p: usize) -> u64 {
+        // niter * (cost(mul) + cost(sqrt) + cost(add)) + 2 * cost(div),
+        // where the Newton iteration gives cost(sqrt) ~ 4 * cost(mul), and cost(div) ~ 2 * cost(mul).
+        let p_wrk = p + 2 * log2_ceil(p) + 8;
+        let niter = 2 * log2_ceil(p_wrk) as u64;
+        let cost_mul = calc_mul_cost(p_wrk) as u64;
+        let cost_add = calc_add_cost(p_wrk) as u64;
+
+        niter * (5 * cost_mul + cost_add) + 4 * cost_mul
+    }
+
+    // ln(x) for 0.5 <= x < 1 using the arithmetic-geometric mean:
+    // ln(s) = pi / (2 * AGM(1, 4/s)) with the error less than 4*ln(s)/s^2 when s > 2^(p/2),
+    // and ln(x) = ln(x * 2^k) - k*ln(2).
+    fn ln_agm(x: Self, cc: &mut Consts) -> Result<Self, Error> {
+        let p = x.mantissa_max_bit_len();
+
+        // the AGM computation increases the absolute error by about log2(p) ulp,
+        // and the result is of order p before the subtraction of k*ln(2).
+        let p_wrk = p + 2 * log2_ceil(p) + 8;
+        let k = (p + log2_ceil(p)) / 2 + 4;
+
+        // 4/s = 2^(2-k) / x
+        let mut b = ONE.div(&x, p_wrk, RoundingMode::None)?;
+        let e = b.exponent() as isize + 2 - k as isize;
+        if e < EXPONENT_MIN as isize {
+            return Err(Error::InvalidArgument);
+        }
+        b.set_exponent(e as Exponent);
+
+        let mut a = Self::from_word(1, p_wrk)?;
+
+        // when a and b agree in half of the bits, their arithmetic mean is the AGM.
+        let thrsh = p_wrk as isize / 2 + 1;
+        loop {
+            let mut an = a.add(&b, p_wrk, RoundingMode::None)?;
+            an.set_exponent(an.exponent() - 1);
+
+            let d = a.sub(&b, p_wrk, RoundingMode::None)?;
+            if d.is_zero() || (an.exponent() as isize - d.exponent() as isize) >= thrsh {
+                a = an;
+                break;
+            }
+
+            b = a.mul(&b, p_wrk, RoundingMode::None)?;
+            b = b.sqrt(p_wrk, RoundingMode::None)?;
+            a = an;
+        }
+
+        let mut pi = cc.pi_num(p_wrk, RoundingMode::None)?;
+        pi.set_exponent(pi.exponent() - 1);
+        let ls = pi.div(&a, p_wrk, RoundingMode::None)?;
+
+        let ln2 = cc.ln_2_num(p_wrk, RoundingMode::None)?;
+        let kln2 = ln2.mul(&Self::from_usize(k)?, p_wrk, RoundingMode::None)?;
+
+        let mut ret = ls.sub(&kln2, p_wrk, RoundingMode::None)?;
+        ret.set_precision(p, RoundingMode::None)?;
+        ret.set_inexact(true);
+
+        Ok(ret)
+    }
+
     fn ln_series(mut x: Self, rm: RoundingMode) -> Result<Self, Error> {
         let p = x.mantissa_max_bit_len();
         let mut polycoeff_gen = AtanhPolycoeffGen::new(p)?;
@@ -267,7 +358,7 @@ impl BigFloatNumber {
             let p_x = p_wrk + additional_prec;
             x.set_precision(p_x, RoundingMode::None)?;
 
-            let p1 = Self::ln_series(x, RoundingMode::None)?;
+            let p1 = Self::ln_reduced(x, cc)?;
 
             let p2 = cc.ln_2_num(p_x, RoundingMode::None)?;
 
@@ -807,6 +898,42 @@ mod tests {
         assert!(d1.cmp(&refn) == 0);
     }
 
+    #[test]
+    fn test_ln_agm() {
+        let mut cc = Consts::new().unwrap();
+
+        for p in [LN_AGM_MIN_PREC, 8192] {
+            for _ in 0..3 {
+                let mut x = BigFloatNumber::random_normal(p, 0, 0).unwrap();
+                x.set_sign(Sign::Pos);
+
+                let d1 = BigFloatNumber::ln_series(x.clone().unwrap(), RoundingMode::None).unwrap();
+                let d2 = BigFloatNumber::ln_agm(x, &mut cc).unwrap();
+
+                let d = d1.sub(&d2, p, RoundingMode::None).unwrap();
+                assert!(d.is_zero() || (d.exponent() as isize) < 4 - p as isize);
+            }
+        }
+
+        // ln(e^x) = x up to the rounding error of e^x
+        let p = 8192;
+        let mut x = BigFloatNumber::random_normal(p, -20, 5).unwrap();
+        x.set_sign(Sign::Pos);
+        let y = x.exp(p, RoundingMode::ToEven, &mut cc).unwrap();
+        let d1 = y.ln(p, RoundingMode::ToEven, &mut cc).unwrap();
+        let d = d1.sub(&x, p, RoundingMode::None).unwrap();
+        assert!(d.is_zero() || (d.exponent() as isize) < 8 - p as isize);
+
+        // argument near 1
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(-300);
+        let x = ONE.sub(&d1, p, RoundingMode::None).unwrap();
+        let d2 = x.ln(p, RoundingMode::ToEven, &mut cc).unwrap();
+        let d3 = d2.exp(p, RoundingMode::ToEven, &mut cc).unwrap();
+        let d = d3.sub(&x, p, RoundingMode::None).unwrap();
+        assert!(d.is_zero() || (d.exponent() as isize) < 3 - p as isize);
+    }
+
     #[ignore]
     #[test]
     #[cfg(feature = "std")]
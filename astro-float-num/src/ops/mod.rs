@@ -2,20 +2,40 @@
 
 mod acos;
 mod acosh;
+mod angle;
 mod asin;
 mod asinh;
 mod atan;
 mod atanh;
+mod besseli;
+mod besselj;
+mod besselk;
+mod bessely;
 mod cbrt;
 pub mod consts;
 mod cos;
 mod cosh;
+mod equation;
+mod erf;
+mod erfinv;
+mod expm1;
+mod fast;
+mod fused;
+mod gamma;
+mod hypot;
+mod kepler;
 mod log;
+mod multiple;
 mod pow;
+mod product;
+mod sech;
 mod series;
+pub mod solve;
 mod sin;
 mod sinh;
+mod sinpi;
 mod sqrt;
+mod sum;
 mod tan;
 mod tanh;
 mod util;
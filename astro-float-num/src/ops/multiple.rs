@@ -0,0 +1,205 @@
+//! Rounding to a multiple of a number.
+
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::Sign;
+
+impl BigFloatNumber {
+    /// Rounds `self` to an integer multiple of `step` and returns the result with precision `p`.
+    /// The multiple is chosen according to the rounding mode `rm`, as if `self / step` was rounded to an integer,
+    /// and the result is rounded to precision `p` using the same rounding mode `rm`, so the result is computed using a single rounding.
+    /// `RoundingMode::None` chooses the multiple toward zero. The sign of `step` is ignored.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large number.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect, or `step` is zero.
+    pub fn round_to_multiple(
+        &self,
+        step: &Self,
+        p: usize,
+        rm: RoundingMode,
+    ) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if step.is_zero() {
+            return Err(Error::InvalidArgument);
+        }
+
+        let inexact = self.inexact() | step.inexact();
+
+        let mut x = self.clone()?;
+        x.set_sign(Sign::Pos);
+        let mut s = step.clone()?;
+        s.set_sign(Sign::Pos);
+
+        // |self| = n * |step| + r, where n is an integer and 0 <= r < |step|
+        let r = x.rem(&s)?;
+
+        let up = !r.is_zero()
+            && match rm {
+                RoundingMode::None | RoundingMode::ToZero => false,
+                RoundingMode::FromZero => true,
+                RoundingMode::Up => self.is_positive(),
+                RoundingMode::Down => self.is_negative(),
+                RoundingMode::ToEven | RoundingMode::ToOdd => {
+                    let c = r.cmp(&s.sub_full_prec(&r)?);
+                    if c == 0 {
+                        let odd = Self::is_quotient_odd(&x, &s, &r)?;
+                        odd == (rm == RoundingMode::ToEven)
+                    } else {
+                        c > 0
+                    }
+                }
+            };
+
+        // n * |step| is computed exactly as |self| - r
+        let mut ret = x.sub_full_prec(&r)?;
+        ret.set_sign(self.sign());
+
+        if up {
+            s.set_sign(self.sign());
+            ret = ret.add(&s, p, rm)?;
+        } else {
+            ret.set_precision(p, rm)?;
+        }
+
+        ret.set_inexact(ret.inexact() | inexact);
+
+        Ok(ret)
+    }
+
+    // Returns true if the integer quotient `n` of `x = n * s + r` is odd, where `x` and `s` are positive, and `r = x rem s`.
+    fn is_quotient_odd(x: &Self, s: &Self, r: &Self) -> Result<bool, Error> {
+        match s.add_full_prec(s) {
+            Ok(s2) => {
+                // x rem 2s is r + s if n is odd, and r otherwise
+                let r2 = x.rem(&s2)?;
+                Ok(r2.cmp(r) != 0)
+            }
+            Err(Error::ExponentOverflow(_)) => Ok(x.cmp(s) >= 0),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::defs::{EXPONENT_MAX, WORD_BIT_SIZE};
+    use rand::random;
+
+    #[test]
+    fn test_round_to_multiple() {
+        let p = 128;
+
+        let num = |f: f64| BigFloatNumber::from_f64(p, f).unwrap();
+
+        // x, step, to even, down, up, to zero, from zero
+        let vals = [
+            (7.3, 2.0, 8.0, 6.0, 8.0, 6.0, 8.0),
+            (5.0, 2.0, 4.0, 4.0, 6.0, 4.0, 6.0),
+            (3.0, 2.0, 4.0, 2.0, 4.0, 2.0, 4.0),
+            (-3.0, 2.0, -4.0, -4.0, -2.0, -2.0, -4.0),
+            (-7.3, -2.0, -8.0, -8.0, -6.0, -6.0, -8.0),
+            (0.3, 0.25, 0.25, 0.25, 0.5, 0.25, 0.5),
+            (6.0, 1.5, 6.0, 6.0, 6.0, 6.0, 6.0),
+            (0.1, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0),
+        ];
+
+        for (x, s, te, dn, up, tz, fz) in vals {
+            let x = num(x);
+            let s = num(s);
+            for (rm, r) in [
+                (RoundingMode::ToEven, te),
+                (RoundingMode::Down, dn),
+                (RoundingMode::Up, up),
+                (RoundingMode::ToZero, tz),
+                (RoundingMode::FromZero, fz),
+                (RoundingMode::None, tz),
+            ] {
+                let d = x.round_to_multiple(&s, p, rm).unwrap();
+                assert!(d.cmp(&num(r)) == 0);
+            }
+        }
+
+        // ties to odd
+        let d = num(5.0)
+            .round_to_multiple(&num(2.0), p, RoundingMode::ToOdd)
+            .unwrap();
+        assert!(d.cmp(&num(6.0)) == 0);
+        let d = num(3.0)
+            .round_to_multiple(&num(2.0), p, RoundingMode::ToOdd)
+            .unwrap();
+        assert!(d.cmp(&num(2.0)) == 0);
+
+        // the step is not a power of two: the multiple is rounded once
+        let step = BigFloatNumber::from_word(1, p)
+            .unwrap()
+            .div(
+                &BigFloatNumber::from_word(3, p).unwrap(),
+                p,
+                RoundingMode::ToEven,
+            )
+            .unwrap();
+        let x = num(1.0);
+        let d = x.round_to_multiple(&step, p, RoundingMode::ToEven).unwrap();
+        let n = x
+            .div(&step, p, RoundingMode::ToEven)
+            .unwrap()
+            .round(0, RoundingMode::ToEven)
+            .unwrap();
+        assert!(d.cmp(&n.mul(&step, p, RoundingMode::ToEven).unwrap()) == 0);
+
+        for _ in 0..100 {
+            let p1 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+            let p2 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+            let x = BigFloatNumber::random_normal(p1, -20, 20).unwrap();
+            let s = BigFloatNumber::random_normal(p2, -10, 10).unwrap();
+
+            // the precision is sufficient for the exact result
+            let d = x.round_to_multiple(&s, 1024, RoundingMode::ToEven).unwrap();
+            assert!(!d.inexact());
+            assert!(d.rem(&s).unwrap().is_zero());
+
+            // the nearest multiple
+            let diff = d.sub_full_prec(&x).unwrap().abs().unwrap();
+            let mut hs = s.abs().unwrap();
+            hs.set_exponent(hs.exponent() - 1);
+            assert!(diff.cmp(&hs) <= 0);
+        }
+
+        // zero
+        let zero = BigFloatNumber::new(p).unwrap();
+        let d = zero
+            .round_to_multiple(&num(3.0), p, RoundingMode::Up)
+            .unwrap();
+        assert!(d.is_zero());
+
+        // large quotient
+        let mut x = num(1.5);
+        x.set_exponent(EXPONENT_MAX);
+        let d = x
+            .round_to_multiple(&num(1.0), p, RoundingMode::ToEven)
+            .unwrap();
+        assert!(d.cmp(&x) == 0);
+
+        // step is larger than the half of the maximum value
+        let s = BigFloatNumber::max_value(p).unwrap();
+        let d = x.round_to_multiple(&s, p, RoundingMode::ToEven).unwrap();
+        assert!(d.cmp(&s) == 0);
+
+        // zero step
+        assert_eq!(
+            x.round_to_multiple(&zero, p, RoundingMode::ToEven)
+                .unwrap_err(),
+            Error::InvalidArgument
+        );
+    }
+}
@@ -1,9 +1,10 @@
 //! Exponentiation.
 
 use crate::common::consts::{FOUR, THREE};
-use crate::common::util::{calc_add_cost, calc_mul_cost, round_p};
+use crate::common::util::{calc_add_cost, calc_mul_cost, log2_floor, round_p};
 use crate::ops::consts::Consts;
 use crate::ops::util::compute_small_exp;
+use crate::Exponent;
 use crate::EXPONENT_MIN;
 use crate::{
     common::consts::ONE,
@@ -12,7 +13,24 @@ use crate::{
     RoundingMode, Sign,
 };
 
-use super::series::{series_cost_optimize, series_run, ArgReductionEstimator, PolycoeffGen};
+use super::series::{
+    series_binary_splitting, series_cost_optimize, series_run, ArgReductionEstimator, PolycoeffGen,
+    TermRatio,
+};
+
+/// Precision starting from which the exponent is computed using binary splitting.
+const EXP_BINARY_SPLITTING_THRESHOLD: usize = 5120;
+
+// Ratio x/k of the consecutive terms of the series of e^x.
+struct ExpTermRatio<'a> {
+    x: &'a BigFloatNumber,
+}
+
+impl TermRatio for ExpTermRatio<'_> {
+    fn ratio(&self, k: usize) -> Result<(BigFloatNumber, BigFloatNumber), Error> {
+        Ok((self.x.clone()?, BigFloatNumber::from_usize(k)?))
+    }
+}
 
 // Polynomial coefficient generator.
 struct SinhPolycoeffGen {
@@ -357,6 +375,17 @@ impl BigFloatNumber {
 
         let p = self.mantissa_max_bit_len();
 
+        if p >= EXP_BINARY_SPLITTING_THRESHOLD && self.is_positive() {
+            self.expf_binary_splitting()
+        } else {
+            self.expf_series()
+        }
+    }
+
+    // e^self for |self| < 1 using the series of sinh.
+    fn expf_series(self) -> Result<Self, Error> {
+        let p = self.mantissa_max_bit_len();
+
         let sh = self.sinh_series(p, RoundingMode::None)?; // faster convergence than direct series
 
         // e = sh + sqrt(sh^2 + 1)
@@ -366,6 +395,61 @@ impl BigFloatNumber {
         sq3.add(&sh, p, RoundingMode::FromZero)
     }
 
+    // e^self for 0 < self < 1 using binary splitting.
+    // The argument is split into chunks holding the bits 1..W, W+1..2W, 2W+1..4W, and so on, after the binary point,
+    // and e^self is computed as the product of the exponents of the chunks (the bit-burst algorithm).
+    // The series of a chunk with k bits which lays below 2^(-k) needs about p/k terms.
+    fn expf_binary_splitting(self) -> Result<Self, Error> {
+        let p = self.mantissa_max_bit_len();
+
+        // the error of each chunk is below 2 ulp, the number of chunks is below W.
+        let p_wrk = p + WORD_BIT_SIZE;
+
+        let mut ret = Self::from_word(1, p_wrk)?;
+        let mut rest = self;
+        let mut l_prev = 0;
+        let mut l = WORD_BIT_SIZE;
+
+        while !rest.is_zero() {
+            let e = rest.exponent() as isize;
+
+            if e + l as isize > 0 {
+                // split rest into the chunk holding the bits down to 2^(-l), and the remainder
+                let mut t = rest;
+                t.set_exponent((e + l as isize) as Exponent);
+
+                // the chunk has at most l significant bits
+                let mut chunk = t.int()?;
+                chunk.set_precision(l, RoundingMode::None)?;
+                chunk.set_exponent(chunk.exponent() - l as Exponent);
+
+                rest = t.fract()?;
+                if !rest.is_zero() {
+                    rest.set_exponent(rest.exponent() - l as Exponent);
+                }
+
+                // the first omitted term x^n/n! is below 2^(-n*l_prev)/n!
+                let mut n = 1;
+                let mut fct_bits = 0;
+                while n * l_prev + fct_bits < p_wrk + 2 {
+                    n += 1;
+                    fct_bits += log2_floor(n);
+                }
+
+                let (t, q) = series_binary_splitting(&ExpTermRatio { x: &chunk }, n.max(2))?;
+                let s = t.div(&q, p_wrk, RoundingMode::None)?;
+                ret = ret.mul(&s, p_wrk, RoundingMode::None)?;
+            }
+
+            l_prev = l;
+            l *= 2;
+        }
+
+        ret.set_precision(p, RoundingMode::FromZero)?;
+
+        Ok(ret)
+    }
+
     /// sinh using series, for |x| < 1
     pub fn sinh_series(mut self, p: usize, rm: RoundingMode) -> Result<Self, Error> {
         // sinh:  x + x^3/3! + x^5/5! + x^7/7! + ...
@@ -795,6 +879,45 @@ mod test {
         assert!(d1.cmp(&d2) == 0);
     }
 
+    #[test]
+    fn test_exp_binary_splitting() {
+        for p in [EXP_BINARY_SPLITTING_THRESHOLD, 8192] {
+            for e in [0, -1, -50, -200, -(p as Exponent) / 2] {
+                let mut x = BigFloatNumber::random_normal(p, e, e).unwrap();
+                x.set_sign(Sign::Pos);
+
+                let d1 = x.clone().unwrap().expf_series().unwrap();
+                let d2 = x.clone().unwrap().expf_binary_splitting().unwrap();
+
+                // both results have an error of a few ulp
+                let d = d1.sub(&d2, p, RoundingMode::None).unwrap();
+                assert!(d.is_zero() || (d.exponent() as isize) < 4 - p as isize);
+            }
+
+            // argument with few significant bits
+            let mut x = BigFloatNumber::from_word(3, p).unwrap();
+            x.set_exponent(-10);
+            let d1 = x.clone().unwrap().expf_series().unwrap();
+            let d2 = x.expf_binary_splitting().unwrap();
+            let d = d1.sub(&d2, p, RoundingMode::None).unwrap();
+            assert!(d.is_zero() || (d.exponent() as isize) < 4 - p as isize);
+        }
+
+        // e^x * e^(-x) = 1
+        let mut cc = Consts::new().unwrap();
+        let p = 8192;
+        let x = BigFloatNumber::random_normal(p, 3, 3).unwrap();
+        let d1 = x.exp(p, RoundingMode::ToEven, &mut cc).unwrap();
+        let d2 = x
+            .neg()
+            .unwrap()
+            .exp(p, RoundingMode::ToEven, &mut cc)
+            .unwrap();
+        let d3 = d1.mul(&d2, p, RoundingMode::ToEven).unwrap();
+        let d = d3.sub(&ONE, p, RoundingMode::None).unwrap();
+        assert!(d.is_zero() || (d.exponent() as isize) < 3 - p as isize);
+    }
+
     #[ignore]
     #[test]
     #[cfg(feature = "std")]
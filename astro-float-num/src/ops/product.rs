@@ -0,0 +1,175 @@
+//! Product of a sequence of numbers.
+
+use crate::common::util::log2_ceil;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes the product of `values` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The product is rounded once, as if it was computed exactly and then rounded.
+    /// The product of an empty sequence is one.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result or one of the partial products is too large.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn product(values: &[&Self], p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        let inexact = values.iter().any(|v| v.inexact());
+
+        let (first, rest) = match values.split_first() {
+            Some(v) => v,
+            None => return Self::from_word(1, p),
+        };
+
+        if values.iter().any(|v| v.is_zero()) {
+            let s = if values.iter().filter(|v| v.is_negative()).count() % 2 == 0 {
+                Sign::Pos
+            } else {
+                Sign::Neg
+            };
+
+            return Self::new2(p, s, inexact);
+        }
+
+        // the exact product fits in the sum of the precisions of the values
+        let p_exact = values
+            .iter()
+            .map(|v| v.mantissa_max_bit_len())
+            .sum::<usize>();
+
+        // each multiplication has a relative error smaller than 2^(1 - p_wrk),
+        // so the product has a relative error smaller than 2^(log_n - p_wrk) and at least p_wrk - log_n - 1 correct bits
+        let log_n = log2_ceil(values.len()) + 1;
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p + log_n + 1 + p_inc;
+
+        loop {
+            let exact = p_wrk >= p_exact;
+            if exact {
+                p_wrk = p_exact;
+            }
+
+            let mut ret = (*first).clone()?;
+            for v in rest.iter() {
+                ret = ret.mul(v, p_wrk, RoundingMode::None)?;
+            }
+
+            if exact {
+                ret.set_precision(p, rm)?;
+                ret.set_inexact(ret.inexact() | inexact);
+                return Ok(ret);
+            }
+
+            // the roundability check requires the number of correct bits to be aligned to the word size
+            let s = (p_wrk - log_n - 1) / WORD_BIT_SIZE * WORD_BIT_SIZE;
+
+            // the product can be inexact, so rounding is checked
+            ret.set_inexact(true);
+            if ret.try_set_precision(p, rm, s)? {
+                return Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::common::util::random_subnormal;
+    use rand::random;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_product() {
+        // compare with the exact product
+        for _ in 0..100 {
+            let p = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let n = random::<usize>() % 20 + 1;
+            let mut values = Vec::new();
+            for _ in 0..n {
+                let p1 = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+                values.push(BigFloatNumber::random_normal(p1, -100, 100).unwrap());
+            }
+
+            let refs: Vec<&BigFloatNumber> = values.iter().collect();
+
+            let mut exact = BigFloatNumber::from_word(1, p).unwrap();
+            for v in values.iter() {
+                exact = exact.mul_full_prec(v).unwrap();
+            }
+
+            for rm in [
+                RoundingMode::ToEven,
+                RoundingMode::ToOdd,
+                RoundingMode::Up,
+                RoundingMode::Down,
+                RoundingMode::ToZero,
+                RoundingMode::FromZero,
+            ] {
+                let d1 = BigFloatNumber::product(&refs, p, rm).unwrap();
+                let mut d2 = exact.clone().unwrap();
+                d2.set_precision(p, rm).unwrap();
+                assert!(d1.cmp(&d2) == 0);
+                assert_eq!(d1.inexact(), d2.inexact());
+            }
+        }
+
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // exact product of numbers with large exponents
+        let mut d1 = BigFloatNumber::from_word(3, p).unwrap();
+        d1.set_exponent(1002);
+        let mut d2 = BigFloatNumber::from_word(1, p).unwrap();
+        d2.set_exponent(-999);
+        let d = BigFloatNumber::product(&[&d1, &d2, &d1, &d2], p, rm).unwrap();
+        assert!(d.cmp(&BigFloatNumber::from_word(9, p).unwrap()) == 0 && !d.inexact());
+
+        // subnormal
+        let d1 = random_subnormal(p);
+        let d2 = BigFloatNumber::from_word(3, p).unwrap();
+        let d = BigFloatNumber::product(&[&d1, &d2], p, rm).unwrap();
+        let d3 = d1.mul(&d2, p, rm).unwrap();
+        assert!(d.cmp(&d3) == 0);
+
+        // empty sequence and zeros
+        let d = BigFloatNumber::product(&[], p, rm).unwrap();
+        assert!(d.cmp(&BigFloatNumber::from_word(1, p).unwrap()) == 0 && !d.inexact());
+        let z1 = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let mut d1 = BigFloatNumber::from_word(5, p).unwrap();
+        d1.set_sign(Sign::Neg);
+        let d = BigFloatNumber::product(&[&z1, &d1], p, rm).unwrap();
+        assert!(d.is_zero() && d.is_positive() && !d.inexact());
+        let d = BigFloatNumber::product(&[&z1, &d1, &d1], p, rm).unwrap();
+        assert!(d.is_zero() && d.is_negative());
+
+        // inexact arguments
+        let mut d1 = BigFloatNumber::from_word(3, p).unwrap();
+        d1.set_inexact(true);
+        let d = BigFloatNumber::product(&[&d1, &d2], p, rm).unwrap();
+        assert!(d.cmp(&BigFloatNumber::from_word(9, p).unwrap()) == 0 && d.inexact());
+
+        // overflow
+        let d1 = BigFloatNumber::max_value(p).unwrap();
+        assert_eq!(
+            BigFloatNumber::product(&[&d1, &d1], p, rm).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+    }
+}
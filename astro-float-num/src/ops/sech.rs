@@ -0,0 +1,599 @@
+//! Hyperbolic secant, cosecant, cotangent, and their inverses.
+
+use crate::common::consts::ONE;
+use crate::common::consts::TWO;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::defs::EXPONENT_MAX;
+use crate::num::BigFloatNumber;
+use crate::ops::util::compute_small_exp;
+use crate::Consts;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes the hyperbolic secant of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn sech(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            let mut ret = Self::from_word(1, p)?;
+            ret.set_inexact(self.inexact());
+            return Ok(ret);
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len());
+
+        // sech(x) = 1 - x^2/2 + ...
+        compute_small_exp!(ONE, self.exponent() as isize * 2 - 1, true, p_wrk, p, rm);
+
+        p_wrk += p_inc;
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+
+        loop {
+            let p_x = p_wrk + 4;
+            x.set_precision(p_x, RoundingMode::None)?;
+
+            // the result is too small if cosh(x) overflows
+            let ch = match x.cosh(p_x, RoundingMode::None, cc) {
+                Err(Error::ExponentOverflow(_)) => return Self::new2(p, Sign::Pos, true),
+                v => v,
+            }?;
+
+            let mut ret = ch.reciprocal(p_x, RoundingMode::None)?;
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    /// Computes the hyperbolic cosecant of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large, or `self` is zero.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn csch(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            return Err(Error::ExponentOverflow(self.sign()));
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len());
+
+        // csch(x) = 1/x - x/6 + ..., and 1/x is exact if x is a power of 2
+        if self.exponent() < 0 && self.is_pow_of_two() {
+            let r = self.reciprocal(p, RoundingMode::None)?;
+            compute_small_exp!(r, self.exponent() as isize * 2 - 1, true, p_wrk, p, rm);
+        }
+
+        p_wrk += p_inc;
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+
+        loop {
+            let p_x = p_wrk + 4;
+            x.set_precision(p_x, RoundingMode::None)?;
+
+            // the result is too small if sinh(x) overflows
+            let sh = match x.sinh(p_x, RoundingMode::None, cc) {
+                Err(Error::ExponentOverflow(s)) => return Self::new2(p, s, true),
+                v => v,
+            }?;
+
+            let mut ret = sh.reciprocal(p_x, RoundingMode::None)?;
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    /// Computes the hyperbolic cotangent of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large, or `self` is zero.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn coth(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            return Err(Error::ExponentOverflow(self.sign()));
+        }
+
+        // prevent overflow
+        if self.exponent() == EXPONENT_MAX {
+            return self.coth_large_arg(p, rm);
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len());
+
+        // coth(x) = 1/x + x/3 - ..., and 1/x is exact if x is a power of 2
+        if self.exponent() < 0 && self.is_pow_of_two() {
+            let r = self.reciprocal(p, RoundingMode::None)?;
+            compute_small_exp!(r, self.exponent() as isize * 2 - 1, false, p_wrk, p, rm);
+        }
+
+        // (e^(2*x) + 1) / (e^(2*x) - 1)
+
+        let mut additional_prec = 4;
+        if self.exponent() < 0 {
+            additional_prec += self.exponent().unsigned_abs() as usize;
+        }
+
+        p_wrk += p_inc;
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+        x.set_sign(Sign::Pos);
+        x.set_exponent(x.exponent() + 1);
+
+        loop {
+            let p_x = p_wrk + additional_prec;
+            x.set_precision(p_x, RoundingMode::None)?;
+
+            let xexp = match x.exp(p_x, RoundingMode::FromZero, cc) {
+                Err(Error::ExponentOverflow(_)) => return self.coth_large_arg(p, rm),
+                v => v,
+            }?;
+
+            if xexp.exponent() as isize > p_x as isize {
+                return self.coth_large_arg(p, rm);
+            }
+
+            let d1 = xexp.add(&ONE, p_x, RoundingMode::None)?;
+            let d2 = xexp.sub(&ONE, p_x, RoundingMode::None)?;
+
+            let mut ret = d1.div(&d2, p_x, RoundingMode::None)?;
+
+            ret.set_sign(self.sign());
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    // coth(x) for large |x| is 1 + 2/(e^(2*x) - 1) with the sign of x.
+    fn coth_large_arg(&self, p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        let ret = Self::from_i8(self.sign().to_int(), p)?;
+        let mut ret = ret.add_correction(false)?;
+        ret.set_precision(p, rm)?;
+        Ok(ret)
+    }
+
+    /// Computes the hyperbolic arcsecant of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: `self` is zero.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: `self` is negative or larger than 1, or the precision is incorrect.
+    pub fn asech(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            return Err(Error::ExponentOverflow(Sign::Pos));
+        }
+
+        if self.is_negative()
+            || self.exponent() > 1
+            || (self.exponent() == 1 && self.abs_cmp(&ONE) != 0)
+        {
+            return Err(Error::InvalidArgument);
+        }
+
+        if self.exponent() == 1 {
+            // asech(1) = 0
+            return Self::new2(p, Sign::Pos, self.inexact());
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len()) + p_inc;
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+
+        loop {
+            let p_x = p_wrk + 4;
+            x.set_precision(p_x, RoundingMode::None)?;
+
+            // ln(1 + sqrt((1 - x) * (1 + x))) - ln(x): both terms are positive, and 1 - x is exact near 1
+            let d1 = ONE.sub(&x, p_x, RoundingMode::None)?;
+            let d2 = ONE.add(&x, p_x, RoundingMode::None)?;
+            let d3 = d1.mul(&d2, p_x, RoundingMode::None)?;
+            let d4 = d3.sqrt(p_x, RoundingMode::None)?;
+            let d5 = d4.ln_1p(p_x, RoundingMode::None, cc)?;
+            let d6 = x.ln(p_x, RoundingMode::None, cc)?;
+
+            let mut ret = d5.sub(&d6, p_x, RoundingMode::None)?;
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    /// Computes the hyperbolic arccosecant of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: `self` is zero.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn acsch(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() {
+            return Err(Error::ExponentOverflow(self.sign()));
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len());
+
+        // acsch(x) = 1/x - 1/(6*x^3) + ..., and 1/x is exact if x is a power of 2
+        if self.exponent() > 0 && self.is_pow_of_two() {
+            let r = self.reciprocal(p, RoundingMode::None)?;
+            compute_small_exp!(r, 1 - self.exponent() as isize * 2, true, p_wrk, p, rm);
+        }
+
+        p_wrk += p_inc;
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+        x.set_sign(Sign::Pos);
+
+        loop {
+            let p_x = p_wrk + 4;
+            x.set_precision(p_x, RoundingMode::None)?;
+
+            let mut ret = if x.exponent() > 0 {
+                // asinh(1/x)
+                let r = x.reciprocal(p_x, RoundingMode::None)?;
+                r.asinh(p_x, RoundingMode::None, cc)
+            } else {
+                // ln(1 + sqrt(1 + x^2)) - ln(x): both terms are positive, and 1/x may overflow
+                let d1 = x.mul(&x, p_x, RoundingMode::None)?;
+                let d2 = d1.add(&ONE, p_x, RoundingMode::None)?;
+                let d3 = d2.sqrt(p_x, RoundingMode::None)?;
+                let d4 = d3.add(&ONE, p_x, RoundingMode::None)?;
+                let d5 = d4.ln(p_x, RoundingMode::None, cc)?;
+                let d6 = x.ln(p_x, RoundingMode::None, cc)?;
+
+                d5.sub(&d6, p_x, RoundingMode::None)
+            }?;
+
+            ret.set_sign(self.sign());
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+
+    /// Computes the hyperbolic arccotangent of a number with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the absolute value of `self` is 1.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the absolute value of `self` is less than 1, or the precision is incorrect.
+    pub fn acoth(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        if self.is_zero() || self.exponent() < 1 {
+            return Err(Error::InvalidArgument);
+        }
+
+        if self.exponent() == 1 && self.abs_cmp(&ONE) == 0 {
+            return Err(Error::ExponentOverflow(self.sign()));
+        }
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(self.mantissa_max_bit_len());
+
+        // acoth(x) = 1/x + 1/(3*x^3) + ..., and 1/x is exact if x is a power of 2
+        if self.is_pow_of_two() {
+            let r = self.reciprocal(p, RoundingMode::None)?;
+            compute_small_exp!(r, 1 - self.exponent() as isize * 2, false, p_wrk, p, rm);
+        }
+
+        p_wrk += p_inc;
+
+        let mut x = self.clone()?;
+        x.set_inexact(false);
+        x.set_sign(Sign::Pos);
+
+        loop {
+            let p_x = p_wrk + 4;
+            x.set_precision(p_x, RoundingMode::None)?;
+
+            // ln(1 + 2 / (x - 1)) / 2: x - 1 is exact near 1
+            let d1 = x.sub(&ONE, p_x, RoundingMode::None)?;
+            let d2 = TWO.div(&d1, p_x, RoundingMode::None)?;
+
+            let mut ret = d2.ln_1p(p_x, RoundingMode::None, cc)?;
+            ret.div_by_2(RoundingMode::None);
+            ret.set_sign(self.sign());
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(ret.inexact() | self.inexact());
+                break Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::common::util::random_subnormal;
+    use rand::random;
+
+    #[test]
+    fn test_sech_csch_coth() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        // compare with the reciprocals computed with large precision
+        for _ in 0..100 {
+            let p = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let p1 = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let d1 = BigFloatNumber::random_normal(p1, -100, 8).unwrap();
+            let p_x = p + 1024;
+
+            let mut d2 = d1
+                .cosh(p_x, RoundingMode::None, &mut cc)
+                .unwrap()
+                .reciprocal(p_x, RoundingMode::None)
+                .unwrap();
+            d2.set_precision(p, rm).unwrap();
+            assert!(d1.sech(p, rm, &mut cc).unwrap().cmp(&d2) == 0);
+
+            let mut d2 = d1
+                .sinh(p_x, RoundingMode::None, &mut cc)
+                .unwrap()
+                .reciprocal(p_x, RoundingMode::None)
+                .unwrap();
+            d2.set_precision(p, rm).unwrap();
+            assert!(d1.csch(p, rm, &mut cc).unwrap().cmp(&d2) == 0);
+
+            let mut d2 = d1
+                .tanh(p_x, RoundingMode::None, &mut cc)
+                .unwrap()
+                .reciprocal(p_x, RoundingMode::None)
+                .unwrap();
+            d2.set_precision(p, rm).unwrap();
+            assert!(d1.coth(p, rm, &mut cc).unwrap().cmp(&d2) == 0);
+        }
+
+        let p = 320;
+
+        // small argument
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(-1000);
+        let mut r = BigFloatNumber::from_word(1, p).unwrap();
+        r.set_exponent(1002);
+
+        assert!(d1.sech(p, rm, &mut cc).unwrap().cmp(&ONE) == 0);
+        assert!(d1.sech(p, RoundingMode::Up, &mut cc).unwrap().cmp(&ONE) == 0);
+        assert!(d1.sech(p, RoundingMode::Down, &mut cc).unwrap().cmp(&ONE) < 0);
+
+        assert!(d1.csch(p, rm, &mut cc).unwrap().cmp(&r) == 0);
+        assert!(d1.csch(p, RoundingMode::Up, &mut cc).unwrap().cmp(&r) == 0);
+        assert!(d1.csch(p, RoundingMode::Down, &mut cc).unwrap().cmp(&r) < 0);
+
+        assert!(d1.coth(p, rm, &mut cc).unwrap().cmp(&r) == 0);
+        assert!(d1.coth(p, RoundingMode::Up, &mut cc).unwrap().cmp(&r) > 0);
+        assert!(d1.coth(p, RoundingMode::Down, &mut cc).unwrap().cmp(&r) == 0);
+
+        // large argument
+        let d1 = BigFloatNumber::from_word(100000, p).unwrap();
+        let d2 = d1.neg().unwrap();
+        let m1 = ONE.neg().unwrap();
+
+        assert!(d1.coth(p, rm, &mut cc).unwrap().cmp(&ONE) == 0);
+        assert!(d1.coth(p, RoundingMode::Up, &mut cc).unwrap().cmp(&ONE) > 0);
+        assert!(d2.coth(p, rm, &mut cc).unwrap().cmp(&m1) == 0);
+        assert!(d2.coth(p, RoundingMode::Down, &mut cc).unwrap().cmp(&m1) < 0);
+
+        let d1 = BigFloatNumber::max_value(p).unwrap();
+        let d2 = BigFloatNumber::min_value(p).unwrap();
+
+        assert!(d1.sech(p, rm, &mut cc).unwrap().is_zero());
+        assert!(d2.sech(p, rm, &mut cc).unwrap().is_zero());
+        let d3 = d2.csch(p, rm, &mut cc).unwrap();
+        assert!(d3.is_zero() && d3.is_negative());
+        assert!(d1.coth(p, rm, &mut cc).unwrap().cmp(&ONE) == 0);
+        assert!(d2.coth(p, rm, &mut cc).unwrap().cmp(&m1) == 0);
+
+        // zero and subnormal
+        let zero = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        assert!(zero.sech(p, rm, &mut cc).unwrap().cmp(&ONE) == 0);
+        assert_eq!(
+            zero.csch(p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Neg)
+        );
+        assert_eq!(
+            zero.coth(p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Neg)
+        );
+
+        let d1 = random_subnormal(p);
+        assert!(d1.sech(p, rm, &mut cc).unwrap().cmp(&ONE) == 0);
+        assert_eq!(
+            d1.csch(p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(d1.sign())
+        );
+    }
+
+    #[test]
+    fn test_asech_acsch_acoth() {
+        let mut cc = Consts::new().unwrap();
+        let rm = RoundingMode::ToEven;
+
+        // compare with the inverse functions of reciprocals computed with large precision
+        for _ in 0..100 {
+            let p = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let p1 = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let d1 = BigFloatNumber::random_normal(p1, -100, 100).unwrap();
+            let p_x = p + 1024;
+            let r = d1.reciprocal(p_x, RoundingMode::None).unwrap();
+
+            let mut d2 = r.asinh(p_x, RoundingMode::None, &mut cc).unwrap();
+            d2.set_precision(p, rm).unwrap();
+            assert!(d1.acsch(p, rm, &mut cc).unwrap().cmp(&d2) == 0);
+
+            if d1.exponent() <= 0 {
+                let x = d1.abs().unwrap();
+                let r = x.reciprocal(p_x, RoundingMode::None).unwrap();
+                let mut d2 = r.acosh(p_x, RoundingMode::None, &mut cc).unwrap();
+                d2.set_precision(p, rm).unwrap();
+                assert!(x.asech(p, rm, &mut cc).unwrap().cmp(&d2) == 0);
+            } else if d1.abs_cmp(&ONE) != 0 {
+                let mut d2 = r.atanh(p_x, RoundingMode::None, &mut cc).unwrap();
+                d2.set_precision(p, rm).unwrap();
+                assert!(d1.acoth(p, rm, &mut cc).unwrap().cmp(&d2) == 0);
+            }
+        }
+
+        let p = 320;
+
+        // near 1
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(2 - p as crate::Exponent);
+        let x1 = ONE.sub(&d1, p, RoundingMode::None).unwrap();
+        let x2 = ONE.add(&d1, p, RoundingMode::None).unwrap();
+        let p_x = p * 4;
+        let mut d2 = x1
+            .reciprocal(p_x, RoundingMode::None)
+            .unwrap()
+            .acosh(p_x, RoundingMode::None, &mut cc)
+            .unwrap();
+        d2.set_precision(p, rm).unwrap();
+        assert!(x1.asech(p, rm, &mut cc).unwrap().cmp(&d2) == 0);
+        let mut d2 = x2
+            .reciprocal(p_x, RoundingMode::None)
+            .unwrap()
+            .atanh(p_x, RoundingMode::None, &mut cc)
+            .unwrap();
+        d2.set_precision(p, rm).unwrap();
+        assert!(x2.acoth(p, rm, &mut cc).unwrap().cmp(&d2) == 0);
+
+        // large argument
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(1000);
+        let mut r = BigFloatNumber::from_word(1, p).unwrap();
+        r.set_exponent(-998);
+
+        assert!(d1.acsch(p, rm, &mut cc).unwrap().cmp(&r) == 0);
+        assert!(d1.acsch(p, RoundingMode::Down, &mut cc).unwrap().cmp(&r) < 0);
+        assert!(d1.acoth(p, rm, &mut cc).unwrap().cmp(&r) == 0);
+        assert!(d1.acoth(p, RoundingMode::Up, &mut cc).unwrap().cmp(&r) > 0);
+
+        let d1 = BigFloatNumber::max_value(p).unwrap();
+        assert!(d1.acsch(p, rm, &mut cc).unwrap().is_positive());
+        assert!(d1.acoth(p, rm, &mut cc).unwrap().is_positive());
+
+        // special values and domain
+        assert!(ONE.asech(p, rm, &mut cc).unwrap().is_zero());
+
+        let zero = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        assert_eq!(
+            zero.asech(p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+        assert_eq!(
+            zero.acsch(p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Neg)
+        );
+        assert_eq!(
+            zero.acoth(p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        let m1 = ONE.neg().unwrap();
+        assert_eq!(
+            m1.acoth(p, rm, &mut cc).unwrap_err(),
+            Error::ExponentOverflow(Sign::Neg)
+        );
+        assert_eq!(
+            m1.asech(p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+        assert_eq!(
+            TWO.asech(p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        let mut d1 = BigFloatNumber::from_word(3, p).unwrap();
+        d1.set_exponent(0);
+        assert_eq!(
+            d1.acoth(p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        let d1 = random_subnormal(p).abs().unwrap();
+        assert!(d1.asech(p, rm, &mut cc).unwrap().exponent() > 0);
+        assert!(d1.acsch(p, rm, &mut cc).unwrap().exponent() > 0);
+    }
+}
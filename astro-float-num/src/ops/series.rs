@@ -8,12 +8,12 @@ use crate::common::util::sqrt_int;
 use crate::defs::Error;
 use crate::defs::RoundingMode;
 use crate::num::BigFloatNumber;
+use crate::tuning;
 
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
 const MAX_CACHE: usize = 128;
-const RECT_ITER_THRESHOLD: usize = MAX_CACHE / 10 * 9;
 
 //
 // Public part
@@ -92,7 +92,7 @@ pub(crate) fn series_run<T: PolycoeffGen>(
 ) -> Result<BigFloatNumber, Error> {
     let mut ret = if x_first.is_zero() || x_step.is_zero() {
         series_compute_fast(acc, x_first, polycoeff_gen)
-    } else if niter >= RECT_ITER_THRESHOLD {
+    } else if niter >= tuning::series_rect() {
         series_rectangular(niter, acc, x_first, x_step, polycoeff_gen)
     } else if polycoeff_gen.is_div() {
         series_linear(acc, x_first, x_step, polycoeff_gen)
@@ -105,10 +105,60 @@ pub(crate) fn series_run<T: PolycoeffGen>(
     Ok(ret)
 }
 
+/// Ratio of two consecutive terms of a series.
+pub(crate) trait TermRatio {
+    /// Returns the numerator and the denominator of the ratio of the term `k` to the term `k - 1`, `k >= 1`.
+    /// The numerator and the denominator are expected to have a small number of significant bits.
+    fn ratio(&self, k: usize) -> Result<(BigFloatNumber, BigFloatNumber), Error>;
+}
+
+/// Computes the sum of the first `n` terms of the series `1 + r(1) + r(1)*r(2) + ...` using binary splitting,
+/// where `r(k)` is the ratio given by `term_ratio`.
+/// Returns the numerator and the denominator of the sum. Both are computed exactly.
+/// The cost is O(M(p)*log(p)^2) instead of O(M(p)*n) of the other schemes if the ratios have a small number of bits.
+pub(crate) fn series_binary_splitting<R: TermRatio>(
+    term_ratio: &R,
+    n: usize,
+) -> Result<(BigFloatNumber, BigFloatNumber), Error> {
+    debug_assert!(n > 1);
+
+    let (_, q, t) = binary_splitting_pqt(term_ratio, 1, n)?;
+
+    // 1 + t / q
+    Ok((t.add_full_prec(&q)?, q))
+}
+
 //
 // Private part
 //
 
+/// Returns P, Q, and T for the terms `a..b` of the series given by `term_ratio`, where
+/// P = p(a)*...*p(b-1), Q = q(a)*...*q(b-1), and T / Q = r(a) + r(a)*r(a+1) + ... + r(a)*...*r(b-1).
+fn binary_splitting_pqt<R: TermRatio>(
+    term_ratio: &R,
+    a: usize,
+    b: usize,
+) -> Result<(BigFloatNumber, BigFloatNumber, BigFloatNumber), Error> {
+    if b - a == 1 {
+        let (p, q) = term_ratio.ratio(a)?;
+        let t = p.clone()?;
+
+        Ok((p, q, t))
+    } else {
+        let m = (a + b) / 2;
+
+        let (p1, q1, t1) = binary_splitting_pqt(term_ratio, a, m)?;
+        let (p2, q2, t2) = binary_splitting_pqt(term_ratio, m, b)?;
+
+        // T = T1*Q2 + P1*T2
+        let t = t1
+            .mul_full_prec(&q2)?
+            .add_full_prec(&p1.mul_full_prec(&t2)?)?;
+
+        Ok((p1.mul_full_prec(&p2)?, q1.mul_full_prec(&q2)?, t))
+    }
+}
+
 /// Estimate of the number of series iterations.
 /// p is the precision, m is the negative power of x
 /// (i.e. x = f*2^(-m), where 0.5 <= f < 1).
@@ -122,12 +172,12 @@ fn series_niter(p: usize, m: usize) -> usize {
 /// niter is the estimated number of series iterations
 /// p is the numbers precision
 /// polycoeff_gen is the coefficient generator
-fn series_cost<T: PolycoeffGen>(niter: usize, p: usize, polycoeff_gen: &T) -> u64 {
+pub(crate) fn series_cost<T: PolycoeffGen>(niter: usize, p: usize, polycoeff_gen: &T) -> u64 {
     let cost_mul = calc_mul_cost(p);
     let cost_add = calc_add_cost(p);
     let cost = niter as u64 * (cost_mul + cost_add + polycoeff_gen.iter_cost()) as u64;
 
-    if niter >= RECT_ITER_THRESHOLD {
+    if niter >= tuning::series_rect() {
         // niter * (cost(mul) + cost(add) + cost(polcoeff_gen.next)) + sqrt(niter) * cost(mul)
         // + niter / 10 * (2 * cost(mul) + cost(add) + cost(polcoeff_gen.next))
         cost + sqrt_int(niter as u32) as u64 * cost_mul as u64
@@ -425,7 +475,6 @@ fn compute_cube<T: PolycoeffGen>(
 ) -> Result<BigFloatNumber, Error> {
     if n > 1 {
         let mut acc = BigFloatNumber::new(p)?;
-        let cache_dim_sz = cache_dim_sz;
         // no need to multityply the returned coefficient of the first cube by 1.
         let poly_val = compute_cube(p, n - 1, cache, cache_dim_sz, polycoeff_gen)?;
         acc = acc.add(&poly_val, p, RoundingMode::None)?;
@@ -0,0 +1,361 @@
+//! Sine, cosine, and tangent of a number multiplied by pi.
+
+use crate::common::consts::ONE;
+use crate::common::util::invert_rm_for_sign;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::ops::consts::Consts;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes `sin(pi * self)` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The argument is reduced exactly, so the result is accurate for large arguments,
+    /// and integer and half-integer arguments give exact results.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn sin_pi(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        let (f, odd) = self.reduce_half()?;
+
+        if f.is_zero() {
+            // sin(pi * n) = 0
+            return Self::new2(p, self.sign(), self.inexact());
+        }
+
+        if f.exponent() == 0 && f.is_pow_of_two() {
+            // sin(pi * (n + 1/2)) = (-1)^n
+            let mut ret = Self::from_word(1, p)?;
+            ret.set_sign(if odd { f.sign().invert() } else { f.sign() });
+            ret.set_inexact(self.inexact());
+            return Ok(ret);
+        }
+
+        // sin(pi * (n + f)) = (-1)^n * sin(pi * f)
+        Self::trig_pi(&f, odd, p, rm, cc, |x, p, rm, cc| x.sin(p, rm, cc))
+    }
+
+    /// Computes `cos(pi * self)` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The argument is reduced exactly, so the result is accurate for large arguments,
+    /// and integer and half-integer arguments give exact results.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn cos_pi(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        let (f, odd) = self.reduce_half()?;
+
+        if f.is_zero() {
+            // cos(pi * n) = (-1)^n
+            let mut ret = Self::from_word(1, p)?;
+            ret.set_sign(if odd { Sign::Neg } else { Sign::Pos });
+            ret.set_inexact(self.inexact());
+            return Ok(ret);
+        }
+
+        if f.exponent() == 0 && f.is_pow_of_two() {
+            // cos(pi * (n + 1/2)) = 0
+            return Self::new2(p, Sign::Pos, self.inexact());
+        }
+
+        if f.exponent() < -1 || (f.exponent() == -1 && f.is_pow_of_two()) {
+            // |f| <= 1/4: cos(pi * (n + f)) = (-1)^n * cos(pi * f)
+            Self::trig_pi(&f, odd, p, rm, cc, |x, p, rm, cc| x.cos(p, rm, cc))
+        } else {
+            // |f| > 1/4: cos(pi * (n + f)) = (-1)^n * sin(pi * (1/2 - |f|)) without cancellation
+            let g = Self::half()?.sub_full_prec(&f.abs()?)?;
+            Self::trig_pi(&g, odd, p, rm, cc, |x, p, rm, cc| x.sin(p, rm, cc))
+        }
+    }
+
+    /// Computes `tan(pi * self)` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The argument is reduced exactly, so the result is accurate for large arguments,
+    /// and integer and quarter-integer arguments give exact results.
+    /// This function requires constants cache `cc` for computing the result.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large, or `self` is a half-integer.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn tan_pi(&self, p: usize, rm: RoundingMode, cc: &mut Consts) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        let (f, odd) = self.reduce_half()?;
+
+        if f.is_zero() {
+            // tan(pi * n) = 0
+            let s = if odd { self.sign().invert() } else { self.sign() };
+            return Self::new2(p, s, self.inexact());
+        }
+
+        if f.exponent() == 0 && f.is_pow_of_two() {
+            // tan(pi * (n + 1/2)) is a pole
+            return Err(Error::ExponentOverflow(f.sign()));
+        }
+
+        if f.exponent() == -1 && f.is_pow_of_two() {
+            // tan(pi * (n + 1/4)) = 1
+            let mut ret = Self::from_word(1, p)?;
+            ret.set_sign(f.sign());
+            ret.set_inexact(self.inexact());
+            return Ok(ret);
+        }
+
+        if f.exponent() < -1 {
+            // |f| < 1/4: tan(pi * (n + f)) = tan(pi * f)
+            Self::trig_pi(&f, false, p, rm, cc, |x, p, rm, cc| x.tan(p, rm, cc))
+        } else {
+            // |f| > 1/4: tan(pi * (n + f)) = sign(f) / tan(pi * (1/2 - |f|)) near the pole
+            let mut g = Self::half()?.sub_full_prec(&f.abs()?)?;
+            g.set_sign(f.sign());
+            Self::trig_pi(&g, false, p, rm, cc, |x, p, rm, cc| {
+                let t = x.tan(p, rm, cc)?;
+                ONE.div(&t, p, rm)
+            })
+        }
+    }
+
+    // Splits `self` into n + f, where n is an integer, and |f| <= 1/2.
+    // Returns f and a flag indicating n is odd.
+    // The reduction is exact.
+    fn reduce_half(&self) -> Result<(Self, bool), Error> {
+        let n = self.round(0, RoundingMode::ToEven)?;
+        let odd = !n.is_zero() && n.is_odd_int();
+        let f = self.sub_full_prec(&n)?;
+
+        Ok((f, odd))
+    }
+
+    // Returns 1/2.
+    fn half() -> Result<Self, Error> {
+        let mut ret = Self::from_word(1, WORD_BIT_SIZE)?;
+        ret.set_exponent(0);
+        Ok(ret)
+    }
+
+    // Returns true if the absolute value of `self` is a power of two.
+    pub(super) fn is_pow_of_two(&self) -> bool {
+        let (m, n, _, _, _) = self.as_raw_parts();
+        n > 0 && m.iter().map(|w| w.count_ones()).sum::<u32>() == 1
+    }
+
+    // Computes `(-1)^n * fun(pi * f)` if `odd` is true, or `fun(pi * f)` otherwise, with precision `p` using Ziv's loop.
+    fn trig_pi<F>(
+        f: &Self,
+        odd: bool,
+        p: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+        fun: F,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(&Self, usize, RoundingMode, &mut Consts) -> Result<Self, Error>,
+    {
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p.max(f.mantissa_max_bit_len()) + p_inc;
+
+        loop {
+            let p_x = p_wrk + 6;
+
+            let pi = cc.pi_num(p_x, RoundingMode::None)?;
+            let x = f.mul(&pi, p_x, RoundingMode::None)?;
+
+            let mut ret = if x.is_zero() || x.is_subnormal() {
+                // the argument is too close to zero: fun(x) is indistinguishable from x, 1, or 1/x
+                let rm = if odd { invert_rm_for_sign(rm) } else { rm };
+                let x = f.mul(&pi, p, rm)?;
+                let mut ret = fun(&x, p, rm, cc)?;
+                if odd {
+                    ret.inv_sign();
+                }
+                ret.set_inexact(true);
+                return Ok(ret);
+            } else {
+                fun(&x, p_x, RoundingMode::None, cc)?
+            };
+
+            if odd {
+                ret.inv_sign();
+            }
+
+            if ret.try_set_precision(p, rm, p_wrk)? {
+                ret.set_inexact(true);
+                return Ok(ret);
+            }
+
+            p_wrk += p_inc;
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::common::util::random_subnormal;
+    use crate::defs::EXPONENT_MAX;
+    use rand::random;
+
+    #[test]
+    fn test_sin_pi() {
+        let mut cc = Consts::new().unwrap();
+        let p = 320;
+        let rm = RoundingMode::ToEven;
+
+        // compare with the functions of pi * x computed with large precision
+        for _ in 0..300 {
+            let p1 = (random::<usize>() % 3 + 1) * WORD_BIT_SIZE;
+            let d1 = BigFloatNumber::random_normal(p1, -20, 6).unwrap();
+
+            let p_x = p + 1024 + p1;
+            let x = d1
+                .mul(
+                    &cc.pi_num(p_x, RoundingMode::None).unwrap(),
+                    p_x,
+                    RoundingMode::None,
+                )
+                .unwrap();
+
+            let mut s = x.sin(p_x, RoundingMode::None, &mut cc).unwrap();
+            s.set_precision(p, rm).unwrap();
+            let mut c = x.cos(p_x, RoundingMode::None, &mut cc).unwrap();
+            c.set_precision(p, rm).unwrap();
+            let mut t = x.tan(p_x, RoundingMode::None, &mut cc).unwrap();
+            t.set_precision(p, rm).unwrap();
+
+            assert!(d1.sin_pi(p, rm, &mut cc).unwrap().cmp(&s) == 0);
+            assert!(d1.cos_pi(p, rm, &mut cc).unwrap().cmp(&c) == 0);
+            assert!(d1.tan_pi(p, rm, &mut cc).unwrap().cmp(&t) == 0);
+        }
+
+        // exact values
+        let one = BigFloatNumber::from_word(1, p).unwrap();
+        let half = BigFloatNumber::half().unwrap();
+        let mut quarter = half.clone().unwrap();
+        quarter.set_exponent(-1);
+
+        for (n, c) in [(0, 1), (1, -1), (2, 1), (3, -1)] {
+            let d1 = BigFloatNumber::from_word(n, p).unwrap();
+            let d2 = d1.sin_pi(p, rm, &mut cc).unwrap();
+            assert!(d2.is_zero() && d2.is_positive() && !d2.inexact());
+            let d2 = d1.neg().unwrap().sin_pi(p, rm, &mut cc).unwrap();
+            assert!(d2.is_zero() && (n == 0 || d2.is_negative()));
+
+            let d2 = d1.cos_pi(p, rm, &mut cc).unwrap();
+            assert!(d2.abs_cmp(&one) == 0 && !d2.inexact());
+            assert!(d2.is_positive() == (c > 0));
+
+            let d2 = d1.tan_pi(p, rm, &mut cc).unwrap();
+            assert!(d2.is_zero() && d2.is_negative() == (n % 2 == 1));
+
+            // n + 1/2
+            let d1 = d1.add(&half, p, rm).unwrap();
+            let d2 = d1.sin_pi(p, rm, &mut cc).unwrap();
+            assert!(d2.abs_cmp(&one) == 0 && !d2.inexact());
+            assert!(d2.is_positive() == (c > 0));
+            let d2 = d1.neg().unwrap().sin_pi(p, rm, &mut cc).unwrap();
+            assert!(d2.abs_cmp(&one) == 0 && d2.is_positive() == (c < 0));
+
+            let d2 = d1.cos_pi(p, rm, &mut cc).unwrap();
+            assert!(d2.is_zero() && d2.is_positive() && !d2.inexact());
+
+            assert_eq!(
+                d1.tan_pi(p, rm, &mut cc).unwrap_err(),
+                Error::ExponentOverflow(if n % 2 == 0 { Sign::Pos } else { Sign::Neg })
+            );
+
+            // n + 1/4, n - 1/4
+            let d1 = d1.sub(&quarter, p, rm).unwrap();
+            let d2 = d1.tan_pi(p, rm, &mut cc).unwrap();
+            assert!(d2.cmp(&one) == 0 && !d2.inexact());
+            let d1 = d1.sub(&half, p, rm).unwrap();
+            let d2 = d1.tan_pi(p, rm, &mut cc).unwrap();
+            assert!(d2.cmp(&one.neg().unwrap()) == 0 && !d2.inexact());
+        }
+
+        // large argument
+        let mut d1 = BigFloatNumber::from_word(3, p).unwrap();
+        d1.set_exponent(EXPONENT_MAX);
+        assert!(d1.sin_pi(p, rm, &mut cc).unwrap().is_zero());
+        assert!(d1.cos_pi(p, rm, &mut cc).unwrap().cmp(&one) == 0);
+
+        // 2^100 + 1 + 1/8
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(101);
+        let mut d2 = half.clone().unwrap();
+        d2.set_exponent(-2);
+        let d1 = d1.add(&one, p, rm).unwrap().add(&d2, p, rm).unwrap();
+        let p_x = p * 2;
+        let mut d3 = cc.pi_num(p_x, RoundingMode::None).unwrap();
+        d3.set_exponent(d3.exponent() - 3);
+        let mut s = d3
+            .sin(p_x, RoundingMode::None, &mut cc)
+            .unwrap()
+            .neg()
+            .unwrap();
+        s.set_precision(p, rm).unwrap();
+        assert!(d1.sin_pi(p, rm, &mut cc).unwrap().cmp(&s) == 0);
+        let mut c = d3
+            .cos(p_x, RoundingMode::None, &mut cc)
+            .unwrap()
+            .neg()
+            .unwrap();
+        c.set_precision(p, rm).unwrap();
+        assert!(d1.cos_pi(p, rm, &mut cc).unwrap().cmp(&c) == 0);
+        let mut t = d3.tan(p_x, RoundingMode::None, &mut cc).unwrap();
+        t.set_precision(p, rm).unwrap();
+        assert!(d1.tan_pi(p, rm, &mut cc).unwrap().cmp(&t) == 0);
+
+        // near the pole and the zeroes of cos
+        let mut eps = BigFloatNumber::from_word(1, p).unwrap();
+        eps.set_exponent(-1000);
+        let d1 = half.sub(&eps, p * 4, RoundingMode::None).unwrap();
+        let d2 = d1.cos_pi(p, rm, &mut cc).unwrap();
+        let mut d3 = cc.pi_num(p_x, RoundingMode::None).unwrap();
+        d3.set_exponent(d3.exponent() - 1001);
+        let mut d4 = d3.clone().unwrap();
+        d4.set_precision(p, rm).unwrap();
+        assert!(d2.cmp(&d4) == 0);
+        let d2 = d1.tan_pi(p, rm, &mut cc).unwrap();
+        let d4 = one.div(&d3, p, rm).unwrap();
+        assert!(d2.cmp(&d4) == 0);
+
+        // directed rounding
+        let d1 = one
+            .div(&BigFloatNumber::from_word(3, p).unwrap(), p, rm)
+            .unwrap();
+        let up = d1.sin_pi(p, RoundingMode::Up, &mut cc).unwrap();
+        let down = d1.sin_pi(p, RoundingMode::Down, &mut cc).unwrap();
+        assert!(up.cmp(&down) > 0);
+
+        // small argument
+        let d1 = random_subnormal(p);
+        let d2 = d1.sin_pi(p, rm, &mut cc).unwrap();
+        assert!(d2.abs_cmp(&d1) > 0 && d2.inexact());
+        let d2 = d1.cos_pi(p, rm, &mut cc).unwrap();
+        assert!(d2.cmp(&one) == 0 && d2.inexact());
+
+        let d1 = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let d2 = d1.sin_pi(p, rm, &mut cc).unwrap();
+        assert!(d2.is_zero() && d2.is_negative() && !d2.inexact());
+    }
+}
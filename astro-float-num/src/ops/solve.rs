@@ -0,0 +1,563 @@
+//! Root finding for functions given by closures.
+//!
+//! The bracketing methods `bisection` and `brent` require an interval on which the function changes its sign,
+//! and keep the root enclosed in a shrinking interval, so the returned error bound is certified
+//! for a continuous function. Newton's method converges faster from a good initial approximation,
+//! and can optionally verify the result by checking the sign change of the function around the root.
+//!
+//! ```
+//! use astro_float_num::solve::{brent, newton, Tolerance};
+//! use astro_float_num::{BigFloat, RoundingMode};
+//!
+//! let p = 256;
+//! let rm = RoundingMode::ToEven;
+//! let two = BigFloat::from_word(2, p);
+//!
+//! // the root of x^2 - 2
+//! let f = |x: &BigFloat| x.mul(x, p, rm).sub(&two, p, rm);
+//! let df = |x: &BigFloat| x.mul(&two, p, rm);
+//!
+//! let tol = Tolerance::Ulps(4);
+//! let r1 = brent(f, &BigFloat::from_word(1, p), &two, &tol, p, 1000).unwrap();
+//! let r2 = newton(f, df, &BigFloat::from_word(1, p), &tol, p, 100, true).unwrap();
+//!
+//! let sqrt2 = two.sqrt(p, rm);
+//! assert!(r1.x().sub(&sqrt2, p, rm).abs() <= *r1.err());
+//! assert!(r2.x().sub(&sqrt2, p, rm).abs() <= *r2.err());
+//! ```
+
+use crate::ext::ONE;
+use crate::ext::TWO;
+use crate::{BigFloat, RoundingMode};
+use core::fmt::Display;
+
+/// Stopping criterion of a root-finding method.
+#[derive(Debug, Clone)]
+pub enum Tolerance {
+    /// The distance between the result and the root is at most the given number of units in the last place
+    /// of the result with the precision of the computation.
+    Ulps(usize),
+
+    /// The distance between the result and the root is at most the given absolute value.
+    Abs(BigFloat),
+}
+
+/// Result of a root-finding method.
+#[derive(Debug, Clone)]
+pub struct Root {
+    x: BigFloat,
+    err: BigFloat,
+    iterations: usize,
+}
+
+impl Root {
+    /// Returns the approximation of the root.
+    pub fn x(&self) -> &BigFloat {
+        &self.x
+    }
+
+    /// Returns the bound of the distance between the approximation and the root.
+    /// The bound is certified for the bracketing methods and for verified results of Newton's method.
+    /// For unverified results of Newton's method it is the size of the last step.
+    pub fn err(&self) -> &BigFloat {
+        &self.err
+    }
+
+    /// Returns the number of iterations performed.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+}
+
+/// Root-finding errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveError {
+    /// The function does not change its sign on the interval.
+    NotBracketed,
+
+    /// The tolerance was not reached within the maximum number of iterations or with the given precision.
+    NoConvergence,
+
+    /// The function or its derivative returned NaN or Inf, or the derivative became zero.
+    InvalidValue,
+
+    /// Newton's method converged, but the sign change of the function around the result was not confirmed.
+    NotVerified,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SolveError {}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let repr = match self {
+            SolveError::NotBracketed => "the function does not change its sign on the interval",
+            SolveError::NoConvergence => "the tolerance is not reached",
+            SolveError::InvalidValue => "invalid function or derivative value",
+            SolveError::NotVerified => "the root is not verified",
+        };
+        f.write_str(repr)
+    }
+}
+
+/// Finds a root of `f` on the interval between `a` and `b` using bisection with precision `p`.
+/// `f` must have opposite signs at `a` and `b`, or be zero at one of them.
+/// The method performs at most `max_iter` iterations.
+/// Precision is rounded upwards to the word size.
+///
+/// ## Errors
+///
+///  - NotBracketed: `f` has the same sign at `a` and `b`.
+///  - NoConvergence: the tolerance is not reached in `max_iter` iterations or with precision `p`.
+///  - InvalidValue: `f` returned NaN or Inf, or `a` or `b` is NaN or Inf.
+pub fn bisection<F>(
+    mut f: F,
+    a: &BigFloat,
+    b: &BigFloat,
+    tol: &Tolerance,
+    p: usize,
+    max_iter: usize,
+) -> Result<Root, SolveError>
+where
+    F: FnMut(&BigFloat) -> BigFloat,
+{
+    let (mut a, mut b, fa, fb) = bracket(&mut f, a, b)?;
+
+    if fa.is_zero() {
+        return Ok(exact(a, 0));
+    } else if fb.is_zero() {
+        return Ok(exact(b, 0));
+    }
+
+    let neg_a = fa.is_negative();
+
+    for i in 1..=max_iter {
+        let m = a
+            .add(&b, p, RoundingMode::ToEven)
+            .div(&TWO, p, RoundingMode::ToEven);
+        let w = half_width(&a, &b, p);
+
+        if w <= tol_abs(tol, &m, p) {
+            return Ok(Root {
+                x: m,
+                err: w,
+                iterations: i - 1,
+            });
+        }
+
+        if m == a || m == b {
+            return Err(SolveError::NoConvergence);
+        }
+
+        let fm = value(f(&m))?;
+        if fm.is_zero() {
+            return Ok(exact(m, i));
+        }
+
+        if fm.is_negative() == neg_a {
+            a = m;
+        } else {
+            b = m;
+        }
+    }
+
+    Err(SolveError::NoConvergence)
+}
+
+/// Finds a root of `f` on the interval between `a` and `b` using Brent's method with precision `p`.
+/// Brent's method combines bisection with the secant method and inverse quadratic interpolation,
+/// so it converges superlinearly for smooth functions while keeping the root enclosed.
+/// `f` must have opposite signs at `a` and `b`, or be zero at one of them.
+/// The method performs at most `max_iter` iterations.
+/// Precision is rounded upwards to the word size.
+///
+/// ## Errors
+///
+///  - NotBracketed: `f` has the same sign at `a` and `b`.
+///  - NoConvergence: the tolerance is not reached in `max_iter` iterations or with precision `p`.
+///  - InvalidValue: `f` returned NaN or Inf, or `a` or `b` is NaN or Inf.
+pub fn brent<F>(
+    mut f: F,
+    a: &BigFloat,
+    b: &BigFloat,
+    tol: &Tolerance,
+    p: usize,
+    max_iter: usize,
+) -> Result<Root, SolveError>
+where
+    F: FnMut(&BigFloat) -> BigFloat,
+{
+    let rm = RoundingMode::ToEven;
+
+    let (mut a, mut b, mut fa, mut fb) = bracket(&mut f, a, b)?;
+
+    if fa.is_zero() {
+        return Ok(exact(a, 0));
+    }
+
+    let mut c = a.clone();
+    let mut fc = fa.clone();
+    let mut d = b.sub(&a, p, rm);
+    let mut e = d.clone();
+
+    for i in 0..=max_iter {
+        if fb.is_zero() {
+            return Ok(exact(b, i));
+        }
+
+        if fb.is_negative() == fc.is_negative() {
+            c = a.clone();
+            fc = fa.clone();
+            d = b.sub(&a, p, rm);
+            e = d.clone();
+        }
+
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a.clone();
+            fa = fb;
+            fb = fc;
+            fc = fa.clone();
+        }
+
+        // the root is between b and c
+        let w = half_width(&b, &c, p);
+        let err = w.add(&w, p, RoundingMode::FromZero);
+        let tol = tol_abs(tol, &b, p);
+        if err <= tol {
+            return Ok(Root {
+                x: b,
+                err,
+                iterations: i,
+            });
+        }
+
+        if i == max_iter {
+            break;
+        }
+
+        // b and c are adjacent numbers
+        let ulp = b.ulp(p);
+        if err <= ulp.min(&c.ulp(p)) {
+            return Err(SolveError::NoConvergence);
+        }
+
+        let tol1 = tol.div(&TWO, p, rm).max(&ulp);
+        let m = c.sub(&b, p, rm).div(&TWO, p, rm);
+
+        if e.abs() >= tol1 && fa.abs() > fb.abs() {
+            // interpolation
+            let s = fb.div(&fa, p, rm);
+            let (mut pp, mut q);
+
+            if a == c {
+                // secant
+                pp = m.mul(&TWO, p, rm).mul(&s, p, rm);
+                q = ONE.sub(&s, p, rm);
+            } else {
+                // inverse quadratic
+                let q0 = fa.div(&fc, p, rm);
+                let r = fb.div(&fc, p, rm);
+                let t1 = m
+                    .mul(&TWO, p, rm)
+                    .mul(&q0, p, rm)
+                    .mul(&q0.sub(&r, p, rm), p, rm);
+                let t2 = b.sub(&a, p, rm).mul(&r.sub(&ONE, p, rm), p, rm);
+                pp = s.mul(&t1.sub(&t2, p, rm), p, rm);
+                q = q0.sub(&ONE, p, rm).mul(&r.sub(&ONE, p, rm), p, rm).mul(
+                    &s.sub(&ONE, p, rm),
+                    p,
+                    rm,
+                );
+            }
+
+            if pp.is_positive() {
+                q = q.neg();
+            } else {
+                pp = pp.neg();
+            }
+
+            let lim1 = BigFloat::from_word(3, p).mul(&m, p, rm).mul(&q, p, rm).sub(
+                &tol1.mul(&q, p, rm).abs(),
+                p,
+                rm,
+            );
+            let lim2 = e.mul(&q, p, rm).div(&TWO, p, rm).abs();
+
+            if pp.mul(&TWO, p, rm) < lim1 && pp < lim2 {
+                e = d;
+                d = pp.div(&q, p, rm);
+            } else {
+                d = m.clone();
+                e = m.clone();
+            }
+        } else {
+            // bisection
+            d = m.clone();
+            e = m.clone();
+        }
+
+        a = b.clone();
+        fa = fb;
+
+        if d.abs() > tol1 {
+            b = b.add(&d, p, rm);
+        } else if m.is_negative() {
+            b = b.sub(&tol1, p, rm);
+        } else {
+            b = b.add(&tol1, p, rm);
+        }
+
+        fb = value(f(&b))?;
+    }
+
+    Err(SolveError::NoConvergence)
+}
+
+/// Finds a root of `f` using Newton's method with the derivative `df` starting from `x0` with precision `p`.
+/// The iterations stop when the step becomes smaller than the tolerance.
+/// If `verify` is true, the sign change of `f` is checked on the interval around the result
+/// with the radius equal to the tolerance, which certifies the error bound of the result for a continuous `f`.
+/// The method performs at most `max_iter` iterations.
+/// Precision is rounded upwards to the word size.
+///
+/// ## Errors
+///
+///  - NoConvergence: the tolerance is not reached in `max_iter` iterations.
+///  - InvalidValue: `f` or `df` returned NaN or Inf, `df` returned zero, or `x0` is NaN or Inf.
+///  - NotVerified: `verify` is true, and the sign change of `f` around the result is not confirmed.
+pub fn newton<F, D>(
+    mut f: F,
+    mut df: D,
+    x0: &BigFloat,
+    tol: &Tolerance,
+    p: usize,
+    max_iter: usize,
+    verify: bool,
+) -> Result<Root, SolveError>
+where
+    F: FnMut(&BigFloat) -> BigFloat,
+    D: FnMut(&BigFloat) -> BigFloat,
+{
+    let rm = RoundingMode::ToEven;
+
+    let mut x = value(x0.clone())?;
+
+    for i in 1..=max_iter {
+        let fx = value(f(&x))?;
+        if fx.is_zero() {
+            return Ok(exact(x, i - 1));
+        }
+
+        let dfx = value(df(&x))?;
+        if dfx.is_zero() {
+            return Err(SolveError::InvalidValue);
+        }
+
+        let step = fx.div(&dfx, p, rm);
+        x = value(x.sub(&step, p, rm))?;
+
+        let t = tol_abs(tol, &x, p);
+        let step = step.abs();
+
+        if step <= t {
+            if !verify {
+                return Ok(Root {
+                    x,
+                    err: step,
+                    iterations: i,
+                });
+            }
+
+            let fx = value(f(&x))?;
+            if fx.is_zero() {
+                return Ok(exact(x, i));
+            }
+
+            let lo = value(f(&x.sub(&t, p, RoundingMode::None)))?;
+            let hi = value(f(&x.add(&t, p, RoundingMode::None)))?;
+
+            return if lo.is_zero()
+                || hi.is_zero()
+                || lo.is_negative() != fx.is_negative()
+                || hi.is_negative() != fx.is_negative()
+            {
+                Ok(Root {
+                    x,
+                    err: t,
+                    iterations: i,
+                })
+            } else {
+                Err(SolveError::NotVerified)
+            };
+        }
+    }
+
+    Err(SolveError::NoConvergence)
+}
+
+// Checks the arguments and the function values at the ends of the interval.
+fn bracket<F>(
+    f: &mut F,
+    a: &BigFloat,
+    b: &BigFloat,
+) -> Result<(BigFloat, BigFloat, BigFloat, BigFloat), SolveError>
+where
+    F: FnMut(&BigFloat) -> BigFloat,
+{
+    let a = value(a.clone())?;
+    let b = value(b.clone())?;
+
+    let fa = value(f(&a))?;
+    let fb = value(f(&b))?;
+
+    if !fa.is_zero() && !fb.is_zero() && fa.is_negative() == fb.is_negative() {
+        return Err(SolveError::NotBracketed);
+    }
+
+    Ok((a, b, fa, fb))
+}
+
+fn value(x: BigFloat) -> Result<BigFloat, SolveError> {
+    if x.is_nan() || x.is_inf() {
+        Err(SolveError::InvalidValue)
+    } else {
+        Ok(x)
+    }
+}
+
+fn exact(x: BigFloat, iterations: usize) -> Root {
+    Root {
+        x,
+        err: BigFloat::new(1),
+        iterations,
+    }
+}
+
+// Returns the upper bound of |a - b| / 2.
+fn half_width(a: &BigFloat, b: &BigFloat, p: usize) -> BigFloat {
+    a.sub(b, p, RoundingMode::FromZero)
+        .abs()
+        .div(&TWO, p, RoundingMode::FromZero)
+}
+
+// Returns the absolute tolerance at `x`.
+fn tol_abs(tol: &Tolerance, x: &BigFloat, p: usize) -> BigFloat {
+    match tol {
+        Tolerance::Ulps(n) => {
+            x.ulp(p)
+                .mul(&BigFloat::from_u64(*n as u64, 64), p, RoundingMode::ToZero)
+        }
+        Tolerance::Abs(e) => e.abs(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Consts;
+
+    #[test]
+    fn test_solve() {
+        let p = 256;
+        let rm = RoundingMode::ToEven;
+        let mut cc = Consts::new().unwrap();
+
+        let two = BigFloat::from_word(2, p);
+        let sqrt2 = two.sqrt(p, rm);
+        let f = |x: &BigFloat| x.mul(x, p, rm).sub(&two, p, rm);
+        let df = |x: &BigFloat| x.mul(&two, p, rm);
+        let one = BigFloat::from_word(1, p);
+
+        for tol in [
+            Tolerance::Ulps(1),
+            Tolerance::Ulps(10),
+            Tolerance::Abs(BigFloat::from_f64(1e-30, p)),
+        ] {
+            let r = bisection(f, &one, &two, &tol, p, 1000).unwrap();
+            assert!(r.x().sub(&sqrt2, p, rm).abs() <= *r.err());
+            assert!(*r.err() <= tol_abs(&tol, r.x(), p));
+
+            let r2 = brent(f, &two, &one, &tol, p, 1000).unwrap();
+            assert!(r2.x().sub(&sqrt2, p, rm).abs() <= *r2.err());
+            assert!(*r2.err() <= tol_abs(&tol, r2.x(), p));
+            assert!(r2.iterations() < r.iterations());
+
+            let r3 = newton(f, df, &one, &tol, p, 100, true).unwrap();
+            assert!(r3.x().sub(&sqrt2, p, rm).abs() <= *r3.err());
+            assert!(r3.iterations() < r2.iterations());
+
+            let r4 = newton(f, df, &one, &tol, p, 100, false).unwrap();
+            assert_eq!(r4.x(), r3.x());
+        }
+
+        // the root of cos(x) - x
+        let mut cc2 = Consts::new().unwrap();
+        let h = |x: &BigFloat| x.cos(p, rm, &mut cc2).sub(x, p, rm);
+        let r = brent(h, &BigFloat::new(p), &one, &Tolerance::Ulps(2), p, 1000).unwrap();
+        let x = r.x();
+        let c = x.cos(p, rm, &mut cc);
+        assert!(c.sub(x, p, rm).abs() <= x.ulp(p).mul(&BigFloat::from_word(8, p), p, rm));
+
+        // exact roots
+        let g = |x: &BigFloat| x.sub(&BigFloat::from_f64(0.375, p), p, rm);
+        let r = bisection(g, &BigFloat::new(p), &one, &Tolerance::Ulps(1), p, 1000).unwrap();
+        assert_eq!(r.x(), &BigFloat::from_f64(0.375, p));
+        assert!(r.err().is_zero());
+        let r = brent(
+            g,
+            &BigFloat::from_f64(0.375, p),
+            &one,
+            &Tolerance::Ulps(1),
+            p,
+            10,
+        )
+        .unwrap();
+        assert_eq!(r.x(), &BigFloat::from_f64(0.375, p));
+        assert_eq!(r.iterations(), 0);
+
+        // errors
+        let tol = Tolerance::Ulps(1);
+        assert_eq!(
+            bisection(f, &two, &BigFloat::from_word(3, p), &tol, p, 1000).unwrap_err(),
+            SolveError::NotBracketed
+        );
+        assert_eq!(
+            brent(f, &one, &BigFloat::nan(None), &tol, p, 1000).unwrap_err(),
+            SolveError::InvalidValue
+        );
+        assert_eq!(
+            bisection(f, &one, &two, &tol, p, 10).unwrap_err(),
+            SolveError::NoConvergence
+        );
+        assert_eq!(
+            bisection(
+                f,
+                &one,
+                &two,
+                &Tolerance::Abs(BigFloat::from_f64(1e-300, p)),
+                p,
+                10000
+            )
+            .unwrap_err(),
+            SolveError::NoConvergence
+        );
+        let f = |x: &BigFloat| x.mul(x, p, rm).sub(&two, p, rm);
+        let df = |x: &BigFloat| x.mul(&two, p, rm);
+        assert_eq!(
+            newton(f, df, &BigFloat::new(p), &tol, p, 100, true).unwrap_err(),
+            SolveError::InvalidValue
+        );
+
+        // verification fails for x^2 which does not change its sign at the root
+        let f = |x: &BigFloat| x.mul(x, p, rm);
+        let tol = Tolerance::Abs(BigFloat::from_f64(1e-10, p));
+        assert_eq!(
+            newton(f, df, &one, &tol, p, 1000, true).unwrap_err(),
+            SolveError::NotVerified
+        );
+        assert!(newton(f, df, &one, &tol, p, 1000, false).is_ok());
+    }
+}
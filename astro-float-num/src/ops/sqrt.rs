@@ -28,6 +28,10 @@ impl BigFloatNumber {
             return Err(Error::InvalidArgument);
         }
 
+        if let Some(ret) = self.sqrt_fast(p)? {
+            return Ok(ret);
+        }
+
         let (e1, m1_opt) = self.normalize()?;
         let m1_normalized = m1_opt.as_ref().unwrap_or_else(|| self.mantissa());
 
@@ -0,0 +1,194 @@
+//! Sum of a sequence of numbers.
+
+use crate::common::util::log2_ceil;
+use crate::common::util::round_p;
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::num::BigFloatNumber;
+use crate::Sign;
+use crate::WORD_BIT_SIZE;
+
+impl BigFloatNumber {
+    /// Computes the sum of `values` with precision `p`. The result is rounded using the rounding mode `rm`.
+    /// The sum is rounded once, as if it was computed exactly and then rounded.
+    /// The sum of an empty sequence is positive zero, and the sum of negative zeros is negative zero.
+    /// Precision is rounded upwards to the word size.
+    ///
+    /// ## Errors
+    ///
+    ///  - ExponentOverflow: the result is too large.
+    ///  - MemoryAllocation: failed to allocate memory.
+    ///  - InvalidArgument: the precision is incorrect.
+    pub fn sum(values: &[&Self], p: usize, rm: RoundingMode) -> Result<Self, Error> {
+        let p = round_p(p);
+        Self::p_assertion(p)?;
+
+        let inexact = values.iter().any(|v| v.inexact());
+
+        let mut n = 0;
+        let mut emax = isize::MIN;
+        let mut lsb = isize::MAX;
+
+        for v in values.iter().filter(|v| !v.is_zero()) {
+            n += 1;
+            emax = emax.max(v.exponent() as isize);
+            lsb = lsb.min(v.exponent() as isize - v.mantissa_max_bit_len() as isize);
+        }
+
+        if n == 0 {
+            let s = if !values.is_empty() && values.iter().all(|v| v.is_negative()) {
+                Sign::Neg
+            } else {
+                Sign::Pos
+            };
+
+            return Self::new2(p, s, inexact);
+        }
+
+        // the partial sums are smaller than 2^(emax + log_n),
+        // and the exact sum is computed with any precision which covers all bits down to lsb
+        let log_n = log2_ceil(n) as isize + 1;
+        let p_exact = round_p((emax + log_n - lsb) as usize);
+
+        let mut p_inc = WORD_BIT_SIZE;
+        let mut p_wrk = p + log_n as usize + p_inc;
+
+        loop {
+            let exact = p_wrk >= p_exact;
+            if exact {
+                p_wrk = p_exact;
+            }
+
+            let mut ret = Self::new(p_wrk)?;
+            for v in values.iter() {
+                ret = ret.add(v, p_wrk, RoundingMode::None)?;
+            }
+
+            if exact {
+                ret.set_precision(p, rm)?;
+                ret.set_inexact(ret.inexact() | inexact);
+                return Ok(ret);
+            }
+
+            if !ret.is_zero() {
+                // each addition has an error smaller than 2^(emax + log_n - p_wrk)
+                let s = p_wrk as isize - (emax + log_n - ret.exponent() as isize) - log_n;
+
+                // the roundability check requires the number of correct bits to be aligned to the word size
+                let s = s / WORD_BIT_SIZE as isize * WORD_BIT_SIZE as isize;
+
+                if s >= (p + WORD_BIT_SIZE) as isize {
+                    // the sum can be inexact, so rounding is checked
+                    ret.set_inexact(true);
+                    if ret.try_set_precision(p, rm, s as usize)? {
+                        return Ok(ret);
+                    }
+                }
+
+                p_wrk += p_inc.max((p as isize - s).max(0) as usize);
+            } else {
+                p_wrk += p_inc;
+            }
+
+            p_inc = round_p(p_wrk / 5);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::common::util::random_subnormal;
+    use rand::random;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_sum() {
+        // compare with the exact sum
+        for _ in 0..100 {
+            let p = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+            let n = random::<usize>() % 20 + 1;
+            let mut values = Vec::new();
+            for _ in 0..n {
+                let p1 = (random::<usize>() % 4 + 1) * WORD_BIT_SIZE;
+                values.push(BigFloatNumber::random_normal(p1, -100, 100).unwrap());
+            }
+
+            let refs: Vec<&BigFloatNumber> = values.iter().collect();
+
+            let mut exact = BigFloatNumber::new(p).unwrap();
+            for v in values.iter() {
+                exact = exact.add_full_prec(v).unwrap();
+            }
+
+            for rm in [
+                RoundingMode::ToEven,
+                RoundingMode::ToOdd,
+                RoundingMode::Up,
+                RoundingMode::Down,
+                RoundingMode::ToZero,
+                RoundingMode::FromZero,
+            ] {
+                let d1 = BigFloatNumber::sum(&refs, p, rm).unwrap();
+                let mut d2 = exact.clone().unwrap();
+                d2.set_precision(p, rm).unwrap();
+                assert!(d1.cmp(&d2) == 0);
+                assert_eq!(d1.inexact(), d2.inexact());
+            }
+        }
+
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // cancellation
+        let mut d1 = BigFloatNumber::from_word(1, p).unwrap();
+        d1.set_exponent(1000);
+        let mut d2 = BigFloatNumber::from_word(3, p).unwrap();
+        d2.set_exponent(-1000);
+        let d3 = d1.neg().unwrap();
+        let d = BigFloatNumber::sum(&[&d1, &d2, &d3], p, rm).unwrap();
+        assert!(d.cmp(&d2) == 0 && !d.inexact());
+
+        // small terms decide directed rounding
+        let mut d2 = BigFloatNumber::from_word(1, p).unwrap();
+        d2.set_exponent(-1000);
+        let d = BigFloatNumber::sum(&[&d1, &d2], p, RoundingMode::Up).unwrap();
+        assert!(d.cmp(&d1) > 0 && d.inexact());
+        let d = BigFloatNumber::sum(&[&d1, &d2], p, RoundingMode::Down).unwrap();
+        assert!(d.cmp(&d1) == 0 && d.inexact());
+
+        // subnormal
+        let d1 = random_subnormal(p);
+        let d2 = random_subnormal(p);
+        let d = BigFloatNumber::sum(&[&d1, &d2], p, rm).unwrap();
+        let mut d3 = d1.add_full_prec(&d2).unwrap();
+        d3.set_precision(p, rm).unwrap();
+        assert!(d.cmp(&d3) == 0);
+
+        // zeros
+        let z1 = BigFloatNumber::new2(p, Sign::Neg, false).unwrap();
+        let z2 = BigFloatNumber::new2(p, Sign::Pos, true).unwrap();
+        let d = BigFloatNumber::sum(&[], p, rm).unwrap();
+        assert!(d.is_zero() && d.is_positive() && !d.inexact());
+        let d = BigFloatNumber::sum(&[&z1, &z1], p, rm).unwrap();
+        assert!(d.is_zero() && d.is_negative() && !d.inexact());
+        let d = BigFloatNumber::sum(&[&z1, &z2], p, rm).unwrap();
+        assert!(d.is_zero() && d.is_positive() && d.inexact());
+
+        // inexact arguments
+        let mut d1 = BigFloatNumber::from_word(3, p).unwrap();
+        d1.set_inexact(true);
+        let d = BigFloatNumber::sum(&[&d1, &z1], p, rm).unwrap();
+        assert!(d.cmp(&d1) == 0 && d.inexact());
+
+        // overflow
+        let d1 = BigFloatNumber::max_value(p).unwrap();
+        assert_eq!(
+            BigFloatNumber::sum(&[&d1, &d1], p, rm).unwrap_err(),
+            Error::ExponentOverflow(Sign::Pos)
+        );
+    }
+}
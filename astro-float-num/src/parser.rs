@@ -1,6 +1,8 @@
 //! Parser parses numbers represented in scientific format.
 
 use crate::defs::Exponent;
+use crate::defs::ParseError;
+use crate::defs::ParseErrorKind;
 use crate::defs::Sign;
 use crate::defs::EXPONENT_MAX;
 use crate::Error;
@@ -12,25 +14,31 @@ use core::str::Chars;
 use alloc::vec::Vec;
 
 pub struct ParserState<'a> {
+    s: &'a str,
     chars: Chars<'a>,
     cur_ch: Option<char>,
     s_len: usize,
+    base: u32,
     sign: Sign,
     mantissa_bytes: Vec<u8>,
     e: isize,
+    bin_e: Option<isize>,
     inf: bool,
     nan: bool,
 }
 
 impl<'a> ParserState<'a> {
-    fn new(s: &'a str) -> Self {
+    fn new(s: &'a str, base: u32) -> Self {
         ParserState {
+            s,
             chars: s.chars(),
             s_len: s.len(),
+            base,
             cur_ch: None,
             sign: Sign::Pos,
             mantissa_bytes: Vec::new(),
             e: 0,
+            bin_e: None,
             inf: false,
             nan: true,
         }
@@ -47,6 +55,48 @@ impl<'a> ParserState<'a> {
         self.cur_ch
     }
 
+    // Returns the character following the current character in lower case without advancing.
+    fn peek_char(&self) -> Option<char> {
+        self.chars.clone().next().map(|c| c.to_ascii_lowercase())
+    }
+
+    // Returns the position of the current character in bytes.
+    fn pos(&self) -> usize {
+        self.s_len - self.chars.as_str().len() - self.cur_ch.map_or(0, |c| c.len_utf8())
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(kind, self.pos())
+    }
+
+    // Returns the error for the current character which is not expected at its position.
+    fn unexpected(&self) -> ParseError {
+        // the character is taken from the string, because the current character is in lower case
+        // hexadecimal digits except for the exponent marker `e` are reported as invalid digits,
+        // and in radixes above 16 all letters are
+        let base = self.base;
+        match self.s[self.pos()..].chars().next() {
+            Some(c)
+                if c.to_digit(36)
+                    .is_some_and(|d| base > 16 || d < 16 && (d != 14 || base > 14)) =>
+            {
+                self.error(ParseErrorKind::InvalidDigit(c))
+            }
+            Some(c) => self.error(ParseErrorKind::UnexpectedChar(c)),
+            None => self.error(ParseErrorKind::UnexpectedEnd),
+        }
+    }
+
+    // Checks that the current character is `c` and advances.
+    fn expect_char(&mut self, c: char) -> Result<(), ParseError> {
+        if self.cur_char() == Some(c) {
+            self.next_char();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
     pub fn is_inf(&self) -> bool {
         self.inf
     }
@@ -63,11 +113,29 @@ impl<'a> ParserState<'a> {
     pub fn raw_parts(&self) -> (&[u8], Sign, Exponent) {
         (&self.mantissa_bytes, self.sign, self.e as Exponent)
     }
+
+    /// Returns the binary exponent if the number is in hexadecimal floating point notation.
+    /// In this case, the mantissa digits and the exponent returned by `raw_parts` are in radix 16,
+    /// and the value must be multiplied by 2 to the power of the binary exponent.
+    pub fn bin_exp(&self) -> Option<isize> {
+        self.bin_e
+    }
 }
 
 /// Parse BigFloat.
-pub fn parse(s: &str, rdx: Radix) -> Result<ParserState, Error> {
-    let mut parser_state = ParserState::new(s);
+/// The whole string `s` must be a number, otherwise an error describing the problem and its position is returned.
+#[cfg(test)]
+pub fn parse(s: &str, rdx: Radix) -> Result<ParserState<'_>, ParseError> {
+    parse_base(s, rdx as u32)
+}
+
+/// Parse BigFloat in radix `base` from 2 to 36.
+/// Letters are used as digits above 9. If `e` is a digit in the radix, the exponent is separated by `_e`.
+/// Letters `i` and `n` start infinity and NaN only if they are not digits in the radix, or if the rest of the string is `inf` or `nan`.
+pub fn parse_base(s: &str, base: u32) -> Result<ParserState<'_>, ParseError> {
+    debug_assert!((2..=36).contains(&base));
+
+    let mut parser_state = ParserState::new(s, base);
     let mut ch = parser_state.next_char();
 
     // sign
@@ -82,85 +150,137 @@ pub fn parse(s: &str, rdx: Radix) -> Result<ParserState, Error> {
         };
     }
 
-    if let Some(c) = ch {
-        match (c, rdx) {
-            ('i', _) => parse_inf(&mut parser_state),
-            ('n', _) => parse_nan(&mut parser_state),
-            ('.' | '0' | '1', Radix::Bin) => parse_num(&mut parser_state, rdx)?,
-            ('.' | '0'..='7', Radix::Oct) => parse_num(&mut parser_state, rdx)?,
-            ('.' | '0'..='9', Radix::Dec) => parse_num(&mut parser_state, rdx)?,
-            ('.' | '0'..='9' | 'a'..='f', Radix::Hex) => parse_num(&mut parser_state, rdx)?,
-            _ => {}
-        };
+    let rest = &s[parser_state.pos()..];
+
+    match ch {
+        Some('0') if parser_state.peek_char() == Some('x') && !is_radix_digit('x', base) => {
+            parse_hex_float(&mut parser_state)?
+        }
+        Some('i') if !is_radix_digit('i', base) || rest.eq_ignore_ascii_case("inf") => {
+            parse_inf(&mut parser_state)?
+        }
+        Some('n') if !is_radix_digit('n', base) || rest.eq_ignore_ascii_case("nan") => {
+            parse_nan(&mut parser_state)?
+        }
+        Some(c) if c == '.' || is_radix_digit(c, base) => parse_num(&mut parser_state, base)?,
+        _ => return Err(parser_state.unexpected()),
+    };
+
+    if parser_state.cur_char().is_some() {
+        return Err(parser_state.unexpected());
     }
 
     Ok(parser_state)
 }
 
-fn parse_inf(parser_state: &mut ParserState) {
-    let n = parser_state.next_char();
-    let f = parser_state.next_char();
-    if Some('n') == n && Some('f') == f {
-        parser_state.inf = true;
-        parser_state.nan = false;
-    }
+fn parse_inf(parser_state: &mut ParserState) -> Result<(), ParseError> {
+    parser_state.next_char();
+    parser_state.expect_char('n')?;
+    parser_state.expect_char('f')?;
+    parser_state.inf = true;
+    parser_state.nan = false;
+    Ok(())
 }
 
-fn parse_nan(parser_state: &mut ParserState) {
-    let a = parser_state.next_char();
-    let n = parser_state.next_char();
-    if Some('n') == n && Some('a') == a {
-        parser_state.nan = true;
-    }
+fn parse_nan(parser_state: &mut ParserState) -> Result<(), ParseError> {
+    parser_state.next_char();
+    parser_state.expect_char('a')?;
+    parser_state.expect_char('n')?;
+    parser_state.nan = true;
+    Ok(())
 }
 
-fn parse_num(parser_state: &mut ParserState, rdx: Radix) -> Result<(), Error> {
-    let (int_len, skip_cnt1) = parse_digits(parser_state, true, true, rdx)?;
+fn parse_num(parser_state: &mut ParserState, base: u32) -> Result<(), ParseError> {
+    let e_marker = is_radix_digit('e', base);
+    let (int_len, skip_cnt1) = parse_digits(parser_state, true, true, base, e_marker)?;
     if Some('.') == parser_state.cur_char() {
         parser_state.next_char();
     }
-    let (frac_len, _) = parse_digits(parser_state, false, false, rdx)?;
-    if frac_len > 0 || int_len > 0 {
-        parser_state.nan = false;
-        if rdx == Radix::Hex {
-            if Some('_') == parser_state.cur_char() {
-                parser_state.next_char();
-                if Some('e') == parser_state.cur_char() {
-                    parser_state.next_char();
-                    parse_exp(parser_state, rdx);
-                }
-            }
-        } else if Some('e') == parser_state.cur_char() {
+    let (frac_len, _) = parse_digits(parser_state, false, false, base, e_marker)?;
+
+    if int_len == 0 && skip_cnt1 == 0 && frac_len == 0 {
+        // no digits
+        return Err(parser_state.unexpected());
+    }
+
+    parser_state.nan = false;
+
+    if e_marker {
+        if Some('_') == parser_state.cur_char() {
             parser_state.next_char();
-            parse_exp(parser_state, rdx);
+            parser_state.expect_char('e')?;
+            parse_exp(parser_state, base)?;
         }
+    } else if Some('e') == parser_state.cur_char() {
+        parser_state.next_char();
+        parse_exp(parser_state, base)?;
+    }
+
+    if frac_len > 0 || int_len > 0 {
         if int_len != 0 {
             parser_state.e = parser_state.e.saturating_add(int_len as isize);
         }
 
         if parser_state.e < EXPONENT_MIN as isize {
             let mut zero = Vec::new();
-            zero.try_reserve_exact(1)?;
+            zero.try_reserve_exact(1)
+                .map_err(|e| parser_state.error(ParseErrorKind::Conversion(e.into())))?;
             zero.push(0);
             parser_state.mantissa_bytes = zero;
             parser_state.e = 0;
         } else if parser_state.e > EXPONENT_MAX as isize {
             parser_state.inf = true;
         }
-    } else if skip_cnt1 > 0 {
+    } else {
         // just zeroes
-        parser_state.nan = false;
+        parser_state.e = 0;
     }
 
     Ok(())
 }
 
+// Parses a number in C99 hexadecimal floating point notation, e.g. 0x1.8p+3.
+// The binary exponent is in decimal.
+fn parse_hex_float(parser_state: &mut ParserState) -> Result<(), ParseError> {
+    parser_state.next_char(); // 'x'
+    parser_state.next_char();
+
+    let (int_len, skip_cnt1) = parse_digits(parser_state, true, true, Radix::Hex as u32, false)?;
+    if Some('.') == parser_state.cur_char() {
+        parser_state.next_char();
+    }
+    let (frac_len, _) = parse_digits(parser_state, false, false, Radix::Hex as u32, false)?;
+
+    if int_len == 0 && skip_cnt1 == 0 && frac_len == 0 {
+        // no digits
+        return Err(parser_state.unexpected());
+    }
+
+    parser_state.nan = false;
+
+    let mut bin_e = 0;
+    if Some('p') == parser_state.cur_char() {
+        parser_state.next_char();
+        parse_exp(parser_state, Radix::Dec as u32)?;
+        bin_e = parser_state.e;
+    }
+
+    parser_state.e = int_len as isize;
+    parser_state.bin_e = Some(bin_e);
+
+    Ok(())
+}
+
+// Returns the number of significant digits and the number of skipped leading zeroes.
+// Digits can be separated by `_` as in Rust literals, but the first character must be a digit.
+// `e_marker` is true if `_e` separates the exponent.
 fn parse_digits(
     parser_state: &mut ParserState,
     skip_zeroes: bool,
     int: bool,
-    rdx: Radix,
-) -> Result<(usize, usize), Error> {
+    base: u32,
+    e_marker: bool,
+) -> Result<(usize, usize), ParseError> {
     let mut ch = parser_state.cur_char();
     let mut len = 0;
     let mut skip_cnt = 0;
@@ -168,31 +288,31 @@ fn parse_digits(
     if skip_zeroes {
         // skip leading zeroes
         while let Some(c) = ch {
-            if is_radix_digit(c, rdx) && c.to_digit(rdx as u32).unwrap() == 0 {
-                // call to unwrap() is unreachable, because c is surely a digit.
+            if c == '0' {
                 skip_cnt += 1;
                 if !int {
                     len += 1; // for fractional part count length
                 }
-            } else {
+            } else if skip_cnt == 0 || !is_separator(parser_state, e_marker) {
                 break;
             }
             ch = parser_state.next_char();
         }
     }
 
-    if ch.is_some() && is_radix_digit(ch.unwrap(), rdx) {
+    if ch.is_some() && is_radix_digit(ch.unwrap(), base) {
         parser_state
             .mantissa_bytes
-            .try_reserve_exact(parser_state.s_len)?;
+            .try_reserve_exact(parser_state.s_len)
+            .map_err(|e| parser_state.error(ParseErrorKind::Conversion(Error::from(e))))?;
 
         while let Some(c) = ch {
-            if is_radix_digit(c, rdx) {
+            if is_radix_digit(c, base) {
                 parser_state
                     .mantissa_bytes
-                    .push(c.to_digit(rdx as u32).unwrap() as u8); // call to unwrap() is unreachable, because c is surely a digit.
+                    .push(c.to_digit(base).unwrap() as u8); // call to unwrap() is unreachable, because c is surely a digit.
                 len += 1;
-            } else {
+            } else if !is_separator(parser_state, e_marker) {
                 break;
             }
             ch = parser_state.next_char();
@@ -207,23 +327,17 @@ fn parse_digits(
     Ok((len, skip_cnt))
 }
 
-fn is_radix_digit(c: char, rdx: Radix) -> bool {
-    matches!(
-        (rdx, c),
-        (Radix::Bin, '0' | '1')
-            | (Radix::Oct, '0'..='7')
-            | (
-                Radix::Dec,
-                '0'..='9'
-            )
-            | (
-                Radix::Hex,
-                '0'..='9' | 'a'..='f'
-            )
-    )
+fn is_radix_digit(c: char, base: u32) -> bool {
+    c.is_digit(base)
+}
+
+// Returns true if the current character is the digit separator `_`.
+// If `e_marker` is true, `_` followed by `e` starts the exponent and is not a separator.
+fn is_separator(parser_state: &ParserState, e_marker: bool) -> bool {
+    parser_state.cur_char() == Some('_') && !(e_marker && parser_state.peek_char() == Some('e'))
 }
 
-fn parse_exp(parser_state: &mut ParserState, rdx: Radix) {
+fn parse_exp(parser_state: &mut ParserState, base: u32) -> Result<(), ParseError> {
     let mut neg = false;
     let mut ch = parser_state.cur_char();
     if let Some(c) = ch {
@@ -239,22 +353,32 @@ fn parse_exp(parser_state: &mut ParserState, rdx: Radix) {
         };
     }
     let e_thres = EXPONENT_MAX.unsigned_abs().max(EXPONENT_MIN.unsigned_abs()) as isize;
+    let mut len = 0;
     while let Some(c) = ch {
-        if is_radix_digit(c, rdx) {
-            if parser_state.e > e_thres {
-                break;
+        if is_radix_digit(c, base) {
+            // the remaining digits of a large exponent are skipped
+            if parser_state.e <= e_thres {
+                parser_state.e = parser_state.e.saturating_mul(base as isize);
+                let digit = c.to_digit(base).unwrap(); // call to unwrap() is unreachable, because c is surely a digit.
+                parser_state.e = parser_state.e.saturating_add(digit as isize);
             }
-            parser_state.e = parser_state.e.saturating_mul(rdx as isize);
-            let digit = c.to_digit(rdx as u32).unwrap(); // call to unwrap() is unreachable, because c is surely a digit.
-            parser_state.e = parser_state.e.saturating_add(digit as isize);
-        } else {
+            len += 1;
+        } else if len == 0 || c != '_' {
             break;
         }
         ch = parser_state.next_char();
     }
+    if len == 0 {
+        let err = parser_state.unexpected();
+        return Err(match err.kind() {
+            ParseErrorKind::InvalidDigit(_) => err,
+            _ => parser_state.error(ParseErrorKind::InvalidExponent),
+        });
+    }
     if neg {
         parser_state.e = -parser_state.e;
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -286,7 +410,7 @@ mod tests {
         let signs = ["", "+", "-"];
         let expected_signs = [Sign::Pos, Sign::Pos, Sign::Neg];
 
-        let exponents = ["", "E", "e", "e123", "e+345", "e-678", "e901", "E+234", "E-567"];
+        let exponents = ["", "e0", "E-0", "e123", "e+345", "e-678", "e901", "E+234", "E-567"];
         let expected_exponents = [0, 0, 0, 123, 345, -678, 901, 234, -567];
 
         let infs = ["inf", "INF", "Inf"];
@@ -362,29 +486,13 @@ mod tests {
         assert!(e == -0x1f7);
 
         // large exp
-        let numstr;
-        #[cfg(not(target_arch = "x86"))]
-        {
-            numstr = "abc.def09123e_e+7FFFFFFF";
-        }
-        #[cfg(target_arch = "x86")]
-        {
-            numstr = "abc.def09123e_e+1FFFFFFF";
-        }
-        let ps = parse(numstr, Radix::Hex).unwrap();
+        let numstr = format!("abc.def09123e_e+{:X}", EXPONENT_MAX);
+        let ps = parse(&numstr, Radix::Hex).unwrap();
         assert!(ps.is_inf());
         assert!(ps.sign().is_positive());
 
-        let numstr;
-        #[cfg(not(target_arch = "x86"))]
-        {
-            numstr = "-abc.def09123e_e+7FFFFFFF";
-        }
-        #[cfg(target_arch = "x86")]
-        {
-            numstr = "-abc.def09123e_e+1FFFFFFF";
-        }
-        let ps = parse(numstr, Radix::Hex).unwrap();
+        let numstr = format!("-abc.def09123e_e+{:X}", EXPONENT_MAX);
+        let ps = parse(&numstr, Radix::Hex).unwrap();
         assert!(ps.is_inf());
         assert!(!ps.is_nan());
         assert!(ps.sign().is_negative());
@@ -442,5 +550,110 @@ mod tests {
         let (m, _s, e) = ps.raw_parts();
         assert_eq!(m.iter().filter(|&&x| x != 0).count(), 0);
         assert!(e == 0);
+
+        // hexadecimal floating point notation
+        for rdx in [Radix::Bin, Radix::Dec, Radix::Hex] {
+            let ps = parse("-0X1a.8Cp-12", rdx).unwrap();
+            let (m, s, e) = ps.raw_parts();
+            assert_eq!(m, [1, 10, 8, 12]);
+            assert_eq!(s, Sign::Neg);
+            assert_eq!(e, 2);
+            assert_eq!(ps.bin_exp(), Some(-12));
+        }
+
+        let ps = parse("0x.008p+1000", Radix::Dec).unwrap();
+        let (m, _s, e) = ps.raw_parts();
+        assert_eq!(m, [0, 0, 8]);
+        assert_eq!(e, 0);
+        assert_eq!(ps.bin_exp(), Some(1000));
+
+        let ps = parse("0x0", Radix::Dec).unwrap();
+        assert!(!ps.is_nan());
+        assert!(ps.raw_parts().0.is_empty());
+        assert_eq!(ps.bin_exp(), Some(0));
+
+        assert_eq!(parse("0.5e1", Radix::Dec).unwrap().bin_exp(), None);
+
+        // errors
+        let errors = [
+            ("", Radix::Dec, ParseErrorKind::UnexpectedEnd, 0),
+            ("-", Radix::Dec, ParseErrorKind::UnexpectedEnd, 1),
+            (".", Radix::Dec, ParseErrorKind::UnexpectedEnd, 1),
+            ("+.e1", Radix::Dec, ParseErrorKind::UnexpectedChar('e'), 2),
+            ("1.5.", Radix::Dec, ParseErrorKind::UnexpectedChar('.'), 3),
+            (" 1", Radix::Dec, ParseErrorKind::UnexpectedChar(' '), 0),
+            ("1 ", Radix::Dec, ParseErrorKind::UnexpectedChar(' '), 1),
+            ("12x", Radix::Dec, ParseErrorKind::UnexpectedChar('x'), 2),
+            ("1.5é", Radix::Dec, ParseErrorKind::UnexpectedChar('é'), 3),
+            ("1é5", Radix::Dec, ParseErrorKind::UnexpectedChar('é'), 1),
+            ("12A", Radix::Dec, ParseErrorKind::InvalidDigit('A'), 2),
+            ("1012", Radix::Bin, ParseErrorKind::InvalidDigit('2'), 3),
+            ("78", Radix::Oct, ParseErrorKind::InvalidDigit('8'), 1),
+            ("9", Radix::Bin, ParseErrorKind::InvalidDigit('9'), 0),
+            ("1e", Radix::Dec, ParseErrorKind::InvalidExponent, 2),
+            ("1E+", Radix::Dec, ParseErrorKind::InvalidExponent, 3),
+            ("0e-x", Radix::Dec, ParseErrorKind::InvalidExponent, 3),
+            ("1e2", Radix::Bin, ParseErrorKind::InvalidDigit('2'), 2),
+            ("1e1e", Radix::Dec, ParseErrorKind::UnexpectedChar('e'), 3),
+            (
+                "1.5e1.5",
+                Radix::Dec,
+                ParseErrorKind::UnexpectedChar('.'),
+                5,
+            ),
+            ("a_e", Radix::Hex, ParseErrorKind::InvalidExponent, 3),
+            ("a_e_", Radix::Hex, ParseErrorKind::InvalidExponent, 3),
+            ("a.g", Radix::Hex, ParseErrorKind::UnexpectedChar('g'), 2),
+            ("a_x", Radix::Hex, ParseErrorKind::UnexpectedChar('x'), 2),
+            ("in", Radix::Dec, ParseErrorKind::UnexpectedEnd, 2),
+            (
+                "-infinity",
+                Radix::Dec,
+                ParseErrorKind::UnexpectedChar('i'),
+                4,
+            ),
+            ("nun", Radix::Dec, ParseErrorKind::UnexpectedChar('u'), 1),
+            ("0x", Radix::Dec, ParseErrorKind::UnexpectedEnd, 2),
+            ("0xp1", Radix::Dec, ParseErrorKind::UnexpectedChar('p'), 2),
+            ("0x1p", Radix::Dec, ParseErrorKind::InvalidExponent, 4),
+            ("0x1pa", Radix::Dec, ParseErrorKind::InvalidDigit('a'), 4),
+            ("0x1p-", Radix::Dec, ParseErrorKind::InvalidExponent, 5),
+            ("0x1g", Radix::Dec, ParseErrorKind::UnexpectedChar('g'), 3),
+            ("_1", Radix::Dec, ParseErrorKind::UnexpectedChar('_'), 0),
+            ("1._5", Radix::Dec, ParseErrorKind::UnexpectedChar('_'), 2),
+            ("._5", Radix::Dec, ParseErrorKind::UnexpectedChar('_'), 1),
+            ("1e_5", Radix::Dec, ParseErrorKind::InvalidExponent, 2),
+            ("-_1", Radix::Dec, ParseErrorKind::UnexpectedChar('_'), 1),
+            ("1_0_e", Radix::Hex, ParseErrorKind::InvalidExponent, 5),
+        ];
+
+        for (s, rdx, kind, pos) in errors {
+            let err = parse(s, rdx).err().expect(s);
+            assert_eq!(err.kind(), kind, "{}", s);
+            assert_eq!(err.position(), pos, "{}", s);
+        }
+
+        // digit separators
+        for (s, rdx, m, e) in [
+            (
+                "1_000_000.000_1",
+                Radix::Dec,
+                &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1][..],
+                7,
+            ),
+            ("0_0_1__2_.3_e1_0", Radix::Dec, &[1, 2, 3], 12),
+            ("1_0.1_", Radix::Bin, &[1, 0, 1], 2),
+            ("a_b_c_e+1_0", Radix::Hex, &[10, 11, 12], 19),
+            ("0x1_0.8_0p1_0", Radix::Dec, &[1, 0, 8, 0], 2),
+        ] {
+            let ps = parse(s, rdx).expect(s);
+            let (m2, _, e2) = ps.raw_parts();
+            assert_eq!(m2, m, "{}", s);
+            assert_eq!(e2, e, "{}", s);
+        }
+
+        // a large exponent is parsed completely
+        let ps = parse("1e-99999999999999999999999999999999999", Radix::Dec).unwrap();
+        assert!(ps.raw_parts().0.iter().all(|&d| d == 0));
     }
 }
@@ -0,0 +1,262 @@
+//! Polynomials with `BigFloat` coefficients.
+//!
+//! A polynomial `a0 + a1*x + ... + an*x^n` is represented by the list of its coefficients starting from `a0`.
+//! The operations accept a precision `p` and a rounding mode `rm` which are applied to each coefficient of the result.
+//! The coefficients of the results of addition, subtraction, multiplication, and differentiation are correctly rounded.
+//!
+//! ```
+//! use astro_float_num::polynomial::Polynomial;
+//! use astro_float_num::{BigFloat, RoundingMode};
+//!
+//! let p = 128;
+//! let rm = RoundingMode::ToEven;
+//!
+//! // (x - 1) * (x + 2) = x^2 + x - 2
+//! let f = Polynomial::new(vec![BigFloat::from_i8(-1, p), BigFloat::from_i8(1, p)]);
+//! let g = Polynomial::new(vec![BigFloat::from_i8(2, p), BigFloat::from_i8(1, p)]);
+//! let h = f.mul(&g, p, rm);
+//!
+//! assert_eq!(h.coeffs(), &[BigFloat::from_i8(-2, p), BigFloat::from_i8(1, p), BigFloat::from_i8(1, p)]);
+//! assert_eq!(h.eval(&BigFloat::from_i8(3, p), p, rm), BigFloat::from_i8(10, p));
+//! assert_eq!(h.derivative(p, rm).eval(&BigFloat::from_i8(3, p), p, rm), BigFloat::from_i8(7, p));
+//! ```
+
+use crate::{BigFloat, RoundingMode};
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+/// Polynomial with `BigFloat` coefficients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    coeffs: Vec<BigFloat>,
+}
+
+impl Polynomial {
+    /// Constructs a polynomial from the coefficients `coeffs`, where `coeffs[i]` is the coefficient of `x^i`.
+    /// Zero coefficients of the highest powers are removed.
+    pub fn new(mut coeffs: Vec<BigFloat>) -> Self {
+        while coeffs.last().is_some_and(|c| c.is_zero()) {
+            coeffs.pop();
+        }
+
+        Polynomial { coeffs }
+    }
+
+    /// Returns the coefficients of the polynomial, where the element with index `i` is the coefficient of `x^i`.
+    /// The zero polynomial has no coefficients.
+    pub fn coeffs(&self) -> &[BigFloat] {
+        &self.coeffs
+    }
+
+    /// Decomposes the polynomial into its coefficients.
+    pub fn into_coeffs(self) -> Vec<BigFloat> {
+        self.coeffs
+    }
+
+    /// Returns the degree of the polynomial, or None for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    /// Evaluates the polynomial at `x` with precision `p` and rounds the result using the rounding mode `rm`.
+    /// The compensated Horner's scheme is used, so the result is as accurate as if it was computed
+    /// with Horner's scheme with twice the precision `p`, and then rounded.
+    /// Precision is rounded upwards to the word size.
+    pub fn eval(&self, x: &BigFloat, p: usize, rm: RoundingMode) -> BigFloat {
+        let (an, rest) = match self.coeffs.split_last() {
+            Some(v) => v,
+            None => return BigFloat::new(p),
+        };
+
+        if x.is_nan() || x.is_inf() || self.coeffs.iter().any(|c| c.is_nan() || c.is_inf()) {
+            let mut s = an.clone();
+            for a in rest.iter().rev() {
+                s = s.mul(x, p, rm).add(a, p, rm);
+            }
+            return s;
+        }
+
+        let mut s = an.clone();
+        let mut c = BigFloat::new(p);
+
+        for a in rest.iter().rev() {
+            // error-free transformations of the product and the sum
+            let prod = s.mul_full_prec(x);
+            let ph = s.mul(x, p, RoundingMode::ToEven);
+            let pi = prod.sub_full_prec(&ph);
+            let (sn, sigma) = ph.two_sum(a, p, RoundingMode::ToEven);
+
+            // the error terms are accumulated with Horner's scheme
+            c = c.mul(x, p, RoundingMode::ToEven).add(
+                &pi.add(&sigma, p, RoundingMode::ToEven),
+                p,
+                RoundingMode::ToEven,
+            );
+            s = sn;
+        }
+
+        s.add(&c, p, rm)
+    }
+
+    /// Returns the negated polynomial.
+    pub fn neg(&self) -> Self {
+        Polynomial {
+            coeffs: self.coeffs.iter().map(|c| c.neg()).collect(),
+        }
+    }
+
+    /// Adds `d2` to `self` and returns the result of the operation with precision `p` rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn add(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
+        let n = self.coeffs.len().max(d2.coeffs.len());
+        let zero = BigFloat::new(p);
+
+        let coeffs = (0..n)
+            .map(|i| {
+                let a = self.coeffs.get(i).unwrap_or(&zero);
+                let b = d2.coeffs.get(i).unwrap_or(&zero);
+                a.add(b, p, rm)
+            })
+            .collect();
+
+        Polynomial::new(coeffs)
+    }
+
+    /// Subtracts `d2` from `self` and returns the result of the operation with precision `p` rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn sub(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
+        self.add(&d2.neg(), p, rm)
+    }
+
+    /// Multiplies `self` by `d2` and returns the result of the operation with precision `p` rounded using the rounding mode `rm`.
+    /// Each coefficient of the result is computed as an exact sum of exact products, and then rounded once.
+    /// Precision is rounded upwards to the word size.
+    pub fn mul(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
+        if self.coeffs.is_empty() || d2.coeffs.is_empty() {
+            return Polynomial { coeffs: Vec::new() };
+        }
+
+        let n = self.coeffs.len() + d2.coeffs.len() - 1;
+        let mut prods = Vec::with_capacity(self.coeffs.len().min(d2.coeffs.len()));
+
+        let coeffs = (0..n)
+            .map(|k| {
+                prods.clear();
+                let lo = k.saturating_sub(d2.coeffs.len() - 1);
+                let hi = k.min(self.coeffs.len() - 1);
+                for i in lo..=hi {
+                    prods.push(self.coeffs[i].mul_full_prec(&d2.coeffs[k - i]));
+                }
+                BigFloat::sum(&prods, p, rm)
+            })
+            .collect();
+
+        Polynomial::new(coeffs)
+    }
+
+    /// Returns the derivative of the polynomial with precision `p` rounded using the rounding mode `rm`.
+    /// Precision is rounded upwards to the word size.
+    pub fn derivative(&self, p: usize, rm: RoundingMode) -> Self {
+        let coeffs = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| c.mul(&BigFloat::from_u64(i as u64, 64), p, rm))
+            .collect();
+
+        Polynomial::new(coeffs)
+    }
+
+    /// Returns the composition `self(d2(x))` computed with precision `p` using Horner's scheme.
+    /// Each intermediate result is rounded using the rounding mode `rm`,
+    /// so the coefficients of the result are not guaranteed to be correctly rounded.
+    /// Precision is rounded upwards to the word size.
+    pub fn compose(&self, d2: &Self, p: usize, rm: RoundingMode) -> Self {
+        let (an, rest) = match self.coeffs.split_last() {
+            Some(v) => v,
+            None => return Polynomial { coeffs: Vec::new() },
+        };
+
+        let mut ret = Polynomial::new(vec![an.clone()]);
+        for a in rest.iter().rev() {
+            ret = ret
+                .mul(d2, p, rm)
+                .add(&Polynomial::new(vec![a.clone()]), p, rm);
+        }
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn poly(c: &[i64], p: usize) -> Polynomial {
+        Polynomial::new(c.iter().map(|&v| BigFloat::from_i64(v, p)).collect())
+    }
+
+    #[test]
+    fn test_polynomial() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        // construction
+        let f = poly(&[1, -3, 0, 2, 0, 0], p);
+        assert_eq!(f.degree(), Some(3));
+        assert_eq!(f.coeffs().len(), 4);
+        assert_eq!(poly(&[0, 0], p).degree(), None);
+        assert_eq!(f.clone().into_coeffs(), f.coeffs());
+
+        // arithmetic
+        let g = poly(&[-1, 1, 5], p);
+        assert_eq!(f.add(&g, p, rm), poly(&[0, -2, 5, 2], p));
+        assert_eq!(f.sub(&f, p, rm).degree(), None);
+        assert_eq!(f.mul(&g, p, rm), poly(&[-1, 4, 2, -17, 2, 10], p));
+        assert_eq!(f.mul(&poly(&[], p), p, rm).degree(), None);
+        assert_eq!(f.derivative(p, rm), poly(&[-3, 0, 6], p));
+        assert_eq!(poly(&[7], p).derivative(p, rm).degree(), None);
+        assert_eq!(f.neg().add(&f, p, rm).degree(), None);
+
+        // composition: f(g(x)) evaluated at x equals f evaluated at g(x)
+        let h = f.compose(&g, p, rm);
+        assert_eq!(h.degree(), Some(6));
+        for v in [-3, 0, 2, 5] {
+            let x = BigFloat::from_i8(v, p);
+            assert_eq!(h.eval(&x, p, rm), f.eval(&g.eval(&x, p, rm), p, rm));
+        }
+
+        // evaluation
+        assert_eq!(
+            f.eval(&BigFloat::from_i8(2, p), p, rm),
+            BigFloat::from_i8(11, p)
+        );
+        assert!(poly(&[], p).eval(&BigFloat::from_i8(2, p), p, rm).is_zero());
+        assert!(f.eval(&BigFloat::nan(None), p, rm).is_nan());
+        assert!(f.eval(&crate::INF_POS, p, rm).is_inf_pos());
+
+        // ill-conditioned evaluation of (x - 1)^5 near x = 1
+        let q = poly(&[-1, 5, -10, 10, -5, 1], p);
+        let mut d = BigFloat::from_word(1, p).div(&BigFloat::from_word(3, p), p, rm);
+        d.set_exponent(-29);
+        let x = BigFloat::from_word(1, p).add(&d, p, rm);
+        let d = x.sub(&BigFloat::from_word(1, p), p, rm);
+        let d2 = d.mul_full_prec(&d);
+        let expected = d2.mul_full_prec(&d2).mul(&d, p, rm);
+        let mut tol = expected.clone();
+        tol.set_exponent(expected.exponent().unwrap() - 90);
+        let y = q.eval(&x, p, rm);
+        assert!(y.sub(&expected, p, rm).abs() < tol);
+
+        // plain Horner's scheme loses most of the digits
+        let mut s = BigFloat::from_word(1, p);
+        for a in q.coeffs().iter().rev().skip(1) {
+            s = s.mul(&x, p, rm).add(a, p, rm);
+        }
+        tol.set_exponent(expected.exponent().unwrap() - 60);
+        assert!(s.sub(&expected, p, rm).abs() > tol);
+    }
+}
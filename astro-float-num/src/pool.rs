@@ -0,0 +1,83 @@
+//! Reuse of the memory of temporary numbers.
+//!
+//! Mathematical functions compute many short-lived intermediate numbers, such as the terms of series
+//! and the copies of the argument made by argument reduction, and each of them allocates memory for its mantissa.
+//! Inside `with_pool` the memory of a mantissa which is dropped is kept by the pool of the current thread,
+//! and a new mantissa of a close size takes its memory from the pool instead of the memory allocator.
+//! The pool keeps at most 64 buffers. Its memory is released when the outermost `with_pool` returns, or when `reset` is called,
+//! so the pool can be reset between the top-level computations of a long-running program.
+//! The results of the computations do not depend on whether the pool is used.
+//!
+//! ```
+//! use astro_float_num::pool;
+//! use astro_float_num::{BigFloat, Consts, RoundingMode};
+//!
+//! let p = 256;
+//! let rm = RoundingMode::ToEven;
+//! let mut cc = Consts::new().expect("Constants cache initialized");
+//!
+//! let x = BigFloat::from_word(3, p);
+//! let f = |cc: &mut Consts| {
+//!     let mut s = BigFloat::new(p);
+//!     for i in 1..100 {
+//!         s = s.add(&x.div(&BigFloat::from_word(i, p), p, rm).sin(p, rm, cc), p, rm);
+//!     }
+//!     s
+//! };
+//!
+//! let y = pool::with_pool(|| f(&mut cc));
+//!
+//! assert_eq!(y, f(&mut cc));
+//! ```
+
+/// Runs `f` with the pool of the current thread enabled, and releases the memory of the pool when `f` returns.
+/// Nested calls share the pool, and the memory is released when the outermost call returns.
+pub fn with_pool<R>(f: impl FnOnce() -> R) -> R {
+    crate::common::buf::with_pool(f)
+}
+
+/// Releases the memory kept by the pool of the current thread.
+/// The pool stays enabled if it is called inside `with_pool`.
+pub fn reset() {
+    crate::common::buf::reset_pool();
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{BigFloat, Consts, RoundingMode};
+
+    #[test]
+    fn test_pool() {
+        let p = 320;
+        let rm = RoundingMode::ToEven;
+        let mut cc = Consts::new().unwrap();
+
+        let x = BigFloat::from_word(7, p).div(&BigFloat::from_word(3, p), p, rm);
+        let expected = [
+            x.exp(p, rm, &mut cc),
+            x.ln(p, rm, &mut cc),
+            x.sin(p, rm, &mut cc),
+            x.atan(p, rm, &mut cc),
+        ];
+
+        let ret = with_pool(|| {
+            let ret1 = [
+                x.exp(p, rm, &mut cc),
+                x.ln(p, rm, &mut cc),
+                x.sin(p, rm, &mut cc),
+                x.atan(p, rm, &mut cc),
+            ];
+
+            // nested use and reset
+            let ret2 = with_pool(|| x.exp(p, rm, &mut cc));
+            reset();
+            assert_eq!(ret2, expected[0]);
+
+            ret1
+        });
+
+        assert_eq!(ret, expected);
+    }
+}
@@ -0,0 +1,438 @@
+//! Numerical integration.
+//!
+//! The integral is computed with the tanh-sinh (double exponential) quadrature.
+//! The substitution `x = tanh(pi/2 * sinh(t))` moves the ends of the interval to infinity,
+//! and makes the integrand decay double exponentially, so the trapezoidal rule converges quickly
+//! even if the integrand has singularities at the ends of the interval.
+//! Infinite intervals are mapped to finite ones before the substitution.
+//!
+//! The integrand is a closure which receives the argument and the precision to use for computing the value.
+//! The step of the trapezoidal rule is halved until the difference between two successive estimates
+//! is below the target error. If the target error is not reached, the working precision is increased,
+//! and the computation is repeated.
+//!
+//! ```
+//! use astro_float_num::quadrature::integrate;
+//! use astro_float_num::{BigFloat, Consts, RoundingMode, INF_POS};
+//!
+//! let p = 128;
+//! let rm = RoundingMode::ToEven;
+//! let mut cc = Consts::new().expect("Constants cache initialized");
+//! let eps = BigFloat::from_f64(1e-30, p);
+//!
+//! // the integral of 1/sqrt(x) from 0 to 1 is 2
+//! let f = |x: &BigFloat, p: usize| x.sqrt(p, RoundingMode::None).reciprocal(p, RoundingMode::None);
+//! let r = integrate(f, &BigFloat::new(p), &BigFloat::from_word(1, p), &eps, p, rm, &mut cc).unwrap();
+//! assert!(r.is_converged());
+//! assert!(r.value().sub(&BigFloat::from_word(2, p), p, rm).abs() < eps);
+//!
+//! // the integral of exp(-x) from 0 to infinity is 1
+//! let mut cc2 = Consts::new().expect("Constants cache initialized");
+//! let f = |x: &BigFloat, p: usize| x.neg().exp(p, RoundingMode::None, &mut cc2);
+//! let r = integrate(f, &BigFloat::new(p), &INF_POS, &eps, p, rm, &mut cc).unwrap();
+//! assert!(r.value().sub(&BigFloat::from_word(1, p), p, rm).abs() < eps);
+//! ```
+
+use crate::common::util::round_p;
+use crate::ext::TWO;
+use crate::{BigFloat, Consts, Error, RoundingMode, WORD_BIT_SIZE};
+
+// The maximum number of halvings of the step.
+const MAX_LEVEL: usize = 10;
+
+// The maximum number of increases of the working precision.
+const MAX_ESCALATIONS: usize = 3;
+
+/// Result of numerical integration.
+#[derive(Debug, Clone)]
+pub struct Integral {
+    value: BigFloat,
+    err: BigFloat,
+    converged: bool,
+}
+
+impl Integral {
+    /// Returns the value of the integral.
+    pub fn value(&self) -> &BigFloat {
+        &self.value
+    }
+
+    /// Returns the estimate of the error of the value.
+    pub fn err(&self) -> &BigFloat {
+        &self.err
+    }
+
+    /// Returns true if the estimate of the error does not exceed the target error.
+    pub fn is_converged(&self) -> bool {
+        self.converged
+    }
+}
+
+// Mapping of the integration interval to [-1, 1].
+enum Map {
+    // [a, b] with half = (b - a) / 2
+    Finite {
+        a: BigFloat,
+        b: BigFloat,
+        half: BigFloat,
+    },
+    // [a, +inf) with x = a + (1 + s) / (1 - s)
+    Right {
+        a: BigFloat,
+    },
+}
+
+/// Computes the integral of `f` from `a` to `b` with the target absolute error `eps`.
+/// The ends of the interval can be infinite. `f(x, p)` must return the value of the integrand at `x` computed with precision `p`.
+/// `f` is not evaluated at the ends of the interval.
+/// The value of the integral is rounded to precision `p` using the rounding mode `rm`.
+/// This function requires constants cache `cc` for computing the result.
+/// Precision is rounded upwards to the word size.
+///
+/// If the target error is not reached, the function returns the best estimate of the integral,
+/// and the `is_converged` method of the result returns false.
+///
+/// ## Errors
+///
+///  - InvalidArgument: `a`, `b`, or `eps` is NaN, `eps` is not positive, or `f` returned NaN or Inf.
+///  - MemoryAllocation: failed to allocate memory.
+pub fn integrate<F>(
+    mut f: F,
+    a: &BigFloat,
+    b: &BigFloat,
+    eps: &BigFloat,
+    p: usize,
+    rm: RoundingMode,
+    cc: &mut Consts,
+) -> Result<Integral, Error>
+where
+    F: FnMut(&BigFloat, usize) -> BigFloat,
+{
+    if a.is_nan() || b.is_nan() || eps.is_nan() || !eps.is_positive() || eps.is_zero() {
+        return Err(Error::InvalidArgument);
+    }
+
+    if a > b {
+        let mut ret = integrate(f, b, a, eps, p, rm, cc)?;
+        ret.value = ret.value.neg();
+        return Ok(ret);
+    }
+
+    let p = round_p(p);
+
+    if a == b {
+        return Ok(Integral {
+            value: BigFloat::new(p),
+            err: BigFloat::new(p),
+            converged: true,
+        });
+    }
+
+    let mut p_inc = WORD_BIT_SIZE;
+    let mut p_wrk = p + p_inc;
+
+    let mut ret = None;
+
+    for _ in 0..=MAX_ESCALATIONS {
+        let (mut value, err, converged) = if a.is_inf() && b.is_inf() {
+            // split at zero
+            let eps2 = eps.div(&TWO, p_wrk, RoundingMode::Down);
+            let map = Map::Right {
+                a: BigFloat::new(p_wrk),
+            };
+            let (v1, e1, c1) = de_sum(
+                &mut |x: &BigFloat, p: usize| f(x, p),
+                &map,
+                &eps2,
+                p_wrk,
+                cc,
+            )?;
+            let (v2, e2, c2) = de_sum(
+                &mut |x: &BigFloat, p: usize| f(&x.neg(), p),
+                &map,
+                &eps2,
+                p_wrk,
+                cc,
+            )?;
+            (
+                v1.add(&v2, p_wrk, RoundingMode::None),
+                e1.add(&e2, p_wrk, RoundingMode::ToEven),
+                c1 && c2,
+            )
+        } else if b.is_inf() {
+            let map = Map::Right { a: a.clone() };
+            de_sum(&mut |x: &BigFloat, p: usize| f(x, p), &map, eps, p_wrk, cc)?
+        } else if a.is_inf() {
+            // mirror (-inf, b] to [-b, +inf)
+            let map = Map::Right { a: b.neg() };
+            de_sum(
+                &mut |x: &BigFloat, p: usize| f(&x.neg(), p),
+                &map,
+                eps,
+                p_wrk,
+                cc,
+            )?
+        } else {
+            let half = b
+                .sub(a, p_wrk, RoundingMode::None)
+                .div(&TWO, p_wrk, RoundingMode::None);
+            let map = Map::Finite {
+                a: a.clone(),
+                b: b.clone(),
+                half,
+            };
+            de_sum(&mut |x: &BigFloat, p: usize| f(x, p), &map, eps, p_wrk, cc)?
+        };
+
+        if let Some(e) = value.err() {
+            return Err(e);
+        }
+
+        value.set_precision(p, rm)?;
+
+        let done = converged;
+        ret = Some(Integral {
+            value,
+            err,
+            converged,
+        });
+
+        if done {
+            break;
+        }
+
+        p_wrk += p_inc;
+        p_inc = round_p(p_wrk / 5);
+    }
+
+    Ok(ret.expect("At least one iteration is done"))
+}
+
+// Computes the integral using the trapezoidal rule after the tanh-sinh substitution.
+// Returns the value, the estimate of the error, and true if the estimate does not exceed `eps`.
+fn de_sum(
+    f: &mut dyn FnMut(&BigFloat, usize) -> BigFloat,
+    map: &Map,
+    eps: &BigFloat,
+    p: usize,
+    cc: &mut Consts,
+) -> Result<(BigFloat, BigFloat, bool), Error> {
+    let rm = RoundingMode::None;
+    let pi2 = cc.pi(p, rm).div(&TWO, p, rm);
+
+    // terms smaller than this are negligible
+    let mut tiny = eps.clone();
+    if let Some(e) = eps.exponent() {
+        tiny.set_exponent(e - WORD_BIT_SIZE as crate::Exponent);
+    }
+
+    // the center node
+    let (x, jac) = node(map, &BigFloat::from_word(1, p), true, p);
+    let mut sum = check(f(&x, p))?.mul(&jac, p, rm).mul(&pi2, p, rm);
+
+    let mut est = BigFloat::new(p);
+    let mut err = BigFloat::new(p);
+
+    for level in 0..=MAX_LEVEL {
+        let mut h = BigFloat::from_word(1, p);
+        h.set_exponent(1 - level as crate::Exponent);
+
+        let (start, step) = if level == 0 { (1, 1) } else { (1, 2) };
+
+        let mut k = start;
+        loop {
+            let t = h.mul(&BigFloat::from_u64(k as u64, 64), p, rm);
+
+            // u = pi/2 * sinh(t), w = pi/2 * cosh(t) / cosh(u)^2 = pi/2 * cosh(t) * d * (2 - d),
+            // where d = 1 - tanh(u) = 2 / (exp(2u) + 1)
+            let et = t.exp(p, rm, cc);
+            let iet = et.reciprocal(p, rm);
+            let sinh = et.sub(&iet, p, rm).div(&TWO, p, rm);
+            let cosh = et.add(&iet, p, rm).div(&TWO, p, rm);
+            let u2 = pi2.mul(&sinh, p, rm).mul(&TWO, p, rm);
+            let d = TWO.div(
+                &u2.exp(p, rm, cc).add(&BigFloat::from_word(1, p), p, rm),
+                p,
+                rm,
+            );
+
+            if d.is_zero() {
+                break;
+            }
+
+            let w = pi2
+                .mul(&cosh, p, rm)
+                .mul(&d, p, rm)
+                .mul(&TWO.sub(&d, p, rm), p, rm);
+
+            let (xl, jl) = node(map, &d, false, p);
+            let (xr, jr) = node(map, &d, true, p);
+
+            if !inside(map, &xl) || !inside(map, &xr) {
+                break;
+            }
+
+            let fl = check(f(&xl, p))?.mul(&jl, p, rm);
+            let fr = check(f(&xr, p))?.mul(&jr, p, rm);
+            let term = fl.add(&fr, p, rm).mul(&w, p, rm);
+
+            sum = sum.add(&term, p, rm);
+
+            if t >= BigFloat::from_word(1, p) && term.mul(&h, p, rm).abs() <= tiny {
+                break;
+            }
+
+            k += step;
+        }
+
+        let est_new = sum.mul(&h, p, rm);
+
+        if level > 0 {
+            err = est_new.sub(&est, p, RoundingMode::FromZero).abs();
+        }
+
+        est = est_new;
+
+        if level >= 3 && err <= *eps {
+            return Ok((est, err, true));
+        }
+
+        if let Some(e) = est.err() {
+            return Err(e);
+        }
+    }
+
+    Ok((est, err, false))
+}
+
+// Returns the argument of the integrand and the derivative of the mapping at the node
+// at the distance `d` from the right end of [-1, 1] if `right` is true, or from the left end otherwise.
+fn node(map: &Map, d: &BigFloat, right: bool, p: usize) -> (BigFloat, BigFloat) {
+    let rm = RoundingMode::None;
+    match map {
+        Map::Finite { a, b, half } => {
+            let dx = half.mul(d, p, rm);
+            if right {
+                (b.sub(&dx, p, rm), half.clone())
+            } else {
+                (a.add(&dx, p, rm), half.clone())
+            }
+        }
+        Map::Right { a } => {
+            // 1 - s and 1 + s
+            let (ms, ps) = if right {
+                (d.clone(), TWO.sub(d, p, rm))
+            } else {
+                (TWO.sub(d, p, rm), d.clone())
+            };
+            let x = a.add(&ps.div(&ms, p, rm), p, rm);
+            let jac = TWO.div(&ms.mul(&ms, p, rm), p, rm);
+            (x, jac)
+        }
+    }
+}
+
+// Returns true if `x` is strictly inside the integration interval.
+fn inside(map: &Map, x: &BigFloat) -> bool {
+    match map {
+        Map::Finite { a, b, .. } => a < x && x < b,
+        Map::Right { a } => a < x && !x.is_inf(),
+    }
+}
+
+fn check(v: BigFloat) -> Result<BigFloat, Error> {
+    if v.is_nan() || v.is_inf() {
+        Err(Error::InvalidArgument)
+    } else {
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{INF_NEG, INF_POS};
+
+    #[test]
+    fn test_integrate() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+        let mut cc = Consts::new().unwrap();
+        let mut cc2 = Consts::new().unwrap();
+        let eps = BigFloat::from_f64(1e-30, p);
+        let zero = BigFloat::new(p);
+        let one = BigFloat::from_word(1, p);
+
+        let close = |v: &BigFloat, expected: &BigFloat| v.sub(expected, p, rm).abs() < eps;
+
+        // polynomial
+        let f = |x: &BigFloat, p: usize| x.mul(x, p, RoundingMode::None);
+        let third = one.div(&BigFloat::from_word(3, p), p, rm);
+        let r = integrate(f, &zero, &one, &eps, p, rm, &mut cc).unwrap();
+        assert!(r.is_converged());
+        assert!(*r.err() <= eps);
+        assert!(close(r.value(), &third));
+
+        // reversed interval
+        let r = integrate(f, &one, &zero, &eps, p, rm, &mut cc).unwrap();
+        assert!(close(r.value(), &third.neg()));
+
+        // singularity at the end of the interval
+        let g = |x: &BigFloat, p: usize| {
+            x.sqrt(p, RoundingMode::None)
+                .reciprocal(p, RoundingMode::None)
+        };
+        let r = integrate(g, &zero, &one, &eps, p, rm, &mut cc).unwrap();
+        assert!(close(r.value(), &BigFloat::from_word(2, p)));
+
+        // semi-infinite intervals
+        let mut h = |x: &BigFloat, p: usize| x.neg().exp(p, RoundingMode::None, &mut cc2);
+        let r = integrate(&mut h, &zero, &INF_POS, &eps, p, rm, &mut cc).unwrap();
+        assert!(close(r.value(), &one));
+        let r = integrate(
+            |x: &BigFloat, p: usize| h(&x.neg(), p),
+            &INF_NEG,
+            &zero,
+            &eps,
+            p,
+            rm,
+            &mut cc,
+        )
+        .unwrap();
+        assert!(close(r.value(), &one));
+
+        // infinite interval: the integral of exp(-x^2) is sqrt(pi)
+        let mut cc3 = Consts::new().unwrap();
+        let q = |x: &BigFloat, p: usize| {
+            x.mul(x, p, RoundingMode::None)
+                .neg()
+                .exp(p, RoundingMode::None, &mut cc3)
+        };
+        let r = integrate(q, &INF_NEG, &INF_POS, &eps, p, rm, &mut cc).unwrap();
+        let sqrt_pi = cc.pi(p, rm).sqrt(p, rm);
+        assert!(close(r.value(), &sqrt_pi));
+
+        // empty interval
+        let r = integrate(f, &one, &one, &eps, p, rm, &mut cc).unwrap();
+        assert!(r.value().is_zero());
+
+        // errors
+        assert_eq!(
+            integrate(f, &zero, &one, &zero, p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+        assert_eq!(
+            integrate(f, &BigFloat::nan(None), &one, &eps, p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+        let bad = |x: &BigFloat, p: usize| {
+            x.sub(&BigFloat::from_f64(0.5, p), p, RoundingMode::None)
+                .reciprocal(p, RoundingMode::None)
+        };
+        assert_eq!(
+            integrate(bad, &zero, &one, &eps, p, rm, &mut cc).unwrap_err(),
+            Error::InvalidArgument
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Dynamic range analysis.
+//!
+//! Numbers computed with a high precision often carry fewer significant bits than their mantissa can hold,
+//! e.g. integers or numbers converted from `f64` have trailing zero words in the mantissa.
+//! The functions of this module scan a set of numbers and report the range of exponents and the precision actually used,
+//! which helps to choose the precision for storing a large set of numbers,
+//! and reduce the memory used by the numbers for storage.
+
+use crate::common::util::round_p;
+use crate::BigFloat;
+use crate::Error;
+use crate::Exponent;
+use crate::RoundingMode;
+use crate::EXPONENT_MIN;
+use crate::WORD_BIT_SIZE;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Summary of the dynamic range of a set of numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DynamicRange {
+    /// The number of finite nonzero numbers.
+    pub count: usize,
+
+    /// The number of zeros.
+    pub zeros: usize,
+
+    /// The number of infinities and NaNs.
+    pub non_finite: usize,
+
+    /// The minimum exponent of finite nonzero numbers, or None if there are no such numbers.
+    pub exp_min: Option<Exponent>,
+
+    /// The maximum exponent of finite nonzero numbers, or None if there are no such numbers.
+    pub exp_max: Option<Exponent>,
+
+    /// The maximum precision of the mantissa in bits.
+    pub precision_max: usize,
+
+    /// The maximum number of bits actually used by the mantissa, i.e. the number of significant bits
+    /// excluding the trailing zero bits.
+    pub precision_used: usize,
+
+    /// The smallest precision which allows to store any of the numbers exactly.
+    pub precision_suggested: usize,
+}
+
+/// Scans `values` and returns the summary of their dynamic range.
+pub fn dynamic_range(values: &[BigFloat]) -> DynamicRange {
+    let mut ret = DynamicRange::default();
+
+    for v in values {
+        let (m, n, _, e, _) = match v.as_raw_parts() {
+            Some(parts) => parts,
+            None => {
+                ret.non_finite += 1;
+                continue;
+            }
+        };
+
+        ret.precision_max = ret.precision_max.max(m.len() * WORD_BIT_SIZE);
+
+        // the least significant nonzero word
+        let (i, w) = match m.iter().enumerate().find(|(_, w)| **w != 0) {
+            Some((i, w)) => (i, *w),
+            None => {
+                ret.zeros += 1;
+                continue;
+            }
+        };
+
+        let tz = i * WORD_BIT_SIZE + w.trailing_zeros() as usize;
+
+        ret.count += 1;
+        ret.precision_used = ret.precision_used.max(n - tz);
+        ret.exp_min = Some(ret.exp_min.map_or(e, |v| v.min(e)));
+        ret.exp_max = Some(ret.exp_max.map_or(e, |v| v.max(e)));
+    }
+
+    ret.precision_suggested = round_p(ret.precision_used.max(1));
+
+    ret
+}
+
+/// Scans `values` and returns the histogram of exponents of finite nonzero numbers.
+/// The exponents are grouped into buckets of `width` consecutive values aligned to multiples of `width`.
+/// The bucket containing `EXPONENT_MIN` starts at `EXPONENT_MIN`.
+/// Each element of the result contains the smallest exponent of a bucket and the number of values in the bucket.
+/// Empty buckets are omitted, and the result is sorted by the exponent in ascending order.
+/// A `width` of 0 is treated as 1.
+pub fn exponent_histogram(values: &[BigFloat], width: usize) -> Vec<(Exponent, usize)> {
+    let width = width.clamp(1, isize::MAX as usize) as isize;
+
+    let mut buckets: Vec<Exponent> = values
+        .iter()
+        .filter(|v| !v.is_zero())
+        .filter_map(|v| v.exponent())
+        .map(|e| {
+            let start = (e as isize).div_euclid(width) * width;
+            start.max(EXPONENT_MIN as isize) as Exponent
+        })
+        .collect();
+
+    buckets.sort_unstable();
+
+    let mut ret: Vec<(Exponent, usize)> = Vec::new();
+
+    for e in buckets {
+        match ret.last_mut() {
+            Some((last, cnt)) if *last == e => *cnt += 1,
+            _ => ret.push((e, 1)),
+        }
+    }
+
+    ret
+}
+
+/// Statistics of compression of a set of numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompressionStats {
+    /// The number of numbers changed by rounding.
+    pub rounded: usize,
+
+    /// The total number of words in the mantissas before compression.
+    pub words_before: usize,
+
+    /// The total number of words in the mantissas after compression.
+    pub words_after: usize,
+
+    /// The relative error introduced by rounding of any of the numbers is less than `2^max_rel_err`,
+    /// or None if no number was changed by rounding.
+    pub max_rel_err: Option<isize>,
+}
+
+/// Reduces the memory used by each number in `values` using `compress_to` with precision `p` and rounding mode `rm`.
+/// If `stats` is not None, the statistics of the compression is written to `stats`.
+/// Collecting the statistics requires a copy of each number before rounding.
+///
+/// ## Errors
+///
+/// The function stops on the first error and returns it.
+/// The numbers preceding the number which caused the error are compressed.
+///
+///  - ExponentOverflow: rounding of a number causes exponent overflow.
+///  - MemoryAllocation: failed to allocate memory.
+///  - InvalidArgument: the precision is incorrect.
+pub fn compress(
+    values: &mut [BigFloat],
+    p: usize,
+    rm: RoundingMode,
+    mut stats: Option<&mut CompressionStats>,
+) -> Result<(), Error> {
+    if let Some(stats) = stats.as_deref_mut() {
+        *stats = CompressionStats::default();
+    }
+
+    for v in values.iter_mut() {
+        let words = v.mantissa_digits().map_or(0, |m| m.len());
+
+        let stats = match stats.as_deref_mut() {
+            Some(stats) => stats,
+            None => {
+                v.compress_to(p, rm)?;
+                continue;
+            }
+        };
+
+        let orig = match v.mantissa_max_bit_len() {
+            Some(l) if l > round_p(p) => Some(v.clone()),
+            _ => None,
+        };
+
+        v.compress_to(p, rm)?;
+
+        stats.words_before += words;
+        stats.words_after += v.mantissa_digits().map_or(0, |m| m.len());
+
+        if let Some(orig) = orig {
+            // the difference is exact
+            let d = orig.sub(
+                v,
+                orig.mantissa_max_bit_len().unwrap_or(p),
+                RoundingMode::None,
+            );
+
+            if !d.is_zero() {
+                if let (Some(ed), Some(e)) = (d.exponent(), orig.exponent()) {
+                    // |d| < 2^ed, |orig| >= 2^(e-1)
+                    let err = ed as isize - e as isize + 1;
+
+                    stats.rounded += 1;
+                    stats.max_rel_err = Some(stats.max_rel_err.map_or(err, |v| v.max(err)));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::common::util::random_subnormal;
+    use crate::defs::DEFAULT_P;
+    use crate::RoundingMode;
+    use crate::INF_NEG;
+    use crate::NAN;
+
+    #[test]
+    fn test_dynamic_range() {
+        let r = dynamic_range(&[]);
+        assert_eq!(
+            r,
+            DynamicRange {
+                precision_suggested: WORD_BIT_SIZE,
+                ..Default::default()
+            }
+        );
+
+        let values = [
+            BigFloat::from_word(3, 4 * WORD_BIT_SIZE),
+            BigFloat::from_f64(0.1, 2 * WORD_BIT_SIZE).neg(),
+            BigFloat::new(DEFAULT_P),
+            BigFloat::from_word(1, WORD_BIT_SIZE).div(
+                &BigFloat::from_word(3, WORD_BIT_SIZE),
+                3 * WORD_BIT_SIZE,
+                RoundingMode::ToEven,
+            ),
+            NAN,
+            INF_NEG,
+        ];
+
+        let r = dynamic_range(&values);
+        assert_eq!(r.count, 3);
+        assert_eq!(r.zeros, 1);
+        assert_eq!(r.non_finite, 2);
+        assert_eq!(r.exp_min, Some(-3));
+        assert_eq!(r.exp_max, Some(2));
+        assert_eq!(r.precision_max, 4 * WORD_BIT_SIZE);
+        assert_eq!(r.precision_used, 3 * WORD_BIT_SIZE);
+        assert_eq!(r.precision_suggested, 3 * WORD_BIT_SIZE);
+
+        let r = dynamic_range(&values[..2]);
+        assert_eq!(r.precision_used, 52);
+        assert_eq!(r.precision_suggested, round_p(52));
+
+        // the numbers can be stored with the suggested precision exactly
+        for v in values[..3].iter() {
+            let mut v2 = v.clone();
+            v2.set_precision(r.precision_suggested, RoundingMode::ToEven)
+                .unwrap();
+            assert!(v2.cmp(v) == Some(0));
+        }
+
+        // subnormal
+        let d1 = random_subnormal(2 * WORD_BIT_SIZE);
+        let n = d1.precision();
+        let r = dynamic_range(&[d1.into()]);
+        assert!(r.precision_used <= n);
+        assert_eq!(r.exp_min, Some(EXPONENT_MIN));
+    }
+
+    #[test]
+    fn test_exponent_histogram() {
+        let values: Vec<BigFloat> = [0.3, 0.7, 1.0, 3.0, 5.0, 100.0, -0.001, 0.0]
+            .iter()
+            .map(|&v| BigFloat::from_f64(v, DEFAULT_P))
+            .chain([NAN, INF_NEG])
+            .collect();
+
+        // exponents: -1, 0, 1, 2, 3, 7, -9
+        assert_eq!(
+            exponent_histogram(&values, 1),
+            [(-9, 1), (-1, 1), (0, 1), (1, 1), (2, 1), (3, 1), (7, 1)]
+        );
+        assert_eq!(
+            exponent_histogram(&values, 0),
+            exponent_histogram(&values, 1)
+        );
+        assert_eq!(
+            exponent_histogram(&values, 4),
+            [(-12, 1), (-4, 1), (0, 4), (4, 1)]
+        );
+        assert_eq!(
+            exponent_histogram(&values, usize::MAX),
+            [(EXPONENT_MIN, 2), (0, 5)]
+        );
+        assert!(exponent_histogram(&values[7..], 1).is_empty());
+
+        let d1: BigFloat = random_subnormal(DEFAULT_P).into();
+        assert_eq!(exponent_histogram(&[d1], 10), [(EXPONENT_MIN, 1)]);
+    }
+
+    #[test]
+    fn test_compress() {
+        let rm = RoundingMode::ToEven;
+
+        let mut values = [
+            BigFloat::from_word(3, 4 * WORD_BIT_SIZE),
+            BigFloat::from_word(1, WORD_BIT_SIZE).div(
+                &BigFloat::from_word(3, WORD_BIT_SIZE),
+                3 * WORD_BIT_SIZE,
+                rm,
+            ),
+            BigFloat::new(DEFAULT_P),
+            NAN,
+        ];
+
+        let mut stats = CompressionStats::default();
+        let values2 = values.clone();
+        compress(&mut values, 2 * WORD_BIT_SIZE, rm, Some(&mut stats)).unwrap();
+
+        assert_eq!(values[0].mantissa_max_bit_len(), Some(WORD_BIT_SIZE));
+        assert_eq!(values[1].mantissa_max_bit_len(), Some(2 * WORD_BIT_SIZE));
+        assert_eq!(values[2].mantissa_max_bit_len(), Some(WORD_BIT_SIZE));
+        assert!(values[3].is_nan());
+
+        assert_eq!(stats.rounded, 1);
+        assert_eq!(stats.words_before, 4 + 3 + DEFAULT_P / WORD_BIT_SIZE);
+        assert_eq!(stats.words_after, 1 + 2 + 1);
+
+        let err = stats.max_rel_err.unwrap();
+        assert!(err <= -(2 * WORD_BIT_SIZE as isize) + 1);
+        assert!(err >= -(3 * WORD_BIT_SIZE as isize));
+
+        // the relative error is within the bound
+        let d = values2[1].sub(&values[1], 4 * WORD_BIT_SIZE, RoundingMode::None);
+        let q = d.div(&values2[1], WORD_BIT_SIZE, RoundingMode::None).abs();
+        let mut bound = BigFloat::from_word(1, 1);
+        bound.set_exponent(err as Exponent + 1);
+        assert!(q < bound);
+
+        // same result without statistics
+        let mut values3 = values2.clone();
+        compress(&mut values3, 2 * WORD_BIT_SIZE, rm, None).unwrap();
+        for (v1, v2) in values.iter().zip(values3.iter()).take(3) {
+            assert!(v1.cmp(v2) == Some(0));
+        }
+
+        // nothing to round
+        compress(&mut values3, 4 * WORD_BIT_SIZE, rm, Some(&mut stats)).unwrap();
+        assert_eq!(stats.rounded, 0);
+        assert_eq!(stats.max_rel_err, None);
+        assert_eq!(stats.words_before, stats.words_after);
+
+        assert_eq!(
+            compress(&mut values3, 0, rm, None).unwrap_err(),
+            Error::InvalidArgument
+        );
+    }
+}
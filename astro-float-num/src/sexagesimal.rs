@@ -0,0 +1,422 @@
+//! Conversion between numbers and sexagesimal notation, e.g. `12:34:56.789` or `12°34′56.789″`.
+//!
+//! Converting sexagesimal fields by repeated multiplication or division by 60 rounds on every step.
+//! The functions of this module do a single rounding: the parsed value is the correctly rounded value of the string,
+//! and the formatted seconds are the correctly rounded seconds of the number.
+
+use crate::defs::Error;
+use crate::defs::RoundingMode;
+use crate::defs::Word;
+use crate::defs::WORD_BIT_SIZE;
+use crate::num::BigFloatNumber;
+use crate::BigFloat;
+use crate::Sign;
+
+use core::fmt::Write;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+/// Notation of the sexagesimal format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    /// Fields separated by colons, e.g. `12:34:56.789`.
+    Colon,
+    /// Degrees, arcminutes, and arcseconds, e.g. `12°34′56.789″`.
+    Degrees,
+    /// Hours, minutes, and seconds, e.g. `12h34m56.789s`.
+    Hours,
+}
+
+// Separators which can follow the first, the second, and the third field.
+const SEPARATORS: [&[char]; 3] =
+    [&[':', '°', 'd', 'h', ' '], &[':', '′', '\'', 'm', ' '], &['″', '"', 's']];
+
+/// Parses a sexagesimal string `s`, and returns the value in units of the first field with precision `p`
+/// rounded using the rounding mode `rm`.
+///
+/// The string consists of an optional sign followed by one to three fields, e.g. `-12:34:56.789`.
+/// The fields can be separated by colons, by spaces, by the symbols `°`, `′`, `″` (or `d`, `'`, `"`),
+/// or by the letters `h`, `m`, `s`. Only the last field can have a fractional part,
+/// and the integer parts of the second and the third field must be less than 60.
+/// The result is computed with a single rounding, e.g. `0:00:00.1` is `1/36000` rounded according to `rm`.
+///
+/// Precision is rounded upwards to the word size.
+/// The function returns NaN if the string can't be parsed, or if the precision `p` is incorrect.
+pub fn parse(s: &str, p: usize, rm: RoundingMode) -> BigFloat {
+    match parse_internal(s, p, rm) {
+        Ok(v) => v.into(),
+        Err(e) => BigFloat::nan(Some(e)),
+    }
+}
+
+/// Formats `x` given in units of the first field using the notation `notation` with `frac_digits` decimal digits
+/// in the fractional part of the seconds, e.g. 12.5 is formatted as `12:30:00.000` with the colon notation and 3 digits.
+///
+/// The seconds are rounded using the rounding mode `rm` with a single rounding applied to `x`.
+/// The rounding mode `RoundingMode::None` truncates the seconds.
+///
+/// ## Errors
+///
+///  - InvalidArgument: `x` is Inf or NaN, `frac_digits` is larger than 30,
+///    or `x` is too large, so that the number of units of the last digit does not fit into 128 bits.
+///  - MemoryAllocation: failed to allocate memory.
+pub fn format(
+    x: &BigFloat,
+    notation: Notation,
+    frac_digits: usize,
+    rm: RoundingMode,
+) -> Result<String, Error> {
+    let x = x.as_number().ok_or(Error::InvalidArgument)?;
+
+    if frac_digits > 30 {
+        return Err(Error::InvalidArgument);
+    }
+
+    let scale = 3600 * 10u128.pow(frac_digits as u32);
+
+    // single rounding of x * 3600 * 10^frac_digits to an integer
+    let rm = if rm == RoundingMode::None { RoundingMode::ToZero } else { rm };
+    let t = x
+        .mul_full_prec(&BigFloatNumber::from_u128(scale, 128)?)?
+        .round(0, rm)?;
+    let t = to_u128(&t).ok_or(Error::InvalidArgument)?;
+
+    let frac = t % 10u128.pow(frac_digits as u32);
+    let secs = t / 10u128.pow(frac_digits as u32);
+
+    let (s1, s2, s3) = match notation {
+        Notation::Colon => (":", ":", ""),
+        Notation::Degrees => ("°", "′", "″"),
+        Notation::Hours => ("h", "m", "s"),
+    };
+
+    let mut ret = String::new();
+
+    if x.is_negative() && t != 0 {
+        ret.push('-');
+    }
+
+    let _ = write!(
+        ret,
+        "{}{}{:02}{}{:02}",
+        secs / 3600,
+        s1,
+        secs / 60 % 60,
+        s2,
+        secs % 60
+    );
+
+    if frac_digits > 0 {
+        let _ = write!(ret, ".{:0w$}", frac, w = frac_digits);
+    }
+
+    ret.push_str(s3);
+
+    Ok(ret)
+}
+
+fn parse_internal(s: &str, p: usize, rm: RoundingMode) -> Result<BigFloatNumber, Error> {
+    let s = s.trim();
+
+    let (sign, s) = if let Some(s) = s.strip_prefix('-') {
+        (Sign::Neg, s)
+    } else {
+        (Sign::Pos, s.strip_prefix('+').unwrap_or(s))
+    };
+
+    // split into fields with their integer and fractional digits
+    let mut fields: [(&str, &str); 3] = [("", ""); 3];
+    let mut n = 0;
+    let mut rest = s;
+
+    loop {
+        if n == 3 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let int_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (int, r) = rest.split_at(int_len);
+
+        let (frac, r) = if let Some(r) = r.strip_prefix('.') {
+            let frac_len = r.find(|c: char| !c.is_ascii_digit()).unwrap_or(r.len());
+            let (frac, r) = r.split_at(frac_len);
+            if frac.is_empty() {
+                return Err(Error::InvalidArgument);
+            }
+            (frac, r)
+        } else {
+            ("", r)
+        };
+
+        if int.is_empty() && frac.is_empty() {
+            return Err(Error::InvalidArgument);
+        }
+
+        fields[n] = (int, frac);
+
+        let mut chars = r.chars();
+        let sep = chars.next();
+        let r = chars.as_str().trim_start();
+
+        match sep {
+            None => {
+                n += 1;
+                break;
+            }
+            Some(c) if SEPARATORS[n].contains(&c) => {
+                n += 1;
+
+                if r.is_empty() && c != ':' && c != ' ' {
+                    break;
+                }
+
+                // only the last field can have a fractional part
+                if !frac.is_empty() {
+                    return Err(Error::InvalidArgument);
+                }
+
+                rest = r;
+            }
+            _ => return Err(Error::InvalidArgument),
+        }
+    }
+
+    // value = (((d * 60 + m) * 60 + s) * 10^k + f) / (60^(n-1) * 10^k)
+    let sixty = BigFloatNumber::from_word(60, WORD_BIT_SIZE)?;
+    let mut num = BigFloatNumber::new(WORD_BIT_SIZE)?;
+    let mut den = BigFloatNumber::from_word(1, WORD_BIT_SIZE)?;
+
+    for (i, (int, frac)) in fields[..n].iter().enumerate() {
+        let v = int_from_digits(int)?;
+
+        if i > 0 {
+            if v.cmp(&sixty) >= 0 {
+                return Err(Error::InvalidArgument);
+            }
+
+            num = num.mul_full_prec(&sixty)?;
+            den = den.mul_full_prec(&sixty)?;
+        }
+
+        num = num.add_full_prec(&v)?;
+
+        if !frac.is_empty() {
+            let mut f = BigFloatNumber::from_word(1, WORD_BIT_SIZE)?;
+            for _ in 0..frac.len() {
+                f = f.mul_full_prec(&BigFloatNumber::from_word(10, WORD_BIT_SIZE)?)?;
+            }
+
+            num = num.mul_full_prec(&f)?;
+            den = den.mul_full_prec(&f)?;
+            num = num.add_full_prec(&int_from_digits(frac)?)?;
+        }
+    }
+
+    num.set_sign(sign);
+
+    num.div(&den, p, rm)
+}
+
+// Converts a string of decimal digits to an integer exactly.
+fn int_from_digits(s: &str) -> Result<BigFloatNumber, Error> {
+    const CHUNK: usize = 9;
+
+    let mut ret = BigFloatNumber::new(WORD_BIT_SIZE)?;
+
+    for chunk in s.as_bytes().chunks(CHUNK) {
+        let mut scale: Word = 1;
+        let mut v: Word = 0;
+
+        for &d in chunk {
+            scale *= 10;
+            v = v * 10 + (d - b'0') as Word;
+        }
+
+        ret = ret
+            .mul_full_prec(&BigFloatNumber::from_word(scale, WORD_BIT_SIZE)?)?
+            .add_full_prec(&BigFloatNumber::from_word(v, WORD_BIT_SIZE)?)?;
+    }
+
+    Ok(ret)
+}
+
+// Returns the absolute value of the integer `x` if it fits into 128 bits.
+fn to_u128(x: &BigFloatNumber) -> Option<u128> {
+    if x.is_zero() {
+        return Some(0);
+    }
+
+    if x.exponent() > 128 {
+        return None;
+    }
+
+    let (m, _, _, e, _) = x.as_raw_parts();
+
+    let mut ret: u128 = 0;
+    let mut rem = e as usize;
+
+    for &w in m.iter().rev() {
+        if rem == 0 {
+            break;
+        }
+
+        let n = rem.min(WORD_BIT_SIZE);
+        ret = (ret << n) | (w >> (WORD_BIT_SIZE - n)) as u128;
+        rem -= n;
+    }
+
+    Some(ret << rem)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Consts;
+    use crate::Radix;
+
+    #[test]
+    fn test_sexagesimal_parse() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+        let mut cc = Consts::new().unwrap();
+
+        for s in [
+            "12:34:56.789",
+            "12°34′56.789″",
+            "12d34'56.789\"",
+            "12h34m56.789s",
+            "12 34 56.789",
+            " +12h 34m 56.789s ",
+        ] {
+            let d = parse(s, p, rm);
+            let expected =
+                BigFloat::parse("45296789", Radix::Dec, p * 2, RoundingMode::None, &mut cc).div(
+                    &BigFloat::from_word(3600000, 1),
+                    p,
+                    rm,
+                );
+            assert_eq!(d, expected, "{}", s);
+        }
+
+        // single rounding
+        for rm in [RoundingMode::Up, RoundingMode::Down, RoundingMode::ToEven] {
+            let d = parse("-0:00:00.1", p, rm);
+            let expected =
+                BigFloat::from_word(1, 1)
+                    .neg()
+                    .div(&BigFloat::from_word(36000, 1), p, rm);
+            assert_eq!(d, expected);
+            assert!(d.inexact());
+        }
+
+        // fewer fields and fractions in the last field
+        assert_eq!(parse("12:30", p, rm), BigFloat::from_f64(12.5, p));
+        let expected = BigFloat::from_word(1501, 1).div(&BigFloat::from_word(120, 1), p, rm);
+        assert_eq!(parse("12:30.5", p, rm), expected);
+        assert_eq!(parse("7.25", p, rm), BigFloat::from_f64(7.25, p));
+        assert_eq!(parse("-0:30", p, rm), BigFloat::from_f64(-0.5, p));
+        let expected = BigFloat::from_word(1, 1).div(&BigFloat::from_word(100, 1), p, rm);
+        assert_eq!(parse("0:00:36", p, rm), expected);
+        assert!(parse("-0:00:00", p, rm).is_zero());
+
+        // large integers and long fractions are exact
+        let d = parse("123456789012345678901234567890:00:00", 256, rm);
+        assert_eq!(d, BigFloat::from_u128(123456789012345678901234567890, 256));
+        let d = parse("0:00:00.00000000000000000000000000001", p, RoundingMode::Up);
+        assert!(d.is_positive() && !d.is_zero());
+
+        // errors
+        for s in [
+            "",
+            "-",
+            "12:",
+            "12::",
+            "12:60",
+            "12:30:60",
+            "12.5:30",
+            "12:30:15:10",
+            "1.",
+            ".",
+            "12x",
+            "12:3a",
+            "12″",
+            "12h34m56.7s5",
+        ] {
+            assert!(parse(s, p, rm).is_nan(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_sexagesimal_format() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+
+        let x = parse("12:34:56.789", p, rm);
+        assert_eq!(format(&x, Notation::Colon, 3, rm).unwrap(), "12:34:56.789");
+        assert_eq!(
+            format(&x, Notation::Degrees, 3, rm).unwrap(),
+            "12°34′56.789″"
+        );
+        assert_eq!(format(&x, Notation::Hours, 3, rm).unwrap(), "12h34m56.789s");
+        assert_eq!(format(&x, Notation::Colon, 1, rm).unwrap(), "12:34:56.8");
+        assert_eq!(
+            format(&x, Notation::Colon, 1, RoundingMode::Down).unwrap(),
+            "12:34:56.7"
+        );
+        assert_eq!(format(&x, Notation::Colon, 0, rm).unwrap(), "12:34:57");
+        assert_eq!(
+            format(&x, Notation::Colon, 6, rm).unwrap(),
+            "12:34:56.789000"
+        );
+
+        // carries propagate through the fields
+        let x = parse("23:59:59.9996", p, rm);
+        assert_eq!(format(&x, Notation::Colon, 3, rm).unwrap(), "24:00:00.000");
+
+        // sign
+        let x = parse("-0:30:00.5", p, rm);
+        assert_eq!(format(&x, Notation::Colon, 1, rm).unwrap(), "-0:30:00.5");
+        assert_eq!(
+            format(&x, Notation::Colon, 0, RoundingMode::Up).unwrap(),
+            "-0:30:00"
+        );
+        assert_eq!(
+            format(&x, Notation::Colon, 0, RoundingMode::Down).unwrap(),
+            "-0:30:01"
+        );
+        let x = BigFloat::from_f64(-1.0e-10, p);
+        assert_eq!(format(&x, Notation::Colon, 3, rm).unwrap(), "0:00:00.000");
+
+        // the formatted value is parsed back to the same value
+        for _ in 0..100 {
+            let x = BigFloat::random_normal(p, -10, 10);
+            let s = format(&x, Notation::Degrees, 30, rm).unwrap();
+            let d = parse(&s, p, rm);
+            let diff = d.sub(&x, p, RoundingMode::None).abs();
+            assert!(diff.exponent().unwrap() < -90 || diff.is_zero(), "{}", s);
+        }
+
+        // errors
+        assert_eq!(
+            format(&crate::NAN, Notation::Colon, 0, rm),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(
+            format(&crate::INF_POS, Notation::Colon, 0, rm),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(
+            format(&BigFloat::from_f64(1.0, p), Notation::Colon, 31, rm),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(
+            format(&BigFloat::from_f64(1.0e40, p), Notation::Colon, 3, rm),
+            Err(Error::InvalidArgument)
+        );
+        assert!(format(&BigFloat::from_f64(1.0e30, p), Notation::Colon, 3, rm).is_ok());
+    }
+}
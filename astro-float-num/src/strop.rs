@@ -1,8 +1,14 @@
 //! BigFloatNumber formatting.
 
+use crate::common::util::round_p;
+use crate::conv::log2_fixed;
 use crate::defs::Error;
+use crate::defs::FormatOptions;
+#[cfg(test)]
 use crate::defs::Radix;
 use crate::defs::RoundingMode;
+use crate::defs::Word;
+use crate::defs::WORD_BIT_SIZE;
 use crate::num::BigFloatNumber;
 use crate::Consts;
 use crate::Exponent;
@@ -12,10 +18,12 @@ use crate::Sign;
 use std::fmt::Write;
 
 #[cfg(not(feature = "std"))]
-use {alloc::string::String, core::fmt::Write};
+use {alloc::string::String, alloc::vec::Vec, core::fmt::Write};
 
-const DIGIT_CHARS: [char; 16] =
-    ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F'];
+const DIGIT_CHARS: [char; 36] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
+    'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
 
 impl BigFloatNumber {
     /// Parses the number from the string `s` using radix `rdx`, precision `p`, and rounding mode `rm`.
@@ -55,69 +63,388 @@ impl BigFloatNumber {
     ///
     ///  - MemoryAllocation: failed to allocate memory for mantissa.
     ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    #[cfg(test)]
     pub fn format(&self, rdx: Radix, rm: RoundingMode, cc: &mut Consts) -> Result<String, Error> {
-        let (s, m, e) = self.convert_to_radix(rdx, rm, cc)?;
-
-        let mut mstr = String::new();
-        let mstr_sz = 8
-            + (self.mantissa_max_bit_len() + core::mem::size_of::<Exponent>() * 8)
-                / match rdx {
-                    Radix::Bin => 1,
-                    Radix::Oct => 3,
-                    Radix::Dec => 3,
-                    Radix::Hex => 4,
-                };
+        self.format_with(rdx as u32, rm, &FormatOptions::default(), cc)
+    }
+
+    /// Formats the number using radix `base` from 2 to 36, rounding mode `rm`, and formatting options `opts`.
+    /// In scientific notation, the output differs from the output of `format` only in separators.
+    /// In positional notation, the number is rounded to `opts.frac_digits` digits after the decimal separator using `rm`.
+    /// Digits of the exponent are not grouped.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    pub fn format_with(
+        &self,
+        base: u32,
+        rm: RoundingMode,
+        opts: &FormatOptions,
+        cc: &mut Consts,
+    ) -> Result<String, Error> {
+        // a lower estimate of log2(base)
+        let bits_per_digit = (31 - base.leading_zeros()) as usize;
+
+        let len = match opts.frac_digits {
+            Some(n) => (self.exponent().max(0) as usize) / bits_per_digit + n + 2,
+            None => {
+                (self.mantissa_max_bit_len() + core::mem::size_of::<Exponent>() * 8)
+                    / bits_per_digit
+            }
+        };
+
+        let mut ret = String::new();
+        ret.try_reserve_exact(
+            8 + Self::grouped_len(len, opts) + opts.decimal_separator.len_utf8(),
+        )?;
+
+        // writing to a string does not fail
+        let _ = self.write_with(&mut ret, base, rm, opts, cc)?;
+
+        Ok(ret)
+    }
+
+    /// Writes the number formatted using radix `base`, rounding mode `rm`, and formatting options `opts` to `w`.
+    /// The output is the same as the output of `format_with`, but the characters are written to `w` directly
+    /// without building an intermediate string.
+    /// The digits are computed into a buffer before writing, so the function still allocates memory.
+    /// The result of writing to `w` is returned in `Ok`. Nothing is written to `w` if an error is returned.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    pub fn write_with<T: Write>(
+        &self,
+        w: &mut T,
+        base: u32,
+        rm: RoundingMode,
+        opts: &FormatOptions,
+        cc: &mut Consts,
+    ) -> Result<core::fmt::Result, Error> {
+        if let Some(n) = opts.frac_digits {
+            let digits = self.positional_digits(base, n, rm, cc)?;
+            Ok(Self::write_positional(
+                w,
+                self.is_negative(),
+                &digits,
+                n,
+                opts,
+            ))
+        } else {
+            let (s, m, e) = self.convert_to_base(base, rm, cc)?;
+            Ok(self.write_scientific(w, s, &m, e, base, opts))
+        }
+    }
+
+    /// Writes `digits` in positional notation with `n` digits after the decimal separator.
+    fn write_positional<T: Write>(
+        w: &mut T,
+        neg: bool,
+        digits: &[u8],
+        n: usize,
+        opts: &FormatOptions,
+    ) -> core::fmt::Result {
+        if neg {
+            w.write_char('-')?;
+        }
+
+        let (int, fract) = digits.split_at(digits.len() - n);
+        Self::write_grouped(w, int, opts, false)?;
+        if n > 0 {
+            w.write_char(opts.decimal_separator)?;
+            Self::write_grouped(w, fract, opts, true)?;
+        }
 
-        mstr.try_reserve_exact(mstr_sz)?;
+        Ok(())
+    }
 
+    /// Writes the sign `s`, the digits `m`, and the exponent `e` produced by `convert_to_base` in scientific notation.
+    /// The exponent is written in radix `base` in lower case, and it is separated by `_` if `e` is a digit in the radix.
+    fn write_scientific<T: Write>(
+        &self,
+        w: &mut T,
+        s: Sign,
+        m: &[u8],
+        e: Exponent,
+        base: u32,
+        opts: &FormatOptions,
+    ) -> core::fmt::Result {
         if s == Sign::Neg {
-            mstr.push('-');
+            w.write_char('-')?;
         }
 
         if m.is_empty() {
-            mstr.push_str("0.0");
+            w.write_char('0')?;
+            w.write_char(opts.decimal_separator)?;
+            return w.write_char('0');
+        }
+
+        let fract = if self.is_subnormal() {
+            w.write_char('0')?;
+            m
         } else {
-            let mut iter = m.iter();
+            w.write_char(DIGIT_CHARS[m[0] as usize])?;
+            &m[1..]
+        };
+
+        w.write_char(opts.decimal_separator)?;
+
+        Self::write_grouped(w, fract, opts, true)?;
+
+        if base > 14 {
+            w.write_char('_')?;
+        }
+
+        let val = if e < 1 {
+            w.write_str("e-")?;
 
             if self.is_subnormal() {
-                mstr.push('0');
+                e.unsigned_abs() as usize
             } else {
-                mstr.push(DIGIT_CHARS[*iter.next().unwrap() as usize]); // m is not empty as checked above, hence unwrap
+                (e as isize - 1).unsigned_abs()
             }
+        } else {
+            w.write_str("e+")?;
+
+            (e as isize - 1) as usize
+        };
+
+        Self::write_exponent(w, val, base)
+    }
 
-            mstr.push('.');
+    /// Writes the absolute value of the exponent `val` in radix `base` in lower case.
+    fn write_exponent<T: Write>(w: &mut T, mut val: usize, base: u32) -> core::fmt::Result {
+        let mut buf = [0u8; usize::BITS as usize];
+        let mut l = 0;
 
-            iter.map(|&d| DIGIT_CHARS[d as usize])
-                .for_each(|v| mstr.push(v));
+        loop {
+            buf[l] = (val % base as usize) as u8;
+            val /= base as usize;
+            l += 1;
 
-            if rdx == Radix::Hex {
-                let _ = write!(mstr, "_");
+            if val == 0 {
+                break;
             }
+        }
 
-            if e < 1 {
-                let val = if self.is_subnormal() {
-                    e.unsigned_abs() as usize
-                } else {
-                    (e as isize - 1).unsigned_abs()
-                };
+        for &d in buf[..l].iter().rev() {
+            w.write_char(DIGIT_CHARS[d as usize].to_ascii_lowercase())?;
+        }
 
-                let _ = match rdx {
-                    Radix::Bin => write!(mstr, "e-{:b}", val),
-                    Radix::Oct => write!(mstr, "e-{:o}", val),
-                    Radix::Dec => write!(mstr, "e-{}", val),
-                    Radix::Hex => write!(mstr, "e-{:x}", val),
-                };
+        Ok(())
+    }
+
+    /// Returns the length in bytes of `n` digits with group separators inserted according to `opts`.
+    fn grouped_len(n: usize, opts: &FormatOptions) -> usize {
+        match n.checked_div(opts.group_size) {
+            Some(groups) => n + groups * opts.group_separator.len_utf8(),
+            None => n,
+        }
+    }
+
+    /// Writes `digits` to `w` inserting the group separator between groups of digits.
+    /// If `from_left` is true, groups are counted from the first digit, otherwise from the last digit.
+    fn write_grouped<T: Write>(
+        w: &mut T,
+        digits: &[u8],
+        opts: &FormatOptions,
+        from_left: bool,
+    ) -> core::fmt::Result {
+        let g = opts.group_size;
+        let l = digits.len();
+
+        for (i, &d) in digits.iter().enumerate() {
+            if g > 0 && i > 0 && (if from_left { i } else { l - i }) % g == 0 {
+                w.write_char(opts.group_separator)?;
+            }
+            w.write_char(DIGIT_CHARS[d as usize])?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the digits of the absolute value of `self` multiplied by `base` to the power of `n`
+    /// and rounded to an integer using rounding mode `rm`.
+    /// The result contains at least `n + 1` digits.
+    fn positional_digits(
+        &self,
+        base: u32,
+        n: usize,
+        rm: RoundingMode,
+        cc: &mut Consts,
+    ) -> Result<Vec<u8>, Error> {
+        let mut x = self.clone()?;
+
+        // scaling by base^n is exact, so rounding to an integer gives the correctly rounded result
+        if n > 0 {
+            x = if base.is_power_of_two() {
+                x.ldexp(
+                    (n * base.trailing_zeros() as usize) as isize,
+                    x.mantissa_max_bit_len(),
+                    RoundingMode::None,
+                )?
             } else {
-                let _ = match rdx {
-                    Radix::Bin => write!(mstr, "e+{:b}", e as isize - 1),
-                    Radix::Oct => write!(mstr, "e+{:o}", e as isize - 1),
-                    Radix::Dec => write!(mstr, "e+{}", e as isize - 1),
-                    Radix::Hex => write!(mstr, "e+{:x}", e as isize - 1),
-                };
+                // powers of 2 in base^n go to the exponent, so only the odd part takes the mantissa bits
+                let odd = base >> base.trailing_zeros();
+                let tp = BigFloatNumber::from_word(base as Word, WORD_BIT_SIZE)?.powi(
+                    n,
+                    round_p(n * (u32::BITS - odd.leading_zeros()) as usize + 1),
+                    RoundingMode::None,
+                )?;
+                x.mul_full_prec(&tp)?
             };
         }
 
-        Ok(mstr)
+        let x = x.round_int(rm)?;
+
+        let mut digits = Vec::new();
+        if !x.is_zero() {
+            let (_, d, e) = if base.is_power_of_two() {
+                x.convert_to_base(base, RoundingMode::None, cc)?
+            } else {
+                // the number of digits is large enough for the integer to be converted exactly
+                let l = (((x.exponent() as u128) << 32) / log2_fixed(base) as u128) as usize + 2;
+                x.conv_to_base_digits(base, l, RoundingMode::ToEven, cc)?
+            };
+            digits = d;
+            digits.resize(e as usize, 0);
+        }
+
+        if digits.len() <= n {
+            let l = digits.len();
+            digits.resize(n + 1, 0);
+            digits.rotate_right(n + 1 - l);
+        }
+
+        Ok(digits)
+    }
+
+    /// Formats the number in C99 hexadecimal floating point notation, e.g. `0x1.8p+3`.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory.
+    pub fn format_hex_float(&self) -> Result<String, Error> {
+        let n = self.mantissa_max_bit_len();
+
+        let mut ret = String::new();
+        ret.try_reserve_exact(n / 4 + 32)?;
+
+        if self.is_negative() {
+            ret.push('-');
+        }
+
+        ret.push_str("0x");
+
+        if self.is_zero() {
+            ret.push_str("0p+0");
+            return Ok(ret);
+        }
+
+        let m = self.mantissa().digits();
+
+        // i-th bit of the mantissa counting from the most significant bit
+        let bit = |i: usize| {
+            let i = n - 1 - i;
+            (m[i / WORD_BIT_SIZE] >> (i % WORD_BIT_SIZE)) & 1
+        };
+
+        // the leading one is preceded by zeroes in subnormal numbers
+        let shift = n - self.precision();
+
+        let mut end = n;
+        while bit(end - 1) == 0 {
+            end -= 1;
+        }
+
+        ret.push('1');
+
+        if end > shift + 1 {
+            ret.push('.');
+
+            for i in (shift + 1..end).step_by(4) {
+                let mut d = 0;
+                for j in i..i + 4 {
+                    d <<= 1;
+                    if j < end {
+                        d |= bit(j);
+                    }
+                }
+                ret.push(DIGIT_CHARS[d as usize].to_ascii_lowercase());
+            }
+        }
+
+        let e = self.exponent() as isize - shift as isize - 1;
+        let _ = write!(
+            ret,
+            "p{}{}",
+            if e < 0 { '-' } else { '+' },
+            e.unsigned_abs()
+        );
+
+        Ok(ret)
+    }
+
+    /// Formats the absolute value of `self` in decimal positional notation with `n` digits after the decimal point.
+    /// The result is correctly rounded to nearest with ties to even.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    #[cfg(feature = "std")]
+    pub(crate) fn format_fixed(&self, n: usize, cc: &mut Consts) -> Result<String, Error> {
+        let opts = FormatOptions {
+            frac_digits: Some(n),
+            ..Default::default()
+        };
+
+        self.abs()?.format_with(10, RoundingMode::ToEven, &opts, cc)
+    }
+
+    /// Formats the absolute value of `self` in decimal scientific notation with `n` digits after the decimal point
+    /// using `exp` as the exponent character.
+    /// The result is correctly rounded to nearest with ties to even.
+    ///
+    /// ## Errors
+    ///
+    ///  - MemoryAllocation: failed to allocate memory for mantissa.
+    ///  - ExponentOverflow: the resulting exponent becomes greater than the maximum allowed value for the exponent.
+    #[cfg(feature = "std")]
+    pub(crate) fn format_sci(&self, n: usize, exp: char, cc: &mut Consts) -> Result<String, Error> {
+        let (_, mut digits, mut e) = self.conv_to_dec_digits(n + 1, RoundingMode::ToEven, cc)?;
+
+        if digits.is_empty() {
+            digits.push(0);
+            e = 1;
+        }
+
+        digits.resize(n + 1, 0);
+
+        let mut ret = String::new();
+        ret.try_reserve_exact(n + 16)?;
+
+        ret.push(DIGIT_CHARS[digits[0] as usize]);
+        if n > 0 {
+            ret.push('.');
+            digits[1..]
+                .iter()
+                .for_each(|&d| ret.push(DIGIT_CHARS[d as usize]));
+        }
+
+        let e = e as isize - 1;
+        let _ = write!(
+            ret,
+            "{}{}{}",
+            exp,
+            if e < 0 { '-' } else { '+' },
+            e.unsigned_abs()
+        );
+
+        Ok(ret)
     }
 }
 
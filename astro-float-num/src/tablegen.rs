@@ -0,0 +1,449 @@
+//! Generation of correctly rounded tables of function values.
+//!
+//! A function is evaluated for each point of an input grid with a precision larger than the precision of the output format,
+//! and the result is rounded to the output format using a single rounding: the range of exponents,
+//! subnormal numbers, and overflow of the output format are taken into account.
+//! The resulting table can be written as a Rust array or as a binary blob.
+//! The grid is split between several threads.
+
+use std::io::Write;
+use std::thread;
+
+use crate::common::util::round_p;
+use crate::BigFloat;
+use crate::Consts;
+use crate::Error;
+use crate::Exponent;
+use crate::RoundingMode;
+use crate::Sign;
+use crate::INF_NEG;
+use crate::INF_POS;
+use crate::WORD_BIT_SIZE;
+
+/// Output format of a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// IEEE 754 binary32 (`f32`).
+    Binary32,
+
+    /// IEEE 754 binary64 (`f64`).
+    Binary64,
+}
+
+impl Format {
+    /// Returns the number of significant bits of a normal number of the format.
+    pub fn precision(&self) -> usize {
+        match self {
+            Format::Binary32 => 24,
+            Format::Binary64 => 53,
+        }
+    }
+
+    // Minimum and maximum exponents of a normal number of the format.
+    // The exponents follow the convention of BigFloat: the mantissa is in the range [0.5, 1).
+    fn exponent_range(&self) -> (isize, isize) {
+        match self {
+            Format::Binary32 => (-125, 128),
+            Format::Binary64 => (-1021, 1024),
+        }
+    }
+
+    /// Returns the size of a single value of the format in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            Format::Binary32 => 4,
+            Format::Binary64 => 8,
+        }
+    }
+}
+
+/// Computes a table of values of the function `f` for the arguments from `grid`.
+///
+/// For each argument `x` the function is evaluated as `f(x, p_wrk, RoundingMode::ToZero, cc)`,
+/// where `p_wrk` is a working precision larger than the precision of `format`.
+/// The result of `f` is expected to be correctly rounded and to have the inexact flag set when the result is not exact,
+/// e.g. `f` can be `BigFloat::exp`, `BigFloat::sin`, or a closure calling one of the other functions.
+/// The result is then rounded to `format` using the rounding mode `rm`.
+/// Each of the returned values is NaN, infinity, or a number exactly representable in `format`.
+///
+/// The arguments are split between `threads` threads, each of which uses its own constants cache.
+/// If `threads` is 0, the number of threads is chosen automatically.
+///
+/// ## Errors
+///
+///  - InvalidArgument: `rm` is `RoundingMode::None`.
+///  - MemoryAllocation: failed to allocate memory.
+pub fn generate<F>(
+    f: F,
+    grid: &[BigFloat],
+    format: Format,
+    rm: RoundingMode,
+    threads: usize,
+) -> Result<Vec<BigFloat>, Error>
+where
+    F: Fn(&BigFloat, usize, RoundingMode, &mut Consts) -> BigFloat + Sync,
+{
+    if rm == RoundingMode::None {
+        return Err(Error::InvalidArgument);
+    }
+
+    let threads = if threads == 0 {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        threads
+    };
+
+    let chunk_len = grid.len().div_ceil(threads).max(1);
+    let p_wrk = round_p(format.precision() + WORD_BIT_SIZE);
+
+    let chunks: Vec<Result<Vec<BigFloat>, Error>> = thread::scope(|s| {
+        let handles: Vec<_> = grid
+            .chunks(chunk_len)
+            .map(|chunk| {
+                let f = &f;
+                s.spawn(move || -> Result<Vec<BigFloat>, Error> {
+                    let mut cc = Consts::new()?;
+                    let mut ret = Vec::new();
+                    ret.try_reserve_exact(chunk.len())?;
+
+                    for x in chunk {
+                        let y = f(x, p_wrk, RoundingMode::ToZero, &mut cc);
+
+                        if let Some(Error::MemoryAllocation) = y.err() {
+                            return Err(Error::MemoryAllocation);
+                        }
+
+                        ret.push(round_to_format(&y, format, rm)?);
+                    }
+
+                    Ok(ret)
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("table generation thread panicked"))
+            .collect()
+    });
+
+    let mut ret = Vec::new();
+    ret.try_reserve_exact(grid.len())?;
+
+    for chunk in chunks {
+        ret.extend(chunk?);
+    }
+
+    Ok(ret)
+}
+
+/// Writes `values` computed by [`generate`] as a Rust constant array named `name` of `f32` or `f64` depending on `format`.
+/// The values are written using the shortest decimal representation which converts back to the same value.
+///
+/// ## Errors
+///
+/// Returns an error if writing to `w` fails, or if one of the values is not representable in `format`.
+pub fn write_rust_array<W: Write>(
+    values: &[BigFloat],
+    format: Format,
+    name: &str,
+    w: &mut W,
+) -> std::io::Result<()> {
+    let ty = match format {
+        Format::Binary32 => "f32",
+        Format::Binary64 => "f64",
+    };
+
+    writeln!(w, "pub const {}: [{}; {}] = [", name, ty, values.len())?;
+
+    for v in values {
+        let d = to_f64(v, format)?;
+
+        if d.is_nan() {
+            writeln!(w, "    {}::NAN,", ty)?;
+        } else if d == f64::INFINITY {
+            writeln!(w, "    {}::INFINITY,", ty)?;
+        } else if d == f64::NEG_INFINITY {
+            writeln!(w, "    {}::NEG_INFINITY,", ty)?;
+        } else if format == Format::Binary32 {
+            writeln!(w, "    {:?},", d as f32)?;
+        } else {
+            writeln!(w, "    {:?},", d)?;
+        }
+    }
+
+    writeln!(w, "];")
+}
+
+/// Writes `values` computed by [`generate`] in the binary encoding of `format` using little-endian byte order.
+///
+/// ## Errors
+///
+/// Returns an error if writing to `w` fails, or if one of the values is not representable in `format`.
+pub fn write_binary<W: Write>(
+    values: &[BigFloat],
+    format: Format,
+    w: &mut W,
+) -> std::io::Result<()> {
+    for v in values {
+        let d = to_f64(v, format)?;
+
+        match format {
+            Format::Binary32 => w.write_all(&(d as f32).to_le_bytes())?,
+            Format::Binary64 => w.write_all(&d.to_le_bytes())?,
+        }
+    }
+
+    Ok(())
+}
+
+// Converts a value of the table to f64.
+fn to_f64(v: &BigFloat, format: Format) -> std::io::Result<f64> {
+    if v.is_nan() {
+        return Ok(f64::NAN);
+    }
+
+    match v.to_f64_exact() {
+        Some(d) if format == Format::Binary64 || (d as f32) as f64 == d => Ok(d),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "value is not representable in the output format",
+        )),
+    }
+}
+
+// Rounds `y` to `format` using the rounding mode `rm`.
+// `y` is a correctly rounded towards zero value, and its inexact flag indicates
+// that the exact value is larger in magnitude.
+fn round_to_format(y: &BigFloat, format: Format, rm: RoundingMode) -> Result<BigFloat, Error> {
+    let (e, s) = match (y.exponent(), y.sign()) {
+        (Some(e), Some(s)) if !y.is_zero() => (e as isize, s),
+        _ => return Ok(y.clone()),
+    };
+
+    let prec = format.precision() as isize;
+    let (emin, emax) = format.exponent_range();
+
+    // The numbers which are smaller than a quarter of the smallest subnormal number of the format
+    // are all rounded in the same way, so they are replaced with a single representative.
+    let tiny = emin - prec - 2;
+
+    let mut x = if e < tiny || y.is_subnormal() {
+        let mut x = BigFloat::from_word(1, WORD_BIT_SIZE);
+        x.set_exponent(tiny as Exponent);
+        x.set_inexact(true);
+        x
+    } else {
+        y.clone()
+    };
+
+    x.set_sign(Sign::Pos);
+
+    // scale the value, so that the rounding position of the format is the bit `prec` of the fractional part
+    let ee = (x.exponent().ok_or(Error::InvalidArgument)? as isize).max(emin);
+    x.set_exponent((x.exponent().ok_or(Error::InvalidArgument)? as isize - ee) as Exponent);
+
+    if x.inexact() {
+        // the exact value is larger than the truncated value: append a sticky bit
+        let n = x.mantissa_max_bit_len().ok_or(Error::InvalidArgument)?;
+        let mut sticky = BigFloat::from_word(1, WORD_BIT_SIZE);
+        sticky.set_exponent(x.exponent().ok_or(Error::InvalidArgument)? - n as Exponent);
+        x = x.add(&sticky, n + WORD_BIT_SIZE, RoundingMode::None);
+    }
+
+    x.set_sign(s);
+
+    let mut ret = x.round(prec as usize, rm);
+
+    if let Some(err) = ret.err() {
+        return Err(err);
+    }
+
+    if !ret.is_zero() {
+        let er = ret.exponent().ok_or(Error::InvalidArgument)? as isize + ee;
+
+        if er > emax {
+            let to_zero = matches!(
+                (rm, s),
+                (RoundingMode::ToZero, _)
+                    | (RoundingMode::Down, Sign::Pos)
+                    | (RoundingMode::Up, Sign::Neg)
+            );
+
+            ret = if to_zero {
+                max_finite(format, s)
+            } else if s == Sign::Pos {
+                INF_POS
+            } else {
+                INF_NEG
+            };
+        } else {
+            ret.set_exponent(er as Exponent);
+        }
+    }
+
+    ret.set_inexact(y.inexact() || ret.inexact());
+
+    Ok(ret)
+}
+
+// The largest finite number of the format with the sign `s`.
+fn max_finite(format: Format, s: Sign) -> BigFloat {
+    let d = match format {
+        Format::Binary32 => f32::MAX as f64,
+        Format::Binary64 => f64::MAX,
+    };
+
+    let mut ret = BigFloat::from_f64(d, WORD_BIT_SIZE);
+    ret.set_sign(s);
+    ret.set_inexact(true);
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::NAN;
+
+    #[test]
+    fn test_tablegen() {
+        let rm = RoundingMode::ToEven;
+
+        // compare with the values computed by the standard library
+        let grid: Vec<BigFloat> = (0..1000)
+            .map(|i| BigFloat::from_word(i, 64).sqrt(64, RoundingMode::None))
+            .collect();
+
+        for format in [Format::Binary32, Format::Binary64] {
+            let t = generate(|x, p, rm, _| x.mul(x, p, rm), &grid, format, rm, 4).unwrap();
+
+            assert_eq!(t.len(), grid.len());
+
+            for (i, v) in t.iter().enumerate() {
+                let d = v.to_f64_exact().unwrap();
+
+                // the square of the truncated sqrt(i) is slightly smaller than i
+                match format {
+                    Format::Binary32 => assert_eq!(d as f32, i as f32),
+                    Format::Binary64 => assert_eq!(d, i as f64),
+                }
+            }
+        }
+
+        // directed rounding and the number of threads do not affect the result
+        let one = BigFloat::from_word(1, 64);
+        let grid: Vec<BigFloat> = (1..200).map(|i| BigFloat::from_word(i, 64)).collect();
+        let recip = |x: &BigFloat, p, rm, _: &mut Consts| one.div(x, p, rm);
+
+        let t1 = generate(recip, &grid, Format::Binary64, rm, 1).unwrap();
+        let t2 = generate(recip, &grid, Format::Binary64, rm, 0).unwrap();
+        let t3 = generate(recip, &grid, Format::Binary64, RoundingMode::Up, 3).unwrap();
+        let t4 = generate(recip, &grid, Format::Binary64, RoundingMode::Down, 7).unwrap();
+
+        for i in 0..grid.len() {
+            let d = 1.0 / (i + 1) as f64;
+            assert_eq!(t1[i].to_f64_exact().unwrap(), d);
+            assert_eq!(t2[i].to_f64_exact().unwrap(), d);
+
+            let (up, down) = (t3[i].to_f64_exact().unwrap(), t4[i].to_f64_exact().unwrap());
+            if (i + 1).is_power_of_two() {
+                assert!(up == d && down == d);
+            } else {
+                assert!(up > down && (up == d || down == d));
+                assert_eq!(up.to_bits() - down.to_bits(), 1);
+            }
+        }
+
+        // ties are resolved using the inexact flag
+        let exact = |x: &BigFloat, _, _, _: &mut Consts| x.clone();
+        let id = |x: &BigFloat, _, _, _: &mut Consts| {
+            let mut y = x.clone();
+            y.set_inexact(!x.is_zero());
+            y
+        };
+
+        let mut half = BigFloat::from_word(1, 1);
+        half.set_exponent(-52);
+        let x = BigFloat::from_word(1, 128).add(&half, 128, RoundingMode::None);
+
+        let t = generate(exact, std::slice::from_ref(&x), Format::Binary64, rm, 1).unwrap();
+        assert_eq!(t[0].to_f64_exact().unwrap(), 1.0);
+        let t = generate(id, std::slice::from_ref(&x), Format::Binary64, rm, 1).unwrap();
+        assert_eq!(t[0].to_f64_exact().unwrap(), 1.0 + f64::EPSILON);
+        let t = generate(id, &[x.neg()], Format::Binary64, RoundingMode::ToZero, 1).unwrap();
+        assert_eq!(t[0].to_f64_exact().unwrap(), -1.0);
+        let t = generate(id, &[x.neg()], Format::Binary64, RoundingMode::Down, 1).unwrap();
+        assert_eq!(t[0].to_f64_exact().unwrap(), -1.0 - f64::EPSILON);
+
+        // subnormal numbers, overflow, special values
+        let mut tiny = BigFloat::from_word(5, 64);
+        tiny.set_exponent(-1072);
+        let mut huge = BigFloat::from_word(1, 64);
+        huge.set_exponent(1025);
+        let grid = [
+            tiny,
+            BigFloat::min_positive(64),
+            huge.clone(),
+            huge.neg(),
+            BigFloat::from_f64(1e-40, 64),
+            BigFloat::from_f64(f64::MAX, 64),
+            BigFloat::from_f64(0.25, 64),
+            BigFloat::new(64).neg(),
+            INF_POS,
+            NAN,
+        ];
+
+        let t = generate(id, &grid, Format::Binary64, rm, 2).unwrap();
+        let d: Vec<Option<f64>> = t.iter().map(|v| v.to_f64_exact()).collect();
+        assert_eq!(d[0], Some(f64::from_bits(3)));
+        assert_eq!(d[1], Some(0.0));
+        assert_eq!(d[2], Some(f64::INFINITY));
+        assert_eq!(d[3], Some(f64::NEG_INFINITY));
+        assert_eq!(d[4], Some(1e-40));
+        assert_eq!(d[5], Some(f64::MAX));
+        assert!(d[7] == Some(0.0) && t[7].is_negative());
+        assert_eq!(d[8], Some(f64::INFINITY));
+        assert!(t[9].is_nan());
+
+        let t = generate(id, &grid, Format::Binary64, RoundingMode::Up, 2).unwrap();
+        let d: Vec<Option<f64>> = t.iter().map(|v| v.to_f64_exact()).collect();
+        assert_eq!(d[0], Some(f64::from_bits(3)));
+        assert_eq!(d[1], Some(f64::from_bits(1)));
+        assert_eq!(d[2], Some(f64::INFINITY));
+        assert_eq!(d[3], Some(-f64::MAX));
+        assert_eq!(d[5], Some(f64::INFINITY));
+
+        let t = generate(id, &grid, Format::Binary64, RoundingMode::Down, 2).unwrap();
+        assert_eq!(t[0].to_f64_exact(), Some(f64::from_bits(2)));
+
+        let t = generate(id, &grid, Format::Binary32, rm, 2).unwrap();
+        let d: Vec<Option<f64>> = t.iter().map(|v| v.to_f64_exact()).collect();
+        assert_eq!(d[4], Some(1e-40f64 as f32 as f64));
+        assert_eq!(d[5], Some(f64::INFINITY));
+
+        assert_eq!(
+            generate(id, &grid, Format::Binary64, RoundingMode::None, 1).unwrap_err(),
+            Error::InvalidArgument
+        );
+
+        // output
+        let t = generate(id, &grid[5..], Format::Binary32, RoundingMode::ToZero, 1).unwrap();
+
+        let mut buf = Vec::new();
+        write_rust_array(&t, Format::Binary32, "TABLE", &mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "pub const TABLE: [f32; 5] = [\n    3.4028235e38,\n    0.25,\n    -0.0,\n    f32::INFINITY,\n    f32::NAN,\n];\n"
+        );
+
+        let mut buf = Vec::new();
+        write_binary(&t, Format::Binary32, &mut buf).unwrap();
+        assert_eq!(buf.len(), 5 * Format::Binary32.size());
+        assert_eq!(buf[..4], f32::MAX.to_le_bytes());
+        assert_eq!(buf[8..12], (-0.0f32).to_le_bytes());
+
+        let mut buf = Vec::new();
+        assert!(write_binary(&[BigFloat::from_f64(0.1, 64)], Format::Binary32, &mut buf).is_err());
+    }
+}
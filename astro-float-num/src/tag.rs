@@ -0,0 +1,112 @@
+//! Provenance tags of numbers.
+//!
+//! With the `debug` feature enabled, each `BigFloat` produced by an operation carries a tag
+//! which identifies the operation, and the evaluation of a context in which the number was produced.
+//! NaN and infinity returned by an operation keep the tag of the NaN or infinity argument of the operation, if there is one.
+//! So, the tag of a NaN or infinity points to the operation where the NaN or infinity first appeared,
+//! even after it has propagated through a large computation.
+//!
+//! Each `Context` numbers its evaluations (`Context::call`, `Context::sum`, `Context::product`, and `expr!`),
+//! and records the tag of the first NaN or infinity produced through it (see `Context::origin`).
+
+/// Provenance tag of a number: the name of the operation which produced the number, and its sequence number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tag {
+    op: &'static str,
+    seq: Option<usize>,
+}
+
+impl Tag {
+    /// Returns a new tag for the operation `op` without a sequence number.
+    pub(crate) fn new(op: &'static str) -> Self {
+        Tag { op, seq: None }
+    }
+
+    /// Sets the sequence number to `seq` if the tag does not have one yet.
+    pub(crate) fn set_seq(&mut self, seq: usize) {
+        self.seq.get_or_insert(seq);
+    }
+
+    /// Returns the name of the operation, e.g. "div", or "sqrt".
+    pub fn op(&self) -> &'static str {
+        self.op
+    }
+
+    /// Returns the sequence number of the evaluation of the context which produced the number.
+    /// Sequence numbers are assigned by each context separately in the order its evaluations complete.
+    /// The function returns None if the number was produced outside of a context.
+    pub fn seq(&self) -> Option<usize> {
+        self.seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        ctx::{Context, Func},
+        BigFloat, Consts, RoundingMode, INF_POS,
+    };
+
+    #[test]
+    fn test_tag() {
+        let p = 128;
+        let rm = RoundingMode::ToEven;
+        let mut cc = Consts::new().unwrap();
+
+        let one = BigFloat::from_word(1, p);
+        let nan = one.neg().sqrt(p, rm);
+        let tag = nan.tag().unwrap();
+        assert_eq!(tag.op(), "sqrt");
+        assert!(tag.seq().is_none());
+
+        // NaN keeps its origin while propagating.
+        let d1 = one.add(&nan, p, rm).mul(&one, p, rm);
+        let d2 = d1.ln(p, rm, &mut cc);
+        assert!(d2.is_nan());
+        assert_eq!(d2.tag(), Some(tag));
+
+        // infinity produced by division by zero.
+        let inf = one.div(&BigFloat::new(p), p, rm);
+        let tag = inf.tag().unwrap();
+        assert_eq!(tag.op(), "div");
+        assert_eq!(inf.add(&one, p, rm).tag(), Some(tag));
+
+        // finite results get new tags.
+        let d1 = one.add(&one, p, rm);
+        assert_eq!(d1.tag().unwrap().op(), "add");
+        assert_eq!(d1.clone().tag(), d1.tag());
+
+        // constants are not tagged.
+        assert!(INF_POS.tag().is_none());
+
+        // context records the first NaN or Inf.
+        let mut ctx = Context::new(p, rm, cc, -100, 100);
+        ctx.update_status(&one, false);
+        assert!(ctx.origin().is_none());
+        ctx.update_status(&nan, false);
+        ctx.update_status(&inf, false);
+        assert_eq!(ctx.origin(), nan.tag());
+        ctx.reset_status();
+        assert!(ctx.origin().is_none());
+
+        // context numbers its evaluations.
+        let two = ctx.sum([&one, &one]);
+        let three = ctx.call(Func::Hypot, &[&two, &one]);
+        assert_eq!(two.tag().unwrap().op(), "sum");
+        assert_eq!(two.tag().unwrap().seq(), Some(0));
+        assert_eq!(three.tag().unwrap().seq(), Some(1));
+
+        let nan2 = ctx.call(Func::Ln, &[&one.neg()]);
+        let tag = nan2.tag().unwrap();
+        assert_eq!((tag.op(), tag.seq()), ("ln", Some(2)));
+        let d1 = ctx.product([&nan2, &two]);
+        assert_eq!(d1.tag(), Some(tag));
+        assert_eq!(ctx.origin(), Some(tag));
+
+        // sequence numbers of different contexts are independent.
+        let mut ctx2 = Context::new(p, rm, Consts::new().unwrap(), -100, 100);
+        let d1 = ctx2.sum([&one, &one]);
+        assert_eq!(d1.tag().unwrap().seq(), Some(0));
+    }
+}
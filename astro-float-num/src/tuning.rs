@@ -0,0 +1,207 @@
+//! Thresholds of the selection of algorithms.
+//!
+//! The library selects the multiplication algorithm by the size of the operands,
+//! and the method of evaluation of a series by the number of its terms.
+//! The default thresholds were measured on x86-64, and the optimal values can be different on other machines.
+//! The thresholds can be changed at runtime with `set_thresholds`. They are shared by all threads,
+//! and the results of the computations do not depend on them, only the speed does.
+//!
+//! ```
+//! use astro_float_num::tuning::{self, Thresholds};
+//!
+//! let t = Thresholds {
+//!     mul_toom3: 2500,
+//!     ..tuning::thresholds()
+//! };
+//!
+//! tuning::set_thresholds(t).expect("Thresholds are valid");
+//! assert_eq!(tuning::thresholds().mul_toom3, 2500);
+//!
+//! tuning::set_thresholds(Thresholds::default()).expect("Thresholds are valid");
+//! ```
+
+use crate::defs::Error;
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::Ordering;
+
+/// Thresholds of the selection of algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    /// Operands of at most this number of words are multiplied using the schoolbook algorithm.
+    pub mul_basic: usize,
+
+    /// Operands of at most this number of words are multiplied using Toom-2 (Karatsuba).
+    pub mul_toom2: usize,
+
+    /// Operands of at most this number of words are multiplied using Toom-3, larger operands use FFT.
+    /// The cost model which selects the parameters of series evaluation switches to the cost of FFT at the same size.
+    pub mul_toom3: usize,
+
+    /// With the `rayon` feature, sub-products with operands of at least this number of words are computed in parallel.
+    pub mul_par: usize,
+
+    /// Series with at least this number of terms are evaluated using rectangular splitting.
+    pub series_rect: usize,
+}
+
+impl Thresholds {
+    /// Default thresholds.
+    pub const DEFAULT: Self = Thresholds {
+        mul_basic: 32,
+        mul_toom2: 220,
+        mul_toom3: 3200,
+        mul_par: 500,
+        series_rect: 108,
+    };
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static MUL_BASIC: AtomicUsize = AtomicUsize::new(Thresholds::DEFAULT.mul_basic);
+static MUL_TOOM2: AtomicUsize = AtomicUsize::new(Thresholds::DEFAULT.mul_toom2);
+static MUL_TOOM3: AtomicUsize = AtomicUsize::new(Thresholds::DEFAULT.mul_toom3);
+static MUL_PAR: AtomicUsize = AtomicUsize::new(Thresholds::DEFAULT.mul_par);
+static SERIES_RECT: AtomicUsize = AtomicUsize::new(Thresholds::DEFAULT.series_rect);
+
+/// Returns the current thresholds.
+pub fn thresholds() -> Thresholds {
+    Thresholds {
+        mul_basic: mul_basic(),
+        mul_toom2: mul_toom2(),
+        mul_toom3: mul_toom3(),
+        mul_par: mul_par(),
+        series_rect: series_rect(),
+    }
+}
+
+/// Replaces the current thresholds with `t`.
+///
+/// ## Errors
+///
+///  - InvalidArgument: `mul_basic` is less than 4, `mul_toom2` is less than `mul_basic`, `mul_toom3` is less than `mul_toom2`, or `series_rect` is less than 4.
+pub fn set_thresholds(t: Thresholds) -> Result<(), Error> {
+    // smaller operands would not be split by Toom-2 into smaller sub-products.
+    if t.mul_basic < 4
+        || t.mul_toom2 < t.mul_basic
+        || t.mul_toom3 < t.mul_toom2
+        || t.series_rect < 4
+    {
+        return Err(Error::InvalidArgument);
+    }
+
+    MUL_BASIC.store(t.mul_basic, Ordering::Relaxed);
+    MUL_TOOM2.store(t.mul_toom2, Ordering::Relaxed);
+    MUL_TOOM3.store(t.mul_toom3, Ordering::Relaxed);
+    MUL_PAR.store(t.mul_par, Ordering::Relaxed);
+    SERIES_RECT.store(t.series_rect, Ordering::Relaxed);
+
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn mul_basic() -> usize {
+    MUL_BASIC.load(Ordering::Relaxed)
+}
+
+#[inline]
+pub(crate) fn mul_toom2() -> usize {
+    MUL_TOOM2.load(Ordering::Relaxed)
+}
+
+#[inline]
+pub(crate) fn mul_toom3() -> usize {
+    MUL_TOOM3.load(Ordering::Relaxed)
+}
+
+#[inline]
+pub(crate) fn mul_par() -> usize {
+    MUL_PAR.load(Ordering::Relaxed)
+}
+
+#[inline]
+pub(crate) fn series_rect() -> usize {
+    SERIES_RECT.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{BigFloatNumber, RoundingMode};
+
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_thresholds() {
+        assert_eq!(thresholds(), Thresholds::default());
+
+        let invalid = [
+            Thresholds {
+                mul_basic: 3,
+                ..Thresholds::DEFAULT
+            },
+            Thresholds {
+                mul_toom2: 31,
+                ..Thresholds::DEFAULT
+            },
+            Thresholds {
+                mul_toom3: 219,
+                ..Thresholds::DEFAULT
+            },
+            Thresholds {
+                series_rect: 3,
+                ..Thresholds::DEFAULT
+            },
+        ];
+        for t in invalid {
+            assert_eq!(set_thresholds(t), Err(Error::InvalidArgument));
+            assert_eq!(thresholds(), Thresholds::default());
+        }
+
+        // the exact products do not depend on the algorithms
+        let mut nums = Vec::new();
+        for p in [64, 320, 1024, 4096, 16384, 65536] {
+            nums.push(BigFloatNumber::random_normal(p, 0, 0).unwrap());
+        }
+        let prods = |nums: &[BigFloatNumber]| -> Vec<BigFloatNumber> {
+            let mut ret = Vec::new();
+            for a in nums {
+                for b in nums {
+                    let p = a.mantissa_max_bit_len() + b.mantissa_max_bit_len();
+                    ret.push(a.mul(b, p, RoundingMode::None).unwrap());
+                }
+            }
+            ret
+        };
+        let expected = prods(&nums);
+
+        for t in [
+            Thresholds {
+                mul_basic: 4,
+                mul_toom2: 8,
+                mul_toom3: 16,
+                mul_par: 4,
+                ..Thresholds::DEFAULT
+            },
+            Thresholds {
+                mul_basic: 400,
+                mul_toom2: 400,
+                mul_toom3: 400,
+                ..Thresholds::DEFAULT
+            },
+        ] {
+            set_thresholds(t).unwrap();
+            assert_eq!(thresholds(), t);
+            for (a, b) in prods(&nums).iter().zip(expected.iter()) {
+                assert!(a.cmp(b) == 0);
+            }
+        }
+
+        set_thresholds(Thresholds::default()).unwrap();
+    }
+}
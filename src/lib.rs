@@ -53,16 +53,19 @@
 //! Macro simplifies syntax and takes care of the error.
 //!
 //! ```
-//! use astro_float::Consts;
 //! use astro_float::RoundingMode;
 //! use astro_float::ctx::Context;
 //! use astro_float::expr;
 //!
 //! // Create a context with precision 1024, rounding to the nearest even,
 //! // and exponent range from -100000 to 100000.
-//! let mut ctx = Context::new(1024, RoundingMode::ToEven,
-//!     Consts::new().expect("Constants cache initialized"),
-//!     -100000, 100000);
+//! let mut ctx = Context::builder()
+//!     .precision(1024)
+//!     .rounding(RoundingMode::ToEven)
+//!     .emin(-100000)
+//!     .emax(100000)
+//!     .build()
+//!     .expect("Context initialized");
 //!
 //! // Compute pi: pi = 6*arctan(1/sqrt(3))
 //! let pi = expr!(6 * atan(1 / sqrt(3)), &mut ctx);
@@ -125,6 +128,92 @@
 //!
 //! When small error is acceptable because of rounding it is recommended to do all computations with `RoundingMode::None`, and use `BigFloat::set_precision` or `BigFloat::round` with a specific rounding mode just once for the final result.
 //!
+//! In loops which update a value repeatedly, the in-place operations `add_assign_ext`, `sub_assign_ext`, `mul_assign_ext`,
+//! and `div_assign_ext`, as well as the operators `+=`, `-=`, `*=`, and `/=`, reuse the memory of the previous value for the next result.
+//!
+//! The mathematical functions allocate memory for many short-lived intermediate numbers.
+//! A computation wrapped in `pool::with_pool` reuses the memory of the dropped intermediate numbers
+//! instead of requesting it from the memory allocator each time.
+//! The batch functions, such as `BigFloat::exp_many` and `BigFloat::sin_many`, evaluate a function for a whole slice of values
+//! with the pool enabled, and compute the constant used by the function just once.
+//!
+//! The sizes at which the multiplication switches between the schoolbook algorithm, Toom-2, Toom-3, and FFT,
+//! and the number of terms at which series are evaluated using rectangular splitting, were measured on x86-64.
+//! They can be changed at runtime with `tuning::set_thresholds` to match another machine.
+//!
+//! ## Tracing
+//!
+//! With the feature `tracing` enabled, the library emits events using the `tracing` crate
+//! when the working precision of a computation has to be increased because the result can not be rounded correctly,
+//! and when a constant from the constants cache has to be computed with a larger precision.
+//! Mathematical functions which require the constants cache are also wrapped in spans.
+//! The events have the level `DEBUG`, and the spans have the level `TRACE`.
+//!
+//! ## Benchmarks
+//!
+//! With the feature `bench` enabled, the module `bench` provides the function `run_scaling_benchmarks`
+//! which measures the time of multiplication, division, exponent, and sine for a range of precisions.
+//! The measured curves can be used to validate the performance of the library on a particular machine,
+//! and to report performance regressions.
+//!
+//! ## Interoperability with num-bigint
+//!
+//! With the feature `num-bigint` enabled, `BigFloat` can be constructed from `BigInt` and `BigUint`
+//! with a specific precision and rounding mode using `BigFloat::from_bigint`, `BigFloat::from_biguint`, or `FromExt`,
+//! and integer values can be extracted exactly using `BigFloat::to_bigint` and `BigFloat::to_biguint`.
+//!
+//! ## Interoperability with num-rational
+//!
+//! With the feature `num-rational` enabled, `BigFloat` can be constructed from `BigRational`
+//! with a specific precision and rounding mode using `BigFloat::from_rational` or `FromExt`,
+//! and any finite number can be converted to `BigRational` exactly using `BigFloat::to_rational_exact`.
+//! This allows exact pre-processing with rational numbers to be mixed with rounded evaluation.
+//!
+//! ## Half precision
+//!
+//! With the feature `half` enabled, `BigFloat` can be constructed from `f16` of the crate `half` exactly,
+//! and converted to `f16` with a specific rounding mode using `BigFloat::to_f16_round`.
+//!
+//! ## Zero-copy archiving
+//!
+//! With the feature `rkyv` enabled, `BigFloat` can be archived with the crate `rkyv`.
+//! The sign, the exponent, and the mantissa of an archived number can be accessed without deserialization
+//! through `ArchivedBigFloat`, which is useful for large arrays of numbers mapped into memory from a file.
+//!
+//! ## Interoperability with num-traits
+//!
+//! With the feature `num-traits` enabled, `BigFloat` implements `Zero`, `One`, `Num`, `Signed`, and `FromPrimitive`
+//! of the crate `num-traits`, so it can be used in generic numeric code.
+//! The trait methods have no precision argument: constants and values converted from primitive types have the precision 128 bits,
+//! and the operators `+`, `-`, `*`, and `/` return results with the precision and the rounding mode set with `BigFloat::set_op_params`.
+//!
+//! ## Parallel multiplication
+//!
+//! With the feature `rayon` enabled, the independent sub-products of the multiplication of very large mantissas
+//! (the Toom-3 products and the pointwise FFT products) are computed in parallel using the `rayon` thread pool.
+//! Smaller operands are multiplied on the current thread, since the synchronization would cost more than it saves.
+//! The results do not depend on whether the feature is enabled.
+//!
+//! ## Arithmetic operators
+//!
+//! The operators `+`, `-`, `*`, `/`, and `%` can be applied to `BigFloat` values and references.
+//! By default, the result of `+`, `-`, `*`, and `/` has the larger precision of the operands and is rounded to the nearest, ties to even.
+//! `BigFloat::set_op_params` sets a different precision and rounding mode for the current thread,
+//! so the precision of a block of operator expressions can be chosen without switching to method calls.
+//! The remainder `%` is always exact.
+//!
+//! `BigFloat` implements `Sum` and `Product`, so `iter().sum()` and `iter().product()` can be used.
+//! The result is rounded once, as if it was computed exactly, with the precision and the rounding mode of the operators.
+//! `Context::sum` and `Context::product` use the precision, the rounding mode, and the exponent range of the context instead.
+//!
+//! ## Exponent range
+//!
+//! The exponent `Exponent` is a signed 32-bit integer, which limits the magnitude of numbers to about 2^(2^31).
+//! With the feature `exp64` enabled, `Exponent` is a signed 64-bit integer, and `EXPONENT_MIN` and `EXPONENT_MAX`
+//! are about -2^61 and 2^61, which is useful for iterated exponentials and other computations with huge exponents.
+//! The feature requires a 64-bit target. The binary encoding of `to_bytes` uses a separate version for the exponents
+//! which do not fit in 32 bits, and the header of `layout` can not hold such exponents.
+//!
 //! ## no_std
 //!
 //! The library can work without the standard library provided there is a memory allocator. The standard library dependency is activated by the feature `std`.
@@ -135,6 +224,11 @@
 //! astro-float = { version = "0.9.2", default-features = false }
 //! ```
 //!
+//! The library does not use randomness or system calls other than memory allocation unless the feature `random` is enabled,
+//! which adds functions generating random numbers. The feature `random` is active by default as well.
+//! The constants cache can be created without allocating memory in a constant expression using `Consts::empty`,
+//! in which case the constants are computed on the first use.
+//!
 
 #![deny(missing_docs)]
 #![deny(clippy::suspicious)]
@@ -148,10 +242,10 @@ extern crate alloc;
 /// Macro takes into account 2 aspects.
 ///
 /// 1. Code simplification. Macro simplifies code and improves its readability by allowing to specify simple and concise expression
-/// and process input arguments transparently.
+///    and process input arguments transparently.
 ///
 /// 2. Error compensation. Macro compensates error caused by [catastrophic cancellation](https://en.wikipedia.org/wiki/Catastrophic_cancellation)
-/// and some other situations where precision can be lost by automatically increasing the working precision internally.
+///    and some other situations where precision can be lost by automatically increasing the working precision internally.
 ///
 /// The macro does not take care of correct rounding, because the completion of the rounding algorithm in finite time depends on the macro's input.
 ///
@@ -159,14 +253,17 @@ extern crate alloc;
 /// The expression can include:
 ///
 ///  - Path expressions: variable names, constant names, etc.
-///  - Integer literals, e.g. `123`, `-5`.
-///  - Floating point literals, e.g. `1.234e-567`.
-///  - String literals, e.g. `"-1.234_e-567"`.
+///  - Integer literals, e.g. `123`, `-5`, `1_000_000`.
+///  - Floating point literals, e.g. `1.234e-567`, `1_000.000_1`.
+///  - String literals, e.g. `"-1.234_e-567"`, `"1_000.000_1"`. Digits in string literals can be separated with `_` as in numeric literals.
 ///  - Binary operators.
 ///  - Unary `-` operator.
 ///  - Mathematical functions.
+///  - Finite sums and products.
+///  - Sums and products of slices.
+///  - Derivatives.
 ///  - Grouping with `(` and `)`.
-///  - Constants `pi`, `e`, `ln_2`, and `ln_10`.
+///  - Constants `pi`, `e`, `ln_2`, `ln_10`, `sqrt_2`, `phi`, and `ln_pi`.
 ///
 /// Binary operators:
 ///
@@ -181,6 +278,7 @@ extern crate alloc;
 ///  - `recip(x)`: reciprocal of `x`.
 ///  - `sqrt(x)`: square root of `x`.
 ///  - `cbrt(x)`: cube root of `x`.
+///  - `hypot(x, y)`: square root of `x^2 + y^2`.
 ///  - `ln(x)`: natural logarithm of `x`.
 ///  - `log2(x)`: logarithm base 2 of `x`.
 ///  - `log10(x)`: logarithm base 10 of `x`.
@@ -199,12 +297,58 @@ extern crate alloc;
 ///  - `asinh(x)`: hyperbolic arcsine of `x`.
 ///  - `acosh(x)`: hyperbolic arccosine of `x`.
 ///  - `atanh(x)`: hyperbolic arctangent of `x`.
+///  - `sech(x)`: hyperbolic secant of `x`.
+///  - `csch(x)`: hyperbolic cosecant of `x`.
+///  - `coth(x)`: hyperbolic cotangent of `x`.
+///  - `asech(x)`: hyperbolic arcsecant of `x`.
+///  - `acsch(x)`: hyperbolic arccosecant of `x`.
+///  - `acoth(x)`: hyperbolic arccotangent of `x`.
+///  - `gamma(x)`: gamma function of `x`.
+///  - `besselj(n, x)`: Bessel function of the first kind of order `n` of `x`.
+///  - `bessely(n, x)`: Bessel function of the second kind of order `n` of `x`.
+///  - `besseli(n, x)`: modified Bessel function of the first kind of order `n` of `x`.
+///  - `besselk(n, x)`: modified Bessel function of the second kind of order `n` of `x`.
+///
+/// Finite sums and products:
+///
+///  - `sum(i, a, b, f)`: sum of `f` for `i` from `a` to `b` inclusive.
+///  - `product(i, a, b, f)`: product of `f` for `i` from `a` to `b` inclusive.
+///
+/// The index `i` is a new variable of type `i64` which can be used in the expression `f`, and in the bounds of nested sums and products.
+/// The bounds `a` and `b` are Rust expressions converted to `i64` with `as`.
+/// The sum of an empty range is 0, and the product of an empty range is 1.
+/// The working precision of the terms is increased to compensate for the accumulated rounding error and for cancellation,
+/// so the result is correctly rounded like the result of any other expression, e.g. `expr!(sum(i, 1, 100, 1 / pow(i, 2)), &mut ctx)`.
+///
+/// Slices:
+///
+///  - `sum_of(v)`: sum of the elements of `v`.
+///  - `product_of(v)`: product of the elements of `v`.
+///  - `v.len()`: number of elements of `v`.
+///
+/// `v` is a Rust expression with the methods `iter()` and `len()`, e.g. a slice, an array, or a `Vec`,
+/// whose elements can be converted to `BigFloat` like variables, and are interpreted as exact.
+/// For example, the mean value of `v` is `expr!(sum_of(v) / v.len(), &mut ctx)`.
+///
+/// Derivatives:
+///
+///  - `deriv(f, x)`: derivative of `f` with respect to the variable `x`.
+///
+/// The derivative is found symbolically when the macro is expanded, and then evaluated like any other expression.
+/// `deriv` can be nested to compute partial and higher order derivatives, e.g. `expr!(deriv(deriv(x * x * y, x), y), &mut ctx)`.
+/// A variable is treated as independent of `x` unless it is `x` itself, and the index of a sum or a product hides `x`.
+/// The derivative of a product of `n` factors is expanded into a sum of `n` products, so it takes `O(n^2)` operations to evaluate.
+/// The derivative of `gamma` of an expression depending on `x` is not supported,
+/// and neither the divisor of `%` nor the order of Bessel functions can depend on `x`.
 ///
 /// Constants:
 ///  - `pi`: pi number.
 ///  - `e`: Euler number.
 ///  - `ln_2`: natural logarithm of 2.
 ///  - `ln_10`: natural logarithm of 10.
+///  - `sqrt_2`: square root of 2.
+///  - `phi`: golden ratio.
+///  - `ln_pi`: natural logarithm of pi.
 ///
 /// The context determines the precision, the rounding mode of the result, and also contains the cache of constants.
 ///
@@ -213,6 +357,22 @@ extern crate alloc;
 ///
 /// A tuple `(usize, RoundingMode, &mut Consts)`, or `(usize, RoundingMode, &mut Consts, Exponent, Exponent)` can be used as a temporary context (see examples below).
 ///
+/// If `Context` is used, the macro updates its sticky status: the status records whether the result of any evaluation was inexact or NaN,
+/// or whether any value was clamped to the exponent range of the context. An intermediate or a resulting value whose exponent is larger than the maximum exponent
+/// of the context raises the overflow flag, and a nonzero value whose exponent is smaller than the minimum exponent raises the underflow flag.
+/// The status can be queried with `Context::status`, and cleared with `Context::reset_status`.
+///
+/// The range mode of `Context`, set with `Context::set_range_mode` or `ContextBuilder::range_mode`, determines how the result is brought into the exponent range.
+/// By default, it is replaced with infinity or zero as described above. `RangeMode::Ieee` and `RangeMode::IeeeSubnormal` emulate the overflow and the underflow
+/// of an IEEE 754 format with the precision and the exponent range of the context, the latter with gradual underflow. Intermediate values are clamped in any mode.
+///
+/// A block of evaluations can be run with a different precision or rounding mode using `Context::with_precision` and `Context::with_rounding`,
+/// e.g. `ctx.with_precision(512, |ctx| expr!(exp(x), ctx))`. The previous precision or rounding mode of the context is restored after the block.
+///
+/// If memoization is enabled in `Context` with `Context::enable_call_cache` or `ContextBuilder::call_cache`,
+/// the results of the functions in the expression are kept in the cache of the context, and repeated evaluations with the same arguments
+/// and the same working precision take the results from the cache.
+///
 /// Any input argument in the expression is interpreted as exact
 /// (i.e. if an argument of an expression has type BigFloat and it is an inexact result of a previous computation).
 ///
@@ -243,6 +403,9 @@ extern crate alloc;
 ///
 /// assert_eq!(ret, BigFloat::from(124));
 ///
+/// // The computation was exact.
+/// assert!(ctx.status().is_clear());
+///
 /// // Destructure context.
 /// let (p, rm, mut cc, emin, emax) = ctx.to_raw_parts();
 ///
@@ -253,4 +416,33 @@ extern crate alloc;
 /// ```
 pub use astro_float_macro::expr;
 
+/// Constructs a number from a literal parsed at compile time.
+///
+/// The macro accepts a decimal literal and a precision in bits.
+/// The literal is an integer, a floating point, or a string literal in the same format as in `expr!`, optionally preceded by `-`.
+/// Strings `"inf"`, `"-inf"`, and `"nan"` produce Inf and NaN.
+/// The precision is an integer literal, and it is rounded up to a multiple of 64 regardless of the word size of the target.
+/// The literal is rounded to the precision using `RoundingMode::ToEven` when the macro is expanded,
+/// and the expansion only copies the words of the mantissa, so no parsing happens at run time and no constant cache is needed.
+/// The number is marked inexact if the literal can't be represented exactly with the precision.
+/// A literal which fails to parse is a compile error.
+///
+/// ## Examples
+///
+/// ```
+/// # use astro_float::bigfloat;
+/// # use astro_float::BigFloat;
+/// # use astro_float::Consts;
+/// # use astro_float::RoundingMode;
+/// let pi = bigfloat!("3.14159265358979323846264338327950288419716939937510", 128);
+///
+/// let mut cc = Consts::new().expect("Failed to allocate constants cache");
+/// assert_eq!(pi, cc.pi(128, RoundingMode::ToEven));
+/// assert!(pi.inexact());
+///
+/// assert_eq!(bigfloat!(-1.5, 64), BigFloat::from(-1.5));
+/// assert!(!bigfloat!(-1.5, 64).inexact());
+/// ```
+pub use astro_float_macro::bigfloat;
+
 pub use astro_float_num::*;
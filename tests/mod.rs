@@ -1,9 +1,10 @@
 // Additional tests of the library.
 
-use astro_float_macro::expr;
+use astro_float_macro::{bigfloat, expr};
 use astro_float_num::{
-    ctx::Context, BigFloat, Consts, Radix, RoundingMode, Sign, EXPONENT_MAX, EXPONENT_MIN,
-    WORD_BIT_SIZE, WORD_MAX, WORD_SIGNIFICANT_BIT,
+    ctx::{Context, Func, RangeMode},
+    BigFloat, Consts, Radix, RoundingMode, Sign, EXPONENT_MAX, EXPONENT_MIN, WORD_BIT_SIZE,
+    WORD_MAX, WORD_SIGNIFICANT_BIT,
 };
 
 #[test]
@@ -53,6 +54,9 @@ fn macro_run_basic_tests() {
     let res: BigFloat = expr!(log(x, y), &mut ctx);
     debug_assert_eq!(res, x.log(&y, p, rm, &mut cc));
 
+    let res: BigFloat = expr!(hypot(x, y), &mut ctx);
+    debug_assert_eq!(res, x.hypot(&y, p, rm));
+
     let res: BigFloat = expr!(exp(x), &mut ctx);
     debug_assert_eq!(res, x.exp(p, rm, &mut cc));
 
@@ -100,6 +104,43 @@ fn macro_run_basic_tests() {
 
     let res: BigFloat = expr!(atanh(x), &mut ctx);
     debug_assert_eq!(res, x.atanh(p, rm, &mut cc));
+
+    let res: BigFloat = expr!(sech(x), &mut ctx);
+    debug_assert_eq!(res, x.sech(p, rm, &mut cc));
+
+    let res: BigFloat = expr!(csch(x), &mut ctx);
+    debug_assert_eq!(res, x.csch(p, rm, &mut cc));
+
+    let res: BigFloat = expr!(coth(x), &mut ctx);
+    debug_assert_eq!(res, x.coth(p, rm, &mut cc));
+
+    let res: BigFloat = expr!(asech(x), &mut ctx);
+    debug_assert_eq!(res, x.asech(p, rm, &mut cc));
+
+    let res: BigFloat = expr!(acsch(x), &mut ctx);
+    debug_assert_eq!(res, x.acsch(p, rm, &mut cc));
+
+    let x = BigFloat::from(8.13);
+
+    let res: BigFloat = expr!(acoth(x), &mut ctx);
+    debug_assert_eq!(res, x.acoth(p, rm, &mut cc));
+
+    let x = BigFloat::from(0.123);
+
+    let res: BigFloat = expr!(gamma(x), &mut ctx);
+    debug_assert_eq!(res, x.gamma(p, rm, &mut cc));
+
+    let res: BigFloat = expr!(besselj(y, x), &mut ctx);
+    debug_assert_eq!(res, x.besselj(&y, p, rm, &mut cc));
+
+    let res: BigFloat = expr!(bessely(y, x), &mut ctx);
+    debug_assert_eq!(res, x.bessely(&y, p, rm, &mut cc));
+
+    let res: BigFloat = expr!(besseli(y, x), &mut ctx);
+    debug_assert_eq!(res, x.besseli(&y, p, rm, &mut cc));
+
+    let res: BigFloat = expr!(besselk(y, x), &mut ctx);
+    debug_assert_eq!(res, x.besselk(&y, p, rm, &mut cc));
 }
 
 #[test]
@@ -149,6 +190,18 @@ fn macro_run_err_test() {
     let x = expr!(ln_10, &mut ctx);
     assert_eq!(x, ctx.const_ln10());
 
+    // constants: sqrt_2, phi, ln_pi
+    let x = expr!(sqrt_2, &mut ctx);
+    assert_eq!(x, cc.sqrt_2(p, rm));
+
+    let x = expr!(phi, &mut ctx);
+    assert_eq!(x, cc.phi(p, rm));
+
+    let x = expr!(ln_pi * 2, &mut ctx);
+    let mut y = cc.ln_pi(p, rm);
+    y.set_exponent(y.exponent().unwrap() + 1);
+    assert_eq!(x, y);
+
     // ln
     for x in [
         BigFloat::from_words(&[234, 0, WORD_SIGNIFICANT_BIT], Sign::Pos, -123),
@@ -530,6 +583,67 @@ fn macro_run_err_test() {
 
     assert_ne!(y1, z);
     assert_eq!(y2, z);
+
+    // gamma
+    let x = BigFloat::from_words(&[123, 123, WORD_SIGNIFICANT_BIT], Sign::Pos, -100);
+    let three = BigFloat::from(3);
+
+    let z = x.sub(&three, p + 1, RoundingMode::None);
+    let y1 = z.gamma(p, rm, &mut cc);
+
+    let z = x.sub(&three, p + 256, RoundingMode::None);
+    let mut y2 = z.gamma(p + 256, RoundingMode::None, &mut cc);
+    y2.set_precision(p, rm).unwrap();
+
+    let z = expr!(gamma(x - 3), &mut ctx);
+
+    assert_ne!(y1, z);
+    assert_eq!(y2, z);
+
+    // besselj near a zero
+    let x = BigFloat::parse(
+        "2.404825557695772768621631879326454643124244909145614",
+        astro_float_num::Radix::Dec,
+        p,
+        RoundingMode::None,
+        &mut cc,
+    );
+    let mut y = BigFloat::from_words(&[123, 123, WORD_SIGNIFICANT_BIT], Sign::Pos, 0);
+    y.set_exponent(-300);
+    let n = BigFloat::from(0);
+
+    let z = x.add(&y, p + 1, RoundingMode::None);
+    let y1 = z.besselj(&n, p, rm, &mut cc);
+
+    let z = x.add(&y, p + 256, RoundingMode::None);
+    let mut y2 = z.besselj(&n, p + 256, RoundingMode::None, &mut cc);
+    y2.set_precision(p, rm).unwrap();
+
+    let z = expr!(besselj(0, x + y), &mut ctx);
+
+    assert_ne!(y1, z);
+    assert_eq!(y2, z);
+
+    // bessely near a zero
+    let x = BigFloat::parse(
+        "0.893576966279167521584887102058338241225146861930014487069229",
+        astro_float_num::Radix::Dec,
+        p,
+        RoundingMode::None,
+        &mut cc,
+    );
+
+    let z = x.add(&y, p + 1, RoundingMode::None);
+    let y1 = z.bessely(&n, p, rm, &mut cc);
+
+    let z = x.add(&y, p + 256, RoundingMode::None);
+    let mut y2 = z.bessely(&n, p + 256, RoundingMode::None, &mut cc);
+    y2.set_precision(p, rm).unwrap();
+
+    let z = expr!(bessely(0, x + y), &mut ctx);
+
+    assert_ne!(y1, z);
+    assert_eq!(y2, z);
 }
 
 // test precision range for error compensation
@@ -558,6 +672,15 @@ fn macro_run_misc_test() {
     let z = expr!("2e-302", &mut ctx);
     assert!(z.is_zero());
 
+    // literals with digit separators
+    let z = expr!(1_000_000.000_1, &mut ctx);
+    assert_eq!(z, expr!(1000000.0001, &mut ctx));
+    assert_eq!(z, expr!("1_000_000.000_1", &mut ctx));
+    assert_eq!(
+        expr!(1_000 + "2_0e1_0", &mut ctx),
+        expr!(1000 + 20e10, &mut ctx)
+    );
+
     // exceed output exponent
     let z = expr!(2e+151 / 2e-151, &mut ctx);
     assert!(z.is_inf_pos());
@@ -689,3 +812,304 @@ fn macro_run_misc_test() {
     let z = expr!(ln(5 * (1 / 5)), &mut ctx);
     assert!(z.is_zero());
 }
+
+#[test]
+fn macro_status_test() {
+    let p = 128;
+    let rm = RoundingMode::ToEven;
+    let mut ctx = Context::new(p, rm, Consts::new().unwrap(), -1000, 1000);
+
+    assert!(ctx.status().is_clear());
+
+    // exact
+    let z = expr!(3 * 4 - 2 / 8, &mut ctx);
+    assert_eq!(z, BigFloat::from_f64(11.75, p));
+    assert!(ctx.status().is_clear());
+
+    // inexact
+    let z = expr!(1 / 3, &mut ctx);
+    assert!(z.inexact());
+    assert!(ctx.status().inexact());
+    assert!(!ctx.status().clamped());
+    assert!(!ctx.status().invalid());
+
+    // the flags are sticky
+    let _ = expr!(1 + 1, &mut ctx);
+    assert!(ctx.status().inexact());
+
+    ctx.reset_status();
+    assert!(ctx.status().is_clear());
+
+    // invalid
+    let z = expr!(sqrt(-1), &mut ctx);
+    assert!(z.is_nan());
+    assert!(ctx.status().invalid());
+
+    // clamped result
+    ctx.reset_status();
+    let z = expr!(2e+151 / 2e-151, &mut ctx);
+    assert!(z.is_inf_pos());
+    assert!(ctx.status().clamped());
+    assert!(ctx.status().overflow());
+    assert!(!ctx.status().underflow());
+
+    // overflow of an intermediate value
+    ctx.reset_status();
+    let z = expr!(1 / exp(1000), &mut ctx);
+    assert!(z.is_zero());
+    assert!(ctx.status().underflow());
+    let z = expr!(sin(exp(1000)), &mut ctx);
+    assert!(z.is_nan());
+    assert!(ctx.status().overflow());
+
+    // clamped literal
+    ctx.reset_status();
+    let z = expr!(1 + 2e-302 * 0, &mut ctx);
+    assert_eq!(z, BigFloat::from_u8(1, p));
+    assert!(ctx.status().clamped());
+    assert!(ctx.status().underflow());
+    assert!(!ctx.status().inexact());
+
+    // constants
+    ctx.reset_status();
+    let _ = ctx.const_pi();
+    assert!(ctx.status().inexact());
+
+    // temporary context does not track status
+    let mut cc = Consts::new().unwrap();
+    let z = expr!(1 / 3, (p, rm, &mut cc));
+    assert!(z.inexact());
+}
+
+#[test]
+fn macro_range_mode_test() {
+    let p = 64;
+    let mut ctx = Context::builder()
+        .precision(p)
+        .rounding(RoundingMode::ToZero)
+        .emin(-1021)
+        .emax(1024)
+        .range_mode(RangeMode::Ieee)
+        .build()
+        .unwrap();
+
+    // overflow towards zero gives the largest finite number
+    let mut max = BigFloat::max_value(p);
+    max.set_exponent(1024);
+    let z = expr!(2e+300 * 2e+300, &mut ctx);
+    assert_eq!(z, max);
+    assert!(ctx.status().overflow() && ctx.status().inexact());
+
+    // underflow is flushed to zero of the same sign
+    ctx.reset_status();
+    let z = expr!(-2e-300 * 2e-300, &mut ctx);
+    assert!(z.is_zero() && z.is_negative());
+    assert!(ctx.status().underflow());
+
+    // gradual underflow gives the same result as a function call through the context
+    ctx.set_range_mode(RangeMode::IeeeSubnormal);
+    ctx.set_rounding_mode(RoundingMode::ToEven);
+    ctx.reset_status();
+    let z = expr!(exp(-740), &mut ctx);
+    let x = BigFloat::from_word(740, p).neg();
+    assert_eq!(z, ctx.call(Func::Exp, &[&x]));
+    assert!(!z.is_zero() && z.exponent() < Some(-1021));
+    assert!(ctx.status().underflow() && !ctx.status().clamped());
+}
+
+#[test]
+fn macro_call_cache_test() {
+    let p = 256;
+    let rm = RoundingMode::ToEven;
+    let mut ctx = Context::builder()
+        .precision(p)
+        .rounding(rm)
+        .call_cache(16)
+        .build()
+        .unwrap();
+
+    let x = BigFloat::from_f64(0.75, p);
+    let y = BigFloat::from_f64(1.25, p);
+
+    // repeated evaluation takes the function results from the cache
+    let z1 = expr!(sin(x) * exp(y) + besselj(0, x) + pow(x, y), &mut ctx);
+    let misses = ctx.call_cache().unwrap().misses();
+    assert!(misses >= 4);
+    assert_eq!(ctx.call_cache().unwrap().hits(), 0);
+
+    let z2 = expr!(sin(x) * exp(y) + besselj(0, x) + pow(x, y), &mut ctx);
+    assert_eq!(z1, z2);
+    assert_eq!(ctx.call_cache().unwrap().misses(), misses);
+    assert!(ctx.call_cache().unwrap().hits() >= 4);
+
+    // the same result as without memoization
+    let mut cc = Consts::new().unwrap();
+    let z3 = expr!(
+        sin(x) * exp(y) + besselj(0, x) + pow(x, y),
+        (p, rm, &mut cc)
+    );
+    assert_eq!(z1, z3);
+
+    // different arguments are not taken from the cache
+    let _ = expr!(sin(y), &mut ctx);
+    assert!(ctx.call_cache().unwrap().misses() > misses);
+}
+
+#[test]
+fn macro_series_test() {
+    let p = 256;
+    let rm = RoundingMode::ToEven;
+    let mut ctx = Context::new(p, rm, Consts::new().unwrap(), -10000, 10000);
+
+    // sum of 1/i^2 compared to the sum computed with a much larger precision
+    let z = expr!(sum(i, 1, 100, 1 / pow(i, 2)), &mut ctx);
+    let p_ref = 1024;
+    let mut s = BigFloat::from_u8(0, p_ref);
+    for i in 1..=100u64 {
+        let d = BigFloat::from_u64(i * i, p_ref);
+        s = s.add(
+            &d.reciprocal(p_ref, RoundingMode::None),
+            p_ref,
+            RoundingMode::None,
+        );
+    }
+    s.set_precision(p, rm).unwrap();
+    assert_eq!(z, s);
+    assert!(z.inexact());
+
+    // exact product
+    let z = expr!(product(k, 1, 20, k), &mut ctx);
+    assert_eq!(z, BigFloat::from_u64(2432902008176640000, p));
+    assert!(!z.inexact());
+
+    // empty ranges
+    let n = 0usize;
+    assert!(expr!(sum(i, 1, n, i), &mut ctx).is_zero());
+    assert_eq!(
+        expr!(product(i, 5, 4, i), &mut ctx),
+        BigFloat::from_u8(1, p)
+    );
+
+    // nested sums, the bounds can depend on the outer index
+    let z = expr!(sum(i, 1, 10, sum(j, 1, i, j)), &mut ctx);
+    assert_eq!(z, BigFloat::from_u8(220, p));
+
+    // cancellation between inexact terms
+    let z = expr!(sum(k, 0, 1, (1 - 2 * k) * (1 / 3 + k * 1e-30)), &mut ctx);
+    assert_eq!(z, expr!(-1e-30, &mut ctx));
+}
+
+#[test]
+fn macro_slice_test() {
+    let p = 192;
+    let rm = RoundingMode::ToEven;
+    let mut ctx = Context::new(p, rm, Consts::new().unwrap(), -10000, 10000);
+
+    // mean value
+    let v = [BigFloat::from(1.5), BigFloat::from(2), BigFloat::from(4)].to_vec();
+    let z = expr!(sum_of(v) / v.len(), &mut ctx);
+    let s = BigFloat::from(7.5);
+    assert_eq!(z, s.div(&BigFloat::from(3), p, rm));
+
+    // arrays and slices of primitive types
+    let a = [2, 3, 7];
+    assert_eq!(expr!(product_of(a), &mut ctx), BigFloat::from(42));
+    assert_eq!(
+        expr!(product_of(&a[1..]) - a.len(), &mut ctx),
+        BigFloat::from(18)
+    );
+    let f = [0.1f64, -0.1];
+    let z = expr!(sum_of(f), &mut ctx);
+    assert!(z.is_zero() && !z.inexact());
+
+    // empty slice
+    let e: [f64; 0] = [];
+    assert!(expr!(sum_of(e), &mut ctx).is_zero());
+    assert_eq!(expr!(product_of(e), &mut ctx), BigFloat::from(1));
+
+    // cancellation between the elements
+    let v = ["1", "1e-40", "-1"];
+    let z = expr!(sum_of(v) * 3, &mut ctx);
+    assert_eq!(z, expr!(3e-40, &mut ctx));
+}
+
+#[test]
+fn macro_deriv_test() {
+    let p = 192;
+    let rm = RoundingMode::ToEven;
+    let mut ctx = Context::new(p, rm, Consts::new().unwrap(), -10000, 10000);
+
+    let x = BigFloat::from(0.75);
+    let y = BigFloat::from(2.5);
+
+    // product, quotient, and chain rules
+    let z = expr!(deriv(sin(x) * x, x), &mut ctx);
+    assert_eq!(z, expr!(cos(x) * x + sin(x), &mut ctx));
+    let z = expr!(deriv(exp(x * x) / y, x), &mut ctx);
+    assert_eq!(z, expr!(2 * x * exp(x * x) / y, &mut ctx));
+    let z = expr!(deriv(ln(sqrt(x)), x), &mut ctx);
+    assert_eq!(z, expr!(0.5 / x, &mut ctx));
+    let z = expr!(deriv(pow(x, 3) + pow(2, x) + y, x), &mut ctx);
+    assert_eq!(z, expr!(3 * x * x + pow(2, x) * ln_2, &mut ctx));
+
+    // partial and higher order derivatives
+    let z = expr!(deriv(deriv(x * x * y * y, x), y), &mut ctx);
+    assert_eq!(z, expr!(4 * x * y, &mut ctx));
+    let z = expr!(deriv(deriv(deriv(atan(x), x), x), x), &mut ctx);
+    assert_eq!(z, expr!((6 * x * x - 2) / pow(1 + x * x, 3), &mut ctx));
+
+    // finite sums and products, and Bessel functions
+    let z = expr!(deriv(sum(i, 1, 4, pow(x, i) / i), x), &mut ctx);
+    assert_eq!(z, expr!(1 + x + x * x + x * x * x, &mut ctx));
+    let z = expr!(deriv(product(i, 1, 3, x + i), x), &mut ctx);
+    assert_eq!(z, expr!(3 * x * x + 12 * x + 11, &mut ctx));
+    let one = BigFloat::from(1);
+    let z = expr!(deriv(product(i, 0, 2, one - i), one), &mut ctx);
+    assert_eq!(z, BigFloat::from(-1));
+    let z = expr!(deriv(besselj(0, x), x), &mut ctx);
+    assert_eq!(z, expr!(-besselj(1, x), &mut ctx));
+
+    // Newton's step for x^2 - 2 = 0
+    let mut r = BigFloat::from(1);
+    for _ in 0..8 {
+        r = expr!(r - (r * r - 2) / deriv(r * r - 2, r), &mut ctx);
+    }
+    assert_eq!(r, expr!(sqrt(2), &mut ctx));
+}
+
+#[test]
+fn macro_bigfloat_test() {
+    let p = 320;
+    let rm = RoundingMode::ToEven;
+    let mut cc = Consts::new().unwrap();
+
+    // exact and inexact literals
+    let s = "1.23456789012345678901234567890123456789012345678901234567890123456789e-1000";
+    let d1 = bigfloat!(
+        "1.23456789012345678901234567890123456789012345678901234567890123456789e-1000",
+        320
+    );
+    let d2 = BigFloat::parse(s, Radix::Dec, p, rm, &mut cc);
+    assert_eq!(d1, d2);
+    assert_eq!(d1.mantissa_max_bit_len(), Some(p));
+    assert!(d1.inexact());
+
+    let d1 = bigfloat!(1_000.000_1, 64);
+    assert_eq!(
+        d1,
+        BigFloat::parse("1000.0001", Radix::Dec, 64, rm, &mut cc)
+    );
+    assert_eq!(bigfloat!(-123, 1), BigFloat::from(-123));
+    assert!(!bigfloat!(-123, 1).inexact());
+    assert_eq!(bigfloat!("0.125", 100).mantissa_max_bit_len(), Some(128));
+
+    // special values
+    assert!(bigfloat!(0, 64).is_zero());
+    assert!(bigfloat!("inf", 64).is_inf_pos());
+    assert!(bigfloat!("-inf", 64).is_inf_neg());
+    assert!(bigfloat!("nan", 64).is_nan());
+
+    // the value is the same as in expr!
+    let mut ctx = Context::new(p, rm, cc, -10000, 10000);
+    assert_eq!(bigfloat!("0.1", 320), expr!(0.1, &mut ctx));
+}